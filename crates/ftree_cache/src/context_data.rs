@@ -2,6 +2,7 @@ use anyhow::{anyhow, Context, Error, Result};
 use dashmap::DashMap;
 use parking_lot::{MappedRwLockReadGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tracing::debug;
 
 #[cfg(feature = "deadlock_ref")]
@@ -189,6 +190,8 @@ pub struct FileContextCache<
     /// If there is no entry, the directory has not been probed yet
     cache: DashMap<PathBuf, Option<T>>,
     args: TArgs,
+    /// Number of times a context file has been looked up on the real filesystem
+    fs_reads: AtomicUsize,
 }
 
 #[cfg(feature = "deadlock_ref")]
@@ -208,6 +211,7 @@ impl<T: ContextData<()>, const CONTEXT_FNAME: &'static str> FileContextCache<T,
         Self {
             cache: DashMap::new(),
             args: (),
+            fs_reads: AtomicUsize::new(0),
         }
     }
 }
@@ -323,6 +327,7 @@ impl<T: ContextData<TArgs>, TArgs: Copy, const CONTEXT_FNAME: &'static str>
 
     fn check_dir_os_fs(&self, base: &Path) -> Result<Option<T>, Error> {
         // probe the real FS for a tsconfig.json file
+        self.fs_reads.fetch_add(1, Ordering::Relaxed);
         let context_file_path = base.to_owned().join(CONTEXT_FNAME);
         let result = T::read_context_data(self.args, &context_file_path)
             .with_context(|| "in read_context_data");
@@ -340,10 +345,21 @@ impl<T: ContextData<TArgs>, TArgs: Copy, const CONTEXT_FNAME: &'static str>
         result
     }
 
+    /// Gets the number of directories that have been checked on the real
+    /// filesystem, rather than answered from the cache.
+    pub fn fs_read_count(&self) -> usize {
+        self.fs_reads.load(Ordering::Relaxed)
+    }
+
     /// Clears the cache for all paths under a subdirectory, recursively.
     pub fn mark_dirty_root(&self, path: &Path) {
         self.cache.retain(|key, _| !key.starts_with(path));
     }
+
+    /// Clears the cache for all paths.
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
 }
 
 // Represents an iterator that steps up all the discovered context files in a directory
@@ -0,0 +1,273 @@
+use crate::{ExternalFences, GoodFencesOptions, ModelFormat, OutputFormat, ViolationGrouping};
+use std::iter::Peekable;
+use std::slice::Iter;
+
+/**
+ * Default value of `--project`
+ */
+pub const DEFAULT_PROJECT: &str = "./tsconfig.paths.json";
+
+/**
+ * Default value of `--baseUrl`
+ */
+pub const DEFAULT_BASE_URL: &str = ".";
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CliArgsError {
+    #[error("option '{0}' argument missing")]
+    MissingValue(String),
+    #[error("option '{flag}' argument '{value}' is invalid")]
+    InvalidValue { flag: String, value: String },
+    #[error("unable to create regex from --ignoredDirs {0}: {1}")]
+    InvalidIgnoredDir(String, String),
+    #[error("unable to create glob from --allowedUnresolved {0}: {1}")]
+    InvalidAllowedUnresolved(String, String),
+    #[error("unknown option '{0}'")]
+    UnknownOption(String),
+    #[error("missing required argument 'path'")]
+    MissingPath,
+}
+
+/**
+ * Parses the arguments of the `good-fences` cli, without the leading node
+ * and script paths (i.e. `process.argv.slice(2)`).
+ *
+ * Accepts the same flags as the original commander-based cli:
+ * `good-fences [options] <path> [morePaths...]`
+ */
+pub fn parse_cli_args(args: &[String]) -> Result<GoodFencesOptions, CliArgsError> {
+    let mut options = GoodFencesOptions {
+        paths: Vec::new(),
+        project: DEFAULT_PROJECT.to_string(),
+        base_url: Some(DEFAULT_BASE_URL.to_string()),
+        err_output_path: None,
+        ignore_external_fences: Some(ExternalFences::Include),
+        ignored_dirs: Some(Vec::new()),
+        allowed_unresolved: Some(Vec::new()),
+        resource_extensions: Some(Vec::new()),
+        assume_unresolved_bare_as_external: Some(false),
+        fence_root: None,
+        html_output_path: None,
+        group_by: None,
+        layer_tag_priority: Some(Vec::new()),
+        export_model_path: None,
+        export_model_format: Some(ModelFormat::Json),
+        output_format: Some(OutputFormat::Human),
+    };
+
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            options.paths.push(arg.clone());
+            continue;
+        }
+
+        // options may also be passed as `--flag=value`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        match flag {
+            "-p" | "--project" => options.project = flag_value(flag, inline_value, &mut args)?,
+            "-o" | "--output" | "--errOutputPath" => {
+                options.err_output_path = Some(flag_value(flag, inline_value, &mut args)?)
+            }
+            "--baseUrl" => options.base_url = Some(flag_value(flag, inline_value, &mut args)?),
+            "--ignoreExternalFences" => {
+                options.ignore_external_fences = Some(ExternalFences::Ignore)
+            }
+            "--ignoredDirs" => {
+                let ignored_dirs = variadic_values(inline_value, &mut args);
+                for ignored_dir in ignored_dirs.iter() {
+                    if let Err(err) = regex::Regex::new(ignored_dir) {
+                        return Err(CliArgsError::InvalidIgnoredDir(
+                            ignored_dir.clone(),
+                            err.to_string(),
+                        ));
+                    }
+                }
+                options
+                    .ignored_dirs
+                    .get_or_insert_with(Vec::new)
+                    .extend(ignored_dirs);
+            }
+            "--allowedUnresolved" => {
+                let allowed_unresolved = variadic_values(inline_value, &mut args);
+                for specifier_glob in allowed_unresolved.iter() {
+                    if let Err(err) = path_utils::Glob::new(specifier_glob) {
+                        return Err(CliArgsError::InvalidAllowedUnresolved(
+                            specifier_glob.clone(),
+                            err.to_string(),
+                        ));
+                    }
+                }
+                options
+                    .allowed_unresolved
+                    .get_or_insert_with(Vec::new)
+                    .extend(allowed_unresolved);
+            }
+            "--resourceExtensions" => {
+                let resource_extensions = variadic_values(inline_value, &mut args);
+                options
+                    .resource_extensions
+                    .get_or_insert_with(Vec::new)
+                    .extend(resource_extensions);
+            }
+            "--assumeUnresolvedBareAsExternal" => {
+                options.assume_unresolved_bare_as_external = Some(true)
+            }
+            "--fenceRoot" => options.fence_root = Some(flag_value(flag, inline_value, &mut args)?),
+            "--htmlOutput" => {
+                options.html_output_path = Some(flag_value(flag, inline_value, &mut args)?)
+            }
+            "--group-by" => {
+                let value = flag_value(flag, inline_value, &mut args)?;
+                options.group_by = match value.as_str() {
+                    "layer" => Some(ViolationGrouping::Layer),
+                    "owner" => Some(ViolationGrouping::Owner),
+                    _ => return Err(invalid_value(flag, value)),
+                };
+            }
+            "--layer-tag-priority" => options
+                .layer_tag_priority
+                .get_or_insert_with(Vec::new)
+                .extend(variadic_values(inline_value, &mut args)),
+            "--export-model" => {
+                options.export_model_path = Some(flag_value(flag, inline_value, &mut args)?)
+            }
+            "--export-model-format" => {
+                let value = flag_value(flag, inline_value, &mut args)?;
+                options.export_model_format = match value.as_str() {
+                    "json" => Some(ModelFormat::Json),
+                    "binary" => Some(ModelFormat::Binary),
+                    _ => return Err(invalid_value(flag, value)),
+                };
+            }
+            "--format" => {
+                let value = flag_value(flag, inline_value, &mut args)?;
+                options.output_format = match value.as_str() {
+                    "human" => Some(OutputFormat::Human),
+                    "json" => Some(OutputFormat::Json),
+                    "ndjson" => Some(OutputFormat::Ndjson),
+                    _ => return Err(invalid_value(flag, value)),
+                };
+            }
+            _ => return Err(CliArgsError::UnknownOption(flag.to_string())),
+        }
+    }
+
+    if options.paths.is_empty() {
+        return Err(CliArgsError::MissingPath);
+    }
+    Ok(options)
+}
+
+// takes the value of a flag, either from `--flag=value` or from the next argument
+fn flag_value(
+    flag: &str,
+    inline_value: Option<&str>,
+    args: &mut Peekable<Iter<String>>,
+) -> Result<String, CliArgsError> {
+    match inline_value {
+        Some(value) => Ok(value.to_string()),
+        None => args
+            .next_if(|next| !next.starts_with('-'))
+            .cloned()
+            .ok_or_else(|| CliArgsError::MissingValue(flag.to_string())),
+    }
+}
+
+// takes the values of a variadic flag, which extend up to the next flag
+fn variadic_values(inline_value: Option<&str>, args: &mut Peekable<Iter<String>>) -> Vec<String> {
+    let mut values: Vec<String> = inline_value.map(str::to_string).into_iter().collect();
+    while let Some(value) = args.next_if(|next| !next.starts_with('-')) {
+        values.push(value.clone());
+    }
+    values
+}
+
+fn invalid_value(flag: &str, value: String) -> CliArgsError {
+    CliArgsError::InvalidValue {
+        flag: flag.to_string(),
+        value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_cli_args, CliArgsError, DEFAULT_BASE_URL};
+    use crate::{ExternalFences, ModelFormat, OutputFormat, ViolationGrouping};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_valid_args() {
+        let options = parse_cli_args(&args(&[
+            "--project",
+            "tsconfig.json",
+            "-o=violations.json",
+            "--ignoredDirs",
+            "lib",
+            "^dist$",
+            "--allowedUnresolved=virtual:*",
+            "--resourceExtensions",
+            "jpg",
+            ".svg",
+            "--assumeUnresolvedBareAsExternal",
+            "--ignoreExternalFences",
+            "--group-by",
+            "layer",
+            "--export-model-format",
+            "binary",
+            "--format=ndjson",
+            "packages",
+            "shared",
+        ]))
+        .unwrap();
+
+        assert_eq!(options.paths, vec!["packages", "shared"]);
+        assert_eq!(options.project, "tsconfig.json");
+        assert_eq!(options.base_url.as_deref(), Some(DEFAULT_BASE_URL));
+        assert_eq!(options.err_output_path.as_deref(), Some("violations.json"));
+        assert_eq!(
+            options.ignored_dirs,
+            Some(vec!["lib".to_string(), "^dist$".to_string()])
+        );
+        assert_eq!(
+            options.allowed_unresolved,
+            Some(vec!["virtual:*".to_string()])
+        );
+        assert_eq!(
+            options.resource_extensions,
+            Some(vec!["jpg".to_string(), ".svg".to_string()])
+        );
+        assert_eq!(options.assume_unresolved_bare_as_external, Some(true));
+        assert_eq!(options.ignore_external_fences, Some(ExternalFences::Ignore));
+        assert_eq!(options.group_by, Some(ViolationGrouping::Layer));
+        assert_eq!(options.export_model_format, Some(ModelFormat::Binary));
+        assert_eq!(options.output_format, Some(OutputFormat::Ndjson));
+    }
+
+    #[test]
+    fn parse_missing_project_value() {
+        let err = parse_cli_args(&args(&["packages", "--project"]))
+            .err()
+            .unwrap();
+
+        assert_eq!(err, CliArgsError::MissingValue("--project".to_string()));
+    }
+
+    #[test]
+    fn parse_bad_ignored_dirs_regex() {
+        let err = parse_cli_args(&args(&["packages", "--ignoredDirs", "(lib"]))
+            .err()
+            .unwrap();
+
+        assert!(
+            matches!(&err, CliArgsError::InvalidIgnoredDir(dir, _) if dir == "(lib"),
+            "unexpected error: {err}"
+        );
+    }
+}
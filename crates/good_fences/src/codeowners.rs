@@ -0,0 +1,152 @@
+use anyhow::Context;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+/**
+ * Locations of the CODEOWNERS file relative to the root of the repo, in the
+ * order they are searched.
+ */
+pub const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/**
+ * A single line of a CODEOWNERS file: a gitignore-style pattern and the
+ * owners of the paths it matches.
+ */
+#[derive(Debug, Clone)]
+struct CodeOwnersRule {
+    matcher: Gitignore,
+    owners: Vec<String>,
+}
+
+/**
+ * The rules of a CODEOWNERS file, for looking up the owners of source files.
+ * As in GitHub, the last rule matching a path decides its owners.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    root_dir: PathBuf,
+    rules: Vec<CodeOwnersRule>,
+}
+
+impl CodeOwners {
+    /**
+     * Reads the CODEOWNERS file of the git repo containing `search_start`,
+     * if there is one.
+     */
+    pub fn discover(search_start: &Path) -> anyhow::Result<Option<CodeOwners>> {
+        let repo_root = repo_root::find_git_root_from(search_start);
+        CodeOwners::from_root(&repo_root)
+    }
+
+    /**
+     * Reads the first CODEOWNERS file found in `root_dir`, its `.github` or its
+     * `docs` directory, if there is one. Patterns are matched relative to `root_dir`.
+     */
+    pub fn from_root(root_dir: &Path) -> anyhow::Result<Option<CodeOwners>> {
+        let root_dir = std::path::absolute(root_dir)?;
+        let codeowners_path = match CODEOWNERS_PATHS
+            .iter()
+            .map(|path| root_dir.join(path))
+            .find(|path| path.is_file())
+        {
+            Some(codeowners_path) => codeowners_path,
+            None => return Ok(None),
+        };
+
+        let content = std::fs::read_to_string(&codeowners_path)
+            .with_context(|| format!("Unable to read {}", codeowners_path.display()))?;
+        CodeOwners::parse(&root_dir, &content)
+            .with_context(|| format!("Unable to parse {}", codeowners_path.display()))
+            .map(Some)
+    }
+
+    /**
+     * Parses the content of a CODEOWNERS file, with patterns relative to `root_dir`.
+     * Lines without owners are kept, so they can remove the owners of a path.
+     */
+    pub fn parse(root_dir: &Path, content: &str) -> anyhow::Result<CodeOwners> {
+        let root_dir = std::path::absolute(root_dir)?;
+        let mut rules = Vec::new();
+        for line in content.lines() {
+            // everything after a `#` is a comment
+            let line = line.split('#').next().unwrap_or_default();
+            let mut parts = line.split_whitespace();
+            let pattern = match parts.next() {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+
+            let mut builder = GitignoreBuilder::new(&root_dir);
+            builder.add_line(None, pattern)?;
+            rules.push(CodeOwnersRule {
+                matcher: builder.build()?,
+                owners: parts.map(str::to_owned).collect(),
+            });
+        }
+        Ok(CodeOwners { root_dir, rules })
+    }
+
+    /**
+     * Gets the owners of a source file, or of the closest directory containing
+     * it, from the last rule that matches. Files outside of the root have no owners.
+     */
+    pub fn owners_of(&self, source_file_path: &Path) -> &[String] {
+        let source_file_path = match std::path::absolute(source_file_path) {
+            Ok(path) => path,
+            Err(_) => return &[],
+        };
+        // the matchers expect paths under their root
+        if !source_file_path.starts_with(&self.root_dir) {
+            return &[];
+        }
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                rule.matcher
+                    .matched_path_or_any_parents(&source_file_path, false)
+                    .is_ignore()
+            })
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CodeOwners;
+    use std::env::current_dir;
+    use std::path::Path;
+
+    #[test]
+    fn test_owners_of() {
+        let code_owners = CodeOwners::parse(
+            &current_dir().unwrap(),
+            r#"
+# default owners
+*                   @team-core
+src/ui/             @team-a @team-design
+*.test.ts           @team-qa # tests are owned by qa
+src/ui/generated/
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            code_owners.owners_of(Path::new("README.md")),
+            ["@team-core"]
+        );
+        assert_eq!(
+            code_owners.owners_of(Path::new("src/ui/button/button.ts")),
+            ["@team-a", "@team-design"]
+        );
+        assert_eq!(
+            code_owners.owners_of(Path::new("src/ui/button/button.test.ts")),
+            ["@team-qa"],
+            "should use the last matching rule"
+        );
+        assert!(code_owners
+            .owners_of(Path::new("src/ui/generated/icons.ts"))
+            .is_empty());
+    }
+}
@@ -71,14 +71,14 @@ impl Display for WalkDirsError {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResolvedImportNotFound {
     pub project_local_path_str: String,
     pub source_file_path: String,
     pub import_specifier: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EvaluateFencesError {
     IgnoredDir(ResolvedImportNotFound), // In case the resolve_ts_import finds a file but it matches any ignoredDirs and is not in source_file_map
     NotScanned(ResolvedImportNotFound), // In case resolve_ts_import finds a file but is not in source_file_map and does not match any ignoredDirs (e.g. running good-fences only on packages and not in shared)
@@ -3,9 +3,11 @@ use crate::fence::{DependencyRule, ExportRule, Fence};
 use crate::fence_collection::FenceCollection;
 use crate::file_extension::no_ext;
 use crate::walk_dirs::SourceFile;
-use glob::Pattern;
-use import_resolver::manual_resolver::{resolve_ts_import, ResolvedImport, SOURCE_EXTENSIONS};
+use import_resolver::manual_resolver::{
+    is_bare_specifier, resolve_ts_import, ResolveOptions, ResolvedImport, SOURCE_EXTENSIONS,
+};
 use path_slash::PathBufExt;
+use path_utils::{join_normalized, Glob};
 use relative_path::RelativePath;
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
@@ -20,8 +22,15 @@ pub enum ViolatedFenceClause<'a> {
     ExportRule(Option<&'a ExportRule>),
     DependencyRule(Option<&'a DependencyRule>),
     ImportAllowList,
+    NoDefaultExports,
 }
 
+/**
+ * The import specifier reported for violations of `"noDefaultExports"`,
+ * which are caused by the file's own exports rather than by one of its imports
+ */
+pub const DEFAULT_EXPORT_SPECIFIER: &str = "export default";
+
 #[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct ImportRuleViolation<'fencelifetime, 'importlifetime> {
     pub violating_file_path: &'importlifetime str,
@@ -67,7 +76,11 @@ impl Display for ImportRuleViolation<'_, '_> {
                             .as_ref()
                             .unwrap_or(vec![].as_ref()),
                         self.violating_file_path
-                    )
+                    )?;
+                    match &rule.version_range {
+                        Some(version_range) => write!(f, " (versionRange {})", version_range),
+                        None => Ok(()),
+                    }
                 } else {
                     write!(
                         f,
@@ -92,6 +105,13 @@ impl Display for ImportRuleViolation<'_, '_> {
                     &self.violating_fence.fence_path,
                 )
             }
+            ViolatedFenceClause::NoDefaultExports => {
+                write!(
+                    f,
+                    "Violation: File {} declares a default export, which the fence {} does not allow",
+                    self.violating_file_path, &self.violating_fence.fence_path,
+                )
+            }
         }
     }
 }
@@ -100,6 +120,21 @@ impl Display for ImportRuleViolation<'_, '_> {
 pub struct FenceEvaluationResult<'fencelifetime, 'importlifetime> {
     pub violations: Vec<ImportRuleViolation<'fencelifetime, 'importlifetime>>,
     pub unresolved_files: Vec<EvaluateFencesError>,
+    // what the evaluation covered, to tell an empty result from an empty walk
+    pub stats: FenceEvaluationStats,
+}
+
+/**
+ * Counts of what an evaluation of the whole walk covered
+ */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FenceEvaluationStats {
+    // source files whose imports were evaluated, excluding ignored files
+    pub files_scanned: usize,
+    // fences discovered by the walk
+    pub fences_loaded: usize,
+    // imports of the scanned files that were checked against the fences
+    pub imports_checked: usize,
 }
 
 impl Default for FenceEvaluationResult<'_, '_> {
@@ -113,53 +148,404 @@ impl FenceEvaluationResult<'_, '_> {
         Self {
             violations: Vec::new(),
             unresolved_files: Vec::new(),
+            stats: FenceEvaluationStats::default(),
         }
     }
 }
 
 fn is_node_dependency_matching(
+    rule_globs: &RuleGlobs,
     permitted_node_dependency_pattern: &str,
     node_dependency: &str,
 ) -> bool {
     if permitted_node_dependency_pattern == node_dependency {
         return true;
     }
-    let export_rule_glob = Pattern::new(permitted_node_dependency_pattern);
+    match rule_globs
+        .dependency_globs
+        .get(permitted_node_dependency_pattern)
+    {
+        Some(Some(glob)) => glob.matches(node_dependency),
+        _ => false,
+    }
+}
 
-    match export_rule_glob {
-        Ok(glob) => glob.matches(node_dependency),
-        Err(_e) => false,
+/**
+ * Finds the dependency rules that apply to an import of a package, by the name
+ * of the package. Rules with a `versionRange` only apply if the version of the
+ * package is known and matches the range.
+ */
+fn matching_dependency_rules<'a>(
+    rule_globs: &RuleGlobs,
+    dependency_rules: &'a [DependencyRule],
+    package_name: &str,
+    package_version: Option<&semver::Version>,
+) -> Vec<&'a DependencyRule> {
+    dependency_rules
+        .iter()
+        .filter(|dependency| {
+            is_node_dependency_matching(rule_globs, &dependency.dependency, package_name)
+                && match dependency.parsed_version_range() {
+                    None => true,
+                    Some(Ok(version_range)) => {
+                        package_version.is_some_and(|version| version_range.matches(version))
+                    }
+                    // version ranges are validated when the fence is parsed
+                    Some(Err(_)) => false,
+                }
+        })
+        .collect()
+}
+
+/**
+ * Gets the name of the package that a bare import specifier imports from,
+ * e.g. `@scope/name` for `@scope/name/lib/file`. None for relative imports.
+ */
+fn bare_package_name(import_specifier: &str) -> Option<&str> {
+    if import_specifier.starts_with('.') || Path::new(import_specifier).has_root() {
+        return None;
     }
+    let mut segments = import_specifier.splitn(3, '/');
+    let first_segment = segments.next()?;
+    let name_len = if first_segment.starts_with('@') {
+        first_segment.len() + 1 + segments.next()?.len()
+    } else {
+        first_segment.len()
+    };
+    Some(&import_specifier[..name_len])
 }
 
-fn export_rule_applies_to_import_path(
+pub(crate) fn export_rule_applies_to_import_path(
+    rule_globs: &RuleGlobs,
     fence_path: &str,
     export_rule: &ExportRule,
     imported_file_path: &Path,
-) -> Result<bool, glob::PatternError> {
-    let mut buf = PathBuf::from(fence_path);
-    buf.pop();
-    buf.push(export_rule.modules.clone());
-    let export_rule_glob = Pattern::new(buf.to_str().unwrap());
-
-    match export_rule_glob {
-        Ok(glob) => Ok(glob.matches(imported_file_path.to_str().unwrap())
-            || glob.matches(no_ext(imported_file_path.to_str().unwrap()))),
-        Err(e) => Err(e),
+) -> bool {
+    let glob = match rule_globs
+        .export_rule_globs
+        .get(fence_path)
+        .and_then(|fence_globs| fence_globs.get(&export_rule.modules))
+    {
+        Some(Some(glob)) => glob,
+        _ => return false,
+    };
+    let imported_file_path = join_normalized("", imported_file_path);
+    match imported_file_path.to_str() {
+        Some(imported_file_str) => {
+            glob.matches(imported_file_str) || glob.matches(no_ext(imported_file_str))
+        }
+        None => {
+            eprintln!(
+                "Warning: skipping export rule {:?} of {}, because {:?} is not valid UTF-8",
+                export_rule.modules, fence_path, imported_file_path
+            );
+            false
+        }
     }
 }
 
-fn is_importer_allowed(accessible_to: &[String], source_file: &SourceFile) -> bool {
+fn is_importer_allowed(
+    accessible_to_globs: &AccessibleToGlobs,
+    accessible_to: &[String],
+    source_file: &SourceFile,
+) -> bool {
     accessible_to.iter().any(|accessible_to_tag| {
-        accessible_to_tag == "*" || source_file.tags.contains(accessible_to_tag)
+        accessible_to_globs.allows_any_tag(
+            accessible_to_tag,
+            source_file.tags.iter().map(String::as_str),
+        )
     })
 }
 
+/**
+ * The `accessibleTo` entries of the export and dependency rules of a set of
+ * fences that are glob patterns, compiled once up front instead of on every check.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct AccessibleToGlobs {
+    // None if the entry is not a valid glob
+    globs: HashMap<String, Option<Glob>>,
+}
+
+// the globs are compiled from the entries, so comparing the entries is enough
+impl PartialEq for AccessibleToGlobs {
+    fn eq(&self, other: &Self) -> bool {
+        self.globs.len() == other.globs.len()
+            && self
+                .globs
+                .keys()
+                .all(|accessible_to_tag| other.globs.contains_key(accessible_to_tag))
+    }
+}
+
+impl AccessibleToGlobs {
+    pub fn from_fences<'a>(fences: impl IntoIterator<Item = &'a Fence>) -> Self {
+        let globs = fences
+            .into_iter()
+            .flat_map(|fence| {
+                let export_tags = fence
+                    .fence
+                    .exports
+                    .iter()
+                    .flatten()
+                    .flat_map(|rule| rule.accessible_to.iter());
+                let dependency_tags = fence
+                    .fence
+                    .dependencies
+                    .iter()
+                    .flatten()
+                    .flat_map(|rule| rule.accessible_to.iter());
+                export_tags.chain(dependency_tags)
+            })
+            .filter(|accessible_to_tag| is_accessible_to_glob(accessible_to_tag))
+            .map(|accessible_to_tag| (accessible_to_tag.clone(), Glob::new(accessible_to_tag).ok()))
+            .collect();
+        AccessibleToGlobs { globs }
+    }
+
+    /**
+     * Checks if an `accessibleTo` entry allows any of the given tags. Entries with
+     * glob metacharacters are matched as globs, so `team-*` allows `team-foo`, while
+     * other entries are compared literally. `*` allows every tag.
+     */
+    pub fn allows_any_tag<'a>(
+        &self,
+        accessible_to_tag: &str,
+        mut tags: impl Iterator<Item = &'a str>,
+    ) -> bool {
+        if accessible_to_tag == "*" {
+            return true;
+        }
+        if !is_accessible_to_glob(accessible_to_tag) {
+            return tags.any(|tag| tag == accessible_to_tag);
+        }
+        let compiled_glob;
+        let glob = match self.globs.get(accessible_to_tag) {
+            Some(glob) => glob.as_ref(),
+            // entries that are not part of the fences, e.g. tags of an import allow list
+            None => {
+                compiled_glob = Glob::new(accessible_to_tag).ok();
+                compiled_glob.as_ref()
+            }
+        };
+        match glob {
+            Some(glob) => tags.any(|tag| glob.matches(tag)),
+            // an invalid pattern can still name a tag literally
+            None => tags.any(|tag| tag == accessible_to_tag),
+        }
+    }
+}
+
+fn is_accessible_to_glob(accessible_to_tag: &str) -> bool {
+    accessible_to_tag != "*" && accessible_to_tag.contains(['*', '?', '[', '{'])
+}
+
+/**
+ * The `dependency` patterns of the dependency rules and the `modules` patterns of
+ * the export rules of a set of fences, compiled once up front instead of on every check.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct RuleGlobs {
+    // keyed by the dependency pattern, None if it is not a valid glob
+    dependency_globs: HashMap<String, Option<Glob>>,
+    // keyed by the path of the fence, then by the modules pattern of the export rule
+    export_rule_globs: HashMap<String, HashMap<String, Option<Glob>>>,
+}
+
+// the globs are compiled from the patterns, so comparing the patterns is enough
+impl PartialEq for RuleGlobs {
+    fn eq(&self, other: &Self) -> bool {
+        self.dependency_globs.len() == other.dependency_globs.len()
+            && self
+                .dependency_globs
+                .keys()
+                .all(|dependency| other.dependency_globs.contains_key(dependency))
+            && self.export_rule_globs.len() == other.export_rule_globs.len()
+            && self
+                .export_rule_globs
+                .iter()
+                .all(|(fence_path, fence_globs)| {
+                    other
+                        .export_rule_globs
+                        .get(fence_path)
+                        .is_some_and(|other_fence_globs| {
+                            fence_globs.len() == other_fence_globs.len()
+                                && fence_globs
+                                    .keys()
+                                    .all(|modules| other_fence_globs.contains_key(modules))
+                        })
+                })
+    }
+}
+
+impl RuleGlobs {
+    pub fn from_fences<'a>(fences: impl IntoIterator<Item = &'a Fence>) -> Self {
+        let mut rule_globs = RuleGlobs::default();
+        for fence in fences {
+            for dependency_rule in fence.fence.dependencies.iter().flatten() {
+                rule_globs
+                    .dependency_globs
+                    .entry(dependency_rule.dependency.clone())
+                    .or_insert_with(|| Glob::new(&dependency_rule.dependency).ok());
+            }
+            let fence_dir = Path::new(&fence.fence_path)
+                .parent()
+                .unwrap_or(Path::new(""));
+            let fence_globs = rule_globs
+                .export_rule_globs
+                .entry(fence.fence_path.clone())
+                .or_default();
+            for export_rule in fence.fence.exports.iter().flatten() {
+                // export rules are relative to the directory of their fence
+                let export_rule_path = join_normalized(fence_dir, &export_rule.modules);
+                fence_globs
+                    .entry(export_rule.modules.clone())
+                    .or_insert_with(|| {
+                        export_rule_path
+                            .to_str()
+                            .and_then(|export_rule_str| Glob::new(export_rule_str).ok())
+                    });
+            }
+        }
+        rule_globs
+    }
+}
+
+/**
+ * Resolves an import of a source file to the node module or the project-local
+ * source file (with its extension) that it refers to
+ */
+pub fn resolve_source_file_import(
+    tsconfig_paths_json: &TsconfigPathsJson,
+    source_file_path: &str,
+    import_specifier: &str,
+    resolve_options: &ResolveOptions,
+) -> anyhow::Result<ResolvedImport> {
+    let importer_rel_path = RelativePath::from_path(source_file_path).unwrap();
+    let resolved_src_import = resolve_ts_import(
+        tsconfig_paths_json,
+        importer_rel_path,
+        import_specifier,
+        resolve_options,
+    );
+    match resolved_src_import {
+        Ok(resolved_import) => match &resolved_import {
+            ResolvedImport::ProjectLocalImport(local_path) => {
+                let with_ext = SOURCE_EXTENSIONS.iter().find_map(|ext| {
+                    local_path
+                        .with_extension(ext)
+                        .exists()
+                        .then(|| local_path.with_extension(ext))
+                });
+                match with_ext {
+                    Some(with_ext) => Ok(ResolvedImport::ProjectLocalImport(with_ext)),
+                    None => {
+                        let with_index = local_path.join("index");
+                        let with_index_ext = SOURCE_EXTENSIONS.iter().find_map(|ext| {
+                            with_index
+                                .with_extension(ext)
+                                .exists()
+                                .then(|| with_index.with_extension(ext))
+                        });
+                        match with_index_ext {
+                            Some(with_index_ext) => {
+                                Ok(ResolvedImport::ProjectLocalImport(with_index_ext))
+                            }
+                            // e.g. a tsconfig path to a package that is not built or installed
+                            None if resolve_options.assume_unresolved_bare_as_external
+                                && is_bare_specifier(import_specifier) =>
+                            {
+                                Ok(ResolvedImport::NodeModulesImport(
+                                    import_specifier.to_owned(),
+                                ))
+                            }
+                            None => Err(anyhow::Error::msg(format!("Unable to resolve path for import specifier {:?} in source file {}", &local_path, source_file_path)))   
+                        }
+                    }
+                }
+            }
+            _ => Ok(resolved_import),
+        },
+        Err(e) => Err(anyhow::Error::msg(e)),
+    }
+}
+
+/**
+ * The imports of a source file by import specifier, resolved to what they
+ * refer to. None if the import could not be resolved.
+ */
+pub type ResolvedImports = HashMap<String, Option<ResolvedImport>>;
+
+/**
+ * Resolves each of the imports of a source file
+ */
+pub fn resolve_source_file_imports(
+    tsconfig_paths_json: &TsconfigPathsJson,
+    source_file: &SourceFile,
+    resolve_options: &ResolveOptions,
+) -> ResolvedImports {
+    source_file
+        .imports
+        .keys()
+        .map(|import_specifier| {
+            let resolved_import = resolve_source_file_import(
+                tsconfig_paths_json,
+                &source_file.source_file_path,
+                import_specifier,
+                resolve_options,
+            )
+            .ok();
+            (import_specifier.clone(), resolved_import)
+        })
+        .collect()
+}
+
+/**
+ * Finds the walked source file that a project-local import resolved to:
+ * either the file itself, or the index file of a directory
+ */
+pub fn find_imported_source_file<'a>(
+    source_files: &'a HashMap<String, SourceFile>,
+    project_local_path: &Path,
+) -> Option<&'a SourceFile> {
+    let project_local_path_str = project_local_path.to_slash().unwrap().to_string();
+    source_files
+        .get(project_local_path_str.as_str())
+        .or_else(|| {
+            let path_with_idx_str = project_local_path
+                .join("index")
+                .to_slash()
+                .unwrap()
+                .to_string();
+            source_files.get(path_with_idx_str.as_str())
+        })
+}
+
 pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
     fence_collection: &'fencecollectionlifetime FenceCollection,
     source_files: &HashMap<String, SourceFile>,
     source_file: &'sourcefilelifetime SourceFile,
     tsconfig_paths_json: &'sourcefilelifetime TsconfigPathsJson,
+    resolve_options: &ResolveOptions,
+) -> FenceEvaluationResult<'fencecollectionlifetime, 'sourcefilelifetime> {
+    let resolved_imports =
+        resolve_source_file_imports(tsconfig_paths_json, source_file, resolve_options);
+    evaluate_resolved_fences(
+        fence_collection,
+        source_files,
+        source_file,
+        &resolved_imports,
+    )
+}
+
+/**
+ * Same as `evaluate_fences`, with the imports of the source file already resolved
+ */
+pub fn evaluate_resolved_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
+    fence_collection: &'fencecollectionlifetime FenceCollection,
+    source_files: &HashMap<String, SourceFile>,
+    source_file: &'sourcefilelifetime SourceFile,
+    resolved_imports: &ResolvedImports,
 ) -> FenceEvaluationResult<'fencecollectionlifetime, 'sourcefilelifetime> {
     let mut violations = Vec::<ImportRuleViolation>::new();
     let mut unresolved_files = Vec::<EvaluateFencesError>::new();
@@ -172,79 +558,46 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
     // the same goes for exported files
     let source_fences_set: HashSet<&Fence> = HashSet::from_iter(source_fences);
 
+    // check the file's own exports against the fences governing it
+    if source_file.has_default_export {
+        for source_fence in source_fences_set.iter() {
+            if source_fence.forbids_default_exports() {
+                violations.push(ImportRuleViolation {
+                    violating_file_path: &source_file.source_file_path,
+                    violating_fence: source_fence,
+                    violating_fence_clause: ViolatedFenceClause::NoDefaultExports,
+                    violating_import_specifier: DEFAULT_EXPORT_SPECIFIER,
+                    violating_imported_name: None,
+                })
+            }
+        }
+    }
+
     for (import_specifier, _imported_names) in source_file.imports.iter() {
-        let importer_rel_path = RelativePath::from_path(&source_file.source_file_path).unwrap();
-        let resolved_src_import =
-            resolve_ts_import(tsconfig_paths_json, importer_rel_path, import_specifier);
-        let resolved_import = match resolved_src_import {
-            Ok(resolved_import) => match &resolved_import {
-                ResolvedImport::ProjectLocalImport(import_specifier) => {
-                    let with_ext = SOURCE_EXTENSIONS.iter().find_map(|ext| {
-                        import_specifier
-                            .with_extension(ext)
-                            .exists()
-                            .then(|| import_specifier.with_extension(ext))
-                    });
-                    match with_ext {
-                        Some(with_ext) => Ok(ResolvedImport::ProjectLocalImport(with_ext)),
-                        None => {
-                            let with_index = import_specifier.join("index");
-                            let with_index_ext = SOURCE_EXTENSIONS.iter().find_map(|ext| {
-                                with_index
-                                    .with_extension(ext)
-                                    .exists()
-                                    .then(|| with_index.with_extension(ext))
-                            });
-                            match with_index_ext {
-                                Some(with_index_ext) => {
-                                    Ok(ResolvedImport::ProjectLocalImport(with_index_ext))
-                                }
-                                None => Err(anyhow::Error::msg(format!("Unable to resolve path for import specifier {:?} in source file {}", &import_specifier, source_file.source_file_path)))   
-                            }
-                        }
-                    }
-                }
-                _ => Ok(resolved_import),
-            },
-            Err(e) => Err(anyhow::Error::msg(e)),
-        };
+        let resolved_import = resolved_imports.get(import_specifier).cloned().flatten();
 
         match resolved_import {
-            Ok(resolved_import) => match resolved_import {
+            Some(resolved_import) => match resolved_import {
                 // grab the project local file, check our tags against the exports of the
                 // fences of the file we are importing.
                 ResolvedImport::ProjectLocalImport(project_local_path) => {
-                    let slashed_project_local_path = project_local_path.to_slash().unwrap();
-                    let project_local_path_str = slashed_project_local_path.to_string();
-                    let project_local_path_str = project_local_path_str.as_str();
-
-                    let imported_source_file_opt = source_files.get(project_local_path_str);
-                    let imported_source_file_with_idx_opt = if imported_source_file_opt.is_none() {
-                        let mut clone_path_with_idx = project_local_path.clone();
-                        clone_path_with_idx.push("index");
-                        let clone_path_with_idx_str =
-                            clone_path_with_idx.to_slash().unwrap().to_string();
-                        source_files.get(clone_path_with_idx_str.as_str())
-                    } else {
-                        None
-                    };
-
-                    let imported_source_file = match imported_source_file_opt {
-                        None => match imported_source_file_with_idx_opt {
+                    let imported_source_file =
+                        match find_imported_source_file(source_files, &project_local_path) {
                             Some(x) => x,
                             None => {
                                 unresolved_files.push(EvaluateFencesError::NotScanned(
                                     ResolvedImportNotFound {
-                                        project_local_path_str: project_local_path_str.to_string(),
+                                        project_local_path_str: project_local_path
+                                            .to_slash()
+                                            .unwrap()
+                                            .to_string(),
                                         source_file_path: source_file.source_file_path.clone(),
                                         import_specifier: import_specifier.to_owned(),
                                     },
                                 ));
                                 continue;
                             }
-                        },
-                        Some(x) => x,
-                    };
+                        };
 
                     let imported_file_path =
                         &PathBuf::from(imported_source_file.source_file_path.clone());
@@ -298,11 +651,11 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
                                     .iter()
                                     .filter(|export_rule| {
                                         export_rule_applies_to_import_path(
+                                            &fence_collection.rule_globs,
                                             &destination_fence.fence_path,
                                             export_rule,
                                             imported_file_path,
                                         )
-                                        .unwrap()
                                     })
                                     .collect();
                             if destination_export_rules.is_empty() {
@@ -317,7 +670,11 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
                             }
                             let any_destination_export_rule_allows_import =
                                 destination_export_rules.iter().any(|clause| {
-                                    is_importer_allowed(&clause.accessible_to, source_file)
+                                    is_importer_allowed(
+                                        &fence_collection.accessible_to_globs,
+                                        &clause.accessible_to,
+                                        source_file,
+                                    )
                                 });
 
                             if !any_destination_export_rule_allows_import {
@@ -338,6 +695,55 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
                             }
                         }
                     }
+
+                    // imports of workspace packages by name are also checked against
+                    // the dependency rules naming the package. Unlike node modules,
+                    // packages that are not on the dependency list are allowed.
+                    // Other bare imports, e.g. of tsconfig path aliases, are not checked.
+                    let workspace_package =
+                        bare_package_name(import_specifier).and_then(|package_name| {
+                            fence_collection
+                                .workspace_packages
+                                .package_of(imported_file_path)
+                                .filter(|package| package.name == package_name)
+                        });
+                    if let Some(workspace_package) = workspace_package {
+                        for source_fence in exclusive_source_fences.iter() {
+                            let matching_dependency_clauses: Vec<
+                                &'fencecollectionlifetime DependencyRule,
+                            > = matching_dependency_rules(
+                                &fence_collection.rule_globs,
+                                source_fence
+                                    .fence
+                                    .dependencies
+                                    .as_deref()
+                                    .unwrap_or_default(),
+                                &workspace_package.name,
+                                workspace_package.version.as_ref(),
+                            );
+                            if matching_dependency_clauses.iter().any(|clause| {
+                                is_importer_allowed(
+                                    &fence_collection.accessible_to_globs,
+                                    &clause.accessible_to,
+                                    source_file,
+                                )
+                            }) {
+                                continue;
+                            }
+                            for dependency_clause in matching_dependency_clauses {
+                                // violation: the package is not exposed to tags for this file
+                                violations.push(ImportRuleViolation {
+                                    violating_file_path: &source_file.source_file_path,
+                                    violating_fence: source_fence,
+                                    violating_fence_clause: ViolatedFenceClause::DependencyRule(
+                                        Some(dependency_clause),
+                                    ),
+                                    violating_import_specifier: import_specifier,
+                                    violating_imported_name: None,
+                                })
+                            }
+                        }
+                    }
                 }
                 // node imports: check the tags against the source fence allow list
                 ResolvedImport::NodeModulesImport(node_module_filter) => {
@@ -345,18 +751,16 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
                         // only filter on dependencies if there is a dependency list
                         if let Some(allowed_dependencies) = source_fence.fence.dependencies.as_ref()
                         {
+                            // the versions of node modules are not known, so rules
+                            // with a version range never apply to them
                             let matching_dependency_clauses: Vec<
                                 &'fencecollectionlifetime DependencyRule,
-                            > = allowed_dependencies
-                                .iter()
-                                // TODO handle glob dependency matches
-                                .filter(|dependency| {
-                                    is_node_dependency_matching(
-                                        &dependency.dependency,
-                                        &node_module_filter,
-                                    )
-                                })
-                                .collect();
+                            > = matching_dependency_rules(
+                                &fence_collection.rule_globs,
+                                allowed_dependencies,
+                                &node_module_filter,
+                                None,
+                            );
                             if matching_dependency_clauses.is_empty() {
                                 // violation: dependency not on allowlist
                                 violations.push(ImportRuleViolation {
@@ -372,7 +776,11 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
                                 // if any of the applicable clauses allow the import, allow it.
                                 let any_matching_dependency_clauses_allows_import =
                                     matching_dependency_clauses.iter().any(|clause| {
-                                        is_importer_allowed(&clause.accessible_to, source_file)
+                                        is_importer_allowed(
+                                            &fence_collection.accessible_to_globs,
+                                            &clause.accessible_to,
+                                            source_file,
+                                        )
                                     });
                                 if !any_matching_dependency_clauses_allows_import {
                                     // none of the applicable clauses allow this import
@@ -400,7 +808,7 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
                 // do nothing for resource file imports
                 ResolvedImport::ResourceFileImport => {}
             },
-            Err(_) => {
+            None => {
                 unresolved_files.push(EvaluateFencesError::ImportNotResolved {
                     import_specifier: import_specifier.clone(),
                     source_file_path: source_file.source_file_path.to_string(),
@@ -412,19 +820,30 @@ pub fn evaluate_fences<'fencecollectionlifetime, 'sourcefilelifetime>(
     FenceEvaluationResult {
         violations,
         unresolved_files,
+        stats: FenceEvaluationStats::default(),
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::evaluate_fences::{evaluate_fences, ImportRuleViolation, ViolatedFenceClause};
+    use crate::error::{EvaluateFencesError, ResolvedImportNotFound};
+    use crate::evaluate_fences::{
+        evaluate_fences, export_rule_applies_to_import_path, is_node_dependency_matching,
+        AccessibleToGlobs, ImportRuleViolation, RuleGlobs, ViolatedFenceClause,
+        DEFAULT_EXPORT_SPECIFIER,
+    };
     use crate::fence::{parse_fence_str, DependencyRule, ExportRule};
     use crate::fence_collection::FenceCollection;
+    use crate::get_imports::parse_source_file;
     use crate::walk_dirs::SourceFile;
+    use import_resolver::manual_resolver::ResolveOptions;
     use lazy_static::lazy_static;
     use relative_path::RelativePathBuf;
+    use serde_json::json;
     use std::collections::{HashMap, HashSet};
     use std::iter::FromIterator;
+    use std::path::Path;
+    use test_fixtures::FixtureRepo;
     use tsconfig_paths::{TsconfigPathsCompilerOptions, TsconfigPathsJson};
 
     macro_rules! map(
@@ -451,36 +870,74 @@ mod test {
         };
     );
 
-    lazy_static! {
-        static ref SOURCE_FILES: HashMap<String, SourceFile> = map!(
-            "tests/evaluate_fences/path/to/source/index.ts" => SourceFile {
+    fn fixture_repo() -> FixtureRepo {
+        FixtureRepo::new()
+            .file(
+                "path/to/source/index.ts",
+                r#"import { internal } from "../protected/internal";"#,
+            )
+            .file(
+                "path/to/source/friend/index.ts",
+                r#"import { internal } from "../../protected/internal";"#,
+            )
+            .file(
+                "path/to/protected/internal.ts",
+                "export const internal = 1;",
+            )
+    }
+
+    // source files of the fixture repo, keyed by their paths relative to the
+    // working directory like the walked source files
+    fn source_files(repo: &FixtureRepo) -> HashMap<String, SourceFile> {
+        map!(
+            repo.relative_path("path/to/source/index.ts") => SourceFile {
                 tags: HashSet::new(),
-                source_file_path: "tests/evaluate_fences/path/to/source/index.ts".to_owned(),
+                source_file_path: repo.relative_path("path/to/source/index.ts"),
+                has_default_export: false,
                 imports: map!(
                         "../protected/internal" => Option::None,
                         "node:querystring" => Option::None
                     ),
 
             },
-            "tests/evaluate_fences/path/to/source/friend/index.ts" => SourceFile {
+            repo.relative_path("path/to/source/friend/index.ts") => SourceFile {
                 tags: set!(
                     "friend"
                 ),
-                source_file_path: "tests/evaluate_fences/path/to/source/friend/index.ts".to_owned(),
+                source_file_path: repo.relative_path("path/to/source/friend/index.ts"),
+                has_default_export: false,
                 imports: map!(
                         "../../protected/internal" => Option::None,
                         "node:querystring" => Option::None
                     ),
 
             },
-            "tests/evaluate_fences/path/to/protected/internal.ts" => SourceFile {
+            repo.relative_path("path/to/protected/internal.ts") => SourceFile {
                 tags: set!(
                     "protected"
                 ),
-                source_file_path: "tests/evaluate_fences/path/to/protected/internal.ts".to_owned(),
+                source_file_path: repo.relative_path("path/to/protected/internal.ts"),
+                has_default_export: false,
                 imports: HashMap::new(),
             }
-        );
+        )
+    }
+
+    fn fence_collection(repo: &FixtureRepo) -> FenceCollection {
+        FenceCollection::new(
+            repo.fence_paths()
+                .iter()
+                .map(|fence_path| {
+                    let fence_str = std::fs::read_to_string(repo.root_join(fence_path)).unwrap();
+                    let fence_path = repo.relative_path(fence_path);
+                    let fence =
+                        parse_fence_str(&fence_str, &RelativePathBuf::from(fence_path.as_str()))
+                            .unwrap();
+                    (fence_path, fence)
+                })
+                .collect(),
+            None,
+        )
     }
 
     lazy_static! {
@@ -494,35 +951,27 @@ mod test {
 
     #[test]
     pub fn test_imports_allow_list_empty_violation() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{"imports": []}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo()
+            .fence("path/to/source", json!({"imports": []}))
+            .fence("path/to/protected", json!({"tags": ["protected"]}));
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/source/fence.json")
+                    .get(&repo.relative_path("path/to/source/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::ImportAllowList,
                 violating_import_specifier: "../protected/internal",
@@ -533,35 +982,27 @@ mod test {
 
     #[test]
     pub fn test_imports_allow_list_mismatch_violation() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{"imports": ["some_tag"]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo()
+            .fence("path/to/source", json!({"imports": ["some_tag"]}))
+            .fence("path/to/protected", json!({"tags": ["protected"]}));
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/source/fence.json")
+                    .get(&repo.relative_path("path/to/source/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::ImportAllowList,
                 violating_import_specifier: "../protected/internal",
@@ -572,35 +1013,28 @@ mod test {
 
     #[test]
     pub fn test_imports_exports_list_empty() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"], "exports": []}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo().fence("path/to/source", json!({})).fence(
+            "path/to/protected",
+            json!({"tags": ["protected"], "exports": []}),
+        );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/protected/fence.json")
+                    .get(&repo.relative_path("path/to/protected/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::ExportRule(Option::None),
                 violating_import_specifier: "../protected/internal",
@@ -611,35 +1045,28 @@ mod test {
 
     #[test]
     pub fn test_imports_exports_list_mismatch() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"], "exports": ["protected-exposed"]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo().fence("path/to/source", json!({})).fence(
+            "path/to/protected",
+            json!({"tags": ["protected"], "exports": ["protected-exposed"]}),
+        );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/protected/fence.json")
+                    .get(&repo.relative_path("path/to/protected/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::ExportRule(Option::None),
                 violating_import_specifier: "../protected/internal",
@@ -650,32 +1077,30 @@ mod test {
 
     #[test]
     pub fn test_imports_exports_list_conflicting_match_allowed() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/source/friend/fence.json" => parse_fence_str(
-                    r#"{
+        let repo = fixture_repo()
+            .fence(
+                "path/to/source",
+                json!({})
+            )
+            .fence(
+                "path/to/source/friend",
+                json!({
                         "tags": ["friend"]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/friend/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"], "exports": [{"modules": "*", "accessibleTo": "test"}, {"modules": "*", "accessibleTo": "friend"}]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+                    })
+            )
+            .fence(
+                "path/to/protected",
+                json!({"tags": ["protected"], "exports": [{"modules": "*", "accessibleTo": "test"}, {"modules": "*", "accessibleTo": "friend"}]})
+            );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/friend/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/friend/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(violations.violations, Vec::new());
@@ -683,31 +1108,24 @@ mod test {
 
     #[test]
     pub fn test_imports_exports_list_not_on_allow_list() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"], "exports": [{
-                         "modules": "internal.ts",
-                         "accessibleTo": [
-                             "nothing"
-                         ]
-                    }]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo().fence("path/to/source", json!({})).fence(
+            "path/to/protected",
+            json!({"tags": ["protected"], "exports": [{
+                 "modules": "internal.ts",
+                 "accessibleTo": [
+                     "nothing"
+                 ]
+            }]}),
+        );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         let d = ExportRule {
@@ -718,10 +1136,10 @@ mod test {
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/protected/fence.json")
+                    .get(&repo.relative_path("path/to/protected/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::ExportRule(Some(&d)),
                 violating_import_specifier: "../protected/internal",
@@ -732,31 +1150,24 @@ mod test {
 
     #[test]
     pub fn test_imports_exports_list_not_on_allow_list_glob() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"], "exports": [{
-                         "modules": "*.ts",
-                         "accessibleTo": [
-                             "nothing"
-                         ]
-                    }]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo().fence("path/to/source", json!({})).fence(
+            "path/to/protected",
+            json!({"tags": ["protected"], "exports": [{
+                 "modules": "*.ts",
+                 "accessibleTo": [
+                     "nothing"
+                 ]
+            }]}),
+        );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         let d = ExportRule {
@@ -767,10 +1178,10 @@ mod test {
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/protected/fence.json")
+                    .get(&repo.relative_path("path/to/protected/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::ExportRule(Some(&d)),
                 violating_import_specifier: "../protected/internal",
@@ -781,69 +1192,182 @@ mod test {
 
     #[test]
     pub fn test_imports_exports_list_on_allow_list_glob() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/source/friend/fence.json" => parse_fence_str(
-                    r#"{
-                        "tags": ["friend"]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/friend/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/protected/fence.json" => parse_fence_str(
-                    r#"{"tags": ["protected"], "exports": [{
-                         "modules": "*.ts",
-                         "accessibleTo": [
-                             "friend"
-                         ]
-                    }]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/protected/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo()
+            .fence("path/to/source", json!({}))
+            .fence(
+                "path/to/source/friend",
+                json!({
+                    "tags": ["friend"]
+                }),
+            )
+            .fence(
+                "path/to/protected",
+                json!({"tags": ["protected"], "exports": [{
+                     "modules": "*.ts",
+                     "accessibleTo": [
+                         "friend"
+                     ]
+                }]}),
+            );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/friend/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/friend/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(violations.violations, Vec::new());
     }
 
+    #[test]
+    pub fn test_imports_exports_list_on_allow_list_alternation_glob() {
+        let repo = fixture_repo()
+            .fence("path/to/source", json!({}))
+            .fence(
+                "path/to/source/friend",
+                json!({
+                    "tags": ["friend"]
+                }),
+            )
+            .fence(
+                "path/to/protected",
+                json!({"tags": ["protected"], "exports": [{
+                     "modules": "**/{internal,public}.ts",
+                     "accessibleTo": [
+                         "friend"
+                     ]
+                }]}),
+            );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
+
+        let violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/friend/index.ts")],
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
+        );
+
+        assert_eq!(violations.violations, Vec::new());
+    }
+
+    // evaluates the import of the protected module by a file with a single tag
+    fn evaluate_import_with_tag(
+        fence_collection: &FenceCollection,
+        repo: &FixtureRepo,
+        tag: &str,
+    ) -> Vec<String> {
+        let mut source_files = source_files(repo);
+        let importer_path = repo.relative_path("path/to/source/friend/index.ts");
+        source_files.get_mut(&importer_path).unwrap().tags = set!(tag);
+
+        evaluate_fences(
+            fence_collection,
+            &source_files,
+            &source_files[&importer_path],
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
+        )
+        .violations
+        .iter()
+        .map(|violation| violation.violating_import_specifier.to_owned())
+        .collect()
+    }
+
+    #[test]
+    pub fn test_imports_exports_list_accessible_to_tag_glob() {
+        let repo = fixture_repo().fence(
+            "path/to/protected",
+            json!({"tags": ["protected"], "exports": [{
+                 "modules": "internal.ts",
+                 "accessibleTo": [
+                     "team-*"
+                 ]
+            }]}),
+        );
+        let fence_collection = fence_collection(&repo);
+
+        assert_eq!(
+            evaluate_import_with_tag(&fence_collection, &repo, "team-foo"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            evaluate_import_with_tag(&fence_collection, &repo, "otherteam"),
+            vec!["../../protected/internal".to_owned()]
+        );
+    }
+
+    #[test]
+    pub fn test_imports_exports_list_accessible_to_wildcard() {
+        let repo = fixture_repo().fence(
+            "path/to/protected",
+            json!({"tags": ["protected"], "exports": [{
+                 "modules": "internal.ts",
+                 "accessibleTo": "*"
+            }]}),
+        );
+        let fence_collection = fence_collection(&repo);
+
+        for tag in ["team-foo", "otherteam", "*"] {
+            assert_eq!(
+                evaluate_import_with_tag(&fence_collection, &repo, tag),
+                Vec::<String>::new()
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_accessible_to_allows_any_tag() {
+        let fence = parse_fence_str(
+            r#"{"exports": [{"modules": "*", "accessibleTo": ["team-*", "team-{a,b}", "team-["]}]}"#,
+            &RelativePathBuf::from("path/to/fence.json"),
+        )
+        .unwrap();
+        // entries that were not compiled from the fences are compiled when checked
+        for accessible_to_globs in [
+            AccessibleToGlobs::from_fences([&fence]),
+            AccessibleToGlobs::default(),
+        ] {
+            let allows = |accessible_to_tag: &str, tag: &str| {
+                accessible_to_globs.allows_any_tag(accessible_to_tag, std::iter::once(tag))
+            };
+            assert!(allows("team-*", "team-foo"));
+            assert!(!allows("team-*", "otherteam"));
+            assert!(allows("team-{a,b}", "team-b"));
+            assert!(allows("*", "anything"));
+            assert!(allows("friend", "friend"));
+            assert!(!allows("friend", "friends"));
+            // invalid globs are compared literally
+            assert!(allows("team-[", "team-["));
+        }
+    }
+
     #[test]
     pub fn test_dependencies_not_allowed_empty_arr() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{"dependencies": []}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo().fence("path/to/source", json!({"dependencies": []}));
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/source/fence.json")
+                    .get(&repo.relative_path("path/to/source/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::DependencyRule(None),
                 violating_import_specifier: "node:querystring",
@@ -854,22 +1378,19 @@ mod test {
 
     #[test]
     pub fn test_dependencies_allowed_on_allow_list() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{"dependencies": ["node:querystring"]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo().fence(
+            "path/to/source",
+            json!({"dependencies": ["node:querystring"]}),
+        );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(violations.violations, Vec::new());
@@ -877,41 +1398,39 @@ mod test {
 
     #[test]
     pub fn test_dependencies_not_allowed_when_not_accessible_to() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{"dependencies": [
-                        {
-                            "dependency": "node:querystring",
-                            "accessibleTo": "some-tag"
-                        }
-                    ]}"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo().fence(
+            "path/to/source",
+            json!({"dependencies": [
+                {
+                    "dependency": "node:querystring",
+                    "accessibleTo": "some-tag"
+                }
+            ]}),
+        );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         let d = DependencyRule {
             dependency: "node:querystring".to_owned(),
             accessible_to: vec!["some-tag".to_owned()],
+            version_range: None,
         };
 
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/source/fence.json")
+                    .get(&repo.relative_path("path/to/source/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::DependencyRule(Some(&d)),
                 violating_import_specifier: "node:querystring",
@@ -922,35 +1441,33 @@ mod test {
 
     #[test]
     pub fn test_dependencies_allowed_when_on_dependency_allow_list() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{
-                        "dependencies": [
-                            {
-                                "dependency": "node:querystring",
-                                "accessibleTo": "friend"
-                            }
-                        ]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/source/friend/fence.json" => parse_fence_str(
-                    r#"{
-                        "tags": ["friend"]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/friend/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo()
+            .fence(
+                "path/to/source",
+                json!({
+                    "dependencies": [
+                        {
+                            "dependency": "node:querystring",
+                            "accessibleTo": "friend"
+                        }
+                    ]
+                }),
+            )
+            .fence(
+                "path/to/source/friend",
+                json!({
+                    "tags": ["friend"]
+                }),
+            );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/friend/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/friend/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         assert_eq!(violations.violations, Vec::new());
@@ -958,49 +1475,48 @@ mod test {
 
     #[test]
     pub fn test_dependencies_not_allowed_when_on_dependency_not_on_allow_list() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{
-                        "dependencies": [
-                            {
-                                "dependency": "node:querystring",
-                                "accessibleTo": "friendzzz"
-                            }
-                        ]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/source/friend/fence.json" => parse_fence_str(
-                    r#"{
-                        "tags": ["friend"]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/friend/fence.json")
-                ).unwrap()
-            ),
-        };
+        let repo = fixture_repo()
+            .fence(
+                "path/to/source",
+                json!({
+                    "dependencies": [
+                        {
+                            "dependency": "node:querystring",
+                            "accessibleTo": "friendzzz"
+                        }
+                    ]
+                }),
+            )
+            .fence(
+                "path/to/source/friend",
+                json!({
+                    "tags": ["friend"]
+                }),
+            );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
 
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/friend/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/friend/index.ts")],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
         let r = DependencyRule {
             dependency: "node:querystring".to_owned(),
             accessible_to: vec!["friendzzz".to_owned()],
+            version_range: None,
         };
 
         assert_eq!(
             violations.violations,
             vec![ImportRuleViolation {
-                violating_file_path: "tests/evaluate_fences/path/to/source/friend/index.ts",
+                violating_file_path: &repo.relative_path("path/to/source/friend/index.ts"),
                 violating_fence: fence_collection
                     .fences_map
-                    .get("tests/evaluate_fences/path/to/source/fence.json")
+                    .get(&repo.relative_path("path/to/source/fence.json"))
                     .unwrap(),
                 violating_fence_clause: ViolatedFenceClause::DependencyRule(Some(&r)),
                 violating_import_specifier: "node:querystring",
@@ -1011,41 +1527,695 @@ mod test {
 
     #[test]
     pub fn test_dependencies_allowed_when_on_dependency_allow_list_with_accessible_to_conflict() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
-                "tests/evaluate_fences/path/to/source/fence.json" => parse_fence_str(
-                    r#"{
-                        "dependencies": [
-                            {
-                                "dependency": "node:querystring",
-                                "accessibleTo": "friend"
-                            },
-                            {
-                                "dependency": "node:querystring",
-                                "accessibleTo": "friendzzz"
-                            }
-                        ]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/fence.json")
-                ).unwrap(),
-                "tests/evaluate_fences/path/to/source/friend/fence.json" => parse_fence_str(
-                    r#"{
-                        "tags": ["friend"]
-                    }"#,
-                    &RelativePathBuf::from("tests/evaluate_fences/path/to/source/friend/fence.json")
-                ).unwrap()
-            ),
+        let repo = fixture_repo()
+            .fence(
+                "path/to/source",
+                json!({
+                    "dependencies": [
+                        {
+                            "dependency": "node:querystring",
+                            "accessibleTo": "friend"
+                        },
+                        {
+                            "dependency": "node:querystring",
+                            "accessibleTo": "friendzzz"
+                        }
+                    ]
+                }),
+            )
+            .fence(
+                "path/to/source/friend",
+                json!({
+                    "tags": ["friend"]
+                }),
+            );
+        let source_files = source_files(&repo);
+        let fence_collection = fence_collection(&repo);
+
+        let violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/friend/index.ts")],
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
+        );
+
+        assert_eq!(violations.violations, Vec::new());
+    }
+
+    #[test]
+    pub fn test_export_rule_modules_normalized() {
+        let fence = parse_fence_str(
+            r#"{"exports": [{"modules": "../sibling/*"}]}"#,
+            &RelativePathBuf::from("path/to/protected/fence.json"),
+        )
+        .unwrap();
+        let rule_globs = RuleGlobs::from_fences([&fence]);
+        let export_rule = &fence.fence.exports.as_ref().unwrap()[0];
+        let applies = |imported_file_path: &str| {
+            export_rule_applies_to_import_path(
+                &rule_globs,
+                "path/to/protected/fence.json",
+                export_rule,
+                std::path::Path::new(imported_file_path),
+            )
         };
 
+        assert!(applies("path/to/sibling/index.ts"));
+        assert!(applies("path/to/protected/../sibling/index.ts"));
+        assert!(!applies("path/to/protected/index.ts"));
+        assert!(!applies("path/to/sibling/../other.ts"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_export_rule_skips_non_utf8_paths() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let fence = parse_fence_str(
+            r#"{"exports": ["*"]}"#,
+            &RelativePathBuf::from("path/to/fence.json"),
+        )
+        .unwrap();
+        let rule_globs = RuleGlobs::from_fences([&fence]);
+        let imported_file_path = std::path::Path::new(OsStr::from_bytes(b"path/to/\xff.ts"));
+
+        assert!(!export_rule_applies_to_import_path(
+            &rule_globs,
+            "path/to/fence.json",
+            &fence.fence.exports.as_ref().unwrap()[0],
+            imported_file_path,
+        ));
+    }
+
+    #[test]
+    pub fn test_rule_globs_compiled_once_per_pattern() {
+        let fences = [
+            parse_fence_str(
+                r#"{"dependencies": ["react", "@scope/*"], "exports": ["index", "lib/*"]}"#,
+                &RelativePathBuf::from("pkg/a/fence.json"),
+            )
+            .unwrap(),
+            parse_fence_str(
+                r#"{"dependencies": ["@scope/*"], "exports": ["lib/*"]}"#,
+                &RelativePathBuf::from("pkg/b/fence.json"),
+            )
+            .unwrap(),
+        ];
+        let rule_globs = RuleGlobs::from_fences(fences.iter());
+
+        assert_eq!(rule_globs.dependency_globs.len(), 2);
+        assert!(is_node_dependency_matching(
+            &rule_globs,
+            "@scope/*",
+            "@scope/name"
+        ));
+        assert!(!is_node_dependency_matching(
+            &rule_globs,
+            "@other/*",
+            "@other/name"
+        ));
+
+        let lib_rule = &fences[1].fence.exports.as_ref().unwrap()[0];
+        assert!(export_rule_applies_to_import_path(
+            &rule_globs,
+            "pkg/b/fence.json",
+            lib_rule,
+            Path::new("pkg/b/lib/file.ts"),
+        ));
+        assert!(!export_rule_applies_to_import_path(
+            &rule_globs,
+            "pkg/a/fence.json",
+            lib_rule,
+            Path::new("pkg/b/lib/file.ts"),
+        ));
+    }
+
+    // barrel files re-exporting from node modules, parsed from the repo
+    fn barrel_source_files(repo: &FixtureRepo) -> HashMap<String, SourceFile> {
+        ["path/to/barrel/named.ts", "path/to/barrel/star.ts"]
+            .iter()
+            .map(|path| {
+                let source_file_path = repo.relative_path(path);
+                let parsed = parse_source_file(&source_file_path).unwrap();
+                let source_file = SourceFile {
+                    source_file_path: source_file_path.clone(),
+                    tags: HashSet::new(),
+                    imports: parsed.imports,
+                    has_default_export: parsed.has_default_export,
+                };
+                (source_file_path, source_file)
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_dependencies_of_reexports_from_node_modules() {
+        let repo = FixtureRepo::new()
+            .file(
+                "path/to/barrel/named.ts",
+                r#"export { debounce } from "lodash";"#,
+            )
+            .file("path/to/barrel/star.ts", r#"export * from "lodash";"#)
+            .fence("path/to/barrel", json!({"dependencies": []}));
+        let source_files = barrel_source_files(&repo);
+        let fence_collection = fence_collection(&repo);
+
+        for path in ["path/to/barrel/named.ts", "path/to/barrel/star.ts"] {
+            let violations = evaluate_fences(
+                &fence_collection,
+                &source_files,
+                &source_files[&repo.relative_path(path)],
+                &TSCONFIG_PATHS_JSON,
+                &ResolveOptions::default(),
+            );
+            assert_eq!(
+                violations.violations,
+                vec![ImportRuleViolation {
+                    violating_file_path: &repo.relative_path(path),
+                    violating_fence: fence_collection
+                        .fences_map
+                        .get(&repo.relative_path("path/to/barrel/fence.json"))
+                        .unwrap(),
+                    violating_fence_clause: ViolatedFenceClause::DependencyRule(None),
+                    violating_import_specifier: "lodash",
+                    violating_imported_name: None
+                }],
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_dependencies_of_reexports_from_node_modules_on_allow_list() {
+        let repo = FixtureRepo::new()
+            .file(
+                "path/to/barrel/named.ts",
+                r#"export { debounce } from "lodash";"#,
+            )
+            .file("path/to/barrel/star.ts", r#"export * from "lodash";"#)
+            .fence("path/to/barrel", json!({"dependencies": ["lodash"]}));
+        let source_files = barrel_source_files(&repo);
+        let fence_collection = fence_collection(&repo);
+
+        for path in ["path/to/barrel/named.ts", "path/to/barrel/star.ts"] {
+            let violations = evaluate_fences(
+                &fence_collection,
+                &source_files,
+                &source_files[&repo.relative_path(path)],
+                &TSCONFIG_PATHS_JSON,
+                &ResolveOptions::default(),
+            );
+            assert_eq!(violations.violations, Vec::new(), "{}", path);
+        }
+    }
+
+    #[test]
+    pub fn test_no_default_exports_violation() {
+        let repo = FixtureRepo::new()
+            .file("path/to/source/default.ts", "export default 1;")
+            .file("path/to/source/named.ts", "export const named = 1;")
+            .fence("path/to/source", json!({"noDefaultExports": true}));
+        let fence_collection = fence_collection(&repo);
+        let source_files: HashMap<String, SourceFile> = map!(
+            repo.relative_path("path/to/source/default.ts") => SourceFile {
+                source_file_path: repo.relative_path("path/to/source/default.ts"),
+                tags: HashSet::new(),
+                imports: HashMap::new(),
+                has_default_export: true,
+            },
+            repo.relative_path("path/to/source/named.ts") => SourceFile {
+                source_file_path: repo.relative_path("path/to/source/named.ts"),
+                tags: HashSet::new(),
+                imports: HashMap::new(),
+                has_default_export: false,
+            }
+        );
+
+        let default_violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/default.ts")],
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
+        );
+        assert_eq!(
+            default_violations.violations,
+            vec![ImportRuleViolation {
+                violating_file_path: &repo.relative_path("path/to/source/default.ts"),
+                violating_fence: fence_collection
+                    .fences_map
+                    .get(&repo.relative_path("path/to/source/fence.json"))
+                    .unwrap(),
+                violating_fence_clause: ViolatedFenceClause::NoDefaultExports,
+                violating_import_specifier: DEFAULT_EXPORT_SPECIFIER,
+                violating_imported_name: None
+            }]
+        );
+
+        let named_violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("path/to/source/named.ts")],
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
+        );
+        assert_eq!(named_violations.violations, Vec::new());
+    }
+
+    // the friend file is nested three fences deep, and can import protected
+    // code only through the tag of the outermost fence
+    fn nested_tags_repo(source_fence: serde_json::Value) -> FixtureRepo {
+        fixture_repo()
+            .fence("path/to", json!({"tags": ["outer"]}))
+            .fence("path/to/source", source_fence)
+            .fence("path/to/source/friend", json!({"tags": ["friend"]}))
+            .fence(
+                "path/to/protected",
+                json!({"tags": ["protected"], "exports": [{
+                     "modules": "internal.ts",
+                     "accessibleTo": ["outer"]
+                }]}),
+            )
+    }
+
+    // source files of the fixture repo, tagged by their fences like the walk tags them
+    fn tagged_source_files(
+        repo: &FixtureRepo,
+        fence_collection: &FenceCollection,
+    ) -> HashMap<String, SourceFile> {
+        source_files(repo)
+            .into_iter()
+            .map(|(source_file_path, source_file)| {
+                let tags = fence_collection.get_tags_for_path(Path::new(&source_file_path));
+                (
+                    source_file_path,
+                    SourceFile {
+                        tags,
+                        ..source_file
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_nested_fences_inherit_tags() {
+        let repo = nested_tags_repo(json!({"tags": ["source"], "inheritTags": true}));
+        let fence_collection = fence_collection(&repo);
+        let source_files = tagged_source_files(&repo, &fence_collection);
+        let friend_path = repo.relative_path("path/to/source/friend/index.ts");
+
+        assert_eq!(
+            source_files[&friend_path].tags,
+            set!("friend", "source", "outer")
+        );
+        let violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&friend_path],
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
+        );
+        assert_eq!(violations.violations, Vec::new());
+    }
+
+    #[test]
+    pub fn test_nested_fences_without_inherited_tags() {
+        let repo = nested_tags_repo(json!({"tags": ["source"], "inheritTags": false}));
+        let fence_collection = fence_collection(&repo);
+        let source_files = tagged_source_files(&repo, &fence_collection);
+        let friend_path = repo.relative_path("path/to/source/friend/index.ts");
+
+        // the tags of fences above the middle fence no longer apply
+        assert_eq!(source_files[&friend_path].tags, set!("friend", "source"));
         let violations = evaluate_fences(
             &fence_collection,
-            &SOURCE_FILES,
-            SOURCE_FILES
-                .get("tests/evaluate_fences/path/to/source/friend/index.ts")
-                .unwrap(),
+            &source_files,
+            &source_files[&friend_path],
             &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
         );
 
+        let d = ExportRule {
+            modules: "internal.ts".to_owned(),
+            accessible_to: vec!["outer".to_owned()],
+        };
+        assert_eq!(
+            violations.violations,
+            vec![ImportRuleViolation {
+                violating_file_path: &friend_path,
+                violating_fence: fence_collection
+                    .fences_map
+                    .get(&repo.relative_path("path/to/protected/fence.json"))
+                    .unwrap(),
+                violating_fence_clause: ViolatedFenceClause::ExportRule(Some(&d)),
+                violating_import_specifier: "../../protected/internal",
+                violating_imported_name: Option::None
+            }]
+        );
+    }
+
+    #[test]
+    pub fn test_resource_extensions() {
+        // the icon is a resource on disk, but not one of the walked source files
+        let repo = FixtureRepo::new()
+            .file(
+                "path/to/source/index.ts",
+                "import './photo.jpg'; import './icon.svg.tsx';",
+            )
+            .file("path/to/source/photo.jpg", "")
+            .file("path/to/source/icon.svg.tsx", "")
+            .fence("path/to/source", json!({"imports": []}));
+        let fence_collection = fence_collection(&repo);
+        let source_files: HashMap<String, SourceFile> = map!(
+            repo.relative_path("path/to/source/index.ts") => SourceFile {
+                source_file_path: repo.relative_path("path/to/source/index.ts"),
+                tags: HashSet::new(),
+                imports: map!(
+                    "./photo.jpg" => Option::None,
+                    "./icon.svg.tsx" => Option::None
+                ),
+                has_default_export: false,
+            }
+        );
+        let source_file = &source_files[&repo.relative_path("path/to/source/index.ts")];
+
+        let default_result = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            source_file,
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions::default(),
+        );
+        assert_eq!(default_result.violations, Vec::new());
+        assert_eq!(
+            default_result.unresolved_files,
+            vec![EvaluateFencesError::NotScanned(ResolvedImportNotFound {
+                project_local_path_str: repo.relative_path("path/to/source/icon.svg.tsx"),
+                source_file_path: repo.relative_path("path/to/source/index.ts"),
+                import_specifier: "./icon.svg.tsx".to_owned(),
+            })]
+        );
+
+        let result = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            source_file,
+            &TSCONFIG_PATHS_JSON,
+            &ResolveOptions {
+                resource_extensions: vec!["jpg".to_owned(), ".svg.tsx".to_owned()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(result.violations, Vec::new());
+        assert_eq!(result.unresolved_files, Vec::new());
+    }
+
+    // an app importing two workspace packages, which resolve by name through
+    // the tsconfig paths
+    fn workspace_repo(app_fence: serde_json::Value) -> FixtureRepo {
+        FixtureRepo::new()
+            .package("@myorg/experimental-widgets", |p| {
+                p.field("version", json!("0.3.0"))
+                    .file("src/index.ts", "export const widget = 1;")
+            })
+            .package("@myorg/stable", |p| {
+                p.field("version", json!("1.2.0"))
+                    .file("src/index.ts", "export const stable = 1;")
+            })
+            .file("packages/app/src/index.ts", "")
+            .fence("packages/app", app_fence)
+    }
+
+    fn workspace_tsconfig(repo: &FixtureRepo) -> TsconfigPathsJson {
+        TsconfigPathsJson {
+            compiler_options: TsconfigPathsCompilerOptions {
+                base_url: Some(repo.relative_path("")),
+                paths: map!(
+                    "@myorg/experimental-widgets" => vec!["packages/experimental-widgets/src/index".to_owned()],
+                    "@myorg/stable" => vec!["packages/stable/src/index".to_owned()]
+                ),
+            },
+        }
+    }
+
+    fn workspace_source_files(
+        repo: &FixtureRepo,
+        app_imports: &[&str],
+    ) -> HashMap<String, SourceFile> {
+        let source_file = |path: &str, imports: &[&str]| {
+            (
+                repo.relative_path(path),
+                SourceFile {
+                    source_file_path: repo.relative_path(path),
+                    tags: HashSet::new(),
+                    imports: imports
+                        .iter()
+                        .map(|import| (import.to_string(), None))
+                        .collect(),
+                    has_default_export: false,
+                },
+            )
+        };
+        HashMap::from_iter([
+            source_file("packages/app/src/index.ts", app_imports),
+            source_file("packages/experimental-widgets/src/index.ts", &[]),
+            source_file("packages/stable/src/index.ts", &[]),
+        ])
+    }
+
+    #[test]
+    pub fn test_dependencies_match_workspace_package_by_name() {
+        let repo = workspace_repo(json!({"dependencies": [
+            {
+                "dependency": "@myorg/experimental-*",
+                "accessibleTo": ["labs"]
+            }
+        ]}));
+        let source_files =
+            workspace_source_files(&repo, &["@myorg/experimental-widgets", "@myorg/stable"]);
+        let fence_collection = fence_collection(&repo);
+        let tsconfig = workspace_tsconfig(&repo);
+
+        let violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("packages/app/src/index.ts")],
+            &tsconfig,
+            &ResolveOptions::default(),
+        );
+
+        // workspace packages that no rule names are allowed
+        let rule = DependencyRule {
+            dependency: "@myorg/experimental-*".to_owned(),
+            accessible_to: vec!["labs".to_owned()],
+            version_range: None,
+        };
+        assert!(
+            violations.unresolved_files.is_empty(),
+            "unexpected unresolved files: {:?}",
+            violations.unresolved_files
+        );
+        assert_eq!(
+            violations.violations,
+            vec![ImportRuleViolation {
+                violating_file_path: &repo.relative_path("packages/app/src/index.ts"),
+                violating_fence: fence_collection
+                    .fences_map
+                    .get(&repo.relative_path("packages/app/fence.json"))
+                    .unwrap(),
+                violating_fence_clause: ViolatedFenceClause::DependencyRule(Some(&rule)),
+                violating_import_specifier: "@myorg/experimental-widgets",
+                violating_imported_name: None
+            }]
+        );
+    }
+
+    #[test]
+    pub fn test_assume_unresolved_bare_as_external() {
+        let repo = workspace_repo(json!({"dependencies": [
+            {
+                "dependency": "@myorg/unbuilt",
+                "accessibleTo": ["labs"]
+            }
+        ]}));
+        let source_files = workspace_source_files(&repo, &["@myorg/unbuilt", "./missing"]);
+        let fence_collection = fence_collection(&repo);
+        // the package the path points to has not been built
+        let mut tsconfig = workspace_tsconfig(&repo);
+        tsconfig.compiler_options.paths.insert(
+            "@myorg/unbuilt".to_owned(),
+            vec!["packages/unbuilt/lib/index".to_owned()],
+        );
+        let source_file = &source_files[&repo.relative_path("packages/app/src/index.ts")];
+        let unresolved = |import_specifier: &str| EvaluateFencesError::ImportNotResolved {
+            import_specifier: import_specifier.to_owned(),
+            source_file_path: repo.relative_path("packages/app/src/index.ts"),
+        };
+
+        let default_result = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            source_file,
+            &tsconfig,
+            &ResolveOptions::default(),
+        );
+        assert_eq!(default_result.violations, Vec::new());
+        assert_eq!(default_result.unresolved_files.len(), 2);
+        assert!(default_result
+            .unresolved_files
+            .contains(&unresolved("@myorg/unbuilt")));
+        assert!(default_result
+            .unresolved_files
+            .contains(&unresolved("./missing")));
+
+        let result = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            source_file,
+            &tsconfig,
+            &ResolveOptions {
+                assume_unresolved_bare_as_external: true,
+                ..Default::default()
+            },
+        );
+        // relative imports are still reported
+        assert_eq!(result.unresolved_files, vec![unresolved("./missing")]);
+        let rule = DependencyRule {
+            dependency: "@myorg/unbuilt".to_owned(),
+            accessible_to: vec!["labs".to_owned()],
+            version_range: None,
+        };
+        assert_eq!(
+            result.violations,
+            vec![ImportRuleViolation {
+                violating_file_path: &repo.relative_path("packages/app/src/index.ts"),
+                violating_fence: fence_collection
+                    .fences_map
+                    .get(&repo.relative_path("packages/app/fence.json"))
+                    .unwrap(),
+                violating_fence_clause: ViolatedFenceClause::DependencyRule(Some(&rule)),
+                violating_import_specifier: "@myorg/unbuilt",
+                violating_imported_name: None
+            }]
+        );
+    }
+
+    #[test]
+    pub fn test_dependencies_version_range_of_workspace_package() {
+        // forbid depending on pre-1.0 packages outside of labs
+        let repo = workspace_repo(json!({"dependencies": [
+            {
+                "dependency": "@myorg/*",
+                "versionRange": "<1.0.0",
+                "accessibleTo": "labs"
+            }
+        ]}));
+        let source_files =
+            workspace_source_files(&repo, &["@myorg/experimental-widgets", "@myorg/stable"]);
+        let fence_collection = fence_collection(&repo);
+        let tsconfig = workspace_tsconfig(&repo);
+
+        let violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("packages/app/src/index.ts")],
+            &tsconfig,
+            &ResolveOptions::default(),
+        );
+
+        let rule = DependencyRule {
+            dependency: "@myorg/*".to_owned(),
+            accessible_to: vec!["labs".to_owned()],
+            version_range: Some("<1.0.0".to_owned()),
+        };
+        assert_eq!(
+            violations.violations,
+            vec![ImportRuleViolation {
+                violating_file_path: &repo.relative_path("packages/app/src/index.ts"),
+                violating_fence: fence_collection
+                    .fences_map
+                    .get(&repo.relative_path("packages/app/fence.json"))
+                    .unwrap(),
+                violating_fence_clause: ViolatedFenceClause::DependencyRule(Some(&rule)),
+                violating_import_specifier: "@myorg/experimental-widgets",
+                violating_imported_name: None
+            }]
+        );
+    }
+
+    #[test]
+    pub fn test_dependencies_ignore_path_imports_of_workspace_package() {
+        let repo = workspace_repo(json!({"dependencies": [
+            {
+                "dependency": "@myorg/experimental-*",
+                "accessibleTo": ["labs"]
+            }
+        ]}));
+        let source_files = workspace_source_files(&repo, &["../../experimental-widgets/src/index"]);
+        let fence_collection = fence_collection(&repo);
+        let tsconfig = workspace_tsconfig(&repo);
+
+        let violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("packages/app/src/index.ts")],
+            &tsconfig,
+            &ResolveOptions::default(),
+        );
+
+        assert!(
+            violations.unresolved_files.is_empty(),
+            "unexpected unresolved files: {:?}",
+            violations.unresolved_files
+        );
+        assert_eq!(violations.violations, Vec::new());
+    }
+
+    #[test]
+    pub fn test_dependencies_ignore_path_aliases() {
+        let repo = workspace_repo(json!({"dependencies": [
+            {
+                "dependency": "*",
+                "accessibleTo": ["labs"]
+            }
+        ]}))
+        .file("packages/app/src/components/Button.ts", "");
+        let mut source_files = workspace_source_files(&repo, &["components/Button"]);
+        let button_path = repo.relative_path("packages/app/src/components/Button.ts");
+        source_files.insert(
+            button_path.clone(),
+            SourceFile {
+                source_file_path: button_path,
+                tags: HashSet::new(),
+                imports: HashMap::new(),
+                has_default_export: false,
+            },
+        );
+        let fence_collection = fence_collection(&repo);
+        // a bare-looking alias that does not name a workspace package
+        let mut tsconfig = workspace_tsconfig(&repo);
+        tsconfig.compiler_options.paths.insert(
+            "components/*".to_owned(),
+            vec!["packages/app/src/components/*".to_owned()],
+        );
+
+        let violations = evaluate_fences(
+            &fence_collection,
+            &source_files,
+            &source_files[&repo.relative_path("packages/app/src/index.ts")],
+            &tsconfig,
+            &ResolveOptions::default(),
+        );
+
+        assert!(
+            violations.unresolved_files.is_empty(),
+            "unexpected unresolved files: {:?}",
+            violations.unresolved_files
+        );
         assert_eq!(violations.violations, Vec::new());
     }
 }
@@ -0,0 +1,143 @@
+use crate::error::EvaluateFencesError;
+use crate::evaluate_fences::{
+    FenceEvaluationResult, ImportRuleViolation, ResolvedImports, ViolatedFenceClause,
+    DEFAULT_EXPORT_SPECIFIER,
+};
+use crate::fence_collection::FenceCollection;
+use crate::walk_dirs::SourceFile;
+use std::collections::{HashMap, HashSet};
+
+/**
+ * The results of evaluating each source file, kept so that only the files
+ * affected by a changed fence have to be evaluated again.
+ */
+#[derive(Debug, Default, PartialEq)]
+pub struct EvaluationCache {
+    // imports of each source file, resolved when the file was first evaluated
+    pub(crate) resolved_imports: HashMap<String, ResolvedImports>,
+    // violations and errors of each source file, as of its last evaluation
+    pub(crate) evaluations: HashMap<String, CachedEvaluation>,
+    // source files whose evaluation is out of date
+    pub(crate) dirty_source_files: HashSet<String>,
+}
+
+/**
+ * The result of evaluating a single source file
+ */
+#[derive(Debug, Default, PartialEq)]
+pub struct CachedEvaluation {
+    pub violations: Vec<CachedViolation>,
+    pub unresolved_files: Vec<EvaluateFencesError>,
+}
+
+/**
+ * An import rule violation that refers to its fence by path instead of by
+ * reference, so it stays valid while other fences are replaced
+ */
+#[derive(Debug, PartialEq)]
+pub struct CachedViolation {
+    fence_path: String,
+    clause: CachedFenceClause,
+    import_specifier: String,
+    imported_name: Option<String>,
+}
+
+// the violated clause of a fence, with rules referred to by their index
+#[derive(Debug, PartialEq)]
+enum CachedFenceClause {
+    ExportRule(Option<usize>),
+    DependencyRule(Option<usize>),
+    ImportAllowList,
+    NoDefaultExports,
+}
+
+impl CachedEvaluation {
+    pub fn new(result: FenceEvaluationResult) -> Self {
+        Self {
+            violations: result.violations.iter().map(CachedViolation::new).collect(),
+            unresolved_files: result.unresolved_files,
+        }
+    }
+}
+
+impl CachedViolation {
+    fn new(violation: &ImportRuleViolation) -> Self {
+        let fence = &violation.violating_fence.fence;
+        let clause = match violation.violating_fence_clause {
+            ViolatedFenceClause::ExportRule(rule) => {
+                CachedFenceClause::ExportRule(rule.and_then(|rule| {
+                    fence
+                        .exports
+                        .as_ref()?
+                        .iter()
+                        .position(|export_rule| std::ptr::eq(export_rule, rule))
+                }))
+            }
+            ViolatedFenceClause::DependencyRule(rule) => {
+                CachedFenceClause::DependencyRule(rule.and_then(|rule| {
+                    fence
+                        .dependencies
+                        .as_ref()?
+                        .iter()
+                        .position(|dependency_rule| std::ptr::eq(dependency_rule, rule))
+                }))
+            }
+            ViolatedFenceClause::ImportAllowList => CachedFenceClause::ImportAllowList,
+            ViolatedFenceClause::NoDefaultExports => CachedFenceClause::NoDefaultExports,
+        };
+
+        Self {
+            fence_path: violation.violating_fence.fence_path.clone(),
+            clause,
+            import_specifier: violation.violating_import_specifier.to_string(),
+            imported_name: violation.violating_imported_name.map(str::to_string),
+        }
+    }
+
+    /**
+     * Gets the violation this refers to, borrowing from the current fences and
+     * source file. Returns None if the fence or rule no longer exists.
+     */
+    pub fn resolve<'a>(
+        &self,
+        fence_collection: &'a FenceCollection,
+        source_file: &'a SourceFile,
+    ) -> Option<ImportRuleViolation<'a, 'a>> {
+        let violating_fence = fence_collection.fences_map.get(&self.fence_path)?;
+        let violating_fence_clause = match self.clause {
+            CachedFenceClause::ExportRule(None) => ViolatedFenceClause::ExportRule(None),
+            CachedFenceClause::ExportRule(Some(index)) => ViolatedFenceClause::ExportRule(Some(
+                violating_fence.fence.exports.as_ref()?.get(index)?,
+            )),
+            CachedFenceClause::DependencyRule(None) => ViolatedFenceClause::DependencyRule(None),
+            CachedFenceClause::DependencyRule(Some(index)) => ViolatedFenceClause::DependencyRule(
+                Some(violating_fence.fence.dependencies.as_ref()?.get(index)?),
+            ),
+            CachedFenceClause::ImportAllowList => ViolatedFenceClause::ImportAllowList,
+            CachedFenceClause::NoDefaultExports => ViolatedFenceClause::NoDefaultExports,
+        };
+        let (violating_import_specifier, violating_imported_name) = match self.clause {
+            // not caused by an import of the file
+            CachedFenceClause::NoDefaultExports => (DEFAULT_EXPORT_SPECIFIER, None),
+            _ => {
+                let (violating_import_specifier, imported_names) =
+                    source_file.imports.get_key_value(&self.import_specifier)?;
+                let violating_imported_name = match &self.imported_name {
+                    Some(imported_name) => {
+                        Some(imported_names.as_ref()?.get(imported_name)?.as_str())
+                    }
+                    None => None,
+                };
+                (violating_import_specifier.as_str(), violating_imported_name)
+            }
+        };
+
+        Some(ImportRuleViolation {
+            violating_file_path: &source_file.source_file_path,
+            violating_fence,
+            violating_fence_clause,
+            violating_import_specifier,
+            violating_imported_name,
+        })
+    }
+}
@@ -0,0 +1,243 @@
+use crate::evaluate_fences::resolve_source_file_import;
+use crate::fence::Fence;
+use crate::fence_collection::FenceCollection;
+use crate::walk_dirs::SourceFile;
+use import_resolver::manual_resolver::{ResolveOptions, ResolvedImport};
+use path_slash::PathBufExt;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use tsconfig_paths::TsconfigPathsJson;
+
+/**
+ * Version of the exported model. Bump this whenever the shape of the
+ * exported model changes, so consumers can tell which shape they are reading.
+ */
+pub const MODEL_FORMAT_VERSION: u32 = 4;
+
+#[derive(Eq, Debug, PartialEq, Copy, Clone)]
+pub enum ModelFormat {
+    // pretty-printed json
+    Json,
+    // compact bincode encoding of the same model
+    Binary,
+}
+
+/**
+ * The fences and source files walked by a GoodFencesRunner, for analysis
+ * outside of good-fences.
+ */
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedModel {
+    pub version: u32,
+    pub fences: Vec<ExportedFence>,
+    pub source_files: Vec<ExportedSourceFile>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedFence {
+    pub fence_path: String,
+    pub tags: Vec<String>,
+    pub root: bool,
+    pub inherit_tags: bool,
+    pub no_default_exports: bool,
+    pub export_rule_count: usize,
+    pub dependency_rule_count: usize,
+    // None if the fence does not restrict imports
+    pub import_allow_list_count: Option<usize>,
+    pub exports: Vec<ExportedExportRule>,
+    pub dependencies: Vec<ExportedDependencyRule>,
+    pub imports: Option<Vec<String>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedExportRule {
+    pub modules: String,
+    pub accessible_to: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedDependencyRule {
+    pub dependency: String,
+    pub accessible_to: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedSourceFile {
+    pub source_file_path: String,
+    pub tags: Vec<String>,
+    // tags of fences above the file that do not apply to it because of `"inheritTags": false`
+    pub suppressed_tags: Vec<String>,
+    pub imports: Vec<ExportedImport>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExportedImport {
+    pub import_specifier: String,
+    // None for side-effect and namespace imports
+    pub imported_names: Option<Vec<String>>,
+    // None if the import could not be resolved
+    pub resolved: Option<ExportedImportTarget>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum ExportedImportTarget {
+    SourceFile(String),
+    NodeModule(String),
+    ResourceFile,
+}
+
+impl From<&Fence> for ExportedFence {
+    fn from(fence: &Fence) -> Self {
+        let exports: Vec<ExportedExportRule> = fence
+            .fence
+            .exports
+            .iter()
+            .flatten()
+            .map(|rule| ExportedExportRule {
+                modules: rule.modules.clone(),
+                accessible_to: rule.accessible_to.clone(),
+            })
+            .collect();
+        let dependencies: Vec<ExportedDependencyRule> = fence
+            .fence
+            .dependencies
+            .iter()
+            .flatten()
+            .map(|rule| ExportedDependencyRule {
+                dependency: rule.dependency.clone(),
+                accessible_to: rule.accessible_to.clone(),
+            })
+            .collect();
+        ExportedFence {
+            fence_path: fence.fence_path.clone(),
+            tags: fence.fence.tags.clone().unwrap_or_default(),
+            root: fence.is_root(),
+            inherit_tags: fence.inherits_tags(),
+            no_default_exports: fence.forbids_default_exports(),
+            export_rule_count: exports.len(),
+            dependency_rule_count: dependencies.len(),
+            import_allow_list_count: fence.fence.imports.as_ref().map(Vec::len),
+            exports,
+            dependencies,
+            imports: fence.fence.imports.clone(),
+        }
+    }
+}
+
+impl ExportedSourceFile {
+    pub fn new(
+        source_file: &SourceFile,
+        fence_collection: &FenceCollection,
+        tsconfig_paths_json: &TsconfigPathsJson,
+        resolve_options: &ResolveOptions,
+    ) -> Self {
+        let mut tags: Vec<String> = source_file.tags.iter().cloned().collect();
+        tags.sort();
+        let mut suppressed_tags: Vec<String> = fence_collection
+            .get_suppressed_tags_for_path(Path::new(&source_file.source_file_path))
+            .into_iter()
+            .collect();
+        suppressed_tags.sort();
+
+        let mut imports: Vec<ExportedImport> = source_file
+            .imports
+            .iter()
+            .map(|(import_specifier, imported_names)| {
+                let resolved = resolve_source_file_import(
+                    tsconfig_paths_json,
+                    &source_file.source_file_path,
+                    import_specifier,
+                    resolve_options,
+                )
+                .ok()
+                .map(|resolved_import| match resolved_import {
+                    ResolvedImport::ProjectLocalImport(path) => {
+                        ExportedImportTarget::SourceFile(path.to_slash_lossy().to_string())
+                    }
+                    ResolvedImport::NodeModulesImport(module) => {
+                        ExportedImportTarget::NodeModule(module)
+                    }
+                    ResolvedImport::ResourceFileImport => ExportedImportTarget::ResourceFile,
+                });
+                ExportedImport {
+                    import_specifier: import_specifier.clone(),
+                    imported_names: imported_names.as_ref().map(|names| {
+                        let mut names: Vec<String> = names.iter().cloned().collect();
+                        names.sort();
+                        names
+                    }),
+                    resolved,
+                }
+            })
+            .collect();
+        imports.sort_by(|a, b| a.import_specifier.cmp(&b.import_specifier));
+
+        ExportedSourceFile {
+            source_file_path: source_file.source_file_path.clone(),
+            tags,
+            suppressed_tags,
+            imports,
+        }
+    }
+}
+
+impl ExportedModel {
+    pub fn new<'a>(
+        fence_collection: &FenceCollection,
+        source_files: impl Iterator<Item = &'a SourceFile>,
+        tsconfig_paths_json: &TsconfigPathsJson,
+        resolve_options: &ResolveOptions,
+    ) -> Self {
+        let mut fences: Vec<ExportedFence> = fence_collection
+            .fences_map
+            .values()
+            .map(ExportedFence::from)
+            .collect();
+        fences.sort_by(|a, b| a.fence_path.cmp(&b.fence_path));
+        let mut source_files: Vec<ExportedSourceFile> = source_files
+            .map(|source_file| {
+                ExportedSourceFile::new(
+                    source_file,
+                    fence_collection,
+                    tsconfig_paths_json,
+                    resolve_options,
+                )
+            })
+            .collect();
+        source_files.sort_by(|a, b| a.source_file_path.cmp(&b.source_file_path));
+
+        ExportedModel {
+            version: MODEL_FORMAT_VERSION,
+            fences,
+            source_files,
+        }
+    }
+
+    pub fn write(&self, writer: impl Write, format: ModelFormat) -> anyhow::Result<()> {
+        match format {
+            ModelFormat::Json => serde_json::to_writer_pretty(writer, self)?,
+            ModelFormat::Binary => bincode::serialize_into(writer, self)?,
+        }
+        Ok(())
+    }
+
+    /**
+     * Reads a model written by `write`, failing if it was written by a
+     * different version of good-fences.
+     */
+    pub fn read(reader: impl Read, format: ModelFormat) -> anyhow::Result<ExportedModel> {
+        let model: ExportedModel = match format {
+            ModelFormat::Json => serde_json::from_reader(reader)?,
+            ModelFormat::Binary => bincode::deserialize_from(reader)?,
+        };
+        if model.version != MODEL_FORMAT_VERSION {
+            return Err(anyhow::anyhow!(
+                "Unsupported model version {} (expected {})",
+                model.version,
+                MODEL_FORMAT_VERSION
+            ));
+        }
+        Ok(model)
+    }
+}
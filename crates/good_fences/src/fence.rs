@@ -7,7 +7,7 @@ use std::path::Path;
 use std::str::FromStr;
 use void::Void;
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct Fence {
     // relative path to the fence, from the root of the workspace
     pub fence_path: String,
@@ -15,13 +15,20 @@ pub struct Fence {
     pub fence: ParsedFence,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct ParsedFence {
     pub tags: Option<Vec<String>>,
     pub exports: Option<Vec<ExportRule>>,
     pub dependencies: Option<Vec<DependencyRule>>,
     pub imports: Option<Vec<String>>,
+    // if true, fences in parent directories do not apply to files under this fence
+    pub root: Option<bool>,
+    // if false, files under this fence do not get the tags of fences in parent
+    // directories, although the other rules of those fences still apply
+    pub inherit_tags: Option<bool>,
+    // if true, files under this fence may not declare a default export
+    pub no_default_exports: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
@@ -30,12 +37,18 @@ struct RawDependencyRule {
     dependency: String,
     #[serde(default, deserialize_with = "expand_to_string_vec")]
     accessible_to: Option<Vec<String>>,
+    #[serde(default)]
+    version_range: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize)]
 pub struct DependencyRule {
     pub dependency: String,
     pub accessible_to: Vec<String>,
+    // if set, the rule only applies to workspace packages whose package.json
+    // version matches this semver requirement, e.g. "<1.0.0"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_range: Option<String>,
 }
 
 impl From<RawDependencyRule> for DependencyRule {
@@ -46,6 +59,7 @@ impl From<RawDependencyRule> for DependencyRule {
                 Some(a) => a,
                 None => vec!["*".to_owned()],
             },
+            version_range: val.version_range,
         }
     }
 }
@@ -57,10 +71,20 @@ impl FromStr for DependencyRule {
         Ok(DependencyRule {
             dependency: s.to_owned(),
             accessible_to: vec!["*".to_owned()],
+            version_range: None,
         })
     }
 }
 
+impl DependencyRule {
+    /**
+     * Parses the version range of the rule, if it has one
+     */
+    pub fn parsed_version_range(&self) -> Option<Result<semver::VersionReq, semver::Error>> {
+        self.version_range.as_deref().map(semver::VersionReq::parse)
+    }
+}
+
 impl<'de> Deserialize<'de> for DependencyRule {
     fn deserialize<D>(deserializer: D) -> Result<DependencyRule, D::Error>
     where
@@ -207,9 +231,59 @@ where
     }
 }
 
+/// Parses the content of a fence.json file, without reading it from disk.
+///
+/// `fence_path` is only used to identify the fence, and is kept as its
+/// `fence_path`. Exports and dependencies written as plain strings are
+/// accessible to every tag (`"*"`).
+///
+/// ```
+/// use good_fences::{parse_fence_str, DependencyRule, ExportRule};
+/// use relative_path::RelativePath;
+///
+/// let fence = parse_fence_str(
+///     r#"{
+///         "tags": ["ui"],
+///         "exports": ["index", { "modules": "internal/*", "accessibleTo": "ui" }],
+///         "dependencies": ["react"]
+///     }"#,
+///     RelativePath::new("packages/ui/fence.json"),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(fence.fence_path, "packages/ui/fence.json");
+/// assert_eq!(fence.fence.tags, Some(vec!["ui".to_string()]));
+/// assert_eq!(
+///     fence.fence.exports.unwrap()[1],
+///     ExportRule {
+///         modules: "internal/*".to_string(),
+///         accessible_to: vec!["ui".to_string()],
+///     }
+/// );
+/// assert_eq!(
+///     fence.fence.dependencies.unwrap(),
+///     [DependencyRule {
+///         dependency: "react".to_string(),
+///         accessible_to: vec!["*".to_string()],
+///         version_range: None,
+///     }]
+/// );
+/// assert!(!fence.is_root());
+/// ```
 pub fn parse_fence_str(fence_str: &str, fence_path: &RelativePath) -> Result<Fence, Error> {
-    let fence = serde_json::from_str(fence_str)
+    let fence: ParsedFence = serde_json::from_str(fence_str)
         .with_context(|| format!("failed to parse fence from {:?}", fence_path,))?;
+    for dependency in fence.dependencies.iter().flatten() {
+        if let Some(Err(e)) = dependency.parsed_version_range() {
+            return Err(anyhow::anyhow!(
+                "failed to parse fence from {:?}: invalid versionRange {:?} of dependency {}: {}",
+                fence_path,
+                dependency.version_range.as_deref().unwrap_or_default(),
+                dependency.dependency,
+                e
+            ));
+        }
+    }
 
     Result::Ok(Fence {
         fence_path: fence_path.to_string(),
@@ -226,6 +300,18 @@ pub fn parse_fence_file<P: AsRef<RelativePath>>(fence_path: P) -> Result<Fence,
 }
 
 impl Fence {
+    pub fn is_root(&self) -> bool {
+        self.fence.root.unwrap_or(false)
+    }
+
+    pub fn inherits_tags(&self) -> bool {
+        self.fence.inherit_tags.unwrap_or(true)
+    }
+
+    pub fn forbids_default_exports(&self) -> bool {
+        self.fence.no_default_exports.unwrap_or(false)
+    }
+
     pub fn path_relative_to(self: &mut Fence, base_path: &Path) {
         println!("relative! {:?}, {:?}", self.fence_path, base_path);
         self.fence_path = pathdiff::diff_paths(self.fence_path.clone(), base_path)
@@ -260,6 +346,9 @@ mod test {
                     exports: Option::None,
                     dependencies: Option::None,
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             },
         );
@@ -284,11 +373,59 @@ mod test {
                     exports: Option::None,
                     dependencies: Option::None,
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             },
         )
     }
 
+    #[test]
+    fn loads_inherit_tags() {
+        let result = parse_fence_str(
+            r#"
+      {
+        "tags": ["demo"],
+        "inheritTags": false
+      }
+      "#,
+            RelativePath::new("test/path/to/fence.json"),
+        );
+        let fence = result.unwrap();
+        assert_eq!(
+            fence,
+            Fence {
+                fence_path: String::from("test/path/to/fence.json"),
+                fence: ParsedFence {
+                    tags: Option::Some(vec!("demo".to_owned())),
+                    exports: Option::None,
+                    dependencies: Option::None,
+                    imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::Some(false),
+                    no_default_exports: Option::None,
+                }
+            },
+        );
+        assert!(!fence.inherits_tags());
+    }
+
+    #[test]
+    fn loads_no_default_exports() {
+        let result = parse_fence_str(
+            r#"
+      {
+        "noDefaultExports": true
+      }
+      "#,
+            RelativePath::new("test/path/to/fence.json"),
+        );
+        let fence = result.unwrap();
+        assert_eq!(fence.fence.no_default_exports, Option::Some(true));
+        assert!(fence.forbids_default_exports());
+    }
+
     #[test]
     fn loads_single_export_rule_accessible_to_str() {
         let result = parse_fence_str(
@@ -316,6 +453,9 @@ mod test {
                     })),
                     dependencies: Option::None,
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             },
         )
@@ -347,6 +487,9 @@ mod test {
                     })),
                     dependencies: Option::None,
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             },
         )
@@ -382,6 +525,9 @@ mod test {
                     })),
                     dependencies: Option::None,
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             }
         )
@@ -411,6 +557,9 @@ mod test {
                     })),
                     dependencies: Option::None,
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             }
         )
@@ -439,9 +588,13 @@ mod test {
                     exports: Option::None,
                     dependencies: Option::Some(vec!(DependencyRule {
                         dependency: "some_dependency".to_owned(),
-                        accessible_to: vec!("*".to_owned())
+                        accessible_to: vec!("*".to_owned()),
+                        version_range: None,
                     })),
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             }
         )
@@ -471,9 +624,13 @@ mod test {
                     exports: Option::None,
                     dependencies: Option::Some(vec!(DependencyRule {
                         dependency: "some_dependency".to_owned(),
-                        accessible_to: vec!("accessible_to_single_str".to_owned())
+                        accessible_to: vec!("accessible_to_single_str".to_owned()),
+                        version_range: None,
                     })),
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             },
         )
@@ -506,9 +663,13 @@ mod test {
                         accessible_to: vec!(
                             "accessible_to_other_1".to_owned(),
                             "accessible_to_other_2".to_owned()
-                        )
+                        ),
+                        version_range: None,
                     })),
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             },
         )
@@ -535,11 +696,51 @@ mod test {
                     exports: Option::None,
                     dependencies: Option::Some(vec!(DependencyRule {
                         dependency: "string_approved_dependency".to_owned(),
-                        accessible_to: vec!("*".to_owned(),)
+                        accessible_to: vec!("*".to_owned(),),
+                        version_range: None,
                     })),
                     imports: Option::None,
+                    root: Option::None,
+                    inherit_tags: Option::None,
+                    no_default_exports: Option::None,
                 }
             }
         )
     }
+
+    #[test]
+    fn loads_dependency_rule_version_range() {
+        let result = parse_fence_str(
+            r#"
+      {
+        "dependencies": [
+          {
+            "dependency": "@myorg/*",
+            "versionRange": "<1.0.0",
+            "accessibleTo": "labs"
+          }
+        ]
+      }
+      "#,
+            RelativePath::new("test/path/to/fence.json"),
+        );
+        assert_eq!(
+            result.unwrap().fence.dependencies,
+            Some(vec!(DependencyRule {
+                dependency: "@myorg/*".to_owned(),
+                accessible_to: vec!("labs".to_owned()),
+                version_range: Some("<1.0.0".to_owned()),
+            }))
+        );
+
+        let err = parse_fence_str(
+            r#"{ "dependencies": [{ "dependency": "@myorg/*", "versionRange": "not a range" }] }"#,
+            RelativePath::new("test/path/to/fence.json"),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("invalid versionRange"),
+            "unexpected error: {err}"
+        );
+    }
 }
@@ -1,19 +1,64 @@
+use crate::evaluate_fences::{export_rule_applies_to_import_path, AccessibleToGlobs, RuleGlobs};
 use crate::fence::Fence;
+use crate::workspace_packages::WorkspacePackages;
 use lazy_static::__Deref;
 use path_slash::PathBufExt;
-use std::collections::HashMap;
+use path_utils::join_normalized;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::env::current_dir;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, PartialEq)]
 pub struct FenceCollection {
     pub fences_map: HashMap<String, Fence>,
+    // if set, fences above this directory are never applied
+    pub fence_root: Option<FenceRoot>,
+    // the glob patterns of the `accessibleTo` entries of the fences
+    pub accessible_to_globs: AccessibleToGlobs,
+    // the glob patterns of the dependency and export rules of the fences
+    pub rule_globs: RuleGlobs,
+    // the workspace packages of imported files, read once per run
+    pub workspace_packages: WorkspacePackages,
 }
 
 impl FenceCollection {
+    pub fn new(fences_map: HashMap<String, Fence>, fence_root: Option<FenceRoot>) -> Self {
+        let accessible_to_globs = AccessibleToGlobs::from_fences(fences_map.values());
+        let rule_globs = RuleGlobs::from_fences(fences_map.values());
+        FenceCollection {
+            fences_map,
+            fence_root,
+            accessible_to_globs,
+            rule_globs,
+            workspace_packages: WorkspacePackages::default(),
+        }
+    }
+
+    /**
+     * Adds or replaces the fence at a path, or removes it if there is no fence,
+     * and recompiles the globs of the fences
+     */
+    pub fn set_fence(&mut self, fence_path: &str, fence: Option<Fence>) {
+        match fence {
+            Some(fence) => {
+                self.fences_map.insert(fence_path.to_string(), fence);
+            }
+            None => {
+                self.fences_map.remove(fence_path);
+            }
+        }
+        self.accessible_to_globs = AccessibleToGlobs::from_fences(self.fences_map.values());
+        self.rule_globs = RuleGlobs::from_fences(self.fences_map.values());
+    }
+
     // TODO rewrite this as a generator?
     pub fn get_fences_for_path<'b>(&'b self, path: &Path) -> Vec<&'b Fence> {
         let mut fences: Vec<&'b Fence> = Vec::with_capacity(5);
         for stub in path.ancestors() {
+            if !is_within_fence_root(self.fence_root.as_ref(), stub) {
+                break;
+            }
+
             let mut key = PathBuf::from(stub);
             key.push("fence.json");
 
@@ -21,20 +66,192 @@ impl FenceCollection {
                 let fence_option = self.fences_map.get(key_str.deref());
 
                 if let Some(fence) = fence_option {
-                    fences.push(fence)
+                    fences.push(fence);
+                    // root fences hide any fences above them
+                    if fence.is_root() {
+                        break;
+                    }
                 }
             }
         }
         fences
     }
+
+    /**
+     * Gets the tags that apply to a path: the tags of the fences in its directory and
+     * the directories above it, up to the first fence that does not inherit tags.
+     */
+    pub fn get_tags_for_path(&self, path: &Path) -> HashSet<String> {
+        let mut tags = HashSet::new();
+        for fence in self.get_fences_for_path(path) {
+            tags.extend(fence.fence.tags.iter().flatten().cloned());
+            if !fence.inherits_tags() {
+                break;
+            }
+        }
+        tags
+    }
+
+    /**
+     * Gets the tags of the fences above a path that do not apply to it, because a
+     * fence between them sets `"inheritTags": false`. Tags that still apply to the
+     * path through another fence are not included.
+     */
+    pub fn get_suppressed_tags_for_path(&self, path: &Path) -> HashSet<String> {
+        let tags = self.get_tags_for_path(path);
+        self.get_fences_for_path(path)
+            .into_iter()
+            .skip_while(|fence| fence.inherits_tags())
+            .skip(1)
+            .flat_map(|fence| fence.fence.tags.iter().flatten())
+            .filter(|tag| !tags.contains(*tag))
+            .cloned()
+            .collect()
+    }
+
+    /**
+     * Checks whether a file may be imported from outside of all of its fences:
+     * every fence of the file that declares `exports` has an export rule covering
+     * it, whichever tags the rule is accessible to.
+     */
+    pub fn is_exported_path(&self, path: &Path) -> bool {
+        self.get_fences_for_path(path)
+            .iter()
+            .all(|fence| match &fence.fence.exports {
+                Some(export_rules) => export_rules.iter().any(|export_rule| {
+                    export_rule_applies_to_import_path(
+                        &self.rule_globs,
+                        &fence.fence_path,
+                        export_rule,
+                        path,
+                    )
+                }),
+                None => true,
+            })
+    }
+
+    /**
+     * Gets every tag referenced by any fence: the tags it declares, the tags it allows
+     * importing, and the tags its exports and dependencies are accessible to.
+     * The `*` wildcard is not a tag.
+     */
+    pub fn all_tags(&self) -> BTreeSet<String> {
+        self.fences_map
+            .values()
+            .flat_map(|fence| {
+                fence
+                    .fence
+                    .tags
+                    .iter()
+                    .flatten()
+                    .chain(referenced_tags(fence))
+            })
+            .filter(|tag| *tag != "*")
+            .cloned()
+            .collect()
+    }
+
+    /**
+     * Gets the tags referenced in the `imports` or `accessibleTo` of any fence that
+     * are not declared in the `tags` of any fence, e.g. because of a typo.
+     */
+    pub fn undefined_tags(&self) -> BTreeSet<String> {
+        let declared_tags: HashSet<&String> = self
+            .fences_map
+            .values()
+            .flat_map(|fence| fence.fence.tags.iter().flatten())
+            .collect();
+        self.fences_map
+            .values()
+            .flat_map(referenced_tags)
+            .filter(|tag| {
+                !declared_tags.contains(tag)
+                    && !self.accessible_to_globs.allows_any_tag(
+                        tag,
+                        declared_tags
+                            .iter()
+                            .map(|declared_tag| declared_tag.as_str()),
+                    )
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/**
+ * Gets the tags a fence refers to without declaring them
+ */
+fn referenced_tags(fence: &Fence) -> impl Iterator<Item = &String> {
+    let export_tags = fence
+        .fence
+        .exports
+        .iter()
+        .flatten()
+        .flat_map(|rule| rule.accessible_to.iter());
+    let dependency_tags = fence
+        .fence
+        .dependencies
+        .iter()
+        .flatten()
+        .flat_map(|rule| rule.accessible_to.iter());
+    fence
+        .fence
+        .imports
+        .iter()
+        .flatten()
+        .chain(export_tags)
+        .chain(dependency_tags)
+}
+
+/**
+ * A directory above which fences are ignored.
+ *
+ * Walked paths may be relative to the working directory or absolute, so both
+ * the root and the checked directories are made absolute before comparing.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct FenceRoot {
+    working_dir: PathBuf,
+    absolute_root: PathBuf,
+}
+
+impl FenceRoot {
+    pub fn new(fence_root: &Path) -> FenceRoot {
+        let working_dir = current_dir().unwrap_or_default();
+        let absolute_root = join_normalized(&working_dir, fence_root);
+        FenceRoot {
+            working_dir,
+            absolute_root,
+        }
+    }
+
+    /**
+     * Checks if a directory, relative to the working directory or absolute, is at
+     * or below the fence root
+     */
+    pub fn contains(&self, dir: &Path) -> bool {
+        join_normalized(&self.working_dir, dir).starts_with(&self.absolute_root)
+    }
+}
+
+/**
+ * Checks if a directory is at or below the fence root, if there is one
+ */
+pub fn is_within_fence_root(fence_root: Option<&FenceRoot>, dir: &Path) -> bool {
+    match fence_root {
+        Some(fence_root) => fence_root.contains(dir),
+        None => true,
+    }
 }
 
 #[cfg(test)]
 mod test {
     use relative_path::RelativePathBuf;
+    use std::collections::{BTreeSet, HashSet};
+    use std::env::current_dir;
     use std::path::Path;
 
-    use super::FenceCollection;
+    use super::{FenceCollection, FenceRoot};
     use crate::fence::parse_fence_str;
 
     macro_rules! map(
@@ -51,8 +268,8 @@ mod test {
 
     #[test]
     fn test_get_fences_for_path() {
-        let fence_collection = FenceCollection {
-            fences_map: map!(
+        let fence_collection = FenceCollection::new(
+            map!(
                 "some/a/b/fence.json" => parse_fence_str(
                     r#"{"tags": ["b"]}"#,
                     &RelativePathBuf::from("path/to/protected/fence.json")
@@ -60,7 +277,8 @@ mod test {
                 "some/fence.json" =>  parse_fence_str(r#"{"tags": ["root"]}"#, &RelativePathBuf::from("path/to/protected/fence.json")).unwrap(),
                 "some/other/fence.json" =>  parse_fence_str(r#"{"tags": ["other"]}"#, &RelativePathBuf::from("path/to/protected/fence.json")).unwrap()
             ),
-        };
+            None,
+        );
 
         assert_eq!(
             fence_collection.get_fences_for_path(Path::new("some/file.ts")),
@@ -79,4 +297,209 @@ mod test {
             "should return multiple fences for file with multiple fences",
         );
     }
+
+    #[test]
+    fn test_is_exported_path() {
+        let fence_collection = FenceCollection::new(
+            map!(
+                "pkg/fence.json" => parse_fence_str(
+                    r#"{"exports": ["index", {"modules": "api/*", "accessibleTo": "app"}]}"#,
+                    &RelativePathBuf::from("pkg/fence.json")
+                ).unwrap(),
+                "pkg/api/fence.json" => parse_fence_str(
+                    r#"{"tags": ["api"]}"#,
+                    &RelativePathBuf::from("pkg/api/fence.json")
+                ).unwrap()
+            ),
+            None,
+        );
+
+        assert!(fence_collection.is_exported_path(Path::new("pkg/index.ts")));
+        assert!(fence_collection.is_exported_path(Path::new("pkg/api/client.ts")));
+        assert!(!fence_collection.is_exported_path(Path::new("pkg/internal.ts")));
+        // files outside of any fence with exports are not restricted
+        assert!(fence_collection.is_exported_path(Path::new("other/internal.ts")));
+    }
+
+    #[test]
+    fn test_get_fences_for_path_bounded() {
+        let fence_collection = FenceCollection::new(
+            map!(
+                "repo/fence.json" => parse_fence_str(
+                    r#"{"tags": ["decoy"]}"#,
+                    &RelativePathBuf::from("repo/fence.json")
+                ).unwrap(),
+                "repo/project/fence.json" => parse_fence_str(
+                    r#"{"tags": ["project"]}"#,
+                    &RelativePathBuf::from("repo/project/fence.json")
+                ).unwrap(),
+                "repo/project/isolated/fence.json" => parse_fence_str(
+                    r#"{"tags": ["isolated"], "root": true}"#,
+                    &RelativePathBuf::from("repo/project/isolated/fence.json")
+                ).unwrap()
+            ),
+            Some(FenceRoot::new(Path::new("repo/project"))),
+        );
+
+        assert_eq!(
+            fence_collection.get_fences_for_path(Path::new("repo/project/file.ts")),
+            vec![fence_collection
+                .fences_map
+                .get("repo/project/fence.json")
+                .unwrap()],
+            "should ignore fences above the fence root",
+        );
+
+        assert_eq!(
+            fence_collection.get_fences_for_path(Path::new("repo/project/isolated/file.ts")),
+            vec![fence_collection
+                .fences_map
+                .get("repo/project/isolated/fence.json")
+                .unwrap()],
+            "should ignore fences above a root fence",
+        );
+    }
+
+    #[test]
+    fn test_get_tags_for_path_inherit_tags() {
+        let fence_collection = FenceCollection::new(
+            map!(
+                "pkg/fence.json" => parse_fence_str(
+                    r#"{"tags": ["pkg", "shared"]}"#,
+                    &RelativePathBuf::from("pkg/fence.json")
+                ).unwrap(),
+                "pkg/demo/fence.json" => parse_fence_str(
+                    r#"{"tags": ["shared"], "inheritTags": false}"#,
+                    &RelativePathBuf::from("pkg/demo/fence.json")
+                ).unwrap(),
+                "pkg/demo/nested/fence.json" => parse_fence_str(
+                    r#"{"tags": ["nested"]}"#,
+                    &RelativePathBuf::from("pkg/demo/nested/fence.json")
+                ).unwrap()
+            ),
+            None,
+        );
+
+        assert_eq!(
+            fence_collection.get_tags_for_path(Path::new("pkg/file.ts")),
+            HashSet::from(["pkg".to_owned(), "shared".to_owned()]),
+        );
+        assert_eq!(
+            fence_collection.get_tags_for_path(Path::new("pkg/demo/nested/file.ts")),
+            HashSet::from(["nested".to_owned(), "shared".to_owned()]),
+            "should keep the tags of the fence that does not inherit tags, and of fences below it",
+        );
+        assert_eq!(
+            fence_collection.get_suppressed_tags_for_path(Path::new("pkg/demo/nested/file.ts")),
+            HashSet::from(["pkg".to_owned()]),
+        );
+        assert_eq!(
+            fence_collection
+                .get_fences_for_path(Path::new("pkg/demo/file.ts"))
+                .len(),
+            2,
+            "should still apply the other rules of the fences above",
+        );
+    }
+
+    #[test]
+    fn test_all_and_undefined_tags() {
+        let fence_collection = FenceCollection::new(
+            map!(
+                "pkg/fence.json" => parse_fence_str(
+                    r#"{
+                        "tags": ["pkg"],
+                        "imports": ["shared"],
+                        "exports": [
+                            { "modules": "index.ts", "accessibleTo": ["frend"] },
+                            { "modules": "api.ts", "accessibleTo": ["shar*", "team-*"] }
+                        ]
+                    }"#,
+                    &RelativePathBuf::from("pkg/fence.json")
+                ).unwrap(),
+                "shared/fence.json" => parse_fence_str(
+                    r#"{
+                        "tags": ["shared", "friend"],
+                        "dependencies": [{ "dependency": "react", "accessibleTo": "*" }]
+                    }"#,
+                    &RelativePathBuf::from("shared/fence.json")
+                ).unwrap()
+            ),
+            None,
+        );
+
+        assert_eq!(
+            fence_collection.all_tags(),
+            BTreeSet::from([
+                "frend".to_owned(),
+                "friend".to_owned(),
+                "pkg".to_owned(),
+                "shar*".to_owned(),
+                "shared".to_owned(),
+                "team-*".to_owned(),
+            ]),
+        );
+        assert_eq!(
+            fence_collection.undefined_tags(),
+            // patterns are defined as long as they match a declared tag
+            BTreeSet::from(["frend".to_owned(), "team-*".to_owned()]),
+        );
+    }
+
+    #[test]
+    fn test_fence_root_contains() {
+        let cwd = current_dir().unwrap();
+        for fence_root in [
+            FenceRoot::new(Path::new("./some/project")),
+            FenceRoot::new(&cwd.join("some/project")),
+        ] {
+            assert!(fence_root.contains(Path::new("some/project")));
+            assert!(fence_root.contains(Path::new("./some/project/src")));
+            assert!(!fence_root.contains(Path::new("some")));
+            assert!(!fence_root.contains(Path::new("")));
+        }
+
+        let fence_root = FenceRoot::new(Path::new("."));
+        assert!(fence_root.contains(Path::new("")));
+        assert!(fence_root.contains(Path::new("some/project")));
+    }
+
+    #[test]
+    fn test_fence_root_above_working_directory() {
+        // e.g. for `--project ../tsconfig.json`, while walking `src`
+        let fence_root = FenceRoot::new(Path::new(".."));
+        assert!(fence_root.contains(Path::new("")));
+        assert!(fence_root.contains(Path::new("src/component")));
+        assert!(fence_root.contains(&current_dir().unwrap().join("src")));
+
+        let sibling_root = FenceRoot::new(Path::new("../sibling"));
+        assert!(!sibling_root.contains(Path::new("src/component")));
+    }
+
+    #[test]
+    fn test_fence_root_absolute_walked_paths() {
+        // relative fence roots still bound absolute walked paths
+        let cwd = current_dir().unwrap();
+        let fence_root = FenceRoot::new(Path::new("some/project"));
+        assert!(fence_root.contains(&cwd.join("some/project/src")));
+        assert!(!fence_root.contains(&cwd.join("some")));
+
+        let fence_collection = FenceCollection::new(
+            map!(
+                cwd.join("some/fence.json").to_slash_lossy().to_string() => parse_fence_str(
+                    r#"{"tags": ["decoy"]}"#,
+                    &RelativePathBuf::from("some/fence.json")
+                ).unwrap(),
+                cwd.join("some/project/fence.json").to_slash_lossy().to_string() => parse_fence_str(
+                    r#"{"tags": ["project"]}"#,
+                    &RelativePathBuf::from("some/project/fence.json")
+                ).unwrap()
+            ),
+            Some(fence_root),
+        );
+        assert_eq!(
+            fence_collection.get_tags_for_path(&cwd.join("some/project/src/file.ts")),
+            HashSet::from(["project".to_owned()])
+        );
+    }
 }
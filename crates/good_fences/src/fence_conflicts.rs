@@ -0,0 +1,262 @@
+use crate::fence::Fence;
+use crate::fence_collection::FenceCollection;
+use serde::Serialize;
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Serialize)]
+pub enum FenceRuleKind {
+    ExportRule,
+    DependencyRule,
+}
+
+impl Display for FenceRuleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenceRuleKind::ExportRule => write!(f, "export rule"),
+            FenceRuleKind::DependencyRule => write!(f, "dependency rule"),
+        }
+    }
+}
+
+/**
+ * A rule of a fence that can never take effect, because another rule of the
+ * same fence matches everything it matches and allows every tag it allows.
+ *
+ * Imports are allowed as soon as any matching rule allows them, so the
+ * ineffective rule can neither grant nor restrict access.
+ */
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct FenceRuleConflict<'a> {
+    pub fence_path: &'a str,
+    pub rule_kind: FenceRuleKind,
+    // index of the ineffective rule in the fence's list of rules of its kind
+    pub ineffective_rule_index: usize,
+    // index of the broader rule that makes it ineffective
+    pub shadowing_rule_index: usize,
+}
+
+impl Display for FenceRuleConflict<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Warning: {kind} #{ineffective} of {fence} can never take effect, since {kind} #{shadowing} already matches the same {targets} for the same tags",
+            kind = self.rule_kind,
+            ineffective = self.ineffective_rule_index,
+            fence = self.fence_path,
+            shadowing = self.shadowing_rule_index,
+            targets = match self.rule_kind {
+                FenceRuleKind::ExportRule => "modules",
+                FenceRuleKind::DependencyRule => "dependencies",
+            },
+        )
+    }
+}
+
+/**
+ * Finds export and dependency rules that are made ineffective by a broader
+ * rule of the same fence, sorted by fence path.
+ */
+pub fn analyze_fence_conflicts(fence_collection: &FenceCollection) -> Vec<FenceRuleConflict<'_>> {
+    let mut conflicts: Vec<FenceRuleConflict> = fence_collection
+        .fences_map
+        .values()
+        .flat_map(analyze_fence)
+        .collect();
+    conflicts.sort_by(|a, b| {
+        a.fence_path
+            .cmp(b.fence_path)
+            .then(a.rule_kind.cmp(&b.rule_kind))
+            .then(a.ineffective_rule_index.cmp(&b.ineffective_rule_index))
+    });
+    conflicts
+}
+
+fn analyze_fence(fence: &Fence) -> Vec<FenceRuleConflict<'_>> {
+    let export_rules: Vec<(&str, &[String])> = fence
+        .fence
+        .exports
+        .iter()
+        .flatten()
+        .map(|rule| (rule.modules.as_str(), rule.accessible_to.as_slice()))
+        .collect();
+    let dependency_rules: Vec<(&str, &[String])> = fence
+        .fence
+        .dependencies
+        .iter()
+        .flatten()
+        .map(|rule| (rule.dependency.as_str(), rule.accessible_to.as_slice()))
+        .collect();
+
+    find_shadowed_rules(&export_rules)
+        .into_iter()
+        .map(|(ineffective, shadowing)| (FenceRuleKind::ExportRule, ineffective, shadowing))
+        .chain(find_shadowed_rules(&dependency_rules).into_iter().map(
+            |(ineffective, shadowing)| (FenceRuleKind::DependencyRule, ineffective, shadowing),
+        ))
+        .map(
+            |(rule_kind, ineffective_rule_index, shadowing_rule_index)| FenceRuleConflict {
+                fence_path: &fence.fence_path,
+                rule_kind,
+                ineffective_rule_index,
+                shadowing_rule_index,
+            },
+        )
+        .collect()
+}
+
+// Finds (ineffective, shadowing) index pairs among a fence's rules of one kind,
+// where each rule is a glob pattern and the tags it is accessible to.
+fn find_shadowed_rules(rules: &[(&str, &[String])]) -> Vec<(usize, usize)> {
+    let covers = |broad: usize, narrow: usize| {
+        let (broad_pattern, broad_access) = rules[broad];
+        let (narrow_pattern, narrow_access) = rules[narrow];
+        pattern_contains(broad_pattern, narrow_pattern)
+            && access_contains(broad_access, narrow_access)
+    };
+
+    (0..rules.len())
+        .filter_map(|narrow| {
+            (0..rules.len())
+                .find(|&broad| {
+                    // of two equivalent rules, only the later one is reported
+                    broad != narrow
+                        && covers(broad, narrow)
+                        && (broad < narrow || !covers(narrow, broad))
+                })
+                .map(|broad| (narrow, broad))
+        })
+        .collect()
+}
+
+fn access_contains(broad: &[String], narrow: &[String]) -> bool {
+    broad.iter().any(|tag| tag == "*") || narrow.iter().all(|tag| broad.contains(tag))
+}
+
+/**
+ * Checks if every path matched by the `narrow` glob is also matched by the
+ * `broad` glob. Fence globs are matched without literal separators, so `*`
+ * and `**` both match any sequence of characters.
+ *
+ * This is conservative: patterns with character classes or alternations
+ * are only considered to contain each other when they are identical.
+ */
+fn pattern_contains(broad: &str, narrow: &str) -> bool {
+    if broad.contains(['[', '{']) || narrow.contains(['[', '{']) {
+        return broad == narrow;
+    }
+
+    let broad: Vec<char> = broad.chars().collect();
+    let narrow: Vec<char> = narrow.chars().collect();
+    // contains[i][j] is whether broad[i..] contains narrow[j..]
+    let mut contains = vec![vec![false; narrow.len() + 1]; broad.len() + 1];
+    contains[broad.len()][narrow.len()] = true;
+    for i in (0..broad.len()).rev() {
+        for j in (0..=narrow.len()).rev() {
+            contains[i][j] = match broad[i] {
+                // a wildcard can absorb any part of the narrow pattern, wildcards included
+                '*' => contains[i + 1][j] || (j < narrow.len() && contains[i][j + 1]),
+                _ if j == narrow.len() => false,
+                '?' => narrow[j] != '*' && contains[i + 1][j + 1],
+                c => narrow[j] == c && contains[i + 1][j + 1],
+            };
+        }
+    }
+    contains[0][0]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{analyze_fence_conflicts, pattern_contains, FenceRuleConflict, FenceRuleKind};
+    use crate::fence::parse_fence_str;
+    use crate::fence_collection::FenceCollection;
+    use relative_path::RelativePathBuf;
+    use std::collections::HashMap;
+
+    fn fence_collection(fence_json: &str) -> FenceCollection {
+        FenceCollection::new(
+            HashMap::from([(
+                "src/fence.json".to_owned(),
+                parse_fence_str(fence_json, &RelativePathBuf::from("src/fence.json")).unwrap(),
+            )]),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_shadowed_rule_flagged() {
+        let fence_collection = fence_collection(
+            r#"{
+                "exports": [
+                    {"modules": "**", "accessibleTo": "*"},
+                    {"modules": "internal/*", "accessibleTo": ["admin"]}
+                ],
+                "dependencies": [
+                    {"dependency": "react*", "accessibleTo": ["ui", "admin"]},
+                    {"dependency": "react-dom", "accessibleTo": "ui"}
+                ]
+            }"#,
+        );
+
+        assert_eq!(
+            analyze_fence_conflicts(&fence_collection),
+            vec![
+                FenceRuleConflict {
+                    fence_path: "src/fence.json",
+                    rule_kind: FenceRuleKind::ExportRule,
+                    ineffective_rule_index: 1,
+                    shadowing_rule_index: 0,
+                },
+                FenceRuleConflict {
+                    fence_path: "src/fence.json",
+                    rule_kind: FenceRuleKind::DependencyRule,
+                    ineffective_rule_index: 1,
+                    shadowing_rule_index: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_overlapping_rules_not_flagged() {
+        let fence_collection = fence_collection(
+            r#"{
+                "exports": [
+                    {"modules": "public/*", "accessibleTo": "*"},
+                    {"modules": "internal/*", "accessibleTo": ["admin"]}
+                ],
+                "dependencies": [
+                    {"dependency": "react", "accessibleTo": "*"},
+                    {"dependency": "lodash", "accessibleTo": "ui"}
+                ]
+            }"#,
+        );
+
+        assert_eq!(analyze_fence_conflicts(&fence_collection), vec![]);
+    }
+
+    #[test]
+    fn test_narrower_more_permissive_rule_not_flagged() {
+        let fence_collection = fence_collection(
+            r#"{
+                "exports": [
+                    {"modules": "**", "accessibleTo": ["admin"]},
+                    {"modules": "internal/*", "accessibleTo": ["admin", "ui"]}
+                ]
+            }"#,
+        );
+
+        assert_eq!(analyze_fence_conflicts(&fence_collection), vec![]);
+    }
+
+    #[test]
+    fn test_pattern_contains() {
+        assert!(pattern_contains("**", "internal/*"));
+        assert!(pattern_contains("*", "internal/**/index"));
+        assert!(pattern_contains("internal/*", "internal/a?c"));
+        assert!(pattern_contains("index", "index"));
+        assert!(!pattern_contains("internal/*", "*"));
+        assert!(!pattern_contains("internal/?", "internal/*"));
+        assert!(!pattern_contains("public/*", "internal/*"));
+        assert!(!pattern_contains("[ab]", "a"));
+    }
+}
@@ -4,8 +4,9 @@ use std::{
 };
 
 use swc_ecma_ast::{
-    BindingIdent, CallExpr, Callee, Id, ImportDecl, ImportSpecifier, Lit, ModuleExportName,
-    NamedExport, TsImportEqualsDecl,
+    BindingIdent, CallExpr, Callee, ExportAll, ExportDefaultDecl, ExportDefaultExpr,
+    ExportSpecifier, Id, ImportDecl, ImportSpecifier, Lit, ModuleExportName, NamedExport,
+    TsImportEqualsDecl, TsImportType,
 };
 use swc_ecma_visit::{Visit, VisitWith};
 
@@ -14,6 +15,9 @@ pub struct ImportPathVisitor {
     pub require_paths: HashSet<String>,
     pub import_paths: HashSet<String>,
     pub imports_map: HashMap<String, HashSet<String>>,
+    // true if the module declares a default export, e.g. `export default foo`
+    // or `export { foo as default }`
+    pub has_default_export: bool,
     require_identifiers: HashSet<Id>,
 }
 
@@ -29,6 +33,7 @@ impl ImportPathVisitor {
             require_paths: HashSet::new(),
             import_paths: HashSet::new(),
             imports_map: HashMap::new(),
+            has_default_export: false,
             require_identifiers: HashSet::new(),
         }
     }
@@ -38,8 +43,17 @@ impl Visit for ImportPathVisitor {
     fn visit_named_export(&mut self, export: &NamedExport) {
         export.visit_children_with(self);
 
+        if export.specifiers.iter().any(is_exported_as_default) {
+            self.has_default_export = true;
+        }
+
         if let Some(source) = &export.src {
             let source = source.value.to_string();
+            // `export * as ns from 'x'` depends on the whole module, which
+            // has no names to record in the imports map
+            if export.specifiers.iter().any(|x| x.is_namespace()) {
+                self.import_paths.insert(source.clone());
+            }
             let mut specifiers: HashSet<String> = export
                 .specifiers
                 .iter()
@@ -67,6 +81,22 @@ impl Visit for ImportPathVisitor {
         }
     }
 
+    // `export * from 'x'` depends on the whole module
+    fn visit_export_all(&mut self, export: &ExportAll) {
+        export.visit_children_with(self);
+        self.import_paths.insert(export.src.value.to_string());
+    }
+
+    fn visit_export_default_decl(&mut self, decl: &ExportDefaultDecl) {
+        decl.visit_children_with(self);
+        self.has_default_export = true;
+    }
+
+    fn visit_export_default_expr(&mut self, expr: &ExportDefaultExpr) {
+        expr.visit_children_with(self);
+        self.has_default_export = true;
+    }
+
     fn visit_binding_ident(&mut self, binding: &BindingIdent) {
         binding.visit_children_with(self);
         if binding.sym == *"require" {
@@ -81,6 +111,12 @@ impl Visit for ImportPathVisitor {
         }
     }
 
+    // `typeof import('foo')` and `import('foo').Foo` in type positions
+    fn visit_ts_import_type(&mut self, import_type: &TsImportType) {
+        import_type.visit_children_with(self);
+        self.import_paths.insert(import_type.arg.value.to_string());
+    }
+
     fn visit_call_expr(&mut self, expr: &CallExpr) {
         expr.visit_children_with(self);
         if let Callee::Import(_) = &expr.callee {
@@ -119,6 +155,18 @@ impl Visit for ImportPathVisitor {
     }
 }
 
+fn is_exported_as_default(spec: &ExportSpecifier) -> bool {
+    let exported_name = match spec {
+        ExportSpecifier::Named(named) => named.exported.as_ref().unwrap_or(&named.orig),
+        ExportSpecifier::Namespace(namespace) => &namespace.name,
+        ExportSpecifier::Default(_) => return false,
+    };
+    match exported_name {
+        ModuleExportName::Ident(ident) => ident.sym == "default",
+        ModuleExportName::Str(str_value) => str_value.value == "default",
+    }
+}
+
 fn append_imported_names(spec: &ImportSpecifier, imported_names: &mut HashSet<String>) {
     if let Some(named) = spec.as_named() {
         match &named.imported {
@@ -185,6 +233,63 @@ mod test {
         assert_eq!(expected_map, visitor.imports_map);
     }
 
+    #[test]
+    fn test_export_all_from() {
+        let (_, module) = parse_ecma_src(
+            "test.ts",
+            r#"
+            export * from 'lodash';
+            export * as fp from 'lodash/fp';
+            export { debounce } from 'lodash-es';
+            "#,
+        );
+
+        let mut visitor = ImportPathVisitor::new();
+        module.visit_with(&mut visitor);
+        let expected_import_paths = HashSet::from(["lodash".to_string(), "lodash/fp".to_string()]);
+        assert_eq!(expected_import_paths, visitor.import_paths);
+        assert_eq!(
+            Some(&HashSet::from(["debounce".to_string()])),
+            visitor.imports_map.get("lodash-es")
+        );
+    }
+
+    #[test]
+    fn test_default_exports() {
+        for (src, expected) in [
+            ("export default function foo() {}", true),
+            ("export default 1 + 1;", true),
+            ("const foo = 1; export { foo as default };", true),
+            ("export { default } from './foo';", true),
+            ("export { default as foo } from './foo';", false),
+            ("type Foo = {}; export type { Foo as default };", true),
+            ("export const foo = 1; import bar from './bar';", false),
+        ] {
+            let (_, module) = parse_ecma_src("test.ts", src);
+            let mut visitor = ImportPathVisitor::new();
+            module.visit_with(&mut visitor);
+            assert_eq!(expected, visitor.has_default_export, "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_import_type() {
+        let (_, module) = parse_ecma_src(
+            "test.ts",
+            r#"
+                type Api = typeof import('./generated/api');
+                type Handler = import('./handlers').Handler;
+                "#
+            .to_string(),
+        );
+        let mut visitor = ImportPathVisitor::new();
+
+        module.visit_with(&mut visitor);
+        let expected_import_paths =
+            HashSet::from(["./generated/api".to_string(), "./handlers".to_string()]);
+        assert_eq!(expected_import_paths, visitor.import_paths);
+    }
+
     #[test]
     fn test_require_imports() {
         let (_, module) = parse_ecma_src("test.ts", r#"require('hello-world')"#.to_string());
@@ -309,6 +414,26 @@ mod test {
         assert_eq!(expected_import_map, visitor.imports_map);
     }
 
+    #[test]
+    fn test_imports_mixed_default_and_specifiers() {
+        let (_, module) = parse_ecma_src(
+            "test.ts",
+            r#"
+            import D, { a, b } from './x';
+            "#,
+        );
+
+        let mut visitor = ImportPathVisitor::new();
+        module.visit_with(&mut visitor);
+
+        let expected_import_map = HashMap::from([(
+            "./x".to_string(),
+            HashSet::from(["default".to_string(), "a".to_string(), "b".to_string()]),
+        )]);
+
+        assert_eq!(expected_import_map, visitor.imports_map);
+    }
+
     #[test]
     fn test_require_redefinition() {
         let mut visitor = ImportPathVisitor::new();
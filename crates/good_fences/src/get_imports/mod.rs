@@ -16,9 +16,23 @@ pub use import_path_visitor::*;
 
 pub type FileImports = HashMap<String, Option<HashSet<String>>>;
 
+/**
+ * What good-fences needs to know about a parsed source file: its imports, and
+ * whether it declares a default export
+ */
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedSourceFile {
+    pub imports: FileImports,
+    pub has_default_export: bool,
+}
+
 pub fn get_imports_map_from_file<P: AsRef<str>>(
     file_path: &P,
 ) -> Result<FileImports, GetImportError> {
+    parse_source_file(file_path).map(|parsed_source_file| parsed_source_file.imports)
+}
+
+pub fn parse_source_file<P: AsRef<str>>(file_path: &P) -> Result<ParsedSourceFile, GetImportError> {
     let path_string: &str = file_path.as_ref();
     let cm = Lrc::<SourceMap>::default();
     let fm = match cm.load_file(Path::new(path_string)) {
@@ -74,9 +88,13 @@ pub fn get_imports_map_from_file<P: AsRef<str>>(
         let resolved = ts_module.clone().fold_with(&mut resolver);
         resolved.visit_with(&mut visitor);
     });
+    let has_default_export = visitor.has_default_export;
     let imports_map = get_imports_map_from_visitor(visitor);
 
-    Ok(imports_map)
+    Ok(ParsedSourceFile {
+        imports: imports_map,
+        has_default_export,
+    })
 }
 
 fn get_imports_map_from_visitor(visitor: ImportPathVisitor) -> FileImports {
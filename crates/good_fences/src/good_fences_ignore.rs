@@ -0,0 +1,65 @@
+use anyhow::Context;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+
+pub const GOOD_FENCES_IGNORE_FILE_NAME: &str = ".good-fences-ignore";
+
+/**
+ * Gitignore-style patterns, read from a `.good-fences-ignore` file at the
+ * root of the repo, for source files that are exempt from fence checks.
+ */
+#[derive(Debug, Clone)]
+pub struct GoodFencesIgnore {
+    gitignore: Gitignore,
+}
+
+impl GoodFencesIgnore {
+    /**
+     * Reads the `.good-fences-ignore` file at the root of the git repo
+     * containing `search_start`, if there is one.
+     */
+    pub fn discover(search_start: &Path) -> anyhow::Result<Option<GoodFencesIgnore>> {
+        let repo_root = repo_root::find_git_root_from(search_start);
+        GoodFencesIgnore::from_root(&repo_root)
+    }
+
+    /**
+     * Reads the `.good-fences-ignore` file in `root_dir`, if there is one.
+     * Patterns are matched relative to `root_dir`.
+     */
+    pub fn from_root(root_dir: &Path) -> anyhow::Result<Option<GoodFencesIgnore>> {
+        let root_dir = std::path::absolute(root_dir)?;
+        let ignore_file_path = root_dir.join(GOOD_FENCES_IGNORE_FILE_NAME);
+        if !ignore_file_path.is_file() {
+            return Ok(None);
+        }
+
+        let mut builder = GitignoreBuilder::new(&root_dir);
+        if let Some(err) = builder.add(&ignore_file_path) {
+            return Err(err)
+                .with_context(|| format!("Unable to read {}", ignore_file_path.display()));
+        }
+        let gitignore = builder
+            .build()
+            .with_context(|| format!("Unable to parse {}", ignore_file_path.display()))?;
+        Ok(Some(GoodFencesIgnore { gitignore }))
+    }
+
+    /**
+     * Checks if a source file, or any of the directories containing it,
+     * matches the ignore file. Files outside of the root never match.
+     */
+    pub fn is_ignored(&self, source_file_path: &Path) -> bool {
+        let source_file_path = match std::path::absolute(source_file_path) {
+            Ok(path) => path,
+            Err(_) => return false,
+        };
+        // the matcher expects paths under its root
+        if !source_file_path.starts_with(self.gitignore.path()) {
+            return false;
+        }
+        self.gitignore
+            .matched_path_or_any_parents(&source_file_path, false)
+            .is_ignore()
+    }
+}
@@ -1,12 +1,28 @@
 extern crate serde_json;
-use crate::evaluate_fences::{evaluate_fences, FenceEvaluationResult};
-use crate::fence::Fence;
-use crate::fence_collection::FenceCollection;
+use crate::error::EvaluateFencesError;
+use crate::evaluate_fences::{
+    evaluate_fences, evaluate_resolved_fences, find_imported_source_file,
+    resolve_source_file_imports, FenceEvaluationResult, FenceEvaluationStats, ImportRuleViolation,
+    ResolvedImports,
+};
+use crate::evaluation_cache::{CachedEvaluation, EvaluationCache};
+use crate::export_model::{ExportedModel, ModelFormat};
+use crate::fence::{parse_fence_file, Fence};
+use crate::fence_collection::{is_within_fence_root, FenceCollection, FenceRoot};
+use crate::fence_conflicts::{analyze_fence_conflicts, FenceRuleConflict};
+use crate::get_imports::ParsedSourceFile;
+use crate::good_fences_ignore::GoodFencesIgnore;
+use crate::tag_access_matrix::TagAccessMatrix;
+use crate::violation_groups::{group_violations_by_layer, ViolationGroup};
 use crate::walk_dirs::{discover_fences_and_files, ExternalFences, SourceFile, WalkFileData};
+use import_resolver::manual_resolver::{ResolveOptions, ResolvedImport};
+use path_utils::Glob;
 use rayon::prelude::*;
+use relative_path::RelativePath;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::iter::{FromIterator, Iterator};
+use std::path::Path;
 use tsconfig_paths::TsconfigPathsJson;
 
 #[derive(Debug, PartialEq)]
@@ -14,6 +30,14 @@ pub struct GoodFencesRunner {
     tsconfig_paths_json: TsconfigPathsJson,
     fence_collection: FenceCollection,
     source_files: HashMap<String, SourceFile>,
+    // source files exempt from fence checks. They can still be imported.
+    ignored_source_files: HashSet<String>,
+    // import specifiers that are expected to never resolve, e.g. virtual modules
+    allowed_unresolved: Vec<Glob>,
+    // how imports are resolved, e.g. which imported files are resources
+    resolve_options: ResolveOptions,
+    // results of `update_import_violations`, reused until a fence they depend on changes
+    evaluation_cache: EvaluationCache,
 }
 
 #[derive(Debug, PartialEq)]
@@ -28,11 +52,20 @@ impl GoodFencesRunner {
         directory_paths_to_walk: &[&str],
         external_fences: ExternalFences,
         ignored_dirs: &[regex::Regex],
+        fence_root: Option<&Path>,
+        resolve_options: ResolveOptions,
     ) -> GoodFencesRunner {
+        let fence_root = fence_root.map(FenceRoot::new);
+
         // find files
-        let walked_files = directory_paths_to_walk
-            .iter()
-            .flat_map(|path| discover_fences_and_files(path, external_fences, ignored_dirs.into()));
+        let walked_files = directory_paths_to_walk.iter().flat_map(|path| {
+            discover_fences_and_files(
+                path,
+                external_fences,
+                ignored_dirs.into(),
+                fence_root.clone(),
+            )
+        });
 
         let (fences_wrapped, sources_wrapped): (Vec<WalkFileData>, Vec<WalkFileData>) =
             // filter to Fences and Source FIles
@@ -71,39 +104,392 @@ impl GoodFencesRunner {
             }));
         GoodFencesRunner {
             source_files: source_file_map,
-            fence_collection: FenceCollection { fences_map },
+            fence_collection: FenceCollection::new(fences_map, fence_root),
+            tsconfig_paths_json,
+            ignored_source_files: HashSet::new(),
+            allowed_unresolved: Vec::new(),
+            resolve_options,
+            evaluation_cache: EvaluationCache::default(),
+        }
+    }
+
+    /**
+     * Creates a runner from fences and source files that were discovered by a
+     * walk outside of good-fences, instead of walking the directories itself.
+     *
+     * Paths are expected in the same form as the walk would produce them:
+     * slash-separated and relative to the working directory. Source files are
+     * tagged by the fences in their directory and the directories above it,
+     * the same way the walk tags them.
+     */
+    pub fn new_from_walked_files(
+        tsconfig_paths_json: TsconfigPathsJson,
+        fences: Vec<Fence>,
+        source_files: Vec<(String, ParsedSourceFile)>,
+        fence_root: Option<&Path>,
+    ) -> GoodFencesRunner {
+        let fence_root = fence_root.map(FenceRoot::new);
+
+        // fences above the fence root do not apply to the project
+        let fences_map: HashMap<String, Fence> = fences
+            .into_iter()
+            .filter(|fence| {
+                let fence_dir = Path::new(&fence.fence_path)
+                    .parent()
+                    .unwrap_or(Path::new(""));
+                is_within_fence_root(fence_root.as_ref(), fence_dir)
+            })
+            .map(|fence| (fence.fence_path.clone(), fence))
+            .collect();
+        let fence_collection = FenceCollection::new(fences_map, fence_root);
+
+        let source_files: HashMap<String, SourceFile> = source_files
+            .into_iter()
+            .map(|(source_file_path, parsed_source_file)| {
+                let tags = fence_collection.get_tags_for_path(Path::new(&source_file_path));
+                let source_file = SourceFile {
+                    source_file_path: source_file_path.clone(),
+                    tags,
+                    imports: parsed_source_file.imports,
+                    has_default_export: parsed_source_file.has_default_export,
+                };
+                (source_file_path, source_file)
+            })
+            .collect();
+
+        GoodFencesRunner {
+            source_files,
+            fence_collection,
             tsconfig_paths_json,
+            ignored_source_files: HashSet::new(),
+            allowed_unresolved: Vec::new(),
+            resolve_options: ResolveOptions::default(),
+            evaluation_cache: EvaluationCache::default(),
+        }
+    }
+
+    /**
+     * Reuses imports that were resolved outside of good-fences, e.g. by the unused
+     * finder, keyed by source file path, instead of resolving them again. Imports
+     * of other source files are still resolved when they are evaluated.
+     */
+    pub fn use_resolved_imports(&mut self, resolved_imports: HashMap<String, ResolvedImports>) {
+        self.evaluation_cache
+            .resolved_imports
+            .extend(resolved_imports);
+    }
+
+    /**
+     * Silently accepts imports that cannot be resolved if their specifier matches
+     * one of the given globs, e.g. virtual modules injected at build time
+     */
+    pub fn allow_unresolved(&mut self, allowed_unresolved: Vec<Glob>) {
+        self.allowed_unresolved = allowed_unresolved;
+    }
+
+    fn is_allowed_unresolved(&self, eval_error: &EvaluateFencesError) -> bool {
+        match eval_error {
+            EvaluateFencesError::ImportNotResolved {
+                import_specifier, ..
+            } => self
+                .allowed_unresolved
+                .iter()
+                .any(|glob| glob.matches(import_specifier)),
+            _ => false,
+        }
+    }
+
+    /**
+     * Skips evaluating the imports of source files matched by a `.good-fences-ignore` file
+     */
+    pub fn ignore_source_files(&mut self, good_fences_ignore: &GoodFencesIgnore) {
+        self.ignored_source_files = self
+            .source_files
+            .keys()
+            .filter(|source_file_path| good_fences_ignore.is_ignored(Path::new(source_file_path)))
+            .cloned()
+            .collect();
+    }
+
+    /**
+     * The number of walked source files that fences are evaluated for
+     */
+    pub fn evaluated_file_count(&self) -> usize {
+        self.source_files.len() - self.ignored_source_files.len()
+    }
+
+    /**
+     * Counts the files, fences and imports that an evaluation of every source
+     * file covers
+     */
+    pub fn evaluation_stats(&self) -> FenceEvaluationStats {
+        FenceEvaluationStats {
+            files_scanned: self.evaluated_file_count(),
+            fences_loaded: self.fence_collection.fences_map.len(),
+            imports_checked: self
+                .source_files
+                .iter()
+                .filter(|(source_file_path, _)| {
+                    !self.ignored_source_files.contains(*source_file_path)
+                })
+                .map(|(_, source_file)| source_file.imports.len())
+                .sum(),
         }
     }
 
     pub fn find_import_violations(&self) -> FenceEvaluationResult<'_, '_> {
-        println!("Evaluating {} files", self.source_files.keys().len());
+        self.find_import_violations_with(|_| Ok(()))
+            .expect("ignoring violations never fails")
+    }
+
+    /**
+     * Finds the import violations like `find_import_violations`, passing each
+     * violation to `on_violation` as soon as the file it belongs to is evaluated.
+     * Files are evaluated in parallel, so violations are passed in no particular
+     * order. Stops at the first error returned by `on_violation`.
+     */
+    pub fn find_import_violations_with(
+        &self,
+        on_violation: impl Fn(&ImportRuleViolation) -> anyhow::Result<()> + Sync,
+    ) -> anyhow::Result<FenceEvaluationResult<'_, '_>> {
         let mut evaluation_results = FenceEvaluationResult::new();
+        evaluation_results.stats = self.evaluation_stats();
 
         let violation_results = self
             .source_files
             .par_iter()
-            .map(|(_, source_file)| {
-                evaluate_fences(
-                    &self.fence_collection,
-                    &self.source_files,
-                    source_file,
-                    &self.tsconfig_paths_json,
-                )
+            .filter(|(source_file_path, _)| !self.ignored_source_files.contains(*source_file_path))
+            .map(|(source_file_path, source_file)| {
+                let result = match self.evaluation_cache.resolved_imports.get(source_file_path) {
+                    Some(resolved_imports) => evaluate_resolved_fences(
+                        &self.fence_collection,
+                        &self.source_files,
+                        source_file,
+                        resolved_imports,
+                    ),
+                    None => evaluate_fences(
+                        &self.fence_collection,
+                        &self.source_files,
+                        source_file,
+                        &self.tsconfig_paths_json,
+                        &self.resolve_options,
+                    ),
+                };
+                result.violations.iter().try_for_each(&on_violation)?;
+                Ok(result)
             })
-            .collect::<Vec<_>>();
+            .collect::<anyhow::Result<Vec<_>>>()?;
         for result in violation_results {
             for v in result.violations {
                 evaluation_results.violations.push(v);
             }
             for eval_error in result.unresolved_files {
-                evaluation_results.unresolved_files.push(eval_error);
+                if !self.is_allowed_unresolved(&eval_error) {
+                    evaluation_results.unresolved_files.push(eval_error);
+                }
             }
         }
 
+        Ok(evaluation_results)
+    }
+
+    /**
+     * Re-reads a fence file that changed on disk, or drops it if it was deleted,
+     * and marks the source files whose violations depend on it for re-evaluation
+     * by the next `update_import_violations`.
+     *
+     * Those are the files within the fence's directory, which it governs, and
+     * the files that import from within its directory.
+     */
+    pub fn mark_fence_dirty(&mut self, fence_path: &str) -> anyhow::Result<()> {
+        let fence_dir = Path::new(fence_path).parent().unwrap_or(Path::new(""));
+        if Path::new(fence_path).exists()
+            && is_within_fence_root(self.fence_collection.fence_root.as_ref(), fence_dir)
+        {
+            let fence = parse_fence_file(RelativePath::new(fence_path))?;
+            self.fence_collection.set_fence(fence_path, Some(fence));
+        } else {
+            self.fence_collection.set_fence(fence_path, None);
+        }
+
+        // files within the fence's directory may have gained or lost its tags
+        for source_file in self.source_files.values_mut() {
+            if Path::new(&source_file.source_file_path).starts_with(fence_dir) {
+                source_file.tags = self
+                    .fence_collection
+                    .get_tags_for_path(Path::new(&source_file.source_file_path));
+            }
+        }
+
+        let affected_source_files = self.source_files.keys().filter(|source_file_path| {
+            if Path::new(source_file_path).starts_with(fence_dir) {
+                return true;
+            }
+            // files whose imports were never resolved are evaluated anyway
+            let resolved_imports = match self
+                .evaluation_cache
+                .resolved_imports
+                .get(*source_file_path)
+            {
+                Some(resolved_imports) => resolved_imports,
+                None => return false,
+            };
+            resolved_imports.values().flatten().any(|resolved_import| {
+                let project_local_path = match resolved_import {
+                    ResolvedImport::ProjectLocalImport(project_local_path) => project_local_path,
+                    _ => return false,
+                };
+                find_imported_source_file(&self.source_files, project_local_path).is_some_and(
+                    |imported_source_file| {
+                        Path::new(&imported_source_file.source_file_path).starts_with(fence_dir)
+                    },
+                )
+            })
+        });
+        self.evaluation_cache
+            .dirty_source_files
+            .extend(affected_source_files.cloned());
+        Ok(())
+    }
+
+    /**
+     * Evaluates the source files marked dirty by `mark_fence_dirty` (or every
+     * file, the first time it is called), reusing the results of the other files
+     * and the resolved imports of every file.
+     *
+     * Returns the paths of the files that were evaluated. The violations of all
+     * files are read with `cached_import_violations`.
+     */
+    pub fn update_import_violations(&mut self) -> Vec<String> {
+        let cache = &mut self.evaluation_cache;
+        let mut evaluated_files: Vec<String> = self
+            .source_files
+            .keys()
+            .filter(|source_file_path| !self.ignored_source_files.contains(*source_file_path))
+            .filter(|source_file_path| {
+                !cache.evaluations.contains_key(*source_file_path)
+                    || cache.dirty_source_files.contains(*source_file_path)
+            })
+            .cloned()
+            .collect();
+        evaluated_files.sort();
+        cache.dirty_source_files.clear();
+
+        let resolved_imports = evaluated_files
+            .par_iter()
+            .filter(|source_file_path| !cache.resolved_imports.contains_key(*source_file_path))
+            .map(|source_file_path| {
+                (
+                    source_file_path.clone(),
+                    resolve_source_file_imports(
+                        &self.tsconfig_paths_json,
+                        &self.source_files[source_file_path],
+                        &self.resolve_options,
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        cache.resolved_imports.extend(resolved_imports);
+
+        let evaluations = evaluated_files
+            .par_iter()
+            .map(|source_file_path| {
+                let evaluation = evaluate_resolved_fences(
+                    &self.fence_collection,
+                    &self.source_files,
+                    &self.source_files[source_file_path],
+                    &cache.resolved_imports[source_file_path],
+                );
+                (source_file_path.clone(), CachedEvaluation::new(evaluation))
+            })
+            .collect::<Vec<_>>();
+        cache.evaluations.extend(evaluations);
+
+        evaluated_files
+    }
+
+    /**
+     * Gets the violations of every source file, as of their last evaluation by
+     * `update_import_violations`
+     */
+    pub fn cached_import_violations(&self) -> FenceEvaluationResult<'_, '_> {
+        let mut evaluation_results = FenceEvaluationResult::new();
+        for (source_file_path, evaluation) in self.evaluation_cache.evaluations.iter() {
+            if self.ignored_source_files.contains(source_file_path) {
+                continue;
+            }
+            let source_file = match self.source_files.get(source_file_path) {
+                Some(source_file) => source_file,
+                None => continue,
+            };
+            evaluation_results.violations.extend(
+                evaluation
+                    .violations
+                    .iter()
+                    .filter_map(|violation| violation.resolve(&self.fence_collection, source_file)),
+            );
+            evaluation_results.unresolved_files.extend(
+                evaluation
+                    .unresolved_files
+                    .iter()
+                    .filter(|eval_error| !self.is_allowed_unresolved(eval_error))
+                    .cloned(),
+            );
+        }
         evaluation_results
     }
 
+    /**
+     * Writes the walked fences and source files, with the resolved targets of
+     * their imports, for analysis outside of good-fences. Does not evaluate fences.
+     */
+    pub fn export_model(
+        &self,
+        writer: impl std::io::Write,
+        format: ModelFormat,
+    ) -> anyhow::Result<()> {
+        ExportedModel::new(
+            &self.fence_collection,
+            self.source_files.values(),
+            &self.tsconfig_paths_json,
+            &self.resolve_options,
+        )
+        .write(writer, format)
+    }
+
+    /**
+     * Finds fence rules that can never take effect because of a broader rule in the same fence
+     */
+    pub fn find_fence_conflicts(&self) -> Vec<FenceRuleConflict<'_>> {
+        analyze_fence_conflicts(&self.fence_collection)
+    }
+
+    /**
+     * Groups the violations of an evaluation by the tags of the importing file
+     * and of the violated fence, using the tags of the walked source files
+     */
+    pub fn group_violations_by_layer(
+        &self,
+        result: &FenceEvaluationResult,
+        tag_priority: &[String],
+        max_samples: usize,
+    ) -> Vec<ViolationGroup> {
+        group_violations_by_layer(
+            &result.violations,
+            &self.source_files,
+            tag_priority,
+            max_samples,
+        )
+    }
+
+    /**
+     * Computes whether files of each tag may import modules of each other tag,
+     * based only on the rules of the discovered fences
+     */
+    pub fn tag_access_matrix(&self) -> TagAccessMatrix {
+        TagAccessMatrix::from_fences(self.fence_collection.fences_map.values())
+    }
+
     /**
      * Finds tags that are referenced but not set in any fences
      */
@@ -133,8 +519,16 @@ impl GoodFencesRunner {
         // there are unreferenced tags, since we expect having undefined tags to be an
         // outlier, and maintaining the map between the consuming file paths and fence
         // items is overhead we don't want to deal with
-        let undefined_tags_set: HashSet<&'a str> =
-            HashSet::from_iter(referenced_tags.difference(&defined_tags).copied());
+        let undefined_tags_set: HashSet<&'a str> = referenced_tags
+            .difference(&defined_tags)
+            .copied()
+            .filter(|tag| {
+                !self
+                    .fence_collection
+                    .accessible_to_globs
+                    .allows_any_tag(tag, defined_tags.iter().copied())
+            })
+            .collect();
         if !undefined_tags_set.is_empty() {
             let mut undefined_tag_references = Vec::<UndefinedTagReference>::new();
 
@@ -166,13 +560,26 @@ impl GoodFencesRunner {
 #[cfg(test)]
 mod test {
     extern crate text_diff;
-    use crate::evaluate_fences::{ImportRuleViolation, ViolatedFenceClause};
-    use crate::fence::{DependencyRule, ExportRule, Fence, ParsedFence};
+    use crate::error::EvaluateFencesError;
+    use crate::evaluate_fences::{FenceEvaluationStats, ImportRuleViolation, ViolatedFenceClause};
+    use crate::evaluation_cache::EvaluationCache;
+    use crate::export_model::{
+        ExportedExportRule, ExportedFence, ExportedModel, ModelFormat, MODEL_FORMAT_VERSION,
+    };
+    use crate::fence::{parse_fence_str, DependencyRule, ExportRule, Fence, ParsedFence};
     use crate::fence_collection::FenceCollection;
+    use crate::get_imports::{FileImports, ParsedSourceFile};
+    use crate::good_fences_ignore::GoodFencesIgnore;
     use crate::good_fences_runner::{GoodFencesRunner, UndefinedTagReference};
     use crate::walk_dirs::{ExternalFences, SourceFile};
+    use import_resolver::manual_resolver::ResolveOptions;
+    use path_slash::PathBufExt;
+    use path_utils::Glob;
+    use relative_path::RelativePath;
     use std::collections::{HashMap, HashSet};
     use std::iter::FromIterator;
+    use std::path::Path;
+    use test_tmpdir::test_tmpdir;
     use text_diff::print_diff;
     use tsconfig_paths::{TsconfigPathsCompilerOptions, TsconfigPathsJson};
 
@@ -207,6 +614,8 @@ mod test {
             &["tests/good_fences_integration/src"],
             ExternalFences::Ignore,
             &Vec::new(),
+            None,
+            ResolveOptions::default(),
         );
 
         assert_eq!(
@@ -218,8 +627,8 @@ mod test {
                         paths: HashMap::new(),
                     },
                 },
-                fence_collection: FenceCollection {
-                    fences_map: map!(
+                fence_collection: FenceCollection::new(
+                    map!(
                         "tests/good_fences_integration/src/componentB/someDeep/complexComponentA/fence.json" => Fence {
                             fence_path: "tests/good_fences_integration/src/componentB/someDeep/complexComponentA/fence.json".to_owned(),
                             fence: ParsedFence {
@@ -231,11 +640,15 @@ mod test {
                                     vec![
                                         DependencyRule {
                                             dependency: "fs".to_owned(),
-                                            accessible_to: vec!["*".to_owned()]
+                                            accessible_to: vec!["*".to_owned()],
+                                            version_range: None,
                                         }
                                     ]
                                 ),
-                                imports: None
+                                imports: None,
+                                root: None,
+                                inherit_tags: None,
+                                no_default_exports: None,
                             }
                         },
                         "tests/good_fences_integration/src/componentB/someDeep/componentA/fence.json" => Fence {
@@ -249,11 +662,15 @@ mod test {
                                     vec![
                                         DependencyRule {
                                             dependency: "fs".to_owned(),
-                                            accessible_to: vec!["*".to_owned()]
+                                            accessible_to: vec!["*".to_owned()],
+                                            version_range: None,
                                         }
                                     ]
                                 ),
-                                imports: None
+                                imports: None,
+                                root: None,
+                                inherit_tags: None,
+                                no_default_exports: None,
                             }
                         },
 
@@ -270,7 +687,10 @@ mod test {
                                     ]
                                 ),
                                 dependencies: None,
-                                imports: None
+                                imports: None,
+                                root: None,
+                                inherit_tags: None,
+                                no_default_exports: None,
                             }
                         },
                         "tests/good_fences_integration/src/componentA/fence.json" => Fence {
@@ -301,6 +721,9 @@ mod test {
                                 imports: Some(
                                     vec![],
                                 ),
+                                root: None,
+                                inherit_tags: None,
+                                no_default_exports: None,
                             },
                         },
                         "tests/good_fences_integration/src/componentB/fence.json" => Fence {
@@ -323,13 +746,18 @@ mod test {
                                 ),
                                 dependencies: None,
                                 imports: None,
+                                root: None,
+                                inherit_tags: None,
+                                no_default_exports: None,
                             },
                         }
                     ),
-                },
+                    None,
+                ),
                 source_files: map!(
                     "tests/good_fences_integration/src/componentB/someDeep/complexComponentA/index.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentB/someDeep/complexComponentA/index.ts".to_owned(),
+                        has_default_export: false,
                         tags: set!("tagB".to_owned()),
                         imports: map!(
                             "../../../componentC/helperC1" => Some(set!("default".to_owned()))
@@ -337,6 +765,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentB/someDeep/componentA/index.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentB/someDeep/componentA/index.ts".to_owned(),
+                        has_default_export: false,
                         tags: set!("tagB".to_owned()),
                         imports: map!(
                             "../../../componentC/helperC1" => Some(set!("default".to_owned()))
@@ -344,11 +773,13 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentC/helperC1.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentC/helperC1.ts".to_owned(),
+                        has_default_export: true,
                         tags: set!("tagC".to_owned()),
                         imports: HashMap::new(),
                     },
                     "tests/good_fences_integration/src/requireImportTest.ts" => SourceFile {
                         source_file_path:"tests/good_fences_integration/src/requireImportTest.ts".to_owned(),
+                        has_default_export: false,
                         tags: HashSet::new(),
                         imports: map!(
                             "something" => None,
@@ -357,6 +788,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentA/helperA1.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentA/helperA1.ts".to_owned(),
+                        has_default_export: true,
                         tags: set!(
                             "tagA".to_owned()
                         ),
@@ -370,6 +802,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentB/componentB.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentB/componentB.ts".to_owned(),
+                        has_default_export: true,
                         tags: set!(
                             "tagB".to_owned()
                         ),
@@ -388,6 +821,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentB/helperB2.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentB/helperB2.ts".to_owned(),
+                        has_default_export: true,
                         tags: set!(
                             "tagB".to_owned()
                         ),
@@ -395,6 +829,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentA/helperA2.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentA/helperA2.ts".to_owned(),
+                        has_default_export: true,
                         tags: set!(
                             "tagA".to_owned()
                         ),
@@ -402,6 +837,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/index.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/index.ts".to_owned(),
+                        has_default_export: false,
                         tags: HashSet::new(),
                         imports: map!(
                                 "./componentA/componentA" => Some(
@@ -418,6 +854,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentB/helperB1.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentB/helperB1.ts".to_owned(),
+                        has_default_export: true,
                         tags: set!(
                             "tagB".to_owned()
                         ),
@@ -425,6 +862,7 @@ mod test {
                     },
                     "tests/good_fences_integration/src/componentA/componentA.ts" => SourceFile {
                         source_file_path: "tests/good_fences_integration/src/componentA/componentA.ts".to_owned(),
+                        has_default_export: true,
                         tags: set!(
                             "tagA".to_owned()
                         ),
@@ -449,7 +887,11 @@ mod test {
                                 )
                             ),
                     }
-                )
+                ),
+                ignored_source_files: HashSet::new(),
+                allowed_unresolved: Vec::new(),
+                resolve_options: ResolveOptions::default(),
+                evaluation_cache: EvaluationCache::default(),
             }
         );
     }
@@ -475,6 +917,8 @@ mod test {
             &["tests/good_fences_integration"],
             ExternalFences::Ignore,
             &Vec::new(),
+            None,
+            ResolveOptions::default(),
         );
 
         let mut results = good_fences_runner.find_import_violations();
@@ -574,6 +1018,8 @@ mod test {
             &["tests/good_fences_integration/src"],
             ExternalFences::Ignore,
             &Vec::new(),
+            None,
+            ResolveOptions::default(),
         );
 
         let orphans = good_fences_runner.find_undefined_tags();
@@ -587,4 +1033,408 @@ mod test {
             })
         );
     }
+
+    #[test]
+    fn good_fences_integration_test_violations_are_passed_as_found() {
+        let good_fences_runner = GoodFencesRunner::new(
+            TsconfigPathsJson::from_path("tests/good_fences_integration/tsconfig.json").unwrap(),
+            &["tests/good_fences_integration"],
+            ExternalFences::Ignore,
+            &Vec::new(),
+            None,
+            ResolveOptions::default(),
+        );
+
+        let found_violations = std::sync::Mutex::new(Vec::new());
+        let mut results = good_fences_runner
+            .find_import_violations_with(|violation| {
+                found_violations
+                    .lock()
+                    .unwrap()
+                    .push(violation.violating_file_path.to_owned());
+                Ok(())
+            })
+            .unwrap();
+        let mut found_violations = found_violations.into_inner().unwrap();
+        found_violations.sort();
+        results.violations.sort_by(compare_violations);
+        assert!(!found_violations.is_empty());
+        assert_eq!(
+            found_violations,
+            results
+                .violations
+                .iter()
+                .map(|violation| violation.violating_file_path.to_owned())
+                .collect::<Vec<_>>()
+        );
+
+        // an error passing a violation stops the evaluation
+        assert!(good_fences_runner
+            .find_import_violations_with(|_| Err(anyhow::anyhow!("closed")))
+            .is_err());
+    }
+
+    #[test]
+    fn good_fences_ignore_file_skips_matching_files() {
+        let mut good_fences_runner = GoodFencesRunner::new(
+            TsconfigPathsJson::from_path("tests/good_fences_ignore/tsconfig.json").unwrap(),
+            &["tests/good_fences_ignore/src"],
+            ExternalFences::Ignore,
+            &Vec::new(),
+            None,
+            ResolveOptions::default(),
+        );
+        let good_fences_ignore = GoodFencesIgnore::from_root(Path::new("tests/good_fences_ignore"))
+            .unwrap()
+            .unwrap();
+        good_fences_runner.ignore_source_files(&good_fences_ignore);
+
+        let results = good_fences_runner.find_import_violations();
+
+        // src/ui/generated/client.ts makes the same import, but is ignored
+        let violating_files: Vec<&str> = results
+            .violations
+            .iter()
+            .map(|violation| violation.violating_file_path)
+            .collect();
+        assert_eq!(
+            violating_files,
+            vec!["tests/good_fences_ignore/src/ui/view.ts"]
+        );
+    }
+
+    #[test]
+    fn good_fences_integration_test_export_model_round_trip() {
+        let good_fences_runner = GoodFencesRunner::new(
+            TsconfigPathsJson::from_path("tests/good_fences_integration/tsconfig.json").unwrap(),
+            &["tests/good_fences_integration/src"],
+            ExternalFences::Ignore,
+            &Vec::new(),
+            None,
+            ResolveOptions::default(),
+        );
+
+        let mut json = Vec::new();
+        good_fences_runner
+            .export_model(&mut json, ModelFormat::Json)
+            .unwrap();
+        let model = ExportedModel::read(json.as_slice(), ModelFormat::Json).unwrap();
+
+        assert_eq!(model.version, MODEL_FORMAT_VERSION);
+        assert_eq!(model.fences.len(), 5);
+        assert_eq!(
+            model.source_files.len(),
+            good_fences_runner.source_files.len()
+        );
+        let component_a_fence = model
+            .fences
+            .iter()
+            .find(|fence| {
+                fence.fence_path == "tests/good_fences_integration/src/componentA/fence.json"
+            })
+            .unwrap();
+        assert_eq!(
+            component_a_fence,
+            &ExportedFence {
+                fence_path: "tests/good_fences_integration/src/componentA/fence.json".to_owned(),
+                tags: vec!["tagA".to_owned()],
+                root: false,
+                inherit_tags: true,
+                no_default_exports: false,
+                export_rule_count: 2,
+                dependency_rule_count: 0,
+                import_allow_list_count: Some(0),
+                exports: vec![
+                    ExportedExportRule {
+                        modules: "componentA".to_owned(),
+                        accessible_to: vec!["*".to_owned()],
+                    },
+                    ExportedExportRule {
+                        modules: "helperA1".to_owned(),
+                        accessible_to: vec!["unknownTag".to_owned()],
+                    },
+                ],
+                dependencies: vec![],
+                imports: Some(vec![]),
+            }
+        );
+
+        // the binary encoding carries the same model
+        let mut binary = Vec::new();
+        good_fences_runner
+            .export_model(&mut binary, ModelFormat::Binary)
+            .unwrap();
+        assert_eq!(
+            ExportedModel::read(binary.as_slice(), ModelFormat::Binary).unwrap(),
+            model
+        );
+    }
+
+    fn parsed(imports: FileImports) -> ParsedSourceFile {
+        ParsedSourceFile {
+            imports,
+            has_default_export: false,
+        }
+    }
+
+    fn inherit_tags_runner(demo_fence: &str) -> GoodFencesRunner {
+        let fences = vec![
+            parse_fence_str(
+                r#"{"tags": ["pkg"]}"#,
+                RelativePath::new("tests/inherit_tags/src/pkg/fence.json"),
+            )
+            .unwrap(),
+            parse_fence_str(
+                demo_fence,
+                RelativePath::new("tests/inherit_tags/src/pkg/demo/fence.json"),
+            )
+            .unwrap(),
+            parse_fence_str(
+                r#"{"tags": ["protected"], "exports": [{"modules": "*", "accessibleTo": "pkg"}]}"#,
+                RelativePath::new("tests/inherit_tags/src/protected/fence.json"),
+            )
+            .unwrap(),
+        ];
+        let source_files = vec![
+            (
+                "tests/inherit_tags/src/pkg/pkgFile.ts".to_owned(),
+                parsed(map!("../protected/protectedFile" => Some(set!("protectedValue")))),
+            ),
+            (
+                "tests/inherit_tags/src/pkg/demo/demoFile.ts".to_owned(),
+                parsed(map!("../../protected/protectedFile" => Some(set!("protectedValue")))),
+            ),
+            (
+                "tests/inherit_tags/src/protected/protectedFile.ts".to_owned(),
+                parsed(HashMap::new()),
+            ),
+        ];
+        GoodFencesRunner::new_from_walked_files(
+            TsconfigPathsJson::from_path("tests/inherit_tags/tsconfig.json").unwrap(),
+            fences,
+            source_files,
+            None,
+        )
+    }
+
+    #[test]
+    fn inherit_tags_false_drops_parent_tag_access() {
+        // the demo folder is allowed to import protected code through the tags of its package
+        let inheriting_runner = inherit_tags_runner("{}");
+        assert_eq!(
+            inheriting_runner.find_import_violations().violations,
+            vec![]
+        );
+
+        let good_fences_runner = inherit_tags_runner(r#"{"inheritTags": false}"#);
+        let protected_fence = &good_fences_runner.fence_collection.fences_map
+            ["tests/inherit_tags/src/protected/fence.json"];
+        let results = good_fences_runner.find_import_violations();
+        assert_eq!(
+            results.violations,
+            vec![ImportRuleViolation {
+                violating_file_path: "tests/inherit_tags/src/pkg/demo/demoFile.ts",
+                violating_fence: protected_fence,
+                violating_fence_clause: ViolatedFenceClause::ExportRule(Some(
+                    &protected_fence.fence.exports.as_ref().unwrap()[0]
+                )),
+                violating_import_specifier: "../../protected/protectedFile",
+                violating_imported_name: None,
+            }]
+        );
+        assert!(results.unresolved_files.is_empty());
+    }
+
+    #[test]
+    fn inherit_tags_false_reports_suppressed_tags_in_model() {
+        let good_fences_runner = inherit_tags_runner(r#"{"inheritTags": false}"#);
+        let mut json = Vec::new();
+        good_fences_runner
+            .export_model(&mut json, ModelFormat::Json)
+            .unwrap();
+        let model = ExportedModel::read(json.as_slice(), ModelFormat::Json).unwrap();
+
+        let suppressed_tags = |source_file_path: &str| {
+            model
+                .source_files
+                .iter()
+                .find(|source_file| source_file.source_file_path == source_file_path)
+                .unwrap()
+                .suppressed_tags
+                .clone()
+        };
+        assert_eq!(
+            suppressed_tags("tests/inherit_tags/src/pkg/demo/demoFile.ts"),
+            vec!["pkg".to_owned()]
+        );
+        assert_eq!(
+            suppressed_tags("tests/inherit_tags/src/pkg/pkgFile.ts"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn allowed_unresolved_specifiers_are_not_reported() {
+        let source_files = vec![(
+            "tests/allowed_unresolved/src/app.ts".to_owned(),
+            parsed(map!(
+                "virtual:svg-icons" => Some(set!("default")),
+                "./missing" => Some(set!("missing"))
+            )),
+        )];
+        let mut good_fences_runner = GoodFencesRunner::new_from_walked_files(
+            TsconfigPathsJson::from_path("tests/allowed_unresolved/tsconfig.json").unwrap(),
+            vec![],
+            source_files,
+            None,
+        );
+        let unresolved_specifiers = |good_fences_runner: &GoodFencesRunner| {
+            let mut specifiers: Vec<String> = good_fences_runner
+                .find_import_violations()
+                .unresolved_files
+                .into_iter()
+                .map(|eval_error| match eval_error {
+                    EvaluateFencesError::ImportNotResolved {
+                        import_specifier, ..
+                    } => import_specifier,
+                    other => panic!("unexpected evaluation error: {}", other),
+                })
+                .collect();
+            specifiers.sort();
+            specifiers
+        };
+
+        assert_eq!(
+            unresolved_specifiers(&good_fences_runner),
+            vec!["./missing".to_owned(), "virtual:svg-icons".to_owned()]
+        );
+
+        good_fences_runner.allow_unresolved(vec![Glob::new("virtual:*").unwrap()]);
+        assert_eq!(
+            unresolved_specifiers(&good_fences_runner),
+            vec!["./missing".to_owned()]
+        );
+    }
+
+    #[test]
+    fn mark_fence_dirty_reevaluates_only_affected_files() {
+        // componentA starts out exporting everything, and is changed on disk to export nothing
+        let fences = vec![
+            parse_fence_str(
+                r#"{"tags": ["a"]}"#,
+                RelativePath::new("tests/incremental_fences/src/a/fence.json"),
+            )
+            .unwrap(),
+            parse_fence_str(
+                r#"{"tags": ["b"]}"#,
+                RelativePath::new("tests/incremental_fences/src/b/fence.json"),
+            )
+            .unwrap(),
+        ];
+        let source_files = vec![
+            (
+                "tests/incremental_fences/src/a/a.ts".to_owned(),
+                parsed(HashMap::new()),
+            ),
+            (
+                "tests/incremental_fences/src/b/b.ts".to_owned(),
+                parsed(map!("../a/a" => Some(set!("a")))),
+            ),
+            (
+                "tests/incremental_fences/src/c/c.ts".to_owned(),
+                parsed(map!("./d" => Some(set!("d")))),
+            ),
+            (
+                "tests/incremental_fences/src/c/d.ts".to_owned(),
+                parsed(HashMap::new()),
+            ),
+        ];
+        let mut good_fences_runner = GoodFencesRunner::new_from_walked_files(
+            TsconfigPathsJson::from_path("tests/incremental_fences/tsconfig.json").unwrap(),
+            fences,
+            source_files,
+            None,
+        );
+
+        assert_eq!(good_fences_runner.update_import_violations().len(), 4);
+        assert_eq!(
+            good_fences_runner.cached_import_violations().violations,
+            vec![]
+        );
+
+        good_fences_runner
+            .mark_fence_dirty("tests/incremental_fences/src/a/fence.json")
+            .unwrap();
+
+        assert_eq!(
+            good_fences_runner.update_import_violations(),
+            vec![
+                "tests/incremental_fences/src/a/a.ts".to_owned(),
+                "tests/incremental_fences/src/b/b.ts".to_owned(),
+            ]
+        );
+        let results = good_fences_runner.cached_import_violations();
+        assert_eq!(
+            results.violations,
+            vec![ImportRuleViolation {
+                violating_file_path: "tests/incremental_fences/src/b/b.ts",
+                violating_fence: &good_fences_runner.fence_collection.fences_map
+                    ["tests/incremental_fences/src/a/fence.json"],
+                violating_fence_clause: ViolatedFenceClause::ExportRule(None),
+                violating_import_specifier: "../a/a",
+                violating_imported_name: None,
+            }]
+        );
+        assert!(results.unresolved_files.is_empty());
+
+        // nothing is re-evaluated until another fence changes
+        assert_eq!(
+            good_fences_runner.update_import_violations(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn find_import_violations_counts_evaluated_files_fences_and_imports() {
+        let tmpdir = test_tmpdir!(
+            "tsconfig.json" => r#"{"compilerOptions": {"paths": {}}}"#,
+            ".good-fences-ignore" => "src/generated/",
+            "src/a/fence.json" => r#"{"tags": ["a"]}"#,
+            "src/a/a.ts" => "export const a = 1;",
+            "src/b/fence.json" => r#"{"tags": ["b"]}"#,
+            "src/b/b.ts" => r#"import { a } from "../a/a"; import { c } from "../c";"#,
+            "src/c.ts" => r#"import { a } from "./a/a"; export const c = a;"#,
+            "src/generated/client.ts" => r#"import { a } from "../a/a";"#
+        );
+        // walked paths are relative to the working directory
+        let root = pathdiff::diff_paths(tmpdir.root(), std::env::current_dir().unwrap())
+            .unwrap()
+            .to_slash_lossy()
+            .to_string();
+
+        let mut good_fences_runner = GoodFencesRunner::new(
+            TsconfigPathsJson::from_path(&format!("{root}/tsconfig.json")).unwrap(),
+            &[format!("{root}/src").as_str()],
+            ExternalFences::Ignore,
+            &Vec::new(),
+            Some(Path::new(&root)),
+            ResolveOptions::default(),
+        );
+        let good_fences_ignore = GoodFencesIgnore::from_root(Path::new(&root))
+            .unwrap()
+            .unwrap();
+        good_fences_runner.ignore_source_files(&good_fences_ignore);
+
+        let results = good_fences_runner.find_import_violations();
+        assert_eq!(results.violations, vec![]);
+        // the ignored file and its import are not counted
+        assert_eq!(
+            results.stats,
+            FenceEvaluationStats {
+                files_scanned: 3,
+                fences_loaded: 2,
+                imports_checked: 3,
+            }
+        );
+    }
 }
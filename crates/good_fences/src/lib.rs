@@ -1,31 +1,65 @@
 use anyhow::Context;
 use error::EvaluateFencesError;
 use serde::Serialize;
+pub mod cli_args;
+pub mod codeowners;
 pub mod error;
 pub mod evaluate_fences;
+pub mod evaluation_cache;
+pub mod export_model;
 pub mod fence;
 pub mod fence_collection;
+pub mod fence_conflicts;
 pub mod file_extension;
 pub mod get_imports;
+pub mod good_fences_ignore;
 pub mod good_fences_runner;
+pub mod output_format;
+pub mod report_html;
+pub mod tag_access_matrix;
+pub mod violation_groups;
 pub mod walk_dirs;
+pub mod workspace_packages;
 
 use core::option::Option::None;
+pub use evaluate_fences::FenceEvaluationStats;
+pub use export_model::ModelFormat;
+pub use fence::{parse_fence_str, DependencyRule, ExportRule, Fence, ParsedFence};
+pub use import_resolver::manual_resolver::{ResolveOptions, ResolvedImport};
+pub use output_format::OutputFormat;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+pub use violation_groups::ViolationGrouping;
 pub use walk_dirs::ExternalFences;
 
-pub fn good_fences(opts: GoodFencesOptions) -> Vec<GoodFencesResult> {
+pub fn good_fences(opts: GoodFencesOptions) -> anyhow::Result<Vec<GoodFencesResult>> {
+    good_fences_with_stats(opts).map(|output| output.results)
+}
+
+/// Runs good-fences like `good_fences`, also returning counts of the files,
+/// fences and imports it evaluated
+pub fn good_fences_with_stats(opts: GoodFencesOptions) -> anyhow::Result<GoodFencesOutput> {
     let mut tsconfig = tsconfig_paths::TsconfigPathsJson::from_path(&opts.project)
-        .with_context(|| format!("Unable to find --project path {}", &opts.project))
-        .unwrap();
+        .with_context(|| format!("Unable to read --project path {}", &opts.project))?;
 
     if opts.base_url.is_some() {
         tsconfig.compiler_options.base_url = opts.base_url;
     }
 
     let ignored_dirs_regexs = create_ignored_dirs_regexes(opts.ignored_dirs);
+    let output_format = opts.output_format.unwrap_or_default();
+
+    // fences above the project directory (or the explicit fence root) never apply
+    let fence_root = match opts.fence_root {
+        Some(fence_root) => PathBuf::from(fence_root),
+        None => Path::new(&opts.project)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
 
     let dirs_to_walk: Vec<&str> = opts.paths.iter().map(|x| x.as_str()).collect();
-    let good_fences_runner = good_fences_runner::GoodFencesRunner::new(
+    let mut good_fences_runner = good_fences_runner::GoodFencesRunner::new(
         tsconfig,
         &dirs_to_walk,
         match opts.ignore_external_fences {
@@ -33,30 +67,153 @@ pub fn good_fences(opts: GoodFencesOptions) -> Vec<GoodFencesResult> {
             None => ExternalFences::Include,
         },
         &ignored_dirs_regexs,
+        Some(fence_root.as_path()),
+        ResolveOptions {
+            resource_extensions: opts.resource_extensions.unwrap_or_default(),
+            assume_unresolved_bare_as_external: opts
+                .assume_unresolved_bare_as_external
+                .unwrap_or_default(),
+        },
     );
 
-    let eval_results = good_fences_runner.find_import_violations();
+    // files matching the repo's .good-fences-ignore are exempt from fence checks
+    let good_fences_ignore = good_fences_ignore::GoodFencesIgnore::discover(
+        &std::env::current_dir().expect("unable to get the current directory"),
+    )?;
+    if let Some(good_fences_ignore) = good_fences_ignore {
+        good_fences_runner.ignore_source_files(&good_fences_ignore);
+    }
+
+    // imports of virtual or build-time injected modules are never resolvable
+    let allowed_unresolved = opts
+        .allowed_unresolved
+        .unwrap_or_default()
+        .iter()
+        .map(|specifier_glob| {
+            path_utils::Glob::new(specifier_glob)
+                .with_context(|| format!("Invalid allowedUnresolved glob {}", specifier_glob))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    good_fences_runner.allow_unresolved(allowed_unresolved);
+
+    // exporting the model replaces evaluation
+    if let Some(export_model_path) = opts.export_model_path {
+        write_model(
+            &good_fences_runner,
+            &export_model_path,
+            opts.export_model_format.unwrap_or(ModelFormat::Json),
+        )?;
+        output_format.print_status(format!("Model written to {}", export_model_path));
+        return Ok(GoodFencesOutput {
+            results: Vec::new(),
+            stats: FenceEvaluationStats::default(),
+        });
+    }
+
+    output_format.print_machine_status(format!(
+        "Evaluating {} files",
+        good_fences_runner.evaluated_file_count()
+    ));
+    let eval_results = match output_format {
+        // ndjson violations are written as they are found
+        OutputFormat::Ndjson => {
+            let stdout = std::io::stdout();
+            good_fences_runner.find_import_violations_with(|violation| {
+                output_format::write_ndjson_line(stdout.lock(), violation)
+            })?
+        }
+        _ => good_fences_runner.find_import_violations(),
+    };
+    let stats = eval_results.stats;
+    let fence_lints = good_fences_runner.find_fence_conflicts();
+
+    let grouped_summary = match opts.group_by {
+        Some(ViolationGrouping::Layer) => Some(good_fences_runner.group_violations_by_layer(
+            &eval_results,
+            &opts.layer_tag_priority.unwrap_or_default(),
+            MAX_GROUP_SAMPLES,
+        )),
+        _ => None,
+    };
+
+    // violations are attributed to the owners of their files in the repo's CODEOWNERS
+    let code_owners = match opts.group_by {
+        Some(ViolationGrouping::Owner) => {
+            let code_owners = codeowners::CodeOwners::discover(
+                &std::env::current_dir().expect("unable to get the current directory"),
+            )?;
+            if code_owners.is_none() {
+                output_format.print_status("No CODEOWNERS file found, all violations are unowned");
+            }
+            Some(code_owners.unwrap_or_default())
+        }
+        _ => None,
+    };
+    let owner_summary = code_owners.as_ref().map(|code_owners| {
+        violation_groups::group_violations_by_owner(
+            &eval_results.violations,
+            code_owners,
+            MAX_GROUP_SAMPLES,
+        )
+    });
+    let violation_owners = code_owners
+        .as_ref()
+        .map(|code_owners| {
+            violation_groups::violation_owners(&eval_results.violations, code_owners)
+        })
+        .unwrap_or_default();
 
     // Print results and statistics
-    if !eval_results.violations.is_empty() {
-        println!("Violations:");
-        eval_results
-            .violations
-            .iter()
-            .for_each(|v| println!("{}", v));
-        println!("Total violations: {}", eval_results.violations.len());
+    match output_format {
+        OutputFormat::Human => {
+            if !eval_results.violations.is_empty() {
+                match (&grouped_summary, &owner_summary) {
+                    (Some(groups), _) => {
+                        println!("Violations by layer:");
+                        groups.iter().for_each(|group| println!("{}", group));
+                    }
+                    (_, Some(groups)) => {
+                        println!("Violations by owner:");
+                        groups.iter().for_each(|group| println!("{}", group));
+                    }
+                    _ => {
+                        println!("Violations:");
+                        eval_results
+                            .violations
+                            .iter()
+                            .for_each(|v| println!("{}", v));
+                    }
+                }
+                println!("Total violations: {}", eval_results.violations.len());
+            }
+        }
+        OutputFormat::Json => {
+            output_format::write_json(std::io::stdout().lock(), &eval_results, &fence_lints)?
+        }
+        // the violations were already written during evaluation
+        OutputFormat::Ndjson => {}
     }
 
-    if !eval_results.unresolved_files.is_empty() {
-        println!("Unresolved files:",);
-        eval_results
-            .unresolved_files
-            .iter()
-            .for_each(|f| println!("{}", f));
-        println!(
-            "Total unresolved files: {}",
-            eval_results.unresolved_files.len()
-        );
+    // the json document already includes the evaluation errors and fence lints
+    if output_format != OutputFormat::Json {
+        if !eval_results.unresolved_files.is_empty() {
+            output_format.print_status("Unresolved files:");
+            eval_results
+                .unresolved_files
+                .iter()
+                .for_each(|f| output_format.print_status(f));
+            output_format.print_status(format!(
+                "Total unresolved files: {}",
+                eval_results.unresolved_files.len()
+            ));
+        }
+
+        if !fence_lints.is_empty() {
+            output_format.print_status("Fence warnings:");
+            fence_lints
+                .iter()
+                .for_each(|lint| output_format.print_status(lint));
+        }
     }
 
     let mut errors: Vec<GoodFencesResult> = Vec::new();
@@ -83,19 +240,36 @@ pub fn good_fences(opts: GoodFencesOptions) -> Vec<GoodFencesResult> {
         });
     });
 
+    if let Some(html_output) = opts.html_output_path {
+        report_html::write_html_report(&eval_results, &html_output)?;
+        output_format.print_machine_status(format!("HTML report written to {}", html_output));
+    }
+
     // Write results to file
     if let Some(output) = opts.err_output_path {
         write_violations_as_json(
             eval_results.violations,
             eval_results.unresolved_files,
-            output,
+            grouped_summary,
+            owner_summary,
+            violation_owners,
+            fence_lints,
+            &output,
         )
         .unwrap();
+        let cwd = std::env::current_dir()?.to_string_lossy().to_string();
+        output_format.print_status(format!("Violations written to {} at {}", output, cwd));
     }
 
-    errors
+    Ok(GoodFencesOutput {
+        results: errors,
+        stats,
+    })
 }
 
+// number of sample violations listed for each group of violations
+const MAX_GROUP_SAMPLES: usize = 5;
+
 fn create_ignored_dirs_regexes(ignored_dirs: Option<Vec<String>>) -> Vec<regex::Regex> {
     match ignored_dirs {
         Some(dirs) => dirs
@@ -116,6 +290,21 @@ pub struct GoodFencesOptions {
     pub err_output_path: Option<String>,
     pub ignore_external_fences: Option<ExternalFences>,
     pub ignored_dirs: Option<Vec<String>>,
+    // globs of import specifiers that are expected to never resolve
+    pub allowed_unresolved: Option<Vec<String>>,
+    // extensions of imported files that are resources rather than source files
+    pub resource_extensions: Option<Vec<String>>,
+    // treat bare specifiers that match a tsconfig path but don't resolve as node_modules imports
+    pub assume_unresolved_bare_as_external: Option<bool>,
+    pub fence_root: Option<String>,
+    pub html_output_path: Option<String>,
+    pub group_by: Option<ViolationGrouping>,
+    // tags to prefer, in order, when picking the tag that represents a layer
+    pub layer_tag_priority: Option<Vec<String>>,
+    pub export_model_path: Option<String>,
+    pub export_model_format: Option<ModelFormat>,
+    // how results are printed to stdout, Human if unset
+    pub output_format: Option<OutputFormat>,
 }
 
 #[derive(Eq, Debug, PartialEq)]
@@ -133,32 +322,51 @@ pub struct GoodFencesResult {
     pub detailed_message: String,
 }
 
+pub struct GoodFencesOutput {
+    pub results: Vec<GoodFencesResult>,
+    // what the evaluation covered, e.g. to check that the expected files were walked
+    pub stats: FenceEvaluationStats,
+}
+
+fn write_model(
+    good_fences_runner: &good_fences_runner::GoodFencesRunner,
+    output_path: &str,
+    format: ModelFormat,
+) -> anyhow::Result<()> {
+    let file = std::fs::File::create(output_path).map_err(|err| {
+        anyhow::format_err!("Unable to write model to {output_path}.\nError: {err}")
+    })?;
+    good_fences_runner.export_model(std::io::BufWriter::new(file), format)?;
+    Ok(())
+}
+
 pub fn write_violations_as_json(
     violations: Vec<evaluate_fences::ImportRuleViolation>,
     fence_eval_errors: Vec<EvaluateFencesError>,
-    err_file_output_path: String,
+    grouped_summary: Option<Vec<violation_groups::ViolationGroup>>,
+    owner_summary: Option<Vec<violation_groups::OwnerViolationGroup>>,
+    violation_owners: BTreeMap<String, Vec<String>>,
+    fence_lints: Vec<fence_conflicts::FenceRuleConflict>,
+    err_file_output_path: &str,
 ) -> anyhow::Result<()> {
     let evaluation_errors: Vec<String> = fence_eval_errors
         .iter()
         .map(|error| error.to_string())
         .collect();
-    match std::fs::write(
-        &err_file_output_path,
+    std::fs::write(
+        err_file_output_path,
         serde_json::to_string_pretty(&JsonErrorFile {
             violations,
             evaluation_errors,
+            grouped_summary,
+            owner_summary,
+            violation_owners,
+            fence_lints,
         })?,
-    ) {
-        Ok(_) => {
-            let cwd = std::env::current_dir()?.to_string_lossy().to_string();
-            println!("Violations written to {} at {}", err_file_output_path, cwd,);
-        }
-        Err(err) => {
-            return Err(anyhow::format_err!(
-                "Unable to write violations to {err_file_output_path}.\nError: {err}"
-            ));
-        }
-    };
+    )
+    .map_err(|err| {
+        anyhow::format_err!("Unable to write violations to {err_file_output_path}.\nError: {err}")
+    })?;
     Ok(())
 }
 
@@ -166,4 +374,13 @@ pub fn write_violations_as_json(
 pub struct JsonErrorFile<'a> {
     pub violations: Vec<evaluate_fences::ImportRuleViolation<'a, 'a>>,
     pub evaluation_errors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouped_summary: Option<Vec<violation_groups::ViolationGroup>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_summary: Option<Vec<violation_groups::OwnerViolationGroup>>,
+    // CODEOWNERS owners of each file with violations, if grouped by owner
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub violation_owners: BTreeMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fence_lints: Vec<fence_conflicts::FenceRuleConflict<'a>>,
 }
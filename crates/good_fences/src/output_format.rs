@@ -0,0 +1,135 @@
+use crate::evaluate_fences::{FenceEvaluationResult, ImportRuleViolation};
+use crate::fence_conflicts::FenceRuleConflict;
+use serde::Serialize;
+use std::fmt::Display;
+use std::io::Write;
+
+#[derive(Eq, Debug, PartialEq, Copy, Clone, Default)]
+pub enum OutputFormat {
+    // the human readable summary of the results
+    #[default]
+    Human,
+    // a single json document of the results, once evaluation is done
+    Json,
+    // one json object per line for each violation, written as it is found
+    Ndjson,
+}
+
+impl OutputFormat {
+    /**
+     * Prints a status message. In the machine readable formats stdout is
+     * reserved for the results, so messages go to stderr instead.
+     */
+    pub fn print_status(&self, message: impl Display) {
+        match self {
+            OutputFormat::Human => println!("{}", message),
+            OutputFormat::Json | OutputFormat::Ndjson => eprintln!("{}", message),
+        }
+    }
+
+    /**
+     * Prints a status message that is not part of the human readable output,
+     * so it is only printed, to stderr, in the machine readable formats.
+     */
+    pub fn print_machine_status(&self, message: impl Display) {
+        match self {
+            OutputFormat::Human => {}
+            OutputFormat::Json | OutputFormat::Ndjson => eprintln!("{}", message),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonOutput<'a> {
+    violations: &'a [ImportRuleViolation<'a, 'a>],
+    evaluation_errors: Vec<String>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    fence_lints: &'a [FenceRuleConflict<'a>],
+}
+
+/**
+ * Writes the violations, evaluation errors and fence lints of an evaluation
+ * as a single json document
+ */
+pub fn write_json(
+    mut writer: impl Write,
+    eval_results: &FenceEvaluationResult,
+    fence_lints: &[FenceRuleConflict],
+) -> anyhow::Result<()> {
+    serde_json::to_writer_pretty(
+        &mut writer,
+        &JsonOutput {
+            violations: &eval_results.violations,
+            evaluation_errors: eval_results
+                .unresolved_files
+                .iter()
+                .map(|error| error.to_string())
+                .collect(),
+            fence_lints,
+        },
+    )?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/**
+ * Writes a violation as a json object on its own line
+ */
+pub fn write_ndjson_line(
+    mut writer: impl Write,
+    violation: &ImportRuleViolation,
+) -> anyhow::Result<()> {
+    serde_json::to_writer(&mut writer, violation)?;
+    writer.write_all(b"\n")?;
+    // flush each line, so a consumer can process violations as they are found
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::write_ndjson_line;
+    use crate::evaluate_fences::{ImportRuleViolation, ViolatedFenceClause};
+    use crate::fence::parse_fence_str;
+    use relative_path::RelativePath;
+
+    #[test]
+    fn test_write_ndjson() {
+        let fence = parse_fence_str(
+            r#"{"tags": ["protected"], "exports": []}"#,
+            RelativePath::new("src/protected/fence.json"),
+        )
+        .unwrap();
+        let violations = vec![
+            ImportRuleViolation {
+                violating_file_path: "src/a.ts",
+                violating_fence: &fence,
+                violating_fence_clause: ViolatedFenceClause::ExportRule(None),
+                violating_import_specifier: "./protected/internal",
+                violating_imported_name: None,
+            },
+            ImportRuleViolation {
+                violating_file_path: "src/b.ts",
+                violating_fence: &fence,
+                violating_fence_clause: ViolatedFenceClause::ImportAllowList,
+                violating_import_specifier: "./protected/internal",
+                violating_imported_name: Some("internal"),
+            },
+        ];
+
+        let mut output = Vec::new();
+        for violation in violations.iter() {
+            write_ndjson_line(&mut output, violation).unwrap();
+        }
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["violating_file_path"], "src/a.ts");
+        assert_eq!(lines[1]["violating_file_path"], "src/b.ts");
+        assert_eq!(lines[1]["violating_imported_name"], "internal");
+    }
+}
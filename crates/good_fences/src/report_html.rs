@@ -0,0 +1,273 @@
+use crate::evaluate_fences::{FenceEvaluationResult, ImportRuleViolation, ViolatedFenceClause};
+use crate::fence::Fence;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/**
+ * Renders a fence evaluation result as a single self-contained HTML page,
+ * with no external assets, so it can be attached to CI artifacts.
+ */
+pub fn render_html_report(result: &FenceEvaluationResult) -> String {
+    // group violations by fence
+    let mut violations_by_fence: BTreeMap<&str, Vec<&ImportRuleViolation>> = BTreeMap::new();
+    for violation in result.violations.iter() {
+        violations_by_fence
+            .entry(violation.violating_fence.fence_path.as_str())
+            .or_default()
+            .push(violation);
+    }
+
+    let mut fence_sections = String::new();
+    for (fence_path, violations) in violations_by_fence.iter_mut() {
+        let fence: &Fence = violations[0].violating_fence;
+        let tags = fence.fence.tags.as_deref().unwrap_or_default().join(" ");
+
+        // list violations grouped by the kind of clause they violated
+        violations.sort_by_key(|violation| clause_kind_order(&violation.violating_fence_clause));
+        let mut clause_sections = String::new();
+        for clause_violations in violations.chunk_by(|a, b| {
+            clause_kind_order(&a.violating_fence_clause)
+                == clause_kind_order(&b.violating_fence_clause)
+        }) {
+            let mut rows = String::new();
+            for violation in clause_violations {
+                writeln!(
+                    rows,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(violation.violating_file_path),
+                    escape_html(violation.violating_import_specifier),
+                    escape_html(violation.violating_imported_name.unwrap_or("")),
+                    escape_html(&clause_rule_description(&violation.violating_fence_clause)),
+                )
+                .unwrap();
+            }
+            writeln!(
+                clause_sections,
+                "<h3>{}</h3>\n<table>\n<tr><th>Importing file</th><th>Specifier</th><th>Imported name</th><th>Rule</th></tr>\n{}</table>",
+                clause_kind_label(&clause_violations[0].violating_fence_clause),
+                rows,
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            fence_sections,
+            "<details class=\"fence\" data-fence-path=\"{path}\" data-tags=\"{tags}\" open>\n<summary>{path} <span class=\"tags\">[{tags}]</span> ({count} violations)</summary>\n{clauses}</details>",
+            path = escape_html(fence_path),
+            tags = escape_html(&tags),
+            count = violations.len(),
+            clauses = clause_sections,
+        )
+        .unwrap();
+    }
+
+    let mut unresolved_section = String::new();
+    if !result.unresolved_files.is_empty() {
+        unresolved_section.push_str(
+            "<details class=\"unresolved\">\n<summary>Unresolved files</summary>\n<ul>\n",
+        );
+        for unresolved in result.unresolved_files.iter() {
+            writeln!(
+                unresolved_section,
+                "<li>{}</li>",
+                escape_html(&unresolved.to_string())
+            )
+            .unwrap();
+        }
+        unresolved_section.push_str("</ul>\n</details>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>good-fences report</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; margin-bottom: 1em; }}
+th, td {{ border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: left; }}
+summary {{ cursor: pointer; font-weight: bold; }}
+.tags {{ color: #666; font-weight: normal; }}
+</style>
+</head>
+<body>
+<h1>good-fences report</h1>
+<ul class="summary">
+<li>Total violations: {violation_count}</li>
+<li>Unresolved files: {unresolved_count}</li>
+<li>Fences involved: {fence_count}</li>
+</ul>
+<input id="filter" type="search" placeholder="Filter by fence path or tag">
+<h2>Violations</h2>
+{fence_sections}{unresolved_section}<script>
+document.getElementById("filter").addEventListener("input", function (e) {{
+  var query = e.target.value.toLowerCase();
+  document.querySelectorAll("details.fence").forEach(function (fence) {{
+    var haystack = (fence.dataset.fencePath + " " + fence.dataset.tags).toLowerCase();
+    fence.style.display = haystack.indexOf(query) === -1 ? "none" : "";
+  }});
+}});
+</script>
+</body>
+</html>
+"#,
+        violation_count = result.violations.len(),
+        unresolved_count = result.unresolved_files.len(),
+        fence_count = violations_by_fence.len(),
+        fence_sections = fence_sections,
+        unresolved_section = unresolved_section,
+    )
+}
+
+/**
+ * Renders the HTML report for a fence evaluation result, and writes it to the given path.
+ */
+pub fn write_html_report(result: &FenceEvaluationResult, output_path: &str) -> anyhow::Result<()> {
+    std::fs::write(output_path, render_html_report(result)).map_err(|err| {
+        anyhow::format_err!("Unable to write html report to {output_path}.\nError: {err}")
+    })?;
+    Ok(())
+}
+
+// order in which clause kinds are listed within a fence
+fn clause_kind_order(clause: &ViolatedFenceClause) -> usize {
+    match clause {
+        ViolatedFenceClause::ExportRule(_) => 0,
+        ViolatedFenceClause::DependencyRule(_) => 1,
+        ViolatedFenceClause::ImportAllowList => 2,
+        ViolatedFenceClause::NoDefaultExports => 3,
+    }
+}
+
+fn clause_kind_label(clause: &ViolatedFenceClause) -> &'static str {
+    match clause {
+        ViolatedFenceClause::ExportRule(_) => "Export rules",
+        ViolatedFenceClause::DependencyRule(_) => "Dependency rules",
+        ViolatedFenceClause::ImportAllowList => "Import allow list",
+        ViolatedFenceClause::NoDefaultExports => "Default exports",
+    }
+}
+
+fn clause_rule_description(clause: &ViolatedFenceClause) -> String {
+    match clause {
+        ViolatedFenceClause::ExportRule(Some(rule)) => format!(
+            "{} only accessible to {}",
+            rule.modules,
+            rule.accessible_to.join(", ")
+        ),
+        ViolatedFenceClause::ExportRule(None) => "not exported".to_owned(),
+        ViolatedFenceClause::DependencyRule(Some(rule)) => format!(
+            "{} only accessible to {}",
+            rule.dependency,
+            rule.accessible_to.join(", ")
+        ),
+        ViolatedFenceClause::DependencyRule(None) => "not in dependency allow list".to_owned(),
+        ViolatedFenceClause::ImportAllowList => "not in import allow list".to_owned(),
+        ViolatedFenceClause::NoDefaultExports => "default exports not allowed".to_owned(),
+    }
+}
+
+fn escape_html(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use relative_path::RelativePathBuf;
+
+    use super::render_html_report;
+    use crate::error::EvaluateFencesError;
+    use crate::evaluate_fences::{FenceEvaluationResult, ImportRuleViolation, ViolatedFenceClause};
+    use crate::fence::parse_fence_str;
+
+    #[test]
+    fn test_render_html_report_golden() {
+        let core_fence = parse_fence_str(
+            r#"{"tags": ["core"], "exports": [{"modules": "index", "accessibleTo": "ui"}], "dependencies": [{"dependency": "react", "accessibleTo": "ui"}]}"#,
+            &RelativePathBuf::from("src/core/fence.json"),
+        )
+        .unwrap();
+        let ui_fence = parse_fence_str(
+            r#"{"tags": ["ui"], "imports": ["core"]}"#,
+            &RelativePathBuf::from("src/ui/fence.json"),
+        )
+        .unwrap();
+        let export_rule = &core_fence.fence.exports.as_ref().unwrap()[0];
+        let dependency_rule = &core_fence.fence.dependencies.as_ref().unwrap()[0];
+
+        let result = FenceEvaluationResult {
+            violations: vec![
+                ImportRuleViolation {
+                    violating_file_path: "src/util/helper.ts",
+                    violating_fence: &core_fence,
+                    violating_fence_clause: ViolatedFenceClause::ExportRule(Some(export_rule)),
+                    violating_import_specifier: "../core",
+                    violating_imported_name: Some("coreHelper"),
+                },
+                ImportRuleViolation {
+                    violating_file_path: "src/util/view.ts",
+                    violating_fence: &core_fence,
+                    violating_fence_clause: ViolatedFenceClause::DependencyRule(Some(
+                        dependency_rule,
+                    )),
+                    violating_import_specifier: "react",
+                    violating_imported_name: None,
+                },
+                ImportRuleViolation {
+                    violating_file_path: "src/ui/button.ts",
+                    violating_fence: &ui_fence,
+                    violating_fence_clause: ViolatedFenceClause::ImportAllowList,
+                    violating_import_specifier: "../util/helper",
+                    violating_imported_name: None,
+                },
+            ],
+            unresolved_files: vec![EvaluateFencesError::ImportNotResolved {
+                import_specifier: "./missing".to_owned(),
+                source_file_path: "src/ui/button.ts".to_owned(),
+            }],
+            stats: Default::default(),
+        };
+
+        let expected = include_str!("../tests/report_html/report.golden.html");
+        assert_eq!(expected, render_html_report(&result));
+    }
+
+    #[test]
+    fn test_render_html_report_escapes_paths() {
+        let fence = parse_fence_str(
+            r#"{"tags": ["<b>"], "imports": []}"#,
+            &RelativePathBuf::from("src/<script>/fence.json"),
+        )
+        .unwrap();
+        let result = FenceEvaluationResult {
+            violations: vec![ImportRuleViolation {
+                violating_file_path: "src/<script>alert(1)</script>.ts",
+                violating_fence: &fence,
+                violating_fence_clause: ViolatedFenceClause::ImportAllowList,
+                violating_import_specifier: "a&b",
+                violating_imported_name: None,
+            }],
+            unresolved_files: vec![],
+            stats: Default::default(),
+        };
+
+        let html = render_html_report(&result);
+        assert!(!html.contains("<script>alert"));
+        assert!(html.contains("src/&lt;script&gt;alert(1)&lt;/script&gt;.ts"));
+        assert!(html.contains("data-fence-path=\"src/&lt;script&gt;/fence.json\""));
+        assert!(html.contains("[&lt;b&gt;]"));
+        assert!(html.contains("a&amp;b"));
+    }
+}
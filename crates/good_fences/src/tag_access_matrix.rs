@@ -0,0 +1,144 @@
+use crate::evaluate_fences::AccessibleToGlobs;
+use crate::fence::Fence;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/**
+ * Whether a file of one tag may import a module of another tag, for every pair
+ * of tags defined across a set of fences.
+ *
+ * This is computed from the fences alone, without walking any source files, so
+ * it assumes the importing file and the imported module sit behind different
+ * fences and ignores which modules each export rule applies to.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagAccessMatrix {
+    // all tags defined by the fences, sorted
+    pub tags: Vec<String>,
+    // permitted[i][j] is true if a file tagged tags[i] may import a module tagged tags[j]
+    pub permitted: Vec<Vec<bool>>,
+}
+
+impl TagAccessMatrix {
+    pub fn from_fences<'a>(fences: impl IntoIterator<Item = &'a Fence>) -> TagAccessMatrix {
+        let fences: Vec<&Fence> = fences.into_iter().collect();
+        let tags: Vec<String> = fences
+            .iter()
+            .filter_map(|fence| fence.fence.tags.as_ref())
+            .flatten()
+            .cloned()
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+
+        let accessible_to_globs = AccessibleToGlobs::from_fences(fences.iter().copied());
+        let permitted = tags
+            .iter()
+            .map(|from_tag| {
+                tags.iter()
+                    .map(|to_tag| {
+                        is_tag_access_permitted(&fences, &accessible_to_globs, from_tag, to_tag)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        TagAccessMatrix { tags, permitted }
+    }
+
+    /**
+     * Returns whether a file tagged from_tag may import a module tagged to_tag,
+     * or None if either tag is not defined by any fence.
+     */
+    pub fn is_permitted(&self, from_tag: &str, to_tag: &str) -> Option<bool> {
+        let from_idx = self.tags.iter().position(|tag| tag == from_tag)?;
+        let to_idx = self.tags.iter().position(|tag| tag == to_tag)?;
+        Some(self.permitted[from_idx][to_idx])
+    }
+}
+
+fn fence_has_tag(fence: &Fence, tag: &str) -> bool {
+    fence
+        .fence
+        .tags
+        .as_ref()
+        .is_some_and(|tags| tags.iter().any(|t| t == tag))
+}
+
+fn is_tag_access_permitted(
+    fences: &[&Fence],
+    accessible_to_globs: &AccessibleToGlobs,
+    from_tag: &str,
+    to_tag: &str,
+) -> bool {
+    // the importer's fences must allow importing the target tag
+    let imports_allowed = fences
+        .iter()
+        .filter(|fence| fence_has_tag(fence, from_tag))
+        .all(|fence| match fence.fence.imports.as_ref() {
+            Some(imports) => imports.iter().any(|tag| tag == to_tag),
+            None => true,
+        });
+
+    // the target's fences must export something to the importer's tag
+    let exports_allowed = fences
+        .iter()
+        .filter(|fence| fence_has_tag(fence, to_tag))
+        .all(|fence| match fence.fence.exports.as_ref() {
+            Some(exports) => exports.iter().any(|export_rule| {
+                export_rule
+                    .accessible_to
+                    .iter()
+                    .any(|tag| accessible_to_globs.allows_any_tag(tag, std::iter::once(from_tag)))
+            }),
+            None => true,
+        });
+
+    imports_allowed && exports_allowed
+}
+
+#[cfg(test)]
+mod test {
+    use relative_path::RelativePathBuf;
+
+    use super::TagAccessMatrix;
+    use crate::fence::parse_fence_str;
+
+    #[test]
+    fn test_tag_access_matrix() {
+        let fences = vec![
+            parse_fence_str(
+                r#"{"tags": ["ui"], "imports": ["ui", "core"]}"#,
+                &RelativePathBuf::from("ui/fence.json"),
+            )
+            .unwrap(),
+            parse_fence_str(
+                r#"{"tags": ["core"], "exports": [{"modules": "index", "accessibleTo": "ui"}]}"#,
+                &RelativePathBuf::from("core/fence.json"),
+            )
+            .unwrap(),
+            parse_fence_str(
+                r#"{"tags": ["util"]}"#,
+                &RelativePathBuf::from("util/fence.json"),
+            )
+            .unwrap(),
+        ];
+
+        let matrix = TagAccessMatrix::from_fences(&fences);
+
+        assert_eq!(matrix.tags, vec!["core", "ui", "util"]);
+        // ui may only import ui and core
+        assert_eq!(matrix.is_permitted("ui", "core"), Some(true));
+        assert_eq!(matrix.is_permitted("ui", "ui"), Some(true));
+        assert_eq!(matrix.is_permitted("ui", "util"), Some(false));
+        // core only exports to ui
+        assert_eq!(matrix.is_permitted("util", "core"), Some(false));
+        assert_eq!(matrix.is_permitted("core", "core"), Some(false));
+        // util is unrestricted in both directions
+        assert_eq!(matrix.is_permitted("util", "ui"), Some(true));
+        assert_eq!(matrix.is_permitted("core", "util"), Some(true));
+        // undefined tags are not in the matrix
+        assert_eq!(matrix.is_permitted("ui", "undefined"), None);
+    }
+}
@@ -0,0 +1,433 @@
+use crate::codeowners::CodeOwners;
+use crate::evaluate_fences::ImportRuleViolation;
+use crate::walk_dirs::SourceFile;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::path::Path;
+
+/**
+ * Tag used for the importing side or the fence side of a group when there
+ * are no tags to pick a representative from.
+ */
+pub const UNTAGGED: &str = "(untagged)";
+
+/**
+ * Owner used for the group of violations in files without any CODEOWNERS owner
+ */
+pub const UNOWNED: &str = "(unowned)";
+
+#[derive(Eq, Debug, PartialEq, Copy, Clone)]
+pub enum ViolationGrouping {
+    // group violations by the tags of the importing file and the violated fence
+    Layer,
+    // group violations by the CODEOWNERS owners of the importing file
+    Owner,
+}
+
+/**
+ * A violation included as an example of the violations in a group
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct ViolationSample {
+    pub violating_file_path: String,
+    pub violating_import_specifier: String,
+    pub violating_fence_path: String,
+}
+
+/**
+ * All violations where files of one layer import across the fence of another
+ * layer, where each layer is represented by a single tag.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct ViolationGroup {
+    pub importer_tag: String,
+    pub fence_tag: String,
+    pub count: usize,
+    // the first violations of the group, in the order they were found
+    pub samples: Vec<ViolationSample>,
+}
+
+impl Display for ViolationGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} → {}: {} violation{}",
+            self.importer_tag,
+            self.fence_tag,
+            self.count,
+            if self.count == 1 { "" } else { "s" }
+        )?;
+        for sample in self.samples.iter() {
+            write!(
+                f,
+                "\n  {} imports {} across {}",
+                sample.violating_file_path,
+                sample.violating_import_specifier,
+                sample.violating_fence_path
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * All violations in files owned by one owner in the CODEOWNERS file.
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct OwnerViolationGroup {
+    pub owner: String,
+    pub count: usize,
+    // the first violations of the group, in the order they were found
+    pub samples: Vec<ViolationSample>,
+}
+
+impl Display for OwnerViolationGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} violation{}",
+            self.owner,
+            self.count,
+            if self.count == 1 { "" } else { "s" }
+        )?;
+        for sample in self.samples.iter() {
+            write!(
+                f,
+                "\n  {} imports {} across {}",
+                sample.violating_file_path,
+                sample.violating_import_specifier,
+                sample.violating_fence_path
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ViolationSample {
+    fn new(violation: &ImportRuleViolation) -> Self {
+        ViolationSample {
+            violating_file_path: violation.violating_file_path.to_owned(),
+            violating_import_specifier: violation.violating_import_specifier.to_owned(),
+            violating_fence_path: violation.violating_fence.fence_path.clone(),
+        }
+    }
+}
+
+/**
+ * Picks a single tag to represent a set of tags. The first tag in the priority
+ * list that is in the set wins, falling back to the alphabetically first tag.
+ */
+fn representative_tag<'a>(
+    tags: impl Iterator<Item = &'a String>,
+    tag_priority: &[String],
+) -> String {
+    let tags: HashSet<&String> = tags.collect();
+    tag_priority
+        .iter()
+        .find(|tag| tags.contains(tag))
+        .or_else(|| tags.iter().min().copied())
+        .cloned()
+        .unwrap_or_else(|| UNTAGGED.to_owned())
+}
+
+/**
+ * Groups violations by the relationship they break, as a directed pair from
+ * the importing file's tag to the violated fence's tag.
+ *
+ * Groups are sorted by descending count, then by tag pair.
+ */
+pub fn group_violations_by_layer(
+    violations: &[ImportRuleViolation],
+    source_files: &HashMap<String, SourceFile>,
+    tag_priority: &[String],
+    max_samples: usize,
+) -> Vec<ViolationGroup> {
+    let mut groups: BTreeMap<(String, String), ViolationGroup> = BTreeMap::new();
+    for violation in violations.iter() {
+        let importer_tag = representative_tag(
+            source_files
+                .get(violation.violating_file_path)
+                .into_iter()
+                .flat_map(|source_file| source_file.tags.iter()),
+            tag_priority,
+        );
+        let fence_tag = representative_tag(
+            violation.violating_fence.fence.tags.iter().flatten(),
+            tag_priority,
+        );
+
+        let group = groups
+            .entry((importer_tag.clone(), fence_tag.clone()))
+            .or_insert_with(|| ViolationGroup {
+                importer_tag,
+                fence_tag,
+                count: 0,
+                samples: Vec::new(),
+            });
+        group.count += 1;
+        if group.samples.len() < max_samples {
+            group.samples.push(ViolationSample::new(violation));
+        }
+    }
+
+    let mut groups: Vec<ViolationGroup> = groups.into_values().collect();
+    // stable sort, so groups with equal counts stay ordered by tag pair
+    groups.sort_by(|a, b| b.count.cmp(&a.count));
+    groups
+}
+
+/**
+ * Gets the CODEOWNERS owners of the file of each violation, by file path.
+ * Files without owners are not included.
+ */
+pub fn violation_owners(
+    violations: &[ImportRuleViolation],
+    code_owners: &CodeOwners,
+) -> BTreeMap<String, Vec<String>> {
+    violations
+        .iter()
+        .filter_map(|violation| {
+            let owners = code_owners.owners_of(Path::new(violation.violating_file_path));
+            if owners.is_empty() {
+                None
+            } else {
+                Some((violation.violating_file_path.to_owned(), owners.to_vec()))
+            }
+        })
+        .collect()
+}
+
+/**
+ * Groups violations by the CODEOWNERS owners of the importing file. A violation
+ * in a file with several owners is counted in the group of each owner.
+ *
+ * Groups are sorted by descending count, then by owner.
+ */
+pub fn group_violations_by_owner(
+    violations: &[ImportRuleViolation],
+    code_owners: &CodeOwners,
+    max_samples: usize,
+) -> Vec<OwnerViolationGroup> {
+    let mut groups: BTreeMap<String, OwnerViolationGroup> = BTreeMap::new();
+    for violation in violations.iter() {
+        let owners = code_owners.owners_of(Path::new(violation.violating_file_path));
+        let unowned = [UNOWNED.to_owned()];
+        let owners = if owners.is_empty() {
+            &unowned[..]
+        } else {
+            owners
+        };
+        for owner in owners.iter() {
+            let group = groups
+                .entry(owner.clone())
+                .or_insert_with(|| OwnerViolationGroup {
+                    owner: owner.clone(),
+                    count: 0,
+                    samples: Vec::new(),
+                });
+            group.count += 1;
+            if group.samples.len() < max_samples {
+                group.samples.push(ViolationSample::new(violation));
+            }
+        }
+    }
+
+    let mut groups: Vec<OwnerViolationGroup> = groups.into_values().collect();
+    // stable sort, so groups with equal counts stay ordered by owner
+    groups.sort_by(|a, b| b.count.cmp(&a.count));
+    groups
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::env::current_dir;
+
+    use relative_path::RelativePathBuf;
+
+    use super::{
+        group_violations_by_layer, group_violations_by_owner, violation_owners,
+        OwnerViolationGroup, ViolationGroup, ViolationSample, UNOWNED, UNTAGGED,
+    };
+    use crate::codeowners::CodeOwners;
+    use crate::evaluate_fences::{ImportRuleViolation, ViolatedFenceClause};
+    use crate::fence::{parse_fence_str, Fence};
+    use crate::walk_dirs::SourceFile;
+
+    fn source_file(path: &str, tags: &[&str]) -> (String, SourceFile) {
+        (
+            path.to_owned(),
+            SourceFile {
+                source_file_path: path.to_owned(),
+                has_default_export: false,
+                tags: HashSet::from_iter(tags.iter().map(|tag| tag.to_string())),
+                imports: HashMap::new(),
+            },
+        )
+    }
+
+    fn violation<'a>(file_path: &'a str, fence: &'a Fence) -> ImportRuleViolation<'a, 'a> {
+        ImportRuleViolation {
+            violating_file_path: file_path,
+            violating_fence: fence,
+            violating_fence_clause: ViolatedFenceClause::ImportAllowList,
+            violating_import_specifier: "../data",
+            violating_imported_name: None,
+        }
+    }
+
+    fn sample(file_path: &str, fence: &Fence) -> ViolationSample {
+        ViolationSample {
+            violating_file_path: file_path.to_owned(),
+            violating_import_specifier: "../data".to_owned(),
+            violating_fence_path: fence.fence_path.clone(),
+        }
+    }
+
+    #[test]
+    fn test_group_violations_by_layer() {
+        let data_fence = parse_fence_str(
+            r#"{"tags": ["data-access", "shared"]}"#,
+            &RelativePathBuf::from("src/data/fence.json"),
+        )
+        .unwrap();
+        let calendar_fence = parse_fence_str(
+            r#"{"tags": ["feature-calendar"]}"#,
+            &RelativePathBuf::from("src/calendar/fence.json"),
+        )
+        .unwrap();
+
+        let source_files = HashMap::from_iter([
+            source_file("src/ui/a.ts", &["ui", "shared"]),
+            source_file("src/ui/b.ts", &["ui"]),
+            source_file("src/ui/c.ts", &["ui"]),
+            source_file("src/mail/d.ts", &["feature-mail"]),
+        ]);
+        let violations = vec![
+            violation("src/ui/a.ts", &data_fence),
+            violation("src/mail/d.ts", &calendar_fence),
+            violation("src/ui/b.ts", &data_fence),
+            violation("src/ui/c.ts", &data_fence),
+        ];
+
+        let groups = group_violations_by_layer(
+            &violations,
+            &source_files,
+            &["ui".to_owned(), "data-access".to_owned()],
+            2,
+        );
+
+        assert_eq!(
+            groups,
+            vec![
+                ViolationGroup {
+                    importer_tag: "ui".to_owned(),
+                    fence_tag: "data-access".to_owned(),
+                    count: 3,
+                    samples: vec![
+                        sample("src/ui/a.ts", &data_fence),
+                        sample("src/ui/b.ts", &data_fence),
+                    ],
+                },
+                ViolationGroup {
+                    importer_tag: "feature-mail".to_owned(),
+                    fence_tag: "feature-calendar".to_owned(),
+                    count: 1,
+                    samples: vec![sample("src/mail/d.ts", &calendar_fence)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_violations_by_layer_untagged() {
+        let data_fence = parse_fence_str(
+            r#"{"tags": ["data-access"]}"#,
+            &RelativePathBuf::from("src/data/fence.json"),
+        )
+        .unwrap();
+        let untagged_fence =
+            parse_fence_str(r#"{}"#, &RelativePathBuf::from("src/other/fence.json")).unwrap();
+
+        let source_files = HashMap::from_iter([source_file("src/ui/a.ts", &[])]);
+        let violations = vec![
+            violation("src/ui/a.ts", &data_fence),
+            // files that were not walked have no tags either
+            violation("src/unknown/b.ts", &untagged_fence),
+        ];
+
+        let groups = group_violations_by_layer(&violations, &source_files, &[], 5);
+
+        assert_eq!(
+            groups,
+            vec![
+                ViolationGroup {
+                    importer_tag: UNTAGGED.to_owned(),
+                    fence_tag: UNTAGGED.to_owned(),
+                    count: 1,
+                    samples: vec![sample("src/unknown/b.ts", &untagged_fence)],
+                },
+                ViolationGroup {
+                    importer_tag: UNTAGGED.to_owned(),
+                    fence_tag: "data-access".to_owned(),
+                    count: 1,
+                    samples: vec![sample("src/ui/a.ts", &data_fence)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_violations_by_owner() {
+        let data_fence = parse_fence_str(
+            r#"{"tags": ["data-access"]}"#,
+            &RelativePathBuf::from("src/data/fence.json"),
+        )
+        .unwrap();
+        let code_owners = CodeOwners::parse(
+            &current_dir().unwrap(),
+            "src/ui/ @team-a\nsrc/mail/ @team-a @team-mail\n",
+        )
+        .unwrap();
+        let violations = vec![
+            violation("src/ui/a.ts", &data_fence),
+            violation("src/mail/b.ts", &data_fence),
+            violation("src/other/c.ts", &data_fence),
+        ];
+
+        assert_eq!(
+            violation_owners(&violations, &code_owners),
+            BTreeMap::from_iter([
+                (
+                    "src/mail/b.ts".to_owned(),
+                    vec!["@team-a".to_owned(), "@team-mail".to_owned()]
+                ),
+                ("src/ui/a.ts".to_owned(), vec!["@team-a".to_owned()]),
+            ])
+        );
+        assert_eq!(
+            group_violations_by_owner(&violations, &code_owners, 5),
+            vec![
+                OwnerViolationGroup {
+                    owner: "@team-a".to_owned(),
+                    count: 2,
+                    samples: vec![
+                        sample("src/ui/a.ts", &data_fence),
+                        sample("src/mail/b.ts", &data_fence),
+                    ],
+                },
+                OwnerViolationGroup {
+                    owner: UNOWNED.to_owned(),
+                    count: 1,
+                    samples: vec![sample("src/other/c.ts", &data_fence)],
+                },
+                OwnerViolationGroup {
+                    owner: "@team-mail".to_owned(),
+                    count: 1,
+                    samples: vec![sample("src/mail/b.ts", &data_fence)],
+                },
+            ]
+        );
+    }
+}
@@ -1,12 +1,13 @@
 use crate::fence::{parse_fence_file, Fence};
-use crate::get_imports::get_imports_map_from_file;
+use crate::fence_collection::{is_within_fence_root, FenceRoot};
+use crate::get_imports::parse_source_file;
 use anyhow::{anyhow, Error, Result};
 use jwalk::WalkDirGeneric;
 use path_slash::PathExt;
 use path_utils::as_relative_slash_path;
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn should_retain_file(s: &str) -> bool {
     s == "fence.json"
@@ -22,6 +23,9 @@ pub struct SourceFile {
     // ref to the strings of tags that apply to this file
     pub tags: HashSet<String>,
     pub imports: HashMap<String, Option<HashSet<String>>>,
+    // true if the file declares a default export
+    #[serde(default)]
+    pub has_default_export: bool,
 }
 
 #[derive(Eq, Debug, PartialEq, Copy, Clone)]
@@ -48,13 +52,14 @@ lazy_static! {
 
 fn discover_js_ts_src(file_path: &PathBuf, tags: HashSet<String>) -> Result<WalkFileData, Error> {
     let relative_file_path = as_relative_slash_path(file_path)?;
-    let imports = get_imports_map_from_file(&relative_file_path)
+    let parsed_source_file = parse_source_file(&relative_file_path)
         .map_err(|e| anyhow!("Error getting imports from file {:?}: {}", file_path, e))?;
 
     Ok(WalkFileData::SourceFile(SourceFile {
         source_file_path: relative_file_path.into_string(),
-        imports,
+        imports: parsed_source_file.imports,
         tags,
+        has_default_export: parsed_source_file.has_default_export,
     }))
 }
 
@@ -62,6 +67,7 @@ pub fn discover_fences_and_files(
     start_path: &str,
     ignore_external_fences: ExternalFences,
     ignored_dirs: Vec<regex::Regex>,
+    fence_root: Option<FenceRoot>,
 ) -> Vec<WalkFileData> {
     let walk_dir = WalkDirGeneric::<(TagList, WalkFileData)>::new(start_path).process_read_dir(
         move |read_dir_state, children| {
@@ -118,6 +124,18 @@ pub fn discover_fences_and_files(
                                             continue;
                                         }
                                     };
+                                    // fences above the fence root do not apply to the project
+                                    let fence_dir = Path::new(&fence.fence_path)
+                                        .parent()
+                                        .unwrap_or(Path::new(""));
+                                    if !is_within_fence_root(fence_root.as_ref(), fence_dir) {
+                                        continue;
+                                    }
+                                    // root fences and fences that do not inherit tags
+                                    // drop the tags of any fences above them
+                                    if fence.is_root() || !fence.inherits_tags() {
+                                        read_dir_state.clear();
+                                    }
                                     // update fences
                                     let tags_clone = fence.fence.tags.clone();
                                     if tags_clone.is_some() {
@@ -195,9 +213,11 @@ pub fn discover_fences_and_files(
 #[cfg(test)]
 mod test {
     use crate::fence::{Fence, ParsedFence};
+    use crate::fence_collection::FenceRoot;
     use crate::walk_dirs::{discover_fences_and_files, SourceFile, WalkFileData};
     use std::collections::{HashMap, HashSet};
     use std::iter::{FromIterator, Iterator};
+    use std::path::Path;
 
     macro_rules! map(
         { $($key:expr => $value:expr),+ } => {
@@ -229,6 +249,7 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             Vec::new(),
+            None,
         );
 
         let expected_root_fence = Fence {
@@ -241,6 +262,9 @@ mod test {
                 exports: Option::None,
                 dependencies: Option::None,
                 imports: Option::None,
+                root: Option::None,
+                inherit_tags: Option::None,
+                no_default_exports: Option::None,
             },
         };
 
@@ -261,6 +285,7 @@ mod test {
             "./tests/comments_panel_test",
             crate::walk_dirs::ExternalFences::Ignore,
             Vec::new(),
+            None,
         );
 
         let expected = "tests/comments_panel_test/packages/accelerator/accelerator-common/src/CommentsPanel/index.ts";
@@ -285,6 +310,7 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             Vec::new(),
+            None,
         );
 
         let expected_subsubdir_fence = Fence {
@@ -294,6 +320,9 @@ mod test {
                 exports: Option::None,
                 dependencies: Option::None,
                 imports: Option::None,
+                root: Option::None,
+                inherit_tags: Option::None,
+                no_default_exports: Option::None,
             },
         };
 
@@ -314,10 +343,12 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             Vec::new(),
+            None,
         );
 
         let expected_root_ts_file = SourceFile {
             source_file_path: "tests/walk_dir_simple/rootFile.ts".to_owned(),
+            has_default_export: false,
             tags: set!("root-fence-tag-1".to_owned(), "root-fence-tag-2".to_owned()),
             imports: map!(
               "root-ts-file-import-1" => Option::Some(set!("importFromRootFile"))
@@ -341,10 +372,12 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             Vec::new(),
+            None,
         );
 
         let expected_subdir_ts_file = SourceFile {
             source_file_path: "tests/walk_dir_simple/subdir/subDirFile.ts".to_owned(),
+            has_default_export: false,
             tags: set!("root-fence-tag-1".to_owned(), "root-fence-tag-2".to_owned()),
             imports: map!(
               "subdir-file-default-import" => Option::Some(set!("default")),
@@ -369,10 +402,12 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             Vec::new(),
+            None,
         );
 
         let expected_subdir_ts_file = SourceFile {
             source_file_path: "tests/walk_dir_simple/subdir/subsubdir/subSubDirFile.ts".to_owned(),
+            has_default_export: false,
             tags: set!(
                 "root-fence-tag-1".to_owned(),
                 "root-fence-tag-2".to_owned(),
@@ -400,10 +435,12 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             vec![regex::Regex::new("tests/.**/subdir").unwrap()],
+            None,
         );
 
         let expected_subdir_ts_file = SourceFile {
             source_file_path: "tests/walk_dir_simple/subdir/subsubdir/subSubDirFile.ts".to_owned(),
+            has_default_export: false,
             tags: set!(
                 "root-fence-tag-1".to_owned(),
                 "root-fence-tag-2".to_owned(),
@@ -430,6 +467,7 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             vec![regex::Regex::new("tests/.**/subdir").unwrap()],
+            None,
         );
 
         assert!(
@@ -449,6 +487,7 @@ mod test {
             "tests/walk_dir_simple",
             crate::walk_dirs::ExternalFences::Ignore,
             vec![regex::Regex::new("^subdir").unwrap()],
+            None,
         );
 
         let expected_subdir_fence = Fence {
@@ -458,6 +497,9 @@ mod test {
                 exports: Option::None,
                 dependencies: Option::None,
                 imports: Option::None,
+                root: Option::None,
+                inherit_tags: Option::None,
+                no_default_exports: Option::None,
             },
         };
 
@@ -470,4 +512,79 @@ mod test {
             discovered
         );
     }
+
+    fn find_source_file<'a>(discovered: &'a [WalkFileData], path: &str) -> &'a SourceFile {
+        discovered
+            .iter()
+            .find_map(|x| match x {
+                WalkFileData::SourceFile(y) if y.source_file_path == path => Some(y),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("expected to discover {}, got {:?}", path, discovered))
+    }
+
+    #[test]
+    fn test_fences_above_fence_root_ignored() {
+        let discovered: Vec<WalkFileData> = discover_fences_and_files(
+            "tests/fence_root",
+            crate::walk_dirs::ExternalFences::Ignore,
+            Vec::new(),
+            Some(FenceRoot::new(Path::new("tests/fence_root/project"))),
+        );
+
+        assert!(
+            !discovered.iter().any(|x| match x {
+                WalkFileData::Fence(y) => y.fence_path == "tests/fence_root/fence.json",
+                _ => false,
+            }),
+            "expected fence above the fence root to be ignored, got {:?}",
+            discovered
+        );
+        assert_eq!(
+            find_source_file(&discovered, "tests/fence_root/project/src/projectFile.ts").tags,
+            set!("project")
+        );
+    }
+
+    #[test]
+    fn test_root_fence_drops_parent_tags() {
+        let discovered: Vec<WalkFileData> = discover_fences_and_files(
+            "tests/fence_root",
+            crate::walk_dirs::ExternalFences::Ignore,
+            Vec::new(),
+            None,
+        );
+
+        assert_eq!(
+            find_source_file(&discovered, "tests/fence_root/project/src/projectFile.ts").tags,
+            set!("decoy", "project")
+        );
+        assert_eq!(
+            find_source_file(
+                &discovered,
+                "tests/fence_root/project/isolated/isolatedFile.ts"
+            )
+            .tags,
+            set!("isolated")
+        );
+    }
+
+    #[test]
+    fn test_inherit_tags_false_drops_parent_tags() {
+        let discovered: Vec<WalkFileData> = discover_fences_and_files(
+            "tests/inherit_tags",
+            crate::walk_dirs::ExternalFences::Ignore,
+            Vec::new(),
+            None,
+        );
+
+        assert_eq!(
+            find_source_file(&discovered, "tests/inherit_tags/src/pkg/pkgFile.ts").tags,
+            set!("pkg")
+        );
+        assert_eq!(
+            find_source_file(&discovered, "tests/inherit_tags/src/pkg/demo/demoFile.ts").tags,
+            HashSet::new()
+        );
+    }
 }
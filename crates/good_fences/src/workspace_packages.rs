@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/**
+ * A workspace package, as declared by its package.json
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspacePackage {
+    pub name: String,
+    // None if the package.json has no valid semver version
+    pub version: Option<semver::Version>,
+}
+
+/**
+ * The workspace packages that files belong to, by the closest package.json above
+ * each file. Each directory is looked up, and each package.json read, at most once.
+ */
+#[derive(Debug, Default)]
+pub struct WorkspacePackages {
+    // the package of each directory looked up so far, None if it is not in a package
+    packages_by_dir: RwLock<HashMap<PathBuf, Option<WorkspacePackage>>>,
+}
+
+// the packages are a cache of the package.json files on disk, not part of the state
+// of whatever holds them
+impl PartialEq for WorkspacePackages {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl WorkspacePackages {
+    /**
+     * Gets the package that a file belongs to, if its closest package.json names one
+     */
+    pub fn package_of(&self, file_path: &Path) -> Option<WorkspacePackage> {
+        self.package_of_dir(file_path.parent()?)
+    }
+
+    fn package_of_dir(&self, dir: &Path) -> Option<WorkspacePackage> {
+        if let Some(package) = self.packages_by_dir.read().unwrap().get(dir) {
+            return package.clone();
+        }
+        let package_json_path = dir.join("package.json");
+        let package = if package_json_path.is_file() {
+            read_workspace_package(&package_json_path)
+        } else {
+            dir.parent().and_then(|parent| self.package_of_dir(parent))
+        };
+        self.packages_by_dir
+            .write()
+            .unwrap()
+            .insert(dir.to_path_buf(), package.clone());
+        package
+    }
+}
+
+fn read_workspace_package(package_json_path: &Path) -> Option<WorkspacePackage> {
+    let package_json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(package_json_path).ok()?).ok()?;
+    Some(WorkspacePackage {
+        name: package_json.get("name")?.as_str()?.to_owned(),
+        version: package_json
+            .get("version")
+            .and_then(|version| version.as_str())
+            .and_then(|version| semver::Version::parse(version).ok()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WorkspacePackage, WorkspacePackages};
+    use serde_json::json;
+    use std::path::Path;
+    use test_fixtures::FixtureRepo;
+
+    #[test]
+    fn test_package_of_nested_file() {
+        let repo = FixtureRepo::new()
+            .package("@myorg/widgets", |p| {
+                p.field("version", json!("0.3.0"))
+                    .file("src/nested/index.ts", "")
+            })
+            .file("loose/index.ts", "");
+        let workspace_packages = WorkspacePackages::default();
+
+        let widgets = Some(WorkspacePackage {
+            name: "@myorg/widgets".to_owned(),
+            version: Some(semver::Version::new(0, 3, 0)),
+        });
+        let nested_file = repo.relative_path("packages/widgets/src/nested/index.ts");
+        assert_eq!(
+            workspace_packages.package_of(Path::new(&nested_file)),
+            widgets
+        );
+
+        // the package.json is only read the first time the package is looked up
+        std::fs::write(
+            repo.root_join("packages/widgets/package.json"),
+            r#"{"name": "@myorg/renamed"}"#,
+        )
+        .unwrap();
+        let other_file = repo.relative_path("packages/widgets/src/index.ts");
+        assert_eq!(
+            workspace_packages.package_of(Path::new(&other_file)),
+            widgets
+        );
+
+        assert_ne!(
+            workspace_packages
+                .package_of(Path::new(&repo.relative_path("loose/index.ts")))
+                .map(|package| package.name),
+            Some("@myorg/widgets".to_owned())
+        );
+    }
+}
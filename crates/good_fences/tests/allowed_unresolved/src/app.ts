@@ -0,0 +1,4 @@
+import icons from 'virtual:svg-icons';
+import { missing } from './missing';
+
+export const app = [icons, missing];
@@ -0,0 +1 @@
+export const isolatedFile = 1;
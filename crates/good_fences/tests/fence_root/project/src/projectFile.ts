@@ -0,0 +1 @@
+export const projectFile = 1;
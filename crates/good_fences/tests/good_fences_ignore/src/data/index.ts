@@ -0,0 +1 @@
+export const data = 1;
@@ -0,0 +1,3 @@
+import { data } from '../data';
+
+export const view = data;
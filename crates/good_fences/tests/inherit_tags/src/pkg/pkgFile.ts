@@ -0,0 +1,3 @@
+import { protectedValue } from '../protected/protectedFile';
+
+export const pkgValue = protectedValue + 1;
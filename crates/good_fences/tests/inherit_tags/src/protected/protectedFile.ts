@@ -0,0 +1 @@
+export const protectedValue = 1;
@@ -0,0 +1,224 @@
+//! A combined entry point that evaluates fences and finds unused items over a
+//! single walk and parse of the repo.
+//!
+//! Imports are resolved once, by the unused finder, and fences are evaluated
+//! against that resolution. Bare imports the unused finder leaves to
+//! node_modules are checked against the fence project's tsconfig paths, so
+//! fences are evaluated the same way as in a standalone good-fences run.
+
+use anyhow::{Context, Result};
+use good_fences::good_fences_ignore::GoodFencesIgnore;
+use good_fences::good_fences_runner::GoodFencesRunner;
+use good_fences::JsonErrorFile;
+use logger::Logger;
+use std::{env, path::Path};
+use unused_finder::{UnusedFinder, UnusedFinderConfig, UnusedFinderReport};
+
+use crate::bridge;
+use crate::config::FencesConfig;
+
+/// The results of both analyses of a single walk
+pub struct Analysis {
+    pub good_fences_runner: GoodFencesRunner,
+    pub unused_finder: UnusedFinder,
+    pub unused_report: UnusedFinderReport,
+}
+
+impl Analysis {
+    /// Evaluates the fences of the walked files, in the same shape as the
+    /// standalone good-fences error file
+    pub fn fences_report(&self) -> JsonErrorFile<'_> {
+        let eval_results = self.good_fences_runner.find_import_violations();
+        JsonErrorFile {
+            violations: eval_results.violations,
+            evaluation_errors: eval_results
+                .unresolved_files
+                .iter()
+                .map(|error| error.to_string())
+                .collect(),
+            grouped_summary: None,
+            owner_summary: None,
+            violation_owners: Default::default(),
+            fence_lints: self.good_fences_runner.find_fence_conflicts(),
+        }
+    }
+}
+
+/// Walks, parses and resolves the root paths of the unused finder config
+/// once, then builds both the fence evaluation and the unused report from
+/// the walk.
+///
+/// Paths in both configs are relative to the working directory. The fence
+/// paths must be within the unused finder's root paths.
+pub fn analyze(
+    logger: impl Logger + Sync,
+    fences_config: FencesConfig,
+    mut unused_config: UnusedFinderConfig,
+) -> Result<Analysis> {
+    unused_config.repo_root = std::path::absolute(&unused_config.repo_root)?
+        .to_string_lossy()
+        .to_string();
+    unused_config.expand_root_paths(&logger)?;
+    bridge::check_fence_paths_walked(
+        &fences_config.paths,
+        Path::new(&unused_config.repo_root),
+        &unused_config.root_paths,
+    )?;
+
+    let walked_files = UnusedFinder::walk(&logger, &unused_config)?;
+    // the unused finder takes ownership of the walk, fence files included
+    let fence_files = walked_files.fence_files.clone();
+    let mut unused_finder =
+        UnusedFinder::new_from_walked_files(&logger, unused_config, walked_files)?;
+
+    // fences
+    let mut tsconfig = tsconfig_paths::TsconfigPathsJson::from_path(&fences_config.project)
+        .with_context(|| format!("Unable to find project path {}", &fences_config.project))?;
+    if fences_config.base_url.is_some() {
+        tsconfig.compiler_options.base_url = fences_config.base_url;
+    }
+    let ignored_dirs: Vec<regex::Regex> = fences_config
+        .ignored_dirs
+        .iter()
+        .map(|dir| {
+            regex::Regex::new(dir)
+                .with_context(|| format!("unable to create regex from ignoredDirs {dir}"))
+        })
+        .collect::<Result<_>>()?;
+    // fences above the project directory (or the explicit fence root) never apply
+    let fence_root = match fences_config.fence_root {
+        Some(fence_root) => Path::new(&fence_root).to_path_buf(),
+        None => Path::new(&fences_config.project)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    let fence_inputs = bridge::fence_inputs_from_walk(
+        &fence_files,
+        unused_finder.source_files(),
+        &tsconfig,
+        &fences_config.paths,
+        &ignored_dirs,
+    )?;
+    let mut good_fences_runner = GoodFencesRunner::new_from_walked_files(
+        tsconfig,
+        fence_inputs.fences,
+        fence_inputs.source_files,
+        Some(fence_root.as_path()),
+    );
+    good_fences_runner.use_resolved_imports(fence_inputs.resolved_imports);
+    if let Some(good_fences_ignore) = GoodFencesIgnore::discover(&env::current_dir()?)? {
+        good_fences_runner.ignore_source_files(&good_fences_ignore);
+    }
+
+    // unused
+    let unused_report = unused_finder.find_unused(&logger)?.get_report();
+
+    Ok(Analysis {
+        good_fences_runner,
+        unused_finder,
+        unused_report,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::analyze;
+    use crate::config::FencesConfig;
+    use good_fences::good_fences_runner::GoodFencesRunner;
+    use good_fences::{ExternalFences, ResolveOptions};
+    use logger::Logger;
+    use std::fmt::Display;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tsconfig_paths::TsconfigPathsJson;
+    use unused_finder::{UnusedFinder, UnusedFinderConfig, UnusedFinderReport};
+
+    // counts the walks of root paths, which each log a line when they start
+    #[derive(Clone, Default)]
+    struct WalkCountingLogger {
+        walks: Arc<AtomicUsize>,
+    }
+
+    impl Logger for WalkCountingLogger {
+        fn log(&self, message: impl Display) {
+            if message.to_string().starts_with("Walking ") {
+                self.walks.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    fn sorted_violations(good_fences_runner: &GoodFencesRunner) -> Vec<String> {
+        let mut violations: Vec<String> = good_fences_runner
+            .find_import_violations()
+            .violations
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        violations.sort();
+        violations
+    }
+
+    fn sort_report(report: &mut UnusedFinderReport) {
+        report.unused_files.sort();
+        report
+            .unused_symbols
+            .values_mut()
+            .for_each(|symbols| symbols.sort_by(|a, b| a.id.cmp(&b.id)));
+    }
+
+    #[test]
+    fn test_analyze_matches_separate_runs() {
+        let repo_root = "../good_fences/tests/good_fences_integration";
+        let tsconfig_path = "../good_fences/tests/good_fences_integration/tsconfig.json";
+        let fence_path = "../good_fences/tests/good_fences_integration/src";
+        let unused_config = UnusedFinderConfig {
+            repo_root: Path::new(repo_root)
+                .canonicalize()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            root_paths: vec!["src".to_string()],
+            ..Default::default()
+        };
+
+        let logger = WalkCountingLogger::default();
+        let analysis = analyze(
+            &logger,
+            FencesConfig {
+                paths: vec![fence_path.to_string()],
+                project: tsconfig_path.to_string(),
+                base_url: None,
+                ignored_dirs: vec![],
+                fence_root: None,
+            },
+            unused_config.clone(),
+        )
+        .unwrap();
+        assert_eq!(logger.walks.load(Ordering::SeqCst), 1);
+
+        let standalone_fences = GoodFencesRunner::new(
+            TsconfigPathsJson::from_path(tsconfig_path).unwrap(),
+            &[fence_path],
+            ExternalFences::Ignore,
+            &[],
+            Some(Path::new(repo_root)),
+            ResolveOptions::default(),
+        );
+        assert_eq!(
+            sorted_violations(&analysis.good_fences_runner),
+            sorted_violations(&standalone_fences)
+        );
+
+        let mut standalone_unused = UnusedFinder::new_from_cfg(&logger, unused_config)
+            .unwrap()
+            .find_unused(&logger)
+            .unwrap()
+            .get_report();
+        let mut unused_report = analysis.unused_report;
+        sort_report(&mut unused_report);
+        sort_report(&mut standalone_unused);
+        assert_eq!(unused_report, standalone_unused);
+    }
+}
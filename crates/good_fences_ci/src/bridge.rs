@@ -0,0 +1,566 @@
+//! Adapters from the files walked and resolved by the unused finder to the
+//! inputs of good-fences, so that both analyses can share a single walk and
+//! resolution of the repo.
+//!
+//! The adapted inputs are in the same shape as good-fences' own walk would
+//! produce them, so fences are evaluated the same way as in a standalone run.
+//! Files the unused finder does not walk (hidden files, directories matched
+//! by its skip patterns, and autogenerated files) are not evaluated.
+
+use anyhow::{anyhow, Result};
+use good_fences::evaluate_fences::{resolve_source_file_import, ResolvedImports};
+use good_fences::fence::{parse_fence_file, Fence};
+use good_fences::get_imports::{FileImports, ParsedSourceFile};
+use good_fences::{ResolveOptions, ResolvedImport};
+use path_clean::PathClean;
+use path_slash::PathExt;
+use path_utils::as_relative_slash_path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use tsconfig_paths::TsconfigPathsJson;
+use unused_finder::{ExportedSymbol, ResolvedImportExportInfo, ResolvedSourceFile};
+
+// extensions of the source files good-fences evaluates
+const FENCED_SOURCE_EXTENSIONS: &[&str] = &[".ts", ".tsx", ".js", ".jsx"];
+
+/// The fences and source files of a shared walk that are within the
+/// directories good-fences evaluates.
+#[derive(Debug)]
+pub struct FenceInputs {
+    pub fences: Vec<Fence>,
+    // source file paths and their imports, in the shape of good-fences' walk
+    pub source_files: Vec<(String, ParsedSourceFile)>,
+    // the imports of each source file, as resolved by the unused finder
+    pub resolved_imports: HashMap<String, ResolvedImports>,
+}
+
+/// Selects the fences and source files of a shared walk that are within
+/// `fence_paths`, skipping directories matched by `ignored_dirs`.
+///
+/// Paths are made relative to the working directory, joined onto the fence
+/// path they were found under, the same way good-fences' walk forms them.
+/// Imports are taken from the unused finder's resolution, except for bare
+/// imports it leaves to node_modules, which are resolved against `tsconfig`
+/// since they may be tsconfig path aliases.
+pub fn fence_inputs_from_walk<'a>(
+    fence_files: &[PathBuf],
+    source_files: impl Iterator<Item = &'a ResolvedSourceFile>,
+    tsconfig: &TsconfigPathsJson,
+    fence_paths: &[String],
+    ignored_dirs: &[regex::Regex],
+) -> Result<FenceInputs> {
+    let cwd = std::env::current_dir()?;
+    let walk_roots: Vec<(&Path, PathBuf)> = fence_paths
+        .iter()
+        .map(|fence_path| (Path::new(fence_path), cwd.join(fence_path).clean()))
+        .collect();
+    let walk_relative_path = |walked_path: &Path| -> Option<PathBuf> {
+        let (walk_root, relative) = find_walk_root(&walk_roots, walked_path)?;
+        if is_ignored(walk_root, relative, ignored_dirs) {
+            return None;
+        }
+        Some(walk_root.join(relative))
+    };
+    // imported files are named the way good-fences' walk names them, if it
+    // would have walked them at all
+    let project_local_path = |resolved_path: &Path| -> PathBuf {
+        match find_walk_root(&walk_roots, resolved_path) {
+            Some((walk_root, relative)) => walk_root.join(relative),
+            None => resolved_path
+                .strip_prefix(&cwd)
+                .unwrap_or(resolved_path)
+                .to_path_buf(),
+        }
+    };
+
+    let fences: Vec<Fence> = fence_files
+        .iter()
+        .filter_map(|fence_file| walk_relative_path(fence_file))
+        .filter_map(|fence_path| {
+            match as_relative_slash_path(fence_path).and_then(parse_fence_file) {
+                Ok(fence) => Some(fence),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let mut fenced_source_files: Vec<(String, ParsedSourceFile)> = Vec::new();
+    let mut resolved_imports: HashMap<String, ResolvedImports> = HashMap::new();
+    for source_file in source_files {
+        if !is_fenced_source_file(&source_file.source_file_path) {
+            continue;
+        }
+        let source_file_path = match walk_relative_path(&source_file.source_file_path) {
+            Some(source_file_path) => as_relative_slash_path(source_file_path)?.into_string(),
+            None => continue,
+        };
+        let import_export_info = &source_file.import_export_info;
+        let imports = fence_imports(import_export_info);
+        let file_resolved_imports = imports
+            .keys()
+            .map(|import_specifier| {
+                let resolved_import = import_export_info
+                    .import_specifiers
+                    .get(import_specifier)
+                    .and_then(|resolved_path| {
+                        fence_resolved_import(
+                            tsconfig,
+                            &source_file_path,
+                            import_specifier,
+                            resolved_path,
+                            &project_local_path,
+                        )
+                    });
+                (import_specifier.clone(), resolved_import)
+            })
+            .collect();
+        resolved_imports.insert(source_file_path.clone(), file_resolved_imports);
+        fenced_source_files.push((
+            source_file_path,
+            ParsedSourceFile {
+                imports,
+                has_default_export: has_default_export(import_export_info),
+            },
+        ));
+    }
+
+    Ok(FenceInputs {
+        fences,
+        source_files: fenced_source_files,
+        resolved_imports,
+    })
+}
+
+/// Checks that each of `fence_paths` is within one of the walked root paths,
+/// since good-fences can only evaluate files found by the shared walk.
+pub fn check_fence_paths_walked(
+    fence_paths: &[String],
+    repo_root: &Path,
+    root_paths: &[String],
+) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let walked_roots: Vec<PathBuf> = root_paths
+        .iter()
+        .map(|root_path| cwd.join(repo_root).join(root_path).clean())
+        .collect();
+    for fence_path in fence_paths {
+        let abs_fence_path = cwd.join(fence_path).clean();
+        if !walked_roots
+            .iter()
+            .any(|walked_root| abs_fence_path.starts_with(walked_root))
+        {
+            return Err(anyhow!(
+                "fence path {fence_path} is not within any of the unused finder's rootPaths"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Converts the resolved imports of the unused finder to the imports
+/// good-fences' parser would have found in the same file.
+pub fn fence_imports(import_export_info: &ResolvedImportExportInfo) -> FileImports {
+    let mut imports: FileImports = HashMap::new();
+    for (import_specifier, resolved_path) in import_export_info.import_specifiers.iter() {
+        let re_exported_symbols = import_export_info.export_from_symbols.get(resolved_path);
+        // require() calls, dynamic imports, and `export * from` / `export * as ns
+        // from` re-exports depend on the whole module rather than specific names
+        let imports_whole_module = import_export_info.require_paths.contains(resolved_path)
+            || import_export_info.imported_paths.contains(resolved_path)
+            || re_exported_symbols.is_some_and(|symbols| {
+                symbols
+                    .keys()
+                    .any(|symbol| symbol.imported == ExportedSymbol::Namespace)
+            });
+        let names: HashSet<String> = import_export_info
+            .imported_symbols
+            .get(resolved_path)
+            .into_iter()
+            .flatten()
+            .chain(
+                re_exported_symbols
+                    .into_iter()
+                    .flat_map(|symbols| symbols.keys().map(|symbol| &symbol.imported)),
+            )
+            .filter_map(imported_name)
+            .collect();
+        // good-fences drops imports without names, like namespace and side-effect
+        // imports, and names take the place of a whole-module entry
+        if !names.is_empty() {
+            imports.insert(import_specifier.clone(), Some(names));
+        } else if imports_whole_module {
+            imports.insert(import_specifier.clone(), None);
+        }
+    }
+
+    imports
+}
+
+/// Converts the path the unused finder resolved an import to into what
+/// good-fences would have resolved the import to.
+fn fence_resolved_import(
+    tsconfig: &TsconfigPathsJson,
+    source_file_path: &str,
+    import_specifier: &str,
+    resolved_path: &Path,
+    project_local_path: impl Fn(&Path) -> PathBuf,
+) -> Option<ResolvedImport> {
+    // the unused finder leaves imports of packages outside the repo unresolved,
+    // without reading tsconfig paths, so check those for path aliases
+    if !resolved_path.is_absolute() {
+        return resolve_source_file_import(
+            tsconfig,
+            source_file_path,
+            import_specifier,
+            &ResolveOptions::default(),
+        )
+        .ok();
+    }
+    if resolved_path
+        .components()
+        .any(|component| component == Component::Normal("node_modules".as_ref()))
+    {
+        return Some(ResolvedImport::NodeModulesImport(
+            import_specifier.to_owned(),
+        ));
+    }
+    if !is_fenced_source_file(resolved_path) {
+        return Some(ResolvedImport::ResourceFileImport);
+    }
+    Some(ResolvedImport::ProjectLocalImport(project_local_path(
+        resolved_path,
+    )))
+}
+
+/// Checks if the unused finder found a default export in a file, either
+/// declared in the file or re-exported from another module.
+pub fn has_default_export(import_export_info: &ResolvedImportExportInfo) -> bool {
+    import_export_info
+        .exported_ids
+        .keys()
+        .chain(
+            import_export_info
+                .export_from_symbols
+                .values()
+                .flat_map(|symbols| symbols.keys().map(|symbol| symbol.exported_name())),
+        )
+        .any(|symbol| *symbol == ExportedSymbol::Default)
+}
+
+fn imported_name(symbol: &ExportedSymbol) -> Option<String> {
+    match symbol {
+        ExportedSymbol::Named(name) => Some(name.clone()),
+        ExportedSymbol::Default => Some("default".to_string()),
+        ExportedSymbol::Namespace | ExportedSymbol::ExecutionOnly => None,
+    }
+}
+
+fn is_fenced_source_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .is_some_and(|file_name| {
+            FENCED_SOURCE_EXTENSIONS
+                .iter()
+                .any(|ext| file_name.ends_with(ext))
+        })
+}
+
+// finds the fence path a walked path is under, and the walked path relative to it
+fn find_walk_root<'a, 'b>(
+    walk_roots: &'a [(&'a Path, PathBuf)],
+    walked_path: &'b Path,
+) -> Option<(&'a Path, &'b Path)> {
+    walk_roots.iter().find_map(|(walk_root, abs_walk_root)| {
+        let relative = walked_path.strip_prefix(abs_walk_root).ok()?;
+        Some((*walk_root, relative))
+    })
+}
+
+// good-fences' walk skips any entry whose path matches an ignored directory
+// regex, so check the path of each entry between the walk root and the file
+fn is_ignored(walk_root: &Path, relative: &Path, ignored_dirs: &[regex::Regex]) -> bool {
+    if ignored_dirs.is_empty() {
+        return false;
+    }
+    let mut entry_path = walk_root.to_path_buf();
+    relative.components().any(|component| {
+        entry_path.push(component);
+        entry_path
+            .to_slash()
+            .is_some_and(|slashed| ignored_dirs.iter().any(|d| d.is_match(&slashed)))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fence_imports, fence_inputs_from_walk, has_default_export};
+    use good_fences::evaluate_fences::ResolvedImports;
+    use good_fences::get_imports::parse_source_file;
+    use good_fences::good_fences_runner::GoodFencesRunner;
+    use good_fences::{ExternalFences, ResolveOptions};
+    use std::collections::{HashMap, HashSet};
+    use std::path::{Path, PathBuf};
+    use tsconfig_paths::TsconfigPathsJson;
+    use unused_finder::{
+        ExportedSymbolMetadata, ReExportedSymbol, ResolvedImportExportInfo, UnusedFinder,
+        UnusedFinderConfig, UnusedFinderReport,
+    };
+
+    fn walk_config(repo_root: &str, root_paths: &[&str]) -> UnusedFinderConfig {
+        UnusedFinderConfig {
+            repo_root: Path::new(repo_root)
+                .canonicalize()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            root_paths: root_paths.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    // builds a runner from a shared walk, and the imports the unused finder resolved
+    fn runner_from_shared_walk(
+        tsconfig_path: &str,
+        walk_config: &UnusedFinderConfig,
+        fence_path: &str,
+        fence_root: Option<&Path>,
+    ) -> (GoodFencesRunner, HashMap<String, ResolvedImports>) {
+        let logger = logger::StdioLogger::new();
+        let walked_files = UnusedFinder::walk(&logger, walk_config).unwrap();
+        let fence_files = walked_files.fence_files.clone();
+        let unused_finder =
+            UnusedFinder::new_from_walked_files(&logger, walk_config.clone(), walked_files)
+                .unwrap();
+        let tsconfig = TsconfigPathsJson::from_path(tsconfig_path).unwrap();
+        let fence_inputs = fence_inputs_from_walk(
+            &fence_files,
+            unused_finder.source_files(),
+            &tsconfig,
+            &[fence_path.to_string()],
+            &[],
+        )
+        .unwrap();
+        let runner = GoodFencesRunner::new_from_walked_files(
+            tsconfig,
+            fence_inputs.fences,
+            fence_inputs.source_files,
+            fence_root,
+        );
+        (runner, fence_inputs.resolved_imports)
+    }
+
+    #[test]
+    fn test_shared_walk_matches_standalone_fences() {
+        let tsconfig_path = "../good_fences/tests/good_fences_integration/tsconfig.json";
+        let fence_path = "../good_fences/tests/good_fences_integration/src";
+
+        let standalone = GoodFencesRunner::new(
+            TsconfigPathsJson::from_path(tsconfig_path).unwrap(),
+            &[fence_path],
+            ExternalFences::Ignore,
+            &[],
+            None,
+            ResolveOptions::default(),
+        );
+        let (mut shared, resolved_imports) = runner_from_shared_walk(
+            tsconfig_path,
+            &walk_config("../good_fences/tests/good_fences_integration", &["src"]),
+            fence_path,
+            None,
+        );
+        assert_eq!(shared, standalone);
+
+        // the imports resolved by the unused finder are evaluated the same way
+        shared.use_resolved_imports(resolved_imports);
+        let shared_results = shared.find_import_violations();
+        let standalone_results = standalone.find_import_violations();
+        let sorted = |mut strings: Vec<String>| {
+            strings.sort();
+            strings
+        };
+        assert_eq!(
+            sorted(
+                shared_results
+                    .violations
+                    .iter()
+                    .map(|violation| violation.to_string())
+                    .collect()
+            ),
+            sorted(
+                standalone_results
+                    .violations
+                    .iter()
+                    .map(|violation| violation.to_string())
+                    .collect()
+            )
+        );
+        assert_eq!(
+            sorted(
+                shared_results
+                    .unresolved_files
+                    .iter()
+                    .map(|error| error.to_string())
+                    .collect()
+            ),
+            sorted(
+                standalone_results
+                    .unresolved_files
+                    .iter()
+                    .map(|error| error.to_string())
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn test_shared_walk_matches_standalone_fence_root() {
+        let tsconfig_path = "../good_fences/tests/good_fences_integration/tsconfig.json";
+        let fence_path = "../good_fences/tests/fence_root";
+        let fence_root = Path::new("../good_fences/tests/fence_root/project");
+
+        let standalone = GoodFencesRunner::new(
+            TsconfigPathsJson::from_path(tsconfig_path).unwrap(),
+            &[fence_path],
+            ExternalFences::Ignore,
+            &[],
+            Some(fence_root),
+            ResolveOptions::default(),
+        );
+        let (shared, _) = runner_from_shared_walk(
+            tsconfig_path,
+            &walk_config("../good_fences/tests/fence_root", &["."]),
+            fence_path,
+            Some(fence_root),
+        );
+
+        assert_eq!(shared, standalone);
+    }
+
+    #[test]
+    fn test_shared_walk_matches_standalone_unused() {
+        let config = walk_config("../unused_finder/tests/unused_finder", &["src"]);
+        let logger = logger::StdioLogger::new();
+
+        let walked_files = UnusedFinder::walk(&logger, &config).unwrap();
+        let mut shared = UnusedFinder::new_from_walked_files(&logger, config.clone(), walked_files)
+            .unwrap()
+            .find_unused(&logger)
+            .unwrap()
+            .get_report();
+        let mut standalone = UnusedFinder::new_from_cfg(&logger, config)
+            .unwrap()
+            .find_unused(&logger)
+            .unwrap()
+            .get_report();
+
+        sort_report(&mut shared);
+        sort_report(&mut standalone);
+        assert_eq!(shared, standalone);
+    }
+
+    fn sort_report(report: &mut UnusedFinderReport) {
+        report.unused_files.sort();
+        report
+            .unused_symbols
+            .values_mut()
+            .for_each(|symbols| symbols.sort_by(|a, b| a.id.cmp(&b.id)));
+    }
+
+    #[test]
+    fn test_fence_imports() {
+        let mut import_export_info = ResolvedImportExportInfo::default();
+        let mut import = |import_specifier: &str| {
+            let resolved_path = PathBuf::from(format!("/repo/src/{import_specifier}.ts"));
+            import_export_info
+                .import_specifiers
+                .insert(import_specifier.to_string(), resolved_path.clone());
+            resolved_path
+        };
+        let named = import("./named");
+        let namespace = import("./namespace");
+        let required = import("./required");
+        let reexported = import("./reexported");
+        let executed = import("./executed");
+        import_export_info.imported_symbols.insert(
+            named,
+            [
+                unused_finder::ExportedSymbol::Named("a".to_string()),
+                unused_finder::ExportedSymbol::Default,
+            ]
+            .into_iter()
+            .collect(),
+        );
+        import_export_info.imported_symbols.insert(
+            namespace,
+            [unused_finder::ExportedSymbol::Namespace]
+                .into_iter()
+                .collect(),
+        );
+        import_export_info.require_paths.insert(required);
+        import_export_info.export_from_symbols.insert(
+            reexported,
+            [(
+                ReExportedSymbol {
+                    imported: unused_finder::ExportedSymbol::Namespace,
+                    renamed_to: None,
+                },
+                ExportedSymbolMetadata::default(),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        import_export_info.executed_paths.insert(executed);
+
+        assert_eq!(
+            fence_imports(&import_export_info),
+            HashMap::from([
+                (
+                    "./named".to_string(),
+                    Some(HashSet::from(["a".to_string(), "default".to_string()]))
+                ),
+                ("./required".to_string(), None),
+                ("./reexported".to_string(), None),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_fence_imports_match_good_fences_parser() {
+        let logger = logger::StdioLogger::new();
+        let config = walk_config("tests/fence_imports", &["."]);
+        let walked_files = UnusedFinder::walk(&logger, &config).unwrap();
+        let unused_finder =
+            UnusedFinder::new_from_walked_files(&logger, config, walked_files).unwrap();
+        let source_file = unused_finder
+            .source_files()
+            .find(|source_file| source_file.source_file_path.ends_with("index.ts"))
+            .unwrap();
+
+        let good_fences_imports =
+            parse_source_file(&source_file.source_file_path.to_str().unwrap())
+                .unwrap()
+                .imports;
+        assert_eq!(
+            fence_imports(&source_file.import_export_info),
+            good_fences_imports
+        );
+    }
+
+    #[test]
+    fn test_has_default_export() {
+        let mut import_export_info = ResolvedImportExportInfo::default();
+        import_export_info.exported_ids.insert(
+            unused_finder::ExportedSymbol::Named("a".to_string()),
+            Default::default(),
+        );
+        assert!(!has_default_export(&import_export_info));
+
+        import_export_info
+            .exported_ids
+            .insert(unused_finder::ExportedSymbol::Default, Default::default());
+        assert!(has_default_export(&import_export_info));
+    }
+}
@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use unused_finder::UnusedFinderJSONConfig;
+
+/// Combined configuration for running good-fences and the unused finder
+/// over a single walk of the repo.
+///
+/// Relative paths are resolved against the directory of the config file.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiConfig {
+    /// Options for the fence evaluation
+    pub fences: FencesConfig,
+    /// Options for the unused analysis, in the same shape as an unused-finder config
+    pub unused: UnusedFinderJSONConfig,
+    /// Limits on the results of each check, beyond which the run fails
+    #[serde(default)]
+    pub thresholds: CiThresholds,
+    /// Path to write the unified report to
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Replace the names of files and symbols in the report with stable
+    /// pseudonyms, so it can be shared externally. The mapping back to the
+    /// original names is written next to the report.
+    #[serde(default)]
+    pub redact: bool,
+}
+
+/// The options of a standalone good-fences run that apply to the CI mode
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FencesConfig {
+    /// Directories to evaluate fences in. These must be within the root
+    /// paths of the unused-finder config, since both share its walk.
+    pub paths: Vec<String>,
+    /// Path to the tsconfig.json used to resolve imports
+    pub project: String,
+    /// Overrides the baseUrl of the tsconfig.json
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Regexes of directories to skip
+    #[serde(default)]
+    pub ignored_dirs: Vec<String>,
+    /// Fences above this directory do not apply. Defaults to the directory
+    /// of the tsconfig.json, the same as a standalone run.
+    #[serde(default)]
+    pub fence_root: Option<String>,
+}
+
+/// The maximum number of results each check may report before the run
+/// fails. Checks without a threshold never fail the run.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiThresholds {
+    #[serde(default = "default_max_fence_violations")]
+    pub max_fence_violations: Option<usize>,
+    #[serde(default)]
+    pub max_unresolved_files: Option<usize>,
+    #[serde(default)]
+    pub max_unused_files: Option<usize>,
+    #[serde(default)]
+    pub max_unused_symbols: Option<usize>,
+}
+
+// fence violations fail a standalone run, so they fail the CI mode by default
+fn default_max_fence_violations() -> Option<usize> {
+    Some(0)
+}
+
+impl Default for CiThresholds {
+    fn default() -> Self {
+        CiThresholds {
+            max_fence_violations: default_max_fence_violations(),
+            max_unresolved_files: None,
+            max_unused_files: None,
+            max_unused_symbols: None,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Runs good-fences and the unused finder over a single walk of the repo, and
+//! combines their results into one report.
+
+pub mod analyze;
+pub mod bridge;
+pub mod config;
+pub mod report;
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use good_fences_ci::analyze;
+use good_fences_ci::config::CiConfig;
+use good_fences_ci::report::{CiReport, CiSummary};
+use logger::{Logger, StdioLogger};
+use std::{
+    convert::TryInto,
+    env, fs,
+    path::{Path, PathBuf},
+};
+use unused_finder::{Redactor, UnusedFinderConfig, WorkflowCommand, WorkflowCommandLevel};
+
+#[derive(Parser, Debug)]
+struct CliArgs {
+    // Path to the combined good-fences and unused-finder config
+    #[arg(short, long, default_value = DEFAULT_CONFIG_PATH)]
+    config: String,
+    // Path to write the unified report to, overriding the config
+    #[arg(short, long, default_value = None)]
+    output: Option<String>,
+    // Redact the names of files and symbols in the report, overriding the config
+    #[arg(long, default_value_t = false)]
+    redact: bool,
+    // Also print the fence violations and unused files and symbols as GitHub
+    // Actions workflow commands, which annotate them in pull requests
+    #[arg(long, default_value_t = false)]
+    github: bool,
+}
+
+const DEFAULT_CONFIG_PATH: &str = "good-fences-ci.json";
+const DEFAULT_OUTPUT_PATH: &str = "good-fences-ci-report.json";
+
+fn main() -> Result<()> {
+    let logger = &StdioLogger::new();
+    let args = CliArgs::parse();
+
+    logger.log(format!("reading config from path {}", args.config));
+    let config_str = fs::read_to_string(&args.config)
+        .with_context(|| format!("reading config file {}", &args.config))?;
+    let config: CiConfig = serde_hjson::from_str(&config_str)
+        .with_context(|| format!("Parsing good-fences-ci config {}", &args.config))?;
+    let output_path = args
+        .output
+        .or_else(|| config.output.clone())
+        .unwrap_or_else(|| DEFAULT_OUTPUT_PATH.to_string());
+    // resolve the output path before moving to the config's directory
+    let output_path = std::path::absolute(output_path)?;
+    let redact = args.redact || config.redact;
+
+    // paths in the config are relative to the config file
+    let config_dir = Path::new(&args.config)
+        .parent()
+        .expect("Failed to get parent directory of config file")
+        .to_path_buf();
+    if !config_dir.as_os_str().is_empty() {
+        logger.log(format!("working in {}..", config_dir.display()));
+        env::set_current_dir(&config_dir)
+            .expect("Failed to change working directory to config file directory");
+    }
+
+    let exit_code = run(logger, config, &output_path, redact, args.github)?;
+    std::process::exit(exit_code);
+}
+
+// Runs both checks over a single walk, writes the unified report, and
+// returns the exit code derived from the thresholds of both checks.
+fn run(
+    logger: &StdioLogger,
+    config: CiConfig,
+    output_path: &Path,
+    redact: bool,
+    github: bool,
+) -> Result<i32> {
+    let CiConfig {
+        fences: fences_config,
+        unused: unused_config,
+        thresholds,
+        ..
+    } = config;
+    let unused_config: UnusedFinderConfig = unused_config.try_into()?;
+    let mut analysis = analyze::analyze(logger, fences_config, unused_config)?;
+    let unused_report = std::mem::take(&mut analysis.unused_report);
+    let fences_report = analysis.fences_report();
+
+    if github {
+        let repo_root = Path::new(&analysis.unused_finder.config().repo_root);
+        // fence violations are reported by paths relative to the working directory
+        let cwd = env::current_dir()?;
+        for violation in fences_report.violations.iter() {
+            let command = WorkflowCommand::new(
+                WorkflowCommandLevel::Error,
+                repo_root,
+                &cwd.join(violation.violating_file_path),
+                violation.to_string(),
+            );
+            println!("{command}");
+        }
+        for command in unused_report.github_workflow_commands(repo_root) {
+            println!("{command}");
+        }
+    }
+
+    let summary = CiSummary::new(&fences_report, &unused_report, &thresholds);
+    logger.log(format!(
+        "fence violations: {}, unresolved files: {}, unused files: {}, unused symbols: {}",
+        summary.fence_violations,
+        summary.unresolved_files,
+        summary.unused_files,
+        summary.unused_symbols,
+    ));
+    if !summary.passed {
+        logger.log(format!(
+            "checks over their threshold: {}",
+            summary.failed_checks.join(", ")
+        ));
+    }
+    let exit_code = summary.exit_code;
+
+    let report = CiReport {
+        summary,
+        fences: fences_report,
+        unused: unused_report,
+    };
+    let mut report_json = serde_json::to_value(&report)?;
+    if redact {
+        // the names of failed checks are part of the report format
+        let mut redactor = Redactor::new().preserve(report.summary.failed_checks.iter().copied());
+        redactor.redact_value(&mut report_json);
+        let mapping_path = redaction_mapping_path(output_path);
+        fs::write(
+            &mapping_path,
+            serde_json::to_string_pretty(&redactor.into_mapping())?,
+        )
+        .with_context(|| format!("writing redaction mapping to {}", mapping_path.display()))?;
+        logger.log(format!(
+            "redaction mapping written to {}. Keep it local, it decodes the redacted report",
+            mapping_path.display()
+        ));
+    }
+    fs::write(output_path, serde_json::to_string_pretty(&report_json)?)
+        .with_context(|| format!("writing report to {}", output_path.display()))?;
+    logger.log(format!("report written to {}", output_path.display()));
+
+    Ok(exit_code)
+}
+
+// the mapping of a redacted report is written next to it, e.g.
+// `report.mapping.json` for `report.json`
+fn redaction_mapping_path(output_path: &Path) -> PathBuf {
+    output_path.with_extension("mapping.json")
+}
@@ -0,0 +1,128 @@
+use crate::config::CiThresholds;
+use good_fences::JsonErrorFile;
+use serde::Serialize;
+use unused_finder::UnusedFinderReport;
+
+/// The unified result of a CI run, with the results of both checks
+#[derive(Debug, Serialize)]
+pub struct CiReport<'a> {
+    pub summary: CiSummary,
+    // the same shape as the standalone good-fences error file
+    pub fences: JsonErrorFile<'a>,
+    // the same shape as the standalone unused-finder report
+    pub unused: UnusedFinderReport,
+}
+
+/// The number of results of each check, and whether they are within
+/// their thresholds.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiSummary {
+    pub fence_violations: usize,
+    pub unresolved_files: usize,
+    pub unused_files: usize,
+    pub unused_symbols: usize,
+    // names of the counts that exceeded their threshold
+    pub failed_checks: Vec<&'static str>,
+    pub passed: bool,
+    pub exit_code: i32,
+}
+
+impl CiSummary {
+    pub fn new(
+        fences: &JsonErrorFile,
+        unused: &UnusedFinderReport,
+        thresholds: &CiThresholds,
+    ) -> Self {
+        let fence_violations = fences.violations.len();
+        let unresolved_files = fences.evaluation_errors.len();
+        let unused_files = unused.unused_files.len();
+        let unused_symbols = unused.unused_symbols.values().map(Vec::len).sum();
+
+        let checks = [
+            (
+                "fenceViolations",
+                fence_violations,
+                thresholds.max_fence_violations,
+            ),
+            (
+                "unresolvedFiles",
+                unresolved_files,
+                thresholds.max_unresolved_files,
+            ),
+            ("unusedFiles", unused_files, thresholds.max_unused_files),
+            (
+                "unusedSymbols",
+                unused_symbols,
+                thresholds.max_unused_symbols,
+            ),
+        ];
+        let failed_checks: Vec<&'static str> = checks
+            .iter()
+            .filter(|(_, count, max)| max.is_some_and(|max| *count > max))
+            .map(|(name, _, _)| *name)
+            .collect();
+        let passed = failed_checks.is_empty();
+
+        CiSummary {
+            fence_violations,
+            unresolved_files,
+            unused_files,
+            unused_symbols,
+            failed_checks,
+            passed,
+            exit_code: if passed { 0 } else { 1 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CiSummary;
+    use crate::config::CiThresholds;
+    use good_fences::JsonErrorFile;
+    use unused_finder::UnusedFinderReport;
+
+    #[test]
+    fn test_summary_thresholds() {
+        let fences = JsonErrorFile {
+            violations: Vec::new(),
+            evaluation_errors: vec!["unresolved".to_string(); 2],
+            grouped_summary: None,
+            owner_summary: None,
+            violation_owners: Default::default(),
+            fence_lints: Vec::new(),
+        };
+        let unused = UnusedFinderReport {
+            unused_files: vec!["a.ts".to_string(), "b.ts".to_string()],
+            ..Default::default()
+        };
+
+        let default_summary = CiSummary::new(&fences, &unused, &CiThresholds::default());
+        assert_eq!(
+            default_summary,
+            CiSummary {
+                fence_violations: 0,
+                unresolved_files: 2,
+                unused_files: 2,
+                unused_symbols: 0,
+                failed_checks: Vec::new(),
+                passed: true,
+                exit_code: 0,
+            }
+        );
+
+        let strict_summary = CiSummary::new(
+            &fences,
+            &unused,
+            &CiThresholds {
+                max_unresolved_files: Some(2),
+                max_unused_files: Some(1),
+                ..Default::default()
+            },
+        );
+        assert_eq!(strict_summary.failed_checks, vec!["unusedFiles"]);
+        assert!(!strict_summary.passed);
+        assert_eq!(strict_summary.exit_code, 1);
+    }
+}
@@ -0,0 +1,2 @@
+export const g = 1;
+export default 1;
@@ -0,0 +1,12 @@
+import { a } from './a';
+import b from './b';
+import * as c from './c';
+import './d';
+export * from './e';
+export * as f from './f';
+export { g, default as h } from './g';
+export * from './a';
+const i = require('./i');
+const j = import('./j');
+
+export const k = [a, b, c, i, j];
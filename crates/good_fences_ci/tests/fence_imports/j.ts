@@ -0,0 +1 @@
+export const j = 1;
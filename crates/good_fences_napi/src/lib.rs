@@ -8,6 +8,16 @@ pub struct GoodFencesOptions {
     pub err_output_path: Option<String>,
     pub ignore_external_fences: Option<ExternalFences>,
     pub ignored_dirs: Option<Vec<String>>,
+    pub allowed_unresolved: Option<Vec<String>>,
+    pub resource_extensions: Option<Vec<String>>,
+    pub assume_unresolved_bare_as_external: Option<bool>,
+    pub fence_root: Option<String>,
+    pub html_output_path: Option<String>,
+    pub group_by: Option<ViolationGrouping>,
+    pub layer_tag_priority: Option<Vec<String>>,
+    pub export_model_path: Option<String>,
+    pub export_model_format: Option<ModelFormat>,
+    pub output_format: Option<OutputFormat>,
 }
 
 impl From<GoodFencesOptions> for good_fences::GoodFencesOptions {
@@ -19,6 +29,16 @@ impl From<GoodFencesOptions> for good_fences::GoodFencesOptions {
             err_output_path: val.err_output_path,
             ignore_external_fences: val.ignore_external_fences.map(Into::into),
             ignored_dirs: val.ignored_dirs,
+            allowed_unresolved: val.allowed_unresolved,
+            resource_extensions: val.resource_extensions,
+            assume_unresolved_bare_as_external: val.assume_unresolved_bare_as_external,
+            fence_root: val.fence_root,
+            html_output_path: val.html_output_path,
+            group_by: val.group_by.map(Into::into),
+            layer_tag_priority: val.layer_tag_priority,
+            export_model_path: val.export_model_path,
+            export_model_format: val.export_model_format.map(Into::into),
+            output_format: val.output_format.map(Into::into),
         }
     }
 }
@@ -39,6 +59,56 @@ impl From<ExternalFences> for good_fences::ExternalFences {
     }
 }
 
+#[derive(Eq, Debug, PartialEq)]
+#[napi]
+pub enum ViolationGrouping {
+    Layer = 0,
+    Owner = 1,
+}
+
+impl From<ViolationGrouping> for good_fences::ViolationGrouping {
+    fn from(val: ViolationGrouping) -> Self {
+        match val {
+            ViolationGrouping::Layer => good_fences::ViolationGrouping::Layer,
+            ViolationGrouping::Owner => good_fences::ViolationGrouping::Owner,
+        }
+    }
+}
+
+#[derive(Eq, Debug, PartialEq)]
+#[napi]
+pub enum ModelFormat {
+    Json = 0,
+    Binary = 1,
+}
+
+impl From<ModelFormat> for good_fences::ModelFormat {
+    fn from(val: ModelFormat) -> Self {
+        match val {
+            ModelFormat::Json => good_fences::ModelFormat::Json,
+            ModelFormat::Binary => good_fences::ModelFormat::Binary,
+        }
+    }
+}
+
+#[derive(Eq, Debug, PartialEq)]
+#[napi]
+pub enum OutputFormat {
+    Human = 0,
+    Json = 1,
+    Ndjson = 2,
+}
+
+impl From<OutputFormat> for good_fences::OutputFormat {
+    fn from(val: OutputFormat) -> Self {
+        match val {
+            OutputFormat::Human => good_fences::OutputFormat::Human,
+            OutputFormat::Json => good_fences::OutputFormat::Json,
+            OutputFormat::Ndjson => good_fences::OutputFormat::Ndjson,
+        }
+    }
+}
+
 #[napi(object)]
 pub struct GoodFencesResult {
     pub result_type: GoodFencesResultType,
@@ -62,6 +132,29 @@ impl From<good_fences::GoodFencesResult> for GoodFencesResult {
     }
 }
 
+#[napi(object)]
+pub struct GoodFencesStats {
+    pub files_scanned: u32,
+    pub fences_loaded: u32,
+    pub imports_checked: u32,
+}
+
+impl From<good_fences::FenceEvaluationStats> for GoodFencesStats {
+    fn from(val: good_fences::FenceEvaluationStats) -> Self {
+        GoodFencesStats {
+            files_scanned: val.files_scanned as u32,
+            fences_loaded: val.fences_loaded as u32,
+            imports_checked: val.imports_checked as u32,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct GoodFencesOutput {
+    pub results: Vec<GoodFencesResult>,
+    pub stats: GoodFencesStats,
+}
+
 #[derive(Eq, Debug, PartialEq)]
 #[napi]
 pub enum GoodFencesResultType {
@@ -81,8 +174,40 @@ impl From<good_fences::GoodFencesResultType> for GoodFencesResultType {
 }
 
 #[napi]
-pub fn good_fences(opts: GoodFencesOptions) -> Vec<GoodFencesResult> {
+pub fn good_fences(opts: GoodFencesOptions) -> napi::Result<Vec<GoodFencesResult>> {
+    let opts_native = opts.into();
+    let eval_results = good_fences::good_fences(opts_native)
+        .map_err(|err| napi::Error::from_reason(format!("{err:#}")))?;
+    Ok(eval_results.into_iter().map(Into::into).collect())
+}
+
+// Runs good-fences like `goodFences`, also returning counts of the files,
+// fences and imports it evaluated
+#[napi]
+pub fn good_fences_with_stats(opts: GoodFencesOptions) -> napi::Result<GoodFencesOutput> {
     let opts_native = opts.into();
-    let eval_results = good_fences::good_fences(opts_native);
-    eval_results.into_iter().map(Into::into).collect()
+    let output = good_fences::good_fences_with_stats(opts_native)
+        .map_err(|err| napi::Error::from_reason(format!("{err:#}")))?;
+    Ok(GoodFencesOutput {
+        results: output.results.into_iter().map(Into::into).collect(),
+        stats: output.stats.into(),
+    })
+}
+
+// Also exported under its snake_case name, for callers that look up the
+// binding as `good_fences` instead of `goodFences`
+#[napi(js_name = "good_fences")]
+pub fn good_fences_snake_case(opts: GoodFencesOptions) -> napi::Result<Vec<GoodFencesResult>> {
+    good_fences(opts)
+}
+
+// Runs good-fences with the arguments of the `good-fences` cli
+// (i.e. `process.argv.slice(2)`)
+#[napi]
+pub fn run_cli(args: Vec<String>) -> napi::Result<Vec<GoodFencesResult>> {
+    let opts_native = good_fences::cli_args::parse_cli_args(&args)
+        .map_err(|err| napi::Error::from_reason(err.to_string()))?;
+    let eval_results = good_fences::good_fences(opts_native)
+        .map_err(|err| napi::Error::from_reason(format!("{err:#}")))?;
+    Ok(eval_results.into_iter().map(Into::into).collect())
 }
@@ -9,7 +9,7 @@ use swc_common::FileName;
 use swc_ecma_loader::resolve::Resolve;
 use tsconfig_paths::TsconfigPathsJson;
 
-#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub enum ResolvedImport {
     NodeModulesImport(String),
     ProjectLocalImport(PathBuf),
@@ -19,6 +19,17 @@ pub enum ResolvedImport {
 pub const SOURCE_EXTENSIONS: &[&str] = &["js", "ts", "d.ts", "tsx", "jsx"];
 pub const ASSET_EXTENSION: &[&str] = &["scss", "css", "svg", "png", "json", "gif"];
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ResolveOptions {
+    // extensions of imported files that are resources rather than source files,
+    // matched case-insensitively with or without a leading dot
+    pub resource_extensions: Vec<String>,
+    // classify bare specifiers that do not resolve to a file as node_modules imports
+    // instead of erroring, e.g. when analyzing a repo before installing node_modules.
+    // Relative imports that fail to resolve are still errors.
+    pub assume_unresolved_bare_as_external: bool,
+}
+
 pub fn resolve_with_extension(
     base: FileName,
     imported_path: &str,
@@ -54,7 +65,6 @@ pub fn resolve_with_extension(
                 }
             }
             return Err(e);
-            // return Ok(ResolvedImport::NodeModulesImport(imported_specifier.to_string()))
         }
     };
     // let resolved = RelativePath::new(&resolved.to_string())..to_path("");
@@ -74,6 +84,12 @@ pub fn resolve_with_extension(
     Ok(ResolvedImport::NodeModulesImport(imported_path.into()))
 }
 
+/// Checks if an import specifier names a package (e.g. "left-pad" or
+/// "@org/pkg/sub"), rather than a relative or absolute path
+pub fn is_bare_specifier(imported_path: &str) -> bool {
+    !imported_path.starts_with('.') && !Path::new(imported_path).has_root()
+}
+
 fn is_resource_file(resolved: &str) -> bool {
     ASSET_EXTENSION
         .iter()
@@ -84,6 +100,7 @@ pub fn resolve_ts_import<'a>(
     tsconfig_paths: &'a TsconfigPathsJson,
     initial_path: &RelativePath,
     raw_import_specifier: &'a str,
+    options: &ResolveOptions,
 ) -> anyhow::Result<ResolvedImport, String> {
     tracing::debug!(
         "resolve_ts_import! {:?}, {:?}",
@@ -107,6 +124,9 @@ pub fn resolve_ts_import<'a>(
     };
 
     // short circuit when importing non-ts resource files.
+    if has_resource_extension(&import_specifier, &options.resource_extensions) {
+        return Ok(ResolvedImport::ResourceFileImport);
+    }
     let buf = PathBuf::from(import_specifier.clone());
     let ext = buf.extension();
     if let Some(ext) = ext {
@@ -184,6 +204,19 @@ pub fn resolve_ts_import<'a>(
     ))
 }
 
+// extensions are matched case-insensitively, with or without a leading dot.
+// They may span several dots, e.g. "svg.tsx" matches "./icon.svg.tsx"
+fn has_resource_extension(import_specifier: &str, resource_extensions: &[String]) -> bool {
+    let import_specifier = import_specifier.to_ascii_lowercase();
+    resource_extensions.iter().any(|extension| {
+        let extension = extension.trim_start_matches('.').to_ascii_lowercase();
+        !extension.is_empty()
+            && import_specifier
+                .strip_suffix(extension.as_str())
+                .is_some_and(|stem| stem.ends_with('.'))
+    })
+}
+
 fn switch_specifier_prefix(
     matched_star_path: &str,
     replace_star_path: &str,
@@ -227,7 +260,9 @@ fn path_buf_from_tsconfig(
 #[cfg(test)]
 mod test {
     extern crate relative_path;
-    use super::{resolve_ts_import, ResolvedImport, TsconfigPathsJson};
+    use super::{
+        is_bare_specifier, resolve_ts_import, ResolveOptions, ResolvedImport, TsconfigPathsJson,
+    };
     use lazy_static::lazy_static;
     use relative_path::RelativePathBuf;
     use std::path::PathBuf;
@@ -263,6 +298,7 @@ mod test {
             &TEST_TSCONFIG_JSON,
             &RelativePathBuf::from("packages/my/importing/module"),
             "../imported/module",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -272,12 +308,51 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_resource_extensions() {
+        let resolve = |specifier: &str, resource_extensions: &[String]| {
+            resolve_ts_import(
+                &TEST_TSCONFIG_JSON,
+                &RelativePathBuf::from("packages/my/importing/module"),
+                specifier,
+                &ResolveOptions {
+                    resource_extensions: resource_extensions.to_vec(),
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            resolve("./icon.svg.tsx", &[]),
+            Ok(ResolvedImport::ProjectLocalImport(PathBuf::from(
+                "packages/my/importing/icon.svg.tsx"
+            )))
+        );
+        let resource_extensions = vec![".svg.tsx".to_owned(), "JPG".to_owned()];
+        assert_eq!(
+            resolve("./icon.svg.tsx", &resource_extensions),
+            Ok(ResolvedImport::ResourceFileImport)
+        );
+        assert_eq!(
+            resolve("./photo.jpg", &resource_extensions),
+            Ok(ResolvedImport::ResourceFileImport)
+        );
+        // only whole extensions match
+        assert_eq!(
+            resolve("./icon-svg.tsx", &resource_extensions),
+            Ok(ResolvedImport::ProjectLocalImport(PathBuf::from(
+                "packages/my/importing/icon-svg.tsx"
+            )))
+        );
+    }
+
     #[test]
     fn test_non_glob_specifier() {
         let result = resolve_ts_import(
             &TEST_TSCONFIG_JSON,
             &RelativePathBuf::from("packages/my/importing/module"),
             "non-glob-specifier",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -293,6 +368,7 @@ mod test {
             &TEST_TSCONFIG_JSON,
             &RelativePathBuf::from("packages/my/importing/module"),
             "glob-specifier/lib/relative/after/glob/specifier/../../the/./specifier",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -316,6 +392,7 @@ mod test {
             },
             &RelativePathBuf::from("packages/my/importing/module"),
             "../imported/module",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -339,6 +416,7 @@ mod test {
             },
             &RelativePathBuf::from("packages/my/importing/module.ts"),
             "../imported/module",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -362,6 +440,7 @@ mod test {
             },
             &RelativePathBuf::from("packages/my/importing/module.ts"),
             ".",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -385,6 +464,7 @@ mod test {
             },
             &RelativePathBuf::from("packages/my/importing/module.ts"),
             "..",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -408,6 +488,7 @@ mod test {
             },
             &RelativePathBuf::from("packages/my/importing/module.ts"),
             "../imported/module",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -430,6 +511,7 @@ mod test {
             },
             &RelativePathBuf::from("packages/my/importing/module"),
             "non-glob-specifier",
+            &ResolveOptions::default(),
         );
         assert_eq!(
             result,
@@ -438,4 +520,40 @@ mod test {
             )))
         )
     }
+
+    #[test]
+    fn test_uppercase_resource_extension() {
+        let resolve = |specifier: &str| {
+            resolve_ts_import(
+                &TEST_TSCONFIG_JSON,
+                &RelativePathBuf::from("packages/my/importing/module"),
+                specifier,
+                &ResolveOptions::default(),
+            )
+        };
+        for specifier in ["./Logo.PNG", "./Data.JSON", "./styles/Theme.Scss"] {
+            assert_eq!(
+                resolve(specifier),
+                Ok(ResolvedImport::ResourceFileImport),
+                "{specifier} should be a resource"
+            );
+        }
+
+        // a basename that only looks like an extension is still resolved as a source file
+        assert_eq!(
+            resolve("./icons/PNG"),
+            Ok(ResolvedImport::ProjectLocalImport(PathBuf::from(
+                "packages/my/importing/icons/PNG"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_is_bare_specifier() {
+        assert!(is_bare_specifier("left-pad"));
+        assert!(is_bare_specifier("@org/pkg/sub"));
+        assert!(!is_bare_specifier("./missing"));
+        assert!(!is_bare_specifier("../missing"));
+        assert!(!is_bare_specifier("/abs/missing"));
+    }
 }
@@ -46,15 +46,42 @@ impl CombinedResolverCaches {
     }
 
     /// Mark the files files in the given path as dirty
-    pub fn mark_dirty_root(&mut self, root: &Path) {
+    pub fn mark_dirty_root(&self, root: &Path) {
         self.tsconfig_cache.mark_dirty_root(root);
         self.node_modules_cache.mark_dirty_root(root);
         self.package_json_cache.mark_dirty_root(root);
     }
 
+    /// Clears all cached entries, for every path
+    pub fn clear(&self) {
+        self.tsconfig_cache.clear();
+        self.node_modules_cache.clear();
+        self.package_json_cache.clear();
+    }
+
+    /// Eagerly checks a directory and each of its parents up to `root_dir`
+    /// for tsconfig.json, package.json and node_modules entries, so later
+    /// resolutions from files under the directory hit the caches.
+    pub fn warm_dir(&self, root_dir: &Path, dir: &Path) -> Result<(), Error> {
+        for ancestor in dir.ancestors().take_while(|a| a.starts_with(root_dir)) {
+            self.tsconfig_cache.check_dir(ancestor)?;
+            self.node_modules_cache.check_dir(ancestor)?;
+            self.package_json_cache.check_dir(ancestor)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the number of directories the caches have checked on the real
+    /// filesystem, across all context files
+    pub fn fs_read_count(&self) -> usize {
+        self.tsconfig_cache.fs_read_count()
+            + self.node_modules_cache.fs_read_count()
+            + self.package_json_cache.fs_read_count()
+    }
+
     // pre-populate a package json cache with a package.json file
-    pub fn package_json_cache(&mut self) -> &mut PackageJsonCache {
-        &mut self.package_json_cache
+    pub fn package_json_cache(&self) -> &PackageJsonCache {
+        &self.package_json_cache
     }
 }
 
@@ -84,7 +111,7 @@ impl Resolve for CombinedResolver<'_> {
                 ProcessedTsconfig::HasPaths(ref tsconfig) => {
                     let resolver =
                         TsconfigPathsResolver::new(tsconfig, &self.node_modules_resolver);
-                    tracing::debug!("matched tsconfig {} with \"paths\". resolving against tsconfig resolver (wrapping node_modules)", path.display());
+                    tracing::debug!("matched tsconfig {} with \"paths\" or \"baseUrl\". resolving against tsconfig resolver (wrapping node_modules)", path.display());
 
                     let resolution = resolver.resolve(base, module_specifier)?;
                     tracing::debug!(
@@ -96,7 +123,7 @@ impl Resolve for CombinedResolver<'_> {
                     Ok(resolution)
                 }
                 ProcessedTsconfig::NoPaths => {
-                    tracing::debug!("matched tsconfig {} with no \"paths\" entry. resolving against node_modules_resolver", path.display());
+                    tracing::debug!("matched tsconfig {} with no \"paths\" or \"baseUrl\" entry. resolving against node_modules_resolver", path.display());
                     self.node_modules_resolver.resolve(base, module_specifier)
                 }
             },
@@ -229,6 +256,51 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_implicit_base_url() {
+        let tmp = test_tmpdir!(
+            "tsconfig.json" => r#"{
+                "compilerOptions": {
+                    "baseUrl": "src"
+                }
+            }"#,
+            "src/components/Button.tsx" => r#"export const Button = 1;"#,
+            "node_modules/to-node-modules/package.json" => r#"{
+                "name": "to-node-modules",
+                "main": "./index.js"
+            }"#,
+            "node_modules/to-node-modules/index.js" => r#"export const something = 1;"#
+        );
+
+        let caches = CombinedResolverCaches::new();
+        let resolver = caches.resolver(
+            tmp.root(),
+            NodeModulesResolverOptions::default_for_env(TargetEnv::Node),
+        );
+        let from = FileName::Real(tmp.root_join("src/pages/home.ts"));
+
+        // bare specifiers are resolved against the baseUrl first
+        assert_eq!(
+            resolver.resolve(&from, "components/Button").unwrap(),
+            Resolution {
+                filename: FileName::Real(tmp.root().to_owned().join("src/components/Button.tsx")),
+                slug: None,
+            }
+        );
+        // and fall back to node_modules
+        assert_eq!(
+            resolver.resolve(&from, "to-node-modules").unwrap(),
+            Resolution {
+                filename: FileName::Real(
+                    tmp.root()
+                        .to_owned()
+                        .join("node_modules/to-node-modules/index.js")
+                ),
+                slug: None,
+            }
+        );
+    }
+
     #[test]
     pub fn test_import_glob() {
         let tmp = test_tmpdir!(
@@ -519,4 +591,157 @@ mod test {
             }
         );
     }
+
+    #[test]
+    pub fn test_string_sugar_exports() {
+        // "exports": "./index.js" is shorthand for { ".": "./index.js" }
+        let tmp = test_tmpdir!(
+            "node_modules/my-pkg/package.json" => r#"
+            {
+                "exports": "./index.js"
+            }
+            "#,
+            "node_modules/my-pkg/index.js" => r#"export const something = 1;"#
+        );
+
+        let caches = CombinedResolverCaches::new();
+        let resolver = caches.resolver(
+            tmp.root(),
+            NodeModulesResolverOptions::default_for_env(TargetEnv::Node),
+        );
+        let from = FileName::Real(tmp.root_join("packages/my/importing/module.ts"));
+
+        assert_eq!(
+            resolver.resolve(&from, "my-pkg").unwrap(),
+            Resolution {
+                filename: FileName::Real(
+                    tmp.root().to_owned().join("node_modules/my-pkg/index.js")
+                ),
+                slug: None,
+            }
+        );
+        // only the package root is exported
+        assert!(resolver.resolve(&from, "my-pkg/other").is_err());
+    }
+
+    #[test]
+    pub fn test_extension_priority() {
+        let tmp = test_tmpdir!(
+            "packages/my/importing/module.ts" => r#"import { x } from "./x";"#,
+            "packages/my/importing/x.js" => r#"export const x = 1;"#,
+            "packages/my/importing/x.ts" => r#"export const x = 2;"#,
+            "packages/my/importing/x.tsx" => r#"export const x = 3;"#
+        );
+        let from = FileName::Real(tmp.root_join("packages/my/importing/module.ts"));
+
+        let resolve_with_priority = |extension_priority: Vec<&str>| -> Resolution {
+            let caches = CombinedResolverCaches::new();
+            let mut options = NodeModulesResolverOptions::default_for_env(TargetEnv::Node);
+            options.extension_priority = extension_priority
+                .into_iter()
+                .map(|s| s.to_string())
+                .collect();
+            let resolver = caches.resolver(tmp.root(), options);
+            resolver.resolve(&from, "./x").unwrap()
+        };
+        let resolution = |file: &str| Resolution {
+            filename: FileName::Real(tmp.root_join("packages/my/importing").join(file)),
+            slug: None,
+        };
+
+        // by default, .js is probed first
+        assert_eq!(resolve_with_priority(vec![]), resolution("x.js"));
+        assert_eq!(resolve_with_priority(vec!["ts", "js"]), resolution("x.ts"));
+        assert_eq!(
+            resolve_with_priority(vec![".tsx", ".ts"]),
+            resolution("x.tsx")
+        );
+    }
+
+    #[test]
+    pub fn test_warm_dir() {
+        let tmp = test_tmpdir!(
+            "tsconfig.json" => r#"{ "compilerOptions": {} }"#,
+            "packages/my/package.json" => r#"{ "name": "my" }"#,
+            "packages/my/importing/module.ts" => r#"import { x } from "dep";"#,
+            "node_modules/dep/package.json" => r#"{ "name": "dep", "main": "index.js" }"#,
+            "node_modules/dep/index.js" => r#"export const x = 1;"#
+        );
+        let from = FileName::Real(tmp.root_join("packages/my/importing/module.ts"));
+
+        let count_fs_reads = |warm: bool| -> usize {
+            let caches = CombinedResolverCaches::new();
+            if warm {
+                caches
+                    .warm_dir(tmp.root(), &tmp.root_join("packages/my/importing"))
+                    .unwrap();
+            }
+            let before = caches.fs_read_count();
+            let resolver = caches.resolver(
+                tmp.root(),
+                NodeModulesResolverOptions::default_for_env(TargetEnv::Node),
+            );
+            assert_eq!(
+                resolver.resolve(&from, "dep").unwrap(),
+                Resolution {
+                    filename: FileName::Real(tmp.root_join("node_modules/dep/index.js")),
+                    slug: None,
+                }
+            );
+            caches.fs_read_count() - before
+        };
+
+        assert!(count_fs_reads(true) < count_fs_reads(false));
+    }
+
+    #[test]
+    pub fn test_known_external_scopes() {
+        let tmp = test_tmpdir!(
+            // reading this package.json would fail, so resolving @ext/pkg
+            // against node_modules is an error
+            "node_modules/@ext/pkg/package.json" => "not json",
+            "node_modules/@ext/internal/package.json" => r#"{
+                "name": "@ext/internal",
+                "main": "./index.js"
+            }"#,
+            "node_modules/@ext/internal/index.js" => r#"export const something = 1;"#
+        );
+        let from = FileName::Real(tmp.root_join("packages/my/importing/module.ts"));
+
+        let caches = CombinedResolverCaches::new();
+        let resolver = caches.resolver(
+            tmp.root(),
+            NodeModulesResolverOptions::default_for_env(TargetEnv::Node),
+        );
+        assert!(resolver.resolve(&from, "@ext/pkg").is_err());
+
+        let caches = CombinedResolverCaches::new();
+        let mut options = NodeModulesResolverOptions::default_for_env(TargetEnv::Node);
+        options.known_external_scopes = vec!["@ext/".to_string()];
+        options.internal_packages = ["@ext/internal".to_string()].into_iter().collect();
+        let resolver = caches.resolver(tmp.root(), options);
+
+        // imports in the scope are left unresolved, without reading node_modules
+        for specifier in ["@ext/pkg", "@ext/pkg/sub/path"] {
+            assert_eq!(
+                resolver.resolve(&from, specifier).unwrap(),
+                Resolution {
+                    filename: FileName::Real(specifier.into()),
+                    slug: None,
+                }
+            );
+        }
+        // internal packages in the scope are still resolved
+        assert_eq!(
+            resolver.resolve(&from, "@ext/internal").unwrap(),
+            Resolution {
+                filename: FileName::Real(
+                    tmp.root()
+                        .to_owned()
+                        .join("node_modules/@ext/internal/index.js")
+                ),
+                slug: None,
+            }
+        );
+    }
 }
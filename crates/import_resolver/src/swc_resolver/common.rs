@@ -1,2 +1,2 @@
 pub type AHashMap<K, V> = swc_common::collections::AHashMap<K, V>;
-// pub type AHashSet<T> = swc_common::collections::AHashSet<T>;
+pub type AHashSet<T> = swc_common::collections::AHashSet<T>;
@@ -44,6 +44,19 @@ impl<R> InternalOnlyResolver<R> {
             self.internal_packages.insert(name.clone());
         }
     }
+
+    /// Replace the set of packages considered "internal"
+    pub fn set_package_names(&mut self, package_names: impl Iterator<Item = impl AsRef<str>>) {
+        self.internal_packages.clear();
+        for name in package_names {
+            self.internal_packages.insert(name.as_ref().to_string());
+        }
+    }
+
+    /// Gets the resolver used to resolve internal modules
+    pub fn inner(&self) -> &R {
+        &self.inner_resolver
+    }
 }
 
 /// Implements Resolve for the InternalOnlyResolver
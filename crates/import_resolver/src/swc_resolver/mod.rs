@@ -47,6 +47,12 @@ impl MonorepoResolver {
         }
         .build()
     }
+
+    /// Gets the caches backing this resolver, so they can be invalidated or
+    /// pre-populated between resolutions.
+    pub fn caches(&self) -> &CombinedResolverCaches {
+        self.borrow_caches()
+    }
 }
 
 impl Debug for MonorepoResolver {
@@ -3,7 +3,7 @@
 //!
 //! See https://github.com/swc-project/swc/blob/f988b66e1fd921266a8abf6fe9bb997b6878e949/crates/swc_ecma_loader/src/resolvers/node.rs
 
-use super::common::AHashMap;
+use super::common::{AHashMap, AHashSet};
 use super::pkgjson_rewrites::PackageJsonRewriteData;
 use super::util;
 use abspath::join_abspath;
@@ -86,8 +86,19 @@ pub struct CachingNodeModulesResolver<'a> {
     // List of export conditions to try when resolving exports
     export_conditions: Vec<String>,
 
-    // list of extensions to use when resolving files
+    // list of extensions to use when resolving files, starting with the
+    // extensions of extension_priority
     extensions: Vec<String>,
+
+    // extensions probed first for extensionless specifiers, in order. If
+    // empty, `.js` is probed first
+    extension_priority: Vec<String>,
+
+    // scopes whose packages are never resolved against node_modules
+    known_external_scopes: Vec<String>,
+
+    // packages exempt from known_external_scopes
+    internal_packages: AHashSet<String>,
 }
 
 pub const DEFAULT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "json", "node"];
@@ -99,7 +110,20 @@ pub struct NodeModulesResolverOptions {
     pub preserve_symlinks: bool,
     pub ignore_node_modules: bool,
     pub extensions: Vec<String>,
+    /// Extensions to probe first for extensionless specifiers, in order
+    /// (e.g. `["tsx", "ts"]`), to match the resolution order of a build tool
+    /// when several files only differ by extension. Leading dots are ignored.
+    ///
+    /// If empty, `.js` is probed first, followed by `extensions` in order.
+    pub extension_priority: Vec<String>,
     pub export_conditions: Vec<String>,
+    /// Scopes (e.g. `@fluentui`) whose packages are known to be external.
+    /// Imports of these packages are left unresolved without probing the
+    /// filesystem, unless they are listed in `internal_packages`.
+    pub known_external_scopes: Vec<String>,
+    /// Packages that are resolved normally, even if they are in one of the
+    /// `known_external_scopes`
+    pub internal_packages: AHashSet<String>,
 }
 
 impl NodeModulesResolverOptions {
@@ -110,10 +134,13 @@ impl NodeModulesResolverOptions {
             preserve_symlinks: false,
             ignore_node_modules: false,
             extensions: DEFAULT_EXTENSIONS.iter().map(|s| s.to_string()).collect(),
+            extension_priority: Vec::new(),
             export_conditions: DEFAULT_EXPORT_CODITIONS
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            known_external_scopes: Vec::new(),
+            internal_packages: AHashSet::default(),
         }
     }
 }
@@ -126,6 +153,22 @@ impl<'caches> CachingNodeModulesResolver<'caches> {
         node_modules_cache: &'caches NodeModulesCache,
         options: NodeModulesResolverOptions,
     ) -> Self {
+        let extension_priority: Vec<String> = options
+            .extension_priority
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_string())
+            .collect();
+        // probe the prioritized extensions first, then the others in order
+        let extensions = extension_priority
+            .iter()
+            .cloned()
+            .chain(
+                options
+                    .extensions
+                    .into_iter()
+                    .filter(|ext| !extension_priority.contains(ext)),
+            )
+            .collect();
         Self {
             monorepo_root,
             pkg_json_cache,
@@ -135,11 +178,31 @@ impl<'caches> CachingNodeModulesResolver<'caches> {
             alias: options.alias,
             preserve_symlinks: options.preserve_symlinks,
             ignore_node_modules: options.ignore_node_modules,
-            extensions: options.extensions,
+            extensions,
+            extension_priority,
             export_conditions: options.export_conditions,
+            known_external_scopes: options.known_external_scopes,
+            internal_packages: options.internal_packages,
         }
     }
 
+    /// Checks if an import is of a package in one of the known external
+    /// scopes, and so does not need to be resolved
+    fn is_known_external(&self, module_specifier: &str) -> bool {
+        let package_name = match util::package_name(module_specifier) {
+            Some(package_name) => package_name,
+            None => return false,
+        };
+        let scope = match package_name.split_once('/') {
+            Some((scope, _)) if scope.starts_with('@') => scope,
+            _ => return false,
+        };
+        self.known_external_scopes
+            .iter()
+            .any(|known_scope| known_scope.trim_end_matches('/') == scope)
+            && !self.internal_packages.contains(package_name)
+    }
+
     fn wrap(&self, path: Option<PathBuf>) -> Result<FileName, Error> {
         if let Some(path) = path {
             if self.preserve_symlinks {
@@ -177,7 +240,7 @@ impl<'caches> CachingNodeModulesResolver<'caches> {
             if path.is_file() {
                 return Ok(Some(path.to_path_buf()));
             }
-        } else {
+        } else if self.extension_priority.is_empty() {
             // We try `.js` first.
             let mut path = path.to_path_buf();
             path.set_extension("js");
@@ -528,6 +591,13 @@ impl<'caches> CachingNodeModulesResolver<'caches> {
             module_specifier, base, self.target_env
         );
 
+        // Known external packages are left unresolved, as they would be by
+        // the InternalOnlyResolver, without going to node_modules
+        if !self.alias.contains_key(module_specifier) && self.is_known_external(module_specifier) {
+            debug!("{} is in a known external scope", module_specifier);
+            return Ok(FileName::Real(PathBuf::from(module_specifier)));
+        }
+
         if !module_specifier.starts_with('.') {
             // Handle absolute path
 
@@ -42,6 +42,8 @@ pub struct ProcessedTsconfigPaths {
 
 #[derive(Debug, Clone)]
 pub enum ProcessedTsconfig {
+    // Has `paths`, a `baseUrl`, or both. Without `paths`, specifiers are
+    // still resolved against the `baseUrl` before node_modules.
     HasPaths(ProcessedTsconfigPaths),
     NoPaths,
 }
@@ -78,9 +80,14 @@ impl ContextData for ProcessedTsconfig {
             }
         };
 
-        let raw_paths = match tsconfig_paths_json.compiler_options.paths {
-            Some(p) => p,
-            None => return Ok(Some(ProcessedTsconfig::NoPaths)),
+        let raw_paths = match (
+            tsconfig_paths_json.compiler_options.paths,
+            &tsconfig_paths_json.compiler_options.base_url,
+        ) {
+            (Some(p), _) => p,
+            // an implicit baseUrl still resolves non-relative specifiers
+            (None, Some(_)) => HashMap::new(),
+            (None, None) => return Ok(Some(ProcessedTsconfig::NoPaths)),
         };
 
         let base_url = match tsconfig_paths_json.compiler_options.base_url {
@@ -1,10 +1,22 @@
 use serde::{Deserialize, Deserializer};
 
-// Either a json string or a boolean
+// The target of an export: a path, null, nested conditions, or a list of fallbacks
 #[derive(Debug, Clone)]
 pub enum ExportedPath {
     Exported(String),
     Private,
+    // Nested export conditions, in the order they appear in package.json
+    //
+    // e.g:
+    // {
+    //   "import": "./foo.node.mjs",
+    //   "require": "./foo.node.cjs"
+    // }
+    Conditional(Vec<(String, ExportedPath)>),
+    // A list of targets, of which the first supported target is used
+    //
+    // e.g: ["./foo.node.mjs", "./foo.js"]
+    Fallbacks(Vec<ExportedPath>),
     // fallback option, see https://github.com/serde-rs/serde/issues/2057#issuecomment-879440712
     //
     // Some packages use non-standard extensions to the "exports" field
@@ -16,21 +28,106 @@ pub enum ExportedPath {
 }
 
 impl ExportedPath {
+    // Gets a reference to a single target. Nested conditions and fallbacks
+    // must be resolved with `resolve` first, and are treated as unrecognized.
     pub fn as_ref(&'_ self) -> ExportedPathRef<'_> {
         match self {
             ExportedPath::Exported(s) => ExportedPathRef::Exported(s),
             ExportedPath::Private => ExportedPathRef::Private,
-            ExportedPath::Unrecognized => ExportedPathRef::Unrecognized,
+            ExportedPath::Conditional(_)
+            | ExportedPath::Fallbacks(_)
+            | ExportedPath::Unrecognized => ExportedPathRef::Unrecognized,
         }
     }
 
-    pub fn map_export(&self, f: impl FnOnce(&str) -> String) -> Self {
+    // Maps every exported path, including those nested under conditions or fallbacks
+    pub fn map_export(&self, f: &impl Fn(&str) -> String) -> Self {
         match self {
             ExportedPath::Exported(s) => ExportedPath::Exported(f(s)),
             ExportedPath::Private => ExportedPath::Private,
+            ExportedPath::Conditional(conditions) => ExportedPath::Conditional(
+                conditions
+                    .iter()
+                    .map(|(condition, target)| (condition.clone(), target.map_export(f)))
+                    .collect(),
+            ),
+            ExportedPath::Fallbacks(targets) => {
+                ExportedPath::Fallbacks(targets.iter().map(|target| target.map_export(f)).collect())
+            }
             ExportedPath::Unrecognized => ExportedPath::Unrecognized,
         }
     }
+
+    // Resolves nested conditions and fallbacks down to a single target.
+    //
+    // At each level of nesting, the requested conditions are tried in order,
+    // and "default" is tried last. If the target under a matching condition
+    // has no match of its own, the next condition is tried. Fallback lists
+    // resolve to their first target that matches and is not unrecognized.
+    //
+    // Returns the target, alongside the nested conditions that led to it,
+    // or None if no condition matched.
+    pub fn resolve<'a, 'c, TStr: Into<&'c str>>(
+        &'a self,
+        requested_conditions: impl Clone + IntoIterator<Item = TStr>,
+    ) -> Option<(ExportedPathRef<'a>, Vec<&'a str>)> {
+        match self {
+            ExportedPath::Exported(s) => Some((ExportedPathRef::Exported(s), Vec::new())),
+            ExportedPath::Private => Some((ExportedPathRef::Private, Vec::new())),
+            ExportedPath::Unrecognized => Some((ExportedPathRef::Unrecognized, Vec::new())),
+            ExportedPath::Conditional(conditions) => {
+                let requested = requested_conditions
+                    .clone()
+                    .into_iter()
+                    .map(|condition| -> &'c str { condition.into() })
+                    .filter(|condition| *condition != "default")
+                    .chain(std::iter::once("default"));
+                for requested_condition in requested {
+                    let matched = conditions
+                        .iter()
+                        .find(|(condition, _)| condition == requested_condition);
+                    if let Some((condition, target)) = matched {
+                        if let Some((resolved, mut nested)) =
+                            target.resolve(requested_conditions.clone())
+                        {
+                            nested.insert(0, condition.as_str());
+                            return Some((resolved, nested));
+                        }
+                    }
+                }
+                None
+            }
+            ExportedPath::Fallbacks(targets) => targets
+                .iter()
+                .filter_map(|target| target.resolve(requested_conditions.clone()))
+                .find(|(resolved, _)| !matches!(resolved, ExportedPathRef::Unrecognized)),
+        }
+    }
+
+    // Gets every exported path reachable from this target, alongside the
+    // nested conditions that lead to it.
+    pub fn exported_targets(&self) -> Vec<(Vec<&str>, &str)> {
+        match self {
+            ExportedPath::Exported(s) => vec![(Vec::new(), s.as_str())],
+            ExportedPath::Private | ExportedPath::Unrecognized => Vec::new(),
+            ExportedPath::Conditional(conditions) => conditions
+                .iter()
+                .flat_map(|(condition, target)| {
+                    target
+                        .exported_targets()
+                        .into_iter()
+                        .map(move |(mut nested, exported)| {
+                            nested.insert(0, condition.as_str());
+                            (nested, exported)
+                        })
+                })
+                .collect(),
+            ExportedPath::Fallbacks(targets) => targets
+                .iter()
+                .flat_map(|target| target.exported_targets())
+                .collect(),
+        }
+    }
 }
 
 impl Default for ExportedPath {
@@ -53,6 +150,8 @@ impl PartialEq for ExportedPath {
         match (self, other) {
             (ExportedPath::Exported(a), ExportedPath::Exported(b)) => a == b,
             (ExportedPath::Private, ExportedPath::Private) => true,
+            (ExportedPath::Conditional(a), ExportedPath::Conditional(b)) => a == b,
+            (ExportedPath::Fallbacks(a), ExportedPath::Fallbacks(b)) => a == b,
             (ExportedPath::Unrecognized, ExportedPath::Unrecognized) => true,
             _ => false,
         }
@@ -115,7 +214,7 @@ impl<'de> serde::de::Visitor<'de> for ExportedPathVisitor {
     type Value = ExportedPath;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a string or null")
+        formatter.write_str("a string, null, a map of conditions, or a list of fallbacks")
     }
 
     fn visit_none<E>(self) -> Result<Self::Value, E>
@@ -283,18 +382,26 @@ impl<'de> serde::de::Visitor<'de> for ExportedPathVisitor {
         Ok(ExportedPath::Unrecognized)
     }
 
-    fn visit_seq<A>(self, _seq: A) -> Result<Self::Value, A::Error>
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
         A: serde::de::SeqAccess<'de>,
     {
-        Ok(ExportedPath::Unrecognized)
+        let mut targets = Vec::new();
+        while let Some(target) = seq.next_element::<ExportedPath>()? {
+            targets.push(target);
+        }
+        Ok(ExportedPath::Fallbacks(targets))
     }
 
-    fn visit_map<A>(self, _map: A) -> Result<Self::Value, A::Error>
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
     where
         A: serde::de::MapAccess<'de>,
     {
-        Ok(ExportedPath::Unrecognized)
+        let mut conditions = Vec::new();
+        while let Some((condition, target)) = map.next_entry::<String, ExportedPath>()? {
+            conditions.push((condition, target));
+        }
+        Ok(ExportedPath::Conditional(conditions))
     }
 
     fn visit_enum<A>(self, _data: A) -> Result<Self::Value, A::Error>
@@ -316,7 +423,7 @@ impl<'de> Deserialize<'de> for ExportedPath {
 
 #[cfg(test)]
 mod test {
-    use super::ExportedPath;
+    use super::{ExportedPath, ExportedPathRef};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -333,7 +440,84 @@ mod test {
 
     #[test]
     fn test_deserialize_unrecognized() {
-        let p: ExportedPath = serde_json::from_str("{}").unwrap();
+        let p: ExportedPath = serde_json::from_str("1").unwrap();
         assert_eq!(p, ExportedPath::Unrecognized);
     }
+
+    #[test]
+    fn test_deserialize_nested_conditions() {
+        let p: ExportedPath = serde_json::from_str(
+            r#"{
+                "node": {
+                    "import": "./foo.node.mjs",
+                    "require": "./foo.node.cjs"
+                },
+                "default": ["./foo.js", null]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            p,
+            ExportedPath::Conditional(vec![
+                (
+                    "node".to_string(),
+                    ExportedPath::Conditional(vec![
+                        (
+                            "import".to_string(),
+                            ExportedPath::Exported("./foo.node.mjs".to_string())
+                        ),
+                        (
+                            "require".to_string(),
+                            ExportedPath::Exported("./foo.node.cjs".to_string())
+                        ),
+                    ])
+                ),
+                (
+                    "default".to_string(),
+                    ExportedPath::Fallbacks(vec![
+                        ExportedPath::Exported("./foo.js".to_string()),
+                        ExportedPath::Private,
+                    ])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_resolve_nested_conditions() {
+        let p: ExportedPath = serde_json::from_str(
+            r#"{
+                "node": {
+                    "import": "./foo.node.mjs",
+                    "require": "./foo.node.cjs"
+                },
+                "default": "./foo.js"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            p.resolve(["node", "import"]),
+            Some((
+                ExportedPathRef::Exported("./foo.node.mjs"),
+                vec!["node", "import"]
+            ))
+        );
+        assert_eq!(
+            p.resolve(["node", "require"]),
+            Some((
+                ExportedPathRef::Exported("./foo.node.cjs"),
+                vec!["node", "require"]
+            ))
+        );
+        // "node" matches, but has no nested match, so "default" is used instead
+        assert_eq!(
+            p.resolve(["node"]),
+            Some((ExportedPathRef::Exported("./foo.js"), vec!["default"]))
+        );
+        assert_eq!(
+            p.resolve(["browser"]),
+            Some((ExportedPathRef::Exported("./foo.js"), vec!["default"]))
+        );
+    }
 }
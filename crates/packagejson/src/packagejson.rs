@@ -34,9 +34,67 @@ pub struct PackageJson {
     pub browser: Option<Browser>,
     #[serde(default)]
     pub exports: Option<PackageJsonExports>,
+    #[serde(default)]
+    pub directories: Option<PackageJsonDirectories>,
+    #[serde(default)]
+    pub workspaces: Option<PackageJsonWorkspaces>,
 }
 
-pub type PackageJsonExports = AHashMap<String, PackageJsonExport>;
+// package.json .directories field
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct PackageJsonDirectories {
+    #[serde(default)]
+    pub test: Option<String>,
+}
+
+// package.json .workspaces field
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PackageJsonWorkspaces {
+    // A list of globs matching package directories
+    //
+    // e.g. "workspaces": ["packages/*"]
+    Globs(Vec<String>),
+    // The object form used by yarn
+    //
+    // e.g. "workspaces": { "packages": ["packages/*"] }
+    Config {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+impl PackageJsonWorkspaces {
+    pub fn globs(&self) -> &[String] {
+        match self {
+            PackageJsonWorkspaces::Globs(globs) => globs,
+            PackageJsonWorkspaces::Config { packages } => packages,
+        }
+    }
+}
+
+// package.json .exports field
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PackageJsonExports {
+    // The string shorthand for a package with a single entry point
+    //
+    // e.g. "exports": "./index.js", which is equivalent to:
+    // {
+    //   ".": "./index.js"
+    // }
+    Sugar(String),
+    // A map of subpaths to their exports
+    //
+    // e.g:
+    // {
+    //   ".": "./index.js",
+    //   "./lib/util": "./lib/util.js"
+    // }
+    Map(PackageJsonExportsMap),
+}
+
+pub type PackageJsonExportsMap = AHashMap<String, PackageJsonExport>;
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
@@ -65,7 +123,22 @@ pub enum PackageJsonExport {
     //     "default": "./lib/util.js"
     //   }
     // }
+    //
+    // Each condition may itself map to nested conditions or to a list of fallbacks:
+    //
+    // e.g:
+    // {
+    //   "node": {
+    //     "import": "./foo.node.mjs",
+    //     "require": "./foo.node.cjs"
+    //   },
+    //   "default": "./foo.js"
+    // }
     Conditional(AHashMap<String, ExportedPath>),
+    // A list of targets, of which the first supported target is used
+    //
+    // e.g: ["./foo.mjs", "./foo.js"]
+    Fallbacks(Vec<ExportedPath>),
 }
 
 impl ContextData for PackageJson {
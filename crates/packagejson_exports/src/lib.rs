@@ -8,7 +8,7 @@ use path_slash::PathBufExt;
 
 use packagejson::{
     exported_path::{ExportedPath, ExportedPathRef},
-    PackageJsonExport, PackageJsonExports,
+    PackageJsonExport, PackageJsonExports, PackageJsonExportsMap,
 };
 
 // Pair path, export-condfition of form ('package-name/imported-path', 'import')
@@ -123,6 +123,23 @@ fn match_star_pattern<'a>(
 pub enum ExportCondition<'a> {
     Default,
     Condition(&'a str),
+    // A path of nested conditions, outermost first (e.g. ["node", "import"])
+    Nested(Vec<&'a str>),
+}
+
+impl<'a> ExportCondition<'a> {
+    // Extends a matched condition with the nested conditions matched under it
+    fn with_nested(self, nested: Vec<&'a str>) -> Self {
+        if nested.is_empty() {
+            return self;
+        }
+        let outer = match self {
+            ExportCondition::Default => vec!["default"],
+            ExportCondition::Condition(condition) => vec![condition],
+            ExportCondition::Nested(conditions) => conditions,
+        };
+        ExportCondition::Nested(outer.into_iter().chain(nested).collect())
+    }
 }
 
 impl<'a> From<&'a str> for ExportCondition<'a> {
@@ -140,6 +157,7 @@ impl Display for ExportCondition<'_> {
         match self {
             ExportCondition::Default => write!(f, "default"),
             ExportCondition::Condition(cond) => write!(f, "{}", cond),
+            ExportCondition::Nested(conds) => write!(f, "{}", conds.join(".")),
         }
     }
 }
@@ -222,10 +240,36 @@ impl<'a> MatchedExport<'a> {
     }
 }
 
+// Orders star patterns from most to least specific, following node's
+// PATTERN_KEY_COMPARE: longer prefixes before the star come first, then
+// longer patterns. This lets a more specific pattern (e.g. a `null` target
+// blocking "./internal/*") take precedence over a catch-all like "./*".
+fn compare_star_pattern_specificity(a: &str, b: &str) -> std::cmp::Ordering {
+    let prefix_len = |pattern: &str| pattern.find('*').unwrap_or(pattern.len());
+    prefix_len(b)
+        .cmp(&prefix_len(a))
+        .then_with(|| b.len().cmp(&a.len()))
+}
+
+// Checks if a path could have been produced by a star pattern. The text
+// before the first star must be a prefix of the path, and the text after the
+// last star (e.g. a file extension) must be its suffix.
 fn reverse_match_star_pattern(star_pattern: &str, path: &str) -> bool {
-    let pattern_segments = star_pattern.split('*').collect::<Vec<&str>>();
-    let mut head: &str = path;
-    for segment in pattern_segments {
+    let mut pattern_segments = star_pattern.split('*');
+    let mut head: &str = match pattern_segments.next() {
+        Some(prefix) => match path.strip_prefix(prefix) {
+            Some(head) => head,
+            None => return false,
+        },
+        None => return false,
+    };
+    let pattern_segments = pattern_segments.collect::<Vec<&str>>();
+    let (suffix, middle_segments) = match pattern_segments.split_last() {
+        Some(split) => split,
+        // a pattern without any stars only matches itself
+        None => return head.is_empty(),
+    };
+    for segment in middle_segments {
         if segment.is_empty() {
             continue;
         }
@@ -236,7 +280,7 @@ fn reverse_match_star_pattern(star_pattern: &str, path: &str) -> bool {
             return false;
         }
     }
-    true
+    head.ends_with(suffix)
 }
 
 impl PackageExportRewriteData {
@@ -264,10 +308,15 @@ impl PackageExportRewriteData {
         {
             let export_key = ExportKey(clean_relative_import.to_string(), export_condition.into());
             if let Some(matched) = self.static_exports.get(&export_key) {
-                return Ok(Some(MatchedExport::with_kind(
-                    matched.as_ref(),
-                    export_condition.into(),
-                )));
+                // outer conditions are matched before any conditions nested under them
+                if let Some((resolved, nested)) =
+                    matched.resolve(requested_export_conditions.clone())
+                {
+                    return Ok(Some(MatchedExport::with_kind(
+                        resolved,
+                        ExportCondition::from(export_condition).with_nested(nested),
+                    )));
+                }
             }
         }
 
@@ -279,7 +328,9 @@ impl PackageExportRewriteData {
                 x.iter()
                     .filter_map(|(directory_pattern, directory_export)| {
                         if clean_relative_import.starts_with(directory_pattern) {
-                            Some((directory_pattern, directory_export))
+                            directory_export
+                                .resolve(requested_export_conditions.clone())
+                                .map(|resolved| (directory_pattern, resolved))
                         } else {
                             None
                         }
@@ -287,13 +338,15 @@ impl PackageExportRewriteData {
                     .next()
             },
         );
-        if let Some(((directory_pattern, directory_export), export_condition)) = directory_match {
+        if let Some(((directory_pattern, (directory_export, nested)), export_condition)) =
+            directory_match
+        {
             return Ok(Some(MatchedExport::with_kind(
-                directory_export.as_ref().map_export(|v| {
+                directory_export.map_export(|v| {
                     rewrite_dir_export(clean_relative_import, directory_pattern, v, out);
                     out as &str
                 }),
-                export_condition,
+                export_condition.with_nested(nested),
             )));
         }
 
@@ -304,21 +357,23 @@ impl PackageExportRewriteData {
             |x: &Vec<(String, ExportedPath)>| {
                 x.iter()
                     .filter_map(|(star_pattern, star_export)| {
-                        match_star_pattern(star_pattern, clean_relative_import)
-                            .map(|star_match| (star_match, star_export))
+                        let star_match = match_star_pattern(star_pattern, clean_relative_import)?;
+                        star_export
+                            .resolve(requested_export_conditions.clone())
+                            .map(|resolved| (star_match, resolved))
                     })
                     .next()
             },
         );
-        if let Some(((star_match, target), export_condition)) = star_match {
+        if let Some(((star_match, (target, nested)), export_condition)) = star_match {
             return Ok(Some(MatchedExport::with_kind(
                 // if we have a star match, rewrite the path and store it in `out`. Otherwise,
                 // do nothing
-                target.as_ref().map_export(|v| {
+                target.map_export(|v| {
                     rewrite_star_export(star_match, v, out);
                     out as &str
                 }),
-                export_condition,
+                export_condition.with_nested(nested),
             )));
         }
 
@@ -327,45 +382,46 @@ impl PackageExportRewriteData {
     }
 
     /// Checks if a given package-relative path is exported or not.
-    /// Returns the set of conditions that export that path.
-    pub fn get_exported_conditions<'a>(&'a self, package_relative_path: &str) -> Vec<&'a str> {
+    /// Returns the set of conditions that export that path. Nested conditions
+    /// are reported as their full path, joined by '.' (e.g. `node.import`).
+    pub fn get_exported_conditions(&self, package_relative_path: &str) -> Vec<String> {
         let mut clean_dest = String::new();
         let cleaned_path = clean_path_avoid_alloc(package_relative_path, &mut clean_dest);
 
-        let mut accum: Vec<&str> = Vec::new();
+        let mut accum: Vec<String> = Vec::new();
+        let mut add_condition = |condition: &str, nested: Vec<&str>| {
+            let condition_path = std::iter::once(condition)
+                .chain(nested)
+                .collect::<Vec<_>>()
+                .join(".");
+            if !accum.contains(&condition_path) {
+                accum.push(condition_path);
+            }
+        };
 
         for (ExportKey(_, condition), exported) in self.static_exports.iter() {
-            if accum.contains(&condition.as_str()) {
-                continue;
-            }
-            if let ExportedPath::Exported(exported_path) = exported {
+            for (nested, exported_path) in exported.exported_targets() {
                 if cleaned_path == exported_path {
-                    accum.push(condition)
+                    add_condition(condition, nested);
                 }
             }
         }
 
         for (condition, exports) in self.directory_exports.iter() {
-            if accum.contains(&condition.as_str()) {
-                continue;
-            }
             for (_, exported) in exports {
-                if let ExportedPath::Exported(exported_dir_path) = exported {
+                for (nested, exported_dir_path) in exported.exported_targets() {
                     if cleaned_path.starts_with(exported_dir_path) {
-                        accum.push(condition)
+                        add_condition(condition, nested);
                     }
                 }
             }
         }
 
         for (condition, exports) in self.star_exports.iter() {
-            if accum.contains(&condition.as_str()) {
-                continue;
-            }
             for (_, exported) in exports {
-                if let ExportedPath::Exported(exported_star_pattern) = exported {
+                for (nested, exported_star_pattern) in exported.exported_targets() {
                     if reverse_match_star_pattern(exported_star_pattern, cleaned_path) {
-                        accum.push(condition)
+                        add_condition(condition, nested);
                     }
                 }
             }
@@ -381,7 +437,7 @@ impl PackageExportRewriteData {
         let cleaned_path = clean_path_avoid_alloc(package_relative_path, &mut clean_dest);
 
         for (_, exported) in self.static_exports.iter() {
-            if let ExportedPath::Exported(exported_path) = exported {
+            for (_, exported_path) in exported.exported_targets() {
                 if cleaned_path == exported_path {
                     return true;
                 }
@@ -390,7 +446,7 @@ impl PackageExportRewriteData {
 
         for (_, exports) in self.directory_exports.iter() {
             for (_, exported) in exports {
-                if let ExportedPath::Exported(exported_dir_path) = exported {
+                for (_, exported_dir_path) in exported.exported_targets() {
                     if cleaned_path.starts_with(exported_dir_path) {
                         return true;
                     }
@@ -400,7 +456,7 @@ impl PackageExportRewriteData {
 
         for (_, exports) in self.star_exports.iter() {
             for (_, exported) in exports {
-                if let ExportedPath::Exported(exported_star_pattern) = exported {
+                for (_, exported_star_pattern) in exported.exported_targets() {
                     if reverse_match_star_pattern(exported_star_pattern, cleaned_path) {
                         return true;
                     }
@@ -410,12 +466,42 @@ impl PackageExportRewriteData {
 
         false
     }
+
+    /// Checks if a given package-relative path is the target of the package's
+    /// index export (the "." entry) under any export condition.
+    pub fn is_index_export(&self, package_relative_path: &str) -> bool {
+        let mut clean_dest = String::new();
+        let cleaned_path = clean_path_avoid_alloc(package_relative_path, &mut clean_dest);
+
+        self.static_exports
+            .iter()
+            .any(|(ExportKey(export_path, _), exported)| {
+                export_path == "."
+                    && exported
+                        .exported_targets()
+                        .iter()
+                        .any(|(_, exported_path)| cleaned_path == *exported_path)
+            })
+    }
 }
 
 impl TryFrom<&PackageJsonExports> for PackageExportRewriteData {
     type Error = anyhow::Error;
 
-    fn try_from(exports_map: &PackageJsonExports) -> Result<Self> {
+    fn try_from(exports: &PackageJsonExports) -> Result<Self> {
+        // for the string shorthand, simulate an exports map with a single entry, "."
+        let sugar_exports_map: PackageJsonExportsMap;
+        let exports_map = match exports {
+            PackageJsonExports::Sugar(export_target) => {
+                sugar_exports_map = PackageJsonExportsMap::from_iter([(
+                    ".".to_string(),
+                    PackageJsonExport::Single(Some(export_target.clone())),
+                )]);
+                &sugar_exports_map
+            }
+            PackageJsonExports::Map(exports_map) => exports_map,
+        };
+
         let mut resolution_data = PackageExportRewriteData::default();
         for (export_path, exported) in exports_map.iter() {
             if !export_path.starts_with("./") && export_path != "." {
@@ -440,6 +526,14 @@ impl TryFrom<&PackageJsonExports> for PackageExportRewriteData {
                     &my_cond_exp
                 }
                 PackageJsonExport::Conditional(conditional_exports) => conditional_exports,
+                PackageJsonExport::Fallbacks(export_targets) => {
+                    let entry = (
+                        "default".to_string(),
+                        ExportedPath::Fallbacks(export_targets.clone()),
+                    );
+                    my_cond_exp = ahashmap::AHashMap::from_iter(vec![entry].drain(..));
+                    &my_cond_exp
+                }
             };
 
             let export_path_star_ct = export_path.chars().filter(|c| *c == '*').count();
@@ -452,7 +546,7 @@ impl TryFrom<&PackageJsonExports> for PackageExportRewriteData {
                         .or_insert_with(Vec::new)
                         .push((
                             clean_path(export_path),
-                            export_target.map_export(clean_path),
+                            export_target.map_export(&clean_path),
                         ));
                 }
             } else if export_path_star_ct > 1 {
@@ -470,7 +564,7 @@ impl TryFrom<&PackageJsonExports> for PackageExportRewriteData {
                         .or_insert_with(Vec::new)
                         .push((
                             clean_path(export_path),
-                            export_target.map_export(clean_path),
+                            export_target.map_export(&clean_path),
                         ));
                 }
             } else {
@@ -478,11 +572,21 @@ impl TryFrom<&PackageJsonExports> for PackageExportRewriteData {
                 for (export_condition, export_target) in conditional_exports.iter() {
                     resolution_data.static_exports.insert(
                         ExportKey(clean_path(export_path), export_condition.clone()),
-                        export_target.map_export(clean_path),
+                        export_target.map_export(&clean_path),
                     );
                 }
             }
         }
+
+        // match the most specific directory and star patterns first, regardless
+        // of the order they were declared in
+        for directory_exports in resolution_data.directory_exports.values_mut() {
+            directory_exports.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+        }
+        for star_exports in resolution_data.star_exports.values_mut() {
+            star_exports.sort_by(|(a, _), (b, _)| compare_star_pattern_specificity(a, b));
+        }
+
         Ok(resolution_data)
     }
 }
@@ -534,6 +638,27 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_string_sugar_export() {
+        run_test_case(TestCase {
+            exports: r#""./index.js""#,
+            expected: map2!(
+                "./index.js" => vec!["default"],
+                "./other.js" => vec![]
+            ),
+        });
+    }
+
+    #[test]
+    fn test_string_sugar_is_index_export() {
+        let exports: packagejson::PackageJsonExports =
+            serde_json::from_str(r#""./lib/index.js""#).unwrap();
+        let parsed_exports = PackageExportRewriteData::try_from(&exports).unwrap();
+
+        assert!(parsed_exports.is_index_export("./lib/index.js"));
+        assert!(!parsed_exports.is_index_export("./lib/other.js"));
+    }
+
     #[test]
     fn test_conditional_literal_export() {
         run_test_case(TestCase {
@@ -652,4 +777,208 @@ mod test {
             ),
         })
     }
+
+    #[test]
+    fn test_nested_conditional_literal_export() {
+        run_test_case(TestCase {
+            exports: r#"{
+                    "./foo": {
+                        "node": {
+                            "import": "./foo.node.mjs",
+                            "require": "./foo.node.cjs"
+                        },
+                        "default": "./foo.js"
+                    }
+                }"#,
+            expected: map2!(
+                "./foo.node.mjs" => vec!["node.import"],
+                "./foo.node.cjs" => vec!["node.require"],
+                "./foo.js" => vec!["default"],
+                "./bar.js" => vec![]
+            ),
+        });
+    }
+
+    #[test]
+    fn test_fallback_export() {
+        run_test_case(TestCase {
+            exports: r#"{
+                    "./foo": ["./foo.mjs", "./foo.js"]
+                }"#,
+            expected: map2!(
+                "./foo.mjs" => vec!["default"],
+                "./foo.js" => vec!["default"],
+                "./bar.js" => vec![]
+            ),
+        });
+    }
+
+    #[test]
+    fn test_rewrite_nested_conditional_export() {
+        let exports: packagejson::PackageJsonExports = serde_json::from_str(
+            r#"{
+                "./foo": {
+                    "node": {
+                        "import": "./foo.node.mjs",
+                        "require": "./foo.node.cjs"
+                    },
+                    "default": "./foo.js"
+                },
+                "./bar/*": {
+                    "node": { "import": "./bar/*.mjs" },
+                    "default": "./bar/*.js"
+                }
+            }"#,
+        )
+        .unwrap();
+        let parsed_exports = PackageExportRewriteData::try_from(&exports).unwrap();
+        let rewrite = |import: &'static str, conditions: Vec<&'static str>| {
+            let mut out = String::new();
+            let matched = parsed_exports
+                .rewrite_relative_export(import, conditions, &mut out)
+                .unwrap()
+                .unwrap();
+            let rewritten = match matched.rewritten_export {
+                ExportedPathRef::Exported(path) => path.to_string(),
+                other => panic!("unexpected export {:?}", other),
+            };
+            (rewritten, matched.export_kind.to_string())
+        };
+
+        assert_eq!(
+            rewrite("./foo", vec!["node", "import"]),
+            ("./foo.node.mjs".to_string(), "node.import".to_string())
+        );
+        assert_eq!(
+            rewrite("./foo", vec!["node", "require"]),
+            ("./foo.node.cjs".to_string(), "node.require".to_string())
+        );
+        // "node" matches, but nothing under it does, so "default" is used
+        assert_eq!(
+            rewrite("./foo", vec!["node"]),
+            ("./foo.js".to_string(), "default".to_string())
+        );
+        assert_eq!(
+            rewrite("./bar/baz", vec!["node", "import"]),
+            ("./bar/baz.mjs".to_string(), "node.import".to_string())
+        );
+        assert_eq!(
+            rewrite("./bar/baz", vec!["browser"]),
+            ("./bar/baz.js".to_string(), "default".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_null_star_export_is_private() {
+        let exports: packagejson::PackageJsonExports = serde_json::from_str(
+            r#"{
+                "./*": "./src/*.js",
+                "./internal/*": null
+            }"#,
+        )
+        .unwrap();
+        let parsed_exports = PackageExportRewriteData::try_from(&exports).unwrap();
+        let rewrite = |import: &'static str| {
+            let mut out = String::new();
+            parsed_exports
+                .rewrite_relative_export(import, vec!["import"], &mut out)
+                .unwrap()
+                .map(|matched| match matched.rewritten_export {
+                    ExportedPathRef::Exported(path) => Some(path.to_string()),
+                    ExportedPathRef::Private => None,
+                    other => panic!("unexpected export {:?}", other),
+                })
+        };
+
+        // blocked by the more specific `null` pattern, even though "./*" also matches
+        assert_eq!(rewrite("./internal/secret"), Some(None));
+        assert_eq!(
+            rewrite("./public"),
+            Some(Some("./src/public.js".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rewrite_null_star_export_is_distinct_from_unmatched() {
+        let exports: packagejson::PackageJsonExports = serde_json::from_str(
+            r#"{
+                ".": "./index.js",
+                "./internal/*": null
+            }"#,
+        )
+        .unwrap();
+        let parsed_exports = PackageExportRewriteData::try_from(&exports).unwrap();
+
+        let mut out = String::new();
+        let blocked = parsed_exports
+            .rewrite_relative_export("./internal/secret", vec!["import"], &mut out)
+            .unwrap()
+            .unwrap();
+        assert_eq!(blocked.rewritten_export, ExportedPathRef::Private);
+        assert!(!parsed_exports.is_exported("./internal/secret"));
+
+        let mut out = String::new();
+        let unmatched = parsed_exports
+            .rewrite_relative_export("./other/file", vec!["import"], &mut out)
+            .unwrap();
+        assert!(unmatched.is_none());
+    }
+
+    #[test]
+    fn test_rewrite_star_export_with_extension() {
+        // the public .js specifier maps to the .ts source file
+        let exports: packagejson::PackageJsonExports = serde_json::from_str(
+            r#"{
+                "./features/*.js": "./src/features/*.ts"
+            }"#,
+        )
+        .unwrap();
+        let parsed_exports = PackageExportRewriteData::try_from(&exports).unwrap();
+        let rewrite = |import: &'static str| {
+            let mut out = String::new();
+            parsed_exports
+                .rewrite_relative_export(import, vec!["import"], &mut out)
+                .unwrap()
+                .map(|matched| match matched.rewritten_export {
+                    ExportedPathRef::Exported(path) => path.to_string(),
+                    other => panic!("unexpected export {:?}", other),
+                })
+        };
+
+        assert_eq!(
+            rewrite("./features/foo.js"),
+            Some("./src/features/foo.ts".to_string())
+        );
+        assert_eq!(
+            rewrite("./features/nested/foo.js"),
+            Some("./src/features/nested/foo.ts".to_string())
+        );
+        // the extension in the key must match the specifier
+        assert_eq!(rewrite("./features/foo.ts"), None);
+        assert_eq!(rewrite("./features/foo"), None);
+
+        assert!(parsed_exports.is_exported("./src/features/foo.ts"));
+        assert!(!parsed_exports.is_exported("./src/features/foo.js"));
+        assert!(!parsed_exports.is_exported("./src/features/foo.tsx"));
+    }
+
+    #[test]
+    fn test_is_index_export() {
+        let exports: packagejson::PackageJsonExports = serde_json::from_str(
+            r#"{
+                ".": {
+                    "source": "./src/index.ts",
+                    "default": "./lib/index.js"
+                },
+                "./foo": "./src/foo.ts"
+            }"#,
+        )
+        .unwrap();
+        let parsed_exports = PackageExportRewriteData::try_from(&exports).unwrap();
+
+        assert!(parsed_exports.is_index_export("./src/index.ts"));
+        assert!(parsed_exports.is_index_export("./lib/index.js"));
+        assert!(!parsed_exports.is_index_export("./src/foo.ts"));
+        assert!(!parsed_exports.is_index_export("./src/other.ts"));
+    }
 }
@@ -0,0 +1,68 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+pub type GlobError = globset::Error;
+
+/// A glob pattern in the syntax shared by every config field that accepts
+/// globs, so a pattern that works in one field works in all of them.
+///
+/// Supported syntax:
+/// - `?` matches any single character
+/// - `*` matches any sequence of characters, including path separators
+/// - `**` matches any number of directories (e.g. `**/__tests__/**`)
+/// - `[ab]`, `[a-z]` and `[!ab]` match a single character in (or not in) a class
+/// - `{a,b}` matches either of the comma-separated alternatives
+/// - `\` escapes the following character, except on windows
+#[derive(Debug, Clone)]
+pub struct Glob {
+    glob: globset::Glob,
+    matcher: globset::GlobMatcher,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Self, GlobError> {
+        let glob = globset::GlobBuilder::new(pattern)
+            .literal_separator(false)
+            .build()?;
+        let matcher = glob.compile_matcher();
+        Ok(Self { glob, matcher })
+    }
+
+    /// The pattern this glob was compiled from
+    pub fn as_str(&self) -> &str {
+        self.glob.glob()
+    }
+
+    /// Checks if a string, such as a package name, matches the glob
+    pub fn matches(&self, s: &str) -> bool {
+        self.matcher.is_match(s)
+    }
+
+    /// Checks if a path matches the glob
+    pub fn matches_path(&self, path: impl AsRef<Path>) -> bool {
+        self.matcher.is_match(path)
+    }
+}
+
+impl PartialEq for Glob {
+    fn eq(&self, other: &Self) -> bool {
+        self.glob == other.glob
+    }
+}
+
+impl Eq for Glob {}
+
+impl Display for Glob {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Glob {
+    type Err = GlobError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
@@ -1,8 +1,13 @@
 use anyhow::{Context, Result};
+use path_clean::PathClean;
 use path_slash::PathExt;
 use relative_path::RelativePathBuf;
 use std::path::{Path, PathBuf};
 
+mod glob;
+
+pub use glob::{Glob, GlobError};
+
 pub fn as_relative_slash_path<P: AsRef<Path>>(p: P) -> Result<RelativePathBuf> {
     let pref = p.as_ref();
     let relative_fence_path: RelativePathBuf =
@@ -19,3 +24,31 @@ pub fn as_relative_slash_path<P: AsRef<Path>>(p: P) -> Result<RelativePathBuf> {
         })?;
     Ok(RelativePathBuf::from(slashed_pbuf))
 }
+
+/// Joins a path onto a directory, resolving `.` and `..` segments lexically.
+///
+/// Unlike `abspath::join_abspath`, the directory may be relative, in which
+/// case leading `..` segments that cannot be resolved are kept. Glob
+/// characters in either path are left untouched.
+pub fn join_normalized<B: AsRef<Path>, P: AsRef<Path>>(dir: B, path: P) -> PathBuf {
+    dir.as_ref().join(path).clean()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::join_normalized;
+
+    #[test]
+    fn test_join_normalized() {
+        assert_eq!(
+            join_normalized("path/to/fence", "../sibling/*"),
+            PathBuf::from("path/to/sibling/*")
+        );
+        assert_eq!(
+            join_normalized("../../path", "./a/../../b"),
+            PathBuf::from("../../b")
+        );
+    }
+}
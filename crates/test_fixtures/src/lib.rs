@@ -0,0 +1,337 @@
+//! Builders for the small repos that integration tests run the unused finder
+//! and good-fences against.
+//!
+//! A [`FixtureRepo`] is a temporary directory that is deleted when it is
+//! dropped. Packages added to it get a `package.json`, an optional `fence.json`
+//! and an entry in the `paths` of the repo's `tsconfig.json`, so they can be
+//! imported by name.
+//!
+//! ```
+//! use serde_json::json;
+//! use test_fixtures::FixtureRepo;
+//!
+//! let repo = FixtureRepo::new()
+//!     .package("@org/a", |p| {
+//!         p.file("src/index.ts", "export const a = 1;")
+//!             .exports_map(json!({ ".": "./src/index.ts" }))
+//!     })
+//!     .package("@org/b", |p| {
+//!         p.main("./src/index.ts")
+//!             .file("src/index.ts", r#"import { a } from "@org/a";"#)
+//!             .fence(json!({ "tags": ["b"] }))
+//!     });
+//!
+//! assert!(repo.root_join("packages/a/package.json").is_file());
+//! assert!(repo.root_join("packages/b/fence.json").is_file());
+//! assert_eq!(repo.fence_paths(), ["packages/b/fence.json"]);
+//! ```
+
+use path_slash::PathBufExt;
+use serde_json::{json, Map, Value};
+use std::ops::Deref;
+use test_tmpdir::TmpDir;
+
+#[cfg(feature = "good_fences")]
+use good_fences::{GoodFencesOptions, GoodFencesResult};
+#[cfg(feature = "unused_finder")]
+use unused_finder::{UnusedFinderJSONConfig, UnusedFinderReport};
+
+/// A repo in a temporary directory, built up from packages and loose files.
+///
+/// Files are written as soon as they are added. The `tsconfig.json` at the
+/// root of the repo is regenerated each time a package is added, mapping the
+/// name of each package to its directory.
+pub struct FixtureRepo {
+    tmpdir: TmpDir,
+    // (name, directory, main file) of each package, relative to the repo root
+    packages: Vec<(String, String, Option<String>)>,
+    fence_paths: Vec<String>,
+}
+
+/// A package of a [`FixtureRepo`], passed to the closure of
+/// [`FixtureRepo::package`].
+pub struct FixturePackage {
+    name: String,
+    dir: String,
+    package_json: Map<String, Value>,
+    files: Vec<(String, String)>,
+    fence: Option<Value>,
+}
+
+impl Default for FixtureRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for FixtureRepo {
+    type Target = TmpDir;
+
+    fn deref(&self) -> &TmpDir {
+        &self.tmpdir
+    }
+}
+
+impl FixtureRepo {
+    /// Creates an empty repo, with a `tsconfig.json` that has no paths.
+    pub fn new() -> FixtureRepo {
+        let repo = FixtureRepo {
+            tmpdir: TmpDir::new(),
+            packages: Vec::new(),
+            fence_paths: Vec::new(),
+        };
+        repo.write_tsconfig();
+        repo
+    }
+
+    /// Writes a file, relative to the root of the repo.
+    pub fn file(self, path: &str, content: &str) -> Self {
+        self.write(path, content);
+        self
+    }
+
+    /// Writes a `fence.json` in a directory relative to the root of the repo.
+    pub fn fence(mut self, dir: &str, fence: Value) -> Self {
+        self.write_fence(dir, &fence);
+        self
+    }
+
+    /// Adds a package to the repo, in `packages/<name>` without the scope of
+    /// the name unless the closure picks another directory.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use test_fixtures::FixtureRepo;
+    ///
+    /// let repo = FixtureRepo::new().package("entrypoint", |p| {
+    ///     p.dir("packages/root")
+    ///         .main("./main.js")
+    ///         .field("private", json!(true))
+    ///         .file("main.js", "export const root = 1;")
+    /// });
+    ///
+    /// let package_json = std::fs::read_to_string(repo.root_join("packages/root/package.json"));
+    /// assert!(package_json.unwrap().contains(r#""main": "./main.js""#));
+    /// ```
+    pub fn package(
+        mut self,
+        name: &str,
+        build: impl FnOnce(FixturePackage) -> FixturePackage,
+    ) -> Self {
+        let package = build(FixturePackage::new(name));
+
+        let mut package_json = package.package_json;
+        package_json.insert("name".to_string(), json!(package.name));
+        self.write(
+            &format!("{}/package.json", package.dir),
+            &serde_json::to_string_pretty(&package_json).unwrap(),
+        );
+        for (path, content) in package.files.iter() {
+            self.write(&format!("{}/{}", package.dir, path), content);
+        }
+        if let Some(fence) = package.fence {
+            self.write_fence(&package.dir, &fence);
+        }
+
+        // bare imports of the package resolve to its main file, if it has one
+        let main = package_json
+            .get("main")
+            .and_then(Value::as_str)
+            .map(|main| format!("{}/{}", package.dir, main.trim_start_matches("./")));
+        self.packages.push((package.name, package.dir, main));
+        self.write_tsconfig();
+        self
+    }
+
+    /// Gets the absolute path of a file in the repo, with forward slashes.
+    pub fn path(&self, path: &str) -> String {
+        self.tmpdir.root_join(path).to_slash_lossy().to_string()
+    }
+
+    /// Gets the path of a file in the repo relative to the working directory,
+    /// with forward slashes. good-fences identifies source files and fences
+    /// by these paths.
+    pub fn relative_path(&self, path: &str) -> String {
+        let cwd = std::env::current_dir().unwrap();
+        pathdiff::diff_paths(self.tmpdir.root_join(path), cwd)
+            .unwrap()
+            .to_slash_lossy()
+            .to_string()
+    }
+
+    /// Gets the paths of the `fence.json` files written to the repo, relative
+    /// to its root, in the order they were written.
+    pub fn fence_paths(&self) -> &[String] {
+        &self.fence_paths
+    }
+
+    fn write(&self, path: &str, content: &str) {
+        self.tmpdir
+            .write_batch(&[(path.to_string(), content)].into_iter().collect())
+            .unwrap();
+    }
+
+    fn write_fence(&mut self, dir: &str, fence: &Value) {
+        let fence_path = match dir.trim_end_matches('/') {
+            "" => "fence.json".to_string(),
+            dir => format!("{dir}/fence.json"),
+        };
+        self.write(&fence_path, &serde_json::to_string_pretty(fence).unwrap());
+        self.fence_paths.push(fence_path);
+    }
+
+    fn write_tsconfig(&self) {
+        let mut paths = Map::new();
+        for (name, dir, main) in self.packages.iter() {
+            paths.insert(name.clone(), json!([main.as_ref().unwrap_or(dir)]));
+            paths.insert(format!("{name}/*"), json!([format!("{dir}/*")]));
+        }
+        let tsconfig = json!({
+            "compilerOptions": {
+                "baseUrl": ".",
+                "paths": paths,
+            }
+        });
+        self.write(
+            "tsconfig.json",
+            &serde_json::to_string_pretty(&tsconfig).unwrap(),
+        );
+    }
+}
+
+impl FixturePackage {
+    fn new(name: &str) -> FixturePackage {
+        let unscoped_name = name.rsplit('/').next().unwrap_or(name);
+        FixturePackage {
+            name: name.to_string(),
+            dir: format!("packages/{unscoped_name}"),
+            package_json: Map::new(),
+            files: Vec::new(),
+            fence: None,
+        }
+    }
+
+    /// Moves the package to another directory, relative to the root of the repo.
+    pub fn dir(mut self, dir: &str) -> Self {
+        self.dir = dir.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the `main` field of the package.json, e.g. `./src/index.ts`.
+    pub fn main(self, main: &str) -> Self {
+        self.field("main", json!(main))
+    }
+
+    /// Sets the `exports` field of the package.json.
+    pub fn exports_map(self, exports: Value) -> Self {
+        self.field("exports", exports)
+    }
+
+    /// Sets any other field of the package.json.
+    pub fn field(mut self, key: &str, value: Value) -> Self {
+        self.package_json.insert(key.to_string(), value);
+        self
+    }
+
+    /// Adds a file, relative to the directory of the package.
+    pub fn file(mut self, path: &str, content: &str) -> Self {
+        self.files.push((path.to_string(), content.to_string()));
+        self
+    }
+
+    /// Adds a `fence.json` to the directory of the package.
+    pub fn fence(mut self, fence: Value) -> Self {
+        self.fence = Some(fence);
+        self
+    }
+}
+
+/// Runs the unused finder over a whole repo.
+///
+/// The config walks the root of the repo, and can be adjusted by
+/// `cfg_mutator`, e.g. to pick the entry packages.
+///
+/// ```
+/// use test_fixtures::{run_unused_finder, FixtureRepo};
+///
+/// let repo = FixtureRepo::new().package("entrypoint", |p| {
+///     p.main("./main.js")
+///         .file("main.js", r#"import { used } from "./used.js";"#)
+///         .file("used.js", "export const used = 1;")
+///         .file("unused.js", "export const unused = 1;")
+/// });
+///
+/// let report = run_unused_finder(&repo, |cfg| {
+///     cfg.entry_packages = vec!["entrypoint".to_string()];
+/// })
+/// .unwrap();
+/// assert_eq!(report.unused_files, [repo.path("packages/entrypoint/unused.js")]);
+/// ```
+#[cfg(feature = "unused_finder")]
+pub fn run_unused_finder(
+    repo: &FixtureRepo,
+    cfg_mutator: impl FnOnce(&mut UnusedFinderJSONConfig),
+) -> Result<UnusedFinderReport, js_err::JsErr> {
+    let root = repo.root().to_string_lossy().to_string();
+    let mut config = UnusedFinderJSONConfig {
+        repo_root: root.clone(),
+        root_paths: vec![root],
+        ..Default::default()
+    };
+    cfg_mutator(&mut config);
+    unused_finder::find_unused_items(logger::StdioLogger::new(), config)
+}
+
+/// Runs good-fences over a whole repo, using the repo's `tsconfig.json`.
+///
+/// Like the good-fences cli, paths are passed relative to the working
+/// directory. The options can be adjusted by `opts_mutator`, e.g. to write
+/// the violations to a file. Panics if good-fences fails to run, e.g.
+/// because the `tsconfig.json` cannot be read.
+///
+/// ```
+/// use good_fences::GoodFencesResultType;
+/// use serde_json::json;
+/// use test_fixtures::{run_good_fences, FixtureRepo};
+///
+/// let repo = FixtureRepo::new()
+///     .package("@org/a", |p| {
+///         p.file("src/index.ts", "export const a = 1;")
+///             .fence(json!({ "tags": ["a"] }))
+///     })
+///     .package("@org/b", |p| {
+///         p.file("src/index.ts", r#"import { a } from "../../a/src/index";"#)
+///             .fence(json!({ "imports": [] }))
+///     });
+///
+/// let results = run_good_fences(&repo, |_| {});
+/// assert_eq!(results.len(), 1);
+/// assert_eq!(results[0].result_type, GoodFencesResultType::Violation);
+/// ```
+#[cfg(feature = "good_fences")]
+pub fn run_good_fences(
+    repo: &FixtureRepo,
+    opts_mutator: impl FnOnce(&mut GoodFencesOptions),
+) -> Vec<GoodFencesResult> {
+    let root = repo.relative_path("");
+    let mut opts = GoodFencesOptions {
+        paths: vec![root.clone()],
+        project: repo.relative_path("tsconfig.json"),
+        base_url: Some(root),
+        err_output_path: None,
+        ignore_external_fences: None,
+        ignored_dirs: None,
+        allowed_unresolved: None,
+        resource_extensions: None,
+        assume_unresolved_bare_as_external: None,
+        fence_root: None,
+        html_output_path: None,
+        group_by: None,
+        layer_tag_priority: None,
+        export_model_path: None,
+        export_model_format: None,
+        output_format: None,
+    };
+    opts_mutator(&mut opts);
+    good_fences::good_fences(opts).expect("good-fences should run")
+}
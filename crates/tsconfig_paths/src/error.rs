@@ -1,7 +1,19 @@
 #[derive(Debug, thiserror::Error)]
 pub enum OpenTsConfigError {
-    #[error("Serde deserialization error: {0}")]
-    SerdeError(serde_json::Error),
-    #[error("Disk I/O Error: {0}")]
-    IOError(std::io::Error),
+    #[error("tsconfig {path} does not exist")]
+    NotFound { path: String },
+    #[error("Disk I/O Error reading tsconfig {path}: {source}")]
+    IOError {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("Error parsing tsconfig {path}: {source}")]
+    SerdeError {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[error("tsconfig {path} extends {extends:?}, which does not exist")]
+    MissingExtends { path: String, extends: String },
+    #[error("tsconfigs extend each other in a cycle: {}", .cycle.join(" -> "))]
+    CyclicExtends { cycle: Vec<String> },
 }
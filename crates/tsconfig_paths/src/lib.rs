@@ -5,4 +5,5 @@ extern crate thiserror;
 mod error;
 mod tsconfig_paths_json;
 
-pub use tsconfig_paths_json::{TsconfigPathsCompilerOptions, TsconfigPathsJson};
+pub use error::OpenTsConfigError;
+pub use tsconfig_paths_json::{TsconfigFileSet, TsconfigPathsCompilerOptions, TsconfigPathsJson};
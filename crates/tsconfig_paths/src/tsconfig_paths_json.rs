@@ -1,8 +1,8 @@
 use crate::error::OpenTsConfigError;
+use path_clean::PathClean;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::path::{Path, PathBuf};
 use std::vec::Vec;
 
 #[derive(Debug, Deserialize, PartialEq, Eq, Default, Clone)]
@@ -12,18 +12,20 @@ pub struct TsconfigPathsJson {
 }
 
 impl TsconfigPathsJson {
-    // Reads and parses the tsconfig.json at the provided path
+    // Reads and parses the tsconfig.json at the provided path, merged over
+    // the tsconfigs it `extends`
+    //
+    // A `baseUrl` inherited from an extended tsconfig is rewritten to be
+    // relative to the directory of the tsconfig at the provided path.
     pub fn from_path(tsconfig_path: &str) -> Result<Self, OpenTsConfigError> {
-        let file = match File::open(tsconfig_path) {
-            Ok(f) => f,
-            Err(err) => return Err(OpenTsConfigError::IOError(err)),
-        };
-        let buf_reader = BufReader::new(file);
-        let tsconfig_paths_json: TsconfigPathsJson = match serde_json::from_reader(buf_reader) {
-            Ok(tsconfig) => tsconfig,
-            Err(e) => return Err(OpenTsConfigError::SerdeError(e)),
-        };
-        Ok(tsconfig_paths_json)
+        let compiler_options =
+            read_tsconfig(Path::new(tsconfig_path), &mut Vec::new())?.compiler_options;
+        Ok(TsconfigPathsJson {
+            compiler_options: TsconfigPathsCompilerOptions {
+                base_url: compiler_options.base_url,
+                paths: compiler_options.paths.unwrap_or_default(),
+            },
+        })
     }
 }
 
@@ -33,3 +35,363 @@ pub struct TsconfigPathsCompilerOptions {
     pub base_url: Option<String>,
     pub paths: HashMap<String, Vec<String>>,
 }
+
+/// The files that a tsconfig.json makes part of its project, from its
+/// `files`, `include` and `exclude`, merged over the tsconfigs it `extends`.
+///
+/// Paths and patterns are relative to the directory of the tsconfig, including
+/// the ones inherited from an extended tsconfig.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct TsconfigFileSet {
+    /// Files that are always part of the project, or None if unset
+    pub files: Option<Vec<String>>,
+    /// Patterns of the files in the project, or None if unset
+    pub include: Option<Vec<String>>,
+    /// Patterns of the files that `include` does not add to the project, or
+    /// None if unset
+    pub exclude: Option<Vec<String>>,
+}
+
+impl TsconfigFileSet {
+    // Reads the file set of the tsconfig.json at the provided path, merged
+    // over the tsconfigs it `extends`
+    pub fn from_path(tsconfig_path: &str) -> Result<Self, OpenTsConfigError> {
+        let tsconfig = read_tsconfig(Path::new(tsconfig_path), &mut Vec::new())?;
+        Ok(TsconfigFileSet {
+            files: tsconfig.files,
+            include: tsconfig.include,
+            exclude: tsconfig.exclude,
+        })
+    }
+}
+
+// The parts of a single tsconfig.json that are read, before `extends` is applied
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTsconfig {
+    #[serde(default)]
+    extends: Option<RawExtends>,
+    #[serde(default)]
+    compiler_options: RawCompilerOptions,
+    #[serde(default)]
+    files: Option<Vec<String>>,
+    #[serde(default)]
+    include: Option<Vec<String>>,
+    #[serde(default)]
+    exclude: Option<Vec<String>>,
+}
+
+// `extends` is a single tsconfig, or a list of tsconfigs applied in order
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawExtends {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCompilerOptions {
+    base_url: Option<String>,
+    paths: Option<HashMap<String, Vec<String>>>,
+}
+
+impl RawCompilerOptions {
+    // options set in self take precedence over the ones in base
+    fn merged_over(self, base: RawCompilerOptions) -> RawCompilerOptions {
+        RawCompilerOptions {
+            base_url: self.base_url.or(base.base_url),
+            paths: self.paths.or(base.paths),
+        }
+    }
+}
+
+// The parts of a tsconfig that are read, merged over the ones it extends
+#[derive(Debug, Default)]
+struct MergedTsconfig {
+    compiler_options: RawCompilerOptions,
+    files: Option<Vec<String>>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+}
+
+impl MergedTsconfig {
+    // Rewrites the paths of a tsconfig in `dir` to be relative to the
+    // directory of the tsconfig that extends it
+    fn rebased(self, dir: &Path) -> MergedTsconfig {
+        let rebase = |path: String| dir.join(path).clean().to_string_lossy().to_string();
+        let rebase_all = |paths: Option<Vec<String>>| {
+            paths.map(|paths| paths.into_iter().map(rebase).collect::<Vec<_>>())
+        };
+        MergedTsconfig {
+            compiler_options: RawCompilerOptions {
+                base_url: self.compiler_options.base_url.map(rebase),
+                paths: self.compiler_options.paths,
+            },
+            files: rebase_all(self.files),
+            include: rebase_all(self.include),
+            exclude: rebase_all(self.exclude),
+        }
+    }
+
+    // fields set in self take precedence over the ones in base
+    fn merged_over(self, base: MergedTsconfig) -> MergedTsconfig {
+        MergedTsconfig {
+            compiler_options: self.compiler_options.merged_over(base.compiler_options),
+            files: self.files.or(base.files),
+            include: self.include.or(base.include),
+            exclude: self.exclude.or(base.exclude),
+        }
+    }
+}
+
+// Reads a tsconfig, merged over the ones it extends.
+//
+// `chain` holds the canonical paths of the tsconfigs being read, from the
+// root tsconfig down to the one that extends this one, to detect cycles.
+fn read_tsconfig(
+    tsconfig_path: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<MergedTsconfig, OpenTsConfigError> {
+    let path_str = tsconfig_path.display().to_string();
+    let contents = std::fs::read_to_string(tsconfig_path).map_err(|err| match err.kind() {
+        std::io::ErrorKind::NotFound => OpenTsConfigError::NotFound {
+            path: path_str.clone(),
+        },
+        _ => OpenTsConfigError::IOError {
+            path: path_str.clone(),
+            source: err,
+        },
+    })?;
+    let canonical_path = tsconfig_path
+        .canonicalize()
+        .unwrap_or_else(|_| tsconfig_path.to_path_buf());
+    if let Some(cycle_start) = chain.iter().position(|path| *path == canonical_path) {
+        return Err(OpenTsConfigError::CyclicExtends {
+            cycle: chain[cycle_start..]
+                .iter()
+                .chain(std::iter::once(&canonical_path))
+                .map(|path| path.display().to_string())
+                .collect(),
+        });
+    }
+
+    let tsconfig: RawTsconfig =
+        serde_json::from_str(&contents).map_err(|err| OpenTsConfigError::SerdeError {
+            path: path_str.clone(),
+            source: err,
+        })?;
+    let extends = match tsconfig.extends {
+        None => Vec::new(),
+        Some(RawExtends::One(extends)) => vec![extends],
+        Some(RawExtends::Many(extends)) => extends,
+    };
+
+    let tsconfig_dir = tsconfig_path.parent().unwrap_or(Path::new(""));
+    let mut inherited = MergedTsconfig::default();
+    chain.push(canonical_path);
+    for extends in extends {
+        let extended_path = match find_extended_tsconfig(tsconfig_dir, &extends) {
+            Some(extended_path) => extended_path,
+            None => {
+                return Err(OpenTsConfigError::MissingExtends {
+                    path: path_str,
+                    extends,
+                })
+            }
+        };
+        // the paths of a tsconfig are relative to its own directory
+        let extended = read_tsconfig(&tsconfig_dir.join(&extended_path), chain)?
+            .rebased(extended_path.parent().unwrap_or(Path::new("")));
+        inherited = extended.merged_over(inherited);
+    }
+    chain.pop();
+
+    let tsconfig = MergedTsconfig {
+        compiler_options: tsconfig.compiler_options,
+        files: tsconfig.files,
+        include: tsconfig.include,
+        exclude: tsconfig.exclude,
+    };
+    Ok(tsconfig.merged_over(inherited))
+}
+
+// Finds the tsconfig named by an `extends`, as a path relative to the
+// directory of the extending tsconfig (or an absolute path).
+//
+// Relative and absolute paths are used as-is, or with a `.json` extension.
+// Other specifiers name a tsconfig in a package in `node_modules`.
+fn find_extended_tsconfig(tsconfig_dir: &Path, extends: &str) -> Option<PathBuf> {
+    let candidates = |path: PathBuf| {
+        [
+            path.clone(),
+            PathBuf::from(format!("{}.json", path.display())),
+            path.join("tsconfig.json"),
+        ]
+    };
+    let is_path = extends.starts_with("./")
+        || extends.starts_with("../")
+        || extends == "."
+        || extends == ".."
+        || Path::new(extends).is_absolute();
+    if is_path {
+        return candidates(PathBuf::from(extends))
+            .into_iter()
+            .find(|candidate| tsconfig_dir.join(candidate).is_file());
+    }
+
+    // look for the package in the node_modules of each ancestor directory
+    let canonical_dir = tsconfig_dir
+        .canonicalize()
+        .unwrap_or_else(|_| tsconfig_dir.to_path_buf());
+    canonical_dir
+        .ancestors()
+        .enumerate()
+        .find_map(|(depth, _)| {
+            let package_path = std::iter::repeat("..")
+                .take(depth)
+                .collect::<PathBuf>()
+                .join("node_modules")
+                .join(extends);
+            candidates(package_path)
+                .into_iter()
+                .find(|candidate| canonical_dir.join(candidate).is_file())
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TsconfigFileSet, TsconfigPathsJson};
+    use crate::error::OpenTsConfigError;
+    use std::collections::HashMap;
+    use test_tmpdir::test_tmpdir;
+
+    fn from_path(
+        tmpdir: &test_tmpdir::TmpDir,
+        path: &str,
+    ) -> Result<TsconfigPathsJson, OpenTsConfigError> {
+        TsconfigPathsJson::from_path(tmpdir.root_join(path).to_str().unwrap())
+    }
+
+    #[test]
+    fn test_extends() {
+        let tmpdir = test_tmpdir!(
+            "tsconfig.base.json" => r#"{
+                "compilerOptions": {
+                    "baseUrl": "./src",
+                    "paths": { "@org/*": ["packages/*"] }
+                }
+            }"#,
+            "node_modules/@org/tsconfig/tsconfig.json" => r#"{
+                "compilerOptions": { "paths": { "shadowed": ["shadowed"] } }
+            }"#,
+            "packages/app/tsconfig.json" => r#"{
+                "extends": ["@org/tsconfig", "../../tsconfig.base"],
+                "compilerOptions": { "strict": true }
+            }"#
+        );
+
+        let tsconfig = from_path(&tmpdir, "packages/app/tsconfig.json").unwrap();
+        assert_eq!(
+            tsconfig.compiler_options.base_url.as_deref(),
+            Some("../../src")
+        );
+        assert_eq!(
+            tsconfig.compiler_options.paths,
+            HashMap::from([("@org/*".to_string(), vec!["packages/*".to_string()])])
+        );
+    }
+
+    #[test]
+    fn test_file_set_extends() {
+        let tmpdir = test_tmpdir!(
+            "tsconfig.base.json" => r#"{
+                "include": ["src"],
+                "exclude": ["src/**/*.stories.ts"]
+            }"#,
+            "packages/app/tsconfig.json" => r#"{
+                "extends": "../../tsconfig.base.json",
+                "exclude": ["legacy"]
+            }"#
+        );
+
+        let file_set = TsconfigFileSet::from_path(
+            tmpdir
+                .root_join("packages/app/tsconfig.json")
+                .to_str()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            file_set,
+            TsconfigFileSet {
+                files: None,
+                include: Some(vec!["../../src".to_string()]),
+                exclude: Some(vec!["legacy".to_string()]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_tsconfig() {
+        let tmpdir = test_tmpdir!("unrelated.json" => "{}");
+
+        let err = from_path(&tmpdir, "tsconfig.json").unwrap_err();
+        assert!(matches!(err, OpenTsConfigError::NotFound { .. }), "{err:?}");
+        assert!(err.to_string().ends_with("tsconfig.json does not exist"));
+    }
+
+    #[test]
+    fn test_unparseable_tsconfig() {
+        let tmpdir = test_tmpdir!("tsconfig.json" => r#"{ "compilerOptions": { "paths": [] } }"#);
+
+        let err = from_path(&tmpdir, "tsconfig.json").unwrap_err();
+        assert!(
+            matches!(err, OpenTsConfigError::SerdeError { .. }),
+            "{err:?}"
+        );
+        assert!(err.to_string().starts_with("Error parsing tsconfig"));
+    }
+
+    #[test]
+    fn test_missing_extends() {
+        let tmpdir = test_tmpdir!(
+            "tsconfig.json" => r#"{ "extends": "./tsconfig.missing.json" }"#
+        );
+
+        let err = from_path(&tmpdir, "tsconfig.json").unwrap_err();
+        match &err {
+            OpenTsConfigError::MissingExtends { extends, .. } => {
+                assert_eq!(extends, "./tsconfig.missing.json")
+            }
+            _ => panic!("expected a missing extends error, got {err:?}"),
+        }
+        assert!(err
+            .to_string()
+            .ends_with(r#"extends "./tsconfig.missing.json", which does not exist"#));
+    }
+
+    #[test]
+    fn test_cyclic_extends() {
+        let tmpdir = test_tmpdir!(
+            "tsconfig.json" => r#"{ "extends": "./tsconfig.a.json" }"#,
+            "tsconfig.a.json" => r#"{ "extends": "./tsconfig.b" }"#,
+            "tsconfig.b.json" => r#"{ "extends": "./tsconfig.a.json" }"#
+        );
+
+        let err = from_path(&tmpdir, "tsconfig.json").unwrap_err();
+        match &err {
+            OpenTsConfigError::CyclicExtends { cycle } => {
+                let cycle: Vec<&str> = cycle
+                    .iter()
+                    .map(|path| path.rsplit(['/', '\\']).next().unwrap())
+                    .collect();
+                assert_eq!(
+                    cycle,
+                    vec!["tsconfig.a.json", "tsconfig.b.json", "tsconfig.a.json"]
+                );
+            }
+            _ => panic!("expected a cyclic extends error, got {err:?}"),
+        }
+    }
+}
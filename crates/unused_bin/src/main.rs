@@ -1,7 +1,7 @@
 extern crate unused_finder;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use logger::{Logger, StdioLogger};
 use std::{convert::TryInto, env, fs, io::Write, path::Path};
 use unused_finder::UnusedFinderConfig;
@@ -22,17 +22,78 @@ struct CliArgs {
     // If this flag is set, run the parking_lot deadlock detector
     #[arg(short = 'D', long, default_value_t = true)]
     deadlock_detector: std::primitive::bool,
+    // If this flag is set, print the effective configuration and exit
+    #[arg(long, default_value_t = false)]
+    explain_config: std::primitive::bool,
+    // Overrides a field of the config file, e.g. --set reportExportedSymbols=true.
+    // Values are parsed as JSON, falling back to a plain string
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    config_overrides: Vec<String>,
+    // If this flag is set, print the files exported by each entry package,
+    // and the entry packages that export no files, and exit
+    #[arg(long, default_value_t = false)]
+    explain_entrypoints: std::primitive::bool,
+    // If this flag is set, print the unused files and symbols as GitHub Actions
+    // workflow commands, which annotate them in pull requests
+    #[arg(long, default_value_t = false)]
+    github: std::primitive::bool,
+    // If set, fail when more than this many imports resolve to files outside
+    // of the root paths, which usually means the rootPaths config is incomplete
+    #[arg(long, default_value = None)]
+    max_resolution_gaps: Option<usize>,
+    // If set, write the files reachable from the entrypoints to this path, as
+    // a JSON array if it ends with .json, or one path per line otherwise
+    #[arg(long, default_value = None)]
+    used_manifest: Option<String>,
 
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum GraphCollapse {
+    /// One node per package, with files outside of packages as "(unrooted)"
+    Packages,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Generates a dot graph of the dependency graph
     Graph {
         #[arg(short = 'f', alias = "filter")]
         filter: Option<String>,
+        /// Collapse the nodes of the graph, e.g. into packages for a
+        /// high-level view. The filter then matches the collapsed nodes
+        #[arg(long, value_enum)]
+        collapse: Option<GraphCollapse>,
+    },
+    /// Lists every import of an exported symbol, including through barrel files
+    Usages {
+        /// The file exporting the symbol
+        file: String,
+        /// The exported name of the symbol, "default" for the default export, or
+        /// "*" for the namespace. Names equal to those are escaped as "\default"
+        /// and "\*"
+        symbol: String,
+    },
+    /// Prints everything known about how a file was analyzed, as JSON
+    Inspect {
+        /// The file to inspect
+        file: String,
+    },
+    /// Fails if the unused files and symbols differ from a committed snapshot
+    /// of the report, printing only the differences
+    VerifySnapshot {
+        /// The snapshot, as written by --update
+        #[arg(long)]
+        snapshot: String,
+        /// Rewrite the snapshot with the current report instead of comparing
+        #[arg(long, default_value_t = false)]
+        update: bool,
+        /// Also report unused symbols whose spans changed, e.g. because they
+        /// moved within their file
+        #[arg(long, default_value_t = false)]
+        strict_spans: bool,
     },
 }
 
@@ -115,6 +176,8 @@ fn main() -> Result<()> {
     let logger = &StdioLogger::new();
 
     let args = CliArgs::parse();
+    // paths given on the command line are relative to where the command was run
+    let invocation_dir = env::current_dir().context("getting the current directory")?;
     #[cfg(feature = "rstack")]
     if args.rstack {
         let _ = rstack_self::child();
@@ -137,7 +200,10 @@ fn main() -> Result<()> {
     // read and parse the config file
     let config_str = fs::read_to_string(&config_path)
         .with_context(|| format!("reading config file {}", &config_path))?;
-    let config: unused_finder::UnusedFinderJSONConfig = serde_hjson::from_str(&config_str)
+    let mut config: serde_json::Value = serde_hjson::from_str(&config_str)
+        .with_context(|| format!("Parsing unused-finder config {config_path}"))?;
+    unused_finder::apply_config_overrides(&mut config, &args.config_overrides)?;
+    let config: unused_finder::UnusedFinderJSONConfig = serde_json::from_value(config)
         .with_context(|| format!("Parsing unused-finder config {config_path}"))?;
     let mut parsed_config: UnusedFinderConfig = config.try_into()?;
     // HACK: if the repo_root is not an absolute path, make it relative to the config file
@@ -150,6 +216,14 @@ fn main() -> Result<()> {
             .to_string();
     }
 
+    if args.explain_config {
+        let effective_config = parsed_config
+            .effective_config()
+            .with_overrides(&args.config_overrides);
+        println!("{}", effective_config.to_pretty_json());
+        return Ok(());
+    }
+
     // move the the working directory of the config path
     let config_dir = Path::new(&config_path)
         .parent()
@@ -161,22 +235,127 @@ fn main() -> Result<()> {
         .expect("Failed to change working directory to config file directory");
 
     let mut unused_finder = unused_finder::UnusedFinder::new_from_cfg(logger, parsed_config)?;
-    let result: unused_finder::UnusedFinderResult = unused_finder.find_unused(logger)?;
+    if args.explain_entrypoints {
+        let dump = unused_finder.explain_entrypoints(logger)?;
+        println!("{}", dump.to_pretty_json());
+        return Ok(());
+    }
+    let result = unused_finder.find_unused(logger)?;
     let report = result.get_report();
     logger.log(format!("result:\n{report}"));
+    if args.github {
+        let repo_root = Path::new(&unused_finder.config().repo_root);
+        for command in report.github_workflow_commands(repo_root) {
+            println!("{command}");
+        }
+    }
+
+    if let Some(used_manifest) = &args.used_manifest {
+        let manifest_path = invocation_dir.join(used_manifest);
+        let json = manifest_path
+            .extension()
+            .is_some_and(|extension| extension == "json");
+        fs::write(&manifest_path, result.used_files_manifest(json))
+            .with_context(|| format!("writing used manifest {}", manifest_path.display()))?;
+        logger.log(format!("wrote used manifest {}", manifest_path.display()));
+    }
 
     match &args.command {
-        Some(Commands::Graph { filter }) => {
+        Some(Commands::Graph { filter, collapse }) => {
             println!("Generating graph.dot file...");
             let file = std::fs::File::create("graph.dot").expect("Failed to create graph.dot");
             let mut stream = std::io::BufWriter::new(file);
-            result.write_dot_graph(logger, filter.as_ref().map(|x| x.as_str()), &mut stream)?;
+            let filter = filter.as_ref().map(|x| x.as_str());
+            match collapse {
+                Some(GraphCollapse::Packages) => {
+                    result.write_package_dot_graph(logger, filter, &mut stream)?
+                }
+                None => result.write_dot_graph(logger, filter, &mut stream)?,
+            }
             stream.flush().expect("Failed to flush graph.dot");
             println!("Done!");
         }
+        Some(Commands::Usages { file, symbol }) => {
+            let file_path = invocation_dir.join(file);
+            let import_sites = result.find_import_sites(
+                &file_path,
+                &unused_finder::ExportedSymbol::from(symbol.as_str()),
+            );
+            println!(
+                "{} import sites of {symbol} in {}:",
+                import_sites.len(),
+                file_path.display()
+            );
+            for import_site in import_sites {
+                print!(
+                    "  {}:{}-{}",
+                    import_site.file.display(),
+                    import_site.span.lo.0,
+                    import_site.span.hi.0
+                );
+                if import_site.namespace {
+                    print!(" (namespace)");
+                }
+                if !import_site.via.is_empty() {
+                    let via: Vec<String> = import_site
+                        .via
+                        .iter()
+                        .map(|barrel| barrel.display().to_string())
+                        .collect();
+                    print!(" via {}", via.join(" -> "));
+                }
+                println!();
+            }
+        }
+        Some(Commands::Inspect { file }) => {
+            let dump = unused_finder.dump_file_analysis(logger, invocation_dir.join(file))?;
+            println!("{}", dump.to_pretty_json());
+        }
+        Some(Commands::VerifySnapshot {
+            snapshot,
+            update,
+            strict_spans,
+        }) => {
+            let snapshot_path = invocation_dir.join(snapshot);
+            let current = result
+                .get_report_with_path_display(unused_finder::PathDisplay::RepoRelative)
+                .to_snapshot();
+            if *update {
+                fs::write(&snapshot_path, current.to_snapshot_json())
+                    .with_context(|| format!("writing snapshot {}", snapshot_path.display()))?;
+                println!("Updated snapshot {}", snapshot_path.display());
+            } else {
+                let snapshot_str = fs::read_to_string(&snapshot_path)
+                    .with_context(|| format!("reading snapshot {}", snapshot_path.display()))?;
+                let baseline = unused_finder::UnusedFinderReport::from_snapshot_json(&snapshot_str)
+                    .with_context(|| format!("parsing snapshot {}", snapshot_path.display()))?;
+                let diff = current.diff(
+                    &baseline,
+                    unused_finder::ReportDiffOptions {
+                        strict_spans: *strict_spans,
+                    },
+                );
+                if !diff.is_empty() {
+                    print!("{diff}");
+                    anyhow::bail!(
+                        "the report differs from the snapshot {}, \
+                        rerun with --update to accept the changes",
+                        snapshot_path.display()
+                    );
+                }
+            }
+        }
         None => {}
     }
 
+    if let Some(max_resolution_gaps) = args.max_resolution_gaps {
+        logger.log(format!(
+            "{} imports resolve outside of the root paths",
+            result.resolution_gap_count()
+        ));
+        result.check_resolution_gaps(max_resolution_gaps)?;
+    }
+
     logger.log("done!");
     Ok(())
 }
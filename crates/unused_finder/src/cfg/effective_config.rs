@@ -0,0 +1,367 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::path_mirrors::PathMirror;
+use super::{ResolverTargetEnv, UnusedFinderConfig};
+use crate::report::{AmbiguousSymbolNames, PathDisplay};
+use crate::walk::PackageDiscovery;
+
+/// Where the value of a configuration flag came from.
+///
+/// Flags that are set to their default value in the config file are reported
+/// as coming from the default, since explicitly setting a flag to its default
+/// has no effect. Flags set by an override always report the override.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSource {
+    Config,
+    Default,
+    // a `key=value` override, e.g. `--set` on the command line
+    Override,
+}
+
+/// A boolean flag, alongside the source of its value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectiveFlag {
+    pub value: bool,
+    pub source: ValueSource,
+}
+
+impl EffectiveFlag {
+    fn new(value: bool, default: bool) -> Self {
+        Self {
+            value,
+            source: if value == default {
+                ValueSource::Default
+            } else {
+                ValueSource::Config
+            },
+        }
+    }
+}
+
+/// How a pattern from the config is matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PatternInterpretation {
+    /// Matched literally against package names
+    Literal,
+    /// Matched as a glob against package or directory names
+    NameGlob,
+    /// Matched as a glob against paths, relative to the repo root
+    PathGlob,
+}
+
+/// A pattern from the config, alongside how it was interpreted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectivePattern {
+    pub pattern: String,
+    pub interpretation: PatternInterpretation,
+}
+
+impl EffectivePattern {
+    fn new(pattern: impl Into<String>, interpretation: PatternInterpretation) -> Self {
+        Self {
+            pattern: pattern.into(),
+            interpretation,
+        }
+    }
+}
+
+/// The fully-resolved configuration that an UnusedFinder will run with.
+///
+/// This is intended for debugging configuration issues, and is serialized
+/// deterministically so it can be compared between runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+    pub repo_root: String,
+    pub root_paths: Vec<String>,
+    pub skip: Vec<EffectivePattern>,
+    pub entry_packages: Vec<EffectivePattern>,
+    pub entry_files: Vec<String>,
+    pub test_files: Vec<EffectivePattern>,
+    pub report_exported_symbols: EffectiveFlag,
+    pub allow_unused_types: EffectiveFlag,
+    pub barrel_exports_count_as_used: EffectiveFlag,
+    pub report_unused_entry_exports: EffectiveFlag,
+    pub infer_test_dirs_from_package_json: EffectiveFlag,
+    pub use_default_test_dirs: EffectiveFlag,
+    pub verify_test_files: EffectiveFlag,
+    pub suggest_same_name_exports: EffectiveFlag,
+    pub report_ambiguous_symbol_names: AmbiguousSymbolNames,
+    pub count_import_kinds: EffectiveFlag,
+    pub report_barrel_bypass_imports: EffectiveFlag,
+    pub report_unused_imports: EffectiveFlag,
+    pub effect_imports_mark_symbols: EffectiveFlag,
+    pub fail_on_parse_error: EffectiveFlag,
+    pub restrict_to_tsconfig_files: EffectiveFlag,
+    pub strict_entry_packages: EffectiveFlag,
+    pub respect_fences: EffectiveFlag,
+    pub god_module_threshold: Option<usize>,
+    pub opaque_module_extensions: Vec<String>,
+    pub path_display: PathDisplay,
+    pub keep_alive_decorators: Vec<String>,
+    pub keep_pragmas: Vec<String>,
+    pub package_discovery: PackageDiscovery,
+    pub path_mirrors: Vec<PathMirror>,
+    pub target_env: ResolverTargetEnv,
+}
+
+impl From<&UnusedFinderConfig> for EffectiveConfig {
+    fn from(config: &UnusedFinderConfig) -> Self {
+        let repo_root = Path::new(&config.repo_root);
+        // fall back to the configured path if the repo root can't be canonicalized
+        // (e.g. if it does not exist yet)
+        let canonical_root = repo_root
+            .canonicalize()
+            .unwrap_or_else(|_| repo_root.to_path_buf());
+
+        let root_paths = config
+            .root_paths
+            .iter()
+            .map(
+                |root_path| match abspath::join_abspath(&canonical_root, root_path) {
+                    Ok(abs_path) => abs_path.to_string_lossy().to_string(),
+                    Err(_) => root_path.clone(),
+                },
+            )
+            .collect();
+
+        // literal names are stored in a hashset, so sort them for a stable output
+        let mut literal_names = config
+            .entry_packages
+            .names
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        literal_names.sort();
+        let entry_packages = literal_names
+            .into_iter()
+            .map(|name| EffectivePattern::new(name, PatternInterpretation::Literal))
+            .chain(config.entry_packages.name_patterns.iter().map(|pattern| {
+                EffectivePattern::new(pattern.as_str(), PatternInterpretation::NameGlob)
+            }))
+            .chain(config.entry_packages.path_patterns.iter().map(|pattern| {
+                EffectivePattern::new(
+                    format!("./{}", pattern.as_str()),
+                    PatternInterpretation::PathGlob,
+                )
+            }))
+            .collect();
+
+        let defaults = UnusedFinderConfig::default();
+        EffectiveConfig {
+            repo_root: canonical_root.to_string_lossy().to_string(),
+            root_paths,
+            skip: config
+                .skip
+                .iter()
+                .map(|pattern| EffectivePattern::new(pattern, PatternInterpretation::NameGlob))
+                .collect(),
+            entry_packages,
+            entry_files: config.entry_files.clone(),
+            test_files: config
+                .test_files
+                .iter()
+                .map(|pattern| {
+                    EffectivePattern::new(pattern.as_str(), PatternInterpretation::PathGlob)
+                })
+                .collect(),
+            report_exported_symbols: EffectiveFlag::new(
+                config.report_exported_symbols,
+                defaults.report_exported_symbols,
+            ),
+            allow_unused_types: EffectiveFlag::new(
+                config.allow_unused_types,
+                defaults.allow_unused_types,
+            ),
+            barrel_exports_count_as_used: EffectiveFlag::new(
+                config.barrel_exports_count_as_used,
+                defaults.barrel_exports_count_as_used,
+            ),
+            report_unused_entry_exports: EffectiveFlag::new(
+                config.report_unused_entry_exports,
+                defaults.report_unused_entry_exports,
+            ),
+            infer_test_dirs_from_package_json: EffectiveFlag::new(
+                config.infer_test_dirs_from_package_json,
+                defaults.infer_test_dirs_from_package_json,
+            ),
+            use_default_test_dirs: EffectiveFlag::new(
+                config.use_default_test_dirs,
+                defaults.use_default_test_dirs,
+            ),
+            verify_test_files: EffectiveFlag::new(
+                config.verify_test_files,
+                defaults.verify_test_files,
+            ),
+            suggest_same_name_exports: EffectiveFlag::new(
+                config.suggest_same_name_exports,
+                defaults.suggest_same_name_exports,
+            ),
+            report_ambiguous_symbol_names: config.report_ambiguous_symbol_names,
+            count_import_kinds: EffectiveFlag::new(
+                config.count_import_kinds,
+                defaults.count_import_kinds,
+            ),
+            report_barrel_bypass_imports: EffectiveFlag::new(
+                config.report_barrel_bypass_imports,
+                defaults.report_barrel_bypass_imports,
+            ),
+            report_unused_imports: EffectiveFlag::new(
+                config.report_unused_imports,
+                defaults.report_unused_imports,
+            ),
+            effect_imports_mark_symbols: EffectiveFlag::new(
+                config.effect_imports_mark_symbols,
+                defaults.effect_imports_mark_symbols,
+            ),
+            fail_on_parse_error: EffectiveFlag::new(
+                config.fail_on_parse_error,
+                defaults.fail_on_parse_error,
+            ),
+            restrict_to_tsconfig_files: EffectiveFlag::new(
+                config.restrict_to_tsconfig_files,
+                defaults.restrict_to_tsconfig_files,
+            ),
+            strict_entry_packages: EffectiveFlag::new(
+                config.strict_entry_packages,
+                defaults.strict_entry_packages,
+            ),
+            respect_fences: EffectiveFlag::new(config.respect_fences, defaults.respect_fences),
+            god_module_threshold: config.god_module_threshold,
+            opaque_module_extensions: config.opaque_module_extensions.clone(),
+            path_display: config.path_display,
+            keep_alive_decorators: config.keep_alive_decorators.clone(),
+            keep_pragmas: config.keep_pragmas.clone(),
+            package_discovery: config.package_discovery,
+            path_mirrors: config.path_mirrors.mirrors.clone(),
+            target_env: config.target_env,
+        }
+    }
+}
+
+impl EffectiveConfig {
+    /// Reports the flags set by `key=value` overrides (see
+    /// [crate::apply_config_overrides]) as coming from the override.
+    pub fn with_overrides(mut self, overrides: &[String]) -> Self {
+        for config_override in overrides {
+            let key = match config_override.split_once('=') {
+                Some((key, _)) => key,
+                None => config_override,
+            };
+            if let Some(flag) = self.flag_mut(key) {
+                flag.source = ValueSource::Override;
+            }
+        }
+        self
+    }
+
+    /// Prints the effective configuration as pretty-printed JSON
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("EffectiveConfig should always be serializable")
+    }
+
+    // the flag named by a field of the JSON config, if that field is a flag
+    fn flag_mut(&mut self, key: &str) -> Option<&mut EffectiveFlag> {
+        match key {
+            "reportExportedSymbols" => Some(&mut self.report_exported_symbols),
+            "allowUnusedTypes" => Some(&mut self.allow_unused_types),
+            "barrelExportsCountAsUsed" => Some(&mut self.barrel_exports_count_as_used),
+            "reportUnusedEntryExports" => Some(&mut self.report_unused_entry_exports),
+            "inferTestDirsFromPackageJson" => Some(&mut self.infer_test_dirs_from_package_json),
+            "useDefaultTestDirs" => Some(&mut self.use_default_test_dirs),
+            "verifyTestFiles" => Some(&mut self.verify_test_files),
+            "suggestSameNameExports" => Some(&mut self.suggest_same_name_exports),
+            "countImportKinds" => Some(&mut self.count_import_kinds),
+            "reportBarrelBypassImports" => Some(&mut self.report_barrel_bypass_imports),
+            "reportUnusedImports" => Some(&mut self.report_unused_imports),
+            "effectImportsMarkSymbols" => Some(&mut self.effect_imports_mark_symbols),
+            "failOnParseError" => Some(&mut self.fail_on_parse_error),
+            "restrictToTsconfigFiles" => Some(&mut self.restrict_to_tsconfig_files),
+            "strictEntryPackages" => Some(&mut self.strict_entry_packages),
+            "respectFences" => Some(&mut self.respect_fences),
+            _ => None,
+        }
+    }
+}
+
+impl UnusedFinderConfig {
+    /// Gets the fully-resolved configuration, after all defaults and
+    /// post-processing have been applied.
+    pub fn effective_config(&self) -> EffectiveConfig {
+        EffectiveConfig::from(self)
+    }
+
+    /// Prints the effective configuration as pretty-printed JSON
+    pub fn print_effective_config(&self) -> String {
+        self.effective_config().to_pretty_json()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{apply_config_overrides, UnusedFinderJSONConfig};
+
+    fn config_from_json(json_config: &str) -> UnusedFinderConfig {
+        let config: UnusedFinderJSONConfig = serde_json::from_str(json_config).unwrap();
+        UnusedFinderConfig::try_from(config).unwrap()
+    }
+
+    #[test]
+    fn test_effective_config_golden() {
+        // overrides are reported even when they set a flag to its default
+        let overrides = [
+            "reportExportedSymbols=true".to_string(),
+            "countImportKinds=false".to_string(),
+        ];
+        let mut json_config = serde_json::json!({
+            "repoRoot": "/path/to/nonexistent/repo",
+            "rootPaths": ["packages", "/abs/shared"],
+            "skip": ["generated", "*.snap"],
+            "allowUnusedTypes": true,
+            "entryPackages": [
+                "./apps/**",
+                "my-pkg-b",
+                "@scope/*",
+                "my-pkg-a"
+            ],
+            "testFiles": ["**/*.test.ts"]
+        });
+        apply_config_overrides(&mut json_config, &overrides).unwrap();
+        let config: UnusedFinderJSONConfig = serde_json::from_value(json_config).unwrap();
+        let config = UnusedFinderConfig::try_from(config).unwrap();
+
+        let expected = include_str!("../../tests/effective_config/effective-config.golden.json");
+        assert_eq!(
+            expected.trim_end(),
+            config
+                .effective_config()
+                .with_overrides(&overrides)
+                .to_pretty_json()
+        );
+    }
+
+    #[test]
+    fn test_effective_config_entry_package_interpretation() {
+        let config = config_from_json(
+            r#"{
+                "repoRoot": "/path/to/repo",
+                "rootPaths": ["."],
+                "entryPackages": ["my-pkg", "my-pkg-*", "./shared/**"]
+            }"#,
+        );
+
+        assert_eq!(
+            config.effective_config().entry_packages,
+            vec![
+                EffectivePattern::new("my-pkg", PatternInterpretation::Literal),
+                EffectivePattern::new("my-pkg-*", PatternInterpretation::NameGlob),
+                EffectivePattern::new("./shared/**", PatternInterpretation::PathGlob),
+            ]
+        );
+    }
+}
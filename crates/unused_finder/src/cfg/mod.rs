@@ -2,11 +2,19 @@ use std::fmt::{Display, Formatter};
 
 use itertools::Itertools;
 use package_match_rules::PackageMatchRules;
+use path_mirrors::{PathMirror, PathMirrorRules};
+use path_utils::{Glob, GlobError};
 use rayon::iter::Either;
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+use crate::report::{AmbiguousSymbolNames, PathDisplay};
+use crate::walk::PackageDiscovery;
+
+pub mod effective_config;
 pub mod package_match_rules;
+pub mod path_mirrors;
+pub mod root_paths;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct ErrList<E>(Vec<E>);
@@ -35,8 +43,8 @@ impl Display for GlobInterp {
     }
 }
 
-#[derive(Debug)]
-pub struct PatErr(usize, GlobInterp, glob::PatternError);
+#[derive(Debug, PartialEq)]
+pub struct PatErr(usize, GlobInterp, GlobError);
 
 impl Display for PatErr {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -44,19 +52,30 @@ impl Display for PatErr {
     }
 }
 
-impl PartialEq for PatErr {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
-            && self.1 == other.1
-            && self.2.pos == other.2.pos
-            && self.2.msg == other.2.msg
-    }
-}
-
 #[derive(Debug, PartialEq, thiserror::Error)]
 pub enum ConfigError {
     #[error("Error parsing package match rules: {0}")]
     InvalidGlobPatterns(ErrList<PatErr>),
+    #[error("Invalid path mirror at idx {0}: {1}")]
+    InvalidPathMirror(usize, String),
+    #[error("Root path glob {0:?} did not match any directories")]
+    EmptyRootPathGlob(String),
+    #[error("Invalid root path glob {0:?}: {1}")]
+    InvalidRootPathGlob(String, String),
+    #[error("Invalid config override {0:?}: {1}")]
+    InvalidOverride(String, String),
+}
+
+/// The environment imports are resolved for, which decides the conditions
+/// used to pick targets from the `exports` of packages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResolverTargetEnv {
+    /// Resolve for browsers, following the `browser` field of packages
+    #[default]
+    Browser,
+    /// Resolve for node, also matching the `node` export condition
+    Node,
 }
 
 /// A JSON serializable proxy for the UnusedFinderConfig struct
@@ -72,12 +91,22 @@ pub struct UnusedFinderJSONConfig {
     /// Root paths to walk as source files
     ///
     /// These can be either absolute paths, or paths relative to the repo root
+    ///
+    /// Paths relative to the repo root may be glob patterns (e.g. `packages/*/src`),
+    /// which are expanded into the directories they match before the walk.
+    /// Patterns starting with `!` remove the paths they match from the
+    /// previously listed root paths (e.g. `!packages/legacy-*/src`).
+    /// Since they are expanded against the file system, root path globs do
+    /// not support `{a,b}` alternation.
     pub root_paths: Vec<String>,
     /// A List of globs.
     /// Matching files and directories won't be scanned during the file walk
     ///
     /// Matches are made against the names of the individual directories,
     /// NOT the full directory paths
+    ///
+    /// Globs support `*`, `?`, character classes and `{a,b}` alternation
+    /// (e.g. `{dist,build}`).
     #[serde(default)]
     pub skip: Vec<String>,
     /// If true, individual exported symbols are also tracked
@@ -88,6 +117,20 @@ pub struct UnusedFinderJSONConfig {
     /// reported as unused.
     #[serde(default)]
     pub allow_unused_types: bool,
+    /// If true, symbols re-exported from a package's public barrel (the file
+    /// exported as "." or as the package's main / module entry) are considered
+    /// used, even if nothing imports them from the barrel.
+    #[serde(default)]
+    pub barrel_exports_count_as_used: bool,
+    /// If true, unused exports are reported in the files of entry packages
+    /// that are only exported because their package has no "exports" field.
+    ///
+    /// Only the exports of files exported as the package's main / module entry
+    /// or through its "exports" field are then considered used. The other
+    /// files of the package are still used, but their exports must be
+    /// imported to be used.
+    #[serde(default)]
+    pub report_unused_entry_exports: bool,
     /// List of packages that should be considered "entry" packages
     /// All transitive imports from the exposed exports of these packages
     /// will be considered used
@@ -99,23 +142,196 @@ pub struct UnusedFinderJSONConfig {
     /// Items are parsed in one of three ways:
     /// 1. If the item starts with "./", it is treated as a path glob, and evaluated
     ///    against the paths of package folders, relative to the repo root.
-    /// 2. If the item contains any of "~)('!*", it is treated as a name-glob, and evaluated
+    /// 2. If the item contains any of "~)('!*?[{", it is treated as a name-glob, and evaluated
     ///    as a glob against the names of packages.
     /// 3. Otherwise, the item is treated as the name of an individual package, and matched
     ///    literally.
+    ///
+    /// Globs support `*`, `**`, `?`, character classes and `{a,b}` alternation
+    /// (e.g. `@scope/{app,web}-*`), with the same syntax as `testFiles`.
+    ///
+    /// In repos without any package.json, the special value "<root>" marks the
+    /// `index.ts` and `main.ts` files directly under each root path as entrypoints.
     pub entry_packages: Vec<String>,
+    /// Files to treat as entrypoints in repos without any package.json, as
+    /// paths relative to the repo root.
+    ///
+    /// When the walk finds no package.json and entry files are configured
+    /// (here, or through "<root>" in `entryPackages`), all files are attributed
+    /// to an implicit root package named after the repo directory, which
+    /// exports only those entry files.
+    #[serde(default)]
+    pub entry_files: Vec<String>,
     /// List of glob patterns to mark as "tests".
     /// These files will be marked as used, and all of their transitive
     /// dependencies will also be marked as used
     ///
     /// glob patterns are matched against the relative file path from the
     /// root of the repository
+    ///
+    /// Globs support `*`, `**`, `?`, character classes and `{a,b}` alternation
+    /// (e.g. `**/*.{test,spec}.ts`), with the same syntax as `entryPackages`.
     #[serde(default)]
     pub test_files: Vec<String>,
+    /// If true, files under the test directory a package declares in its
+    /// package.json (`"directories": { "test": "..." }`) are also marked as
+    /// tests, in addition to files matching `testFiles`.
+    #[serde(default)]
+    pub infer_test_dirs_from_package_json: bool,
+    /// If true, packages that do not declare a test directory in their
+    /// package.json fall back to `test`, `tests` and `__tests__`.
+    ///
+    /// Only used when `inferTestDirsFromPackageJson` is set.
+    #[serde(default)]
+    pub use_default_test_dirs: bool,
+    /// If true, warns about each file tagged as a test that never calls a
+    /// test framework function (`describe`, `it`, `test` or `expect`).
+    ///
+    /// This helps catch `testFiles` globs that are broad enough to match
+    /// non-test files, which would otherwise hide them from the report.
+    #[serde(default)]
+    pub verify_test_files: bool,
+    /// If true, each unused exported symbol is listed in the report with the
+    /// other files that export a symbol of the same name.
+    ///
+    /// This is a lightweight hint for duplicated code: the other files may be
+    /// the canonical source that the unused symbol should be imported from.
+    #[serde(default)]
+    pub suggest_same_name_exports: bool,
+    /// Lists the names exported by more than one file in the report as
+    /// `ambiguousSymbolNames`, with the files each name is imported from.
+    ///
+    /// Copy-pasted symbols often keep their name, so importers may pick up
+    /// either copy. With "conflicting-imports", only names that are imported
+    /// from more than one of the files exporting them are listed.
+    #[serde(default)]
+    pub report_ambiguous_symbol_names: AmbiguousSymbolNames,
+    /// If true, the report includes `importKindCounts`: the number of static
+    /// named, default and namespace imports, dynamic `import()` calls,
+    /// `require()` calls, re-exports and side-effect imports across all files.
+    #[serde(default)]
+    pub count_import_kinds: bool,
+    /// If true, the report includes `barrelBypassImports`: imports of a deep
+    /// path into another package (e.g. `@scope/pkg/src/util`) for symbols that
+    /// the package also exports from its public barrel (e.g. `@scope/pkg`).
+    ///
+    /// This helps enforce importing packages through their root instead of
+    /// depending on their internal file layout.
+    #[serde(default)]
+    pub report_barrel_bypass_imports: bool,
+    /// If true, the report includes `unusedImports`: the imported bindings of
+    /// each file that are never referenced within the file, e.g. `b` in
+    /// `import { a, b } from './foo'` when only `a` is used.
+    #[serde(default)]
+    pub report_unused_imports: bool,
+    /// If true, side-effect imports (e.g. `import './register-icons'`) use
+    /// the symbols re-exported by the imported file, as they do when the file
+    /// is imported for its exports.
+    ///
+    /// Set this to false so that executing a module for its side effects only
+    /// marks the module and its own imports as used, keeping the symbols it
+    /// exports or re-exports unused unless they are imported elsewhere.
+    #[serde(default = "default_effect_imports_mark_symbols")]
+    pub effect_imports_mark_symbols: bool,
+    /// If true, the run fails if any source file cannot be parsed, with an
+    /// error naming every unparseable file. By default, the imports of
+    /// unparseable files are found by a lexical scan, with a warning.
+    #[serde(default)]
+    pub fail_on_parse_error: bool,
+    /// If true, only the walked files that the tsconfig.json at the repo root
+    /// makes part of its project are analyzed: the files listed in its
+    /// `files`, and the files matching its `include` but not its `exclude`,
+    /// including the ones inherited through `extends`.
+    ///
+    /// This mirrors the membership of the TypeScript project, on top of
+    /// `rootPaths` and `skip`.
+    #[serde(default)]
+    pub restrict_to_tsconfig_files: bool,
+    /// If true, the run fails if a package matched by `entryPackages` does not
+    /// explicitly export any source file through its "exports", "main" or
+    /// "module" fields. By default, such packages are only reported with a
+    /// warning.
+    #[serde(default)]
+    pub strict_entry_packages: bool,
+    /// If true, good-fences `fence.json` files found during the walk narrow
+    /// the entrypoints of entry packages: a file exported by its package is
+    /// only an entrypoint if each of its fences that declares `exports` has
+    /// an export rule covering it.
+    ///
+    /// Files that a fence keeps private can then be reported unused, even if
+    /// a barrel exported by the package re-exports them.
+    #[serde(default)]
+    pub respect_fences: bool,
+    /// If set, files exporting more than this many symbols are listed in the
+    /// report as `largeModules`, alongside their number of exported symbols.
+    #[serde(default)]
+    pub god_module_threshold: Option<usize>,
+    /// Extensions of files that are tracked as opaque modules, such as
+    /// single-file components. These files are not parsed: they are treated
+    /// as modules with a single default export and no imports of their own.
+    #[serde(default = "default_opaque_module_extensions")]
+    pub opaque_module_extensions: Vec<String>,
+    /// How file paths are rendered in the report: as absolute paths, or
+    /// relative to the repo root or to the current working directory.
+    #[serde(default)]
+    pub path_display: PathDisplay,
+    /// Names of class decorators that mark an exported class as used, for
+    /// frameworks that wire classes together at runtime rather than through
+    /// imports (e.g. `Injectable` for `@Injectable()`).
+    ///
+    /// Exported classes with any of these decorators are treated as entry
+    /// points, along with their transitive dependencies.
+    #[serde(default)]
+    pub keep_alive_decorators: Vec<String>,
+    /// Pragma comments that mark an exported symbol as intentionally kept,
+    /// e.g. `good-fences-used` for `// good-fences-used`.
+    ///
+    /// Exported symbols whose export statement is preceded by a line comment
+    /// containing any of these pragmas are not reported as unused.
+    #[serde(default)]
+    pub keep_pragmas: Vec<String>,
+    /// How packages are discovered: "walk" treats every package.json found
+    /// under the root paths as a package, while "workspaces" only uses the
+    /// packages matched by the `workspaces` globs of the root package.json,
+    /// or by the `packages` globs of pnpm-workspace.yaml.
+    ///
+    /// Use "workspaces" in monorepos that contain package.json files which
+    /// are not workspace packages, such as test fixtures.
+    #[serde(default)]
+    pub package_discovery: PackageDiscovery,
+    /// Directories of generated files that mirror directories of source
+    /// files, e.g. `{ "from": "packages/*/lib/", "to": "packages/*/src/" }`
+    /// when `src/**/*.ts` is compiled to `lib/**/*.js`.
+    ///
+    /// Imports that resolve into a `from` directory also use the matching
+    /// source file in the `to` directory, probing `.ts` and `.tsx` for
+    /// generated `.js` files. The generated files are not reported as unused.
+    ///
+    /// Paths are relative to the repo root. `*` matches a single directory
+    /// name, and must appear the same number of times in `from` and `to`.
+    #[serde(default)]
+    pub path_mirrors: Vec<PathMirror>,
+    /// The environment imports are resolved for: "browser" follows the
+    /// `browser` field of packages, while "node" also matches the `node`
+    /// condition of their `exports`. This changes which files of a package
+    /// its imports reach.
+    #[serde(default)]
+    pub target_env: ResolverTargetEnv,
+}
+
+fn default_effect_imports_mark_symbols() -> bool {
+    true
+}
+
+fn default_opaque_module_extensions() -> Vec<String> {
+    crate::walk::DEFAULT_OPAQUE_MODULE_EXTENSIONS
+        .iter()
+        .map(|ext| ext.to_string())
+        .collect()
 }
 
 /// Configuration for the unused symbols finder
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct UnusedFinderConfig {
     /// If true, the finder should report exported symbols that are not used anywhere in the project
     pub report_exported_symbols: bool,
@@ -123,22 +339,113 @@ pub struct UnusedFinderConfig {
     /// However, the transitive dependencies of unused types will still be
     /// reported as unused.
     pub allow_unused_types: bool,
+    /// If true, symbols re-exported from a package's public barrel are
+    /// considered used.
+    pub barrel_exports_count_as_used: bool,
+    /// If true, only the exports of files explicitly exported by an entry
+    /// package are considered used, rather than the exports of every file of
+    /// an entry package without an "exports" field.
+    pub report_unused_entry_exports: bool,
 
     /// Path to the root directory of the repository
     pub repo_root: String,
 
     /// Pats to walk as "internal" source files
+    ///
+    /// May contain glob patterns until expanded with `expand_root_paths`
     pub root_paths: Vec<String>,
 
     /// packages we should consider as "entry" packages
     pub entry_packages: PackageMatchRules,
 
+    /// Entry files of the implicit root package of a repo without any
+    /// package.json, relative to the repo root
+    pub entry_files: Vec<String>,
+
     /// List of globs that will be matched against files in the repository
     ///
     /// Matches are made against the relative file paths from the repo root.
     /// A matching file will be tagged as a "test" file, and will be excluded
     /// from the list of unused files
-    pub test_files: Vec<glob::Pattern>,
+    pub test_files: Vec<Glob>,
+
+    /// If true, files under each package's package.json "directories.test"
+    /// are also tagged as test files.
+    pub infer_test_dirs_from_package_json: bool,
+
+    /// If true, packages without a "directories.test" fall back to the
+    /// default test directory names when inferring test directories.
+    pub use_default_test_dirs: bool,
+
+    /// If true, warns about files tagged as tests that do not call any test
+    /// framework functions.
+    pub verify_test_files: bool,
+
+    /// If true, unused exported symbols are reported with the other files
+    /// exporting a symbol of the same name.
+    pub suggest_same_name_exports: bool,
+
+    /// Which names exported by more than one file are listed in reports
+    pub report_ambiguous_symbol_names: AmbiguousSymbolNames,
+
+    /// If true, reports count the imports of each kind across all files.
+    pub count_import_kinds: bool,
+
+    /// If true, reports list the imports of a deep path into another package
+    /// for symbols that the package also exports from its public barrel.
+    pub report_barrel_bypass_imports: bool,
+
+    /// If true, reports list the imported bindings of each file that are
+    /// never referenced within the file.
+    pub report_unused_imports: bool,
+
+    /// If true, side-effect imports also use the symbols re-exported by the
+    /// imported file. Otherwise they only mark the file and its imports as used.
+    pub effect_imports_mark_symbols: bool,
+
+    /// If true, the run fails if any source file cannot be parsed, instead of
+    /// scanning the imports of the file with a warning.
+    pub fail_on_parse_error: bool,
+
+    /// If true, walked files are only analyzed if the tsconfig.json at the
+    /// repo root includes them in its project.
+    pub restrict_to_tsconfig_files: bool,
+
+    /// If true, the run fails if an entry package does not explicitly export
+    /// any source file, instead of reporting the package with a warning.
+    pub strict_entry_packages: bool,
+
+    /// If true, files exported by entry packages are only entrypoints if the
+    /// `exports` of their good-fences fences cover them.
+    pub respect_fences: bool,
+
+    /// If set, files exporting more than this many symbols are reported as
+    /// large modules.
+    pub god_module_threshold: Option<usize>,
+
+    /// Extensions of files that are tracked as opaque modules, with a
+    /// synthetic default export and no parsed imports.
+    pub opaque_module_extensions: Vec<String>,
+
+    /// How file paths are rendered in the report
+    pub path_display: PathDisplay,
+
+    /// Names of class decorators that mark exported classes as entry points
+    pub keep_alive_decorators: Vec<String>,
+
+    /// Pragmas that mark exported symbols as kept when found in a line
+    /// comment leading their export statement
+    pub keep_pragmas: Vec<String>,
+
+    /// How packages are discovered during the walk
+    pub package_discovery: PackageDiscovery,
+
+    /// Directories of generated files, and the directories of source files
+    /// they mirror
+    pub path_mirrors: PathMirrorRules,
+
+    /// The environment imports are resolved for
+    pub target_env: ResolverTargetEnv,
 
     /// Globs of individual files & directories to skip during the file walk.
     ///
@@ -147,15 +454,91 @@ pub struct UnusedFinderConfig {
     pub skip: Vec<String>,
 }
 
+impl Default for UnusedFinderConfig {
+    fn default() -> Self {
+        UnusedFinderConfig {
+            report_exported_symbols: false,
+            allow_unused_types: false,
+            barrel_exports_count_as_used: false,
+            report_unused_entry_exports: false,
+            repo_root: String::new(),
+            root_paths: Vec::new(),
+            entry_packages: PackageMatchRules::default(),
+            entry_files: Vec::new(),
+            test_files: Vec::new(),
+            infer_test_dirs_from_package_json: false,
+            use_default_test_dirs: false,
+            verify_test_files: false,
+            suggest_same_name_exports: false,
+            report_ambiguous_symbol_names: AmbiguousSymbolNames::default(),
+            count_import_kinds: false,
+            report_barrel_bypass_imports: false,
+            report_unused_imports: false,
+            effect_imports_mark_symbols: default_effect_imports_mark_symbols(),
+            fail_on_parse_error: false,
+            restrict_to_tsconfig_files: false,
+            strict_entry_packages: false,
+            respect_fences: false,
+            god_module_threshold: None,
+            opaque_module_extensions: Vec::new(),
+            path_display: PathDisplay::default(),
+            keep_alive_decorators: Vec::new(),
+            keep_pragmas: Vec::new(),
+            package_discovery: PackageDiscovery::default(),
+            path_mirrors: PathMirrorRules::default(),
+            target_env: ResolverTargetEnv::default(),
+            skip: Vec::new(),
+        }
+    }
+}
+
+/// Applies `key=value` overrides, e.g. from the command line, to the fields
+/// of a JSON config before it is parsed.
+///
+/// Keys are the names of top-level fields of the JSON config. Values are
+/// parsed as JSON, falling back to a plain string, so `allowUnusedTypes=true`
+/// sets a flag and `repoRoot=../repo` sets a path.
+pub fn apply_config_overrides(
+    config: &mut serde_json::Value,
+    overrides: &[String],
+) -> Result<(), ConfigError> {
+    for config_override in overrides {
+        let (key, raw_value) = match config_override.split_once('=') {
+            Some((key, raw_value)) if !key.is_empty() => (key, raw_value),
+            _ => {
+                return Err(ConfigError::InvalidOverride(
+                    config_override.clone(),
+                    "expected key=value".to_string(),
+                ))
+            }
+        };
+        let value = serde_json::from_str(raw_value)
+            .unwrap_or_else(|_| serde_json::Value::String(raw_value.to_string()));
+        match config.as_object_mut() {
+            Some(fields) => {
+                fields.insert(key.to_string(), value);
+            }
+            None => {
+                return Err(ConfigError::InvalidOverride(
+                    config_override.clone(),
+                    "the config is not a JSON object".to_string(),
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
 impl TryFrom<UnusedFinderJSONConfig> for UnusedFinderConfig {
     type Error = ConfigError;
     fn try_from(value: UnusedFinderJSONConfig) -> std::result::Result<Self, Self::Error> {
-        let (test_globs, test_glob_errs): (Vec<glob::Pattern>, Vec<_>) = value
+        let (test_globs, test_glob_errs): (Vec<Glob>, Vec<_>) = value
             .test_files
             .iter()
-            .partition_map(|pat| match glob::Pattern::new(pat) {
+            .enumerate()
+            .partition_map(|(i, pat)| match Glob::new(pat) {
                 Ok(pat) => Either::Left(pat),
-                Err(err) => Either::Right(PatErr(0, GlobInterp::Path, err)),
+                Err(err) => Either::Right(PatErr(i, GlobInterp::Path, err)),
             });
         if !test_glob_errs.is_empty() {
             return Err(ConfigError::InvalidGlobPatterns(ErrList(test_glob_errs)));
@@ -165,11 +548,35 @@ impl TryFrom<UnusedFinderJSONConfig> for UnusedFinderConfig {
             // raw fields that are copied from the JSON config
             report_exported_symbols: value.report_exported_symbols,
             allow_unused_types: value.allow_unused_types,
+            barrel_exports_count_as_used: value.barrel_exports_count_as_used,
+            report_unused_entry_exports: value.report_unused_entry_exports,
             root_paths: value.root_paths,
             repo_root: value.repo_root,
             // other fields that are processed before use
             entry_packages: value.entry_packages.try_into()?,
+            entry_files: value.entry_files,
             test_files: test_globs,
+            infer_test_dirs_from_package_json: value.infer_test_dirs_from_package_json,
+            use_default_test_dirs: value.use_default_test_dirs,
+            verify_test_files: value.verify_test_files,
+            suggest_same_name_exports: value.suggest_same_name_exports,
+            report_ambiguous_symbol_names: value.report_ambiguous_symbol_names,
+            count_import_kinds: value.count_import_kinds,
+            report_barrel_bypass_imports: value.report_barrel_bypass_imports,
+            report_unused_imports: value.report_unused_imports,
+            effect_imports_mark_symbols: value.effect_imports_mark_symbols,
+            fail_on_parse_error: value.fail_on_parse_error,
+            restrict_to_tsconfig_files: value.restrict_to_tsconfig_files,
+            strict_entry_packages: value.strict_entry_packages,
+            respect_fences: value.respect_fences,
+            god_module_threshold: value.god_module_threshold,
+            opaque_module_extensions: value.opaque_module_extensions,
+            path_display: value.path_display,
+            keep_alive_decorators: value.keep_alive_decorators,
+            keep_pragmas: value.keep_pragmas,
+            package_discovery: value.package_discovery,
+            path_mirrors: value.path_mirrors.try_into()?,
+            target_env: value.target_env,
             skip: value.skip,
         })
     }
@@ -184,7 +591,7 @@ mod test {
         let json_config = r#"{
             "repoRoot": "/path/to/repo",
             "rootPaths": ["src"],
-            "entryPackages": ["./***"],
+            "entryPackages": ["./foo/[a"],
             "skip": []
         }"#;
 
@@ -193,10 +600,7 @@ mod test {
         let expected_err = ConfigError::InvalidGlobPatterns(ErrList(vec![PatErr(
             0,
             GlobInterp::Path,
-            glob::PatternError {
-                pos: 2,
-                msg: "wildcards are either regular `*` or recursive `**`",
-            },
+            Glob::new("foo/[a").unwrap_err(),
         )]));
 
         assert_eq!(err, expected_err);
@@ -207,7 +611,7 @@ mod test {
         let json_config = r#"{
             "repoRoot": "/path/to/repo",
             "rootPaths": ["src"],
-            "entryPackages": ["@foo/***"],
+            "entryPackages": ["@foo/{a,b"],
             "skip": []
         }"#;
 
@@ -216,10 +620,7 @@ mod test {
         let expected_err = ConfigError::InvalidGlobPatterns(ErrList(vec![PatErr(
             0,
             GlobInterp::Name,
-            glob::PatternError {
-                pos: 7,
-                msg: "wildcards are either regular `*` or recursive `**`",
-            },
+            Glob::new("@foo/{a,b").unwrap_err(),
         )]));
 
         assert_eq!(err, expected_err);
@@ -232,9 +633,9 @@ mod test {
             "rootPaths": ["src"],
             "entryPackages": [
                 "my-pkg1",
-                "@foo/***",
+                "@foo/{a,b",
                 "my-pkg2-*",
-                "./foo/***"
+                "./foo/[a"
             ],
             "skip": []
         }"#;
@@ -242,24 +643,40 @@ mod test {
         let config: UnusedFinderJSONConfig = serde_json::from_str(json_config).unwrap();
         let err: ConfigError = UnusedFinderConfig::try_from(config).unwrap_err();
         let expected_err = ConfigError::InvalidGlobPatterns(ErrList(vec![
-            PatErr(
-                1,
-                GlobInterp::Name,
-                glob::PatternError {
-                    pos: 7,
-                    msg: "wildcards are either regular `*` or recursive `**`",
-                },
-            ),
-            PatErr(
-                3,
-                GlobInterp::Path,
-                glob::PatternError {
-                    pos: 6,
-                    msg: "wildcards are either regular `*` or recursive `**`",
-                },
-            ),
+            PatErr(1, GlobInterp::Name, Glob::new("@foo/{a,b").unwrap_err()),
+            PatErr(3, GlobInterp::Path, Glob::new("foo/[a").unwrap_err()),
         ]));
 
         assert_eq!(expected_err, err);
     }
+
+    #[test]
+    fn test_invalid_config_override_err() {
+        let mut json_config = serde_json::json!({ "rootPaths": ["src"] });
+        let err = apply_config_overrides(&mut json_config, &["=true".to_string()]).unwrap_err();
+        assert_eq!(
+            err,
+            ConfigError::InvalidOverride("=true".to_string(), "expected key=value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_test_files_glob_err() {
+        let json_config = r#"{
+            "repoRoot": "/path/to/repo",
+            "rootPaths": ["src"],
+            "entryPackages": [],
+            "testFiles": ["**/*.test.ts", "**/*.{spec,test.tsx"]
+        }"#;
+
+        let config: UnusedFinderJSONConfig = serde_json::from_str(json_config).unwrap();
+        let err: ConfigError = UnusedFinderConfig::try_from(config).unwrap_err();
+        let expected_err = ConfigError::InvalidGlobPatterns(ErrList(vec![PatErr(
+            1,
+            GlobInterp::Path,
+            Glob::new("**/*.{spec,test.tsx").unwrap_err(),
+        )]));
+
+        assert_eq!(err, expected_err);
+    }
 }
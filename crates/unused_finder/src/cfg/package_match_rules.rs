@@ -1,14 +1,15 @@
 use std::path::Path;
 
 use ahashmap::AHashSet;
+use path_utils::Glob;
 
 use super::{ConfigError, GlobInterp, PatErr};
 
 #[derive(Debug, Default, Clone)]
 pub struct PackageMatchRules {
     pub names: AHashSet<String>,
-    pub name_patterns: Vec<glob::Pattern>,
-    pub path_patterns: Vec<glob::Pattern>,
+    pub name_patterns: Vec<Glob>,
+    pub path_patterns: Vec<Glob>,
 }
 
 impl PackageMatchRules {
@@ -17,12 +18,21 @@ impl PackageMatchRules {
     /// * `package_path` - The path to the package, relative to the repo root
     /// * `package_name` - The name of the package
     pub fn matches(&self, package_path: &Path, package_name: &str) -> bool {
+        self.matching_rule(package_path, package_name).is_some()
+    }
+
+    /// Gets the first rule that matches a package, as it was written in the
+    /// config: a package name, a name pattern, or a "./"-prefixed path pattern
+    ///
+    /// * `package_path` - The path to the package, relative to the repo root
+    /// * `package_name` - The name of the package
+    pub fn matching_rule(&self, package_path: &Path, package_name: &str) -> Option<String> {
         if self.names.contains(package_name) {
-            return true;
+            return Some(package_name.to_string());
         }
         for pattern in &self.name_patterns {
             if pattern.matches(package_name) {
-                return true;
+                return Some(pattern.as_str().to_string());
             }
         }
         for pattern in &self.path_patterns {
@@ -36,11 +46,11 @@ impl PackageMatchRules {
                 &path_string
             });
             if pattern.matches(path_string_ref) {
-                return true;
+                return Some(format!("./{}", pattern.as_str()));
             }
         }
 
-        false
+        None
     }
 }
 
@@ -59,12 +69,12 @@ impl<T: AsRef<str> + ToString> TryFrom<Vec<T>> for PackageMatchRules {
         let mut errs: Vec<PatErr> = Vec::new();
         for (i, item) in value.into_iter().enumerate() {
             if let Some(trimmed) = item.as_ref().strip_prefix("./") {
-                match glob::Pattern::new(trimmed) {
+                match Glob::new(trimmed) {
                     Err(e) => errs.push(PatErr(i, GlobInterp::Path, e)),
                     Ok(r) => path_patterns.push(r),
                 };
-            } else if item.as_ref().chars().any(|c| "~)('!*".contains(c)) {
-                match glob::Pattern::new(item.as_ref()) {
+            } else if item.as_ref().chars().any(|c| "~)('!*?[{".contains(c)) {
+                match Glob::new(item.as_ref()) {
                     Err(e) => errs.push(PatErr(i, GlobInterp::Name, e)),
                     Ok(r) => name_patterns.push(r),
                 };
@@ -97,4 +107,40 @@ mod test {
             package_match_rules.matches(Path::new("shared/n/my-pkg/package.json"), "@me/my-pkg")
         );
     }
+
+    #[test]
+    fn test_package_matching_rule() {
+        let package_match_rules =
+            PackageMatchRules::try_from(vec!["@me/app", "@me/web-*", "./tools/**"]).unwrap();
+
+        assert_eq!(
+            package_match_rules.matching_rule(Path::new("packages/app"), "@me/app"),
+            Some("@me/app".to_string())
+        );
+        assert_eq!(
+            package_match_rules.matching_rule(Path::new("packages/web"), "@me/web-client"),
+            Some("@me/web-*".to_string())
+        );
+        assert_eq!(
+            package_match_rules.matching_rule(Path::new("tools/cli/package.json"), "cli"),
+            Some("./tools/**".to_string())
+        );
+        assert_eq!(
+            package_match_rules.matching_rule(Path::new("packages/lib"), "@me/lib"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_package_match_brace_alternation() {
+        let package_match_rules =
+            PackageMatchRules::try_from(vec!["@me/{app,web}-*", "./{apps,tools}/**/cli"]).unwrap();
+
+        assert!(package_match_rules.matches(Path::new("packages/a"), "@me/app-shell"));
+        assert!(package_match_rules.matches(Path::new("packages/b"), "@me/web-client"));
+        assert!(!package_match_rules.matches(Path::new("packages/c"), "@me/lib-core"));
+        assert!(package_match_rules.matches(Path::new("apps/cli"), "cli"));
+        assert!(package_match_rules.matches(Path::new("tools/build/cli"), "build-cli"));
+        assert!(!package_match_rules.matches(Path::new("packages/cli"), "cli"));
+    }
 }
@@ -0,0 +1,227 @@
+use std::path::{Path, PathBuf};
+
+use path_slash::PathExt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ConfigError;
+
+/// A directory of generated files that mirrors a directory of source files,
+/// e.g. `{ "from": "packages/*/lib/", "to": "packages/*/src/" }` for builds
+/// that compile `src/**/*.ts` into `lib/**/*.js`.
+///
+/// Paths are relative to the repo root. A `*` matches a single directory
+/// name, and must appear as a whole path segment. Each `*` in `from`
+/// corresponds to the `*` at the same position in `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PathMirror {
+    pub from: String,
+    pub to: String,
+}
+
+/// The path mirrors of a config, validated and split into path segments
+#[derive(Debug, Default, Clone)]
+pub struct PathMirrorRules {
+    pub mirrors: Vec<PathMirror>,
+    // (from segments, to segments) of each mirror
+    segments: Vec<(Vec<String>, Vec<String>)>,
+}
+
+/// Extensions of generated files, which are stripped before probing for the
+/// corresponding source file
+const MIRRORED_OUTPUT_EXTENSIONS: &[&str] = &[".d.ts", ".js", ".jsx", ".mjs", ".cjs"];
+
+/// Extensions probed for the source file of a generated file, in order
+const MIRRORED_SOURCE_EXTENSIONS: &[&str] = &[".ts", ".tsx", ".mts", ".cts", ".js", ".jsx"];
+
+impl PathMirrorRules {
+    pub fn is_empty(&self) -> bool {
+        self.mirrors.is_empty()
+    }
+
+    /// Checks if a file is within the `from` directory of any mirror
+    pub fn is_mirror_file(&self, repo_root: &Path, file_path: &Path) -> bool {
+        self.twin_path(repo_root, file_path).is_some()
+    }
+
+    /// Gets the source file that a file within the `from` directory of a
+    /// mirror was generated from, probing `is_source_file` for each of the
+    /// candidate source extensions.
+    ///
+    /// Returns None if the file is not within any mirror, and Some(Err) with
+    /// the path the source file was expected at if no candidate matched.
+    pub fn find_source_file(
+        &self,
+        repo_root: &Path,
+        file_path: &Path,
+        is_source_file: impl Fn(&Path) -> bool,
+    ) -> Option<Result<PathBuf, PathBuf>> {
+        let twin_path = self.twin_path(repo_root, file_path)?;
+        let file_name = twin_path.file_name()?.to_string_lossy().to_string();
+
+        let stem = MIRRORED_OUTPUT_EXTENSIONS
+            .iter()
+            .find_map(|ext| file_name.strip_suffix(ext));
+        let candidates: Vec<PathBuf> = match stem {
+            Some(stem) => MIRRORED_SOURCE_EXTENSIONS
+                .iter()
+                .map(|ext| twin_path.with_file_name(format!("{stem}{ext}")))
+                .collect(),
+            // other files (e.g. assets) are copied as they are
+            None => vec![twin_path.clone()],
+        };
+
+        Some(
+            candidates
+                .into_iter()
+                .find(|candidate| is_source_file(candidate))
+                .ok_or(twin_path),
+        )
+    }
+
+    // maps a path within the `from` directory of a mirror to the same path
+    // within its `to` directory
+    fn twin_path(&self, repo_root: &Path, file_path: &Path) -> Option<PathBuf> {
+        let relative_path = file_path.strip_prefix(repo_root).ok()?.to_slash()?;
+        let path_segments: Vec<&str> = relative_path.split('/').collect();
+
+        self.segments.iter().find_map(|(from, to)| {
+            // the mirrored path must be a file within the `from` directory
+            if path_segments.len() <= from.len() {
+                return None;
+            }
+            let mut captures = Vec::new();
+            for (pattern_segment, path_segment) in from.iter().zip(path_segments.iter()) {
+                if pattern_segment == "*" {
+                    captures.push(*path_segment);
+                } else if pattern_segment != path_segment {
+                    return None;
+                }
+            }
+
+            let mut captures = captures.into_iter();
+            let mut twin_path = repo_root.to_path_buf();
+            for pattern_segment in to.iter() {
+                match pattern_segment.as_str() {
+                    "*" => twin_path.push(captures.next()?),
+                    segment => twin_path.push(segment),
+                }
+            }
+            twin_path.extend(&path_segments[from.len()..]);
+            Some(twin_path)
+        })
+    }
+}
+
+fn split_mirror_pattern(pattern: &str) -> Result<Vec<String>, String> {
+    let segments: Vec<String> = pattern
+        .trim_start_matches("./")
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.to_string())
+        .collect();
+    if segments.is_empty() {
+        return Err(format!("{pattern:?} does not name a directory"));
+    }
+    if let Some(segment) = segments
+        .iter()
+        .find(|segment| segment.contains('*') && segment.as_str() != "*")
+    {
+        return Err(format!(
+            "{pattern:?} uses `*` within the segment {segment:?}, but `*` must be a whole path segment"
+        ));
+    }
+    Ok(segments)
+}
+
+impl TryFrom<Vec<PathMirror>> for PathMirrorRules {
+    type Error = ConfigError;
+    fn try_from(mirrors: Vec<PathMirror>) -> Result<Self, Self::Error> {
+        let mut segments = Vec::with_capacity(mirrors.len());
+        for (i, mirror) in mirrors.iter().enumerate() {
+            let invalid = |msg: String| ConfigError::InvalidPathMirror(i, msg);
+            let from = split_mirror_pattern(&mirror.from).map_err(invalid)?;
+            let to = split_mirror_pattern(&mirror.to).map_err(invalid)?;
+            let from_stars = from.iter().filter(|segment| *segment == "*").count();
+            let to_stars = to.iter().filter(|segment| *segment == "*").count();
+            if from_stars != to_stars {
+                return Err(invalid(format!(
+                    "{:?} has {from_stars} `*` segments, but {:?} has {to_stars}",
+                    mirror.from, mirror.to
+                )));
+            }
+            segments.push((from, to));
+        }
+
+        Ok(Self { mirrors, segments })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::{PathMirror, PathMirrorRules};
+    use crate::cfg::ConfigError;
+
+    fn mirror(from: &str, to: &str) -> PathMirror {
+        PathMirror {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_find_source_file() {
+        let rules =
+            PathMirrorRules::try_from(vec![mirror("packages/*/lib/", "packages/*/src/")]).unwrap();
+        let repo_root = Path::new("/repo");
+        let source_file = PathBuf::from("/repo/packages/a/src/util/format.ts");
+
+        assert_eq!(
+            rules.find_source_file(
+                repo_root,
+                Path::new("/repo/packages/a/lib/util/format.js"),
+                |path| path == source_file
+            ),
+            Some(Ok(source_file.clone()))
+        );
+        assert_eq!(
+            rules.find_source_file(
+                repo_root,
+                Path::new("/repo/packages/a/lib/missing.js"),
+                |path| path == source_file
+            ),
+            Some(Err(PathBuf::from("/repo/packages/a/src/missing.js")))
+        );
+        assert_eq!(
+            rules.find_source_file(
+                repo_root,
+                Path::new("/repo/packages/a/src/util/format.ts"),
+                |_| true
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_mismatched_stars_err() {
+        let err = PathMirrorRules::try_from(vec![mirror("packages/*/lib/", "src/")]).unwrap_err();
+
+        assert_eq!(
+            err,
+            ConfigError::InvalidPathMirror(
+                0,
+                r#""packages/*/lib/" has 1 `*` segments, but "src/" has 0"#.to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_partial_segment_star_err() {
+        let err = PathMirrorRules::try_from(vec![mirror("packages/lib-*/", "packages/src-*/")])
+            .unwrap_err();
+
+        assert!(matches!(err, ConfigError::InvalidPathMirror(0, _)));
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use logger::Logger;
+use path_slash::PathExt;
+use path_utils::Glob;
+
+use super::{ConfigError, ErrList, GlobInterp, PatErr, UnusedFinderConfig};
+
+/// Characters that make a root path a glob pattern instead of a literal path
+const GLOB_CHARS: &[char] = &['*', '?', '['];
+
+/// Checks if a root path needs to be expanded, either because it is a glob
+/// pattern or because it excludes previously listed paths
+fn is_root_path_pattern(root_path: &str) -> bool {
+    root_path.starts_with('!') || root_path.contains(GLOB_CHARS)
+}
+
+/// Expands the glob patterns in a list of root paths into the directories
+/// they match under the repo root, e.g. `packages/*/src`.
+///
+/// Root paths are evaluated in order: literal paths are kept as they are,
+/// glob patterns add the directories they match, and patterns starting with
+/// `!` remove the previously added paths they match. Patterns are relative to
+/// the repo root, and a glob pattern that matches no directories is an error.
+pub fn expand_root_paths(
+    repo_root: &Path,
+    root_paths: &[String],
+) -> Result<Vec<String>, ConfigError> {
+    let escaped_repo_root = glob::Pattern::escape(&repo_root.to_string_lossy());
+    let mut expanded: Vec<String> = Vec::new();
+    for (i, root_path) in root_paths.iter().enumerate() {
+        if let Some(excluded) = root_path.strip_prefix('!') {
+            let pattern = Glob::new(excluded.trim_start_matches("./")).map_err(|err| {
+                ConfigError::InvalidGlobPatterns(ErrList(vec![PatErr(i, GlobInterp::Path, err)]))
+            })?;
+            expanded.retain(|path| !pattern.matches(path.trim_start_matches("./")));
+        } else if root_path.contains(GLOB_CHARS) {
+            // root path globs are expanded against the file system, which
+            // does not support every syntax of the other globs
+            let pattern_err = |err: glob::PatternError| {
+                ConfigError::InvalidRootPathGlob(root_path.clone(), err.to_string())
+            };
+            let relative_pattern = root_path.trim_start_matches("./");
+            glob::Pattern::new(relative_pattern).map_err(pattern_err)?;
+            let full_pattern = format!("{escaped_repo_root}/{relative_pattern}");
+
+            let mut matched_any = false;
+            // unreadable directories are skipped, as they are by the walk
+            for matched_path in glob::glob(&full_pattern).map_err(pattern_err)?.flatten() {
+                if !matched_path.is_dir() {
+                    continue;
+                }
+                let relative_path = match matched_path.strip_prefix(repo_root) {
+                    Ok(relative_path) => relative_path.to_slash_lossy().to_string(),
+                    Err(_) => continue,
+                };
+                matched_any = true;
+                if !expanded.contains(&relative_path) {
+                    expanded.push(relative_path);
+                }
+            }
+            if !matched_any {
+                return Err(ConfigError::EmptyRootPathGlob(root_path.clone()));
+            }
+        } else if !expanded.contains(root_path) {
+            expanded.push(root_path.clone());
+        }
+    }
+    Ok(expanded)
+}
+
+impl UnusedFinderConfig {
+    /// Replaces the glob patterns in `root_paths` with the directories they
+    /// match, logging the expanded root paths.
+    ///
+    /// Root paths without any patterns are left unchanged, so expanding an
+    /// already expanded config is a no-op.
+    pub fn expand_root_paths(&mut self, logger: impl Logger) -> Result<(), ConfigError> {
+        if !self
+            .root_paths
+            .iter()
+            .any(|root_path| is_root_path_pattern(root_path))
+        {
+            return Ok(());
+        }
+
+        let expanded = expand_root_paths(Path::new(&self.repo_root), &self.root_paths)?;
+        logger.log(format!(
+            "Expanded root paths {:?} to {:?}",
+            self.root_paths, expanded
+        ));
+        self.root_paths = expanded;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_tmpdir::test_tmpdir;
+
+    use super::expand_root_paths;
+    use crate::cfg::ConfigError;
+
+    fn root_paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand_glob_with_exclusion() {
+        let tmpdir = test_tmpdir!(
+            "packages/a/src/index.ts" => "export const a = 1;",
+            "packages/a/scripts/build.ts" => "export const build = 1;",
+            "packages/b/src/index.ts" => "export const b = 1;",
+            "packages/legacy-c/src/index.ts" => "export const c = 1;"
+        );
+
+        let expanded = expand_root_paths(
+            tmpdir.root(),
+            &root_paths(&["packages/*/src", "!packages/legacy-*/src"]),
+        )
+        .unwrap();
+
+        assert_eq!(expanded, vec!["packages/a/src", "packages/b/src"]);
+    }
+
+    #[test]
+    fn test_expand_literal_unchanged() {
+        let tmpdir = test_tmpdir!(
+            "packages/a/src/index.ts" => "export const a = 1;",
+            "tools/index.ts" => "export const tool = 1;"
+        );
+
+        let expanded =
+            expand_root_paths(tmpdir.root(), &root_paths(&["tools", "packages/*/src"])).unwrap();
+
+        assert_eq!(expanded, vec!["tools", "packages/a/src"]);
+    }
+
+    #[test]
+    fn test_expand_zero_matches_err() {
+        let tmpdir = test_tmpdir!(
+            "packages/a/src/index.ts" => "export const a = 1;"
+        );
+
+        let err = expand_root_paths(tmpdir.root(), &root_paths(&["apps/*/src"])).unwrap_err();
+
+        assert_eq!(
+            err,
+            ConfigError::EmptyRootPathGlob("apps/*/src".to_string())
+        );
+    }
+}
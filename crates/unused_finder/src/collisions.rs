@@ -0,0 +1,442 @@
+use std::{
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use ahashmap::{AHashMap, AHashSet};
+use swc_common::BytePos;
+
+use crate::{
+    graph::Graph,
+    parse::{ExportedSymbol, ReExportedSymbol, ResolvedImportExportInfo},
+    walked_file::ResolvedSourceFile,
+};
+
+/// A file and symbol that a name exported from an entry file resolves to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SymbolDefinition {
+    pub file_path: PathBuf,
+    pub symbol: ExportedSymbol,
+}
+
+/// A name exported from an entry file that resolves to more than one
+/// definition, e.g. when a barrel re-exports a `formatDate` by name, which
+/// shadows the `formatDate` of a file it re-exports with `export *`.
+///
+/// Importers of the name only get one of the definitions, so the others are
+/// effectively dead through the entry file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolCollision {
+    pub entry_file: PathBuf,
+    pub name: ExportedSymbol,
+    /// Every definition of the name, from the lowest to the highest priority.
+    /// The last one is the one importers of the name get.
+    pub definitions: Vec<SymbolDefinition>,
+}
+
+impl SymbolCollision {
+    /// The definition that importers of the name currently get
+    pub fn winner(&self) -> &SymbolDefinition {
+        self.definitions
+            .last()
+            .expect("collisions have more than one definition")
+    }
+}
+
+/// A name that a file gets from more than one `export *`, with a different
+/// definition from each.
+///
+/// Per the ES spec, the file does not export an ambiguous name at all, so
+/// importing it by name is an error at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousReexport {
+    pub file_path: PathBuf,
+    pub name: ExportedSymbol,
+    /// The files of the `export *` contributing the name, in the order the
+    /// file lists them
+    pub sources: Vec<PathBuf>,
+}
+
+/// An import of a deep path into another package, for a symbol that the
+/// package also exports from its public barrel, e.g. importing `util` from
+/// `@scope/pkg/src/util` when `@scope/pkg` re-exports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BarrelBypassImport {
+    pub importer: PathBuf,
+    pub imported_file: PathBuf,
+    pub symbol: ExportedSymbol,
+    /// The package that owns the imported file
+    pub package_name: String,
+    /// The public barrel of the package that also exports the symbol
+    pub barrel_file: PathBuf,
+    /// The name the barrel exports the symbol as
+    pub barrel_name: ExportedSymbol,
+}
+
+// Names exported from a file, each with its definitions ordered from the
+// lowest to the highest priority, so the last one is the one importers get
+type ExportedDefinitions = AHashMap<ExportedSymbol, Vec<SymbolDefinition>>;
+
+/// Finds the names exported from each entry file that resolve to more than one
+/// distinct definition, after following re-exports and expanding `export *`.
+///
+/// Local exports and named re-exports take priority over names from
+/// `export *`. Names that are ambiguous between several `export *` are not
+/// exported, so they never collide.
+pub fn find_symbol_collisions(graph: &Graph, entry_files: &[&Path]) -> Vec<SymbolCollision> {
+    let mut resolver = DefinitionResolver::new(|file_path: &Path| {
+        graph
+            .get_file_by_path(file_path)
+            .map(|file| &file.import_export_info)
+    });
+    let mut collisions: Vec<SymbolCollision> = entry_files
+        .iter()
+        .filter(|entry_file| graph.path_to_id.contains_key(**entry_file))
+        .flat_map(|entry_file| {
+            resolver
+                .exported_definitions(entry_file)
+                .iter()
+                .filter(|(_, definitions)| definitions.len() > 1)
+                .map(|(name, definitions)| SymbolCollision {
+                    entry_file: entry_file.to_path_buf(),
+                    name: name.clone(),
+                    definitions: definitions.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    collisions.sort_by(|a, b| {
+        a.entry_file
+            .cmp(&b.entry_file)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    collisions
+}
+
+/// Gets the definitions of every name a file in the graph exports, after
+/// following re-exports and expanding `export *`, sorted by name.
+pub(crate) fn resolve_exported_definitions(
+    graph: &Graph,
+    file_path: &Path,
+) -> Vec<(ExportedSymbol, Vec<SymbolDefinition>)> {
+    let mut resolver = DefinitionResolver::new(|file_path: &Path| {
+        graph
+            .get_file_by_path(file_path)
+            .map(|file| &file.import_export_info)
+    });
+    let mut exported_definitions: Vec<(ExportedSymbol, Vec<SymbolDefinition>)> = resolver
+        .exported_definitions(file_path)
+        .iter()
+        .map(|(name, definitions)| (name.clone(), definitions.clone()))
+        .collect();
+    exported_definitions.sort_by(|a, b| a.0.cmp(&b.0));
+    exported_definitions
+}
+
+/// Finds the names that source files get from more than one `export *` with
+/// different definitions, and which they do not export locally or by name.
+pub fn find_ambiguous_reexports(
+    source_files: &AHashMap<PathBuf, ResolvedSourceFile>,
+) -> Vec<AmbiguousReexport> {
+    let mut resolver = DefinitionResolver::new(|file_path: &Path| {
+        source_files
+            .get(file_path)
+            .map(|file| &file.import_export_info)
+    });
+    for (file_path, file) in source_files.iter() {
+        // only files with several `export *` can have ambiguous names
+        let num_star_exports = file
+            .import_export_info
+            .export_from_symbols
+            .values()
+            .flat_map(|re_exports| re_exports.keys())
+            .filter(|re_export| is_star_export(re_export))
+            .count();
+        if num_star_exports > 1 {
+            resolver.exported_definitions(file_path);
+        }
+    }
+
+    let mut ambiguous_reexports = resolver.ambiguous_reexports;
+    ambiguous_reexports.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ambiguous_reexports
+}
+
+/// Finds the imports of files of other packages that are not the package's
+/// public barrel, for symbols that resolve to the same definition as a name
+/// exported from the barrel.
+///
+/// `package_barrels` maps each package name to its barrel files. Namespace
+/// and side-effect imports are not reported, since they depend on the whole
+/// module rather than on a single symbol.
+pub fn find_barrel_bypass_imports(
+    graph: &Graph,
+    package_barrels: &AHashMap<String, Vec<PathBuf>>,
+) -> Vec<BarrelBypassImport> {
+    let mut resolver = DefinitionResolver::new(|file_path: &Path| {
+        graph
+            .get_file_by_path(file_path)
+            .map(|file| &file.import_export_info)
+    });
+    let mut bypass_imports: Vec<BarrelBypassImport> = Vec::new();
+    for file in graph.files.iter() {
+        for (imported_path, symbols) in file.import_export_info.imported_symbols.iter() {
+            // only imports across packages bypass the public exports of a package
+            let package_name = match graph
+                .get_file_by_path(imported_path)
+                .and_then(|imported_file| imported_file.owning_package.as_ref())
+            {
+                Some(package_name) if file.owning_package.as_ref() != Some(package_name) => {
+                    package_name
+                }
+                _ => continue,
+            };
+            let barrels = match package_barrels.get(package_name) {
+                Some(barrels) if !barrels.contains(imported_path) => barrels,
+                _ => continue,
+            };
+
+            let imported_definitions = resolver.exported_definitions(imported_path);
+            for symbol in symbols.iter() {
+                if matches!(
+                    symbol,
+                    ExportedSymbol::Namespace | ExportedSymbol::ExecutionOnly
+                ) {
+                    continue;
+                }
+                let definition = imported_definitions
+                    .get(symbol)
+                    .and_then(|definitions| definitions.last())
+                    .cloned()
+                    .unwrap_or_else(|| SymbolDefinition {
+                        file_path: imported_path.clone(),
+                        symbol: symbol.clone(),
+                    });
+                let barrel_export = barrels.iter().find_map(|barrel| {
+                    let barrel_definitions = resolver.exported_definitions(barrel);
+                    exported_name_of(&barrel_definitions, symbol, &definition)
+                        .map(|barrel_name| (barrel, barrel_name))
+                });
+                if let Some((barrel_file, barrel_name)) = barrel_export {
+                    bypass_imports.push(BarrelBypassImport {
+                        importer: file.file_path.clone(),
+                        imported_file: imported_path.clone(),
+                        symbol: symbol.clone(),
+                        package_name: package_name.clone(),
+                        barrel_file: barrel_file.clone(),
+                        barrel_name,
+                    });
+                }
+            }
+        }
+    }
+    bypass_imports.sort_by(|a, b| {
+        (&a.importer, &a.imported_file, &a.symbol).cmp(&(&b.importer, &b.imported_file, &b.symbol))
+    });
+    bypass_imports
+}
+
+// Finds the name that importers get a definition through, preferring the
+// name it was imported as when the file exports it under several names
+fn exported_name_of(
+    exported_definitions: &ExportedDefinitions,
+    preferred_name: &ExportedSymbol,
+    definition: &SymbolDefinition,
+) -> Option<ExportedSymbol> {
+    let provides = |definitions: &Vec<SymbolDefinition>| definitions.last() == Some(definition);
+    if exported_definitions
+        .get(preferred_name)
+        .is_some_and(provides)
+    {
+        return Some(preferred_name.clone());
+    }
+    exported_definitions
+        .iter()
+        .filter(|(_, definitions)| provides(*definitions))
+        .map(|(name, _)| name)
+        .min()
+        .cloned()
+}
+
+// `export * from './foo'`, as opposed to `export * as foo from './foo'`
+fn is_star_export(re_export: &ReExportedSymbol) -> bool {
+    re_export.imported == ExportedSymbol::Namespace && re_export.renamed_to.is_none()
+}
+
+// Appends definitions of a name, with a higher priority than the existing ones
+fn add_definitions(
+    exported_definitions: &mut ExportedDefinitions,
+    name: ExportedSymbol,
+    definitions: Vec<SymbolDefinition>,
+) {
+    let name_definitions = exported_definitions.entry(name).or_default();
+    for definition in definitions {
+        // the same definition reached through several re-exports is not a collision
+        name_definitions.retain(|existing| *existing != definition);
+        name_definitions.push(definition);
+    }
+}
+
+// Resolves the definitions of the names exported from files, memoized so
+// barrels shared between files are only resolved once. Ambiguous names are
+// recorded as each file is resolved.
+struct DefinitionResolver<'a, F> {
+    files: F,
+    resolved: AHashMap<PathBuf, Rc<ExportedDefinitions>>,
+    in_progress: AHashSet<PathBuf>,
+    ambiguous_reexports: Vec<AmbiguousReexport>,
+    _files: PhantomData<&'a ResolvedImportExportInfo>,
+}
+
+impl<'a, F: Fn(&Path) -> Option<&'a ResolvedImportExportInfo>> DefinitionResolver<'a, F> {
+    fn new(files: F) -> Self {
+        Self {
+            files,
+            resolved: AHashMap::default(),
+            in_progress: AHashSet::default(),
+            ambiguous_reexports: Vec::new(),
+            _files: PhantomData,
+        }
+    }
+
+    fn exported_definitions(&mut self, file_path: &Path) -> Rc<ExportedDefinitions> {
+        if let Some(definitions) = self.resolved.get(file_path) {
+            return definitions.clone();
+        }
+        let info = match (self.files)(file_path) {
+            Some(info) => info,
+            None => return Rc::default(),
+        };
+        if !self.in_progress.insert(file_path.to_path_buf()) {
+            // a cycle of re-exports adds nothing beyond what is already being resolved
+            return Rc::default();
+        }
+
+        // (position in the file, exported name, definitions)
+        let mut explicit_entries: Vec<(BytePos, ExportedSymbol, Vec<SymbolDefinition>)> =
+            Vec::new();
+        // (position in the file, source file, exported name, definitions)
+        let mut star_entries: Vec<(BytePos, &PathBuf, ExportedSymbol, Vec<SymbolDefinition>)> =
+            Vec::new();
+        for (symbol, meta) in info.exported_ids.iter() {
+            explicit_entries.push((
+                meta.span.lo,
+                symbol.clone(),
+                vec![SymbolDefinition {
+                    file_path: file_path.to_path_buf(),
+                    symbol: symbol.clone(),
+                }],
+            ));
+        }
+        for (source_path, re_exports) in info.export_from_symbols.iter() {
+            let source_known = (self.files)(source_path).is_some();
+            for (re_export, meta) in re_exports.iter() {
+                let source_definition = || SymbolDefinition {
+                    file_path: source_path.clone(),
+                    symbol: re_export.imported.clone(),
+                };
+                match (&re_export.imported, &re_export.renamed_to, source_known) {
+                    // `export * from './foo'` re-exports every name but the default export
+                    (ExportedSymbol::Namespace, None, true) => {
+                        for (name, definitions) in self.exported_definitions(source_path).iter() {
+                            if *name != ExportedSymbol::Default {
+                                star_entries.push((
+                                    meta.span.lo,
+                                    source_path,
+                                    name.clone(),
+                                    definitions.clone(),
+                                ));
+                            }
+                        }
+                    }
+                    // the names of files outside of the graph are unknown
+                    (ExportedSymbol::Namespace, None, false) => {}
+                    // `export * as foo from './foo'` defines a new namespace
+                    (ExportedSymbol::Namespace, Some(_), _) | (_, _, false) => {
+                        explicit_entries.push((
+                            meta.span.lo,
+                            re_export.exported_name().clone(),
+                            vec![source_definition()],
+                        ));
+                    }
+                    (imported, _, true) => {
+                        let definitions = self
+                            .exported_definitions(source_path)
+                            .get(imported)
+                            .cloned()
+                            .unwrap_or_else(|| vec![source_definition()]);
+                        explicit_entries.push((
+                            meta.span.lo,
+                            re_export.exported_name().clone(),
+                            definitions,
+                        ));
+                    }
+                }
+            }
+        }
+        explicit_entries.sort_by(|a, b| a.0.cmp(&b.0));
+        star_entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let explicit_names: AHashSet<&ExportedSymbol> =
+            explicit_entries.iter().map(|(_, name, _)| name).collect();
+
+        // group the names from `export *` by name, keeping the order of the file
+        let mut star_names: Vec<ExportedSymbol> = Vec::new();
+        let mut star_contributions: AHashMap<
+            ExportedSymbol,
+            Vec<(&PathBuf, Vec<SymbolDefinition>)>,
+        > = AHashMap::default();
+        for (_, source_path, name, definitions) in star_entries {
+            let contributions = star_contributions.entry(name.clone()).or_default();
+            if contributions.is_empty() {
+                star_names.push(name);
+            }
+            contributions.push((source_path, definitions));
+        }
+
+        // apply the names from `export *` first, so explicit exports take priority
+        let mut exported_definitions = ExportedDefinitions::default();
+        for name in star_names {
+            let contributions = star_contributions.remove(&name).unwrap_or_default();
+            if !explicit_names.contains(&name) {
+                // each source provides the definition its importers would get
+                let mut provided: Vec<&SymbolDefinition> = contributions
+                    .iter()
+                    .filter_map(|(_, definitions)| definitions.last())
+                    .collect();
+                provided.sort_by(|a, b| (&a.file_path, &a.symbol).cmp(&(&b.file_path, &b.symbol)));
+                provided.dedup();
+                if provided.len() > 1 {
+                    let mut sources: Vec<PathBuf> = Vec::new();
+                    for (source_path, _) in contributions.iter() {
+                        if !sources.contains(*source_path) {
+                            sources.push((*source_path).clone());
+                        }
+                    }
+                    self.ambiguous_reexports.push(AmbiguousReexport {
+                        file_path: file_path.to_path_buf(),
+                        name,
+                        sources,
+                    });
+                    continue;
+                }
+            }
+            for (_, definitions) in contributions {
+                add_definitions(&mut exported_definitions, name.clone(), definitions);
+            }
+        }
+        for (_, name, definitions) in explicit_entries {
+            add_definitions(&mut exported_definitions, name, definitions);
+        }
+
+        let exported_definitions = Rc::new(exported_definitions);
+        self.in_progress.remove(file_path);
+        self.resolved
+            .insert(file_path.to_path_buf(), exported_definitions.clone());
+        exported_definitions
+    }
+}
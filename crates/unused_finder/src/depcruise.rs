@@ -0,0 +1,109 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+use swc_ecma_loader::NODE_BUILTINS;
+
+use crate::{
+    report::{display_path, is_used},
+    UnusedFinderResult,
+};
+
+/// The import graph of an UnusedFinderResult, in the `modules` shape of
+/// dependency-cruiser's JSON output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepcruiseOutput {
+    pub modules: Vec<DepcruiseModule>,
+}
+
+/// A file in the graph, with the modules it depends on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepcruiseModule {
+    pub source: String,
+    pub dependencies: Vec<DepcruiseDependency>,
+    /// True if the file is unused
+    pub orphan: bool,
+}
+
+/// A module imported, required, or re-exported from a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepcruiseDependency {
+    /// The path of a local dependency, or the module name of an npm or core
+    /// dependency
+    pub resolved: String,
+    pub dependency_types: Vec<DepcruiseDependencyType>,
+    pub core_module: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepcruiseDependencyType {
+    /// A file within the repo
+    Local,
+    /// A package from outside the repo
+    Npm,
+    /// A built-in node module (e.g. `fs`)
+    Core,
+}
+
+impl DepcruiseDependencyType {
+    fn of_resolved_path(resolved_path: &Path) -> Self {
+        // imports of external packages are left unresolved, as the module name
+        if resolved_path.is_absolute() {
+            return DepcruiseDependencyType::Local;
+        }
+        let module_name = resolved_path.to_string_lossy();
+        let module_name = module_name.strip_prefix("node:").unwrap_or(&module_name);
+        if NODE_BUILTINS.contains(&module_name) {
+            DepcruiseDependencyType::Core
+        } else {
+            DepcruiseDependencyType::Npm
+        }
+    }
+}
+
+impl From<&UnusedFinderResult> for DepcruiseOutput {
+    fn from(value: &UnusedFinderResult) -> Self {
+        let base_dir = value.path_display.base_dir(&value.repo_root);
+        let mut modules: Vec<DepcruiseModule> = value
+            .graph
+            .files
+            .iter()
+            .map(|file| {
+                // a file can import the same module more than once (e.g. an
+                // import and a re-export), but depends on it only once
+                let dependencies: BTreeMap<String, DepcruiseDependencyType> = file
+                    .import_export_info
+                    .iter_imported_symbols_meta()
+                    .map(|(resolved_path, _, _)| {
+                        let dependency_type =
+                            DepcruiseDependencyType::of_resolved_path(resolved_path);
+                        let resolved = match dependency_type {
+                            DepcruiseDependencyType::Local => {
+                                display_path(resolved_path, base_dir.as_deref())
+                            }
+                            _ => resolved_path.to_string_lossy().to_string(),
+                        };
+                        (resolved, dependency_type)
+                    })
+                    .collect();
+
+                DepcruiseModule {
+                    source: display_path(&file.file_path, base_dir.as_deref()),
+                    dependencies: dependencies
+                        .into_iter()
+                        .map(|(resolved, dependency_type)| DepcruiseDependency {
+                            resolved,
+                            dependency_types: vec![dependency_type],
+                            core_module: dependency_type == DepcruiseDependencyType::Core,
+                        })
+                        .collect(),
+                    orphan: !is_used(&file.file_tags),
+                }
+            })
+            .collect();
+        modules.sort_by(|a, b| a.source.cmp(&b.source));
+
+        DepcruiseOutput { modules }
+    }
+}
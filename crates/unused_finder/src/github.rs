@@ -0,0 +1,176 @@
+//! GitHub Actions workflow commands, which annotate the files of a pull
+//! request with the results of a run without uploading a SARIF file.
+//!
+//! See <https://docs.github.com/en/actions/writing-workflows/choosing-what-your-workflow-does/workflow-commands-for-github-actions>
+
+use std::fmt::Display;
+use std::path::Path;
+
+use path_slash::PathExt;
+
+use crate::report::UnusedFinderReport;
+
+/// The severity of a workflow command annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkflowCommandLevel {
+    Warning,
+    Error,
+}
+
+/// A `::warning` or `::error` workflow command, annotating a file or one of
+/// its lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkflowCommand {
+    pub level: WorkflowCommandLevel,
+    /// The annotated file, relative to the root of the repo
+    pub file: String,
+    /// The 1-based line to annotate, or None to annotate the whole file
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl WorkflowCommand {
+    /// Creates a command annotating a file, given its path and the root of
+    /// the repo it is in. Paths outside of the repo root are kept as-is.
+    pub fn new(
+        level: WorkflowCommandLevel,
+        repo_root: &Path,
+        file_path: &Path,
+        message: impl Into<String>,
+    ) -> Self {
+        let file_path = repo_root.join(file_path);
+        let relative_path = file_path.strip_prefix(repo_root).unwrap_or(&file_path);
+        WorkflowCommand {
+            level,
+            file: relative_path.to_slash_lossy().to_string(),
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    /// Annotates a single line of the file instead of the whole file
+    pub fn at_line(mut self, line: Option<usize>) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl Display for WorkflowCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.level {
+            WorkflowCommandLevel::Warning => "warning",
+            WorkflowCommandLevel::Error => "error",
+        };
+        write!(f, "::{} file={}", level, escape_property(&self.file))?;
+        if let Some(line) = self.line {
+            write!(f, ",line={}", line)?;
+        }
+        write!(f, "::{}", escape_data(&self.message))
+    }
+}
+
+// the message of a command ends at the end of its line
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+// properties are also delimited by `,` and `::`
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+// Gets the 1-based line of the start of a span in the source of its file.
+//
+// Each file is parsed with its own SourceMap, where the file starts at
+// BytePos(1) because swc reserves BytePos(0) for dummy spans.
+fn line_of_span_start(src: &str, start: u32) -> usize {
+    let offset = (start as usize).saturating_sub(1).min(src.len());
+    src.as_bytes()[..offset]
+        .iter()
+        .filter(|byte| **byte == b'\n')
+        .count()
+        + 1
+}
+
+impl UnusedFinderReport {
+    /// Gets a warning for each unused file, and for each unused symbol of the
+    /// files that are partially unused.
+    ///
+    /// The paths of the report are resolved against `repo_root`. Symbols are
+    /// annotated at the line they are exported on, if their file can be read.
+    pub fn github_workflow_commands(&self, repo_root: &Path) -> Vec<WorkflowCommand> {
+        let mut unused_files: Vec<&String> = self.unused_files.iter().collect();
+        unused_files.sort();
+        let mut commands: Vec<WorkflowCommand> = unused_files
+            .iter()
+            .map(|file_path| {
+                WorkflowCommand::new(
+                    WorkflowCommandLevel::Warning,
+                    repo_root,
+                    Path::new(file_path),
+                    "This file is completely unused",
+                )
+            })
+            .collect();
+
+        let mut unused_symbols: Vec<_> = self
+            .unused_symbols
+            .iter()
+            .filter(|(file_path, _)| !self.unused_files.contains(file_path))
+            .collect();
+        unused_symbols.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (file_path, symbols) in unused_symbols {
+            let src = std::fs::read_to_string(repo_root.join(file_path)).ok();
+            let mut symbols: Vec<_> = symbols.iter().collect();
+            symbols.sort_by(|a, b| (a.start, &a.id).cmp(&(b.start, &b.id)));
+            for symbol in symbols {
+                let message = if symbol.locally_used {
+                    format!("{} is exported, but only used in this file", symbol.id)
+                } else {
+                    format!("{} is exported, but never used", symbol.id)
+                };
+                commands.push(
+                    WorkflowCommand::new(
+                        WorkflowCommandLevel::Warning,
+                        repo_root,
+                        Path::new(file_path),
+                        message,
+                    )
+                    .at_line(
+                        src.as_deref()
+                            .map(|src| line_of_span_start(src, symbol.start)),
+                    ),
+                );
+            }
+        }
+
+        commands
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{WorkflowCommand, WorkflowCommandLevel};
+
+    #[test]
+    fn test_workflow_command_escaping() {
+        let command = WorkflowCommand::new(
+            WorkflowCommandLevel::Error,
+            Path::new("/repo"),
+            Path::new("/repo/src/a,b:c.ts"),
+            "100% unused\nsee the report",
+        )
+        .at_line(Some(4));
+
+        assert_eq!(
+            command.to_string(),
+            "::error file=src/a%2Cb%3Ac.ts,line=4::100%25 unused%0Asee the report"
+        );
+    }
+}
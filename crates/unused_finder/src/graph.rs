@@ -25,6 +25,8 @@ pub struct GraphFile {
     pub symbol_tags: AHashMap<ExportedSymbol, UsedTag>,
     // The path of this file within the graph
     pub file_path: PathBuf,
+    // Name of the package that this file belongs to, if any
+    pub owning_package: Option<String>,
     // Map of re-exported items to the file that they came from
     // Resolved import/export information w
     pub import_export_info: ResolvedImportExportInfo,
@@ -39,6 +41,7 @@ impl GraphFile {
                 Default::default(),
             ),
             file_path: file.source_file_path.clone(),
+            owning_package: file.owning_package.clone(),
             import_export_info: file.import_export_info.clone(),
         }
     }
@@ -122,6 +125,52 @@ impl Graph {
         Graph { path_to_id, files }
     }
 
+    /// Replaces the nodes of files already in the graph with their updated
+    /// source files, which also replaces their outgoing edges. Source files
+    /// that are not in the graph are ignored.
+    ///
+    /// Returns the number of nodes that were replaced.
+    pub fn update_files<'a>(
+        &mut self,
+        source_files: impl Iterator<Item = &'a ResolvedSourceFile>,
+    ) -> usize {
+        let mut updated = 0;
+        for source_file in source_files {
+            if let Some(file_id) = self.path_to_id.get(&source_file.source_file_path) {
+                self.files[*file_id] = GraphFile::new_from_source_file(source_file);
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// Removes the tags of every file and symbol, so the graph can be traversed again
+    pub fn clear_tags(&mut self) {
+        for file in self.files.iter_mut() {
+            file.file_tags = UsedTag::default();
+            file.symbol_tags.clear();
+        }
+    }
+
+    /// Gets the ids of the files that import, require, or re-export from each
+    /// file in the graph, indexed by file id.
+    pub fn importers(&self) -> Vec<Vec<usize>> {
+        let mut importers: Vec<Vec<usize>> = vec![Vec::new(); self.files.len()];
+        for (importer_id, file) in self.files.iter().enumerate() {
+            let mut imported_ids: Vec<usize> = file
+                .import_export_info
+                .iter_imported_symbols_meta()
+                .filter_map(|(path, _, _)| self.path_to_id.get(path).copied())
+                .collect();
+            imported_ids.sort();
+            imported_ids.dedup();
+            for imported_id in imported_ids {
+                importers[imported_id].push(importer_id);
+            }
+        }
+        importers
+    }
+
     pub fn mark_symbol(&mut self, path: &Path, symbol: &ExportedSymbol, tag: UsedTag) {
         let file_id = match self.path_to_id.get(path) {
             Some(id) => *id,
@@ -146,6 +195,7 @@ impl Graph {
         initial_frontier_files: Vec<&Path>,
         initial_frontier_symbols: Vec<(&Path, Vec<ExportedSymbol>)>,
         tag: UsedTag,
+        effect_imports_mark_symbols: bool,
     ) -> Result<()> {
         logger.debug(format!(
             "initial_frontier_files ({}:{}):\n  {}",
@@ -239,7 +289,8 @@ impl Graph {
         // Traverse the graph until we exhaust the frontier
         const MAX_ITERATIONS: usize = 1_000_000;
         for _ in 0..MAX_ITERATIONS {
-            let next_frontier: Vec<Edge> = self.bfs_step(&mut visited, &frontier, tag);
+            let next_frontier: Vec<Edge> =
+                self.bfs_step(&mut visited, &frontier, tag, effect_imports_mark_symbols);
             frontier = next_frontier;
             if frontier.is_empty() {
                 return Ok(());
@@ -253,11 +304,17 @@ impl Graph {
     }
 
     /// Perform a single step of the BFS algorithm, returning the list of files that should be visited next
+    ///
+    /// If `effect_imports_mark_symbols` is false, files only reached through
+    /// side-effect imports in this step are marked as used and have their
+    /// imports traversed, but not their re-exports, since executing a module
+    /// does not use the symbols it exports.
     fn bfs_step(
         &mut self,
         visited: &mut AHashSet<Edge>,
         frontier: &[Edge],
         tag: UsedTag,
+        effect_imports_mark_symbols: bool,
     ) -> Vec<Edge> {
         // get list of unique files that are being visited in this pass
         let mut from_files = frontier
@@ -277,11 +334,21 @@ impl Graph {
             self.files[*file].file_tags |= tag;
         }
 
+        // files reached through an edge that uses their symbols, rather than
+        // only executing them
+        let symbol_files = frontier
+            .iter()
+            .filter(|edge| edge.symbol != ExportedSymbol::ExecutionOnly)
+            .map(|Edge { file_id, .. }| *file_id)
+            .collect::<AHashSet<_>>();
+
         // generate the next frontier in a parallel pass over the files
         let next_frontier_symbols = from_files
             .par_iter()
             .map(|file_id| {
                 let file = &self.files[*file_id];
+                let follow_re_exports =
+                    effect_imports_mark_symbols || symbol_files.contains(file_id);
                 // if the file was not visited before, add all its imports
                 // to the frontier
                 //
@@ -296,7 +363,7 @@ impl Graph {
                         // TODO: should this be a TraversalMode that the graph is parameterized on? e.g.
                         // track USED_ENTRY and USED_ENTRY_AS_TYPE as separate tags?
                         if let Some(meta) = meta {
-                            if meta.is_type_only {
+                            if meta.is_type_only || !follow_re_exports {
                                 return None;
                             }
                         }
@@ -0,0 +1,458 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use swc_common::{source_map::SmallPos, Span};
+
+use crate::{
+    collisions::resolve_exported_definitions,
+    graph::{Graph, GraphFile},
+    parse::{ExportedSymbol, RawImportExportInfo},
+    UsedTagEnum,
+};
+
+/// Everything known about how a single file was analyzed by the last
+/// traversal, to debug why the file or its exports were (or were not)
+/// reported as unused.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAnalysisDump {
+    pub file_path: String,
+    /// How the walk classified the file
+    pub walk: WalkedFileDump,
+    /// The error parsing the file, if it could not be parsed. The imports of
+    /// such a file are found by a lexical scan, without their symbols
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse_error: Option<String>,
+    /// The imports of the file as written, with what each of them resolves to
+    pub imports: Vec<ImportDump>,
+    /// The names the file exports, with the definitions they resolve to after
+    /// following re-exports and expanding `export *`
+    pub exports: Vec<ExportDump>,
+    /// The imports of this file by the other files in the graph
+    pub incoming_edges: Vec<IncomingEdgeDump>,
+    /// The tags of the file. A file in the graph without any tags is unused.
+    pub tags: Vec<UsedTagEnum>,
+    /// Why the traversals started from the file, if they did. A file without
+    /// any reasons is only used through its incoming edges.
+    pub entrypoint_reasons: Vec<EntrypointReason>,
+}
+
+impl FileAnalysisDump {
+    /// Prints the dump as pretty-printed JSON
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("FileAnalysisDump should always be serializable")
+    }
+}
+
+/// How the walk classified a file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkedFileDump {
+    /// False if the file is not part of the graph, e.g. because it was skipped
+    /// or is outside of the root paths
+    pub in_graph: bool,
+    pub owning_package: Option<String>,
+    /// Matched by the `test_files` globs, or within a test directory of its package
+    pub test: bool,
+    /// Ignored by an `.unusedignore` file
+    pub ignored: bool,
+    /// A generated file within a path mirror, whose imports are credited to
+    /// the source file it mirrors
+    pub generated: bool,
+    /// An opaque module, whose own imports are not parsed
+    pub opaque_module: bool,
+    /// Matched by the `skip` config, so the walk did not visit it
+    pub skipped: bool,
+    /// Skip patterns that would have skipped the file if they were matched
+    /// case-insensitively
+    pub skip_near_misses: Vec<String>,
+}
+
+/// The syntax a file is imported with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportKind {
+    /// `import foo, { bar } from './foo'`
+    Static,
+    /// `require('./foo')`
+    Require,
+    /// `import('./foo')`
+    Dynamic,
+    /// `export { foo } from './foo'` and `export * from './foo'`
+    ReExport,
+    /// `import './foo'`
+    EffectOnly,
+}
+
+/// An import of a file as written, and what its specifier resolves to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportDump {
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// The imported names, sorted by name. Only set for static imports and
+    /// re-exports.
+    pub symbols: Vec<ImportedSymbolDump>,
+    /// The path the specifier resolves to. External packages are left
+    /// unresolved, as the specifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution_error: Option<String>,
+}
+
+/// A name imported or re-exported by an import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedSymbolDump {
+    pub name: String,
+    /// The name a re-export exports the symbol as, if it renames it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_to: Option<String>,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// A name exported from a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDump {
+    pub name: String,
+    /// The span of the export. Names from `export *` have no span of their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<u32>,
+    /// The file the name is re-exported from by name, if it is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reexported_from: Option<String>,
+    /// True if the name comes from an `export *`
+    pub from_star_export: bool,
+    pub tags: Vec<UsedTagEnum>,
+    /// Every definition of the name, from the lowest to the highest priority.
+    /// Importers of the name get the last one.
+    pub definitions: Vec<ExportDefinitionDump>,
+}
+
+/// A file and symbol that an exported name resolves to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDefinitionDump {
+    pub file_path: String,
+    pub symbol: String,
+}
+
+/// An import of a file by another file in the graph
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingEdgeDump {
+    pub importer: String,
+    /// The imported symbol, in its canonical string form
+    pub symbol: String,
+    pub kind: ImportKind,
+    /// True for type-only re-exports, which the traversals do not follow
+    pub type_only: bool,
+    /// The traversals that followed the edge
+    pub followed_by: Vec<UsedTagEnum>,
+}
+
+/// Why a traversal started from a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "reason")]
+pub enum EntrypointReason {
+    /// The file is exported by an entry package
+    PackageExport { package: String },
+    /// The file is only exported because its package has no "exports" field.
+    /// With `report_unused_entry_exports`, it is visited without marking its
+    /// symbols as used.
+    ImplicitPackageExport { package: String },
+    /// The file is the public barrel of its package, and
+    /// `barrel_exports_count_as_used` marks its re-exports as used
+    PackageBarrel { symbols: Vec<String> },
+    /// Symbols annotated with one of the `keep_alive_decorators`
+    KeepAlive { symbols: Vec<String> },
+    /// Symbols allowed to be unused by an `@ALLOW-UNUSED-EXPORT` comment or
+    /// one of the `keep_pragmas`
+    AllowedUnused { symbols: Vec<String> },
+    /// The file is ignored by an `.unusedignore` file
+    IgnoreFile,
+    /// The file is a test file
+    TestFile,
+}
+
+fn symbol_names(symbols: &[ExportedSymbol]) -> Vec<String> {
+    let mut names: Vec<String> = symbols.iter().map(ToString::to_string).collect();
+    names.sort();
+    names
+}
+
+impl EntrypointReason {
+    pub(crate) fn package_barrel(symbols: &[ExportedSymbol]) -> Self {
+        Self::PackageBarrel {
+            symbols: symbol_names(symbols),
+        }
+    }
+
+    pub(crate) fn keep_alive(symbols: &[ExportedSymbol]) -> Self {
+        Self::KeepAlive {
+            symbols: symbol_names(symbols),
+        }
+    }
+
+    pub(crate) fn allowed_unused(symbols: &[ExportedSymbol]) -> Self {
+        Self::AllowedUnused {
+            symbols: symbol_names(symbols),
+        }
+    }
+}
+
+/// The files that the traversals start from because entry packages export
+/// them, to debug which packages `entry_packages` selects and what they export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntrypointsDump {
+    /// The entry packages that export at least one file, sorted by name
+    pub packages: Vec<EntryPackageDump>,
+    /// The entry packages that do not explicitly export any source file,
+    /// sorted by name
+    pub packages_without_entrypoints: Vec<EntryPackageWithoutEntrypoints>,
+}
+
+impl EntrypointsDump {
+    /// Prints the dump as pretty-printed JSON
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("EntrypointsDump should always be serializable")
+    }
+}
+
+/// An entry package and the files it exports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryPackageDump {
+    pub package: String,
+    /// The `entry_packages` rule that selected the package. The implicit root
+    /// package of a repo without any package.json has no rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_rule: Option<String>,
+    /// The exported files, sorted
+    pub entrypoints: Vec<String>,
+}
+
+/// A package selected by `entry_packages` that does not explicitly export any
+/// of the walked source files through its "exports", "main" or "module" fields.
+///
+/// This is usually a misconfiguration: the package keeps none of the code
+/// that only it uses alive, so that code is reported as unused.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryPackageWithoutEntrypoints {
+    pub package: String,
+    pub package_json_path: String,
+    /// The `entry_packages` rule that selected the package
+    pub match_rule: String,
+    /// The entrypoint fields that are set in the package.json, none of which
+    /// point to a walked source file
+    pub entrypoint_fields: Vec<String>,
+}
+
+impl std::fmt::Display for EntryPackageWithoutEntrypoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Entry package {} ({}), selected by entryPackages rule {:?}, ",
+            self.package, self.package_json_path, self.match_rule,
+        )?;
+        write!(f, "does not explicitly export any source file: ")?;
+        if self.entrypoint_fields.is_empty() {
+            write!(
+                f,
+                "none of the \"exports\", \"main\" or \"module\" fields are set"
+            )
+        } else {
+            write!(
+                f,
+                "checked the {:?} fields, which do not point to any source file",
+                self.entrypoint_fields
+            )
+        }
+    }
+}
+
+/// Lists the imports of a file as written, resolving each specifier with
+/// `resolve`, sorted by specifier.
+pub(crate) fn dump_imports(
+    info: &RawImportExportInfo,
+    resolve: impl Fn(&str) -> anyhow::Result<PathBuf>,
+    display: impl Fn(&Path) -> String,
+) -> Vec<ImportDump> {
+    let imported_symbol = |specifier: &str, symbol: &ExportedSymbol| {
+        let span = info
+            .import_spans
+            .get(specifier)
+            .and_then(|spans| spans.get(symbol))
+            .copied()
+            .unwrap_or_default();
+        ImportedSymbolDump {
+            name: symbol.to_string(),
+            renamed_to: None,
+            start: span.lo.to_u32(),
+            end: span.hi.to_u32(),
+        }
+    };
+
+    let mut entries: Vec<(&String, ImportKind, Vec<ImportedSymbolDump>)> = Vec::new();
+    for (specifier, symbols) in info.imported_path_ids.iter() {
+        let mut symbols: Vec<ImportedSymbolDump> = symbols
+            .iter()
+            .map(|symbol| imported_symbol(specifier, symbol))
+            .collect();
+        symbols.sort_by(|a, b| a.name.cmp(&b.name));
+        entries.push((specifier, ImportKind::Static, symbols));
+    }
+    for specifier in info.require_paths.iter() {
+        entries.push((specifier, ImportKind::Require, Vec::new()));
+    }
+    for specifier in info.imported_paths.iter() {
+        entries.push((specifier, ImportKind::Dynamic, Vec::new()));
+    }
+    for (specifier, re_exports) in info.export_from_ids.iter() {
+        let mut symbols: Vec<ImportedSymbolDump> = re_exports
+            .iter()
+            .map(|(re_export, meta)| ImportedSymbolDump {
+                name: re_export.imported.to_string(),
+                renamed_to: re_export.renamed_to.as_ref().map(ToString::to_string),
+                start: meta.span.lo.to_u32(),
+                end: meta.span.hi.to_u32(),
+            })
+            .collect();
+        symbols.sort_by(|a, b| a.name.cmp(&b.name));
+        entries.push((specifier, ImportKind::ReExport, symbols));
+    }
+    for specifier in info.executed_paths.iter() {
+        entries.push((specifier, ImportKind::EffectOnly, Vec::new()));
+    }
+    entries.sort_by(|a, b| a.0.cmp(b.0).then(a.1.cmp(&b.1)));
+
+    entries
+        .into_iter()
+        .map(|(specifier, kind, symbols)| {
+            let (resolved_path, resolution_error) = match resolve(specifier) {
+                Ok(resolved_path) => (Some(display(&resolved_path)), None),
+                Err(e) => (None, Some(format!("{e:#}"))),
+            };
+            ImportDump {
+                specifier: specifier.clone(),
+                kind,
+                symbols,
+                resolved_path,
+                resolution_error,
+            }
+        })
+        .collect()
+}
+
+/// Lists the names a file in the graph exports, sorted by name
+pub(crate) fn dump_exports(
+    graph: &Graph,
+    file: &GraphFile,
+    display: impl Fn(&Path) -> String,
+) -> Vec<ExportDump> {
+    let info = &file.import_export_info;
+    resolve_exported_definitions(graph, &file.file_path)
+        .into_iter()
+        .map(|(name, definitions)| {
+            let named_re_export = info
+                .export_from_symbols
+                .iter()
+                .find_map(|(path, re_exports)| {
+                    re_exports
+                        .iter()
+                        .find(|(re_export, _)| *re_export.exported_name() == name)
+                        .map(|(_, meta)| (path, meta.span))
+                });
+            let (span, reexported_from): (Option<Span>, Option<String>) =
+                match (info.exported_ids.get(&name), named_re_export) {
+                    (Some(meta), _) => (Some(meta.span), None),
+                    (None, Some((path, span))) => (Some(span), Some(display(path))),
+                    (None, None) => (None, None),
+                };
+            ExportDump {
+                name: name.to_string(),
+                start: span.map(|span| span.lo.to_u32()),
+                end: span.map(|span| span.hi.to_u32()),
+                reexported_from,
+                from_star_export: span.is_none(),
+                tags: file
+                    .symbol_tags
+                    .get(&name)
+                    .copied()
+                    .unwrap_or_default()
+                    .into(),
+                definitions: definitions
+                    .iter()
+                    .map(|definition| ExportDefinitionDump {
+                        file_path: display(&definition.file_path),
+                        symbol: definition.symbol.to_string(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Lists the imports of a file by the other files in the graph, sorted by
+/// importer.
+///
+/// The traversals follow every import of each file they visit, except for
+/// type-only re-exports, so an edge was followed by exactly the traversals
+/// that tagged its importer.
+pub(crate) fn dump_incoming_edges(
+    graph: &Graph,
+    file_path: &Path,
+    display: impl Fn(&Path) -> String,
+) -> Vec<IncomingEdgeDump> {
+    let mut incoming_edges: Vec<IncomingEdgeDump> = Vec::new();
+    for importer in graph.files.iter() {
+        let info = &importer.import_export_info;
+        let mut push_edge = |symbol: &ExportedSymbol, kind: ImportKind, type_only: bool| {
+            incoming_edges.push(IncomingEdgeDump {
+                importer: display(&importer.file_path),
+                symbol: symbol.to_string(),
+                kind,
+                type_only,
+                followed_by: if type_only {
+                    Vec::new()
+                } else {
+                    importer.file_tags.into()
+                },
+            })
+        };
+
+        for symbol in info.imported_symbols.get(file_path).into_iter().flatten() {
+            push_edge(symbol, ImportKind::Static, false);
+        }
+        if info.require_paths.contains(file_path) {
+            push_edge(&ExportedSymbol::Namespace, ImportKind::Require, false);
+        }
+        if info.imported_paths.contains(file_path) {
+            push_edge(&ExportedSymbol::Namespace, ImportKind::Dynamic, false);
+        }
+        for (re_export, meta) in info
+            .export_from_symbols
+            .get(file_path)
+            .into_iter()
+            .flatten()
+        {
+            push_edge(&re_export.imported, ImportKind::ReExport, meta.is_type_only);
+        }
+        if info.executed_paths.contains(file_path) {
+            push_edge(
+                &ExportedSymbol::ExecutionOnly,
+                ImportKind::EffectOnly,
+                false,
+            );
+        }
+    }
+    incoming_edges.sort();
+    incoming_edges
+}
@@ -13,22 +13,69 @@ extern crate pretty_assertions;
 extern crate test_tmpdir;
 
 mod cfg;
+mod collisions;
+mod depcruise;
+mod github;
 mod graph;
 mod ignore_file;
+mod inspect;
+mod package_graph;
 mod parse;
+mod redact;
 mod report;
+mod report_diff;
 mod tag;
 #[cfg(test)]
 mod test;
+mod timings;
 mod unused_finder;
 mod walk;
 mod walked_file;
+mod workspaces;
 
-pub use cfg::{UnusedFinderConfig, UnusedFinderJSONConfig};
-pub use parse::data::ResolvedImportExportInfo;
-pub use report::{SymbolReport, SymbolReportWithTags, UnusedFinderReport};
+pub use cfg::{
+    apply_config_overrides,
+    effective_config::EffectiveConfig,
+    path_mirrors::{PathMirror, PathMirrorRules},
+    ResolverTargetEnv, UnusedFinderConfig, UnusedFinderJSONConfig,
+};
+pub use collisions::{AmbiguousReexport, BarrelBypassImport, SymbolCollision, SymbolDefinition};
+pub use depcruise::{
+    DepcruiseDependency, DepcruiseDependencyType, DepcruiseModule, DepcruiseOutput,
+};
+pub use github::{WorkflowCommand, WorkflowCommandLevel};
+pub use inspect::{
+    EntryPackageDump, EntryPackageWithoutEntrypoints, EntrypointReason, EntrypointsDump,
+    ExportDefinitionDump, ExportDump, FileAnalysisDump, ImportDump, ImportKind, ImportedSymbolDump,
+    IncomingEdgeDump, WalkedFileDump,
+};
+pub use package_graph::{
+    CollapsedPackageGraph, PackageDependency, PackageEdge, PackageGraph, PackageGraphDiff,
+    PackageNode, UNROOTED_PACKAGE,
+};
+pub use parse::data::{
+    ExportDeclaration, ExportedSymbol, ExportedSymbolMetadata, RawImportExportInfo,
+    ReExportedSymbol, ResolvedImportExportInfo, UnusedImport,
+};
+pub use redact::{RedactionMapping, Redactor};
+pub use report::{
+    split_declaration_suffix, AmbiguousReexportReport, AmbiguousSymbolNameReport,
+    AmbiguousSymbolNames, BarrelBypassImportReport, DegradedFileReport, ImportKindCounts,
+    LargeModuleReport, PathDisplay, SameNameExportReport, SymbolCollisionReport,
+    SymbolDefinitionReport, SymbolRef, SymbolReport, SymbolReportWithTags, UnusedFinderReport,
+    UnusedImportReport, TYPE_DECLARATION_SUFFIX,
+};
+pub use report_diff::{ReportDiffOptions, UnusedFinderReportDiff, UnusedSymbolDiff};
 pub use tag::UsedTagEnum;
-pub use unused_finder::{UnusedFinder, UnusedFinderResult};
+pub use timings::UnusedFinderTimings;
+pub use unused_finder::{
+    ImportSite, ResolvedImport, SymbolOrigin, SymbolStatus, UnusedFinder, UnusedFinderResult,
+};
+pub use walk::{
+    walk_repo, FileVisitor, PackageDiscovery, RepoPackages, VisitedFile, WalkConfig, WalkSummary,
+    WalkedFiles, DEFAULT_OPAQUE_MODULE_EXTENSIONS,
+};
+pub use walked_file::{ResolvedSourceFile, WalkedPackage, WalkedSourceFile};
 
 pub fn find_unused_items(
     logger: impl logger::Logger + Sync,
@@ -0,0 +1,399 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use path_utils::Glob;
+use serde::{Deserialize, Serialize};
+
+/// Name of the package that files without an owning package are collapsed
+/// into, in a [`CollapsedPackageGraph`]
+pub const UNROOTED_PACKAGE: &str = "(unrooted)";
+
+/// Dependencies between the packages of a repo, keyed by the name of the
+/// importing package.
+///
+/// Serializes as a map from each package to the packages it depends on, so a
+/// snapshot taken on one commit can be compared against another with `diff`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PackageGraph {
+    pub dependencies: HashMap<String, HashSet<String>>,
+}
+
+/// A dependency of one package on another
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PackageDependency {
+    pub from: String,
+    pub to: String,
+}
+
+/// The package coupling introduced by a package graph, relative to a baseline
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageGraphDiff {
+    /// Dependencies that are not in the baseline, sorted
+    pub new_dependencies: Vec<PackageDependency>,
+    /// Package cycles that are not part of a cycle of the baseline, each
+    /// listing its packages in sorted order
+    pub new_cycles: Vec<Vec<String>>,
+}
+
+impl PackageGraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.new_dependencies.is_empty() && self.new_cycles.is_empty()
+    }
+}
+
+/// The files of a package in a [`CollapsedPackageGraph`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackageNode {
+    pub files: usize,
+    pub unused_files: usize,
+}
+
+/// The imports from the files of one package into the files of another
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackageEdge {
+    /// Number of file-level edges, i.e. pairs of importing and imported files
+    pub file_edges: usize,
+    /// Number of imports of each symbol across those edges. Side-effect
+    /// imports are not counted.
+    pub symbols: BTreeMap<String, usize>,
+}
+
+impl PackageEdge {
+    /// Gets the `n` most imported symbols, most imported first, then by name.
+    pub fn top_symbols(&self, n: usize) -> Vec<&str> {
+        let mut symbols: Vec<(&String, &usize)> = self.symbols.iter().collect();
+        symbols.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+        symbols
+            .into_iter()
+            .take(n)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// The file-level import graph collapsed into packages, for a high-level view
+/// of a repo. Files without an owning package are collapsed into
+/// [`UNROOTED_PACKAGE`], and imports within a package are dropped.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CollapsedPackageGraph {
+    pub packages: BTreeMap<String, PackageNode>,
+    /// Edges keyed by the importing and the imported package
+    pub edges: BTreeMap<(String, String), PackageEdge>,
+}
+
+impl CollapsedPackageGraph {
+    /// Gets the dependencies between the packages of this graph, leaving out
+    /// [`UNROOTED_PACKAGE`].
+    pub fn to_package_graph(&self) -> PackageGraph {
+        let mut dependencies: HashMap<String, HashSet<String>> = self
+            .packages
+            .keys()
+            .filter(|package| *package != UNROOTED_PACKAGE)
+            .map(|package| (package.clone(), HashSet::new()))
+            .collect();
+        for (from, to) in self.edges.keys() {
+            if from == UNROOTED_PACKAGE || to == UNROOTED_PACKAGE {
+                continue;
+            }
+            if let Some(package_dependencies) = dependencies.get_mut(from) {
+                package_dependencies.insert(to.clone());
+            }
+        }
+        PackageGraph::from(dependencies)
+    }
+
+    /// Keeps the packages whose names match `filter_glob`, along with every
+    /// package that depends on them or that they depend on, directly or
+    /// through other packages.
+    pub fn filter(&self, filter_glob: &Glob) -> CollapsedPackageGraph {
+        let matched: Vec<&String> = self
+            .packages
+            .keys()
+            .filter(|package| filter_glob.matches(package))
+            .collect();
+
+        let mut kept: HashSet<&String> = matched.iter().copied().collect();
+        // expand upwards through importers, then downwards through imports
+        for upwards in [true, false] {
+            let mut visited: HashSet<&String> = matched.iter().copied().collect();
+            let mut frontier = matched.clone();
+            while let Some(package) = frontier.pop() {
+                for (from, to) in self.edges.keys() {
+                    let (source, next) = if upwards { (to, from) } else { (from, to) };
+                    if source == package && visited.insert(next) {
+                        frontier.push(next);
+                    }
+                }
+            }
+            kept.extend(visited);
+        }
+
+        CollapsedPackageGraph {
+            packages: self
+                .packages
+                .iter()
+                .filter(|(package, _)| kept.contains(package))
+                .map(|(package, node)| (package.clone(), node.clone()))
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|((from, to), _)| kept.contains(from) && kept.contains(to))
+                .map(|(key, edge)| (key.clone(), edge.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<HashMap<String, HashSet<String>>> for PackageGraph {
+    fn from(dependencies: HashMap<String, HashSet<String>>) -> Self {
+        PackageGraph { dependencies }
+    }
+}
+
+impl PackageGraph {
+    /// Finds the groups of packages that depend on each other, directly or
+    /// through other packages of the group.
+    ///
+    /// These are the strongly connected components of the graph with more
+    /// than one package, or with a package that depends on itself. Packages
+    /// are sorted within each cycle, and cycles are sorted by their packages.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut cycles: Vec<Vec<String>> = self
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|component| match component.as_slice() {
+                [package] => self
+                    .dependencies
+                    .get(package)
+                    .is_some_and(|dependencies| dependencies.contains(package)),
+                _ => true,
+            })
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    /// Compares this graph against a baseline, e.g. the package graph of the
+    /// target branch, reporting the dependencies and cycles it introduces.
+    ///
+    /// A cycle is only new if its packages are not all part of a single cycle
+    /// of the baseline, so breaking up an existing cycle is not reported.
+    pub fn diff(&self, baseline: &PackageGraph) -> PackageGraphDiff {
+        let mut new_dependencies: Vec<PackageDependency> = self
+            .dependencies
+            .iter()
+            .flat_map(|(from, dependencies)| {
+                let baseline_dependencies = baseline.dependencies.get(from);
+                dependencies
+                    .iter()
+                    .filter(move |to| {
+                        !baseline_dependencies.is_some_and(|baseline| baseline.contains(*to))
+                    })
+                    .map(move |to| PackageDependency {
+                        from: from.clone(),
+                        to: to.clone(),
+                    })
+            })
+            .collect();
+        new_dependencies.sort();
+
+        let baseline_cycles: Vec<HashSet<String>> = baseline
+            .cycles()
+            .into_iter()
+            .map(|cycle| cycle.into_iter().collect())
+            .collect();
+        let new_cycles = self
+            .cycles()
+            .into_iter()
+            .filter(|cycle| {
+                !baseline_cycles.iter().any(|baseline_cycle| {
+                    cycle.iter().all(|package| baseline_cycle.contains(package))
+                })
+            })
+            .collect();
+
+        PackageGraphDiff {
+            new_dependencies,
+            new_cycles,
+        }
+    }
+
+    // Tarjan's algorithm, iterating over packages in sorted order so the
+    // components come out in a stable order
+    fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        let mut packages: BTreeSet<&String> = self.dependencies.keys().collect();
+        packages.extend(self.dependencies.values().flatten());
+        let packages: Vec<&String> = packages.into_iter().collect();
+        let package_ids: HashMap<&String, usize> = packages
+            .iter()
+            .enumerate()
+            .map(|(id, package)| (*package, id))
+            .collect();
+        let edges: Vec<Vec<usize>> = packages
+            .iter()
+            .map(|package| {
+                let mut edges: Vec<usize> = self
+                    .dependencies
+                    .get(*package)
+                    .into_iter()
+                    .flatten()
+                    .map(|dependency| package_ids[dependency])
+                    .collect();
+                edges.sort();
+                edges
+            })
+            .collect();
+
+        let mut tarjan = Tarjan {
+            edges: &edges,
+            indices: vec![None; packages.len()],
+            low_links: vec![0; packages.len()],
+            on_stack: vec![false; packages.len()],
+            stack: Vec::new(),
+            next_index: 0,
+            components: Vec::new(),
+        };
+        for id in 0..packages.len() {
+            if tarjan.indices[id].is_none() {
+                tarjan.visit(id);
+            }
+        }
+
+        tarjan
+            .components
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|id| packages[id].clone())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    // recursion is bounded by the number of packages, which stays small
+    fn visit(&mut self, id: usize) {
+        self.indices[id] = Some(self.next_index);
+        self.low_links[id] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(id);
+        self.on_stack[id] = true;
+
+        for &dependency in self.edges[id].iter() {
+            match self.indices[dependency] {
+                None => {
+                    self.visit(dependency);
+                    self.low_links[id] = self.low_links[id].min(self.low_links[dependency]);
+                }
+                Some(index) if self.on_stack[dependency] => {
+                    self.low_links[id] = self.low_links[id].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_links[id]) == self.indices[id] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == id {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+
+    use super::{PackageDependency, PackageGraph, PackageGraphDiff};
+
+    fn package_graph(edges: &[(&str, &str)]) -> PackageGraph {
+        let mut dependencies: HashMap<String, HashSet<String>> = HashMap::new();
+        for (from, to) in edges {
+            dependencies
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string());
+            dependencies.entry(to.to_string()).or_default();
+        }
+        PackageGraph::from(dependencies)
+    }
+
+    fn dependency(from: &str, to: &str) -> PackageDependency {
+        PackageDependency {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_new_dependency() {
+        let baseline = package_graph(&[("a", "b"), ("b", "c")]);
+        let current = package_graph(&[("a", "b"), ("b", "c"), ("a", "c")]);
+
+        assert_eq!(
+            current.diff(&baseline),
+            PackageGraphDiff {
+                new_dependencies: vec![dependency("a", "c")],
+                new_cycles: vec![],
+            }
+        );
+        assert!(baseline.diff(&baseline).is_empty());
+    }
+
+    #[test]
+    fn test_diff_new_cycle() {
+        let baseline = package_graph(&[("a", "b"), ("c", "a")]);
+        let current = package_graph(&[("a", "b"), ("c", "a"), ("a", "c")]);
+
+        assert_eq!(
+            current.diff(&baseline),
+            PackageGraphDiff {
+                new_dependencies: vec![dependency("a", "c")],
+                new_cycles: vec![vec!["a".to_string(), "c".to_string()]],
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_existing_cycle() {
+        // breaking up a cycle of the baseline does not introduce a new one
+        let baseline = package_graph(&[("a", "b"), ("b", "a"), ("b", "c"), ("c", "b")]);
+        let current = package_graph(&[("a", "b"), ("b", "a"), ("b", "c")]);
+
+        assert_eq!(
+            baseline.cycles(),
+            vec![vec!["a".to_string(), "b".to_string(), "c".to_string()]]
+        );
+        assert_eq!(
+            current.cycles(),
+            vec![vec!["a".to_string(), "b".to_string()]]
+        );
+        assert_eq!(current.diff(&baseline), PackageGraphDiff::default());
+    }
+}
@@ -9,6 +9,22 @@ use swc_common::{FileName, Span};
 use swc_ecma_ast::ModuleExportName;
 use swc_ecma_loader::resolve::Resolve;
 
+/// A symbol exported from or imported into a module.
+///
+/// Symbols cross API boundaries (reports, the napi bindings, dot graphs) as
+/// strings, using the canonical grammar of the `Display` and `FromStr`
+/// implementations, which round-trip every symbol:
+///
+/// - `default` is the default export
+/// - `*` is the namespace of the module (e.g. `import * as foo`)
+/// - `<execution-only>` is a side-effect import that uses no names (e.g.
+///   `import './foo'`)
+/// - any other string is a named export with that name
+///
+/// Named exports whose name is one of the sentinels above, or starts with
+/// `\`, are escaped by prefixing them with `\`. For example, the string
+/// export name in `export { foo as "*" }` is written as `\*`, and a name
+/// starting with a backslash such as `\x` is written as `\\x`.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
 pub enum ExportedSymbol {
     // A named export
@@ -20,22 +36,53 @@ pub enum ExportedSymbol {
     ExecutionOnly, // in case of `import './foo';` this executes code in file but imports nothing
 }
 
+const DEFAULT_SYMBOL: &str = "default";
+const NAMESPACE_SYMBOL: &str = "*";
+const EXECUTION_ONLY_SYMBOL: &str = "<execution-only>";
+const SYMBOL_ESCAPE: char = '\\';
+
+/// Parses a symbol from its canonical string form. See [ExportedSymbol].
 impl From<&str> for ExportedSymbol {
     fn from(s: &str) -> Self {
+        if let Some(name) = s.strip_prefix(SYMBOL_ESCAPE) {
+            return ExportedSymbol::Named(name.to_string());
+        }
         match s {
-            "default" => ExportedSymbol::Default,
+            DEFAULT_SYMBOL => ExportedSymbol::Default,
+            NAMESPACE_SYMBOL => ExportedSymbol::Namespace,
+            EXECUTION_ONLY_SYMBOL => ExportedSymbol::ExecutionOnly,
             _ => ExportedSymbol::Named(s.to_string()),
         }
     }
 }
 
+impl std::str::FromStr for ExportedSymbol {
+    type Err = std::convert::Infallible;
+
+    /// Parses a symbol from its canonical string form. Every string is a
+    /// valid symbol, so this never fails.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(ExportedSymbol::from(s))
+    }
+}
+
+/// Writes the canonical string form of a symbol. See [ExportedSymbol].
 impl std::fmt::Display for ExportedSymbol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ExportedSymbol::Named(name) => write!(f, "{}", name),
-            ExportedSymbol::Default => write!(f, "default"),
-            ExportedSymbol::Namespace => write!(f, "*"),
-            ExportedSymbol::ExecutionOnly => write!(f, "import '<path>'"),
+            ExportedSymbol::Named(name) => {
+                let needs_escape = name.starts_with(SYMBOL_ESCAPE)
+                    || [DEFAULT_SYMBOL, NAMESPACE_SYMBOL, EXECUTION_ONLY_SYMBOL]
+                        .contains(&name.as_str());
+                if needs_escape {
+                    write!(f, "{}{}", SYMBOL_ESCAPE, name)
+                } else {
+                    write!(f, "{}", name)
+                }
+            }
+            ExportedSymbol::Default => write!(f, "{}", DEFAULT_SYMBOL),
+            ExportedSymbol::Namespace => write!(f, "{}", NAMESPACE_SYMBOL),
+            ExportedSymbol::ExecutionOnly => write!(f, "{}", EXECUTION_ONLY_SYMBOL),
         }
     }
 }
@@ -61,6 +108,43 @@ pub struct ExportedSymbolMetadata {
     pub allow_unused: bool,
     // if this symbol is a typeonly export / import
     pub is_type_only: bool,
+    // if this symbol is referenced elsewhere in the file that exports it
+    pub locally_used: bool,
+    // for default exports, the local name bound to the exported value, if any
+    // (e.g. `Foo` in `export default function Foo() {}`)
+    pub local_name: Option<String>,
+    // for exported classes, the names of the decorators applied to the class
+    // (e.g. `Injectable` in `@Injectable() export class Foo {}`)
+    pub decorators: Vec<String>,
+    // for exports declared in this file, the trimmed text of the line comments
+    // leading the export statement (e.g. `good-fences-used`)
+    pub line_comments: Vec<String>,
+    // every declaration of the name in this file, in source order, if it is
+    // declared more than once (e.g. overload signatures, or an interface merged
+    // with a const of the same name). Empty for names declared once.
+    pub declarations: Vec<ExportDeclaration>,
+}
+
+/// One of the declarations of an exported name that is declared more than
+/// once in a file, e.g. an overload signature of a function
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct ExportDeclaration {
+    pub span: Span,
+    /// If true, the declaration only declares a type (an interface or a type alias)
+    pub is_type_only: bool,
+}
+
+/// An imported binding that is never referenced within the importing file,
+/// e.g. `b` in `import { a, b } from './foo'` when only `a` is used
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct UnusedImport {
+    /// The import specifier, as written in the import statement
+    pub specifier: String,
+    /// The symbol imported from the module
+    pub imported: ExportedSymbol,
+    /// The span of the binding's import specifier, e.g. `b as c` in
+    /// `import { b as c } from './foo'`
+    pub span: Span,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
@@ -72,6 +156,31 @@ pub struct ReExportedSymbol {
     pub renamed_to: Option<ExportedSymbol>,
 }
 
+impl ReExportedSymbol {
+    /// The name this symbol is exported as from the re-exporting module
+    pub fn exported_name(&self) -> &ExportedSymbol {
+        self.renamed_to.as_ref().unwrap_or(&self.imported)
+    }
+
+    /// Gets the name that `symbol`, exported from the module this re-exports
+    /// from, is exported as from the re-exporting module. Returns None if this
+    /// re-export does not include the symbol.
+    ///
+    /// `export * as ns from './foo'` includes every symbol of `./foo` through
+    /// the `ns` namespace, so it returns `ns` for every named symbol.
+    pub fn re_exported_name(&self, symbol: &ExportedSymbol) -> Option<&ExportedSymbol> {
+        match (&self.imported, symbol) {
+            // `export * from './foo'` re-exports every named export, but not the default
+            (ExportedSymbol::Namespace, ExportedSymbol::Named(_)) => {
+                Some(self.renamed_to.as_ref().unwrap_or(symbol))
+            }
+            (ExportedSymbol::Namespace, _) => None,
+            (imported, symbol) if imported == symbol => Some(self.exported_name()),
+            _ => None,
+        }
+    }
+}
+
 /// Represents the raw import/export information from a file, where import
 /// specifiers are not yet resolved to their final paths.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -88,6 +197,22 @@ pub struct RawImportExportInfo {
     pub exported_ids: AHashMap<ExportedSymbol, ExportedSymbolMetadata>,
     // `import './foo'`
     pub executed_paths: AHashSet<String>,
+    // spans of the import of each symbol, e.g. `bar as something` in
+    // `import {bar as something} from './foo'`. `require('foo')` and
+    // `import('./foo')` record the span of the call as a `Namespace` import
+    pub import_spans: AHashMap<String, AHashMap<ExportedSymbol, Span>>,
+    // true if the file calls a test framework function, e.g. `describe(...)`,
+    // `it(...)` or `expect(...)`
+    pub has_test_markers: bool,
+    // imported bindings that are never referenced within the file, in the
+    // order they are imported
+    pub unused_imports: Vec<UnusedImport>,
+    // true if the file failed to parse, and its imports were found by a
+    // lexical scan instead. Degraded files only have file-level imports,
+    // recorded as namespace imports, and no exports
+    pub degraded: bool,
+    // the error of the full parse of a degraded file
+    pub parse_error: Option<String>,
 }
 
 /// Represents the raw import/export information from a file, where import
@@ -106,22 +231,39 @@ pub struct ResolvedImportExportInfo {
     pub exported_ids: AHashMap<ExportedSymbol, ExportedSymbolMetadata>,
     // `import './foo'`
     pub executed_paths: AHashSet<PathBuf>,
+    // spans of the import of each symbol, by the path of the imported file
+    pub import_spans: AHashMap<PathBuf, AHashMap<ExportedSymbol, Span>>,
+    // true if the file calls a test framework function
+    pub has_test_markers: bool,
+    // imported bindings that are never referenced within the file. Their
+    // specifiers are left as written, rather than resolved
+    pub unused_imports: Vec<UnusedImport>,
+    // true if the file failed to parse, and its imports were found by a
+    // lexical scan instead
+    pub degraded: bool,
+    // the error of the full parse of a degraded file
+    pub parse_error: Option<String>,
+    // the path each import specifier of the file resolved to, so other tools
+    // can reuse the resolution
+    pub import_specifiers: AHashMap<String, PathBuf>,
 }
 
 impl ResolvedImportExportInfo {
     pub fn num_exported_symbols(&self) -> usize {
-        self.exported_ids.len() + self.export_from_symbols.len()
+        self.exported_ids.len()
+            + self
+                .export_from_symbols
+                .values()
+                .map(|symbols| symbols.len())
+                .sum::<usize>()
     }
 
     /// Returns an iterator over all the imports originating from this file.
     pub fn iter_exported_symbols(&self) -> impl Iterator<Item = (Option<&Path>, &ExportedSymbol)> {
         let export_from_symbols = self.export_from_symbols.iter().flat_map(|(path, symbols)| {
-            symbols.iter().map(|(symbol, _meta)| {
-                (
-                    Some(path.as_path()),
-                    symbol.renamed_to.as_ref().unwrap_or(&symbol.imported),
-                )
-            })
+            symbols
+                .iter()
+                .map(|(symbol, _meta)| (Some(path.as_path()), symbol.exported_name()))
         });
 
         let exported_ids = self.exported_ids.keys().map(|symbol| (None, symbol));
@@ -134,12 +276,9 @@ impl ResolvedImportExportInfo {
         &self,
     ) -> impl Iterator<Item = (Option<&Path>, (&ExportedSymbol, &ExportedSymbolMetadata))> {
         let export_from_symbols = self.export_from_symbols.iter().flat_map(|(path, symbols)| {
-            symbols.iter().map(|(symbol, meta)| {
-                (
-                    Some(path.as_path()),
-                    (symbol.renamed_to.as_ref().unwrap_or(&symbol.imported), meta),
-                )
-            })
+            symbols
+                .iter()
+                .map(|(symbol, meta)| (Some(path.as_path()), (symbol.exported_name(), meta)))
         });
 
         let exported_ids = self.exported_ids.iter().map(|symbol| (None, symbol));
@@ -188,6 +327,42 @@ impl ResolvedImportExportInfo {
             .chain(re_exports)
             .chain(executed_paths)
     }
+
+    /// Records each import of the generated file at `mirror_path` as an import
+    /// of `source_path`, the source file it was generated from, so that using
+    /// the generated file also uses the source file.
+    ///
+    /// Re-exports are moved rather than copied, so that the file does not
+    /// appear to re-export each name from two different files.
+    pub fn add_mirrored_imports(&mut self, mirror_path: &Path, source_path: &Path) {
+        if let Some(symbols) = self.imported_symbols.get(mirror_path).cloned() {
+            self.imported_symbols
+                .entry(source_path.to_path_buf())
+                .or_default()
+                .extend(symbols);
+        }
+        if self.require_paths.contains(mirror_path) {
+            self.require_paths.insert(source_path.to_path_buf());
+        }
+        if self.imported_paths.contains(mirror_path) {
+            self.imported_paths.insert(source_path.to_path_buf());
+        }
+        if let Some(symbols) = self.export_from_symbols.remove(mirror_path) {
+            self.export_from_symbols
+                .entry(source_path.to_path_buf())
+                .or_default()
+                .extend(symbols);
+        }
+        if self.executed_paths.contains(mirror_path) {
+            self.executed_paths.insert(source_path.to_path_buf());
+        }
+        if let Some(spans) = self.import_spans.get(mirror_path).cloned() {
+            self.import_spans
+                .entry(source_path.to_path_buf())
+                .or_default()
+                .extend(spans);
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash, Default)]
@@ -205,8 +380,23 @@ impl RawImportExportInfo {
             export_from_ids: AHashMap::default(),
             exported_ids: AHashMap::default(),
             executed_paths: AHashSet::default(),
+            import_spans: AHashMap::default(),
+            has_test_markers: false,
+            unused_imports: Vec::new(),
+            degraded: false,
+            parse_error: None,
         }
     }
+
+    /// Import/export info for a file whose contents are not parsed, such as a
+    /// `.vue` or `.svelte` component. It is treated as a module with a single
+    /// default export and no imports of its own.
+    pub fn new_opaque_module() -> Self {
+        let mut info = Self::new();
+        info.exported_ids
+            .insert(ExportedSymbol::Default, ExportedSymbolMetadata::default());
+        info
+    }
 }
 
 impl Default for RawImportExportInfo {
@@ -219,6 +409,7 @@ fn resolve_hashmap<T>(
     from_file: &FileName,
     resolver: impl Resolve,
     mut map: AHashMap<String, T>,
+    import_specifiers: &mut AHashMap<String, PathBuf>,
 ) -> MultiResult<AHashMap<PathBuf, T>, anyhow::Error> {
     let mut accum = AHashMap::with_capacity_and_hasher(map.len(), ARandomState::new());
     let mut errs: MultiErr<anyhow::Error> = MultiErr::new();
@@ -233,6 +424,7 @@ fn resolve_hashmap<T>(
 
         match resolved.filename {
             FileName::Real(resolved_path) => {
+                import_specifiers.insert(import_specifier, resolved_path.clone());
                 accum.insert(resolved_path, imported_symbols);
             }
             _ => {
@@ -250,6 +442,7 @@ fn resolve_hashset(
     from_file: &FileName,
     resolver: impl Resolve,
     mut set: AHashSet<String>,
+    import_specifiers: &mut AHashMap<String, PathBuf>,
 ) -> MultiResult<AHashSet<PathBuf>, anyhow::Error> {
     let mut accum = AHashSet::with_capacity_and_hasher(set.len(), ARandomState::new());
     let mut errs = MultiErr::<anyhow::Error>::new();
@@ -264,6 +457,7 @@ fn resolve_hashset(
 
         match resolved.filename {
             FileName::Real(path) => {
+                import_specifiers.insert(import_specifier, path.clone());
                 accum.insert(path);
             }
             _ => {
@@ -291,19 +485,55 @@ impl RawImportExportInfo {
             export_from_ids,
             exported_ids,
             executed_paths,
+            import_spans,
+            has_test_markers,
+            unused_imports,
+            degraded,
+            parse_error,
         } = self;
 
         let from_file = FileName::Real(from_file_path.to_path_buf());
 
         let mut errs = MultiErr::<anyhow::Error>::new();
+        let mut import_specifiers = AHashMap::default();
 
-        let imported_symbols =
-            errs.extract(resolve_hashmap(&from_file, &resolver, imported_path_ids));
-        let require_paths = errs.extract(resolve_hashset(&from_file, &resolver, require_paths));
-        let imported_paths = errs.extract(resolve_hashset(&from_file, &resolver, imported_paths));
-        let export_from_symbols =
-            errs.extract(resolve_hashmap(&from_file, &resolver, export_from_ids));
-        let executed_paths = errs.extract(resolve_hashset(&from_file, &resolver, executed_paths));
+        let imported_symbols = errs.extract(resolve_hashmap(
+            &from_file,
+            &resolver,
+            imported_path_ids,
+            &mut import_specifiers,
+        ));
+        let require_paths = errs.extract(resolve_hashset(
+            &from_file,
+            &resolver,
+            require_paths,
+            &mut import_specifiers,
+        ));
+        let imported_paths = errs.extract(resolve_hashset(
+            &from_file,
+            &resolver,
+            imported_paths,
+            &mut import_specifiers,
+        ));
+        let export_from_symbols = errs.extract(resolve_hashmap(
+            &from_file,
+            &resolver,
+            export_from_ids,
+            &mut import_specifiers,
+        ));
+        let executed_paths = errs.extract(resolve_hashset(
+            &from_file,
+            &resolver,
+            executed_paths,
+            &mut import_specifiers,
+        ));
+        // the same specifiers were resolved above, so their errors are already reported
+        let import_spans = MultiErr::<anyhow::Error>::new().extract(resolve_hashmap(
+            &from_file,
+            &resolver,
+            import_spans,
+            &mut import_specifiers,
+        ));
 
         MultiResult::with_errs(
             ResolvedImportExportInfo {
@@ -313,8 +543,85 @@ impl RawImportExportInfo {
                 export_from_symbols,
                 exported_ids,
                 executed_paths,
+                import_spans,
+                has_test_markers,
+                unused_imports,
+                degraded,
+                parse_error,
+                import_specifiers,
             },
             errs,
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ExportedSymbol;
+
+    // names that look like the sentinels of the string form, or their escapes
+    const REPRESENTATIVE_NAMES: &[&str] = &[
+        "foo",
+        "$foo_1",
+        "default",
+        "Default",
+        "*",
+        "**",
+        "<execution-only>",
+        "\\",
+        "\\default",
+        "\\*",
+        "\\\\foo",
+        "a \\ b",
+        "",
+        "名前",
+    ];
+
+    #[test]
+    fn test_symbol_string_round_trip() {
+        let symbols = REPRESENTATIVE_NAMES
+            .iter()
+            .map(|name| ExportedSymbol::Named(name.to_string()))
+            .chain([
+                ExportedSymbol::Default,
+                ExportedSymbol::Namespace,
+                ExportedSymbol::ExecutionOnly,
+            ])
+            .collect::<Vec<_>>();
+
+        for symbol in symbols.iter() {
+            let string_form = symbol.to_string();
+            assert_eq!(
+                string_form.parse::<ExportedSymbol>(),
+                Ok(symbol.clone()),
+                "{string_form:?} did not round-trip"
+            );
+        }
+
+        // every symbol has a distinct string form
+        let mut string_forms = symbols.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        string_forms.sort();
+        string_forms.dedup();
+        assert_eq!(string_forms.len(), symbols.len());
+    }
+
+    #[test]
+    fn test_symbol_escaping() {
+        assert_eq!(ExportedSymbol::Default.to_string(), "default");
+        assert_eq!(ExportedSymbol::Namespace.to_string(), "*");
+        assert_eq!(
+            ExportedSymbol::Named("default".to_string()).to_string(),
+            "\\default"
+        );
+        assert_eq!(ExportedSymbol::Named("*".to_string()).to_string(), "\\*");
+        assert_eq!(
+            ExportedSymbol::Named("\\x".to_string()).to_string(),
+            "\\\\x"
+        );
+        assert_eq!(ExportedSymbol::Named("foo".to_string()).to_string(), "foo");
+        assert_eq!(
+            ExportedSymbol::from("\\default"),
+            ExportedSymbol::Named("default".to_string())
+        );
+    }
+}
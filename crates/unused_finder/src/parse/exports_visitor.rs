@@ -1,4 +1,7 @@
-use super::{ExportedSymbol, ExportedSymbolMetadata, RawImportExportInfo, ReExportedSymbol};
+use super::{
+    ExportDeclaration, ExportedSymbol, ExportedSymbolMetadata, RawImportExportInfo,
+    ReExportedSymbol, UnusedImport,
+};
 use ahashmap::{AHashMap, AHashSet};
 use logger_srcfile::SrcFileLogger;
 use std::{collections::HashSet, iter::FromIterator};
@@ -7,9 +10,10 @@ use swc_common::{
     BytePos, Span, Spanned,
 };
 use swc_ecma_ast::{
-    BindingIdent, CallExpr, Callee, Decl, ExportAll, ExportDecl, ExportDefaultDecl,
-    ExportDefaultExpr, ExportSpecifier, Id, ImportDecl, ImportSpecifier, Lit, ModuleExportName,
-    NamedExport, Str, TsImportEqualsDecl, TsModuleName,
+    AssignPat, BindingIdent, CallExpr, Callee, Decl, Decorator, DefaultDecl, ExportAll, ExportDecl,
+    ExportDefaultDecl, ExportDefaultExpr, ExportSpecifier, Expr, Id, Ident, ImportDecl,
+    ImportSpecifier, JSXElementName, JSXFragment, JSXObject, Lit, ModuleExportName, NamedExport,
+    Prop, Str, TsEntityName, TsImportEqualsDecl, TsImportType, TsModuleName,
 };
 use swc_ecma_visit::{Visit, VisitWith};
 
@@ -30,9 +34,24 @@ pub struct ExportsVisitor<TLogger: SrcFileLogger> {
     // Side-effect-only imports.
     // import './foo';
     pub executed_paths: AHashSet<String>,
+    // spans of the import of each symbol, by import path
+    pub import_spans: AHashMap<String, AHashMap<ExportedSymbol, Span>>,
+    // true if the file calls a test framework function, e.g. `describe(...)`
+    pub has_test_markers: bool,
     // exported from this file
     // const foo = require('foo') generates ["foo"]
     require_identifiers: AHashSet<Id>,
+    // local bindings behind exported symbols, alongside the span of the export
+    exported_local_ids: AHashMap<ExportedSymbol, (Id, Span)>,
+    // positions of each reference to a local binding
+    local_references: AHashMap<Id, Vec<BytePos>>,
+    // local bindings of imported symbols, with the specifier they are imported from
+    imported_bindings: AHashMap<Id, (String, ExportedSymbol)>,
+    // spans of the import specifiers of local bindings of imported symbols
+    imported_binding_spans: AHashMap<Id, Span>,
+    // true if the file contains JSX, which references `React` implicitly
+    // with the classic JSX runtime
+    has_jsx: bool,
     pub comments: SingleThreadedComments,
 }
 
@@ -44,13 +63,89 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
             imported_paths: AHashSet::default(),
             export_from_ids: AHashMap::default(),
             executed_paths: AHashSet::default(),
+            import_spans: AHashMap::default(),
+            has_test_markers: false,
             require_identifiers: AHashSet::default(),
             exported_ids: AHashMap::default(),
+            exported_local_ids: AHashMap::default(),
+            local_references: AHashMap::default(),
+            imported_bindings: AHashMap::default(),
+            imported_binding_spans: AHashMap::default(),
+            has_jsx: false,
             logger,
             comments,
         }
     }
 
+    // keeps the first span if a symbol is imported more than once
+    fn record_import_span(&mut self, import_path: &str, symbol: ExportedSymbol, span: Span) {
+        self.import_spans
+            .entry(import_path.to_string())
+            .or_default()
+            .entry(symbol)
+            .or_insert(span);
+    }
+
+    fn record_local_reference(&mut self, ident: &Ident) {
+        self.local_references
+            .entry(ident.to_id())
+            .or_default()
+            .push(ident.span.lo);
+    }
+
+    /**
+     * Marks exported symbols whose local binding is referenced elsewhere in this file.
+     *
+     * References from within the export's own declaration (e.g. recursive calls)
+     * are not counted, since they are removed along with the declaration.
+     */
+    fn mark_locally_used_exports(&mut self) {
+        for (symbol, (id, export_span)) in self.exported_local_ids.iter() {
+            let locally_used = self.local_references.get(id).is_some_and(|positions| {
+                positions
+                    .iter()
+                    .any(|pos| *pos < export_span.lo || *pos >= export_span.hi)
+            });
+            if !locally_used {
+                continue;
+            }
+            if let Some(metadata) = self.exported_ids.get_mut(symbol) {
+                metadata.locally_used = true;
+            }
+        }
+    }
+
+    /**
+     * Finds the imported bindings that are never referenced within this file.
+     *
+     * Bindings exported with `export { foo }` count as referenced, as does
+     * `React` in files with JSX.
+     */
+    fn find_unused_imports(&self) -> Vec<UnusedImport> {
+        let exported_ids: AHashSet<&Id> =
+            self.exported_local_ids.values().map(|(id, _)| id).collect();
+        let mut unused_imports: Vec<UnusedImport> = self
+            .imported_bindings
+            .iter()
+            .filter(|(id, _)| {
+                !self.local_references.contains_key(*id)
+                    && !exported_ids.contains(id)
+                    && !(self.has_jsx && id.0 == *"React")
+            })
+            .map(|(id, (specifier, imported))| UnusedImport {
+                specifier: specifier.clone(),
+                imported: imported.clone(),
+                span: self
+                    .imported_binding_spans
+                    .get(id)
+                    .copied()
+                    .unwrap_or_default(),
+            })
+            .collect();
+        unused_imports.sort_by_key(|unused_import| unused_import.span.lo);
+        unused_imports
+    }
+
     /**
      * Extracts information from each specifier imported in source to treat it as an string
      * Supported sytax list:
@@ -83,6 +178,11 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
                                 span: spec.span(),
                                 allow_unused,
                                 is_type_only: parent_is_type_only || export.type_only,
+                                locally_used: false,
+                                local_name: None,
+                                decorators: Vec::new(),
+                                line_comments: Vec::new(),
+                                declarations: Vec::new(),
                             },
                         ),
                         ExportSpecifier::Default(spec) => (
@@ -94,6 +194,11 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
                                 span: spec.span(),
                                 allow_unused,
                                 is_type_only: parent_is_type_only || export.type_only,
+                                locally_used: false,
+                                local_name: None,
+                                decorators: Vec::new(),
+                                line_comments: Vec::new(),
+                                declarations: Vec::new(),
                             },
                         ),
                         ExportSpecifier::Named(spec) => {
@@ -112,6 +217,11 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
                                     span: spec.span(),
                                     allow_unused,
                                     is_type_only: parent_is_type_only || export.type_only,
+                                    locally_used: false,
+                                    local_name: None,
+                                    decorators: Vec::new(),
+                                    line_comments: Vec::new(),
+                                    declarations: Vec::new(),
                                 },
                             )
                         }
@@ -135,6 +245,7 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
         &mut self,
         specs: &[ExportSpecifier],
         allow_unused: bool,
+        line_comments: Vec<String>,
         parent_is_type_only: bool,
         span: Span,
     ) {
@@ -146,6 +257,10 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
                     if let ModuleExportName::Ident(id) = exported {
                         let sym = &id.sym;
                         // export { foo as default }
+                        if let ModuleExportName::Ident(orig) = &named.orig {
+                            self.exported_local_ids
+                                .insert(ExportedSymbol::from(sym.as_str()), (orig.to_id(), span));
+                        }
                         if sym == "default" {
                             self.exported_ids.insert(
                                 ExportedSymbol::Default,
@@ -153,6 +268,14 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
                                     span,
                                     allow_unused,
                                     is_type_only,
+                                    locally_used: false,
+                                    local_name: match &named.orig {
+                                        ModuleExportName::Ident(orig) => Some(orig.sym.to_string()),
+                                        ModuleExportName::Str(_) => None,
+                                    },
+                                    decorators: Vec::new(),
+                                    line_comments: line_comments.clone(),
+                                    declarations: Vec::new(),
                                 },
                             );
                         } else {
@@ -162,18 +285,32 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
                                     span,
                                     allow_unused,
                                     is_type_only,
+                                    locally_used: false,
+                                    local_name: None,
+                                    decorators: Vec::new(),
+                                    line_comments: line_comments.clone(),
+                                    declarations: Vec::new(),
                                 },
                             );
                         }
                     }
                 } else if let ModuleExportName::Ident(id) = &named.orig {
                     // handles `export { foo }`
+                    self.exported_local_ids.insert(
+                        ExportedSymbol::Named(id.sym.to_string()),
+                        (id.to_id(), span),
+                    );
                     self.exported_ids.insert(
                         ExportedSymbol::Named(id.sym.to_string()),
                         ExportedSymbolMetadata {
                             span,
                             allow_unused,
                             is_type_only,
+                            locally_used: false,
+                            local_name: None,
+                            decorators: Vec::new(),
+                            line_comments: line_comments.clone(),
+                            declarations: Vec::new(),
                         },
                     );
                 }
@@ -184,6 +321,10 @@ impl<TLogger: SrcFileLogger> ExportsVisitor<TLogger> {
     pub fn has_disable_export_comment(&self, lo: BytePos) -> bool {
         has_disable_export_comment(&self.comments, lo)
     }
+
+    pub fn leading_line_comments(&self, lo: BytePos) -> Vec<String> {
+        leading_line_comments(&self.comments, lo)
+    }
 }
 
 pub fn has_disable_export_comment(comments: &SingleThreadedComments, lo: BytePos) -> bool {
@@ -195,8 +336,22 @@ pub fn has_disable_export_comment(comments: &SingleThreadedComments, lo: BytePos
     false
 }
 
+/// Gets the text of the line comments leading a node, trimmed
+pub fn leading_line_comments(comments: &SingleThreadedComments, lo: BytePos) -> Vec<String> {
+    match comments.get_leading(lo) {
+        Some(comments) => comments
+            .iter()
+            .filter(|c| c.kind == CommentKind::Line)
+            .map(|c| c.text.trim().to_string())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
 impl<T: SrcFileLogger> From<ExportsVisitor<T>> for RawImportExportInfo {
-    fn from(x: ExportsVisitor<T>) -> Self {
+    fn from(mut x: ExportsVisitor<T>) -> Self {
+        x.mark_locally_used_exports();
+        let unused_imports = x.find_unused_imports();
         Self {
             imported_path_ids: x.imported_ids_path_name,
             require_paths: x.require_paths,
@@ -204,6 +359,11 @@ impl<T: SrcFileLogger> From<ExportsVisitor<T>> for RawImportExportInfo {
             export_from_ids: x.export_from_ids, // TODO replace with Exportx maps
             exported_ids: x.exported_ids,
             executed_paths: x.executed_paths,
+            import_spans: x.import_spans,
+            has_test_markers: x.has_test_markers,
+            unused_imports,
+            degraded: false,
+            parse_error: None,
         }
     }
 }
@@ -212,12 +372,22 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
     // Handles `export default foo`
     fn visit_export_default_expr(&mut self, expr: &ExportDefaultExpr) {
         expr.visit_children_with(self);
+        // `export default foo` exports the local binding `foo`
+        let local_name = match &*expr.expr {
+            Expr::Ident(ident) => Some(ident.sym.to_string()),
+            _ => None,
+        };
         self.exported_ids.insert(
             ExportedSymbol::Default,
             ExportedSymbolMetadata {
                 span: expr.span(),
                 allow_unused: self.has_disable_export_comment(expr.span_lo()),
                 is_type_only: false,
+                locally_used: false,
+                local_name,
+                decorators: Vec::new(),
+                line_comments: self.leading_line_comments(expr.span_lo()),
+                declarations: Vec::new(),
             },
         );
     }
@@ -228,12 +398,42 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
     fn visit_export_default_decl(&mut self, decl: &ExportDefaultDecl) {
         decl.visit_children_with(self);
         let is_type_only = decl.decl.is_ts_interface_decl();
+        let local_ident = match &decl.decl {
+            DefaultDecl::Class(class) => class.ident.as_ref(),
+            DefaultDecl::Fn(func) => func.ident.as_ref(),
+            DefaultDecl::TsInterfaceDecl(interface) => Some(&interface.id),
+        };
+        let decorators = match &decl.decl {
+            DefaultDecl::Class(class) => decorator_names(&class.class.decorators),
+            _ => Vec::new(),
+        };
+        // the statement starts at the first decorator, which may precede
+        // `export`, e.g. `@observer export default class Foo {}`
+        let span = match &decl.decl {
+            DefaultDecl::Class(class) => class
+                .class
+                .decorators
+                .iter()
+                .fold(decl.span(), |span, decorator| {
+                    span.with_lo(span.lo.min(decorator.span.lo))
+                }),
+            _ => decl.span(),
+        };
+        if let Some(local_ident) = local_ident {
+            self.exported_local_ids
+                .insert(ExportedSymbol::Default, (local_ident.to_id(), span));
+        }
         self.exported_ids.insert(
             ExportedSymbol::Default,
             ExportedSymbolMetadata {
-                span: decl.span(),
+                span,
                 allow_unused: self.has_disable_export_comment(decl.span_lo()),
                 is_type_only,
+                locally_used: false,
+                local_name: local_ident.map(|ident| ident.sym.to_string()),
+                decorators,
+                line_comments: self.leading_line_comments(span.lo),
+                declarations: Vec::new(),
             },
         );
     }
@@ -242,7 +442,12 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
     fn visit_export_decl(&mut self, export: &ExportDecl) {
         export.visit_children_with(self);
         let allow_unused = self.has_disable_export_comment(export.span_lo());
+        let line_comments = self.leading_line_comments(export.span_lo());
         let is_type_only = export.decl.is_ts_interface() || export.decl.is_ts_type_alias();
+        let decorators = match &export.decl {
+            Decl::Class(decl) => decorator_names(&decl.class.decorators),
+            _ => Vec::new(),
+        };
         let idents = match &export.decl {
             Decl::Class(decl) => {
                 vec![decl.ident.sym.to_string()]
@@ -279,15 +484,29 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
             }
         };
 
+        for id in exported_decl_ids(&export.decl) {
+            self.exported_local_ids
+                .insert(ExportedSymbol::Named(id.0.to_string()), (id, export.span()));
+        }
+
         for ident in idents {
-            self.exported_ids.insert(
-                ExportedSymbol::Named(ident),
-                ExportedSymbolMetadata {
-                    span: export.span(),
-                    allow_unused,
-                    is_type_only,
-                },
-            );
+            let symbol = ExportedSymbol::Named(ident);
+            let metadata = ExportedSymbolMetadata {
+                span: export.span(),
+                allow_unused,
+                is_type_only,
+                locally_used: false,
+                local_name: None,
+                decorators: decorators.clone(),
+                line_comments: line_comments.clone(),
+                declarations: Vec::new(),
+            };
+            // overload signatures and declaration merging declare a name more than once
+            let metadata = match self.exported_ids.remove(&symbol) {
+                Some(earlier) => merge_declaration(earlier, metadata),
+                None => metadata,
+            };
+            self.exported_ids.insert(symbol, metadata);
         }
     }
 
@@ -305,6 +524,11 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
                 span: export.span(),
                 allow_unused,
                 is_type_only: export.type_only,
+                locally_used: false,
+                local_name: None,
+                decorators: Vec::new(),
+                line_comments: Vec::new(),
+                declarations: Vec::new(),
             },
         );
     }
@@ -324,12 +548,62 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
             self.handle_export_named_specifiers(
                 &export.specifiers,
                 self.has_disable_export_comment(export.span_lo()),
+                self.leading_line_comments(export.span_lo()),
                 export.type_only,
                 export.span(),
             );
         }
     }
 
+    // `foo()`, `foo + 1`, etc.
+    fn visit_expr(&mut self, expr: &Expr) {
+        expr.visit_children_with(self);
+        if let Expr::Ident(ident) = expr {
+            self.record_local_reference(ident);
+        }
+    }
+
+    // `{ foo }`
+    fn visit_prop(&mut self, prop: &Prop) {
+        prop.visit_children_with(self);
+        if let Prop::Shorthand(ident) = prop {
+            self.record_local_reference(ident);
+        }
+    }
+
+    // `let x: Foo` or `typeof foo`
+    fn visit_ts_entity_name(&mut self, name: &TsEntityName) {
+        name.visit_children_with(self);
+        if let TsEntityName::Ident(ident) = name {
+            self.record_local_reference(ident);
+        }
+    }
+
+    // `<Foo />` or `<Foo.Bar />`
+    fn visit_jsx_element_name(&mut self, name: &JSXElementName) {
+        name.visit_children_with(self);
+        self.has_jsx = true;
+        match name {
+            JSXElementName::Ident(ident) => self.record_local_reference(ident),
+            JSXElementName::JSXMemberExpr(member) => {
+                let mut object = &member.obj;
+                while let JSXObject::JSXMemberExpr(inner) = object {
+                    object = &inner.obj;
+                }
+                if let JSXObject::Ident(ident) = object {
+                    self.record_local_reference(ident);
+                }
+            }
+            JSXElementName::JSXNamespacedName(_) => {}
+        }
+    }
+
+    // `<>...</>`
+    fn visit_jsx_fragment(&mut self, fragment: &JSXFragment) {
+        fragment.visit_children_with(self);
+        self.has_jsx = true;
+    }
+
     // const foo = require; // <- Binding
     // const p = foo('./path')
     fn visit_binding_ident(&mut self, binding: &BindingIdent) {
@@ -343,11 +617,36 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
     fn visit_ts_import_equals_decl(&mut self, decl: &TsImportEqualsDecl) {
         decl.visit_children_with(self);
         if let Some(module_ref) = decl.module_ref.as_ts_external_module_ref() {
-            self.imported_paths
-                .insert(module_ref.expr.value.to_string());
+            let import_path = module_ref.expr.value.to_string();
+            self.record_import_span(&import_path, ExportedSymbol::Namespace, decl.span());
+            self.imported_paths.insert(import_path);
         }
     }
 
+    // `typeof import('./foo')` or `import('./foo').Foo` in a type position.
+    //
+    // These are recorded like `import type`, as an import of the qualifying
+    // name (or of the namespace, when unqualified).
+    fn visit_ts_import_type(&mut self, import_type: &TsImportType) {
+        // the qualifier names an export of the imported module rather than a
+        // local binding, so only the type arguments are visited
+        import_type.type_args.visit_with(self);
+
+        let import_path = import_type.arg.value.to_string();
+        let symbol = match &import_type.qualifier {
+            Some(qualifier) => match leftmost_entity_ident(qualifier).sym.as_ref() {
+                "default" => ExportedSymbol::Default,
+                name => ExportedSymbol::Named(name.to_string()),
+            },
+            None => ExportedSymbol::Namespace,
+        };
+        self.record_import_span(&import_path, symbol.clone(), import_type.span);
+        self.imported_ids_path_name
+            .entry(import_path)
+            .or_default()
+            .insert(symbol);
+    }
+
     // import('foo')
     // or
     // require('foo')
@@ -356,15 +655,24 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
         if let Callee::Import(_) = &expr.callee {
             match extract_argument_value(expr) {
                 Some(import_path) => {
+                    self.record_import_span(&import_path, ExportedSymbol::Namespace, expr.span());
                     self.imported_paths.insert(import_path);
                 }
                 None => return,
             }
         }
         if let Callee::Expr(callee) = &expr.callee {
+            if is_test_framework_callee(callee) {
+                self.has_test_markers = true;
+            }
             if let Some(ident) = callee.as_ident() {
                 if ident.sym == "require" && !self.require_identifiers.contains(&ident.to_id()) {
                     if let Some(import_path) = extract_argument_value(expr) {
+                        self.record_import_span(
+                            &import_path,
+                            ExportedSymbol::Namespace,
+                            expr.span(),
+                        );
                         self.require_paths.insert(import_path);
                     }
                 }
@@ -425,6 +733,19 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
             })
             .collect();
 
+        for (spec, symbol) in import.specifiers.iter().zip(specifiers.iter()) {
+            self.record_import_span(&src, symbol.clone(), spec.span());
+            let local = match spec {
+                ImportSpecifier::Named(named) => &named.local,
+                ImportSpecifier::Default(default) => &default.local,
+                ImportSpecifier::Namespace(namespace) => &namespace.local,
+            };
+            self.imported_bindings
+                .insert(local.to_id(), (src.clone(), symbol.clone()));
+            self.imported_binding_spans
+                .insert(local.to_id(), spec.span());
+        }
+
         if let Some(entry) = self.imported_ids_path_name.get_mut(&src) {
             specifiers.drain(0..).for_each(|s| {
                 entry.insert(s);
@@ -436,6 +757,133 @@ impl<T: SrcFileLogger> Visit for ExportsVisitor<T> {
     }
 }
 
+/**
+ * Gets the first identifier of a qualified name, e.g. `a` for `a.b.c`
+ */
+fn leftmost_entity_ident(name: &TsEntityName) -> &Ident {
+    match name {
+        TsEntityName::Ident(ident) => ident,
+        TsEntityName::TsQualifiedName(qualified) => leftmost_entity_ident(&qualified.left),
+    }
+}
+
+// Functions whose calls mark a file as a test, across jest, mocha and vitest
+const TEST_FRAMEWORK_FUNCTIONS: &[&str] = &["describe", "it", "test", "expect"];
+
+/**
+ * Checks if a callee is a test framework function, including its modifiers
+ * and chained forms, e.g. `it.only`, `describe.each([...])` or `expect(a).toBe`
+ */
+fn is_test_framework_callee(callee: &Expr) -> bool {
+    match callee {
+        Expr::Ident(ident) => TEST_FRAMEWORK_FUNCTIONS.contains(&ident.sym.as_ref()),
+        Expr::Member(member) => is_test_framework_callee(&member.obj),
+        Expr::Call(call) => match &call.callee {
+            Callee::Expr(callee) => is_test_framework_callee(callee),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/**
+ * Gets the local bindings declared by an exported declaration
+ */
+fn exported_decl_ids(decl: &Decl) -> Vec<Id> {
+    match decl {
+        Decl::Class(decl) => vec![decl.ident.to_id()],
+        Decl::Fn(decl) => vec![decl.ident.to_id()],
+        Decl::Var(decl) => {
+            let mut collector = BindingIdCollector::default();
+            for declarator in decl.decls.iter() {
+                declarator.name.visit_with(&mut collector);
+            }
+            collector.ids
+        }
+        Decl::TsInterface(decl) => vec![decl.id.to_id()],
+        Decl::TsTypeAlias(decl) => vec![decl.id.to_id()],
+        Decl::TsEnum(decl) => vec![decl.id.to_id()],
+        Decl::TsModule(decl) => match &decl.id {
+            TsModuleName::Ident(ident) => vec![ident.to_id()],
+            TsModuleName::Str(_) => vec![],
+        },
+        Decl::Using(_) => vec![],
+    }
+}
+
+// Collects the identifiers bound by a pattern, e.g. `a` and `b` in `{ a, b: [b] }`
+#[derive(Default)]
+struct BindingIdCollector {
+    ids: Vec<Id>,
+}
+
+impl Visit for BindingIdCollector {
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.ids.push(binding.id.to_id());
+    }
+
+    // skip default values, which may bind their own names (e.g. arrow function params)
+    fn visit_assign_pat(&mut self, pat: &AssignPat) {
+        pat.left.visit_with(self);
+    }
+
+    // skip computed keys
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+/**
+ * Merges another declaration of an exported name (e.g. an overload signature,
+ * or an interface merged with a const) into the metadata of its earlier
+ * declarations, recording every declaration so reports can tell them apart.
+ *
+ * The merged metadata keeps the span of the first declaration, and is only
+ * type-only if every declaration is.
+ */
+fn merge_declaration(
+    mut merged: ExportedSymbolMetadata,
+    declaration: ExportedSymbolMetadata,
+) -> ExportedSymbolMetadata {
+    if merged.declarations.is_empty() {
+        merged.declarations.push(ExportDeclaration {
+            span: merged.span,
+            is_type_only: merged.is_type_only,
+        });
+    }
+    merged.declarations.push(ExportDeclaration {
+        span: declaration.span,
+        is_type_only: declaration.is_type_only,
+    });
+    merged.is_type_only &= declaration.is_type_only;
+    merged.allow_unused |= declaration.allow_unused;
+    merged.decorators.extend(declaration.decorators);
+    merged.line_comments.extend(declaration.line_comments);
+    merged
+}
+
+/**
+ * Gets the names of the decorators applied to a class, e.g. `Injectable` for
+ * `@Injectable()`, or `Component` for `@ng.Component({ ... })`
+ */
+fn decorator_names(decorators: &[Decorator]) -> Vec<String> {
+    decorators
+        .iter()
+        .filter_map(|decorator| decorator_name(&decorator.expr))
+        .collect()
+}
+
+fn decorator_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(member) => member.prop.as_ident().map(|prop| prop.sym.to_string()),
+        Expr::Call(call) => match &call.callee {
+            Callee::Expr(callee) => decorator_name(callee),
+            _ => None,
+        },
+        Expr::Paren(paren) => decorator_name(&paren.expr),
+        _ => None,
+    }
+}
+
 fn extract_argument_value(expr: &CallExpr) -> Option<String> {
     let import_path = match expr.args.is_empty() {
         true => return None,
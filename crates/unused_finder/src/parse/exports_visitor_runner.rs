@@ -14,6 +14,7 @@ use swc_ecma_visit::{Fold, VisitWith};
 use swc_utils_parse::create_lexer;
 
 use crate::parse::exports_visitor::ExportsVisitor;
+use crate::parse::import_scanner::scan_imports;
 use crate::parse::RawImportExportInfo;
 
 #[derive(Debug, thiserror::Error)]
@@ -28,6 +29,25 @@ pub enum SourceFileParseError {
     Parser(PathBuf, String),
 }
 
+/// Gets the import/export info of a file like [get_file_import_export_info],
+/// falling back to a lexical scan of its imports if the file fails to parse,
+/// e.g. because it uses syntax newer than the parser supports.
+///
+/// The info of a scanned file is marked as degraded, and keeps the parse error.
+pub fn get_file_import_export_info_or_scan(
+    file_path: &Path,
+) -> Result<RawImportExportInfo, SourceFileParseError> {
+    match get_file_import_export_info(file_path) {
+        Err(
+            e @ (SourceFileParseError::TypeScriptSyntax(..) | SourceFileParseError::Parser(..)),
+        ) => match std::fs::read_to_string(file_path) {
+            Ok(src) => Ok(scan_imports(&src, e.to_string())),
+            Err(_) => Err(e),
+        },
+        result => result,
+    }
+}
+
 /// Gets the _unresolved_ import/export info from a file by reading it from disk and parsing it.
 pub fn get_file_import_export_info(
     file_path: &Path,
@@ -91,12 +111,5 @@ pub fn get_file_import_export_info(
         resolved.visit_with(&mut visitor)
     });
 
-    Ok(RawImportExportInfo {
-        imported_path_ids: visitor.imported_ids_path_name,
-        require_paths: visitor.require_paths,
-        imported_paths: visitor.imported_paths,
-        export_from_ids: visitor.export_from_ids, // TODO replace with ExportVisitor maps
-        exported_ids: visitor.exported_ids,
-        executed_paths: visitor.executed_paths,
-    })
+    Ok(RawImportExportInfo::from(visitor))
 }
@@ -11,7 +11,7 @@ mod test {
     use swc_ecma_parser::{Capturing, Parser};
     use swc_ecma_visit::VisitWith;
 
-    use crate::parse::{ExportedSymbol, ReExportedSymbol};
+    use crate::parse::{ExportedSymbol, RawImportExportInfo, ReExportedSymbol};
     use swc_utils_parse::create_lexer;
 
     use crate::parse::exports_visitor::ExportsVisitor;
@@ -636,6 +636,35 @@ mod test {
         assert_eq!(expected_map, visitor.imported_ids_path_name);
     }
 
+    #[test]
+    fn test_typeof_import_type() {
+        let visitor = visit(
+            r#"
+            type Api = typeof import('./generated/api');
+            "#,
+        );
+        let expected_map: AHashMap<String, AHashSet<ExportedSymbol>> =
+            amap!("./generated/api" => aset!(ExportedSymbol::Namespace));
+        assert_eq!(expected_map, visitor.imported_ids_path_name);
+    }
+
+    #[test]
+    fn test_qualified_import_type() {
+        let visitor = visit(
+            r#"
+            type FnParams = Parameters<typeof import('./x').fn>;
+            type Config = import('./config').default;
+            type Nested = import('./types').Models.User;
+            "#,
+        );
+        let expected_map: AHashMap<String, AHashSet<ExportedSymbol>> = amap!(
+            "./x" => aset!(ExportedSymbol::Named("fn".to_owned())),
+            "./config" => aset!(ExportedSymbol::Default),
+            "./types" => aset!(ExportedSymbol::Named("Models".to_owned()))
+        );
+        assert_eq!(expected_map, visitor.imported_ids_path_name);
+    }
+
     #[test]
     fn test_import_specifier_with_alias() {
         let visitor = visit(
@@ -758,4 +787,230 @@ mod test {
                 .collect::<AHashSet<_>>()
         );
     }
+
+    #[test]
+    fn test_locally_used_exports() {
+        let info = RawImportExportInfo::from(visit(
+            r#"
+            export function helper() {}
+            export function dead() { return dead(); }
+            export const value = helper();
+            const local = { value };
+            export { local };
+            "#,
+        ));
+
+        let locally_used = info
+            .exported_ids
+            .iter()
+            .map(|(k, v)| (k.clone(), v.locally_used))
+            .collect::<AHashMap<_, _>>();
+        assert_eq!(
+            amap2!(
+                "helper".into() => true,
+                "dead".into() => false,
+                "value".into() => true,
+                "local".into() => false
+            ),
+            locally_used
+        );
+    }
+
+    #[test]
+    fn test_unused_imports() {
+        let info = RawImportExportInfo::from(visit(
+            r#"
+            import { a, b } from "./ab";
+            import * as ns from "./ns";
+            import { Shape } from "./types";
+            import { reExported } from "./re-exported";
+            import unused from "./unused";
+            export { reExported };
+            let shape: Shape = a(ns.value);
+            "#,
+        ));
+
+        let unused_imports = info
+            .unused_imports
+            .iter()
+            .map(|unused_import| {
+                (
+                    unused_import.specifier.as_str(),
+                    unused_import.imported.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("./ab", ExportedSymbol::Named("b".to_string())),
+                ("./unused", ExportedSymbol::Default),
+            ],
+            unused_imports
+        );
+    }
+
+    #[test]
+    fn test_class_decorators() {
+        let info = RawImportExportInfo::from(visit(
+            r#"
+            @Injectable()
+            export class Service {}
+            @ng.Component({ selector: "app" })
+            export default class App {}
+            export class Plain {}
+            export const notAClass = 1;
+            "#,
+        ));
+
+        let decorators = info
+            .exported_ids
+            .iter()
+            .map(|(k, v)| (k.clone(), v.decorators.clone()))
+            .collect::<AHashMap<_, _>>();
+        assert_eq!(
+            amap2!(
+                "Service".into() => vec!["Injectable".to_string()],
+                ExportedSymbol::Default => vec!["Component".to_string()],
+                "Plain".into() => vec![],
+                "notAClass".into() => vec![]
+            ),
+            decorators
+        );
+    }
+
+    // the default export's span, and the specifiers of the imports that are
+    // never referenced, e.g. by the default export
+    fn default_export(src: &str) -> (usize, Vec<String>) {
+        let info = RawImportExportInfo::from(visit(src));
+        let metadata = info.exported_ids.get(&ExportedSymbol::Default).unwrap();
+        (
+            (metadata.span.hi.0 - metadata.span.lo.0) as usize,
+            info.unused_imports
+                .into_iter()
+                .map(|unused_import| unused_import.specifier)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_default_export_extends_import() {
+        let statement = "export default class Foo extends Base {}";
+        let (span_len, unused_imports) = default_export(&format!(
+            r#"
+            import Base from './base';
+            import {{ helper }} from './helper';
+            {statement}
+            "#
+        ));
+
+        assert_eq!(span_len, statement.len());
+        assert_eq!(unused_imports, vec!["./helper".to_string()]);
+    }
+
+    #[test]
+    fn test_default_export_wrapped_import() {
+        let statement = "export default observer(Component);";
+        let (span_len, unused_imports) = default_export(&format!(
+            r#"
+            import {{ observer }} from 'mobx-react';
+            import Component from './component';
+            import {{ unused }} from './unused';
+            {statement}
+            "#
+        ));
+
+        assert_eq!(span_len, statement.len());
+        assert_eq!(unused_imports, vec!["./unused".to_string()]);
+
+        // decorators before `export` are part of the statement
+        let statement = "@observer export default class App {}";
+        let (span_len, unused_imports) = default_export(&format!(
+            r#"
+            import {{ observer }} from 'mobx-react';
+            {statement}
+            "#
+        ));
+
+        assert_eq!(span_len, statement.len());
+        assert_eq!(unused_imports, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_default_export_literal() {
+        let statement = "export default 42;";
+        let (span_len, unused_imports) = default_export(&format!(
+            r#"
+            import Base from './base';
+            {statement}
+            "#
+        ));
+
+        assert_eq!(span_len, statement.len());
+        assert_eq!(unused_imports, vec!["./base".to_string()]);
+    }
+
+    #[test]
+    fn test_repeated_declarations() {
+        let visitor = visit(
+            r#"
+            export function formatDate(date: Date): string;
+            export function formatDate(date: number): string;
+            export function formatDate(date: any): string { return String(date); }
+            export interface Options { verbose: boolean }
+            export const Options = { verbose: false };
+            export const once = 1;
+            "#,
+        );
+        let metadata = |name: &str| {
+            visitor
+                .exported_ids
+                .get(&ExportedSymbol::from(name))
+                .unwrap()
+        };
+        let declarations_type_only = |name: &str| -> Vec<bool> {
+            metadata(name)
+                .declarations
+                .iter()
+                .map(|declaration| declaration.is_type_only)
+                .collect()
+        };
+
+        assert_eq!(
+            declarations_type_only("formatDate"),
+            vec![false, false, false]
+        );
+        assert_eq!(declarations_type_only("Options"), vec![true, false]);
+        assert_eq!(declarations_type_only("once"), Vec::<bool>::new());
+        // the name keeps the span of its first declaration, and is only
+        // type-only if every declaration is
+        assert_eq!(
+            metadata("formatDate").span,
+            metadata("formatDate").declarations[0].span
+        );
+        assert!(!metadata("Options").is_type_only);
+    }
+
+    #[test]
+    fn test_test_markers() {
+        let chained_test_calls = [
+            r#"describe("suite", () => {});"#,
+            r#"it.only("case", () => {});"#,
+            r#"test.each([1, 2])("case %i", (n) => {});"#,
+            r#"expect(1).toBe(1);"#,
+        ];
+        for source in chained_test_calls {
+            assert!(
+                RawImportExportInfo::from(visit(source)).has_test_markers,
+                "expected test markers in {source}"
+            );
+        }
+
+        let info = RawImportExportInfo::from(visit(
+            r#"
+            export function describe(value) { return value; }
+            export const testHelper = () => suite.it;
+            "#,
+        ));
+        assert!(!info.has_test_markers);
+    }
 }
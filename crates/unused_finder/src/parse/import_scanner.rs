@@ -0,0 +1,234 @@
+//! A lexical scan for the imports of files that fail to parse, e.g. because
+//! they use TypeScript syntax newer than the parser supports.
+//!
+//! The scan only looks for string literals following `import`, `from`,
+//! `require(` and `import(`, skipping comments and the contents of strings.
+//! It does not understand regular expression literals, but strings end at
+//! the end of their line, so a quote within a regex can only hide imports on
+//! the same line.
+
+use super::{ExportedSymbol, RawImportExportInfo};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Word(&'a str),
+    Str(String),
+    // a template literal, whose contents are never a specifier we can follow
+    Template,
+    Punct(char),
+}
+
+// whether the statement being scanned started with `import` or `export`, so
+// a following `from "..."` names the module it imports from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Statement {
+    ImportOrExport,
+    Other,
+}
+
+/// Scans the source of a file that failed to parse for the specifiers it
+/// imports, building degraded import/export info with the parse error.
+///
+/// Each `import ... from` and `export ... from` becomes a namespace import
+/// of the module, so everything it exports stays used. The file itself has
+/// no exports.
+pub fn scan_imports(src: &str, parse_error: String) -> RawImportExportInfo {
+    let tokens = tokenize(src);
+    let mut info = RawImportExportInfo::new();
+    info.degraded = true;
+    info.parse_error = Some(parse_error);
+
+    let string_at = |i: usize| match tokens.get(i) {
+        Some(Token::Str(specifier)) => Some(specifier.clone()),
+        _ => None,
+    };
+    let is_member_access = |i: usize| i > 0 && tokens[i - 1] == Token::Punct('.');
+
+    let mut statement = Statement::Other;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Word("import") if !is_member_access(i) => match tokens.get(i + 1) {
+                // `import "./polyfill"`
+                Some(Token::Str(specifier)) => {
+                    info.executed_paths.insert(specifier.clone());
+                }
+                // `import("./lazy")`
+                Some(Token::Punct('(')) => {
+                    if let Some(specifier) = string_at(i + 2) {
+                        info.imported_paths.insert(specifier);
+                    }
+                }
+                // `import.meta`
+                Some(Token::Punct('.')) => {}
+                _ => statement = Statement::ImportOrExport,
+            },
+            Token::Word("export") if !is_member_access(i) => {
+                statement = Statement::ImportOrExport;
+            }
+            Token::Word("from") if statement == Statement::ImportOrExport => {
+                if let Some(specifier) = string_at(i + 1) {
+                    info.imported_path_ids
+                        .entry(specifier)
+                        .or_default()
+                        .insert(ExportedSymbol::Namespace);
+                    statement = Statement::Other;
+                }
+            }
+            // `require("./module")`, but not `require.resolve("./module")`
+            Token::Word("require")
+                if !is_member_access(i) && tokens.get(i + 1) == Some(&Token::Punct('(')) =>
+            {
+                if let Some(specifier) = string_at(i + 2) {
+                    info.require_paths.insert(specifier);
+                }
+            }
+            Token::Punct(';') => statement = Statement::Other,
+            _ => {}
+        }
+    }
+
+    info
+}
+
+fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek().map(|(_, next)| *next) == Some('/') => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().map(|(_, next)| *next) == Some('*') => {
+                chars.next();
+                let mut previous = '\0';
+                for (_, c) in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '\'' | '"' => {
+                let quote = c;
+                let mut value = String::new();
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        '\n' => break,
+                        c if c == quote => break,
+                        c => value.push(c),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '`' => {
+                // skip to the closing backtick, including any `${...}`
+                let mut depth = 0;
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '$' if depth == 0 && chars.peek().map(|(_, next)| *next) == Some('{') => {
+                            chars.next();
+                            depth += 1;
+                        }
+                        '{' if depth > 0 => depth += 1,
+                        '}' if depth > 0 => depth -= 1,
+                        '`' if depth == 0 => break,
+                        _ => {}
+                    }
+                }
+                tokens.push(Token::Template);
+            }
+            c if is_word_char(c) => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = chars.peek() {
+                    if !is_word_char(*c) {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Word(&src[start..end]));
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+    tokens
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod test {
+    use ahashmap::{AHashMap, AHashSet};
+
+    use super::scan_imports;
+    use crate::parse::ExportedSymbol;
+
+    fn set(specifiers: &[&str]) -> AHashSet<String> {
+        specifiers.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_scan_imports() {
+        let info = scan_imports(
+            r#"
+            import { a } from "./a";
+            import type { B } from './b';
+            export * from "./c";
+            export { d as default } from "./d"
+            import "./polyfill";
+            const lazy = import("./lazy");
+            const legacy = require('./legacy');
+            const resolved = require.resolve("./not-imported");
+            using handle = open(`./template-${from}`);
+            "#,
+            "unexpected token".to_string(),
+        );
+
+        assert!(info.degraded);
+        assert_eq!(info.parse_error.as_deref(), Some("unexpected token"));
+        let namespace: AHashSet<ExportedSymbol> = [ExportedSymbol::Namespace].into_iter().collect();
+        let expected: AHashMap<String, AHashSet<ExportedSymbol>> = ["./a", "./b", "./c", "./d"]
+            .into_iter()
+            .map(|specifier| (specifier.to_string(), namespace.clone()))
+            .collect();
+        assert_eq!(info.imported_path_ids, expected);
+        assert_eq!(info.executed_paths, set(&["./polyfill"]));
+        assert_eq!(info.imported_paths, set(&["./lazy"]));
+        assert_eq!(info.require_paths, set(&["./legacy"]));
+        assert!(info.exported_ids.is_empty());
+    }
+
+    #[test]
+    fn test_scan_imports_skips_comments_and_strings() {
+        let info = scan_imports(
+            r#"
+            // import { a } from "./commented";
+            /* export * from "./block-commented"; */
+            const message = 'import { b } from "./in-a-string"';
+            const from = "./not-a-specifier";
+            const template = `require("./in-a-template")`;
+            import { c } from "./c";
+            "#,
+            "unexpected token".to_string(),
+        );
+
+        let specifiers: Vec<&String> = info.imported_path_ids.keys().collect();
+        assert_eq!(specifiers, vec!["./c"]);
+        assert!(info.require_paths.is_empty());
+    }
+}
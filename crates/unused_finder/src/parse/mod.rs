@@ -3,6 +3,9 @@ pub mod exports_visitor;
 pub mod exports_visitor_runner;
 #[cfg(test)]
 pub mod exports_visitor_tests;
+pub mod import_scanner;
 
 pub use data::*;
-pub use exports_visitor_runner::get_file_import_export_info;
+pub use exports_visitor_runner::{
+    get_file_import_export_info, get_file_import_export_info_or_scan,
+};
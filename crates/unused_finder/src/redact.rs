@@ -0,0 +1,397 @@
+use std::collections::BTreeMap;
+
+use ahashmap::{AHashMap, AHashSet};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{report::split_declaration_suffix, UnusedFinderReport, UsedTagEnum};
+
+/// Strings that are part of the report format rather than names from the
+/// repo, and so are never redacted
+const PRESERVED_NAMES: &[&str] = &["default", "*", "<execution-only>"];
+
+const PATH_SEPARATORS: [char; 2] = ['/', '\\'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum NameKind {
+    Dir,
+    File,
+    Symbol,
+    // strings containing whitespace, e.g. error messages
+    Text,
+}
+
+impl NameKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            NameKind::Dir => "dir",
+            NameKind::File => "file",
+            NameKind::Symbol => "symbol",
+            NameKind::Text => "text",
+        }
+    }
+}
+
+/// Maps each pseudonym of a redacted report back to the name it replaced.
+///
+/// This is only meant to be kept locally, to decode answers about a shared
+/// redacted report.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionMapping {
+    pub names: BTreeMap<String, String>,
+}
+
+impl RedactionMapping {
+    /// Restores the original names in a string of a redacted report, e.g.
+    /// `dir_0001/file_0002.ts` or `symbol_0003`. Strings without any
+    /// pseudonyms are returned as they are.
+    pub fn decode(&self, redacted: &str) -> String {
+        if let Some(name) = self.names.get(redacted) {
+            return name.clone();
+        }
+        if let (name, Some(suffix)) = split_declaration_suffix(redacted) {
+            if let Some(original) = self.names.get(name) {
+                return format!("{original}#{suffix}");
+            }
+        }
+        redacted
+            .split_inclusive(PATH_SEPARATORS)
+            .map(|segment| {
+                let (name, separator) = split_separator(segment);
+                if let Some(original) = self.names.get(name) {
+                    return format!("{original}{separator}");
+                }
+                let (stem, extension) = split_extension(name);
+                match self.names.get(stem) {
+                    Some(original) => format!("{original}{extension}{separator}"),
+                    None => segment.to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Replaces the names of directories, files and symbols in a serialized
+/// report with stable pseudonyms, e.g. `dir_0001/file_0002.ts` and
+/// `symbol_0003`, so the report can be shared without leaking them.
+///
+/// The same name always gets the same pseudonym, so a redacted report keeps
+/// the structure of the original: directory depth, file extensions, counts,
+/// spans and tags are all unchanged.
+#[derive(Debug)]
+pub struct Redactor {
+    preserved: AHashSet<String>,
+    pseudonyms: AHashMap<(NameKind, String), String>,
+    counts: AHashMap<NameKind, usize>,
+    mapping: RedactionMapping,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Redactor {
+    pub fn new() -> Self {
+        let tags = [
+            UsedTagEnum::Entry,
+            UsedTagEnum::Ignored,
+            UsedTagEnum::Test,
+            UsedTagEnum::TypeOnly,
+        ]
+        .into_iter()
+        .filter_map(|tag| match serde_json::to_value(tag) {
+            Ok(Value::String(tag)) => Some(tag),
+            _ => None,
+        });
+
+        Self {
+            preserved: PRESERVED_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .chain(tags)
+                .collect(),
+            pseudonyms: AHashMap::default(),
+            counts: AHashMap::default(),
+            mapping: RedactionMapping::default(),
+        }
+    }
+
+    /// Keeps the given strings as they are, e.g. the names of checks in a
+    /// summary.
+    pub fn preserve<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        self.preserved
+            .extend(names.into_iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Redacts every string and map key of a serialized report in place.
+    ///
+    /// This works on the json value rather than on the report types, so that
+    /// fields added to a report later are redacted without any changes here.
+    /// Struct fields serialize as map keys as well, so keys are only redacted
+    /// if they look like paths.
+    pub fn redact_value(&mut self, value: &mut Value) {
+        match value {
+            Value::String(string) => *string = self.redact_str(string),
+            Value::Array(items) => {
+                for item in items.iter_mut() {
+                    self.redact_value(item);
+                }
+            }
+            Value::Object(map) => {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        let key = if is_path_like(&key) {
+                            self.redact_path(&key)
+                        } else {
+                            key
+                        };
+                        self.redact_value(&mut value);
+                        (key, value)
+                    })
+                    .collect();
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+    }
+
+    /// Replaces each segment of a path with a pseudonym, keeping the
+    /// extension of the file name and any `.` or `..` segments.
+    pub fn redact_path(&mut self, path: &str) -> String {
+        path.split_inclusive(PATH_SEPARATORS)
+            .map(|segment| {
+                let (name, separator) = split_separator(segment);
+                let name = match name {
+                    "" | "." | ".." => name.to_string(),
+                    // only the last segment of a path is a file name
+                    _ if separator.is_empty() => {
+                        let (stem, extension) = split_extension(name);
+                        format!("{}{extension}", self.pseudonym(NameKind::File, stem))
+                    }
+                    _ => self.pseudonym(NameKind::Dir, name),
+                };
+                name + separator
+            })
+            .collect()
+    }
+
+    /// Gets the mapping from the pseudonyms handed out so far back to the
+    /// names they replaced
+    pub fn into_mapping(self) -> RedactionMapping {
+        self.mapping
+    }
+
+    fn redact_str(&mut self, string: &str) -> String {
+        if string.is_empty() || self.preserved.contains(string) {
+            string.to_string()
+        } else if is_path_like(string) {
+            self.redact_path(string)
+        } else if string.contains(char::is_whitespace) {
+            self.pseudonym(NameKind::Text, string)
+        } else if let (name, Some(suffix)) = split_declaration_suffix(string) {
+            // the declarations of a name declared more than once share its pseudonym
+            format!("{}#{suffix}", self.pseudonym(NameKind::Symbol, name))
+        } else {
+            self.pseudonym(NameKind::Symbol, string)
+        }
+    }
+
+    fn pseudonym(&mut self, kind: NameKind, name: &str) -> String {
+        let key = (kind, name.to_string());
+        if let Some(pseudonym) = self.pseudonyms.get(&key) {
+            return pseudonym.clone();
+        }
+
+        let count = self.counts.entry(kind).or_default();
+        *count += 1;
+        let pseudonym = format!("{}_{:04}", kind.prefix(), count);
+        self.mapping
+            .names
+            .insert(pseudonym.clone(), name.to_string());
+        self.pseudonyms.insert(key, pseudonym.clone());
+        pseudonym
+    }
+}
+
+impl UnusedFinderReport {
+    /// Serializes the report with every name replaced by a pseudonym, along
+    /// with the mapping to decode them. See [Redactor].
+    pub fn to_redacted_json(&self) -> Result<(Value, RedactionMapping), serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        let mut redactor = Redactor::new();
+        redactor.redact_value(&mut value);
+        Ok((value, redactor.into_mapping()))
+    }
+}
+
+// a path, or a single file name with an extension
+fn is_path_like(string: &str) -> bool {
+    string.contains(PATH_SEPARATORS)
+        || (!string.contains(char::is_whitespace) && !split_extension(string).1.is_empty())
+}
+
+// splits the trailing separator off a segment of a path
+fn split_separator(segment: &str) -> (&str, &str) {
+    match segment.char_indices().last() {
+        Some((i, c)) if PATH_SEPARATORS.contains(&c) => segment.split_at(i),
+        _ => (segment, ""),
+    }
+}
+
+// splits a file name into its stem and its extension, which includes the
+// `.d` of declaration files
+fn split_extension(name: &str) -> (&str, &str) {
+    let dot = match name.rfind('.') {
+        Some(dot)
+            if dot > 0
+                && dot + 1 < name.len()
+                && name[dot + 1..].chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            dot
+        }
+        _ => return (name, ""),
+    };
+    let (stem, extension) = name.split_at(dot);
+    match stem.strip_suffix(".d") {
+        Some(declaration_stem) if !declaration_stem.is_empty() => {
+            name.split_at(declaration_stem.len())
+        }
+        _ => (stem, extension),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ahashmap::AHashMap;
+    use serde_json::Value;
+
+    use super::{RedactionMapping, Redactor};
+    use crate::{
+        SameNameExportReport, SymbolReport, SymbolReportWithTags, UnusedFinderReport, UsedTagEnum,
+    };
+
+    fn symbol(id: &str, start: u32) -> SymbolReport {
+        SymbolReport {
+            id: id.to_string(),
+            start,
+            end: start + 10,
+            locally_used: false,
+            local_name: None,
+        }
+    }
+
+    // restores every string and key of a redacted value
+    fn decode_value(value: &Value, mapping: &RedactionMapping) -> Value {
+        match value {
+            Value::String(string) => Value::String(mapping.decode(string)),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| decode_value(item, mapping))
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| (mapping.decode(key), decode_value(value, mapping)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    #[test]
+    fn test_redacted_report_isomorphic() {
+        let report = UnusedFinderReport {
+            unused_files: vec![
+                "/home/alice/acme-monorepo/packages/billing-core/src/invoiceMath.ts".to_string(),
+            ],
+            unused_symbols: AHashMap::from_iter([(
+                "/home/alice/acme-monorepo/packages/billing-core/src/invoiceMath.ts".to_string(),
+                vec![symbol("computeTaxRate", 12), symbol("default", 40)],
+            )]),
+            extra_file_tags: AHashMap::from_iter([(
+                "/home/alice/acme-monorepo/packages/billing-core/src/types.d.ts".to_string(),
+                vec![UsedTagEnum::TypeOnly],
+            )]),
+            extra_symbol_tags: AHashMap::from_iter([(
+                "/home/alice/acme-monorepo/packages/billing-core/src/types.d.ts".to_string(),
+                vec![SymbolReportWithTags {
+                    symbol: symbol("InvoiceLine", 3),
+                    tags: vec![UsedTagEnum::TypeOnly],
+                }],
+            )]),
+            same_name_exports: AHashMap::from_iter([(
+                "/home/alice/acme-monorepo/packages/billing-core/src/invoiceMath.ts".to_string(),
+                vec![SameNameExportReport {
+                    id: "computeTaxRate".to_string(),
+                    file_paths: vec![
+                        "/home/alice/acme-monorepo/packages/payroll/src/taxes.js".to_string()
+                    ],
+                }],
+            )]),
+            ..Default::default()
+        };
+        let original = serde_json::to_value(&report).unwrap();
+
+        let (redacted, mapping) = report.to_redacted_json().unwrap();
+
+        let redacted_str = serde_json::to_string(&redacted).unwrap();
+        for name in [
+            "home",
+            "alice",
+            "acme-monorepo",
+            "packages",
+            "billing-core",
+            "src",
+            "invoiceMath",
+            "types",
+            "payroll",
+            "taxes",
+            "computeTaxRate",
+            "InvoiceLine",
+        ] {
+            assert!(
+                !redacted_str.contains(name),
+                "redacted report contains {name}: {redacted_str}"
+            );
+        }
+        // keys are visited in order, so the tagged declaration file comes first
+        let redacted_file = "/dir_0001/dir_0002/dir_0003/dir_0004/dir_0005/dir_0006/file_0002.ts";
+        let redacted_types =
+            "/dir_0001/dir_0002/dir_0003/dir_0004/dir_0005/dir_0006/file_0001.d.ts";
+        assert_eq!(redacted["unused_files"][0], redacted_file);
+        assert_eq!(
+            redacted["unused_symbols"][redacted_file][1]["id"],
+            "default"
+        );
+        assert_eq!(
+            redacted["extra_file_tags"][redacted_types],
+            serde_json::json!(["typeonly"])
+        );
+
+        // the mapping restores the original report exactly
+        assert_eq!(decode_value(&redacted, &mapping), original);
+    }
+
+    #[test]
+    fn test_stable_pseudonyms() {
+        let mut redactor = Redactor::new().preserve(["unusedFiles"]);
+        let mut value = serde_json::json!({
+            "checks": ["unusedFiles", "computeTaxRate"],
+            "src/a.ts": ["computeTaxRate", "Unable to resolve ./secret"],
+        });
+
+        redactor.redact_value(&mut value);
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "checks": ["unusedFiles", "symbol_0001"],
+                "dir_0001/file_0001.ts": ["symbol_0001", "dir_0002/file_0002"],
+            })
+        );
+    }
+}
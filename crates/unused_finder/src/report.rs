@@ -2,17 +2,23 @@ use core::{
     convert::Into,
     option::Option::{None, Some},
 };
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 use ahashmap::AHashMap;
 use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use swc_common::source_map::SmallPos;
 
 use crate::{
+    collisions::{AmbiguousReexport, BarrelBypassImport, SymbolCollision},
     graph::{Graph, GraphFile},
-    parse::ExportedSymbol,
+    parse::{ExportDeclaration, ExportedSymbol, ExportedSymbolMetadata, ResolvedImportExportInfo},
     tag::UsedTag,
+    walk::is_opaque_module,
     UnusedFinderResult, UsedTagEnum,
 };
 
@@ -22,6 +28,93 @@ pub struct SymbolReport {
     pub id: String,
     pub start: u32,
     pub end: u32,
+    /// If true, the symbol is referenced within its own file, so only the
+    /// `export` is unused. Only set for unused symbols.
+    #[serde(default)]
+    pub locally_used: bool,
+    /// For default exports, the local name of the exported value, if it has
+    /// one. The id stays `default` so reports can still be matched on it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_name: Option<String>,
+}
+
+impl SymbolReport {
+    /// Checks if an id, e.g. from a suppression list, refers to this symbol.
+    ///
+    /// Ids without a declaration suffix also match the other declarations of
+    /// the name (e.g. `formatDate` matches `formatDate#2`), so ids recorded
+    /// before a name was declared more than once keep matching.
+    pub fn matches_id(&self, id: &str) -> bool {
+        if self.id == id {
+            return true;
+        }
+        match split_declaration_suffix(id) {
+            (_, Some(_)) => false,
+            (name, None) => split_declaration_suffix(&self.id).0 == name,
+        }
+    }
+}
+
+/// Suffix of the ids of type declarations merged with a value of the same
+/// name, e.g. `Options#type` for `interface Options` next to `const Options`
+pub const TYPE_DECLARATION_SUFFIX: &str = "type";
+
+/// Splits the id of a symbol into its name and the suffix that tells apart the
+/// declarations of a name declared more than once in a file, e.g.
+/// `("formatDate", Some("2"))` for `formatDate#2`, or `("Options", Some("type"))`
+/// for `Options#type`. Ids without a suffix are returned as they are.
+pub fn split_declaration_suffix(id: &str) -> (&str, Option<&str>) {
+    if let Some((name, suffix)) = id.rsplit_once('#') {
+        let is_ordinal = |ordinal: &str| ordinal.chars().all(|c| c.is_ascii_digit());
+        let is_suffix = match suffix.strip_prefix(TYPE_DECLARATION_SUFFIX) {
+            Some(ordinal) => is_ordinal(ordinal),
+            None => !suffix.is_empty() && is_ordinal(suffix),
+        };
+        if is_suffix && !name.is_empty() {
+            return (name, Some(suffix));
+        }
+    }
+    (id, None)
+}
+
+/// Gets the report ids of the declarations of a name declared more than once
+/// in a file, in source order.
+///
+/// When types are merged with values of the same name, the type declarations
+/// are suffixed with `#type`. Other repeated declarations (e.g. overload
+/// signatures) are suffixed with their ordinal, starting from `#2`, so the
+/// first declaration keeps the plain name.
+pub(crate) fn declaration_ids(
+    symbol: &ExportedSymbol,
+    declarations: &[ExportDeclaration],
+) -> Vec<String> {
+    let name = symbol.to_string();
+    let merges_types = declarations
+        .iter()
+        .any(|declaration| declaration.is_type_only)
+        && declarations
+            .iter()
+            .any(|declaration| !declaration.is_type_only);
+    let mut num_values = 0;
+    let mut num_types = 0;
+    declarations
+        .iter()
+        .map(|declaration| {
+            if merges_types && declaration.is_type_only {
+                num_types += 1;
+                match num_types {
+                    1 => format!("{name}#{TYPE_DECLARATION_SUFFIX}"),
+                    n => format!("{name}#{TYPE_DECLARATION_SUFFIX}{n}"),
+                }
+            } else {
+                num_values += 1;
+                match num_values {
+                    1 => name.clone(),
+                    n => format!("{name}#{n}"),
+                }
+            }
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Serialize, Deserialize)]
@@ -30,6 +123,253 @@ pub struct SymbolReportWithTags {
     pub tags: Vec<UsedTagEnum>,
 }
 
+/// A file exporting more symbols than the configured `god_module_threshold`
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Serialize, Deserialize)]
+pub struct LargeModuleReport {
+    pub file_path: String,
+    pub num_exported_symbols: usize,
+}
+
+/// A file that failed to parse, whose imports were found by a lexical scan
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Serialize, Deserialize)]
+pub struct DegradedFileReport {
+    pub file_path: String,
+    pub parse_error: String,
+}
+
+/// A name exported from an entry file that resolves to more than one
+/// definition, after following re-exports and expanding `export *`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolCollisionReport {
+    pub entry_file: String,
+    pub name: String,
+    /// Every definition of the name, in the order the entry file lists them
+    pub definitions: Vec<SymbolDefinitionReport>,
+}
+
+/// A definition of a colliding name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolDefinitionReport {
+    pub file_path: String,
+    /// The name of the symbol in the file that defines it
+    pub symbol: String,
+    pub start: u32,
+    pub end: u32,
+    /// True for the definition that importers of the name currently get
+    pub wins: bool,
+}
+
+/// A name that a file gets from more than one `export *` with different
+/// definitions, so the file does not export it at all
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmbiguousReexportReport {
+    pub file_path: String,
+    pub name: String,
+    /// The files of the `export *` contributing the name
+    pub sources: Vec<String>,
+}
+
+/// An import of a deep path into another package, for a symbol that the
+/// package also exports from its public barrel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BarrelBypassImportReport {
+    /// The file with the import
+    pub importer: String,
+    /// The file of the other package it imports the symbol from
+    pub imported_file: String,
+    pub symbol: String,
+    /// The package to import the symbol from instead
+    pub package_name: String,
+    /// The public barrel of the package
+    pub barrel_file: String,
+    /// The name the barrel exports the symbol as
+    pub barrel_name: String,
+}
+
+/// An unused exported symbol, with the other files that export a symbol of
+/// the same name. One of them may be the canonical source the symbol was
+/// duplicated from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SameNameExportReport {
+    pub id: String,
+    /// Other files exporting a symbol of the same name, sorted by path
+    pub file_paths: Vec<String>,
+}
+
+/// A name exported by more than one file, so importers may pick up the wrong
+/// copy of the symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmbiguousSymbolNameReport {
+    pub name: String,
+    /// The files exporting a symbol of the name, sorted by path
+    pub file_paths: Vec<String>,
+    /// The files of `file_paths` that other files import the name from,
+    /// sorted by path
+    pub imported_from: Vec<String>,
+}
+
+/// An imported binding that is never referenced within the importing file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnusedImportReport {
+    /// The import specifier, as written in the import statement
+    pub specifier: String,
+    /// The imported symbol, in the canonical string form of ExportedSymbol
+    pub id: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// An exported symbol of a file in the graph of a result, borrowed without
+/// copying its name or location
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolRef<'a> {
+    pub symbol: &'a ExportedSymbol,
+    pub metadata: &'a ExportedSymbolMetadata,
+}
+
+impl SymbolRef<'_> {
+    /// Copies the symbol into an owned report
+    pub fn to_report(&self) -> SymbolReport {
+        SymbolReport {
+            id: self.symbol.to_string(),
+            start: self.metadata.span.lo().to_u32(),
+            end: self.metadata.span.hi().to_u32(),
+            locally_used: self.metadata.locally_used,
+            local_name: self.metadata.local_name.clone(),
+        }
+    }
+
+    /// Gets the number of reports `to_reports` copies the symbol into
+    pub fn report_count(&self) -> usize {
+        self.metadata.declarations.len().max(1)
+    }
+
+    /// Copies the symbol into owned reports, one for each of its declarations.
+    /// The declarations of a name declared more than once get distinct ids,
+    /// see [split_declaration_suffix].
+    pub fn to_reports(&self) -> Vec<SymbolReport> {
+        if self.metadata.declarations.is_empty() {
+            return vec![self.to_report()];
+        }
+        declaration_ids(self.symbol, &self.metadata.declarations)
+            .into_iter()
+            .zip(self.metadata.declarations.iter())
+            .map(|(id, declaration)| SymbolReport {
+                id,
+                start: declaration.span.lo().to_u32(),
+                end: declaration.span.hi().to_u32(),
+                locally_used: self.metadata.locally_used,
+                local_name: self.metadata.local_name.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Number of imports of each kind, e.g. to gauge the mix of ES modules and
+/// CommonJS in a repo.
+///
+/// Static imports and re-exports count once per imported name. The other
+/// kinds count once per imported path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportKindCounts {
+    /// `import { foo } from './foo'`
+    pub static_named: usize,
+    /// `import foo from './foo'`
+    pub static_default: usize,
+    /// `import * as foo from './foo'`
+    pub namespace: usize,
+    /// `import('./foo')`
+    pub dynamic: usize,
+    /// `require('./foo')`
+    pub require: usize,
+    /// `export { foo } from './foo'` and `export * from './foo'`
+    pub re_export: usize,
+    /// `import './foo'`
+    pub effect_only: usize,
+}
+
+impl ImportKindCounts {
+    /// Adds the imports of a single file to the counts
+    pub fn add_file(&mut self, import_export_info: &ResolvedImportExportInfo) {
+        for symbol in import_export_info.imported_symbols.values().flatten() {
+            match symbol {
+                ExportedSymbol::Named(_) => self.static_named += 1,
+                ExportedSymbol::Default => self.static_default += 1,
+                ExportedSymbol::Namespace => self.namespace += 1,
+                ExportedSymbol::ExecutionOnly => self.effect_only += 1,
+            }
+        }
+        self.dynamic += import_export_info.imported_paths.len();
+        self.require += import_export_info.require_paths.len();
+        self.re_export += import_export_info
+            .export_from_symbols
+            .values()
+            .map(|symbols| symbols.len())
+            .sum::<usize>();
+        self.effect_only += import_export_info.executed_paths.len();
+    }
+}
+
+/// Counts the imports of each kind across a set of files
+pub(crate) fn import_kind_counts<'a>(
+    files: impl Iterator<Item = &'a GraphFile>,
+) -> ImportKindCounts {
+    let mut counts = ImportKindCounts::default();
+    for file in files {
+        counts.add_file(&file.import_export_info);
+    }
+    counts
+}
+
+/// How file paths are rendered in an UnusedFinderReport
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PathDisplay {
+    /// Absolute paths
+    #[default]
+    Absolute,
+    /// Paths relative to the repo root
+    RepoRelative,
+    /// Paths relative to the current working directory
+    CwdRelative,
+}
+
+/// Which names exported by more than one file are listed in the
+/// `ambiguous_symbol_names` of an UnusedFinderReport
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum AmbiguousSymbolNames {
+    /// No names are listed
+    #[default]
+    Off,
+    /// Every name exported by more than one file
+    All,
+    /// Only names that are imported from more than one of the files that
+    /// export them
+    ConflictingImports,
+}
+
+impl PathDisplay {
+    /// Gets the directory paths are rendered relative to, or None if paths
+    /// are left absolute.
+    pub(crate) fn base_dir(self, repo_root: &Path) -> Option<PathBuf> {
+        match self {
+            PathDisplay::Absolute => None,
+            PathDisplay::RepoRelative => Some(repo_root.to_path_buf()),
+            PathDisplay::CwdRelative => std::env::current_dir().ok(),
+        }
+    }
+}
+
+/// Renders an absolute path relative to `base_dir`, if there is one.
+/// Paths that can't be made relative are left as they are.
+pub(crate) fn display_path(file_path: &Path, base_dir: Option<&Path>) -> String {
+    match base_dir.and_then(|base_dir| pathdiff::diff_paths(file_path, base_dir)) {
+        Some(relative_path) => relative_path.to_string_lossy().to_string(),
+        None => file_path.to_string_lossy().to_string(),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 struct FileInfo {
     tags: Vec<UsedTagEnum>,
@@ -49,6 +389,225 @@ pub struct UnusedFinderReport {
     /// File tag information for files that are used.
     pub extra_file_tags: AHashMap<String, Vec<UsedTagEnum>>,
     pub extra_symbol_tags: AHashMap<String, Vec<SymbolReportWithTags>>,
+
+    /// Files exporting more symbols than the `god_module_threshold`, with the
+    /// largest first. Empty if no threshold is configured.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub large_modules: Vec<LargeModuleReport>,
+
+    /// Files tracked as opaque modules (e.g. `.vue` or `.svelte` components).
+    /// Their own imports are not parsed, so files that only they import may
+    /// be reported as unused.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub opaque_modules: Vec<String>,
+
+    /// Files that failed to parse, with their parse errors. Their imports were
+    /// found by a lexical scan, so the files they import stay used, but their
+    /// own symbols are not reported.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub degraded_files: Vec<DegradedFileReport>,
+
+    /// Name of the implicit root package synthesized for a repo without any
+    /// package.json. Only its configured entry files are entrypoints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synthetic_root_package: Option<String>,
+
+    /// Names exported from entry files that resolve to more than one
+    /// definition. Only one of the definitions is reachable through the name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbol_collisions: Vec<SymbolCollisionReport>,
+
+    /// Names that files get from more than one `export *` with different
+    /// definitions. The files do not export these names, so importing them
+    /// fails at runtime.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ambiguous_reexports: Vec<AmbiguousReexportReport>,
+
+    /// Imports of deep paths into other packages for symbols that the
+    /// packages also export from their public barrels. Only set when
+    /// `report_barrel_bypass_imports` is configured.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub barrel_bypass_imports: Vec<BarrelBypassImportReport>,
+
+    /// Unused exported symbols of each file that share their name with
+    /// symbols exported from other files. Only set when
+    /// `suggest_same_name_exports` is configured.
+    #[serde(default, skip_serializing_if = "AHashMap::is_empty")]
+    pub same_name_exports: AHashMap<String, Vec<SameNameExportReport>>,
+
+    /// Names exported by more than one file, sorted by name. Only set when
+    /// `report_ambiguous_symbol_names` is configured.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ambiguous_symbol_names: Vec<AmbiguousSymbolNameReport>,
+
+    /// Imported bindings of each file that are never referenced within the
+    /// file. Only set when `report_unused_imports` is configured.
+    #[serde(default, skip_serializing_if = "AHashMap::is_empty")]
+    pub unused_imports: AHashMap<String, Vec<UnusedImportReport>>,
+
+    /// Number of imports of each kind across the files of the report. Only
+    /// set when `count_import_kinds` is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_kind_counts: Option<ImportKindCounts>,
+}
+
+impl UnusedFinderReport {
+    /// Drops all entries for files that do not match the predicate
+    pub(crate) fn retain_files(&mut self, predicate: impl Fn(&str) -> bool) {
+        self.unused_files.retain(|file_path| predicate(file_path));
+        self.unused_symbols
+            .retain(|file_path, _| predicate(file_path));
+        self.extra_file_tags
+            .retain(|file_path, _| predicate(file_path));
+        self.extra_symbol_tags
+            .retain(|file_path, _| predicate(file_path));
+        self.large_modules
+            .retain(|large_module| predicate(&large_module.file_path));
+        self.opaque_modules.retain(|file_path| predicate(file_path));
+        self.degraded_files
+            .retain(|degraded_file| predicate(&degraded_file.file_path));
+        self.symbol_collisions
+            .retain(|collision| predicate(&collision.entry_file));
+        self.ambiguous_reexports
+            .retain(|ambiguous_reexport| predicate(&ambiguous_reexport.file_path));
+        self.barrel_bypass_imports
+            .retain(|bypass_import| predicate(&bypass_import.importer));
+        self.same_name_exports
+            .retain(|file_path, _| predicate(file_path));
+        for ambiguous_name in self.ambiguous_symbol_names.iter_mut() {
+            ambiguous_name.file_paths.retain(|x| predicate(x));
+            ambiguous_name.imported_from.retain(|x| predicate(x));
+        }
+        self.ambiguous_symbol_names
+            .retain(|ambiguous_name| ambiguous_name.file_paths.len() > 1);
+        self.unused_imports
+            .retain(|file_path, _| predicate(file_path));
+    }
+
+    /// Renders all file paths in the report according to `path_display`.
+    ///
+    /// Paths are expected to be absolute, as they are when the report is built.
+    /// Paths that can't be made relative are left as they are.
+    pub fn with_path_display(mut self, path_display: PathDisplay, repo_root: &Path) -> Self {
+        let base = match path_display.base_dir(repo_root) {
+            Some(base) => base,
+            None => return self,
+        };
+
+        self.map_file_paths(|file_path| display_path(Path::new(file_path), Some(&base)));
+        self
+    }
+
+    /// Rewrites every file path in the report
+    fn map_file_paths(&mut self, f: impl Fn(&str) -> String) {
+        self.unused_files = self.unused_files.iter().map(|x| f(x)).collect();
+        self.unused_symbols = std::mem::take(&mut self.unused_symbols)
+            .into_iter()
+            .map(|(file_path, symbols)| (f(&file_path), symbols))
+            .collect();
+        self.extra_file_tags = std::mem::take(&mut self.extra_file_tags)
+            .into_iter()
+            .map(|(file_path, tags)| (f(&file_path), tags))
+            .collect();
+        self.extra_symbol_tags = std::mem::take(&mut self.extra_symbol_tags)
+            .into_iter()
+            .map(|(file_path, symbols)| (f(&file_path), symbols))
+            .collect();
+        for large_module in self.large_modules.iter_mut() {
+            large_module.file_path = f(&large_module.file_path);
+        }
+        self.opaque_modules = self.opaque_modules.iter().map(|x| f(x)).collect();
+        for degraded_file in self.degraded_files.iter_mut() {
+            degraded_file.file_path = f(&degraded_file.file_path);
+        }
+        for collision in self.symbol_collisions.iter_mut() {
+            collision.entry_file = f(&collision.entry_file);
+            for definition in collision.definitions.iter_mut() {
+                definition.file_path = f(&definition.file_path);
+            }
+        }
+        for ambiguous_reexport in self.ambiguous_reexports.iter_mut() {
+            ambiguous_reexport.file_path = f(&ambiguous_reexport.file_path);
+            ambiguous_reexport.sources = ambiguous_reexport.sources.iter().map(|x| f(x)).collect();
+        }
+        for bypass_import in self.barrel_bypass_imports.iter_mut() {
+            bypass_import.importer = f(&bypass_import.importer);
+            bypass_import.imported_file = f(&bypass_import.imported_file);
+            bypass_import.barrel_file = f(&bypass_import.barrel_file);
+        }
+        self.same_name_exports = std::mem::take(&mut self.same_name_exports)
+            .into_iter()
+            .map(|(file_path, exports)| {
+                let exports = exports
+                    .into_iter()
+                    .map(|export| SameNameExportReport {
+                        file_paths: export.file_paths.iter().map(|x| f(x)).collect(),
+                        ..export
+                    })
+                    .collect();
+                (f(&file_path), exports)
+            })
+            .collect();
+        for ambiguous_name in self.ambiguous_symbol_names.iter_mut() {
+            ambiguous_name.file_paths = ambiguous_name.file_paths.iter().map(|x| f(x)).collect();
+            ambiguous_name.imported_from =
+                ambiguous_name.imported_from.iter().map(|x| f(x)).collect();
+        }
+        self.unused_imports = std::mem::take(&mut self.unused_imports)
+            .into_iter()
+            .map(|(file_path, imports)| (f(&file_path), imports))
+            .collect();
+    }
+}
+
+// Writes the line of a completely unused file, with the number of its unused
+// exports, if it has any
+pub(crate) fn write_unused_file(
+    f: &mut std::fmt::Formatter<'_>,
+    file_path: &str,
+    item_count: usize,
+) -> std::fmt::Result {
+    match item_count {
+        0 => writeln!(f, "{} is completely unused", file_path),
+        _ => writeln!(
+            f,
+            "{} is completely unused ({} item{})",
+            file_path,
+            item_count,
+            if item_count > 1 { "s" } else { "" },
+        ),
+    }
+}
+
+// Writes the header line of a file with some unused exports, which are
+// written after it by write_unused_symbol
+pub(crate) fn write_partially_unused_file(
+    f: &mut std::fmt::Formatter<'_>,
+    file_path: &str,
+    item_count: usize,
+) -> std::fmt::Result {
+    writeln!(
+        f,
+        "{} is partially unused ({} unused export{}):",
+        file_path,
+        item_count,
+        if item_count > 1 { "s" } else { "" },
+    )
+}
+
+pub(crate) fn write_unused_symbol(
+    f: &mut std::fmt::Formatter<'_>,
+    item: &SymbolReport,
+) -> std::fmt::Result {
+    let label = match &item.local_name {
+        Some(local_name) => format!("{} ({})", item.id, local_name),
+        None => item.id.clone(),
+    };
+    if item.locally_used {
+        writeln!(f, "  - {} (only used locally)", label)
+    } else {
+        writeln!(f, "  - {}", label)
+    }
 }
 
 impl Display for UnusedFinderReport {
@@ -66,41 +625,357 @@ impl Display for UnusedFinderReport {
             .collect::<Vec<String>>();
 
         for file_path in unused_files.iter() {
-            match self.unused_symbols.get(file_path) {
-                Some(items) => writeln!(
-                    f,
-                    "{} is completely unused ({} item{})",
-                    file_path,
-                    items.len(),
-                    if items.len() > 1 { "s" } else { "" },
-                )?,
-                None => writeln!(f, "{} is completely unused", file_path)?,
-            };
+            let item_count = self.unused_symbols.get(file_path).map_or(0, Vec::len);
+            write_unused_file(f, file_path, item_count)?;
         }
 
         for (file_path, items) in self.unused_symbols.iter() {
             if unused_files_set.contains(file_path) {
                 continue;
             }
+            write_partially_unused_file(f, file_path, items.len())?;
+            for item in items.iter() {
+                write_unused_symbol(f, item)?;
+            }
+        }
+
+        for collision in self.symbol_collisions.iter() {
             writeln!(
                 f,
-                "{} is partially unused ({} unused export{}):",
-                file_path,
-                items.len(),
-                if items.len() > 1 { "s" } else { "" },
+                "{} exports {} from {} definitions:",
+                collision.entry_file,
+                collision.name,
+                collision.definitions.len(),
             )?;
-            for item in items.iter() {
-                writeln!(f, "  - {}", item.id)?;
+            for definition in collision.definitions.iter() {
+                writeln!(
+                    f,
+                    "  - {} in {}{}",
+                    definition.symbol,
+                    definition.file_path,
+                    if definition.wins { " (wins)" } else { "" },
+                )?;
             }
         }
 
+        for ambiguous_reexport in self.ambiguous_reexports.iter() {
+            writeln!(
+                f,
+                "{} does not export {}, which is ambiguous between: {}",
+                ambiguous_reexport.file_path,
+                ambiguous_reexport.name,
+                ambiguous_reexport.sources.join(", "),
+            )?;
+        }
+
+        for bypass_import in self.barrel_bypass_imports.iter() {
+            writeln!(
+                f,
+                "{} imports {} from {}, which should be imported from {} instead",
+                bypass_import.importer,
+                bypass_import.symbol,
+                bypass_import.imported_file,
+                bypass_import.package_name,
+            )?;
+        }
+
+        for (file_path, exports) in self.same_name_exports.iter() {
+            for export in exports.iter() {
+                writeln!(
+                    f,
+                    "{} exports unused {}, which is also exported from: {}",
+                    file_path,
+                    export.id,
+                    export.file_paths.join(", "),
+                )?;
+            }
+        }
+
+        for ambiguous_name in self.ambiguous_symbol_names.iter() {
+            writeln!(
+                f,
+                "{} is exported from {} files: {}",
+                ambiguous_name.name,
+                ambiguous_name.file_paths.len(),
+                ambiguous_name.file_paths.join(", "),
+            )?;
+            if !ambiguous_name.imported_from.is_empty() {
+                writeln!(
+                    f,
+                    "  - imported from: {}",
+                    ambiguous_name.imported_from.join(", "),
+                )?;
+            }
+        }
+
+        for (file_path, imports) in self.unused_imports.iter() {
+            for import in imports.iter() {
+                writeln!(
+                    f,
+                    "{} imports {} from {}, which is never used",
+                    file_path, import.id, import.specifier,
+                )?;
+            }
+        }
+
+        for large_module in self.large_modules.iter() {
+            writeln!(
+                f,
+                "{} is a large module ({} exported symbols)",
+                large_module.file_path, large_module.num_exported_symbols,
+            )?;
+        }
+
+        if !self.opaque_modules.is_empty() {
+            writeln!(
+                f,
+                "Note: {} opaque module{} tracked without parsing {} imports. Files imported only by opaque modules may be reported as unused.",
+                self.opaque_modules.len(),
+                if self.opaque_modules.len() > 1 { "s were" } else { " was" },
+                if self.opaque_modules.len() > 1 { "their" } else { "its" },
+            )?;
+        }
+
+        for degraded_file in self.degraded_files.iter() {
+            writeln!(
+                f,
+                "{} could not be parsed, so only its imports were scanned: {}",
+                degraded_file.file_path, degraded_file.parse_error,
+            )?;
+        }
+
+        if let Some(counts) = &self.import_kind_counts {
+            writeln!(
+                f,
+                "Imports by kind: {} named, {} default, {} namespace, {} dynamic, {} require, {} re-export, {} effect-only",
+                counts.static_named,
+                counts.static_default,
+                counts.namespace,
+                counts.dynamic,
+                counts.require,
+                counts.re_export,
+                counts.effect_only,
+            )?;
+        }
+
+        if let Some(synthetic_root_package) = &self.synthetic_root_package {
+            writeln!(
+                f,
+                "Note: no package.json was found, so all files were attributed to the synthetic package {:?}. Only its configured entry files were used as entrypoints.",
+                synthetic_root_package,
+            )?;
+        }
+
         Ok(())
     }
 }
 
+fn ambiguous_reexport_report(ambiguous_reexport: &AmbiguousReexport) -> AmbiguousReexportReport {
+    AmbiguousReexportReport {
+        file_path: ambiguous_reexport.file_path.to_string_lossy().to_string(),
+        name: ambiguous_reexport.name.to_string(),
+        sources: ambiguous_reexport
+            .sources
+            .iter()
+            .map(|source| source.to_string_lossy().to_string())
+            .collect(),
+    }
+}
+
+fn barrel_bypass_import_report(bypass_import: &BarrelBypassImport) -> BarrelBypassImportReport {
+    BarrelBypassImportReport {
+        importer: bypass_import.importer.to_string_lossy().to_string(),
+        imported_file: bypass_import.imported_file.to_string_lossy().to_string(),
+        symbol: bypass_import.symbol.to_string(),
+        package_name: bypass_import.package_name.clone(),
+        barrel_file: bypass_import.barrel_file.to_string_lossy().to_string(),
+        barrel_name: bypass_import.barrel_name.to_string(),
+    }
+}
+
+fn symbol_collision_report(graph: &Graph, collision: &SymbolCollision) -> SymbolCollisionReport {
+    let winner = collision.winner();
+    SymbolCollisionReport {
+        entry_file: collision.entry_file.to_string_lossy().to_string(),
+        name: collision.name.to_string(),
+        definitions: collision
+            .definitions
+            .iter()
+            .map(|definition| {
+                // namespaces and files outside of the graph have no location
+                let span = graph
+                    .get_file_by_path(&definition.file_path)
+                    .and_then(|file| file.import_export_info.exported_ids.get(&definition.symbol))
+                    .map(|meta| meta.span)
+                    .unwrap_or_default();
+                SymbolDefinitionReport {
+                    file_path: definition.file_path.to_string_lossy().to_string(),
+                    symbol: definition.symbol.to_string(),
+                    start: span.lo().to_u32(),
+                    end: span.hi().to_u32(),
+                    wins: definition == winner,
+                }
+            })
+            .collect(),
+    }
+}
+
+// Indexes the files of the graph by the names they export. Only named exports
+// are indexed, since default exports are not imported by name.
+fn files_by_export_name(graph: &Graph) -> AHashMap<&str, Vec<&Path>> {
+    let mut files_by_name: AHashMap<&str, Vec<&Path>> = AHashMap::default();
+    for file in graph.files.iter() {
+        for symbol in file.import_export_info.exported_ids.keys() {
+            if let ExportedSymbol::Named(name) = symbol {
+                files_by_name
+                    .entry(name.as_str())
+                    .or_default()
+                    .push(&file.file_path);
+            }
+        }
+    }
+    files_by_name
+}
+
+// Finds the other files that export a symbol with the name of each unused symbol
+fn same_name_exports(
+    graph: &Graph,
+    unused_symbols: &AHashMap<String, Vec<SymbolReport>>,
+) -> AHashMap<String, Vec<SameNameExportReport>> {
+    let files_by_name = files_by_export_name(graph);
+
+    unused_symbols
+        .iter()
+        .filter_map(|(file_path, symbols)| {
+            // the other declarations of a name declared more than once share its files
+            let exports = symbols
+                .iter()
+                .filter(|symbol| split_declaration_suffix(&symbol.id).1.is_none())
+                .filter_map(|symbol| {
+                    // ids are the canonical string form of symbols, which escapes some names
+                    let name = match ExportedSymbol::from(symbol.id.as_str()) {
+                        ExportedSymbol::Named(name) => name,
+                        _ => return None,
+                    };
+                    let mut file_paths = files_by_name
+                        .get(name.as_str())?
+                        .iter()
+                        .map(|other_path| other_path.to_string_lossy().to_string())
+                        .filter(|other_path| other_path != file_path)
+                        .collect::<Vec<_>>();
+                    file_paths.sort();
+                    if file_paths.is_empty() {
+                        return None;
+                    }
+                    Some(SameNameExportReport {
+                        id: symbol.id.clone(),
+                        file_paths,
+                    })
+                })
+                .collect::<Vec<_>>();
+            if exports.is_empty() {
+                None
+            } else {
+                Some((file_path.clone(), exports))
+            }
+        })
+        .collect()
+}
+
+// Finds the names exported by more than one file of the graph, with the files
+// each name is imported from
+fn ambiguous_symbol_names(
+    graph: &Graph,
+    mode: AmbiguousSymbolNames,
+) -> Vec<AmbiguousSymbolNameReport> {
+    if mode == AmbiguousSymbolNames::Off {
+        return Vec::new();
+    }
+
+    let mut files_by_name = files_by_export_name(graph);
+    files_by_name.retain(|_, file_paths| file_paths.len() > 1);
+
+    // the files each ambiguous name is imported from, by name or through a re-export
+    let mut imported_from: AHashMap<&str, Vec<&Path>> = AHashMap::default();
+    for file in graph.files.iter() {
+        let info = &file.import_export_info;
+        let imported_symbols = info
+            .imported_symbols
+            .iter()
+            .flat_map(|(path, symbols)| symbols.iter().map(move |symbol| (path, symbol)));
+        let reexported_symbols = info
+            .export_from_symbols
+            .iter()
+            .flat_map(|(path, symbols)| symbols.keys().map(move |symbol| (path, &symbol.imported)));
+        for (path, symbol) in imported_symbols.chain(reexported_symbols) {
+            if let ExportedSymbol::Named(name) = symbol {
+                if files_by_name
+                    .get(name.as_str())
+                    .is_some_and(|file_paths| file_paths.contains(&path.as_path()))
+                {
+                    imported_from
+                        .entry(name.as_str())
+                        .or_default()
+                        .push(path.as_path());
+                }
+            }
+        }
+    }
+
+    let to_strings = |file_paths: &[&Path]| {
+        let mut file_paths: Vec<String> = file_paths
+            .iter()
+            .map(|file_path| file_path.to_string_lossy().to_string())
+            .collect();
+        file_paths.sort();
+        file_paths.dedup();
+        file_paths
+    };
+    let mut ambiguous_names: Vec<AmbiguousSymbolNameReport> = files_by_name
+        .into_iter()
+        .map(|(name, file_paths)| AmbiguousSymbolNameReport {
+            name: name.to_string(),
+            file_paths: to_strings(&file_paths),
+            imported_from: imported_from
+                .get(name)
+                .map(|file_paths| to_strings(file_paths))
+                .unwrap_or_default(),
+        })
+        .filter(|ambiguous_name| match mode {
+            AmbiguousSymbolNames::ConflictingImports => ambiguous_name.imported_from.len() > 1,
+            _ => true,
+        })
+        .collect();
+    ambiguous_names.sort_by(|a, b| a.name.cmp(&b.name));
+    ambiguous_names
+}
+
+// Lists the unused imports recorded for each file of the graph
+fn unused_imports(graph: &Graph) -> AHashMap<String, Vec<UnusedImportReport>> {
+    graph
+        .files
+        .iter()
+        .filter(|file| !file.import_export_info.unused_imports.is_empty())
+        .map(|file| {
+            let imports = file
+                .import_export_info
+                .unused_imports
+                .iter()
+                .map(|unused_import| UnusedImportReport {
+                    specifier: unused_import.specifier.clone(),
+                    id: unused_import.imported.to_string(),
+                    start: unused_import.span.lo().to_u32(),
+                    end: unused_import.span.hi().to_u32(),
+                })
+                .collect();
+            (file.file_path.to_string_lossy().to_string(), imports)
+        })
+        .collect()
+}
+
 fn extract_symbols<T: Send + Sync>(
     graph: &Graph,
-    include_symbol: impl Fn(&GraphFile, &ExportedSymbol) -> Option<T> + Sync,
+    include_symbol: impl Fn(&GraphFile, &ExportedSymbol) -> Vec<T> + Sync,
 ) -> AHashMap<String, Vec<T>> {
     graph
         .files
@@ -110,7 +985,7 @@ fn extract_symbols<T: Send + Sync>(
             let unused_symbols = graph_file
                 .import_export_info
                 .iter_exported_symbols()
-                .filter_map(|(_, symbol): (_, &ExportedSymbol)| -> Option<T> {
+                .flat_map(|(_, symbol): (_, &ExportedSymbol)| -> Vec<T> {
                     include_symbol(graph_file, symbol)
                 })
                 .collect::<Vec<_>>();
@@ -127,7 +1002,7 @@ fn extract_symbols<T: Send + Sync>(
         .collect::<AHashMap<String, Vec<T>>>()
 }
 
-fn is_used(tags: &UsedTag) -> bool {
+pub(crate) fn is_used(tags: &UsedTag) -> bool {
     tags.contains(UsedTag::FROM_ENTRY)
         || tags.contains(UsedTag::FROM_IGNORED)
         || tags.contains(UsedTag::FROM_TEST)
@@ -140,15 +1015,8 @@ fn include_extra(tags: &UsedTag) -> bool {
 impl From<&UnusedFinderResult> for UnusedFinderReport {
     fn from(value: &UnusedFinderResult) -> Self {
         let mut unused_files: Vec<String> = value
-            .graph
-            .files
-            .par_iter()
-            .filter_map(|file| {
-                if is_used(&file.file_tags) {
-                    return None;
-                }
-                Some(file.file_path.to_string_lossy().to_string())
-            })
+            .par_iter_unused_files()
+            .map(|file_path| file_path.to_string_lossy().to_string())
             .collect();
         unused_files.sort();
         let extra_file_tags = value
@@ -167,50 +1035,118 @@ impl From<&UnusedFinderResult> for UnusedFinderReport {
             })
             .collect();
 
-        let unused_symbols =
-            extract_symbols(&value.graph, |file, symbol_name| -> Option<SymbolReport> {
-                let default: UsedTag = Default::default();
-                let symbol_bitflags: &UsedTag =
-                    file.symbol_tags.get(symbol_name).unwrap_or(&default);
-
-                if is_used(symbol_bitflags) {
-                    // don't return used symbols
+        let unused_symbols = value
+            .graph
+            .files
+            .par_iter()
+            .filter_map(|file| -> Option<(String, Vec<SymbolReport>)> {
+                let unused_symbols: Vec<SymbolReport> = value
+                    .unused_symbols_of_file(file)
+                    .flat_map(|symbol| symbol.to_reports())
+                    .collect();
+                if unused_symbols.is_empty() {
                     return None;
                 }
-
-                let ast_symbol = file.import_export_info.exported_ids.get(symbol_name)?;
-
-                Some(SymbolReport {
-                    id: symbol_name.to_string(),
-                    start: ast_symbol.span.lo().to_u32(),
-                    end: ast_symbol.span.hi().to_u32(),
-                })
-            });
+                Some((file.file_path.to_string_lossy().to_string(), unused_symbols))
+            })
+            .collect();
 
         let extra_symbol_tags = extract_symbols(
             &value.graph,
-            |file, symbol_name| -> Option<SymbolReportWithTags> {
+            |file, symbol_name| -> Vec<SymbolReportWithTags> {
                 let default: UsedTag = Default::default();
                 let symbol_bitflags: &UsedTag =
                     file.symbol_tags.get(symbol_name).unwrap_or(&default);
-                if !include_extra(symbol_bitflags) {
-                    // don't return symbols that are used or symbols that are truly unused
-                    return None;
+                if !include_extra(symbol_bitflags)
+                    || is_opaque_module(&file.file_path, &value.opaque_module_extensions)
+                {
+                    // don't return symbols that are used or symbols that are truly unused,
+                    // or the synthetic exports of opaque modules
+                    return Vec::new();
                 }
 
-                let ast_symbol = file.import_export_info.exported_ids.get(symbol_name)?;
+                let (symbol, metadata) = match file
+                    .import_export_info
+                    .exported_ids
+                    .get_key_value(symbol_name)
+                {
+                    Some(exported) => exported,
+                    None => return Vec::new(),
+                };
 
-                Some(SymbolReportWithTags {
-                    symbol: SymbolReport {
-                        id: symbol_name.to_string(),
-                        start: ast_symbol.span.lo().to_u32(),
-                        end: ast_symbol.span.hi().to_u32(),
-                    },
-                    tags: (*symbol_bitflags).into(),
-                })
+                SymbolRef { symbol, metadata }
+                    .to_reports()
+                    .into_iter()
+                    .map(|report| SymbolReportWithTags {
+                        symbol: SymbolReport {
+                            locally_used: false,
+                            ..report
+                        },
+                        tags: (*symbol_bitflags).into(),
+                    })
+                    .collect()
             },
         );
 
+        let mut large_modules: Vec<LargeModuleReport> = match value.god_module_threshold {
+            Some(threshold) => value
+                .graph
+                .files
+                .iter()
+                .map(|file| LargeModuleReport {
+                    file_path: file.file_path.to_string_lossy().to_string(),
+                    num_exported_symbols: file.import_export_info.num_exported_symbols(),
+                })
+                .filter(|large_module| large_module.num_exported_symbols > threshold)
+                .collect(),
+            None => Vec::new(),
+        };
+        large_modules.sort_by(|a, b| {
+            b.num_exported_symbols
+                .cmp(&a.num_exported_symbols)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+
+        let mut opaque_modules: Vec<String> = value
+            .graph
+            .files
+            .iter()
+            .filter(|file| is_opaque_module(&file.file_path, &value.opaque_module_extensions))
+            .map(|file| file.file_path.to_string_lossy().to_string())
+            .collect();
+        opaque_modules.sort();
+
+        let mut degraded_files: Vec<DegradedFileReport> = value
+            .graph
+            .files
+            .iter()
+            .filter(|file| file.import_export_info.degraded)
+            .map(|file| DegradedFileReport {
+                file_path: file.file_path.to_string_lossy().to_string(),
+                parse_error: file
+                    .import_export_info
+                    .parse_error
+                    .clone()
+                    .unwrap_or_default(),
+            })
+            .collect();
+        degraded_files.sort();
+
+        let same_name_exports = if value.suggest_same_name_exports {
+            same_name_exports(&value.graph, &unused_symbols)
+        } else {
+            AHashMap::default()
+        };
+
+        let ambiguous_symbol_names =
+            ambiguous_symbol_names(&value.graph, value.report_ambiguous_symbol_names);
+
+        let unused_imports = if value.report_unused_imports {
+            unused_imports(&value.graph)
+        } else {
+            AHashMap::default()
+        };
+
         UnusedFinderReport {
             unused_files,
             unused_symbols,
@@ -218,6 +1154,31 @@ impl From<&UnusedFinderResult> for UnusedFinderReport {
             // entrypoints into the project
             extra_file_tags,
             extra_symbol_tags,
+            large_modules,
+            opaque_modules,
+            degraded_files,
+            synthetic_root_package: value.synthetic_root_package.clone(),
+            symbol_collisions: value
+                .symbol_collisions
+                .iter()
+                .map(|collision| symbol_collision_report(&value.graph, collision))
+                .collect(),
+            ambiguous_reexports: value
+                .ambiguous_reexports
+                .iter()
+                .map(ambiguous_reexport_report)
+                .collect(),
+            barrel_bypass_imports: value
+                .barrel_bypass_imports
+                .iter()
+                .map(barrel_bypass_import_report)
+                .collect(),
+            same_name_exports,
+            ambiguous_symbol_names,
+            unused_imports,
+            import_kind_counts: value
+                .count_import_kinds
+                .then(|| import_kind_counts(value.graph.files.iter())),
         }
     }
 }
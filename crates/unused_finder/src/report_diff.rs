@@ -0,0 +1,322 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use ahashmap::AHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::report::{SymbolReport, UnusedFinderReport};
+
+/// Options for comparing an UnusedFinderReport against a baseline
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReportDiffOptions {
+    /// If true, an unused symbol whose span changed counts as a difference.
+    /// By default symbols are matched by file and name only, so unrelated
+    /// edits that move a symbol within its file do not change the diff.
+    pub strict_spans: bool,
+}
+
+/// An unused symbol of a file, as listed in a report diff
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedSymbolDiff {
+    pub file_path: String,
+    pub id: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// The unused files and symbols of a report that differ from a baseline
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedFinderReportDiff {
+    /// Unused files that are not in the baseline, sorted
+    pub new_unused_files: Vec<String>,
+    /// Unused files of the baseline that are no longer unused, sorted
+    pub resolved_unused_files: Vec<String>,
+    /// Unused symbols that are not in the baseline, sorted
+    pub new_unused_symbols: Vec<UnusedSymbolDiff>,
+    /// Unused symbols of the baseline that are no longer unused, sorted
+    pub resolved_unused_symbols: Vec<UnusedSymbolDiff>,
+}
+
+impl UnusedFinderReportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.new_unused_files.is_empty()
+            && self.resolved_unused_files.is_empty()
+            && self.new_unused_symbols.is_empty()
+            && self.resolved_unused_symbols.is_empty()
+    }
+}
+
+impl Display for UnusedFinderReportDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for file_path in self.new_unused_files.iter() {
+            writeln!(f, "+ {} is completely unused", file_path)?;
+        }
+        for file_path in self.resolved_unused_files.iter() {
+            writeln!(f, "- {} is completely unused", file_path)?;
+        }
+        for symbol in self.new_unused_symbols.iter() {
+            writeln!(
+                f,
+                "+ {} is unused in {} ({}-{})",
+                symbol.id, symbol.file_path, symbol.start, symbol.end
+            )?;
+        }
+        for symbol in self.resolved_unused_symbols.iter() {
+            writeln!(
+                f,
+                "- {} is unused in {} ({}-{})",
+                symbol.id, symbol.file_path, symbol.start, symbol.end
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl UnusedFinderReport {
+    /// Compares the unused files and symbols of this report against a
+    /// baseline, e.g. a snapshot committed to the repo.
+    ///
+    /// Both reports are expected to render paths the same way. Other parts of
+    /// the reports, like tags, are not compared.
+    pub fn diff(
+        &self,
+        baseline: &UnusedFinderReport,
+        options: ReportDiffOptions,
+    ) -> UnusedFinderReportDiff {
+        let unused_files: BTreeSet<&String> = self.unused_files.iter().collect();
+        let baseline_unused_files: BTreeSet<&String> = baseline.unused_files.iter().collect();
+
+        let unused_symbols = symbol_diffs(&self.unused_symbols);
+        let baseline_unused_symbols = symbol_diffs(&baseline.unused_symbols);
+        let symbol_key = |symbol: &UnusedSymbolDiff| {
+            if options.strict_spans {
+                (
+                    symbol.file_path.clone(),
+                    symbol.id.clone(),
+                    symbol.start,
+                    symbol.end,
+                )
+            } else {
+                (symbol.file_path.clone(), symbol.id.clone(), 0, 0)
+            }
+        };
+        let symbol_keys: BTreeSet<_> = unused_symbols.iter().map(symbol_key).collect();
+        let baseline_symbol_keys: BTreeSet<_> =
+            baseline_unused_symbols.iter().map(symbol_key).collect();
+
+        UnusedFinderReportDiff {
+            new_unused_files: unused_files
+                .difference(&baseline_unused_files)
+                .map(|file_path| file_path.to_string())
+                .collect(),
+            resolved_unused_files: baseline_unused_files
+                .difference(&unused_files)
+                .map(|file_path| file_path.to_string())
+                .collect(),
+            new_unused_symbols: unused_symbols
+                .iter()
+                .filter(|symbol| !baseline_symbol_keys.contains(&symbol_key(*symbol)))
+                .cloned()
+                .collect(),
+            resolved_unused_symbols: baseline_unused_symbols
+                .iter()
+                .filter(|symbol| !symbol_keys.contains(&symbol_key(*symbol)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Gets the unused files and symbols of this report in a stable form for
+    /// committing as a snapshot: sorted, with forward slashes in paths.
+    ///
+    /// Other parts of the report are dropped, since they change with edits
+    /// to files that are in use.
+    pub fn to_snapshot(&self) -> UnusedFinderReport {
+        let normalize_path = |file_path: &str| file_path.replace('\\', "/");
+
+        let mut unused_files: Vec<String> = self
+            .unused_files
+            .iter()
+            .map(|file_path| normalize_path(file_path))
+            .collect();
+        unused_files.sort();
+        unused_files.dedup();
+
+        let unused_symbols: AHashMap<String, Vec<SymbolReport>> = self
+            .unused_symbols
+            .iter()
+            .map(|(file_path, symbols)| {
+                let mut symbols = symbols.clone();
+                symbols.sort_by(|a, b| (a.start, a.end, &a.id).cmp(&(b.start, b.end, &b.id)));
+                (normalize_path(file_path), symbols)
+            })
+            .collect();
+
+        UnusedFinderReport {
+            unused_files,
+            unused_symbols,
+            ..Default::default()
+        }
+    }
+
+    /// Serializes the snapshot of this report as pretty JSON, with the keys
+    /// of maps sorted so the output is stable.
+    pub fn to_snapshot_json(&self) -> String {
+        let value = serde_json::to_value(self.to_snapshot())
+            .expect("UnusedFinderReport should always be serializable");
+        serde_json::to_string_pretty(&value).expect("JSON values should always be serializable")
+    }
+
+    /// Reads a snapshot written by `to_snapshot_json`, or any other JSON
+    /// report, normalized the same way.
+    pub fn from_snapshot_json(snapshot: &str) -> Result<UnusedFinderReport, serde_json::Error> {
+        let report: UnusedFinderReport = serde_json::from_str(snapshot)?;
+        Ok(report.to_snapshot())
+    }
+}
+
+// flattens the unused symbols of a report, sorted by file, name and span
+fn symbol_diffs(unused_symbols: &AHashMap<String, Vec<SymbolReport>>) -> Vec<UnusedSymbolDiff> {
+    let mut symbols: Vec<UnusedSymbolDiff> = unused_symbols
+        .iter()
+        .flat_map(|(file_path, symbols)| {
+            symbols.iter().map(move |symbol| UnusedSymbolDiff {
+                file_path: file_path.clone(),
+                id: symbol.id.clone(),
+                start: symbol.start,
+                end: symbol.end,
+            })
+        })
+        .collect();
+    symbols.sort();
+    symbols
+}
+
+#[cfg(test)]
+mod test {
+    use ahashmap::AHashMap;
+
+    use super::{ReportDiffOptions, UnusedFinderReportDiff, UnusedSymbolDiff};
+    use crate::report::{SymbolReport, UnusedFinderReport};
+
+    const STRICT_SPANS: ReportDiffOptions = ReportDiffOptions { strict_spans: true };
+
+    fn report(unused_files: &[&str], unused_symbols: &[(&str, &str, u32)]) -> UnusedFinderReport {
+        let mut symbols: AHashMap<String, Vec<SymbolReport>> = AHashMap::default();
+        for (file_path, id, start) in unused_symbols {
+            symbols
+                .entry(file_path.to_string())
+                .or_default()
+                .push(SymbolReport {
+                    id: id.to_string(),
+                    start: *start,
+                    end: start + 10,
+                    locally_used: false,
+                    local_name: None,
+                });
+        }
+        UnusedFinderReport {
+            unused_files: unused_files.iter().map(|x| x.to_string()).collect(),
+            unused_symbols: symbols,
+            ..Default::default()
+        }
+    }
+
+    fn symbol(file_path: &str, id: &str, start: u32) -> UnusedSymbolDiff {
+        UnusedSymbolDiff {
+            file_path: file_path.to_string(),
+            id: id.to_string(),
+            start,
+            end: start + 10,
+        }
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let baseline = report(
+            &["a/unused.ts", "a/other.ts"],
+            &[("a/index.ts", "foo", 0), ("a/index.ts", "bar", 20)],
+        );
+        // the order of files and symbols does not matter
+        let current = report(
+            &["a/other.ts", "a/unused.ts"],
+            &[("a/index.ts", "bar", 20), ("a/index.ts", "foo", 0)],
+        );
+
+        let diff = current.diff(&baseline, ReportDiffOptions::default());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+        assert!(current.diff(&baseline, STRICT_SPANS).is_empty());
+    }
+
+    #[test]
+    fn test_diff_symbol_moved() {
+        let baseline = report(&[], &[("a/index.ts", "foo", 0)]);
+        let current = report(&[], &[("a/index.ts", "foo", 40)]);
+
+        assert!(current
+            .diff(&baseline, ReportDiffOptions::default())
+            .is_empty());
+        assert_eq!(
+            current.diff(&baseline, STRICT_SPANS),
+            UnusedFinderReportDiff {
+                new_unused_symbols: vec![symbol("a/index.ts", "foo", 40)],
+                resolved_unused_symbols: vec![symbol("a/index.ts", "foo", 0)],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_new_unused_symbol() {
+        let baseline = report(&[], &[("a/index.ts", "foo", 0)]);
+        let current = report(&[], &[("a/index.ts", "foo", 0), ("b/index.ts", "bar", 5)]);
+
+        let diff = current.diff(&baseline, ReportDiffOptions::default());
+        assert_eq!(
+            diff,
+            UnusedFinderReportDiff {
+                new_unused_symbols: vec![symbol("b/index.ts", "bar", 5)],
+                ..Default::default()
+            }
+        );
+        assert_eq!(diff.to_string(), "+ bar is unused in b/index.ts (5-15)\n");
+    }
+
+    #[test]
+    fn test_diff_resolved_unused_file() {
+        let baseline = report(&["a/unused.ts", "b/unused.ts"], &[]);
+        let current = report(&["b/unused.ts"], &[]);
+
+        let diff = current.diff(&baseline, ReportDiffOptions::default());
+        assert_eq!(
+            diff,
+            UnusedFinderReportDiff {
+                resolved_unused_files: vec!["a/unused.ts".to_string()],
+                ..Default::default()
+            }
+        );
+        assert_eq!(diff.to_string(), "- a/unused.ts is completely unused\n");
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let current = report(
+            &["b\\unused.ts", "a/unused.ts"],
+            &[("a\\index.ts", "foo", 20), ("a\\index.ts", "bar", 0)],
+        );
+
+        let snapshot = UnusedFinderReport::from_snapshot_json(&current.to_snapshot_json()).unwrap();
+        assert_eq!(snapshot.unused_files, ["a/unused.ts", "b/unused.ts"]);
+        let ids: Vec<&str> = snapshot.unused_symbols["a/index.ts"]
+            .iter()
+            .map(|symbol| symbol.id.as_str())
+            .collect();
+        assert_eq!(ids, ["bar", "foo"]);
+        assert!(current
+            .to_snapshot()
+            .diff(&snapshot, STRICT_SPANS)
+            .is_empty());
+    }
+}
@@ -56,6 +56,17 @@ impl Display for UsedTagEnum {
     }
 }
 
+impl From<UsedTagEnum> for UsedTag {
+    fn from(tag: UsedTagEnum) -> Self {
+        match tag {
+            UsedTagEnum::Entry => UsedTag::FROM_ENTRY,
+            UsedTagEnum::Ignored => UsedTag::FROM_IGNORED,
+            UsedTagEnum::Test => UsedTag::FROM_TEST,
+            UsedTagEnum::TypeOnly => UsedTag::TYPE_ONLY,
+        }
+    }
+}
+
 impl From<UsedTag> for Vec<UsedTagEnum> {
     fn from(flags: UsedTag) -> Self {
         let mut result = Vec::new();
@@ -1,19 +1,81 @@
 use core::result::Result;
-use std::{collections::HashMap, path::PathBuf, str::FromStr};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use path_slash::PathBufExt;
+use path_utils::{Glob, GlobError};
+use serde_json::json;
+use test_fixtures::FixtureRepo;
 use test_tmpdir::{amap, test_tmpdir};
 
 use crate::{
     cfg::package_match_rules::PackageMatchRules, report::SymbolReport, tag::UsedTag,
-    SymbolReportWithTags, UnusedFinder, UnusedFinderConfig, UnusedFinderReport,
+    AmbiguousReexportReport, AmbiguousSymbolNameReport, AmbiguousSymbolNames,
+    BarrelBypassImportReport, DegradedFileReport, DepcruiseDependency, DepcruiseDependencyType,
+    DepcruiseModule, DepcruiseOutput, ExportedSymbol, ImportKindCounts, LargeModuleReport,
+    PathDisplay, PathMirror, ResolvedImport, ResolverTargetEnv, SameNameExportReport,
+    SymbolCollisionReport, SymbolDefinitionReport, SymbolOrigin, SymbolReportWithTags,
+    SymbolStatus, UnusedFinder, UnusedFinderConfig, UnusedFinderReport, UnusedFinderResult,
+    UsedTagEnum,
 };
 
+// Counts the allocations made by each thread, so tests can guard against
+// allocation regressions without interference from tests on other threads
+struct CountingAllocator;
+
+thread_local! {
+    static THREAD_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = THREAD_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// runs f, returning its result and the number of allocations it made on this thread
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = THREAD_ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, THREAD_ALLOCATIONS.with(Cell::get) - before)
+}
+
 fn symbol(id: &str) -> SymbolReport {
     SymbolReport {
         id: id.to_string(),
         start: 0,
         end: 0,
+        locally_used: false,
+        local_name: None,
+    }
+}
+
+fn locally_used_symbol(id: &str) -> SymbolReport {
+    SymbolReport {
+        locally_used: true,
+        ..symbol(id)
+    }
+}
+
+fn named_default_symbol(local_name: &str) -> SymbolReport {
+    SymbolReport {
+        local_name: Some(local_name.to_string()),
+        ..symbol("default")
     }
 }
 
@@ -63,6 +125,108 @@ fn normalize_test_report(
                 (normalize_path(tmpdir, &k), s_v)
             })
             .collect(),
+        large_modules: result
+            .large_modules
+            .into_iter()
+            .map(|large_module| LargeModuleReport {
+                file_path: normalize_path(tmpdir, &large_module.file_path),
+                ..large_module
+            })
+            .collect(),
+        opaque_modules: result
+            .opaque_modules
+            .iter()
+            .map(|x| normalize_path(tmpdir, x))
+            .collect(),
+        degraded_files: result
+            .degraded_files
+            .into_iter()
+            .map(|degraded_file| DegradedFileReport {
+                file_path: normalize_path(tmpdir, &degraded_file.file_path),
+                ..degraded_file
+            })
+            .collect(),
+        synthetic_root_package: result.synthetic_root_package,
+        symbol_collisions: result
+            .symbol_collisions
+            .into_iter()
+            .map(|collision| SymbolCollisionReport {
+                entry_file: normalize_path(tmpdir, &collision.entry_file),
+                definitions: collision
+                    .definitions
+                    .into_iter()
+                    .map(|definition| SymbolDefinitionReport {
+                        file_path: normalize_path(tmpdir, &definition.file_path),
+                        ..definition
+                    })
+                    .collect(),
+                ..collision
+            })
+            .collect(),
+        ambiguous_reexports: result
+            .ambiguous_reexports
+            .into_iter()
+            .map(|ambiguous_reexport| AmbiguousReexportReport {
+                file_path: normalize_path(tmpdir, &ambiguous_reexport.file_path),
+                sources: ambiguous_reexport
+                    .sources
+                    .iter()
+                    .map(|x| normalize_path(tmpdir, x))
+                    .collect(),
+                ..ambiguous_reexport
+            })
+            .collect(),
+        barrel_bypass_imports: result
+            .barrel_bypass_imports
+            .into_iter()
+            .map(|bypass_import| BarrelBypassImportReport {
+                importer: normalize_path(tmpdir, &bypass_import.importer),
+                imported_file: normalize_path(tmpdir, &bypass_import.imported_file),
+                barrel_file: normalize_path(tmpdir, &bypass_import.barrel_file),
+                ..bypass_import
+            })
+            .collect(),
+        same_name_exports: result
+            .same_name_exports
+            .into_iter()
+            .map(|(k, v)| {
+                let v = v
+                    .into_iter()
+                    .map(|export| SameNameExportReport {
+                        file_paths: export
+                            .file_paths
+                            .iter()
+                            .map(|x| normalize_path(tmpdir, x))
+                            .collect(),
+                        ..export
+                    })
+                    .collect();
+                (normalize_path(tmpdir, &k), v)
+            })
+            .collect(),
+        ambiguous_symbol_names: result
+            .ambiguous_symbol_names
+            .into_iter()
+            .map(|ambiguous_name| AmbiguousSymbolNameReport {
+                file_paths: ambiguous_name
+                    .file_paths
+                    .iter()
+                    .map(|x| normalize_path(tmpdir, x))
+                    .collect(),
+                imported_from: ambiguous_name
+                    .imported_from
+                    .iter()
+                    .map(|x| normalize_path(tmpdir, x))
+                    .collect(),
+                ..ambiguous_name
+            })
+            .collect(),
+        unused_imports: result
+            .unused_imports
+            .into_iter()
+            .map(|(k, v)| (normalize_path(tmpdir, &k), v))
+            .collect(),
+        import_kind_counts: result.import_kind_counts,
     }
 }
 
@@ -212,21 +376,22 @@ fn test_package_exports_walk_roots() {
 
 #[test]
 fn test_root_export_symbols_used() {
-    let tmpdir = test_tmpdir!(
-        "packages/root/package.json" => r#"{
-            "name": "entrypoint",
-            "main": "./main.js",
-            "exports": {}
-        }"#,
-        "packages/root/main.js" => r#"
+    let repo = FixtureRepo::new().package("entrypoint", |p| {
+        p.dir("packages/root")
+            .main("./main.js")
+            .exports_map(json!({}))
+            .file(
+                "main.js",
+                r#"
             export const root_symbol = "root_symbol";
-        "#
-    );
+        "#,
+            )
+    });
 
     run_unused_test(
-        &tmpdir,
+        &repo,
         UnusedFinderConfig {
-            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            root_paths: vec![repo.root().to_string_lossy().to_string()],
             entry_packages: vec!["entrypoint"].try_into().unwrap(),
             ..Default::default()
         },
@@ -238,27 +403,34 @@ fn test_root_export_symbols_used() {
 
 #[test]
 fn test_transitive_re_export() {
-    let tmpdir = test_tmpdir!(
-        "packages/root/package.json" => r#"{
-            "name": "entrypoint",
-            "main": "./main.js",
-            "exports": {}
-        }"#,
-        "packages/root/main.js" => r#"
+    let repo = FixtureRepo::new().package("entrypoint", |p| {
+        p.dir("packages/root")
+            .main("./main.js")
+            .exports_map(json!({}))
+            .file(
+                "main.js",
+                r#"
             export { transitiveReExport } from "./transitive-1.js"
         "#,
-        "packages/root/transitive-1.js" => r#"
+            )
+            .file(
+                "transitive-1.js",
+                r#"
             export { transitive as transitiveReExport } from "./transitive-2.js"
         "#,
-        "packages/root/transitive-2.js" => r#"
+            )
+            .file(
+                "transitive-2.js",
+                r#"
             export function transitive() {}
-        "#
-    );
+        "#,
+            )
+    });
 
     run_unused_test(
-        &tmpdir,
+        &repo,
         UnusedFinderConfig {
-            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            root_paths: vec![repo.root().to_string_lossy().to_string()],
             entry_packages: vec!["entrypoint"].try_into().unwrap(),
             ..Default::default()
         },
@@ -270,25 +442,29 @@ fn test_transitive_re_export() {
 
 #[test]
 fn test_partially_unused_file() {
-    let tmpdir = test_tmpdir!(
-        "packages/root/package.json" => r#"{
-            "name": "entrypoint",
-            "main": "./main.js",
-            "exports": {}
-        }"#,
-        "packages/root/main.js" => r#"
+    let repo = FixtureRepo::new().package("entrypoint", |p| {
+        p.dir("packages/root")
+            .main("./main.js")
+            .exports_map(json!({}))
+            .file(
+                "main.js",
+                r#"
             import { a } from "./imported-1.js";
         "#,
-        "packages/root/imported-1.js" => r#"
+            )
+            .file(
+                "imported-1.js",
+                r#"
             export const a = 1;
             export const b = 2;
-        "#
-    );
+        "#,
+            )
+    });
 
     run_unused_test(
-        &tmpdir,
+        &repo,
         UnusedFinderConfig {
-            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            root_paths: vec![repo.root().to_string_lossy().to_string()],
             entry_packages: vec!["entrypoint"].try_into().unwrap(),
             ..Default::default()
         },
@@ -363,6 +539,7 @@ ignored-*.js
                     tagged_symbol("b", UsedTag::FROM_IGNORED),
                 ]
             ),
+            ..Default::default()
         },
     );
 }
@@ -415,8 +592,8 @@ fn test_test_pattern() {
             root_paths: vec!["search_root".to_string()],
             test_files: vec!["**/__tests__/*Test.js"]
                 .into_iter()
-                .map(glob::Pattern::new)
-                .collect::<Result<Vec<glob::Pattern>, glob::PatternError>>()
+                .map(Glob::new)
+                .collect::<Result<Vec<Glob>, GlobError>>()
                 .unwrap(),
             ..Default::default()
         },
@@ -435,6 +612,76 @@ fn test_test_pattern() {
     );
 }
 
+#[test]
+fn test_test_dirs_from_package_json() {
+    // Tests tagging "test" files from the test directories packages declare
+    let tmpdir = test_tmpdir!(
+        "search_root/packages/custom/package.json" => r#"{
+            "name": "custom",
+            "directories": { "test": "spec" }
+        }"#,
+        "search_root/packages/custom/src/util.js" => r#"
+            export const customUtil = 1;
+        "#,
+        "search_root/packages/custom/spec/util.js" => r#"
+            import { customUtil } from "../src/util";
+        "#,
+        "search_root/packages/defaulted/package.json" => r#"{
+            "name": "defaulted"
+        }"#,
+        "search_root/packages/defaulted/src/util.js" => r#"
+            export const defaultedUtil = 1;
+        "#,
+        "search_root/packages/defaulted/__tests__/util.js" => r#"
+            import { defaultedUtil } from "../src/util";
+        "#,
+        "search_root/packages/globbed/package.json" => r#"{
+            "name": "globbed",
+            "directories": { "test": "spec" }
+        }"#,
+        "search_root/packages/globbed/util.js" => r#"
+            export const globbedUtil = 1;
+        "#,
+        "search_root/packages/globbed/util.check.js" => r#"
+            import { globbedUtil } from "./util";
+        "#
+    );
+
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec!["search_root".to_string()],
+            test_files: vec![Glob::new("**/*.check.js").unwrap()],
+            infer_test_dirs_from_package_json: true,
+            use_default_test_dirs: true,
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            extra_file_tags: amap!(
+                "<root>/search_root/packages/custom/spec/util.js" => UsedTag::FROM_TEST.into(),
+                "<root>/search_root/packages/custom/src/util.js" => UsedTag::FROM_TEST.into(),
+                "<root>/search_root/packages/defaulted/__tests__/util.js" => UsedTag::FROM_TEST.into(),
+                "<root>/search_root/packages/defaulted/src/util.js" => UsedTag::FROM_TEST.into(),
+                "<root>/search_root/packages/globbed/util.check.js" => UsedTag::FROM_TEST.into(),
+                "<root>/search_root/packages/globbed/util.js" => UsedTag::FROM_TEST.into()
+            ),
+            extra_symbol_tags: amap!(
+                "<root>/search_root/packages/custom/src/util.js" => vec![
+                    tagged_symbol("customUtil", UsedTag::FROM_TEST),
+                ],
+                "<root>/search_root/packages/defaulted/src/util.js" => vec![
+                    tagged_symbol("defaultedUtil", UsedTag::FROM_TEST),
+                ],
+                "<root>/search_root/packages/globbed/util.js" => vec![
+                    tagged_symbol("globbedUtil", UsedTag::FROM_TEST),
+                ]
+            ),
+            ..Default::default()
+        },
+    );
+}
+
 #[test]
 fn test_relative_test_pattern() {
     // Tests tagging "test" files with relative patterns
@@ -454,8 +701,8 @@ fn test_relative_test_pattern() {
             root_paths: vec!["search_root".to_string()],
             test_files: vec!["search_root/tests/**"]
                 .into_iter()
-                .map(glob::Pattern::new)
-                .collect::<Result<Vec<glob::Pattern>, glob::PatternError>>()
+                .map(Glob::new)
+                .collect::<Result<Vec<Glob>, GlobError>>()
                 .unwrap(),
             ..Default::default()
         },
@@ -474,6 +721,73 @@ fn test_relative_test_pattern() {
     );
 }
 
+#[test]
+fn test_brace_and_recursive_globs() {
+    // brace alternation and `**` work the same in every field that takes globs
+    let tmpdir = test_tmpdir!(
+        "packages/app/package.json" => r#"{ "name": "@me/app", "main": "./index.js" }"#,
+        "packages/app/index.js" => r#"
+            export const app = 1;
+        "#,
+        "packages/web/package.json" => r#"{ "name": "@me/web", "main": "./index.js" }"#,
+        "packages/web/index.js" => r#"
+            export const web = 1;
+        "#,
+        "packages/lib/package.json" => r#"{ "name": "@me/lib", "main": "./index.js" }"#,
+        "packages/lib/index.js" => r#"
+            export const lib = 1;
+        "#,
+        "packages/lib/orphan.js" => r#"
+            export const orphan = 1;
+        "#,
+        "packages/lib/src/a.spec.js" => r#"
+            import { lib } from "../index";
+        "#,
+        "packages/lib/src/nested/b.test.js" => r#"
+            import "../../index";
+        "#,
+        "packages/lib/dist/out.js" => r#"
+            export const out = 1;
+        "#,
+        "packages/lib/build/out.js" => r#"
+            export const out = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec!["packages".to_string()],
+            entry_packages: vec!["@me/{app,web}"].try_into().unwrap(),
+            test_files: vec![Glob::new("**/*.{spec,test}.js").unwrap()],
+            skip: vec!["{dist,build}".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+    let report = normalize_test_report(&tmpdir, result.get_report());
+
+    // app and web are entry packages, lib is only used by the tests, and the
+    // skipped dist and build directories are never walked
+    assert_eq!(
+        report.unused_files,
+        vec!["<root>/packages/lib/orphan.js".to_string()]
+    );
+    for test_file in [
+        "<root>/packages/lib/src/a.spec.js",
+        "<root>/packages/lib/src/nested/b.test.js",
+    ] {
+        assert_eq!(
+            report.extra_file_tags.get(test_file),
+            Some(&Vec::<UsedTagEnum>::from(UsedTag::FROM_TEST)),
+            "{test_file} should be tagged as a test"
+        );
+    }
+}
+
 #[test]
 fn test_testfiles_ignored() {
     // Tests that test files are ignored
@@ -503,7 +817,7 @@ fn test_testfiles_ignored() {
             repo_root: tmpdir.root().to_string_lossy().to_string(),
             root_paths: vec!["search_root".to_string()],
             entry_packages: PackageMatchRules::empty(),
-            test_files: vec![glob::Pattern::from_str("**/__tests__/**").unwrap()],
+            test_files: vec![Glob::from_str("**/__tests__/**").unwrap()],
             ..Default::default()
         },
         UnusedFinderReport {
@@ -518,6 +832,7 @@ fn test_testfiles_ignored() {
             extra_symbol_tags: amap![
                 "<root>/search_root/packages/test-helpers/test-helpers.js" => vec![tagged_symbol("myFunction", UsedTag::FROM_TEST)]
             ],
+            ..Default::default()
         },
     );
 }
@@ -601,3 +916,3129 @@ fn test_typeonly_interface_allowed() {
         },
     );
 }
+
+#[test]
+fn test_locally_used_exports() {
+    let repo = FixtureRepo::new().package("entrypoint", |p| {
+        p.dir("packages/root")
+            .main("./main.js")
+            .exports_map(json!({}))
+            .file(
+                "main.js",
+                r#"
+            import { used } from "./other.js";
+        "#,
+            )
+            .file(
+                "other.js",
+                r#"
+            export function helper() {}
+            export function deadHelper() {}
+            export const used = helper();
+        "#,
+            )
+    });
+
+    run_unused_test(
+        &repo,
+        UnusedFinderConfig {
+            root_paths: vec![repo.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_files: vec![],
+            unused_symbols: amap!(
+                "<root>/packages/root/other.js" => vec![
+                    symbol("deadHelper"),
+                    locally_used_symbol("helper"),
+                ]
+            ),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_report_for_package() {
+    let repo = FixtureRepo::new()
+        .package("entrypoint", |p| {
+            p.dir("packages/root")
+                .main("./main.js")
+                .exports_map(json!({}))
+                .file(
+                    "main.js",
+                    r#"
+            import { usedA } from "../lib-a/a.js";
+        "#,
+                )
+        })
+        .package("lib-a", |p| {
+            p.file(
+                "a.js",
+                r#"
+            export const usedA = 1;
+            export const unusedA = 2;
+        "#,
+            )
+            .file(
+                "a-unused.js",
+                r#"
+            export const deadA = 1;
+        "#,
+            )
+        })
+        .package("lib-b", |p| {
+            p.file(
+                "b.js",
+                r#"
+            export const deadB = 1;
+        "#,
+            )
+        });
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: repo.root().to_string_lossy().to_string(),
+            root_paths: vec![repo.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+
+    // the global report includes other packages
+    let global_report = normalize_test_report(&repo, result.get_report());
+    assert!(global_report
+        .unused_files
+        .contains(&"<root>/packages/lib-b/b.js".to_string()));
+
+    // the scoped report only includes lib-a, and usedA is still used from the entrypoint
+    let report = normalize_test_report(&repo, result.report_for_package("lib-a"));
+    assert_eq!(
+        report.unused_files,
+        vec!["<root>/packages/lib-a/a-unused.js".to_string()]
+    );
+    let mut unused_symbols = report
+        .unused_symbols
+        .iter()
+        .map(|(file_path, symbols)| {
+            (
+                file_path.clone(),
+                symbols.iter().map(|s| s.id.clone()).collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    unused_symbols.sort();
+    assert_eq!(
+        unused_symbols,
+        vec![
+            (
+                "<root>/packages/lib-a/a-unused.js".to_string(),
+                vec!["deadA".to_string()]
+            ),
+            (
+                "<root>/packages/lib-a/a.js".to_string(),
+                vec!["unusedA".to_string()]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_default_export_local_names() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {}
+        }"#,
+        "packages/root/main.js" => r#"
+            import { named } from "./named.js";
+            import { anonymous } from "./anonymous.js";
+        "#,
+        "packages/root/named.js" => r#"
+            export const named = 1;
+            export default function Foo() {}
+        "#,
+        "packages/root/anonymous.js" => r#"
+            export const anonymous = 1;
+            export default function() {}
+        "#
+    );
+
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_files: vec![],
+            unused_symbols: amap!(
+                "<root>/packages/root/named.js" => vec![named_default_symbol("Foo")],
+                "<root>/packages/root/anonymous.js" => vec![symbol("default")]
+            ),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_barrel_exports_count_as_used() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {}
+        }"#,
+        "packages/root/main.js" => r#""#,
+        "packages/lib/package.json" => r#"{
+            "name": "lib-pkg",
+            "main": "./index.js"
+        }"#,
+        "packages/lib/index.js" => r#"
+            export { barrelOnly } from "./impl";
+        "#,
+        "packages/lib/impl.js" => r#"
+            export const barrelOnly = 1;
+        "#
+    );
+
+    // without the flag, the barrel and its re-exported symbol are unused
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_files: [
+                "<root>/packages/lib/impl.js",
+                "<root>/packages/lib/index.js",
+            ]
+            .iter()
+            .map(|x| x.to_string())
+            .collect(),
+            unused_symbols: amap!(
+                "<root>/packages/lib/impl.js" => vec![
+                    symbol("barrelOnly"),
+                ]
+            ),
+            ..Default::default()
+        },
+    );
+
+    // with the flag, symbols re-exported from the barrel are used
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            barrel_exports_count_as_used: true,
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_report_unused_entry_exports() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js"
+        }"#,
+        "packages/root/main.js" => r#"
+            export { publicApi } from "./lib.js";
+        "#,
+        "packages/root/lib.js" => r#"
+            import { internalHelper } from "./internal.js";
+            export const publicApi = internalHelper;
+            export const privateUnused = 2;
+        "#,
+        "packages/root/internal.js" => r#"
+            export const internalHelper = 1;
+            export const internalUnused = 2;
+        "#
+    );
+
+    // without the flag, every file of a package without "exports" is an
+    // entrypoint, so all of their exports are used
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            ..Default::default()
+        },
+    );
+
+    // with the flag, only the exports of the main entry are public
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            report_unused_entry_exports: true,
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_symbols: amap!(
+                "<root>/packages/root/lib.js" => vec![
+                    symbol("privateUnused"),
+                ],
+                "<root>/packages/root/internal.js" => vec![
+                    symbol("internalUnused"),
+                ]
+            ),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_find_unused_memoized_until_dirty() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./other";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+            export const unused = 2;
+        "#
+    );
+
+    let unused_ids = |result: &UnusedFinderResult| -> Vec<String> {
+        result
+            .get_report()
+            .unused_symbols
+            .values()
+            .flatten()
+            .map(|symbol| symbol.id.clone())
+            .collect()
+    };
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // consecutive calls without any dirty marks reuse the same result
+    let first = finder.find_unused(&logger).unwrap();
+    let second = finder.find_unused(&logger).unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(unused_ids(&first), vec!["unused".to_string()]);
+
+    // forcing recomputes, even when nothing is dirty
+    let forced = finder.find_unused_with_force(&logger, true).unwrap();
+    assert!(!Arc::ptr_eq(&second, &forced));
+    assert_eq!(first.get_report(), forced.get_report());
+
+    // marking a file dirty invalidates the memoized result
+    std::fs::write(
+        tmpdir.root_join("packages/root/other.js"),
+        "export const used = 1;",
+    )
+    .unwrap();
+    finder.mark_dirty([tmpdir.root_join("packages/root/other.js")]);
+    let after_dirty = finder.find_unused(&logger).unwrap();
+    assert!(!Arc::ptr_eq(&forced, &after_dirty));
+    assert_eq!(unused_ids(&after_dirty), Vec::<String>::new());
+}
+
+fn symbol_status_test_finder(
+    tmpdir: &test_tmpdir::TmpDir,
+    logger: &logger::StdioLogger,
+) -> UnusedFinder {
+    UnusedFinder::new_from_cfg(
+        logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_check_symbols() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./other.js";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+            export const unused = 2;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+
+    let statuses = finder
+        .check_symbols(
+            &logger,
+            vec![
+                (tmpdir.root_join("packages/root/other.js"), "used"),
+                // relative paths are resolved against the repo root
+                (PathBuf::from("packages/root/other.js"), "unused"),
+                (tmpdir.root_join("packages/root/missing.js"), "used"),
+                (tmpdir.root_join("packages/root/other.js"), "missing"),
+            ],
+        )
+        .unwrap();
+
+    assert_eq!(
+        statuses,
+        vec![
+            SymbolStatus::Used(vec![UsedTagEnum::Entry]),
+            SymbolStatus::Unused,
+            SymbolStatus::UnknownFile,
+            SymbolStatus::UnknownSymbol,
+        ]
+    );
+}
+
+#[test]
+fn test_check_symbols_recomputes_when_dirty() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./other.js";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+            export const unused = 2;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+    let query = || vec![(tmpdir.root_join("packages/root/other.js"), "unused")];
+
+    // checking symbols while clean reuses the memoized result
+    let before = finder.find_unused(&logger).unwrap();
+    assert_eq!(
+        finder.check_symbols(&logger, query()).unwrap(),
+        vec![SymbolStatus::Unused]
+    );
+    let after = finder.find_unused(&logger).unwrap();
+    assert!(Arc::ptr_eq(&before, &after));
+
+    // once a file is marked dirty, checking symbols sees the change
+    std::fs::write(
+        tmpdir.root_join("packages/root/main.js"),
+        r#"export { used, unused } from "./other.js";"#,
+    )
+    .unwrap();
+    finder.mark_dirty([tmpdir.root_join("packages/root/main.js")]);
+    assert_eq!(
+        finder.check_symbols(&logger, query()).unwrap(),
+        vec![SymbolStatus::Used(vec![UsedTagEnum::Entry])]
+    );
+    let recomputed = finder.find_unused(&logger).unwrap();
+    assert!(!Arc::ptr_eq(&after, &recomputed));
+}
+
+#[test]
+fn test_single_file_updates_reuse_resolver() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./other";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+            export const unused = 2;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(finder.resolver_constructions(), 1);
+
+    let initial = finder.find_unused(&logger).unwrap();
+    for _ in 0..100 {
+        finder.mark_dirty([tmpdir.root_join("packages/root/other.js")]);
+        let updated = finder.find_unused(&logger).unwrap();
+        assert_eq!(initial.get_report(), updated.get_report());
+    }
+    assert_eq!(finder.resolver_constructions(), 1);
+
+    // a dirty package.json refreshes the resolver state under its directory,
+    // without rebuilding the resolver
+    finder.mark_dirty([tmpdir.root_join("packages/root/package.json")]);
+    let refreshed = finder.find_unused(&logger).unwrap();
+    assert_eq!(initial.get_report(), refreshed.get_report());
+    assert_eq!(finder.resolver_constructions(), 1);
+
+    // a full refresh reuses the same resolver
+    finder.mark_all_dirty();
+    let refreshed = finder.find_unused(&logger).unwrap();
+    assert_eq!(initial.get_report(), refreshed.get_report());
+    assert_eq!(finder.resolver_constructions(), 1);
+}
+
+#[test]
+fn test_single_file_updates_patch_graph() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./other";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+            export const unused = 2;
+        "#,
+        "packages/root/a.js" => "export const a = 1;",
+        "packages/root/b.js" => "export const b = 1;"
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // the first traversal builds a node for every file
+    finder.find_unused(&logger).unwrap();
+    assert_eq!(finder.graph_file_constructions(), 4);
+
+    // marking a single file dirty only rebuilds its node, while the traversal
+    // still sees its new imports
+    std::fs::write(
+        tmpdir.root_join("packages/root/other.js"),
+        r#"export { a as used } from "./a";"#,
+    )
+    .unwrap();
+    finder.mark_dirty([tmpdir.root_join("packages/root/other.js")]);
+    let updated = finder.find_unused(&logger).unwrap();
+    assert_eq!(finder.graph_file_constructions(), 5);
+    let mut unused_files = normalize_test_report(&tmpdir, updated.get_report()).unused_files;
+    unused_files.sort();
+    assert_eq!(unused_files, vec!["<root>/packages/root/b.js".to_string()]);
+
+    // a full refresh rebuilds every node
+    finder.mark_all_dirty();
+    finder.find_unused(&logger).unwrap();
+    assert_eq!(finder.graph_file_constructions(), 9);
+}
+
+// Renders a report with its unordered parts sorted, so reports of the same
+// repo compare equal regardless of the order files were added to the graph
+fn canonical_report(tmpdir: &test_tmpdir::TmpDir, result: &UnusedFinderResult) -> String {
+    let mut report = normalize_test_report(tmpdir, result.get_report());
+    report.unused_files.sort();
+    report.opaque_modules.sort();
+    serde_json::to_string_pretty(&serde_json::to_value(&report).unwrap()).unwrap()
+}
+
+#[test]
+fn test_incremental_graph_matches_rebuild() {
+    let tmpdir = test_tmpdir!(
+        "packages/a/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./src/index.ts",
+            "exports": {
+                ".": "./src/index.ts"
+            }
+        }"#,
+        "packages/a/src/index.ts" => r#"export { b } from "./b";"#,
+        "packages/a/src/b.ts" => "export const b = 1;",
+        "packages/a/src/c.ts" => "export const c = 1;",
+        "packages/a/src/d.ts" => "export const d = 1;",
+        "packages/a/lib/b.js" => "export const b = 1;",
+        "packages/a/lib/c.js" => "export const c = 1;",
+        "packages/a/test/a.test.ts" => r#"test("nothing", () => {});"#
+    );
+
+    // the contents each file is edited to
+    let edits: Vec<(&str, Vec<&str>)> = vec![
+        (
+            "packages/a/src/index.ts",
+            vec![
+                r#"export { b } from "./b";"#,
+                r#"import { c } from "./c"; export const index = c;"#,
+                r#"export * from "./d";"#,
+                r#"export * as all from "./b"; export { default } from "./d";"#,
+            ],
+        ),
+        (
+            "packages/a/src/b.ts",
+            vec![
+                "export const b = 1; export const unusedB = 2;",
+                r#"import { d } from "./d"; export const b = d;"#,
+                r#"export type B = number; export const b = 1;"#,
+            ],
+        ),
+        (
+            "packages/a/src/c.ts",
+            vec![
+                "export const c = 1;",
+                r#"export { b as c } from "./b";"#,
+                r#"import * as d from "./d"; export const c = d;"#,
+            ],
+        ),
+        (
+            "packages/a/src/d.ts",
+            vec![
+                "export const d = 1; export default 2;",
+                r#"import { c } from "./c"; export const d = c;"#,
+                "export const d = 1;",
+            ],
+        ),
+        (
+            "packages/a/test/a.test.ts",
+            vec![
+                r#"import { b } from "../lib/b"; test("b", () => expect(b).toBe(1));"#,
+                r#"import { c } from "../lib/c"; test("c", () => expect(c).toBe(1));"#,
+                r#"test("nothing", () => {});"#,
+            ],
+        ),
+    ];
+
+    let config = || UnusedFinderConfig {
+        repo_root: tmpdir.root().to_string_lossy().to_string(),
+        root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+        entry_packages: vec!["entrypoint"].try_into().unwrap(),
+        test_files: vec![Glob::new("**/*.test.ts").unwrap()],
+        path_mirrors: vec![PathMirror {
+            from: "packages/*/lib/".to_string(),
+            to: "packages/*/src/".to_string(),
+        }]
+        .try_into()
+        .unwrap(),
+        ..Default::default()
+    };
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(&logger, config()).unwrap();
+    finder.find_unused(&logger).unwrap();
+
+    // deterministic xorshift, so that a failing sequence of edits can be replayed
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next_random = |bound: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound as u64) as usize
+    };
+
+    let mut applied_edits: Vec<String> = Vec::new();
+    for _ in 0..100 {
+        let (file, contents) = &edits[next_random(edits.len())];
+        let content = contents[next_random(contents.len())];
+        std::fs::write(tmpdir.root_join(file), content).unwrap();
+        applied_edits.push(format!("{file}: {content}"));
+
+        finder.mark_dirty([tmpdir.root_join(file)]);
+        let incremental = finder.find_unused(&logger).unwrap();
+        let rebuilt = UnusedFinder::new_from_cfg(&logger, config())
+            .unwrap()
+            .find_unused(&logger)
+            .unwrap();
+        pretty_assertions::assert_eq!(
+            canonical_report(&tmpdir, &rebuilt),
+            canonical_report(&tmpdir, &incremental),
+            "after edits:\n{}",
+            applied_edits.join("\n")
+        );
+    }
+}
+
+#[test]
+fn test_imports_into_skipped_dirs() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { generated } from "./generated/gen.js";
+            export const used = generated;
+        "#,
+        "packages/root/generated/gen.js" => r#"
+            export const generated = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            skip: vec!["generated".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let main_path = tmpdir.root_join("packages/root/main.js");
+    let generated_path = tmpdir.root_join("packages/root/generated/gen.js");
+    assert_eq!(finder.classify_import(&main_path), ResolvedImport::Walked);
+    assert_eq!(
+        finder.classify_import(&generated_path),
+        ResolvedImport::Skipped
+    );
+
+    let result = finder.find_unused(&logger).unwrap();
+    assert_eq!(
+        result.skipped_imports.get(&main_path),
+        Some(&vec![generated_path])
+    );
+}
+
+#[test]
+fn test_resolution_gaps() {
+    let tmpdir = test_tmpdir!(
+        "packages/app/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/app/main.js" => r#"
+            import { format } from "../shared/format.js";
+            export const used = format;
+        "#,
+        "packages/shared/format.js" => r#"
+            export const format = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir
+                .root_join("packages/app")
+                .to_string_lossy()
+                .to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let main_path = tmpdir.root_join("packages/app/main.js");
+    let format_path = tmpdir.root_join("packages/shared/format.js");
+    assert_eq!(
+        finder.classify_import(&format_path),
+        ResolvedImport::OutsideRootPaths
+    );
+
+    let result = finder.find_unused(&logger).unwrap();
+    assert_eq!(
+        result.resolution_gaps.get(&main_path),
+        Some(&vec![format_path])
+    );
+    assert_eq!(result.resolution_gap_count(), 1);
+    // above the threshold, the run fails
+    assert!(result.check_resolution_gaps(0).is_err());
+    // at or below the threshold, it passes
+    assert!(result.check_resolution_gaps(1).is_ok());
+}
+
+#[test]
+fn test_path_mirrors() {
+    let tmpdir = test_tmpdir!(
+        "packages/a/package.json" => r#"{ "name": "a" }"#,
+        "packages/a/src/format.ts" => r#"
+            export const format = 1;
+        "#,
+        "packages/a/lib/format.js" => r#"
+            export const format = 1;
+        "#,
+        "packages/a/lib/removed.js" => r#"
+            export const removed = 1;
+        "#,
+        "packages/a/test/format.test.ts" => r#"
+            import { format } from "../lib/format";
+            import { removed } from "../lib/removed";
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            test_files: vec![Glob::new("**/*.test.ts").unwrap()],
+            path_mirrors: vec![PathMirror {
+                from: "packages/*/lib/".to_string(),
+                to: "packages/*/src/".to_string(),
+            }]
+            .try_into()
+            .unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = finder.find_unused(&logger).unwrap();
+    // the import of the generated lib/ file uses its src/ twin
+    assert_eq!(
+        result.symbol_status(&tmpdir.root_join("packages/a/src/format.ts"), "format"),
+        SymbolStatus::Used(vec![UsedTagEnum::Test])
+    );
+    assert_eq!(
+        normalize_test_report(&tmpdir, result.get_report()).unused_files,
+        Vec::<String>::new()
+    );
+    // the generated file without a source file is reported as a diagnostic
+    assert_eq!(
+        result
+            .unmatched_mirror_imports
+            .get(&tmpdir.root_join("packages/a/test/format.test.ts")),
+        Some(&vec![tmpdir.root_join("packages/a/lib/removed.js")])
+    );
+}
+
+#[test]
+fn test_same_name_exports() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { formatDate } from "./dates";
+            export const today = formatDate(new Date());
+        "#,
+        "packages/root/dates.js" => r#"
+            export function formatDate(date) { return date.toISOString(); }
+        "#,
+        "packages/root/legacy/dateUtils.js" => r#"
+            export function formatDate(date) { return date.toString(); }
+            export function parseDate(date) { return new Date(date); }
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            suggest_same_name_exports: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = normalize_test_report(&tmpdir, finder.find_unused(&logger).unwrap().get_report());
+    // parseDate is not exported anywhere else, so it has no suggestion
+    assert_eq!(
+        report.same_name_exports,
+        amap!(
+            "<root>/packages/root/legacy/dateUtils.js" => vec![SameNameExportReport {
+                id: "formatDate".to_string(),
+                file_paths: vec!["<root>/packages/root/dates.js".to_string()],
+            }]
+        )
+    );
+}
+
+#[test]
+fn test_ambiguous_symbol_names() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { Button, Icon } from "./components/Button";
+            import { Toolbar } from "./toolbar";
+            export const app = [Button, Icon, Toolbar];
+        "#,
+        "packages/root/toolbar.js" => r#"
+            import { Button } from "./legacy/Button";
+            export const Toolbar = [Button];
+        "#,
+        "packages/root/components/Button.js" => r#"
+            export const Button = "button";
+            export const Icon = "icon";
+        "#,
+        "packages/root/legacy/Button.js" => r#"
+            export const Button = "legacy button";
+            export const Icon = "legacy icon";
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let find_ambiguous_names = |mode: AmbiguousSymbolNames| {
+        let mut finder = UnusedFinder::new_from_cfg(
+            &logger,
+            UnusedFinderConfig {
+                repo_root: tmpdir.root().to_string_lossy().to_string(),
+                root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+                entry_packages: vec!["entrypoint"].try_into().unwrap(),
+                report_ambiguous_symbol_names: mode,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        normalize_test_report(&tmpdir, finder.find_unused(&logger).unwrap().get_report())
+            .ambiguous_symbol_names
+    };
+
+    let button = AmbiguousSymbolNameReport {
+        name: "Button".to_string(),
+        file_paths: vec![
+            "<root>/packages/root/components/Button.js".to_string(),
+            "<root>/packages/root/legacy/Button.js".to_string(),
+        ],
+        imported_from: vec![
+            "<root>/packages/root/components/Button.js".to_string(),
+            "<root>/packages/root/legacy/Button.js".to_string(),
+        ],
+    };
+    let icon = AmbiguousSymbolNameReport {
+        name: "Icon".to_string(),
+        file_paths: button.file_paths.clone(),
+        imported_from: vec!["<root>/packages/root/components/Button.js".to_string()],
+    };
+    assert_eq!(
+        find_ambiguous_names(AmbiguousSymbolNames::All),
+        vec![button.clone(), icon]
+    );
+    // Icon is only ever imported from one of its files
+    assert_eq!(
+        find_ambiguous_names(AmbiguousSymbolNames::ConflictingImports),
+        vec![button]
+    );
+    assert!(find_ambiguous_names(AmbiguousSymbolNames::Off).is_empty());
+}
+
+#[test]
+fn test_used_files() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { helper } from "./helper";
+            export const main = helper;
+        "#,
+        "packages/root/helper.js" => r#"
+            export const helper = 1;
+        "#,
+        "packages/root/unused.js" => r#"
+            import { helper } from "./helper";
+            export const unused = helper;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            path_display: PathDisplay::RepoRelative,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+
+    assert_eq!(
+        result.used_files(),
+        vec![
+            tmpdir.root_join("packages/root/helper.js"),
+            tmpdir.root_join("packages/root/main.js"),
+        ]
+    );
+    let manifest: Vec<String> = serde_json::from_str(&result.used_files_manifest(true)).unwrap();
+    assert_eq!(
+        manifest
+            .iter()
+            .map(|file_path| file_path.replace('\\', "/"))
+            .collect::<Vec<_>>(),
+        vec!["packages/root/helper.js", "packages/root/main.js"]
+    );
+    assert_eq!(
+        result
+            .used_files_manifest(false)
+            .lines()
+            .collect::<Vec<_>>(),
+        manifest
+    );
+}
+
+#[test]
+fn test_single_importer_files() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { helper } from "./helper";
+            import { shared } from "./shared";
+            const { helperConfig } = require("./helper");
+            export const main = [helper, helperConfig, shared];
+        "#,
+        "packages/root/helper.js" => r#"
+            import { shared } from "./shared";
+            export const helper = shared;
+            export const helperConfig = {};
+        "#,
+        "packages/root/shared.js" => r#"
+            export const shared = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+
+    // helper.js is imported and required by main.js only, which counts once.
+    // shared.js has two importers
+    assert_eq!(
+        result.single_importer_files(),
+        vec![(
+            tmpdir.root_join("packages/root/helper.js"),
+            tmpdir.root_join("packages/root/main.js"),
+        )]
+    );
+}
+
+#[test]
+fn test_cascade_if_deleted() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { Button, Icon } from "./components";
+            import { format } from "./format";
+            export const main = [Button, Icon, format];
+        "#,
+        "packages/root/components/index.js" => r#"
+            export { Button } from "./Button";
+            export { Icon } from "./Icon";
+        "#,
+        "packages/root/components/Button.js" => r#"
+            import { format } from "../format";
+            export const Button = format("button");
+        "#,
+        "packages/root/components/Icon.js" => r#"
+            export const Icon = "icon";
+        "#,
+        "packages/root/format.js" => r#"
+            export const format = (x) => x;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+
+    // the leaves only re-exported by the barrel become unused with it, but
+    // format.js is still imported by main.js
+    assert_eq!(
+        result.cascade_if_deleted(&tmpdir.root_join("packages/root/components/index.js")),
+        vec![
+            tmpdir.root_join("packages/root/components/Button.js"),
+            tmpdir.root_join("packages/root/components/Icon.js"),
+        ]
+    );
+    // a leaf has nothing to cascade to
+    assert_eq!(
+        result.cascade_if_deleted(&tmpdir.root_join("packages/root/components/Icon.js")),
+        Vec::<PathBuf>::new()
+    );
+}
+
+#[test]
+fn test_unused_imports() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { a, b } from "./ab";
+            import { View } from "./view.jsx";
+            export const value = a + View;
+        "#,
+        "packages/root/ab.js" => r#"
+            export const a = 1;
+            export const b = 2;
+        "#,
+        // JSX references React implicitly
+        "packages/root/view.jsx" => r#"
+            import React from "react";
+            import { Widget } from "./widget";
+            export const View = () => <Widget.Item />;
+        "#,
+        "packages/root/widget.js" => r#"
+            export const Widget = { Item: () => null };
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            report_unused_imports: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = normalize_test_report(&tmpdir, finder.find_unused(&logger).unwrap().get_report());
+    let unused_imports: HashMap<String, Vec<(String, String, u32)>> = report
+        .unused_imports
+        .iter()
+        .map(|(file_path, imports)| {
+            let imports = imports
+                .iter()
+                .map(|import| {
+                    (
+                        import.specifier.clone(),
+                        import.id.clone(),
+                        import.end - import.start,
+                    )
+                })
+                .collect();
+            (file_path.clone(), imports)
+        })
+        .collect();
+    assert_eq!(
+        unused_imports,
+        HashMap::from([(
+            "<root>/packages/root/main.js".to_string(),
+            vec![("./ab".to_string(), "b".to_string(), "b".len() as u32)]
+        )])
+    );
+    // b is still imported, so it is not an unused export
+    assert!(!report
+        .unused_symbols
+        .get("<root>/packages/root/ab.js")
+        .is_some_and(|symbols| symbols.iter().any(|symbol| symbol.id == "b")));
+}
+
+#[test]
+fn test_import_kind_counts() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { a, b } from "./named";
+            import def from "./def";
+            import * as ns from "./ns";
+            import "./effect";
+            export { c } from "./named";
+            export * from "./ns";
+            const lazy = import("./lazy");
+            const cjs = require("./cjs");
+            export const all = [a, b, def, ns, lazy, cjs];
+        "#,
+        "packages/root/named.js" => r#"
+            export const a = 1;
+            export const b = 2;
+            export const c = 3;
+        "#,
+        "packages/root/def.js" => r#"
+            export default 1;
+        "#,
+        "packages/root/ns.js" => r#"
+            export const n = 1;
+        "#,
+        "packages/root/effect.js" => r#"
+            console.log("loaded");
+        "#,
+        "packages/root/lazy.js" => r#"
+            export const lazy = 1;
+        "#,
+        "packages/root/cjs.js" => r#"
+            module.exports = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            count_import_kinds: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = finder.find_unused(&logger).unwrap().get_report();
+    assert_eq!(
+        report.import_kind_counts,
+        Some(ImportKindCounts {
+            static_named: 2,
+            static_default: 1,
+            namespace: 1,
+            dynamic: 1,
+            require: 1,
+            re_export: 2,
+            effect_only: 1,
+        })
+    );
+}
+
+#[test]
+fn test_iter_unused_matches_report() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { used } from "./other";
+            export const main = used;
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+            export const unused1 = 2;
+            export default function unused2() {}
+        "#,
+        "packages/root/orphan.js" => r#"
+            export const orphan = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+    let report = result.get_report();
+
+    let mut unused_files: Vec<String> = result
+        .iter_unused_files()
+        .map(|file_path| file_path.to_string_lossy().to_string())
+        .collect();
+    unused_files.sort();
+    assert_eq!(unused_files, report.unused_files);
+
+    let mut unused_symbols: HashMap<String, Vec<SymbolReport>> = HashMap::new();
+    for (file_path, symbol) in result.iter_unused_symbols() {
+        unused_symbols
+            .entry(file_path.to_string_lossy().to_string())
+            .or_default()
+            .push(symbol.to_report());
+    }
+    let mut report_symbols: HashMap<String, Vec<SymbolReport>> =
+        report.unused_symbols.clone().into_iter().collect();
+    for symbols in unused_symbols
+        .values_mut()
+        .chain(report_symbols.values_mut())
+    {
+        symbols.sort();
+    }
+    assert_eq!(unused_symbols, report_symbols);
+
+    // the result renders the same unused files and symbols as the report
+    let sorted_lines = |rendered: String| {
+        let mut lines: Vec<String> = rendered.lines().map(str::to_owned).collect();
+        lines.sort();
+        lines
+    };
+    let mut expected_lines = sorted_lines(report.to_string());
+    expected_lines.retain(|line| line.contains(" unused") || line.starts_with("  - "));
+    assert_eq!(sorted_lines(result.to_string()), expected_lines);
+    assert_eq!(expected_lines.len(), 4);
+
+    let mut entry_files: Vec<&Path> = result.iter_tagged_files(UsedTagEnum::Entry).collect();
+    entry_files.sort();
+    assert_eq!(
+        entry_files,
+        vec![
+            tmpdir.root_join("packages/root/main.js"),
+            tmpdir.root_join("packages/root/other.js"),
+        ]
+    );
+
+    // iterating borrows from the graph, while the report copies every path and symbol
+    let (num_items, iter_allocations) = count_allocations(|| {
+        result.iter_unused_files().count()
+            + result.iter_unused_symbols().count()
+            + result.iter_tagged_files(UsedTagEnum::Entry).count()
+    });
+    assert_eq!(num_items, 6);
+    assert_eq!(iter_allocations, 0);
+    let (_, report_allocations) = count_allocations(|| result.get_report());
+    assert!(report_allocations >= num_items);
+}
+
+#[test]
+fn test_find_unused_timings() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./other";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // the initial walk is attributed to the first result
+    let timings = finder.find_unused(&logger).unwrap().timings;
+    assert!(timings.walk > Duration::ZERO);
+    assert!(timings.resolve > Duration::ZERO);
+    assert!(timings.export_star_expansion > Duration::ZERO);
+    assert_eq!(
+        timings.total(),
+        timings.walk
+            + timings.resolve
+            + timings.graph
+            + timings.export_star_expansion
+            + timings.entry_traversal
+            + timings.ignored_traversal
+            + timings.test_traversal
+    );
+
+    // nothing was re-walked for the second result
+    let timings = finder
+        .find_unused_with_force(&logger, true)
+        .unwrap()
+        .timings;
+    assert_eq!(timings.walk, Duration::ZERO);
+    assert_eq!(timings.resolve, Duration::ZERO);
+    // re-exports are expanded for every result
+    assert!(timings.export_star_expansion > Duration::ZERO);
+}
+
+#[test]
+fn test_deletion_order() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export const used = 1;
+        "#,
+        // b.js is only imported by another unused file
+        "packages/root/b.js" => r#"
+            import { c } from "./c.js";
+            export const b = c;
+        "#,
+        "packages/root/c.js" => r#"
+            export const c = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = finder.find_unused(&logger).unwrap();
+    assert_eq!(
+        result.deletion_order(),
+        vec![
+            tmpdir.root_join("packages/root/b.js"),
+            tmpdir.root_join("packages/root/c.js"),
+        ]
+    );
+}
+
+#[test]
+fn test_package_graph() {
+    let tmpdir = test_tmpdir!(
+        "packages/a/package.json" => r#"{
+            "name": "a",
+            "main": "./index.js"
+        }"#,
+        "packages/a/index.js" => r#"
+            import { b } from "../b/index.js";
+            import { util } from "./util.js";
+            export const a = b + util;
+        "#,
+        "packages/a/util.js" => r#"
+            export const util = 1;
+        "#,
+        "packages/b/package.json" => r#"{
+            "name": "b",
+            "main": "./index.js"
+        }"#,
+        "packages/b/index.js" => r#"
+            import { c } from "../c/index.js";
+            export const b = c;
+        "#,
+        "packages/c/package.json" => r#"{
+            "name": "c",
+            "main": "./index.js"
+        }"#,
+        "packages/c/index.js" => r#"
+            export const c = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["a"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = finder.find_unused(&logger).unwrap();
+    let package_graph = result.package_graph();
+    let expected: HashMap<String, HashSet<String>> = HashMap::from([
+        ("a".to_string(), HashSet::from(["b".to_string()])),
+        ("b".to_string(), HashSet::from(["c".to_string()])),
+        ("c".to_string(), HashSet::new()),
+    ]);
+    assert_eq!(package_graph.dependencies, expected);
+}
+
+#[test]
+fn test_collapsed_package_graph() {
+    let tmpdir = test_tmpdir!(
+        "packages/a/package.json" => r#"{
+            "name": "a",
+            "main": "./index.js"
+        }"#,
+        "packages/a/index.js" => r#"
+            import { b, b2 } from "../b/index.js";
+            import { c } from "../c/index.js";
+            import { util } from "./util.js";
+            export const a = b + b2 + c + util;
+        "#,
+        "packages/a/util.js" => r#"
+            import { b } from "../b/index.js";
+            export const util = b;
+        "#,
+        "packages/b/package.json" => r#"{
+            "name": "b",
+            "main": "./index.js"
+        }"#,
+        "packages/b/index.js" => r#"
+            import { c } from "../c/index.js";
+            export const b = c;
+            export const b2 = 2;
+        "#,
+        "packages/b/unused.js" => r#"
+            export const unused = 1;
+        "#,
+        "packages/c/package.json" => r#"{
+            "name": "c",
+            "main": "./index.js"
+        }"#,
+        "packages/c/index.js" => r#"
+            export const c = 1;
+        "#,
+        "scripts/tool.js" => r#"
+            import { c } from "../packages/c/index.js";
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["a"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = finder.find_unused(&logger).unwrap();
+    let collapsed = result.collapsed_package_graph();
+    let nodes: Vec<(&str, usize, usize)> = collapsed
+        .packages
+        .iter()
+        .map(|(package, node)| (package.as_str(), node.files, node.unused_files))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![("(unrooted)", 1, 1), ("a", 2, 0), ("b", 2, 1), ("c", 1, 0),]
+    );
+    // imports within a package are dropped, and imports of the same file
+    // count as a single file-level edge
+    let edges: Vec<(&str, &str, usize, Vec<&str>)> = collapsed
+        .edges
+        .iter()
+        .map(|((from, to), edge)| {
+            (
+                from.as_str(),
+                to.as_str(),
+                edge.file_edges,
+                edge.top_symbols(3),
+            )
+        })
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("(unrooted)", "c", 1, vec!["c"]),
+            ("a", "b", 2, vec!["b", "b2"]),
+            ("a", "c", 1, vec!["c"]),
+            ("b", "c", 1, vec!["c"]),
+        ]
+    );
+    // the unrooted files are not part of the package dependencies
+    assert_eq!(collapsed.to_package_graph(), result.package_graph());
+    assert!(!result
+        .package_graph()
+        .dependencies
+        .contains_key("(unrooted)"));
+
+    // filtering keeps the packages depending on b and the packages b depends on
+    let mut dot = Vec::new();
+    result
+        .write_package_dot_graph(&logger, Some("b"), &mut dot)
+        .unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(dot.contains(r#""b" [label="b\n2 files, 1 unused (50%)""#));
+    assert!(dot.contains(r#""a" -> "b" [label="2 imports\nb, b2"];"#));
+    assert!(dot.contains(r#""b" -> "c" [label="1 import\nc"];"#));
+    assert!(!dot.contains("(unrooted)"));
+}
+
+#[test]
+fn test_large_modules() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { a, b } from "./big.js";
+            export { small } from "./small.js";
+            export const c = 1;
+        "#,
+        "packages/root/big.js" => r#"
+            export const a = 1;
+            export const b = 2;
+            export const c = 3;
+            export const d = 4;
+        "#,
+        "packages/root/small.js" => r#"
+            export const small = 1;
+        "#
+    );
+
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            god_module_threshold: Some(2),
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_symbols: amap!(
+                "<root>/packages/root/big.js" => vec![symbol("c"), symbol("d")]
+            ),
+            large_modules: vec![
+                LargeModuleReport {
+                    file_path: "<root>/packages/root/big.js".to_string(),
+                    num_exported_symbols: 4,
+                },
+                // re-exported symbols count towards the total
+                LargeModuleReport {
+                    file_path: "<root>/packages/root/main.js".to_string(),
+                    num_exported_symbols: 4,
+                },
+            ],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_opaque_modules() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import App from "./App.vue";
+            export const app = App;
+        "#,
+        "packages/root/App.vue" => r#"
+            <template><div>app</div></template>
+        "#,
+        "packages/root/Orphan.vue" => r#"
+            <template><div>orphan</div></template>
+        "#
+    );
+
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            opaque_module_extensions: vec![".vue".to_string(), ".svelte".to_string()],
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_files: vec!["<root>/packages/root/Orphan.vue".to_string()],
+            opaque_modules: vec![
+                "<root>/packages/root/App.vue".to_string(),
+                "<root>/packages/root/Orphan.vue".to_string(),
+            ],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_keep_alive_decorators() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export const main = 1;
+        "#,
+        "packages/root/services.ts" => r#"
+            import { format } from "./format.js";
+
+            @Injectable()
+            export class UserService {
+                name() { return format("user"); }
+            }
+
+            export class UnusedService {}
+        "#,
+        "packages/root/format.js" => r#"
+            export const format = (x) => x;
+        "#
+    );
+
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            keep_alive_decorators: vec!["Injectable".to_string()],
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_symbols: amap!(
+                "<root>/packages/root/services.ts" => vec![
+                    symbol("UnusedService"),
+                ]
+            ),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_keep_pragmas() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { used } from "./lib.js";
+            export const main = used;
+        "#,
+        "packages/root/lib.js" => r#"
+            export const used = 1;
+
+            // good-fences-used
+            export const kept = 2;
+
+            // eslint-disable-next-line no-unused-exports (kept)
+            export function keptByEslintComment() {}
+
+            export const unused = 3;
+        "#
+    );
+
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            keep_pragmas: vec!["good-fences-used".to_string(), "(kept)".to_string()],
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_symbols: amap!(
+                "<root>/packages/root/lib.js" => vec![
+                    symbol("unused"),
+                ]
+            ),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_report_path_display() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./other.js";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+            export const unused = 2;
+        "#,
+        "packages/root/orphan.js" => r#"
+            export const orphan = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+
+    let sorted_symbol_paths = |report: &UnusedFinderReport| -> Vec<String> {
+        let mut paths: Vec<String> = report.unused_symbols.keys().cloned().collect();
+        paths.sort();
+        paths
+    };
+    let absolute_path = |path: &str| -> String {
+        tmpdir
+            .root()
+            .join(PathBuf::from_slash(path))
+            .to_string_lossy()
+            .to_string()
+    };
+    let relative_path =
+        |path: &str| -> String { PathBuf::from_slash(path).to_string_lossy().to_string() };
+
+    // paths are absolute by default
+    let absolute = result.get_report();
+    assert_eq!(
+        absolute.unused_files,
+        vec![absolute_path("packages/root/orphan.js")]
+    );
+    assert_eq!(
+        sorted_symbol_paths(&absolute),
+        vec![
+            absolute_path("packages/root/orphan.js"),
+            absolute_path("packages/root/other.js"),
+        ]
+    );
+
+    // the same result can be reported relative to the repo root
+    let relative = result.get_report_with_path_display(PathDisplay::RepoRelative);
+    assert_eq!(
+        relative.unused_files,
+        vec![relative_path("packages/root/orphan.js")]
+    );
+    assert_eq!(
+        sorted_symbol_paths(&relative),
+        vec![
+            relative_path("packages/root/orphan.js"),
+            relative_path("packages/root/other.js"),
+        ]
+    );
+}
+
+#[test]
+fn test_depcruise_json() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { readFileSync } from "node:fs";
+            import React from "react";
+            export { used } from "./other.js";
+        "#,
+        "packages/root/other.js" => r#"
+            export const used = 1;
+        "#,
+        "packages/root/orphan.js" => r#"
+            import { used } from "./other.js";
+            export const orphan = used;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            path_display: PathDisplay::RepoRelative,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+
+    let mut json: Vec<u8> = Vec::new();
+    result.to_depcruise_json(&mut json).unwrap();
+    let output: DepcruiseOutput = serde_json::from_slice(&json).unwrap();
+
+    let relative_path =
+        |path: &str| -> String { PathBuf::from_slash(path).to_string_lossy().to_string() };
+    let local_dependency = |path: &str| DepcruiseDependency {
+        resolved: relative_path(path),
+        dependency_types: vec![DepcruiseDependencyType::Local],
+        core_module: false,
+    };
+    assert_eq!(
+        output.modules,
+        vec![
+            DepcruiseModule {
+                source: relative_path("packages/root/main.js"),
+                dependencies: vec![
+                    DepcruiseDependency {
+                        resolved: "node:fs".to_string(),
+                        dependency_types: vec![DepcruiseDependencyType::Core],
+                        core_module: true,
+                    },
+                    local_dependency("packages/root/other.js"),
+                    DepcruiseDependency {
+                        resolved: "react".to_string(),
+                        dependency_types: vec![DepcruiseDependencyType::Npm],
+                        core_module: false,
+                    },
+                ],
+                orphan: false,
+            },
+            DepcruiseModule {
+                source: relative_path("packages/root/orphan.js"),
+                dependencies: vec![local_dependency("packages/root/other.js")],
+                orphan: true,
+            },
+            DepcruiseModule {
+                source: relative_path("packages/root/other.js"),
+                dependencies: vec![],
+                orphan: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_find_import_sites() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/helper.js" => r#"
+            export const helper = 1;
+            export const other = 2;
+        "#,
+        // direct import
+        "packages/root/main.js" => r#"
+            import { helper } from "./helper.js";
+            import { other } from "./helper.js";
+        "#,
+        // import through a barrel that renames the symbol
+        "packages/root/barrel.js" => r#"
+            export { helper as renamedHelper, other } from "./helper.js";
+        "#,
+        "packages/root/consumer.js" => r#"
+            import { renamedHelper, other } from "./barrel.js";
+        "#,
+        // namespace import
+        "packages/root/namespace.js" => r#"
+            import * as helpers from "./helper.js";
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+    let import_sites = finder
+        .find_import_sites(&logger, "packages/root/helper.js", "helper")
+        .unwrap();
+
+    // spans cover the imported name, or the whole namespace import
+    let summarized: Vec<(PathBuf, u32, bool, Vec<PathBuf>)> = import_sites
+        .into_iter()
+        .map(|site| {
+            (
+                site.file,
+                site.span.hi.0 - site.span.lo.0,
+                site.namespace,
+                site.via,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summarized,
+        vec![
+            (
+                tmpdir.root_join("packages/root/consumer.js"),
+                "renamedHelper".len() as u32,
+                false,
+                vec![tmpdir.root_join("packages/root/barrel.js")],
+            ),
+            (
+                tmpdir.root_join("packages/root/main.js"),
+                "helper".len() as u32,
+                false,
+                vec![],
+            ),
+            (
+                tmpdir.root_join("packages/root/namespace.js"),
+                "* as helpers".len() as u32,
+                true,
+                vec![],
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_symbol_origin_external() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { debounce, limit, local } from "./barrel.js";
+        "#,
+        "packages/root/barrel.js" => r#"
+            export { debounce } from "lodash";
+            export * from "./utils.js";
+        "#,
+        "packages/root/utils.js" => r#"
+            export { throttle as limit } from "lodash";
+            export const local = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+    let mut origin_of = |symbol_name: &str| {
+        finder
+            .resolve_symbol_origin(&logger, "packages/root/barrel.js", symbol_name)
+            .unwrap()
+    };
+    let barrel = tmpdir.root_join("packages/root/barrel.js");
+    let utils = tmpdir.root_join("packages/root/utils.js");
+
+    assert_eq!(
+        origin_of("debounce"),
+        Some(SymbolOrigin {
+            file_path: PathBuf::from("lodash"),
+            symbol: ExportedSymbol::Named("debounce".to_string()),
+            external: true,
+            re_export_chain: vec![barrel.clone()],
+        })
+    );
+    assert_eq!(
+        origin_of("limit"),
+        Some(SymbolOrigin {
+            file_path: PathBuf::from("lodash"),
+            symbol: ExportedSymbol::Named("throttle".to_string()),
+            external: true,
+            re_export_chain: vec![barrel.clone(), utils.clone()],
+        })
+    );
+    assert_eq!(
+        origin_of("local"),
+        Some(SymbolOrigin {
+            file_path: utils,
+            symbol: ExportedSymbol::Named("local".to_string()),
+            external: false,
+            re_export_chain: vec![barrel],
+        })
+    );
+    assert_eq!(origin_of("missing"), None);
+}
+
+#[test]
+fn test_dump_file_analysis() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import { a, b } from "./barrel.js";
+        "#,
+        "packages/root/barrel.js" => r#"
+            export * from "./a.js";
+            export { b } from "./b.js";
+        "#,
+        "packages/root/a.js" => r#"
+            export const a = 1;
+        "#,
+        "packages/root/b.js" => r#"
+            export const b = 2;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+    let dump = finder
+        .dump_file_analysis(&logger, "packages/root/barrel.js")
+        .unwrap();
+    let display = |path: &str| tmpdir.root_join(path).to_string_lossy().to_string();
+
+    assert!(dump.walk.in_graph);
+    assert_eq!(dump.walk.owning_package.as_deref(), Some("entrypoint"));
+    assert_eq!(dump.parse_error, None);
+    assert_eq!(dump.tags, vec![UsedTagEnum::Entry]);
+    assert!(dump.entrypoint_reasons.is_empty());
+
+    // the name from `export *` has no span of its own, but still resolves
+    // to its definition
+    let summarized_exports: Vec<(String, bool, Option<String>, Vec<String>)> = dump
+        .exports
+        .iter()
+        .map(|export| {
+            (
+                export.name.clone(),
+                export.from_star_export,
+                export.reexported_from.clone(),
+                export
+                    .definitions
+                    .iter()
+                    .map(|definition| definition.file_path.clone())
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summarized_exports,
+        vec![
+            (
+                "a".to_string(),
+                true,
+                None,
+                vec![display("packages/root/a.js")]
+            ),
+            (
+                "b".to_string(),
+                false,
+                Some(display("packages/root/b.js")),
+                vec![display("packages/root/b.js")]
+            ),
+        ]
+    );
+
+    let summarized_edges: Vec<(String, String, Vec<UsedTagEnum>)> = dump
+        .incoming_edges
+        .into_iter()
+        .map(|edge| (edge.importer, edge.symbol, edge.followed_by))
+        .collect();
+    assert_eq!(
+        summarized_edges,
+        vec![
+            (
+                display("packages/root/main.js"),
+                "a".to_string(),
+                vec![UsedTagEnum::Entry]
+            ),
+            (
+                display("packages/root/main.js"),
+                "b".to_string(),
+                vec![UsedTagEnum::Entry]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_verify_test_files() {
+    let tmpdir = test_tmpdir!(
+        "search_root/util.js" => r#"
+            export const util = 1;
+        "#,
+        // matched by the test glob, but only a helper for other tests
+        "search_root/fixtures.test.js" => r#"
+            import { util } from "./util";
+            export const fixture = util;
+        "#,
+        "search_root/util.test.js" => r#"
+            import { util } from "./util";
+            describe("util", () => {
+                it("is 1", () => {
+                    expect(util).toBe(1);
+                });
+            });
+        "#
+    );
+
+    let logger = logger::VecLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec!["search_root".to_string()],
+            test_files: vec![Glob::new("**/*.test.js").unwrap()],
+            verify_test_files: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    finder.find_unused(&logger).unwrap();
+
+    let warnings: Vec<String> = logger
+        .get_logs()
+        .unwrap()
+        .into_iter()
+        .filter(|log| log.starts_with("WARN:"))
+        .collect();
+    assert_eq!(warnings.len(), 1, "unexpected warnings: {warnings:?}");
+    assert!(warnings[0].contains("search_root/fixtures.test.js"));
+}
+
+#[test]
+fn test_restrict_to_tsconfig_files() {
+    let tmpdir = test_tmpdir!(
+        "tsconfig.json" => r#"{
+            "include": ["src"],
+            "exclude": ["src/legacy", "**/*.stories.ts"]
+        }"#,
+        "src/index.ts" => r#"
+            export const index = 1;
+        "#,
+        "src/orphan.ts" => r#"
+            export const orphan = 1;
+        "#,
+        "src/button.stories.ts" => r#"
+            export const story = 1;
+        "#,
+        "src/legacy/old.ts" => r#"
+            export const old = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let find_unused_files = |restrict_to_tsconfig_files: bool| {
+        let mut finder = UnusedFinder::new_from_cfg(
+            &logger,
+            UnusedFinderConfig {
+                repo_root: tmpdir.root().to_string_lossy().to_string(),
+                root_paths: vec!["src".to_string()],
+                entry_packages: vec!["<root>"].try_into().unwrap(),
+                restrict_to_tsconfig_files,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut unused_files =
+            normalize_test_report(&tmpdir, finder.find_unused(&logger).unwrap().get_report())
+                .unused_files;
+        unused_files.sort();
+        unused_files
+    };
+
+    assert_eq!(
+        find_unused_files(false),
+        vec![
+            "<root>/src/button.stories.ts".to_string(),
+            "<root>/src/legacy/old.ts".to_string(),
+            "<root>/src/orphan.ts".to_string(),
+        ]
+    );
+    // files excluded by the tsconfig are not analyzed at all
+    assert_eq!(
+        find_unused_files(true),
+        vec!["<root>/src/orphan.ts".to_string()]
+    );
+}
+
+#[test]
+fn test_synthetic_root_package() {
+    // Tests a plain project without any package.json, using "<root>" to
+    // select the index.ts file of the root path as its entrypoint
+    let tmpdir = test_tmpdir!(
+        "tsconfig.json" => r#"{
+            "compilerOptions": { "strict": true }
+        }"#,
+        "src/index.ts" => r#"
+            import { a } from "./a";
+            console.log(a);
+        "#,
+        "src/a.ts" => r#"
+            import { b } from "./b";
+            export const a = b;
+            export const unusedInA = 1;
+        "#,
+        "src/b.ts" => r#"
+            export const b = 1;
+        "#,
+        "src/orphan.ts" => r#"
+            export const orphan = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec!["src".to_string()],
+            entry_packages: vec!["<root>"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+    let report = normalize_test_report(&tmpdir, result.get_report());
+
+    assert_eq!(
+        report.unused_files,
+        vec!["<root>/src/orphan.ts".to_string()]
+    );
+    // the entry chain index.ts -> a.ts -> b.ts is used, except for unusedInA
+    assert!(!report.unused_symbols.contains_key("<root>/src/b.ts"));
+    assert_eq!(
+        report.unused_symbols["<root>/src/a.ts"]
+            .iter()
+            .map(|symbol| symbol.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["unusedInA"]
+    );
+    assert_eq!(
+        report.synthetic_root_package,
+        tmpdir
+            .root()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    );
+}
+
+#[test]
+fn test_symbol_collisions() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        // both star exports provide formatDate, and the explicit re-export of
+        // legacy.js wins. The renamed re-export of iso.js does not collide.
+        "packages/root/main.js" => r#"
+            export * from "./dates";
+            export * from "./legacy";
+            export { formatDate } from "./legacy";
+            export { formatDate as formatIsoDate } from "./iso";
+        "#,
+        "packages/root/dates.js" => r#"
+            export const formatDate = (date) => date.toString();
+            export const parseDate = (date) => new Date(date);
+        "#,
+        "packages/root/legacy.js" => r#"
+            export const formatDate = (date) => date.toLocaleString();
+        "#,
+        "packages/root/iso.js" => r#"
+            export const formatDate = (date) => date.toISOString();
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+    let result = finder.find_unused(&logger).unwrap();
+    let report = normalize_test_report(&tmpdir, result.get_report());
+
+    let collisions: Vec<(String, String, Vec<(String, String, bool)>)> = report
+        .symbol_collisions
+        .into_iter()
+        .map(|collision| {
+            (
+                collision.entry_file,
+                collision.name,
+                collision
+                    .definitions
+                    .into_iter()
+                    .map(|definition| (definition.file_path, definition.symbol, definition.wins))
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        collisions,
+        vec![(
+            "<root>/packages/root/main.js".to_string(),
+            "formatDate".to_string(),
+            vec![
+                (
+                    "<root>/packages/root/dates.js".to_string(),
+                    "formatDate".to_string(),
+                    false
+                ),
+                (
+                    "<root>/packages/root/legacy.js".to_string(),
+                    "formatDate".to_string(),
+                    true
+                ),
+            ]
+        )]
+    );
+}
+
+#[test]
+fn test_ambiguous_star_reexports() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        // formatDate is ambiguous between the star exports, so main.js does
+        // not export it. parseDate only comes from dates.js.
+        "packages/root/main.js" => r#"
+            export * from "./dates";
+            export * from "./legacy";
+        "#,
+        // the local declaration takes priority over the star exports
+        "packages/root/local.js" => r#"
+            export * from "./dates";
+            export * from "./legacy";
+            export const formatDate = (date) => date.toISOString();
+        "#,
+        // main.js contributes no formatDate, so only legacy.js provides it
+        "packages/root/outer.js" => r#"
+            export * from "./main";
+            export * from "./legacy";
+        "#,
+        "packages/root/dates.js" => r#"
+            export const formatDate = (date) => date.toString();
+            export const parseDate = (date) => new Date(date);
+        "#,
+        "packages/root/legacy.js" => r#"
+            export const formatDate = (date) => date.toLocaleString();
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+    let result = finder.find_unused(&logger).unwrap();
+    let report = normalize_test_report(&tmpdir, result.get_report());
+
+    assert_eq!(
+        report.ambiguous_reexports,
+        vec![AmbiguousReexportReport {
+            file_path: "<root>/packages/root/main.js".to_string(),
+            name: "formatDate".to_string(),
+            sources: vec![
+                "<root>/packages/root/dates.js".to_string(),
+                "<root>/packages/root/legacy.js".to_string(),
+            ],
+        }]
+    );
+    // the ambiguous name is not exported, so it does not collide either
+    assert_eq!(report.symbol_collisions, vec![]);
+}
+
+#[test]
+fn test_target_env_export_conditions() {
+    // without a main file, the generated tsconfig maps "dual" to the package
+    // directory, so its exports decide which file is imported
+    let repo = FixtureRepo::new()
+        .package("app", |p| {
+            p.main("./main.js").file(
+                "main.js",
+                r#"
+            import { platform } from "dual";
+            console.log(platform);
+        "#,
+            )
+        })
+        .package("dual", |p| {
+            p.exports_map(json!({
+                ".": {
+                    "node": "./node.js",
+                    "default": "./browser.js"
+                }
+            }))
+            .file("node.js", r#"export const platform = "node";"#)
+            .file("browser.js", r#"export const platform = "browser";"#)
+        });
+
+    let unused_files = |target_env| {
+        let logger = logger::StdioLogger::new();
+        let mut finder = UnusedFinder::new_from_cfg(
+            &logger,
+            UnusedFinderConfig {
+                repo_root: repo.root().to_string_lossy().to_string(),
+                root_paths: vec![repo.root().to_string_lossy().to_string()],
+                entry_packages: vec!["app"].try_into().unwrap(),
+                target_env,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let result = finder.find_unused(&logger).unwrap();
+        normalize_test_report(&repo, result.get_report()).unused_files
+    };
+
+    assert_eq!(
+        unused_files(ResolverTargetEnv::Browser),
+        vec!["<root>/packages/dual/node.js".to_string()]
+    );
+    assert_eq!(
+        unused_files(ResolverTargetEnv::Node),
+        vec!["<root>/packages/dual/browser.js".to_string()]
+    );
+}
+
+#[test]
+fn test_barrel_bypass_imports() {
+    // the generated tsconfig maps @scope/pkg to its main file, and
+    // @scope/pkg/* to the files of the package
+    let repo = FixtureRepo::new()
+        .package("entrypoint", |p| {
+            p.dir("packages/root")
+                .main("./main.js")
+                .exports_map(json!({ ".": "./main.js" }))
+                // util is also exported from the package root, internalOnly is not,
+                // and importing the package root is always fine
+                .file(
+                    "main.js",
+                    r#"
+            import { util } from "@scope/pkg/src/util";
+            import { internalOnly } from "@scope/pkg/src/internal";
+            import { helper } from "@scope/pkg";
+            console.log(util, internalOnly, helper);
+        "#,
+                )
+        })
+        .package("@scope/pkg", |p| {
+            p.main("./src/index.js")
+                .file(
+                    "src/index.js",
+                    r#"
+            export { util } from "./util";
+            export const helper = 1;
+        "#,
+                )
+                // deep imports within the package are not reported
+                .file(
+                    "src/util.js",
+                    r#"
+            import { internalOnly } from "./internal";
+            export const util = internalOnly;
+        "#,
+                )
+                .file(
+                    "src/internal.js",
+                    r#"
+            export const internalOnly = 2;
+        "#,
+                )
+        });
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: repo.root().to_string_lossy().to_string(),
+            root_paths: vec![repo.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            report_barrel_bypass_imports: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = finder.find_unused(&logger).unwrap();
+    let report = normalize_test_report(&repo, result.get_report());
+
+    assert_eq!(
+        report.barrel_bypass_imports,
+        vec![BarrelBypassImportReport {
+            importer: "<root>/packages/root/main.js".to_string(),
+            imported_file: "<root>/packages/pkg/src/util.js".to_string(),
+            symbol: "util".to_string(),
+            package_name: "@scope/pkg".to_string(),
+            barrel_file: "<root>/packages/pkg/src/index.js".to_string(),
+            barrel_name: "util".to_string(),
+        }]
+    );
+
+    // without the flag, deep imports are not checked
+    let mut finder = symbol_status_test_finder(&repo, &logger);
+    let result = finder.find_unused(&logger).unwrap();
+    assert_eq!(result.get_report().barrel_bypass_imports, vec![]);
+}
+
+#[test]
+fn test_repeated_declarations_get_distinct_ids() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            export { used } from "./dates";
+        "#,
+        // overload signatures, and an interface merged with a const
+        "packages/root/dates.ts" => r#"
+            export const used = 1;
+            export function formatDate(date: Date): string;
+            export function formatDate(date: number): string;
+            export function formatDate(date: any): string { return String(date); }
+            export interface Options { verbose: boolean }
+            export const Options = { verbose: false };
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = symbol_status_test_finder(&tmpdir, &logger);
+    let result = finder.find_unused(&logger).unwrap();
+    let report = normalize_test_report(&tmpdir, result.get_report());
+
+    let symbols = report
+        .unused_symbols
+        .get("<root>/packages/root/dates.ts")
+        .unwrap();
+    assert_eq!(
+        symbols
+            .iter()
+            .map(|symbol| symbol.id.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "Options",
+            "Options#type",
+            "formatDate",
+            "formatDate#2",
+            "formatDate#3"
+        ]
+    );
+    // each declaration is reported with its own span
+    let start_of = |id: &str| symbols.iter().find(|symbol| symbol.id == id).unwrap().start;
+    assert!(start_of("formatDate") < start_of("formatDate#2"));
+    assert!(start_of("formatDate#2") < start_of("formatDate#3"));
+    assert!(start_of("Options#type") < start_of("Options"));
+
+    // ids recorded before the names were declared more than once still match
+    // every declaration of the name, but suffixed ids only match their own
+    let suppressed = ["formatDate", "Options"];
+    assert!(symbols
+        .iter()
+        .all(|symbol| suppressed.iter().any(|id| symbol.matches_id(id))));
+    let overload = symbols
+        .iter()
+        .find(|symbol| symbol.id == "formatDate#2")
+        .unwrap();
+    assert!(overload.matches_id("formatDate#2"));
+    assert!(!overload.matches_id("formatDate#3"));
+
+    let statuses = finder
+        .check_symbols(
+            &logger,
+            vec![
+                (PathBuf::from("packages/root/dates.ts"), "formatDate"),
+                (PathBuf::from("packages/root/dates.ts"), "formatDate#2"),
+                (PathBuf::from("packages/root/dates.ts"), "Options#type"),
+                (PathBuf::from("packages/root/dates.ts"), "formatDate#4"),
+                (PathBuf::from("packages/root/dates.ts"), "used#2"),
+            ],
+        )
+        .unwrap();
+    assert_eq!(
+        statuses,
+        vec![
+            SymbolStatus::Unused,
+            SymbolStatus::Unused,
+            SymbolStatus::Unused,
+            SymbolStatus::UnknownSymbol,
+            SymbolStatus::UnknownSymbol,
+        ]
+    );
+}
+
+#[test]
+fn test_fail_on_parse_error() {
+    let tmpdir = test_tmpdir!(
+        "search_root/valid.js" => r#"
+            export const valid = 1;
+        "#,
+        "search_root/broken.js" => r#"
+            export const = ;
+        "#
+    );
+    let config = |fail_on_parse_error| UnusedFinderConfig {
+        repo_root: tmpdir.root().to_string_lossy().to_string(),
+        root_paths: vec!["search_root".to_string()],
+        fail_on_parse_error,
+        ..Default::default()
+    };
+
+    // strict mode fails the whole run, naming the unparseable file
+    let logger = logger::VecLogger::new();
+    let err = match UnusedFinder::new_from_cfg(&logger, config(true)) {
+        Ok(_) => panic!("expected the run to fail on the unparseable file"),
+        Err(err) => err,
+    };
+    assert!(
+        err.to_string().contains("search_root/broken.js"),
+        "unexpected error: {err}"
+    );
+
+    // lenient mode skips the unparseable file with a warning
+    let logger = logger::VecLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(&logger, config(false)).unwrap();
+    finder.find_unused(&logger).unwrap();
+
+    let warnings: Vec<String> = logger
+        .get_logs()
+        .unwrap()
+        .into_iter()
+        .filter(|log| log.starts_with("WARN:"))
+        .collect();
+    assert_eq!(warnings.len(), 1, "unexpected warnings: {warnings:?}");
+    assert!(warnings[0].contains("search_root/broken.js"));
+}
+
+#[test]
+fn test_degraded_parse_keeps_imports() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "root",
+            "main": "./main.js"
+        }"#,
+        "packages/root/main.js" => r#"
+            import { modern } from "./modern.js";
+            export const root = modern;
+        "#,
+        "packages/root/modern.js" => r#"
+            import { helper } from "./helper.js";
+            const lazy = import("./lazy.js");
+            export const modern = helper + @@@;
+        "#,
+        "packages/root/helper.js" => r#"
+            export const helper = 1;
+            export const otherHelper = 2;
+        "#,
+        "packages/root/lazy.js" => r#"
+            export const lazy = 1;
+        "#,
+        "packages/root/unused.js" => r#"
+            export const unused = 1;
+        "#
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["root"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = finder.find_unused(&logger).unwrap();
+    let report = normalize_test_report(&tmpdir, result.get_report());
+    // the imports of the unparseable file keep everything they import used
+    assert_eq!(
+        report.unused_files,
+        vec!["<root>/packages/root/unused.js".to_string()]
+    );
+    // the symbols of the unparseable file are unknown, so none are reported
+    assert!(!report
+        .unused_symbols
+        .contains_key("<root>/packages/root/modern.js"));
+    assert!(!report
+        .unused_symbols
+        .contains_key("<root>/packages/root/helper.js"));
+    assert_eq!(report.degraded_files.len(), 1);
+    assert_eq!(
+        report.degraded_files[0].file_path,
+        "<root>/packages/root/modern.js"
+    );
+    assert!(report.degraded_files[0]
+        .parse_error
+        .contains("packages/root/modern.js"));
+}
+
+#[test]
+fn test_entry_package_without_entrypoints() {
+    let app = |main: Option<&str>| {
+        FixtureRepo::new().package("@org/app", |p| {
+            let p = p.file("index.js", "export const app = 1;");
+            match main {
+                Some(main) => p.main(main),
+                None => p,
+            }
+        })
+    };
+    let config = |repo: &FixtureRepo, strict_entry_packages| UnusedFinderConfig {
+        repo_root: repo.root().to_string_lossy().to_string(),
+        root_paths: vec![repo.root().to_string_lossy().to_string()],
+        entry_packages: vec!["@org/*"].try_into().unwrap(),
+        strict_entry_packages,
+        ..Default::default()
+    };
+    let warnings = |logger: &logger::VecLogger| -> Vec<String> {
+        logger
+            .get_logs()
+            .unwrap()
+            .into_iter()
+            .filter(|log| log.starts_with("WARN:"))
+            .collect()
+    };
+
+    // a package.json without "exports", "main" or "module" is reported, naming
+    // the package and the rule that selected it
+    let repo = app(None);
+    let logger = logger::VecLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(&logger, config(&repo, false)).unwrap();
+    finder.find_unused(&logger).unwrap();
+    let warnings_without_main = warnings(&logger);
+    assert_eq!(
+        warnings_without_main.len(),
+        1,
+        "unexpected warnings: {warnings_without_main:?}"
+    );
+    assert!(warnings_without_main[0].contains("@org/app"));
+    assert!(warnings_without_main[0].contains(r#""@org/*""#));
+
+    let explained = finder.explain_entrypoints(&logger).unwrap();
+    assert_eq!(explained.packages_without_entrypoints.len(), 1);
+    assert_eq!(
+        explained.packages_without_entrypoints[0].package,
+        "@org/app"
+    );
+    assert!(explained.packages_without_entrypoints[0]
+        .entrypoint_fields
+        .is_empty());
+
+    // strict mode fails the run instead
+    let logger = logger::VecLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(&logger, config(&repo, true)).unwrap();
+    let err = match finder.find_unused(&logger) {
+        Ok(_) => panic!("expected the run to fail on the entry package"),
+        Err(err) => err,
+    };
+    assert!(
+        err.to_string().contains("@org/app"),
+        "unexpected error: {err}"
+    );
+
+    // a "main" field is enough to clear the diagnostic
+    let repo = app(Some("./index.js"));
+    let logger = logger::VecLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(&logger, config(&repo, true)).unwrap();
+    finder.find_unused(&logger).unwrap();
+    assert_eq!(warnings(&logger), Vec::<String>::new());
+
+    let explained = finder.explain_entrypoints(&logger).unwrap();
+    assert!(explained.packages_without_entrypoints.is_empty());
+    assert_eq!(explained.packages.len(), 1);
+    assert_eq!(explained.packages[0].package, "@org/app");
+    assert_eq!(explained.packages[0].match_rule.as_deref(), Some("@org/*"));
+    assert_eq!(
+        explained.packages[0].entrypoints,
+        [repo
+            .root_join("packages/app/index.js")
+            .to_string_lossy()
+            .to_string()]
+    );
+}
+
+#[test]
+fn test_typeof_import_types() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.ts",
+            "exports": {
+                ".": "./main.ts"
+            }
+        }"#,
+        "packages/root/main.ts" => r#"
+            export type Api = typeof import("./api");
+            export type Handler = import("./handlers").Handler;
+        "#,
+        "packages/root/api.ts" => r#"
+            export function get() {}
+            export function post() {}
+        "#,
+        "packages/root/handlers.ts" => r#"
+            export type Handler = () => void;
+            export type UnusedHandler = () => void;
+        "#
+    );
+
+    run_unused_test(
+        &tmpdir,
+        UnusedFinderConfig {
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["entrypoint"].try_into().unwrap(),
+            ..Default::default()
+        },
+        UnusedFinderReport {
+            unused_symbols: amap!(
+                "<root>/packages/root/handlers.ts" => vec![
+                    symbol("UnusedHandler"),
+                ]
+            ),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_effect_imports_mark_symbols() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "entrypoint",
+            "main": "./main.js",
+            "exports": {
+                ".": "./main.js"
+            }
+        }"#,
+        "packages/root/main.js" => r#"
+            import "./register";
+        "#,
+        // a registration module, executed for its side effects
+        "packages/root/register.js" => r#"
+            import { registerIcon } from "./icons";
+            registerIcon("close");
+            export { helper } from "./helpers";
+        "#,
+        "packages/root/icons.js" => r#"
+            export function registerIcon(name) {}
+        "#,
+        "packages/root/helpers.js" => r#"
+            export const helper = 1;
+        "#
+    );
+
+    let find_unused = |effect_imports_mark_symbols: bool| {
+        let logger = logger::StdioLogger::new();
+        let mut finder = UnusedFinder::new_from_cfg(
+            &logger,
+            UnusedFinderConfig {
+                repo_root: tmpdir.root().to_string_lossy().to_string(),
+                root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+                entry_packages: vec!["entrypoint"].try_into().unwrap(),
+                effect_imports_mark_symbols,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let result = finder.find_unused(&logger).unwrap();
+        normalize_test_report(&tmpdir, result.get_report())
+    };
+    let is_helper_unused = |report: &UnusedFinderReport| {
+        report
+            .unused_symbols
+            .get("<root>/packages/root/helpers.js")
+            .is_some_and(|symbols| symbols.iter().any(|symbol| symbol.id == "helper"))
+    };
+
+    // by default, the side-effect import uses the re-exported helper
+    let report = find_unused(true);
+    assert!(!is_helper_unused(&report), "{:#?}", report.unused_symbols);
+    assert!(!report
+        .unused_files
+        .contains(&"<root>/packages/root/icons.js".to_string()));
+
+    // otherwise, the imports of the registration module are still used
+    let report = find_unused(false);
+    assert!(is_helper_unused(&report), "{:#?}", report.unused_symbols);
+    assert!(!report
+        .unused_files
+        .contains(&"<root>/packages/root/icons.js".to_string()));
+    assert!(!report
+        .unused_files
+        .contains(&"<root>/packages/root/register.js".to_string()));
+}
+
+#[test]
+fn test_github_workflow_commands() {
+    let tmpdir = test_tmpdir!(
+        "packages/root/package.json" => r#"{
+            "name": "root",
+            "main": "./main.js"
+        }"#,
+        "packages/root/main.js" => "import { used } from \"./lib.js\";\nexport const root = used;\n",
+        "packages/root/lib.js" => "export const used = 1;\n\nexport const unused = 2;\n"
+    );
+
+    let logger = logger::StdioLogger::new();
+    let mut finder = UnusedFinder::new_from_cfg(
+        &logger,
+        UnusedFinderConfig {
+            report_exported_symbols: true,
+            repo_root: tmpdir.root().to_string_lossy().to_string(),
+            root_paths: vec![tmpdir.root().to_string_lossy().to_string()],
+            entry_packages: vec!["root"].try_into().unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = finder.find_unused(&logger).unwrap();
+    let commands: Vec<String> = result
+        .get_report()
+        .github_workflow_commands(tmpdir.root())
+        .iter()
+        .map(|command| command.to_string())
+        .collect();
+    assert_eq!(
+        commands,
+        vec!["::warning file=packages/root/lib.js,line=3::unused is exported, but never used"]
+    );
+}
+
+#[test]
+fn test_respect_fences() {
+    // the package exports a barrel, but its fence only exports the public module
+    let repo = FixtureRepo::new().package("entrypoint", |p| {
+        p.exports_map(json!({
+            ".": "./index.js",
+            "./public": "./public.js"
+        }))
+        .fence(json!({"exports": ["public"]}))
+        .file("index.js", r#"export { helper } from "./internal.js";"#)
+        .file("internal.js", "export const helper = 1;")
+        .file("public.js", "export const api = 1;")
+    });
+    let unused_files = |respect_fences: bool| -> Vec<String> {
+        let logger = logger::StdioLogger::new();
+        let mut finder = UnusedFinder::new_from_cfg(
+            &logger,
+            UnusedFinderConfig {
+                repo_root: repo.root().to_string_lossy().to_string(),
+                root_paths: vec![repo.root().to_string_lossy().to_string()],
+                entry_packages: vec!["entrypoint"].try_into().unwrap(),
+                respect_fences,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let result = finder.find_unused(&logger).unwrap();
+        let mut unused_files = normalize_test_report(&repo, result.get_report()).unused_files;
+        unused_files.sort();
+        unused_files
+    };
+
+    // by default, everything the barrel re-exports is used
+    assert_eq!(unused_files(false), Vec::<String>::new());
+
+    // the barrel is not exported by the fence, so it is no longer an entrypoint
+    assert_eq!(
+        unused_files(true),
+        vec![
+            "<root>/packages/entrypoint/index.js".to_string(),
+            "<root>/packages/entrypoint/internal.js".to_string(),
+        ]
+    );
+}
@@ -0,0 +1,51 @@
+use core::fmt::{self, Display};
+use std::time::Duration;
+
+/// Breakdown of the time spent in each phase of an unused-finder run.
+///
+/// Walk and resolve durations cover any (re-)scanning of the file tree done
+/// since the previous result was computed, so they are zero when nothing was
+/// refreshed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnusedFinderTimings {
+    /// Time spent walking the file tree and parsing source files.
+    pub walk: Duration,
+    /// Time spent resolving imports to file paths.
+    pub resolve: Duration,
+    /// Time spent building the graph from the resolved source files.
+    pub graph: Duration,
+    /// Time spent expanding `export *` re-exports, to find the names that are
+    /// ambiguous between the files they re-export.
+    pub export_star_expansion: Duration,
+    /// Time spent traversing the graph from entry package exports.
+    pub entry_traversal: Duration,
+    /// Time spent traversing the graph from ignored files and symbols.
+    pub ignored_traversal: Duration,
+    /// Time spent traversing the graph from test files.
+    pub test_traversal: Duration,
+}
+
+impl UnusedFinderTimings {
+    pub fn total(&self) -> Duration {
+        self.walk
+            + self.resolve
+            + self.graph
+            + self.export_star_expansion
+            + self.entry_traversal
+            + self.ignored_traversal
+            + self.test_traversal
+    }
+}
+
+impl Display for UnusedFinderTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  walk:              {:?}", self.walk)?;
+        writeln!(f, "  resolve:           {:?}", self.resolve)?;
+        writeln!(f, "  graph:             {:?}", self.graph)?;
+        writeln!(f, "  export *:          {:?}", self.export_star_expansion)?;
+        writeln!(f, "  entry traversal:   {:?}", self.entry_traversal)?;
+        writeln!(f, "  ignored traversal: {:?}", self.ignored_traversal)?;
+        writeln!(f, "  test traversal:    {:?}", self.test_traversal)?;
+        write!(f, "  total:             {:?}", self.total())
+    }
+}
@@ -1,22 +1,49 @@
 use core::option::Option::None;
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashSet},
+    fmt::Display,
     hash::{DefaultHasher, Hash, Hasher},
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
 };
 
 use crate::{
-    cfg::{UnusedFinderConfig, UnusedFinderJSONConfig},
+    cfg::{ResolverTargetEnv, UnusedFinderConfig, UnusedFinderJSONConfig},
+    collisions::{
+        find_ambiguous_reexports, find_barrel_bypass_imports, find_symbol_collisions,
+        resolve_exported_definitions, AmbiguousReexport, BarrelBypassImport, SymbolCollision,
+    },
+    depcruise::DepcruiseOutput,
     graph::{Graph, GraphFile},
     ignore_file::IgnoreFile,
-    parse::{get_file_import_export_info, ExportedSymbol},
-    report::UnusedFinderReport,
-    tag::UsedTag,
-    walk::{walk_src_files, RepoPackages, WalkedFiles},
-    walked_file::ResolvedSourceFile,
+    inspect::{
+        dump_exports, dump_imports, dump_incoming_edges, EntryPackageDump,
+        EntryPackageWithoutEntrypoints, EntrypointReason, EntrypointsDump, FileAnalysisDump,
+        WalkedFileDump,
+    },
+    package_graph::{CollapsedPackageGraph, PackageGraph, UNROOTED_PACKAGE},
+    parse::{
+        get_file_import_export_info_or_scan, ExportedSymbol, ExportedSymbolMetadata,
+        RawImportExportInfo,
+    },
+    report::{
+        declaration_ids, display_path, import_kind_counts, is_used, split_declaration_suffix,
+        write_partially_unused_file, write_unused_file, write_unused_symbol, AmbiguousSymbolNames,
+        PathDisplay, SymbolRef, UnusedFinderReport,
+    },
+    tag::{UsedTag, UsedTagEnum},
+    timings::UnusedFinderTimings,
+    walk::{
+        is_opaque_module, walk_src_files, RepoPackages, SkipMatcher, TsconfigFileFilter,
+        WalkedFiles,
+    },
+    walked_file::{ResolvedSourceFile, WalkedPackage},
 };
-use ahashmap::AHashMap;
+use ahashmap::{AHashMap, AHashSet};
 use anyhow::{Context, Result};
+use good_fences::fence::parse_fence_str;
+use good_fences::fence_collection::FenceCollection;
 use import_resolver::swc_resolver::{
     combined_resolver::CombinedResolverCaches,
     internal_resolver::InternalOnlyResolver,
@@ -25,7 +52,12 @@ use import_resolver::swc_resolver::{
 };
 use js_err::JsErr;
 use logger::{debug_logf, Logger};
+use multi_err::MultiErr;
+use path_slash::PathExt;
+use path_utils::Glob;
 use rayon::{iter::Either, prelude::*};
+use relative_path::RelativePath;
+use swc_common::{FileName, Span};
 use swc_ecma_loader::{resolve::Resolve, TargetEnv};
 
 #[derive(Debug)]
@@ -48,8 +80,114 @@ pub struct UnusedFinder {
     // since the last time we checked for unused files
     dirty_files: DirtyFiles,
     last_walk_result: SourceFiles,
+
+    // incremented whenever files are marked dirty, so we can tell if the
+    // memoized result of `find_unused` is still valid
+    generation: u64,
+    // the last result of `find_unused`, and the generation it was computed at
+    last_result: Option<(u64, Arc<UnusedFinderResult>)>,
+    // time spent walking / resolving files since the last result was computed
+    pending_timings: UnusedFinderTimings,
+
+    // resolver shared between full walks and single-file updates, so its
+    // caches persist for the lifetime of the UnusedFinder
+    resolver: RepoResolver,
+    // directories whose cached resolver state must be dropped before the
+    // next full refresh
+    dirty_resolver_roots: DirtyFiles,
+    // number of times the resolver has been constructed
+    resolver_constructions: usize,
+    // matches paths that the walk skips, to classify imports into skipped directories
+    skip_matcher: SkipMatcher,
+
+    // the graph of the last traversal. It is shared with the last result, and
+    // patched in place when only some files were marked dirty.
+    graph: Arc<Graph>,
+    // files whose graph nodes must be patched before the next traversal
+    dirty_graph_files: DirtyFiles,
+    // ids of graph files whose imports were rewritten by the path mirrors
+    mirrored_file_ids: Vec<usize>,
+    // number of graph nodes constructed, by rebuilds and by patches
+    graph_file_constructions: usize,
+}
+
+/// How the target of a resolved import relates to the files that were walked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolvedImport {
+    /// The import resolved to a walked file, which is part of the graph
+    Walked,
+    /// The import resolved into a path excluded by the `skip` config, so the
+    /// target is not part of the graph and is not analyzed
+    Skipped,
+    /// The import resolved to a file outside of every root path, so the
+    /// target is not part of the graph. This usually means the `rootPaths`
+    /// config is missing a directory.
+    OutsideRootPaths,
+    /// The import resolved to a file that was not walked for any other reason
+    /// (e.g. an asset within the root paths)
+    NotWalked,
+}
+
+/// Whether an exported symbol is used, as of the last traversal of the graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolStatus {
+    /// The symbol is used. The tags explain what it is used from.
+    Used(Vec<UsedTagEnum>),
+    /// The symbol is exported, but nothing uses it
+    Unused,
+    /// The file is not part of the graph (e.g. it was not walked)
+    UnknownFile,
+    /// The file is part of the graph, but does not export the symbol
+    UnknownSymbol,
+}
+
+/// A place where an exported symbol is imported, and which would need to be
+/// edited if the symbol were renamed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportSite {
+    /// The file containing the import
+    pub file: PathBuf,
+    /// The span of the imported name. For namespace imports, this is the span
+    /// of the whole namespace import (e.g. `* as foo` or `require('./foo')`).
+    pub span: Span,
+    /// True if the symbol is imported through a namespace rather than by name,
+    /// so uses of it are not found at the span of the import
+    pub namespace: bool,
+    /// The barrel files the import passes through, starting from the one that
+    /// re-exports the symbol from its defining file
+    pub via: Vec<PathBuf>,
+}
+
+/// Where a symbol exported from a file is defined, found by following the
+/// re-exports of barrel files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolOrigin {
+    /// The file defining the symbol. For symbols of external packages, this
+    /// is the import specifier of the package instead, e.g. `lodash`.
+    pub file_path: PathBuf,
+    /// The name the symbol is exported as from its origin
+    pub symbol: ExportedSymbol,
+    /// True if the origin is an external package rather than a file of the repo
+    pub external: bool,
+    /// The files re-exporting the symbol on the way to its origin, starting
+    /// from the file the symbol was looked up in
+    pub re_export_chain: Vec<PathBuf>,
 }
 
+type RepoResolver = InternalOnlyResolver<MonorepoResolver>;
+
+/// Test directories used for packages that do not declare one in their
+/// package.json, when `use_default_test_dirs` is set.
+const DEFAULT_TEST_DIRS: &[&str] = &["test", "tests", "__tests__"];
+
+/// Entry package name that marks the files in `DEFAULT_ROOT_ENTRY_FILES` under
+/// each root path as entrypoints, for repos without any package.json.
+const ROOT_ENTRY_PACKAGE: &str = "<root>";
+
+/// Files under each root path used as entrypoints when `<root>` is listed as
+/// an entry package.
+const DEFAULT_ROOT_ENTRY_FILES: &[&str] = &["index.ts", "main.ts"];
+
 /// In-memory representation of the file tree, where imports have been resolved
 /// to file-paths.
 #[derive(Debug)]
@@ -63,6 +201,9 @@ struct SourceFiles {
     /// should be ignored entirely when checking for unused symbols. Those files
     /// are recursively ignored.
     ignore_files: Vec<IgnoreFile>,
+    /// The good-fences fences found during the walk, keyed by their absolute
+    /// paths. Only loaded if the config respects fences.
+    fences: Option<FenceCollection>,
 }
 
 impl SourceFiles {
@@ -118,24 +259,46 @@ impl SourceFiles {
             source_files,
             packages: walk_result.packages,
             ignore_files: walk_result.ignore_files,
+            fences: None,
         })
     }
 }
 
-fn resolver_for_packages(root_dir: PathBuf, packages: &RepoPackages) -> impl Resolve {
-    let mut caches = CombinedResolverCaches::new();
-    // pre-populate the packagejson cache with the loaded package json files
-    let pkg_caches = caches.package_json_cache();
-    for package in packages.packages.iter() {
-        pkg_caches.prepopulate(&package.package_path, package.package_json.clone());
-    }
+/// Parses the fence.json files found during the walk, keyed by their absolute
+/// paths so they apply to the walked source files
+fn load_fences(fence_files: &[PathBuf]) -> Result<FenceCollection, anyhow::Error> {
+    let fences_map = fence_files
+        .iter()
+        .map(|fence_file| -> Result<_, anyhow::Error> {
+            let fence_path = fence_file.to_slash_lossy().to_string();
+            let fence_str = std::fs::read_to_string(fence_file)
+                .with_context(|| format!("Failed to read fence {}", fence_file.display()))?;
+            let fence = parse_fence_str(&fence_str, RelativePath::new(&fence_path))?;
+            Ok((fence_path, fence))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(FenceCollection::new(fences_map, None))
+}
 
+// Builds the resolver shared by walks and single-file updates, counting the
+// construction in `constructions`
+fn build_resolver(
+    root_dir: PathBuf,
+    packages: &RepoPackages,
+    opaque_module_extensions: &[String],
+    target_env: ResolverTargetEnv,
+    constructions: &mut usize,
+) -> RepoResolver {
+    *constructions += 1;
     // TODO: rewrite the monorepo resolver to use an abstract filesystem that supports caching I/O
     // then, use that to prepopulate the locations of files on disk. That will short-circuit the
     // resolver going to disk.
 
     // create a new monorepo resolver
-    let mut resolver_options = NodeModulesResolverOptions::default_for_env(TargetEnv::Browser);
+    let mut resolver_options = NodeModulesResolverOptions::default_for_env(match target_env {
+        ResolverTargetEnv::Browser => TargetEnv::Browser,
+        ResolverTargetEnv::Node => TargetEnv::Node,
+    });
     // include assets during resolution
     let ext_iter = DEFAULT_EXTENSIONS.iter().chain([".svg", ".bmp"].iter());
     // also include d.* extensions during resolution (e.g. "d.ts")
@@ -143,20 +306,47 @@ fn resolver_for_packages(root_dir: PathBuf, packages: &RepoPackages) -> impl Res
         .clone()
         .map(|x| x.to_string())
         .chain(ext_iter.map(|x| format!("{}{}", "d.", x)))
+        // also resolve extensionless imports of opaque modules (e.g. "./App" to "./App.vue")
+        .chain(
+            opaque_module_extensions
+                .iter()
+                .map(|x| x.trim_start_matches('.').to_string()),
+        )
         .collect::<Vec<String>>();
-    // also include "source" import conditions during resolution
+    // also include "source" import conditions during resolution, and "node"
+    // conditions when resolving for node
+    let env_conditions: &[&str] = match target_env {
+        ResolverTargetEnv::Browser => &[],
+        ResolverTargetEnv::Node => &["node"],
+    };
     resolver_options.export_conditions = ["source"]
         .iter()
+        .chain(env_conditions)
         .chain(DEFAULT_EXPORT_CODITIONS)
         .map(|x| x.to_string())
         .collect::<Vec<String>>();
 
-    let monorepo_resolver = MonorepoResolver::new_for_caches(root_dir, caches, resolver_options);
+    let monorepo_resolver =
+        MonorepoResolver::new_for_caches(root_dir, CombinedResolverCaches::new(), resolver_options);
+    // pre-populate the packagejson cache with the loaded package json files
+    prepopulate_package_jsons(monorepo_resolver.caches(), packages, |_| true);
+
     // create a new resolver that uses the source files to resolve imports
-    let walked_files_resolver =
-        InternalOnlyResolver::new_with_package_names(monorepo_resolver, packages.iter_names());
+    InternalOnlyResolver::new_with_package_names(monorepo_resolver, packages.iter_names())
+}
 
-    walked_files_resolver
+fn prepopulate_package_jsons(
+    caches: &CombinedResolverCaches,
+    packages: &RepoPackages,
+    should_prepopulate: impl Fn(&Path) -> bool,
+) {
+    let pkg_caches = caches.package_json_cache();
+    for package in packages.packages.iter() {
+        // synthetic packages have no package.json on disk for the resolver to find
+        if !package.synthetic && should_prepopulate(&package.package_path) {
+            pkg_caches.prepopulate(&package.package_path, package.package_json.clone());
+        }
+    }
 }
 
 impl UnusedFinder {
@@ -170,24 +360,191 @@ impl UnusedFinder {
 
     pub fn new_from_cfg(
         logger: impl Logger + Sync,
-        config: UnusedFinderConfig,
+        mut config: UnusedFinderConfig,
+    ) -> Result<Self, JsErr> {
+        Self::check_repo_root(&config)?;
+        config
+            .expand_root_paths(&logger)
+            .map_err(JsErr::invalid_arg)?;
+
+        // perform initial walk on initialization to get an internal representation of source files
+        let mut pending_timings = UnusedFinderTimings::default();
+        let walked_files = Self::walk_all(&logger, &config, &mut pending_timings)?;
+        Self::new_from_walk(logger, config, walked_files, pending_timings)
+    }
+
+    /// Walks and parses the source files of the root paths in a config, without
+    /// creating an UnusedFinder.
+    ///
+    /// The walked files can be inspected, then passed to `new_from_walked_files`,
+    /// so that other tools can share a single walk with the UnusedFinder.
+    pub fn walk(
+        logger: impl Logger + Sync,
+        config: &UnusedFinderConfig,
+    ) -> Result<WalkedFiles, JsErr> {
+        Self::check_repo_root(config)?;
+        let mut config = config.clone();
+        config
+            .expand_root_paths(&logger)
+            .map_err(JsErr::invalid_arg)?;
+        Self::walk_all(logger, &config, &mut UnusedFinderTimings::default())
+    }
+
+    /// Creates an UnusedFinder from the result of a previous `walk` over the
+    /// same config, instead of walking the root paths again.
+    pub fn new_from_walked_files(
+        logger: impl Logger + Sync,
+        mut config: UnusedFinderConfig,
+        walked_files: WalkedFiles,
     ) -> Result<Self, JsErr> {
+        Self::check_repo_root(&config)?;
+        config
+            .expand_root_paths(&logger)
+            .map_err(JsErr::invalid_arg)?;
+        Self::new_from_walk(logger, config, walked_files, UnusedFinderTimings::default())
+    }
+
+    fn check_repo_root(config: &UnusedFinderConfig) -> Result<(), JsErr> {
         if config.repo_root.is_empty() {
             return Err(JsErr::invalid_arg(anyhow!(
                 "repoRoot must be set in config"
             )));
         }
+        Ok(())
+    }
 
-        // perform initial walk on initialization to get an internal representation of source files
-        let resolved_walked_files = Self::walk_and_resolve_all(logger, &config)?;
+    fn new_from_walk(
+        logger: impl Logger + Sync,
+        config: UnusedFinderConfig,
+        walked_files: WalkedFiles,
+        mut pending_timings: UnusedFinderTimings,
+    ) -> Result<Self, JsErr> {
+        let skip_matcher = SkipMatcher::new(&config.repo_root, &config.root_paths, &config.skip)
+            .map_err(JsErr::invalid_arg)?;
+
+        let mut resolver_constructions = 0;
+        let resolver = build_resolver(
+            PathBuf::from(&config.repo_root),
+            &walked_files.packages,
+            &config.opaque_module_extensions,
+            config.target_env,
+            &mut resolver_constructions,
+        );
+        let resolved_walked_files = Self::resolve_all(
+            &logger,
+            walked_files,
+            &resolver,
+            config.respect_fences,
+            &mut pending_timings,
+        )?;
 
         Ok(Self {
             config,
             dirty_files: DirtyFiles::Some(vec![]),
             last_walk_result: resolved_walked_files,
+            generation: 0,
+            last_result: None,
+            pending_timings,
+            resolver,
+            dirty_resolver_roots: DirtyFiles::Some(vec![]),
+            resolver_constructions,
+            skip_matcher,
+            graph: Arc::default(),
+            dirty_graph_files: DirtyFiles::All,
+            mirrored_file_ids: Vec::new(),
+            graph_file_constructions: 0,
         })
     }
 
+    /// Gets the configuration this UnusedFinder was created with
+    pub fn config(&self) -> &UnusedFinderConfig {
+        &self.config
+    }
+
+    /// Gets the source files of the last walk, with their imports resolved
+    pub fn source_files(&self) -> impl Iterator<Item = &ResolvedSourceFile> {
+        self.last_walk_result.source_files.values()
+    }
+
+    /// Eagerly populates the resolver's caches for the root of every
+    /// discovered package, so the first resolution after startup does not
+    /// have to probe the filesystem for package.json, tsconfig.json and
+    /// node_modules entries.
+    ///
+    /// This never changes the result of a resolution, only its latency.
+    pub fn warm_caches(&self) -> Result<(), JsErr> {
+        let caches = self.resolver.inner().caches();
+        let packages = &self.last_walk_result.packages;
+        prepopulate_package_jsons(caches, packages, |_| true);
+
+        let repo_root = Path::new(&self.config.repo_root);
+        for package in packages.packages.iter() {
+            caches
+                .warm_dir(repo_root, &package.package_path)
+                .map_err(JsErr::generic_failure)?;
+        }
+        Ok(())
+    }
+
+    /// Classifies the resolved path of an import by whether it was walked, and
+    /// if not, whether that is because it was excluded by the `skip` config.
+    pub fn classify_import(&self, resolved_path: &Path) -> ResolvedImport {
+        if self
+            .last_walk_result
+            .source_files
+            .contains_key(resolved_path)
+        {
+            ResolvedImport::Walked
+        } else if self.skip_matcher.is_skipped(resolved_path) {
+            ResolvedImport::Skipped
+        } else if resolved_path.is_absolute()
+            && !self.skip_matcher.is_within_root_paths(resolved_path)
+        {
+            // imports of external packages stay relative, and are not gaps
+            ResolvedImport::OutsideRootPaths
+        } else {
+            ResolvedImport::NotWalked
+        }
+    }
+
+    /// Gets the imports of each file whose resolved paths have the given classification
+    fn get_imports_classified_as(
+        &self,
+        classification: ResolvedImport,
+    ) -> AHashMap<PathBuf, Vec<PathBuf>> {
+        self.last_walk_result
+            .source_files
+            .par_iter()
+            .filter_map(|(file_path, source_file)| {
+                let mut imports = source_file
+                    .import_export_info
+                    .iter_imported_symbols_meta()
+                    .map(|(path, _, _)| path)
+                    .filter(|path| self.classify_import(path) == classification)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if imports.is_empty() {
+                    return None;
+                }
+                imports.sort();
+                imports.dedup();
+                Some((file_path.clone(), imports))
+            })
+            .collect()
+    }
+
+    /// Gets the number of times the resolver has been constructed. Updates
+    /// and refreshes reuse the resolver, so this stays at 1.
+    pub fn resolver_constructions(&self) -> usize {
+        self.resolver_constructions
+    }
+
+    /// Gets the number of graph nodes that have been constructed, by full
+    /// rebuilds and by the patches of single-file updates
+    pub fn graph_file_constructions(&self) -> usize {
+        self.graph_file_constructions
+    }
+
     // Read and parse all files from disk have a fresh in-memory representation of the file tree
     pub fn mark_dirty<I, Item>(&mut self, file_paths: I)
     where
@@ -195,12 +552,29 @@ impl UnusedFinder {
         Item: AsRef<Path>,
     {
         let iterator = file_paths.into_iter();
-        if iterator.clone().any(|path| {
-            // If any of the files are not in the last_walk_result, mark all files as dirty
-            !self.last_walk_result.source_files.contains_key(path.as_ref())
-            // If any of the files are packagejson files, mark all files as dirty
-            || self.last_walk_result.packages.contains_path(path.as_ref())
-        }) {
+        self.generation += 1;
+        // directories containing files that require a full refresh
+        let full_refresh_roots = iterator
+            .clone()
+            .filter_map(|path| {
+                let path: &Path = path.as_ref();
+                // If any of the files are not in the last_walk_result, mark all files as dirty
+                // If any of the files are packagejson files, mark all files as dirty
+                if !self.last_walk_result.source_files.contains_key(path)
+                    || self.last_walk_result.packages.contains_path(path)
+                {
+                    Some(path.parent().unwrap_or(path).to_path_buf())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        if !full_refresh_roots.is_empty() {
+            // Only the cached resolver state next to these files can be affected
+            // (e.g. a changed package.json or tsconfig.json)
+            if let DirtyFiles::Some(ref mut roots) = self.dirty_resolver_roots {
+                roots.extend(full_refresh_roots);
+            }
             self.dirty_files = DirtyFiles::All;
             return;
         }
@@ -216,7 +590,9 @@ impl UnusedFinder {
 
     // Marks all files as dirty, so that the next call to `find_unused` will refresh the entire file tree
     pub fn mark_all_dirty(&mut self) {
+        self.generation += 1;
         self.dirty_files = DirtyFiles::All;
+        self.dirty_resolver_roots = DirtyFiles::All;
     }
 
     // Helper method used before taking a snapshot of the file tree for graph computation.
@@ -225,14 +601,24 @@ impl UnusedFinder {
         match &self.dirty_files {
             DirtyFiles::All => {
                 logger.log("Refreshing all files");
-                // perform initial walk on initialization to get an internal representation of source files
-                self.last_walk_result = Self::walk_and_resolve_all(logger, &self.config)?;
+                let walked_files =
+                    Self::walk_all(&logger, &self.config, &mut self.pending_timings)?;
+                self.refresh_resolver(&walked_files.packages);
+                self.last_walk_result = Self::resolve_all(
+                    &logger,
+                    walked_files,
+                    &self.resolver,
+                    self.config.respect_fences,
+                    &mut self.pending_timings,
+                )?;
+                self.dirty_graph_files = DirtyFiles::All;
             }
             DirtyFiles::Some(files) => {
                 if files.is_empty() {
                     return Ok(());
                 }
                 logger.log("Refreshing only the files that have been marked dirty");
+                let start = Instant::now();
                 let scanned_files = files
                     .par_iter()
                     .map(|file_path| {
@@ -240,6 +626,9 @@ impl UnusedFinder {
                             .map_err(JsErr::generic_failure)
                     })
                     .collect::<Result<Vec<_>, _>>()?;
+                // individual file refreshes parse and resolve in a single step,
+                // so they are accounted as walk time.
+                self.pending_timings.walk += start.elapsed();
 
                 for (file_path, scanned_file) in files.iter().zip(scanned_files) {
                     self.last_walk_result
@@ -248,6 +637,9 @@ impl UnusedFinder {
                         // instead of cloning the key here
                         .insert(file_path.clone(), scanned_file);
                 }
+                if let DirtyFiles::Some(ref mut graph_files) = self.dirty_graph_files {
+                    graph_files.extend(files.iter().cloned());
+                }
             }
         }
 
@@ -256,6 +648,28 @@ impl UnusedFinder {
         Ok(())
     }
 
+    // Drops cached resolver state under any roots marked dirty since the last full
+    // refresh, and updates the resolver with the packages found by the latest walk.
+    // The resolver itself is reused, so `resolver_constructions` is unchanged.
+    fn refresh_resolver(&mut self, packages: &RepoPackages) {
+        let caches = self.resolver.inner().caches();
+        match std::mem::replace(&mut self.dirty_resolver_roots, DirtyFiles::Some(vec![])) {
+            DirtyFiles::All => {
+                caches.clear();
+                prepopulate_package_jsons(caches, packages, |_| true);
+            }
+            DirtyFiles::Some(roots) => {
+                for root in roots.iter() {
+                    caches.mark_dirty_root(root);
+                }
+                prepopulate_package_jsons(caches, packages, |package_path| {
+                    roots.iter().any(|root| package_path.starts_with(root))
+                });
+            }
+        }
+        self.resolver.set_package_names(packages.iter_names());
+    }
+
     fn update_single_file(
         &self,
         file_path: &Path,
@@ -271,7 +685,14 @@ impl UnusedFinder {
             }
         };
 
-        let import_export_info = match get_file_import_export_info(file_path) {
+        let import_export_info =
+            if is_opaque_module(file_path, &self.config.opaque_module_extensions) {
+                // opaque modules are never parsed, so there is nothing to re-read
+                Ok(RawImportExportInfo::new_opaque_module())
+            } else {
+                get_file_import_export_info_or_scan(file_path)
+            };
+        let import_export_info = match import_export_info {
             Ok(import_export_info) => import_export_info,
             Err(e) => {
                 logger.log(format!(
@@ -283,16 +704,11 @@ impl UnusedFinder {
             }
         };
 
-        let resolver = resolver_for_packages(
-            PathBuf::from(self.config.repo_root.clone()),
-            &self.last_walk_result.packages,
-        );
-
         let resolved_source_file = ResolvedSourceFile {
             owning_package,
             source_file_path: file_path.to_path_buf(),
             import_export_info: import_export_info
-                .try_resolve(file_path, resolver)
+                .try_resolve(file_path, &self.resolver)
                 .into_anyhow()
                 .map_err(JsErr::generic_failure)?,
         };
@@ -300,53 +716,549 @@ impl UnusedFinder {
         Ok(resolved_source_file)
     }
 
-    /// Walks and parses all source files in the repo, returning a WalkFileResult
-    /// with
-    fn walk_and_resolve_all(
+    /// Walks and parses all source files in the repo
+    fn walk_all(
         logger: impl Logger + Sync,
         config: &UnusedFinderConfig,
-    ) -> Result<SourceFiles, JsErr> {
-        // Note: this silently ignores any errors that occur during the walk
-        let walked_files =
-            walk_src_files(&logger, &config.root_paths, &config.repo_root, &config.skip)
+        timings: &mut UnusedFinderTimings,
+    ) -> Result<WalkedFiles, JsErr> {
+        // Note: this silently ignores any errors other than parse errors that occur during the walk
+        let start = Instant::now();
+        let mut walked_files = walk_src_files(
+            &logger,
+            &config.root_paths,
+            &config.repo_root,
+            &config.skip,
+            &config.opaque_module_extensions,
+            config.package_discovery,
+        )
+        .map_err(JsErr::generic_failure)?;
+        if config.restrict_to_tsconfig_files {
+            let tsconfig_path = Path::new(&config.repo_root).join("tsconfig.json");
+            let filter = TsconfigFileFilter::from_path(&tsconfig_path)
+                .with_context(|| format!("Reading the files of {}", tsconfig_path.display()))
+                .map_err(JsErr::generic_failure)?;
+            walked_files
+                .source_files
+                .retain(|source_file| filter.is_included(&source_file.source_file_path));
+            walked_files
+                .parse_errors
+                .retain(|(file_path, _)| filter.is_included(file_path));
+        }
+        let parse_errors = std::mem::take(&mut walked_files.parse_errors);
+        if config.fail_on_parse_error && !parse_errors.is_empty() {
+            let mut errs = MultiErr::new();
+            errs.add_iter(parse_errors.into_iter().map(|(_, error)| error));
+            return Err(JsErr::generic_failure(anyhow::Error::from(errs)));
+        }
+        let degraded_files: AHashSet<&Path> = walked_files
+            .source_files
+            .iter()
+            .filter(|source_file| source_file.import_export_info.degraded)
+            .map(|source_file| source_file.source_file_path.as_path())
+            .collect();
+        // parse errors name the file that failed to parse
+        for (file_path, error) in parse_errors {
+            if degraded_files.contains(file_path.as_path()) {
+                logger.warn(format!(
+                    "Scanning the imports of unparseable source file without its symbols. {}",
+                    error
+                ));
+            } else {
+                logger.warn(format!("Skipping unparseable source file. {}", error));
+            }
+        }
+        let has_root_entries = !config.entry_files.is_empty()
+            || config.entry_packages.names.contains(ROOT_ENTRY_PACKAGE);
+        if walked_files.packages.packages.is_empty() && has_root_entries {
+            Self::add_synthetic_root_package(&logger, config, &mut walked_files)
                 .map_err(JsErr::generic_failure)?;
+        }
+        timings.walk += start.elapsed();
+        Ok(walked_files)
+    }
+
+    /// Attributes every source file of a repo without any package.json to an
+    /// implicit root package, which exports the configured `entry_files` and
+    /// the default entry files selected by `<root>`, so that plain projects
+    /// can be analyzed without a package.json.
+    fn add_synthetic_root_package(
+        logger: impl Logger,
+        config: &UnusedFinderConfig,
+        walked_files: &mut WalkedFiles,
+    ) -> Result<()> {
+        let repo_root = Path::new(&config.repo_root);
+        let mut entry_files: Vec<PathBuf> = config.entry_files.iter().map(PathBuf::from).collect();
+        if config.entry_packages.names.contains(ROOT_ENTRY_PACKAGE) {
+            let walked_paths: AHashSet<&Path> = walked_files
+                .source_files
+                .iter()
+                .map(|source_file| source_file.source_file_path.as_path())
+                .collect();
+            for root_path in config.root_paths.iter() {
+                let abs_root_path = abspath::join_abspath(repo_root, root_path)?;
+                let root_path = abs_root_path
+                    .strip_prefix(repo_root)
+                    .unwrap_or(&abs_root_path);
+                entry_files.extend(
+                    DEFAULT_ROOT_ENTRY_FILES
+                        .iter()
+                        .map(|entry_file| root_path.join(entry_file))
+                        .filter(|entry_file| {
+                            walked_paths.contains(repo_root.join(entry_file).as_path())
+                        }),
+                );
+            }
+        }
 
-        let resolver =
-            resolver_for_packages(PathBuf::from(&config.repo_root), &walked_files.packages);
+        let package = WalkedPackage::synthetic_root(repo_root, &entry_files)?;
+        let package_name = package
+            .package_json
+            .name
+            .clone()
+            .expect("synthetic packages are always named");
+        logger.warn(format!(
+            "No package.json found under the root paths. Treating the repo as a single synthetic package {package_name:?} with entry files: {}",
+            entry_files
+                .iter()
+                .map(|entry_file| entry_file.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+        for source_file in walked_files.source_files.iter_mut() {
+            source_file.owning_package = Some(package_name.clone());
+        }
+        walked_files.packages.add(package)
+    }
+
+    /// Resolves the imports of all walked source files with the given resolver
+    fn resolve_all(
+        logger: impl Logger + Sync,
+        walked_files: WalkedFiles,
+        resolver: &RepoResolver,
+        respect_fences: bool,
+        timings: &mut UnusedFinderTimings,
+    ) -> Result<SourceFiles, JsErr> {
+        let fences = if respect_fences {
+            Some(load_fences(&walked_files.fence_files).map_err(JsErr::invalid_arg)?)
+        } else {
+            None
+        };
 
         // TODO: gracefully handle errors during resolution
         logger.log(format!(
             "Resolving {} files...",
             walked_files.source_files.len()
         ));
-        let resolved =
+        let start = Instant::now();
+        let mut resolved =
             SourceFiles::try_resolve(walked_files, resolver).map_err(JsErr::generic_failure)?;
+        resolved.fences = fences;
+        timings.resolve += start.elapsed();
         logger.log("Done resolving files");
         Ok(resolved)
     }
 
     // Gets a report by performing a graph traversal on the current in-memory state of the repo,
     // from the last time the file tree was scanned.
-    pub fn find_unused(&mut self, logger: impl Logger + Sync) -> Result<UnusedFinderResult, JsErr> {
+    //
+    // If no files have been marked dirty since the last call, the previous result is reused.
+    pub fn find_unused(
+        &mut self,
+        logger: impl Logger + Sync,
+    ) -> Result<Arc<UnusedFinderResult>, JsErr> {
+        self.find_unused_with_force(logger, false)
+    }
+
+    // Same as `find_unused`, but when `force` is set, the graph traversal is always
+    // re-run, even if nothing has been marked dirty since the last call.
+    pub fn find_unused_with_force(
+        &mut self,
+        logger: impl Logger + Sync,
+        force: bool,
+    ) -> Result<Arc<UnusedFinderResult>, JsErr> {
+        if !force {
+            if let Some((generation, last_result)) = &self.last_result {
+                if *generation == self.generation {
+                    logger.log("No files marked dirty since the last run, reusing previous result");
+                    return Ok(last_result.clone());
+                }
+            }
+        }
+
+        // the previous result is stale. Dropping it lets the graph it shares
+        // be patched in place, unless the caller still holds on to it.
+        self.last_result = None;
+        let result = Arc::new(self.compute_unused(&logger)?);
+        self.last_result = Some((self.generation, result.clone()));
+        Ok(result)
+    }
+
+    /// Checks whether each of the given exported symbols is used, as `(file path, symbol name)`
+    /// pairs. Relative paths are resolved against the repo root.
+    ///
+    /// Statuses are computed from the memoized result of `find_unused`, so the graph is only
+    /// traversed again if files were marked dirty since the last traversal.
+    pub fn check_symbols(
+        &mut self,
+        logger: impl Logger + Sync,
+        entries: Vec<(impl AsRef<Path>, impl AsRef<str>)>,
+    ) -> Result<Vec<SymbolStatus>, JsErr> {
+        let result = self.find_unused(logger)?;
+        entries
+            .into_iter()
+            .map(|(file_path, symbol_name)| {
+                let file_path = abspath::join_abspath(&self.config.repo_root, file_path)
+                    .map_err(JsErr::invalid_arg)?;
+                Ok(result.symbol_status(&file_path, symbol_name.as_ref()))
+            })
+            .collect()
+    }
+
+    /// Finds every import of an exported symbol, including imports through barrel files.
+    /// A relative `file_path` is resolved against the repo root, and `symbol_name` is the
+    /// canonical string form of the symbol (see [ExportedSymbol]).
+    ///
+    /// See `UnusedFinderResult::find_import_sites`.
+    pub fn find_import_sites(
+        &mut self,
+        logger: impl Logger + Sync,
+        file_path: impl AsRef<Path>,
+        symbol_name: &str,
+    ) -> Result<Vec<ImportSite>, JsErr> {
+        let result = self.find_unused(logger)?;
+        let file_path =
+            abspath::join_abspath(&self.config.repo_root, file_path).map_err(JsErr::invalid_arg)?;
+        Ok(result.find_import_sites(&file_path, &ExportedSymbol::from(symbol_name)))
+    }
+
+    /// Finds where a symbol exported from a file is defined, and the barrel files
+    /// re-exporting it on the way. A relative `file_path` is resolved against the
+    /// repo root, and `symbol_name` is the canonical string form of the symbol.
+    ///
+    /// See `UnusedFinderResult::resolve_symbol_origin`.
+    pub fn resolve_symbol_origin(
+        &mut self,
+        logger: impl Logger + Sync,
+        file_path: impl AsRef<Path>,
+        symbol_name: &str,
+    ) -> Result<Option<SymbolOrigin>, JsErr> {
+        let result = self.find_unused(logger)?;
+        let file_path =
+            abspath::join_abspath(&self.config.repo_root, file_path).map_err(JsErr::invalid_arg)?;
+        Ok(result.resolve_symbol_origin(&file_path, &ExportedSymbol::from(symbol_name)))
+    }
+
+    /// Dumps everything known about how a single file was analyzed: how the walk
+    /// classified it, its raw and resolved imports, its exports and their tags, the
+    /// imports that reached it during the traversal, and why it is an entrypoint.
+    /// A relative `file_path` is resolved against the repo root.
+    ///
+    /// Intended for debugging unexpected verdicts, so the file is parsed again
+    /// and its parse errors are included in the dump rather than returned.
+    pub fn dump_file_analysis(
+        &mut self,
+        logger: impl Logger + Sync,
+        file_path: impl AsRef<Path>,
+    ) -> Result<FileAnalysisDump, JsErr> {
+        let result = self.find_unused(&logger)?;
+        let file_path =
+            abspath::join_abspath(&self.config.repo_root, file_path).map_err(JsErr::invalid_arg)?;
+        let repo_root = Path::new(&self.config.repo_root);
+        let base_dir = self.config.path_display.base_dir(repo_root);
+        let display = |path: &Path| display_path(path, base_dir.as_deref());
+
+        let source_file = self.last_walk_result.source_files.get(&file_path);
+        let graph_file = result.graph.get_file_by_path(&file_path);
+        let opaque_module = is_opaque_module(&file_path, &self.config.opaque_module_extensions);
+        let skip_near_misses = SkipMatcher::case_insensitive_matches(
+            &self.config.repo_root,
+            &self.config.root_paths,
+            &self.config.skip,
+            &file_path,
+        )
+        .map_err(JsErr::generic_failure)?;
+        let walk = WalkedFileDump {
+            in_graph: graph_file.is_some(),
+            owning_package: source_file.and_then(|file| file.owning_package.clone()),
+            test: source_file.is_some_and(|file| {
+                self.is_test_file(&file_path, file, &self.get_package_test_dirs())
+            }),
+            ignored: self.is_file_ignored(&file_path),
+            generated: self
+                .config
+                .path_mirrors
+                .is_mirror_file(repo_root, &file_path),
+            opaque_module,
+            skipped: self.skip_matcher.is_skipped(&file_path),
+            skip_near_misses,
+        };
+
+        let (imports, parse_error) = if opaque_module || !file_path.is_file() {
+            (Vec::new(), None)
+        } else {
+            match get_file_import_export_info_or_scan(&file_path) {
+                Ok(info) => {
+                    let from_file = FileName::Real(file_path.clone());
+                    let resolve = |specifier: &str| -> Result<PathBuf> {
+                        match self.resolver.resolve(&from_file, specifier)?.filename {
+                            FileName::Real(resolved_path) => Ok(resolved_path),
+                            other => Err(anyhow!("resolved to a non-file path?: {:?}", other)),
+                        }
+                    };
+                    (
+                        dump_imports(&info, resolve, display),
+                        info.parse_error.clone(),
+                    )
+                }
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            }
+        };
+
+        let (exports, incoming_edges, tags): (_, _, Vec<UsedTagEnum>) = match graph_file {
+            Some(graph_file) => (
+                dump_exports(&result.graph, graph_file, display),
+                dump_incoming_edges(&result.graph, &file_path, display),
+                graph_file.file_tags.into(),
+            ),
+            None => (Vec::new(), Vec::new(), Vec::new()),
+        };
+
+        let mut entrypoint_reasons = Vec::new();
+        if let Some(source_file) = source_file {
+            if self.is_entry_package_export(&logger, &file_path, source_file) {
+                let package = source_file.owning_package.clone().unwrap_or_default();
+                if self.config.report_unused_entry_exports
+                    && !self.is_explicit_package_export(&logger, &file_path)
+                {
+                    entrypoint_reasons.push(EntrypointReason::ImplicitPackageExport { package });
+                } else {
+                    entrypoint_reasons.push(EntrypointReason::PackageExport { package });
+                }
+            }
+            if self.config.barrel_exports_count_as_used {
+                if let Some(symbols) =
+                    self.barrel_reexported_symbols(&logger, &file_path, source_file)
+                {
+                    entrypoint_reasons.push(EntrypointReason::package_barrel(&symbols));
+                }
+            }
+            let keep_alive_symbols = self.get_file_keep_alive_symbols(source_file);
+            if !keep_alive_symbols.is_empty() {
+                entrypoint_reasons.push(EntrypointReason::keep_alive(&keep_alive_symbols));
+            }
+            let allowed_unused_symbols = self.get_file_ignored_symbols(source_file);
+            if !allowed_unused_symbols.is_empty() {
+                entrypoint_reasons.push(EntrypointReason::allowed_unused(&allowed_unused_symbols));
+            }
+        }
+        if walk.ignored {
+            entrypoint_reasons.push(EntrypointReason::IgnoreFile);
+        }
+        if walk.test {
+            entrypoint_reasons.push(EntrypointReason::TestFile);
+        }
+
+        Ok(FileAnalysisDump {
+            file_path: display(&file_path),
+            walk,
+            parse_error,
+            imports,
+            exports,
+            incoming_edges,
+            tags,
+            entrypoint_reasons,
+        })
+    }
+
+    /// Lists the files that the traversals start from because an entry package
+    /// exports them, grouped by package, along with the entry packages that do
+    /// not export any source file.
+    ///
+    /// Only the walk is brought up to date, so this does not run the traversals.
+    pub fn explain_entrypoints(
+        &mut self,
+        logger: impl Logger + Sync,
+    ) -> Result<EntrypointsDump, JsErr> {
+        self.update_dirty_files(&logger)?;
+        let repo_root = Path::new(&self.config.repo_root);
+        let base_dir = self.config.path_display.base_dir(repo_root);
+
+        let mut entrypoints_by_package: AHashMap<&str, Vec<String>> = AHashMap::default();
+        for file_path in self.get_entrypoints(&logger) {
+            let owning_package = self
+                .last_walk_result
+                .source_files
+                .get(file_path)
+                .and_then(|source_file| source_file.owning_package.as_deref());
+            // entrypoints always have an owning package
+            if let Some(owning_package) = owning_package {
+                entrypoints_by_package
+                    .entry(owning_package)
+                    .or_default()
+                    .push(display_path(file_path, base_dir.as_deref()));
+            }
+        }
+
+        let mut packages: Vec<EntryPackageDump> = entrypoints_by_package
+            .into_iter()
+            .map(|(package_name, mut entrypoints)| {
+                entrypoints.sort();
+                let match_rule = self
+                    .last_walk_result
+                    .packages
+                    .get_by_name(package_name)
+                    .filter(|package| !package.synthetic)
+                    .and_then(|package| self.entry_package_match_rule(package));
+                EntryPackageDump {
+                    package: package_name.to_string(),
+                    match_rule,
+                    entrypoints,
+                }
+            })
+            .collect();
+        packages.sort_by(|a, b| a.package.cmp(&b.package));
+
+        Ok(EntrypointsDump {
+            packages,
+            packages_without_entrypoints: self.get_entry_packages_without_entrypoints(&logger),
+        })
+    }
+
+    // Performs the graph traversal over the current in-memory state of the repo,
+    // without consulting the memoized result.
+    fn compute_unused(&mut self, logger: impl Logger + Sync) -> Result<UnusedFinderResult, JsErr> {
         // Scan the file-system for changed files
         self.update_dirty_files(&logger)?;
 
-        // Create a new graph with all entries marked as "unused".
-        let mut graph = Graph::from_source_files(self.last_walk_result.source_files.values());
+        // Entry packages without entrypoints keep nothing alive, which shows up
+        // as a flood of unused files with no obvious cause
+        let packages_without_entrypoints = self.get_entry_packages_without_entrypoints(&logger);
+        if self.config.strict_entry_packages && !packages_without_entrypoints.is_empty() {
+            let mut errs = MultiErr::new();
+            errs.add_iter(packages_without_entrypoints.into_iter());
+            return Err(JsErr::generic_failure(anyhow::Error::from(errs)));
+        }
+        for package in packages_without_entrypoints.iter() {
+            logger.warn(package.to_string());
+        }
+        let mut timings = std::mem::take(&mut self.pending_timings);
+
+        // Bring the graph up to date, with all entries marked as "unused".
+        let start = Instant::now();
+        // the graph is only copied if a previous result still holds on to it
+        let mut shared_graph = std::mem::take(&mut self.graph);
+        let graph = Arc::make_mut(&mut shared_graph);
+        let unmatched_mirror_imports = self.update_graph(graph);
+        // if the traversal fails, the graph is not put back, so rebuild it next time
+        self.dirty_graph_files = DirtyFiles::All;
+        for (file_path, imports) in unmatched_mirror_imports.iter() {
+            logger.warn(format!(
+                "{} imports generated files without a source file in pathMirrors: {:?}",
+                file_path.display(),
+                imports
+            ));
+        }
+        timings.graph = start.elapsed();
+
+        // expand the `export *` of each file, to find the names that are
+        // ambiguous between the files it re-exports
+        let start = Instant::now();
+        let ambiguous_reexports = find_ambiguous_reexports(&self.last_walk_result.source_files);
+        timings.export_star_expansion = start.elapsed();
 
         // print the entry packages config
         debug_logf!(logger, "Entry packages: {:#?}", self.config.entry_packages);
 
         // Get the walk roots and perform the graph traversal
         let entrypoints = self.get_entrypoints(&logger);
+        let symbol_collisions = find_symbol_collisions(&graph, &entrypoints);
+        for collision in symbol_collisions.iter() {
+            debug_logf!(
+                logger,
+                "{} exports {} from {} files, resolving to {}",
+                collision.entry_file.display(),
+                collision.name,
+                collision.definitions.len(),
+                collision.winner().file_path.display()
+            );
+        }
+        for ambiguous_reexport in ambiguous_reexports.iter() {
+            logger.warn(format!(
+                "{} does not export {}, which is ambiguous between its `export *` of {:?}",
+                ambiguous_reexport.file_path.display(),
+                ambiguous_reexport.name,
+                ambiguous_reexport.sources,
+            ));
+        }
+        let barrel_bypass_imports = if self.config.report_barrel_bypass_imports {
+            let package_barrels = self.get_package_barrels(&logger);
+            find_barrel_bypass_imports(&graph, &package_barrels)
+        } else {
+            vec![]
+        };
+        for bypass_import in barrel_bypass_imports.iter() {
+            debug_logf!(
+                logger,
+                "{} imports {} from {}, which {} exports from {}",
+                bypass_import.importer.display(),
+                bypass_import.symbol,
+                bypass_import.imported_file.display(),
+                bypass_import.package_name,
+                bypass_import.barrel_file.display()
+            );
+        }
+        let mut entry_symbols = if self.config.barrel_exports_count_as_used {
+            self.get_barrel_reexported_symbols(&logger)
+        } else {
+            vec![]
+        };
+        // files only exported because their package has no "exports" field are
+        // visited without marking their symbols, so their unused exports are reported
+        let entrypoints = if self.config.report_unused_entry_exports {
+            let (entrypoints, implicit_entrypoints): (Vec<&Path>, Vec<&Path>) = entrypoints
+                .into_iter()
+                .partition(|file_path| self.is_explicit_package_export(&logger, file_path));
+            logger.log(format!(
+                "Visiting {} implicitly exported entrypoints without marking their symbols",
+                implicit_entrypoints.len(),
+            ));
+            entry_symbols.extend(
+                implicit_entrypoints
+                    .into_iter()
+                    .map(|file_path| (file_path, vec![ExportedSymbol::ExecutionOnly])),
+            );
+            entrypoints
+        } else {
+            entrypoints
+        };
+        let barrel_symbol_count = Self::count_symbols(&entry_symbols);
+        let keep_alive_symbols = self.get_keep_alive_symbols();
+        let keep_alive_symbol_count = Self::count_symbols(&keep_alive_symbols);
+        entry_symbols.extend(keep_alive_symbols);
         logger.log(format!(
-            "Starting {} graph traversal with {} entrypoints",
+            "Starting {} graph traversal with {} entrypoints, {} barrel symbols and {} keep-alive symbols",
             UsedTag::FROM_ENTRY,
-            entrypoints.len()
+            entrypoints.len(),
+            barrel_symbol_count,
+            keep_alive_symbol_count,
         ));
+        let mut traversal_roots: Vec<PathBuf> = entrypoints
+            .iter()
+            .chain(entry_symbols.iter().map(|(file_path, _)| file_path))
+            .map(|file_path| file_path.to_path_buf())
+            .collect();
+        let start = Instant::now();
         graph
-            .traverse_bfs(&logger, entrypoints, vec![], UsedTag::FROM_ENTRY)
+            .traverse_bfs(
+                &logger,
+                entrypoints,
+                entry_symbols,
+                UsedTag::FROM_ENTRY,
+                self.config.effect_imports_mark_symbols,
+            )
             .map_err(JsErr::generic_failure)?;
+        timings.entry_traversal = start.elapsed();
 
         let ignored_entrypoints = self.get_ignored_files();
         let ignored_symbols = self.get_ignored_symbols();
@@ -356,24 +1268,54 @@ impl UnusedFinder {
             ignored_entrypoints.len(),
             Self::count_symbols(&ignored_symbols)
         ));
+        traversal_roots.extend(
+            ignored_entrypoints
+                .iter()
+                .chain(ignored_symbols.iter().map(|(file_path, _)| file_path))
+                .map(|file_path| file_path.to_path_buf()),
+        );
+        let start = Instant::now();
         graph
             .traverse_bfs(
                 &logger,
                 ignored_entrypoints,
                 ignored_symbols,
                 UsedTag::FROM_IGNORED,
+                self.config.effect_imports_mark_symbols,
             )
             .map_err(JsErr::generic_failure)?;
+        timings.ignored_traversal = start.elapsed();
 
         let test_entrypoints = self.get_test_files();
+        if self.config.verify_test_files {
+            for test_file in self.get_test_files_without_tests(&test_entrypoints) {
+                logger.warn(format!(
+                    "{} is tagged as a test, but never calls describe, it, test or expect. Check that the testFiles globs do not match non-test files",
+                    test_file.display()
+                ));
+            }
+        }
         logger.log(format!(
             "Starting {} graph traversal with {} entrypoints",
             UsedTag::FROM_TEST,
             test_entrypoints.len(),
         ));
+        traversal_roots.extend(
+            test_entrypoints
+                .iter()
+                .map(|file_path| file_path.to_path_buf()),
+        );
+        let start = Instant::now();
         graph
-            .traverse_bfs(&logger, test_entrypoints, vec![], UsedTag::FROM_TEST)
+            .traverse_bfs(
+                &logger,
+                test_entrypoints,
+                vec![],
+                UsedTag::FROM_TEST,
+                self.config.effect_imports_mark_symbols,
+            )
             .map_err(JsErr::generic_failure)?;
+        timings.test_traversal = start.elapsed();
 
         for file in graph.files.iter() {
             logger.log(format!(
@@ -427,7 +1369,174 @@ impl UnusedFinder {
             }
         }
 
-        Ok(UnusedFinderResult::new(graph))
+        let skipped_imports = self.get_imports_classified_as(ResolvedImport::Skipped);
+        for (file_path, imports) in skipped_imports.iter() {
+            debug_logf!(
+                logger,
+                "{} imports skipped paths, which are not analyzed: {:?}",
+                file_path.display(),
+                imports
+            );
+        }
+        let resolution_gaps = self.get_imports_classified_as(ResolvedImport::OutsideRootPaths);
+        for (file_path, imports) in resolution_gaps.iter() {
+            debug_logf!(
+                logger,
+                "{} imports files outside of the root paths, which are not analyzed: {:?}",
+                file_path.display(),
+                imports
+            );
+        }
+
+        // unused names declared more than once are reported once per declaration,
+        // with suffixed ids that need explaining
+        for file in graph.files.iter() {
+            for (symbol, metadata) in file.import_export_info.exported_ids.iter() {
+                if metadata.declarations.is_empty()
+                    || file.symbol_tags.get(symbol).is_some_and(is_used)
+                {
+                    continue;
+                }
+                logger.log(format!(
+                    "{} declares {} {} times, reported as {}",
+                    file.file_path.display(),
+                    symbol,
+                    metadata.declarations.len(),
+                    declaration_ids(symbol, &metadata.declarations).join(", "),
+                ));
+            }
+        }
+
+        logger.log(format!("Timings:\n{timings}"));
+
+        self.graph = shared_graph.clone();
+        self.dirty_graph_files = DirtyFiles::Some(vec![]);
+
+        Ok(UnusedFinderResult {
+            symbol_collisions,
+            ambiguous_reexports,
+            barrel_bypass_imports,
+            unmatched_mirror_imports,
+            resolution_gaps,
+            suggest_same_name_exports: self.config.suggest_same_name_exports,
+            report_ambiguous_symbol_names: self.config.report_ambiguous_symbol_names,
+            count_import_kinds: self.config.count_import_kinds,
+            report_unused_imports: self.config.report_unused_imports,
+            traversal_roots,
+            synthetic_root_package: self
+                .last_walk_result
+                .packages
+                .get_synthetic_root()
+                .and_then(|package| package.package_json.name.clone()),
+            ..UnusedFinderResult::new(
+                shared_graph,
+                timings,
+                skipped_imports,
+                self.config.god_module_threshold,
+                self.config.opaque_module_extensions.clone(),
+                self.config.path_display,
+                PathBuf::from(&self.config.repo_root),
+            )
+        })
+    }
+
+    /// Brings the graph up to date with the current source files, and clears
+    /// the tags of the last traversal.
+    ///
+    /// After a full refresh, the graph is rebuilt. Otherwise, only the nodes
+    /// of files marked dirty since the last traversal are replaced.
+    ///
+    /// Returns the imports of each file that resolved into a mirror, but have
+    /// no source file in the graph.
+    fn update_graph(&mut self, graph: &mut Graph) -> AHashMap<PathBuf, Vec<PathBuf>> {
+        let repo_root = Path::new(&self.config.repo_root);
+        match std::mem::replace(&mut self.dirty_graph_files, DirtyFiles::Some(vec![])) {
+            DirtyFiles::All => {
+                // generated files within the path mirrors are not analyzed themselves.
+                // Their imports are credited to the source files they mirror instead.
+                *graph =
+                    Graph::from_source_files(self.last_walk_result.source_files.values().filter(
+                        |source_file| {
+                            !self
+                                .config
+                                .path_mirrors
+                                .is_mirror_file(repo_root, &source_file.source_file_path)
+                        },
+                    ));
+                self.graph_file_constructions += graph.files.len();
+            }
+            DirtyFiles::Some(dirty_files) => {
+                // files rewritten by the path mirrors are restored as well, so
+                // their mirrored imports are not added twice
+                let mirrored_files = self
+                    .mirrored_file_ids
+                    .iter()
+                    .map(|file_id| graph.files[*file_id].file_path.clone())
+                    .collect::<Vec<_>>();
+                self.graph_file_constructions += graph.update_files(
+                    dirty_files
+                        .iter()
+                        .chain(mirrored_files.iter())
+                        .filter_map(|file_path| self.last_walk_result.source_files.get(file_path)),
+                );
+                graph.clear_tags();
+            }
+        }
+
+        let (unmatched_mirror_imports, mirrored_file_ids) = self.add_mirrored_imports(graph);
+        self.mirrored_file_ids = mirrored_file_ids;
+        unmatched_mirror_imports
+    }
+
+    /// Records each import of a generated file within the `path_mirrors` as an
+    /// import of the source file it mirrors.
+    ///
+    /// Returns the imports of each file that resolved into a mirror, but have
+    /// no source file in the graph, and the ids of the files whose imports
+    /// were rewritten.
+    fn add_mirrored_imports(
+        &self,
+        graph: &mut Graph,
+    ) -> (AHashMap<PathBuf, Vec<PathBuf>>, Vec<usize>) {
+        let mut unmatched_imports: AHashMap<PathBuf, Vec<PathBuf>> = AHashMap::default();
+        let mut mirrored_file_ids: Vec<usize> = Vec::new();
+        if self.config.path_mirrors.is_empty() {
+            return (unmatched_imports, mirrored_file_ids);
+        }
+
+        let repo_root = Path::new(&self.config.repo_root);
+        let Graph { path_to_id, files } = graph;
+        for (file_id, file) in files.iter_mut().enumerate() {
+            let mut imported_paths = file
+                .import_export_info
+                .iter_imported_symbols_meta()
+                .map(|(path, _, _)| path.clone())
+                .collect::<Vec<_>>();
+            imported_paths.sort();
+            imported_paths.dedup();
+
+            for imported_path in imported_paths {
+                match self.config.path_mirrors.find_source_file(
+                    repo_root,
+                    &imported_path,
+                    |candidate| path_to_id.contains_key(candidate),
+                ) {
+                    Some(Ok(source_path)) => {
+                        file.import_export_info
+                            .add_mirrored_imports(&imported_path, &source_path);
+                        if mirrored_file_ids.last() != Some(&file_id) {
+                            mirrored_file_ids.push(file_id);
+                        }
+                    }
+                    Some(Err(_)) => unmatched_imports
+                        .entry(file.file_path.clone())
+                        .or_default()
+                        .push(imported_path),
+                    None => {}
+                }
+            }
+        }
+        (unmatched_imports, mirrored_file_ids)
     }
 
     fn count_symbols<T, U>(symbols: &[(T, Vec<U>)]) -> usize {
@@ -493,24 +1602,250 @@ impl UnusedFinder {
             .strip_prefix(&self.config.repo_root)
             .expect("absolue paths of packages within the repo should be relative");
 
-        // only "entry packages" may export scripts
-        if !self
-            .config
-            .entry_packages
-            .matches(relative_package_path, owning_package_name)
+        // only "entry packages" may export scripts. A synthetic root package
+        // only exports the configured entry files, so it is always an entry package
+        if !owning_package.synthetic
+            && !self
+                .config
+                .entry_packages
+                .matches(relative_package_path, owning_package_name)
         {
             return false;
         }
 
         // check if the owning package exports the file. If so, include this file as a package root.
-        owning_package
+        if !owning_package
             .is_abspath_exported(file_path)
             .unwrap_or(false)
+        {
+            return false;
+        }
+
+        // fences can narrow the public surface of a package to the modules they export
+        match &self.last_walk_result.fences {
+            Some(fences) => fences.is_exported_path(file_path),
+            None => true,
+        }
     }
 
-    fn get_ignored_files(&self) -> Vec<&Path> {
-        // TODO: this is n^2, which is bad! Could build a treemap of ignore files?
-        self.last_walk_result
+    /// Gets the `entry_packages` rule that selects a package, if any
+    fn entry_package_match_rule(&self, package: &WalkedPackage) -> Option<String> {
+        let package_name = package.package_json.name.as_deref()?;
+        let relative_package_path = package
+            .package_path
+            .strip_prefix(&self.config.repo_root)
+            .ok()?;
+        self.config
+            .entry_packages
+            .matching_rule(relative_package_path, package_name)
+    }
+
+    /// Gets the packages selected by `entry_packages` that do not explicitly
+    /// export any of the walked source files, sorted by name
+    fn get_entry_packages_without_entrypoints(
+        &self,
+        logger: impl Logger,
+    ) -> Vec<EntryPackageWithoutEntrypoints> {
+        let mut exporting_packages: AHashSet<&str> = AHashSet::default();
+        for (file_path, source_file) in self.last_walk_result.source_files.iter() {
+            let package_name = match source_file.owning_package.as_deref() {
+                Some(package_name) => package_name,
+                None => continue,
+            };
+            if !exporting_packages.contains(package_name)
+                && self.is_explicit_package_export(&logger, file_path)
+            {
+                exporting_packages.insert(package_name);
+            }
+        }
+
+        let repo_root = Path::new(&self.config.repo_root);
+        let base_dir = self.config.path_display.base_dir(repo_root);
+        let mut packages: Vec<EntryPackageWithoutEntrypoints> = self
+            .last_walk_result
+            .packages
+            .packages
+            .iter()
+            // the synthetic root package exports the configured entry files
+            .filter(|package| !package.synthetic)
+            .filter_map(|package| {
+                let package_name = package.package_json.name.as_deref()?;
+                if exporting_packages.contains(package_name) {
+                    return None;
+                }
+                Some(EntryPackageWithoutEntrypoints {
+                    package: package_name.to_string(),
+                    package_json_path: display_path(&package.package_path, base_dir.as_deref()),
+                    match_rule: self.entry_package_match_rule(package)?,
+                    entrypoint_fields: package
+                        .entrypoint_fields()
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect(),
+                })
+            })
+            .collect();
+        packages.sort_by(|a, b| a.package.cmp(&b.package));
+        packages
+    }
+
+    /// Helper that checks if an entrypoint is explicitly exported by its package,
+    /// rather than exported because its package has no "exports" field
+    fn is_explicit_package_export(&self, logger: impl Logger, file_path: &Path) -> bool {
+        let owning_package = self
+            .last_walk_result
+            .source_files
+            .get(file_path)
+            .and_then(|source_file| source_file.owning_package.as_ref())
+            .and_then(|package_name| self.last_walk_result.packages.get_by_name(package_name));
+        match owning_package.map(|package| package.is_abspath_explicitly_exported(file_path)) {
+            Some(Ok(explicitly_exported)) => explicitly_exported,
+            Some(Err(e)) => {
+                logger.log(format!(
+                    "Failed to check if {} is explicitly exported: {:#}",
+                    file_path.display(),
+                    e
+                ));
+                true
+            }
+            // entrypoints always have an owning package
+            None => true,
+        }
+    }
+
+    /// Gets the public barrel files of each package, keyed by package name
+    fn get_package_barrels(&self, logger: impl Logger) -> AHashMap<String, Vec<PathBuf>> {
+        let mut package_barrels: AHashMap<String, Vec<PathBuf>> = AHashMap::default();
+        for (file_path, source_file) in self.last_walk_result.source_files.iter() {
+            let package_name = match source_file.owning_package {
+                Some(ref package_name) => package_name,
+                None => continue,
+            };
+            let package = match self.last_walk_result.packages.get_by_name(package_name) {
+                Some(package) => package,
+                None => continue,
+            };
+            match package.is_abspath_barrel(file_path) {
+                Ok(true) => package_barrels
+                    .entry(package_name.clone())
+                    .or_default()
+                    .push(file_path.clone()),
+                Ok(false) => {}
+                Err(e) => logger.log(format!(
+                    "Failed to check if {} is a package barrel: {:#}",
+                    file_path.display(),
+                    e
+                )),
+            }
+        }
+        for barrels in package_barrels.values_mut() {
+            barrels.sort();
+        }
+        package_barrels
+    }
+
+    /// helper to get the symbols re-exported from the public barrel of each package.
+    fn get_barrel_reexported_symbols(
+        &self,
+        logger: impl Logger + Sync,
+    ) -> Vec<(&Path, Vec<ExportedSymbol>)> {
+        self.last_walk_result
+            .source_files
+            .par_iter()
+            .filter_map(
+                |(file_path, source_file)| -> Option<(&Path, Vec<ExportedSymbol>)> {
+                    let reexported_symbols =
+                        self.barrel_reexported_symbols(&logger, file_path, source_file)?;
+                    Some((file_path, reexported_symbols))
+                },
+            )
+            .collect()
+    }
+
+    /// Gets the symbols re-exported from a file, if it is the public barrel of
+    /// its package and re-exports any symbols
+    fn barrel_reexported_symbols(
+        &self,
+        logger: impl Logger,
+        file_path: &Path,
+        source_file: &ResolvedSourceFile,
+    ) -> Option<Vec<ExportedSymbol>> {
+        let owning_package = self
+            .last_walk_result
+            .packages
+            .get_by_name(source_file.owning_package.as_ref()?)?;
+        match owning_package.is_abspath_barrel(file_path) {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(e) => {
+                logger.log(format!(
+                    "Failed to check if {} is a package barrel: {:#}",
+                    file_path.display(),
+                    e
+                ));
+                return None;
+            }
+        }
+
+        let reexported_symbols = source_file
+            .import_export_info
+            .export_from_symbols
+            .values()
+            .flat_map(|symbols| symbols.keys())
+            .map(|symbol| {
+                symbol
+                    .renamed_to
+                    .as_ref()
+                    .unwrap_or(&symbol.imported)
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+        if reexported_symbols.is_empty() {
+            None
+        } else {
+            Some(reexported_symbols)
+        }
+    }
+
+    /// Gets the exported classes annotated with one of the `keep_alive_decorators`,
+    /// which are wired up at runtime rather than through imports.
+    fn get_keep_alive_symbols(&self) -> Vec<(&Path, Vec<ExportedSymbol>)> {
+        if self.config.keep_alive_decorators.is_empty() {
+            return vec![];
+        }
+
+        self.last_walk_result
+            .source_files
+            .par_iter()
+            .filter_map(|(path_buf, file)| -> Option<(&Path, Vec<ExportedSymbol>)> {
+                let keep_alive_symbols = self.get_file_keep_alive_symbols(file);
+                if keep_alive_symbols.is_empty() {
+                    None
+                } else {
+                    Some((path_buf, keep_alive_symbols))
+                }
+            })
+            .collect()
+    }
+
+    /// Gets the exported symbols of a file annotated with one of the `keep_alive_decorators`
+    fn get_file_keep_alive_symbols(&self, file: &ResolvedSourceFile) -> Vec<ExportedSymbol> {
+        file.import_export_info
+            .exported_ids
+            .iter()
+            .filter(|(_, metadata)| {
+                metadata
+                    .decorators
+                    .iter()
+                    .any(|decorator| self.config.keep_alive_decorators.contains(decorator))
+            })
+            .map(|(symbol, _)| symbol.clone())
+            .collect()
+    }
+
+    fn get_ignored_files(&self) -> Vec<&Path> {
+        // TODO: this is n^2, which is bad! Could build a treemap of ignore files?
+        self.last_walk_result
             .source_files
             .par_iter()
             .filter_map(|(file_path, _)| -> Option<&Path> {
@@ -528,7 +1863,7 @@ impl UnusedFinder {
             .source_files
             .par_iter()
             .filter_map(|(path_buf, file)| -> Option<(&Path, Vec<ExportedSymbol>)> {
-                let ignored_symbols = Self::get_file_ignored_symbols(file);
+                let ignored_symbols = self.get_file_ignored_symbols(file);
                 if ignored_symbols.is_empty() {
                     None
                 } else {
@@ -539,18 +1874,94 @@ impl UnusedFinder {
     }
 
     fn get_test_files(&self) -> Vec<&Path> {
+        let package_test_dirs = self.get_package_test_dirs();
         self.last_walk_result
             .source_files
             .par_iter()
-            .filter_map(|(path, _)| -> Option<&Path> {
-                for test_glob in &self.config.test_files {
-                    let relative = path.strip_prefix(&self.config.repo_root).unwrap_or(path);
-                    if test_glob.matches_path(relative) {
-                        return Some(path);
-                    }
+            .filter_map(|(path, source_file)| -> Option<&Path> {
+                if self.is_test_file(path, source_file, &package_test_dirs) {
+                    Some(path)
+                } else {
+                    None
                 }
+            })
+            .collect()
+    }
 
-                None
+    /// Checks if a file matches the `test_files` globs, or is within one of
+    /// the test directories of the package that owns it
+    fn is_test_file(
+        &self,
+        path: &Path,
+        source_file: &ResolvedSourceFile,
+        package_test_dirs: &AHashMap<&str, Vec<PathBuf>>,
+    ) -> bool {
+        let relative = path.strip_prefix(&self.config.repo_root).unwrap_or(path);
+        if self
+            .config
+            .test_files
+            .iter()
+            .any(|test_glob| test_glob.matches_path(relative))
+        {
+            return true;
+        }
+
+        // check the test directories of the package that owns the file
+        source_file
+            .owning_package
+            .as_ref()
+            .and_then(|package_name| package_test_dirs.get(package_name.as_str()))
+            .is_some_and(|test_dirs| test_dirs.iter().any(|test_dir| path.starts_with(test_dir)))
+    }
+
+    /// Gets the test files that do not call any test framework functions,
+    /// sorted by path.
+    ///
+    /// Opaque modules are never parsed, so they are not checked.
+    fn get_test_files_without_tests<'a>(&self, test_files: &[&'a Path]) -> Vec<&'a Path> {
+        let mut without_tests: Vec<&Path> = test_files
+            .iter()
+            .copied()
+            .filter(|path| !is_opaque_module(path, &self.config.opaque_module_extensions))
+            .filter(|path| {
+                self.last_walk_result
+                    .source_files
+                    .get(*path)
+                    .is_some_and(|source_file| !source_file.import_export_info.has_test_markers)
+            })
+            .collect();
+        without_tests.sort();
+        without_tests
+    }
+
+    /// Gets the absolute paths of each package's test directories, as declared
+    /// in the package's package.json "directories.test" field.
+    fn get_package_test_dirs(&self) -> AHashMap<&str, Vec<PathBuf>> {
+        if !self.config.infer_test_dirs_from_package_json {
+            return AHashMap::default();
+        }
+
+        self.last_walk_result
+            .packages
+            .packages
+            .iter()
+            .filter_map(|package| -> Option<(&str, Vec<PathBuf>)> {
+                let package_name = package.package_json.name.as_deref()?;
+                let package_dir = package.package_path.parent()?;
+                let declared_test_dir = package
+                    .package_json
+                    .directories
+                    .as_ref()
+                    .and_then(|directories| directories.test.as_ref());
+                let test_dirs = match declared_test_dir {
+                    Some(test_dir) => vec![package_dir.join(test_dir)],
+                    None if self.config.use_default_test_dirs => DEFAULT_TEST_DIRS
+                        .iter()
+                        .map(|test_dir| package_dir.join(test_dir))
+                        .collect(),
+                    None => return None,
+                };
+                Some((package_name, test_dirs))
             })
             .collect()
     }
@@ -563,13 +1974,14 @@ impl UnusedFinder {
             .any(|ignore_file| ignore_file.is_ignored(file_path))
     }
 
-    /// Helper that checks if a file is ignored by any of the ignore files
-    fn get_file_ignored_symbols(file: &ResolvedSourceFile) -> Vec<ExportedSymbol> {
+    /// Gets the exported symbols of a file that are allowed to be unused,
+    /// either by an `@ALLOW-UNUSED-EXPORT` comment or by one of the `keep_pragmas`
+    fn get_file_ignored_symbols(&self, file: &ResolvedSourceFile) -> Vec<ExportedSymbol> {
         file.import_export_info
             .exported_ids
             .iter()
             .filter_map(|(symbol, metadata)| {
-                if metadata.allow_unused {
+                if metadata.allow_unused || self.is_kept_by_pragma(metadata) {
                     Some(symbol.clone())
                 } else {
                     None
@@ -577,12 +1989,68 @@ impl UnusedFinder {
             })
             .collect()
     }
+
+    /// Checks if an exported symbol's export statement is preceded by a line
+    /// comment containing one of the `keep_pragmas`
+    fn is_kept_by_pragma(&self, metadata: &ExportedSymbolMetadata) -> bool {
+        metadata.line_comments.iter().any(|comment| {
+            self.config
+                .keep_pragmas
+                .iter()
+                .any(|pragma| comment.contains(pragma.as_str()))
+        })
+    }
 }
 
 /// Represents the result of computing something over the graph.
+#[derive(Debug)]
 pub struct UnusedFinderResult {
     /// The finished, traversed graph, with unused items marked as used / unused.
-    pub graph: Graph,
+    pub graph: Arc<Graph>,
+    /// Time spent in each phase of the run that produced this result.
+    pub timings: UnusedFinderTimings,
+    /// Imports of each file that resolved into paths excluded by the `skip`
+    /// config. Their targets are not part of the graph.
+    pub skipped_imports: AHashMap<PathBuf, Vec<PathBuf>>,
+    /// Files exporting more than this many symbols are reported as large modules.
+    pub god_module_threshold: Option<usize>,
+    /// Files with these extensions were tracked as opaque modules, so their
+    /// own imports are not part of the graph.
+    pub opaque_module_extensions: Vec<String>,
+    /// How file paths are rendered in reports of this result
+    pub path_display: PathDisplay,
+    /// Root of the repo, which repo-relative paths are relative to
+    pub repo_root: PathBuf,
+    /// Name of the implicit package synthesized for a repo without any
+    /// package.json, if there was one
+    pub synthetic_root_package: Option<String>,
+    /// Names exported from entry files that resolve to more than one definition
+    pub symbol_collisions: Vec<SymbolCollision>,
+    /// Names that files get from more than one `export *` with different
+    /// definitions, which the files do not export
+    pub ambiguous_reexports: Vec<AmbiguousReexport>,
+    /// Imports of deep paths into other packages for symbols that the
+    /// packages also export from their public barrels
+    pub barrel_bypass_imports: Vec<BarrelBypassImport>,
+    /// Imports of each file that resolved into the `from` directory of a path
+    /// mirror, but have no corresponding source file.
+    pub unmatched_mirror_imports: AHashMap<PathBuf, Vec<PathBuf>>,
+    /// If true, reports of this result list the other files exporting the
+    /// name of each unused symbol
+    pub suggest_same_name_exports: bool,
+    /// Which names exported by more than one file are listed in reports of
+    /// this result
+    pub report_ambiguous_symbol_names: AmbiguousSymbolNames,
+    /// If true, reports of this result count the imports of each kind
+    pub count_import_kinds: bool,
+    /// If true, reports of this result list the imported bindings of each
+    /// file that are never referenced within the file
+    pub report_unused_imports: bool,
+    /// Imports of each file that resolve to files outside of the root paths
+    pub resolution_gaps: AHashMap<PathBuf, Vec<PathBuf>>,
+    /// Files the traversals started from: entrypoints, tests, ignored files,
+    /// and the files of the symbols they were seeded with
+    pub traversal_roots: Vec<PathBuf>,
 }
 
 fn cluster_id_for_file(graph_file: &GraphFile) -> String {
@@ -601,6 +2069,14 @@ fn cluster_id_for_file(graph_file: &GraphFile) -> String {
     )
 }
 
+// escapes a string to be written within a quoted dot label
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// the number of symbols listed on each edge of a package dot graph
+const PACKAGE_EDGE_LABEL_SYMBOLS: usize = 3;
+
 fn cluster_label_for_file(graph_file: &GraphFile) -> String {
     graph_file
         .file_path
@@ -611,13 +2087,722 @@ fn cluster_label_for_file(graph_file: &GraphFile) -> String {
 }
 
 impl UnusedFinderResult {
-    pub fn new(graph: Graph) -> Self {
-        Self { graph }
+    pub fn new(
+        graph: Arc<Graph>,
+        timings: UnusedFinderTimings,
+        skipped_imports: AHashMap<PathBuf, Vec<PathBuf>>,
+        god_module_threshold: Option<usize>,
+        opaque_module_extensions: Vec<String>,
+        path_display: PathDisplay,
+        repo_root: PathBuf,
+    ) -> Self {
+        Self {
+            graph,
+            timings,
+            skipped_imports,
+            god_module_threshold,
+            opaque_module_extensions,
+            path_display,
+            repo_root,
+            synthetic_root_package: None,
+            symbol_collisions: Vec::new(),
+            ambiguous_reexports: Vec::new(),
+            barrel_bypass_imports: Vec::new(),
+            unmatched_mirror_imports: AHashMap::default(),
+            suggest_same_name_exports: false,
+            report_ambiguous_symbol_names: AmbiguousSymbolNames::Off,
+            count_import_kinds: false,
+            report_unused_imports: false,
+            resolution_gaps: AHashMap::default(),
+            traversal_roots: Vec::new(),
+        }
+    }
+
+    /// Iterates over the files that are completely unused, borrowing their
+    /// paths from the graph. Files are in graph order.
+    pub fn iter_unused_files(&self) -> impl Iterator<Item = &Path> {
+        self.graph
+            .files
+            .iter()
+            .filter(|file| !is_used(&file.file_tags))
+            .map(|file| file.file_path.as_path())
+    }
+
+    /// Parallel version of `iter_unused_files`, for collecting the report
+    pub(crate) fn par_iter_unused_files(&self) -> impl ParallelIterator<Item = &Path> {
+        self.graph
+            .files
+            .par_iter()
+            .filter(|file| !is_used(&file.file_tags))
+            .map(|file| file.file_path.as_path())
+    }
+
+    /// Gets the files that are used: every file of the graph that is not
+    /// completely unused, i.e. reachable from an entrypoint, a test or an
+    /// ignored file. Files are sorted by path.
+    pub fn used_files(&self) -> Vec<PathBuf> {
+        let mut used_files: Vec<PathBuf> = self
+            .graph
+            .files
+            .iter()
+            .filter(|file| is_used(&file.file_tags))
+            .map(|file| file.file_path.clone())
+            .collect();
+        used_files.sort();
+        used_files
+    }
+
+    /// Renders the used files as a manifest, e.g. for the include list of a
+    /// bundler: a JSON array of paths if `json` is set, or one path per line.
+    ///
+    /// Paths are rendered according to the configured `path_display`, and
+    /// sorted so the manifest is stable.
+    pub fn used_files_manifest(&self, json: bool) -> String {
+        let base_dir = self.path_display.base_dir(&self.repo_root);
+        let mut used_files: Vec<String> = self
+            .used_files()
+            .iter()
+            .map(|file_path| display_path(file_path, base_dir.as_deref()))
+            .collect();
+        used_files.sort();
+        if json {
+            serde_json::to_string_pretty(&used_files)
+                .expect("a list of strings should always be serializable")
+        } else {
+            used_files
+                .iter()
+                .map(|file_path| format!("{file_path}\n"))
+                .collect()
+        }
+    }
+
+    /// Iterates over the unused exported symbols of each file, borrowing them
+    /// from the graph. The symbols of a file are yielded together.
+    ///
+    /// The synthetic exports of opaque modules have no location, so they are
+    /// skipped. Unused opaque modules are unused files instead.
+    pub fn iter_unused_symbols(&self) -> impl Iterator<Item = (&Path, SymbolRef<'_>)> {
+        self.graph.files.iter().flat_map(move |file| {
+            self.unused_symbols_of_file(file)
+                .map(move |symbol| (file.file_path.as_path(), symbol))
+        })
+    }
+
+    /// Iterates over the unused exported symbols of one file of the graph
+    pub(crate) fn unused_symbols_of_file<'a>(
+        &'a self,
+        file: &'a GraphFile,
+    ) -> impl Iterator<Item = SymbolRef<'a>> {
+        // the symbols of degraded files are unknown, so none are reported
+        let reported = !is_opaque_module(&file.file_path, &self.opaque_module_extensions)
+            && !file.import_export_info.degraded;
+        file.import_export_info
+            .iter_exported_symbols()
+            .filter(move |(_, symbol)| {
+                reported && !file.symbol_tags.get(*symbol).is_some_and(is_used)
+            })
+            .filter_map(move |(_, symbol)| {
+                let (symbol, metadata) =
+                    file.import_export_info.exported_ids.get_key_value(symbol)?;
+                Some(SymbolRef { symbol, metadata })
+            })
+    }
+
+    /// Iterates over the files that have a tag, borrowing their paths from
+    /// the graph. Files are in graph order.
+    pub fn iter_tagged_files(&self, tag: UsedTagEnum) -> impl Iterator<Item = &Path> {
+        let tag = UsedTag::from(tag);
+        self.graph
+            .files
+            .iter()
+            .filter(move |file| file.file_tags.contains(tag))
+            .map(|file| file.file_path.as_path())
+    }
+
+    /// Gets each file that is imported by exactly one other file, with that
+    /// importer. These files are candidates for inlining into, or merging
+    /// with, their importer.
+    ///
+    /// Importers are counted once per file, however many times and in however
+    /// many ways (e.g. `import` and `require`) they import the file. Files are
+    /// sorted by path.
+    pub fn single_importer_files(&self) -> Vec<(PathBuf, PathBuf)> {
+        let importers = self.graph.importers();
+        let mut single_importer_files: Vec<(PathBuf, PathBuf)> = importers
+            .iter()
+            .enumerate()
+            .filter_map(|(file_id, importer_ids)| match importer_ids.as_slice() {
+                // a file importing itself has no other importer
+                [importer_id] if *importer_id != file_id => Some((
+                    self.graph.files[file_id].file_path.clone(),
+                    self.graph.files[*importer_id].file_path.clone(),
+                )),
+                _ => None,
+            })
+            .collect();
+        single_importer_files.sort();
+        single_importer_files
+    }
+
+    /// Gets the used files that would become unused if the given file, and
+    /// its imports, were deleted. Files are sorted by path.
+    ///
+    /// Only the files the deleted file imports, directly or transitively, are
+    /// re-traversed. Reachability is followed through every import of a file,
+    /// not symbol by symbol, so a file that is only kept alive by a re-export
+    /// that is never imported is conservatively treated as still used.
+    pub fn cascade_if_deleted(&self, file_path: &Path) -> Vec<PathBuf> {
+        let deleted_id = match self.graph.path_to_id.get(file_path) {
+            Some(file_id) => *file_id,
+            None => return vec![],
+        };
+        let is_used_id = |file_id: &usize| is_used(&self.graph.files[*file_id].file_tags);
+
+        // files that are not downstream of the deleted file keep their importers
+        let downstream = self.reachable_file_ids(vec![deleted_id], None);
+        let still_used_roots: Vec<usize> = (0..self.graph.files.len())
+            .filter(|file_id| !downstream.contains(file_id))
+            .chain(
+                self.traversal_roots
+                    .iter()
+                    .filter_map(|root| self.graph.path_to_id.get(root).copied()),
+            )
+            .filter(|file_id| *file_id != deleted_id && is_used_id(file_id))
+            .collect();
+        let still_used = self.reachable_file_ids(still_used_roots, Some(deleted_id));
+
+        let mut cascade: Vec<PathBuf> = downstream
+            .into_iter()
+            .filter(|file_id| {
+                *file_id != deleted_id && is_used_id(file_id) && !still_used.contains(file_id)
+            })
+            .map(|file_id| self.graph.files[file_id].file_path.clone())
+            .collect();
+        cascade.sort();
+        cascade
+    }
+
+    // gets the ids of the files reachable from the given files through their
+    // imports, including the given files, without passing through `excluded`
+    fn reachable_file_ids(&self, from: Vec<usize>, excluded: Option<usize>) -> AHashSet<usize> {
+        let mut reachable: AHashSet<usize> = AHashSet::default();
+        let mut frontier = from;
+        while let Some(file_id) = frontier.pop() {
+            if Some(file_id) == excluded || !reachable.insert(file_id) {
+                continue;
+            }
+            frontier.extend(
+                self.graph.files[file_id]
+                    .import_export_info
+                    .iter_imported_symbols_meta()
+                    .filter_map(|(path, _, _)| self.graph.path_to_id.get(path).copied()),
+            );
+        }
+        reachable
+    }
+
+    /// Gets the number of imports that resolve to files outside of the root paths
+    pub fn resolution_gap_count(&self) -> usize {
+        self.resolution_gaps.values().map(Vec::len).sum()
+    }
+
+    /// Fails if more than `max_resolution_gaps` imports resolve to files
+    /// outside of the root paths. Symbols used only through those files are
+    /// reported as unused, so gaps usually mean the root paths are incomplete.
+    pub fn check_resolution_gaps(&self, max_resolution_gaps: usize) -> Result<(), JsErr> {
+        let resolution_gap_count = self.resolution_gap_count();
+        if resolution_gap_count <= max_resolution_gaps {
+            return Ok(());
+        }
+
+        let mut gaps: Vec<String> = self
+            .resolution_gaps
+            .iter()
+            .flat_map(|(file_path, imports)| {
+                imports
+                    .iter()
+                    .map(move |import| format!("  {} -> {}", file_path.display(), import.display()))
+            })
+            .collect();
+        gaps.sort();
+        Err(JsErr::generic_failure(anyhow!(
+            "{resolution_gap_count} imports resolve outside of the root paths, exceeding the maximum of {max_resolution_gaps}. Add their directories to rootPaths:\n{}",
+            gaps.join("\n")
+        )))
+    }
+
+    /// Gets whether a symbol exported from a file in the graph is used.
+    ///
+    /// `symbol_name` is the canonical string form of the symbol (see [ExportedSymbol]),
+    /// e.g. the exported name, or `default` for the default export. It may also be
+    /// the report id of one declaration of a name declared more than once, e.g.
+    /// `formatDate#2`, which has the status of the name.
+    pub fn symbol_status(&self, file_path: &Path, symbol_name: &str) -> SymbolStatus {
+        let file = match self.graph.get_file_by_path(file_path) {
+            Some(file) => file,
+            None => return SymbolStatus::UnknownFile,
+        };
+
+        let is_exported = |symbol: &ExportedSymbol| {
+            file.import_export_info
+                .iter_exported_symbols()
+                .any(|(_, exported)| exported == symbol)
+        };
+        let mut symbol = ExportedSymbol::from(symbol_name);
+        if !is_exported(&symbol) {
+            symbol = match split_declaration_suffix(symbol_name) {
+                (name, Some(_)) => ExportedSymbol::from(name),
+                (_, None) => return SymbolStatus::UnknownSymbol,
+            };
+            let is_declaration_id = file
+                .import_export_info
+                .exported_ids
+                .get(&symbol)
+                .is_some_and(|metadata| {
+                    declaration_ids(&symbol, &metadata.declarations)
+                        .iter()
+                        .any(|id| id == symbol_name)
+                });
+            if !is_declaration_id {
+                return SymbolStatus::UnknownSymbol;
+            }
+        }
+
+        let tags = file.symbol_tags.get(&symbol).copied().unwrap_or_default();
+        if is_used(&tags) {
+            SymbolStatus::Used(tags.into())
+        } else {
+            SymbolStatus::Unused
+        }
+    }
+
+    /// Finds every import of a symbol exported from `defining_file`, including
+    /// imports through barrel files that re-export it, possibly under a new name.
+    ///
+    /// Namespace imports of the defining file or of a barrel are reported once,
+    /// as a namespace import, rather than once per use of the symbol.
+    pub fn find_import_sites(
+        &self,
+        defining_file: &Path,
+        symbol: &ExportedSymbol,
+    ) -> Vec<ImportSite> {
+        let defining_file_id = match self.graph.path_to_id.get(defining_file) {
+            Some(id) => *id,
+            None => return Vec::new(),
+        };
+        let importers = self.graph.importers();
+
+        let mut sites: Vec<ImportSite> = Vec::new();
+        // files exporting the symbol, the name they export it as, whether that
+        // name is a namespace containing it, and the barrels passed through to get there
+        let mut frontier: Vec<(usize, ExportedSymbol, bool, Vec<PathBuf>)> =
+            vec![(defining_file_id, symbol.clone(), false, Vec::new())];
+        let mut visited: AHashSet<(usize, ExportedSymbol, bool)> = AHashSet::default();
+        visited.insert((defining_file_id, symbol.clone(), false));
+
+        while let Some((file_id, exported_as, through_namespace, via)) = frontier.pop() {
+            let file_path = &self.graph.files[file_id].file_path;
+            for importer_id in importers[file_id].iter() {
+                let importer = &self.graph.files[*importer_id];
+                let info = &importer.import_export_info;
+                let span_of = |imported: &ExportedSymbol| -> Span {
+                    info.import_spans
+                        .get(file_path)
+                        .and_then(|spans| spans.get(imported))
+                        .copied()
+                        .unwrap_or_default()
+                };
+                let mut push_site = |imported: &ExportedSymbol, namespace: bool| {
+                    sites.push(ImportSite {
+                        file: importer.file_path.clone(),
+                        span: span_of(imported),
+                        namespace,
+                        via: via.clone(),
+                    })
+                };
+
+                let imported_symbols = info.imported_symbols.get(file_path);
+                if imported_symbols.is_some_and(|symbols| symbols.contains(&exported_as)) {
+                    push_site(&exported_as, through_namespace);
+                }
+                if imported_symbols
+                    .is_some_and(|symbols| symbols.contains(&ExportedSymbol::Namespace))
+                    || info.require_paths.contains(file_path)
+                    || info.imported_paths.contains(file_path)
+                {
+                    push_site(&ExportedSymbol::Namespace, true);
+                }
+
+                // continue through barrels, under the name they re-export the symbol as
+                for re_exported in info
+                    .export_from_symbols
+                    .get(file_path)
+                    .into_iter()
+                    .flat_map(|re_exported| re_exported.keys())
+                {
+                    let re_exported_as = match re_exported.re_exported_name(&exported_as) {
+                        Some(name) => name.clone(),
+                        None => continue,
+                    };
+                    // the barrel does not export names that are ambiguous between its `export *`
+                    if re_exported.imported == ExportedSymbol::Namespace
+                        && re_exported.renamed_to.is_none()
+                        && self.ambiguous_reexports.iter().any(|ambiguous_reexport| {
+                            ambiguous_reexport.file_path == importer.file_path
+                                && ambiguous_reexport.name == re_exported_as
+                        })
+                    {
+                        continue;
+                    }
+                    let through_namespace = through_namespace
+                        || (re_exported.imported == ExportedSymbol::Namespace
+                            && re_exported.renamed_to.is_some());
+                    if visited.insert((*importer_id, re_exported_as.clone(), through_namespace)) {
+                        let mut via = via.clone();
+                        via.push(importer.file_path.clone());
+                        frontier.push((*importer_id, re_exported_as, through_namespace, via));
+                    }
+                }
+            }
+        }
+
+        sites.sort_by(|a, b| {
+            a.file
+                .cmp(&b.file)
+                .then(a.span.lo.cmp(&b.span.lo))
+                .then(a.via.cmp(&b.via))
+        });
+        sites
+    }
+
+    /// Finds where a symbol exported from `file_path` is defined, following
+    /// re-exports through barrel files, possibly under other names.
+    ///
+    /// Following stops at the first file that defines the symbol, re-exports a
+    /// whole module as a namespace, or is not part of the graph, like external
+    /// packages. Returns None if the file is not in the graph, does not export
+    /// the symbol, or only re-exports it in a cycle.
+    pub fn resolve_symbol_origin(
+        &self,
+        file_path: &Path,
+        symbol: &ExportedSymbol,
+    ) -> Option<SymbolOrigin> {
+        self.graph.get_file_by_path(file_path)?;
+        let mut file_path = file_path.to_path_buf();
+        let mut symbol = symbol.clone();
+        let mut re_export_chain: Vec<PathBuf> = Vec::new();
+        let mut visited: AHashSet<(PathBuf, ExportedSymbol)> = AHashSet::default();
+
+        while let Some(file) = self.graph.get_file_by_path(&file_path) {
+            if symbol == ExportedSymbol::Namespace
+                || file.import_export_info.exported_ids.contains_key(&symbol)
+            {
+                break;
+            }
+            if !visited.insert((file_path.clone(), symbol.clone())) {
+                return None;
+            }
+            let (source_path, imported) = self.re_export_source(file, &symbol)?;
+            re_export_chain.push(file_path);
+            file_path = source_path;
+            symbol = imported;
+        }
+
+        Some(SymbolOrigin {
+            // imports of external packages are left unresolved, as the module name
+            external: !file_path.is_absolute(),
+            file_path,
+            symbol,
+            re_export_chain,
+        })
+    }
+
+    // Finds the file and name that a file re-exports one of its names from.
+    // Named re-exports take priority over `export *`, as they do in modules.
+    fn re_export_source(
+        &self,
+        file: &GraphFile,
+        symbol: &ExportedSymbol,
+    ) -> Option<(PathBuf, ExportedSymbol)> {
+        let mut star_sources: Vec<(swc_common::BytePos, &PathBuf)> = Vec::new();
+        for (source_path, re_exports) in file.import_export_info.export_from_symbols.iter() {
+            for (re_export, meta) in re_exports.iter() {
+                if re_export.imported == ExportedSymbol::Namespace && re_export.renamed_to.is_none()
+                {
+                    star_sources.push((meta.span.lo, source_path));
+                } else if re_export.exported_name() == symbol {
+                    return Some((source_path.clone(), re_export.imported.clone()));
+                }
+            }
+        }
+
+        // `export *` skips the default export, and names that are ambiguous
+        // between several of them
+        if *symbol == ExportedSymbol::Default
+            || self.ambiguous_reexports.iter().any(|ambiguous_reexport| {
+                ambiguous_reexport.file_path == file.file_path && ambiguous_reexport.name == *symbol
+            })
+        {
+            return None;
+        }
+        star_sources.sort();
+        // the names of external packages are unknown, so they are only assumed
+        // to provide the symbol if no file of the repo does
+        let (_, source_path) = star_sources
+            .iter()
+            .find(|(_, source_path)| {
+                resolve_exported_definitions(&self.graph, source_path)
+                    .iter()
+                    .any(|(name, _)| name == symbol)
+            })
+            .or_else(|| {
+                star_sources
+                    .iter()
+                    .find(|(_, source_path)| !source_path.is_absolute())
+            })?;
+        Some(((*source_path).clone(), symbol.clone()))
     }
 
     /// Gets a report that can be presented to the JS bridge.
     pub fn get_report(&self) -> UnusedFinderReport {
-        UnusedFinderReport::from(self)
+        self.get_report_with_path_display(self.path_display)
+    }
+
+    /// Gets a report, with file paths rendered according to `path_display`
+    /// instead of the configured `path_display`.
+    pub fn get_report_with_path_display(&self, path_display: PathDisplay) -> UnusedFinderReport {
+        UnusedFinderReport::from(self).with_path_display(path_display, &self.repo_root)
+    }
+
+    /// Gets a report limited to the files owned by a single package.
+    ///
+    /// Usage is still computed across the whole repo, so symbols that other
+    /// packages import are not reported as unused.
+    pub fn report_for_package(&self, package_name: &str) -> UnusedFinderReport {
+        let package_files: HashSet<String> = self
+            .graph
+            .files
+            .iter()
+            .filter(|file| file.owning_package.as_deref() == Some(package_name))
+            .map(|file| file.file_path.to_string_lossy().to_string())
+            .collect();
+
+        // filter on the absolute paths of the files, before they are re-rooted
+        let mut report = UnusedFinderReport::from(self);
+        report.retain_files(|file_path| package_files.contains(file_path));
+        if report.import_kind_counts.is_some() {
+            report.import_kind_counts =
+                Some(import_kind_counts(self.graph.files.iter().filter(|file| {
+                    file.owning_package.as_deref() == Some(package_name)
+                })));
+        }
+        report.with_path_display(self.path_display, &self.repo_root)
+    }
+
+    /// Gets the unused files in an order they can be deleted one at a time,
+    /// without a remaining file ever importing a file that was already deleted.
+    ///
+    /// Files that import each other in a cycle can only be deleted together.
+    /// The cycle is broken at its alphabetically first file.
+    pub fn deletion_order(&self) -> Vec<PathBuf> {
+        let unused_files: Vec<&GraphFile> = self
+            .graph
+            .files
+            .iter()
+            .filter(|file| !is_used(&file.file_tags))
+            .collect();
+        let unused_paths: AHashSet<&Path> = unused_files
+            .iter()
+            .map(|file| file.file_path.as_path())
+            .collect();
+
+        // the unused files imported by each unused file
+        let imports: AHashMap<&Path, AHashSet<&Path>> = unused_files
+            .iter()
+            .map(|file| {
+                let imported_paths = file
+                    .import_export_info
+                    .iter_imported_symbols_meta()
+                    .map(|(imported_path, _, _)| imported_path.as_path())
+                    .filter(|imported_path| {
+                        *imported_path != file.file_path.as_path()
+                            && unused_paths.contains(imported_path)
+                    })
+                    .collect();
+                (file.file_path.as_path(), imported_paths)
+            })
+            .collect();
+
+        // number of not-yet-deleted files importing each unused file
+        let mut importer_counts: AHashMap<&Path, usize> =
+            unused_paths.iter().map(|path| (*path, 0)).collect();
+        for imported_path in imports.values().flatten() {
+            *importer_counts.get_mut(imported_path).unwrap() += 1;
+        }
+
+        let mut deletable: BTreeSet<&Path> = importer_counts
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(path, _)| *path)
+            .collect();
+        let mut order = Vec::with_capacity(unused_paths.len());
+        while order.len() < unused_paths.len() {
+            let next = match deletable.pop_first() {
+                Some(path) => path,
+                // only import cycles are left
+                None => *importer_counts
+                    .iter()
+                    .filter(|(_, count)| **count > 0)
+                    .map(|(path, _)| path)
+                    .min()
+                    .unwrap(),
+            };
+            importer_counts.remove(next);
+            for imported_path in imports[next].iter() {
+                if let Some(count) = importer_counts.get_mut(imported_path) {
+                    *count -= 1;
+                    if *count == 0 {
+                        deletable.insert(*imported_path);
+                    }
+                }
+            }
+            order.push(next.to_path_buf());
+        }
+        order
+    }
+
+    /// Collapses the import graph into dependencies between packages, keyed
+    /// by the name of the importing package.
+    ///
+    /// Every package owning a file in the graph has an entry, even if it does
+    /// not depend on any other package. Imports within a package, and imports
+    /// of files without an owning package, are not package dependencies.
+    pub fn package_graph(&self) -> PackageGraph {
+        self.collapsed_package_graph().to_package_graph()
+    }
+
+    /// Collapses the import graph into packages, counting the files of each
+    /// package and aggregating the imports between different packages.
+    ///
+    /// Files without an owning package are collapsed into [`UNROOTED_PACKAGE`].
+    pub fn collapsed_package_graph(&self) -> CollapsedPackageGraph {
+        let package_of = |file: &GraphFile| -> String {
+            file.owning_package
+                .clone()
+                .unwrap_or_else(|| UNROOTED_PACKAGE.to_string())
+        };
+
+        let mut collapsed = CollapsedPackageGraph::default();
+        for file in self.graph.files.iter() {
+            let package_name = package_of(file);
+            let node = collapsed.packages.entry(package_name.clone()).or_default();
+            node.files += 1;
+            if !is_used(&file.file_tags) {
+                node.unused_files += 1;
+            }
+
+            let mut imported_files: HashSet<&Path> = HashSet::new();
+            for (imported_path, symbol, _) in file.import_export_info.iter_imported_symbols_meta() {
+                let imported_package = match self.graph.get_file_by_path(imported_path) {
+                    Some(imported_file) => package_of(imported_file),
+                    None => continue,
+                };
+                if imported_package == package_name {
+                    continue;
+                }
+                let edge = collapsed
+                    .edges
+                    .entry((package_name.clone(), imported_package))
+                    .or_default();
+                if imported_files.insert(imported_path.as_path()) {
+                    edge.file_edges += 1;
+                }
+                if *symbol != ExportedSymbol::ExecutionOnly {
+                    *edge.symbols.entry(symbol.to_string()).or_default() += 1;
+                }
+            }
+        }
+        collapsed
+    }
+
+    /// Writes the import graph in the `modules` JSON format of dependency-cruiser,
+    /// so visualizations built on dependency-cruiser's output can read it.
+    ///
+    /// Unused files are marked as `orphan`.
+    pub fn to_depcruise_json(&self, writer: impl std::io::Write) -> Result<(), JsErr> {
+        serde_json::to_writer_pretty(writer, &DepcruiseOutput::from(self)).map_err(JsErr::unknown)
+    }
+
+    /// Writes the import graph collapsed into packages as a dot graph.
+    ///
+    /// Each package is colored by the share of its files that are unused, from
+    /// green to red. Edges are labeled with the number of file-level imports
+    /// they aggregate and the most imported symbols. The filter glob matches
+    /// package names, and is expanded the same way as in `write_dot_graph`.
+    pub fn write_package_dot_graph(
+        &self,
+        logger: impl Logger,
+        filter_glob_str: Option<&str>,
+        writer: &mut dyn std::io::Write,
+    ) -> Result<(), JsErr> {
+        let filter_glob = filter_glob_str
+            .map(Glob::new)
+            .transpose()
+            .map_err(JsErr::invalid_arg)?;
+
+        let mut collapsed = self.collapsed_package_graph();
+        if let Some(filter_glob) = filter_glob {
+            collapsed = collapsed.filter(&filter_glob);
+        }
+        logger.debug(format!(
+            "collapsed graph has {} packages and {} edges",
+            collapsed.packages.len(),
+            collapsed.edges.len()
+        ));
+
+        writeln!(writer, "digraph packages {{").map_err(JsErr::unknown)?;
+        writeln!(writer, "node [shape=box,style=filled];").map_err(JsErr::unknown)?;
+        for (package_name, node) in collapsed.packages.iter() {
+            let unused_ratio = if node.files == 0 {
+                0.0
+            } else {
+                node.unused_files as f64 / node.files as f64
+            };
+            writeln!(
+                writer,
+                r#""{id}" [label="{id}\n{files} files, {unused} unused ({percent}%)",fillcolor="{hue:.3} 0.4 1.0"];"#,
+                id = dot_escape(package_name),
+                files = node.files,
+                unused = node.unused_files,
+                percent = (unused_ratio * 100.0).round(),
+                // from green when every file is used to red when none are
+                hue = (1.0 - unused_ratio) / 3.0,
+            )
+            .map_err(JsErr::unknown)?;
+        }
+        for ((from, to), edge) in collapsed.edges.iter() {
+            let mut symbols: Vec<String> = edge
+                .top_symbols(PACKAGE_EDGE_LABEL_SYMBOLS)
+                .into_iter()
+                .map(dot_escape)
+                .collect();
+            if edge.symbols.len() > symbols.len() {
+                symbols.push(format!("+{} more", edge.symbols.len() - symbols.len()));
+            }
+            writeln!(
+                writer,
+                r#""{from}" -> "{to}" [label="{file_edges} import{plural}\n{symbols}"];"#,
+                from = dot_escape(from),
+                to = dot_escape(to),
+                file_edges = edge.file_edges,
+                plural = if edge.file_edges == 1 { "" } else { "s" },
+                symbols = symbols.join(", "),
+            )
+            .map_err(JsErr::unknown)?;
+        }
+        writeln!(writer, "}}").map_err(JsErr::unknown)?;
+
+        Ok(())
     }
 
     pub fn write_dot_graph(
@@ -628,7 +2813,7 @@ impl UnusedFinderResult {
     ) -> Result<(), JsErr> {
         // Compile the glob
         let filter_glob = filter_glob_str
-            .map(glob::Pattern::new)
+            .map(Glob::new)
             .transpose()
             .map_err(JsErr::invalid_arg)?;
 
@@ -768,7 +2953,7 @@ impl UnusedFinderResult {
 
                         format!(
                             "\"{symbol}{additional_info}\"",
-                            symbol = symbol,
+                            symbol = dot_escape(&symbol.to_string()),
                             additional_info = (if !additional_info.is_empty() {
                                 format!(" ({})", additional_info.join(", "))
                             } else {
@@ -877,3 +3062,40 @@ impl UnusedFinderResult {
         Ok(())
     }
 }
+
+/// Renders the unused files and symbols of the result in the same format as
+/// [UnusedFinderReport], straight from the graph instead of a report. Files
+/// are in graph order, and paths are rendered according to `path_display`.
+impl Display for UnusedFinderResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let base_dir = self.path_display.base_dir(&self.repo_root);
+        let unused_symbol_count = |file: &GraphFile| -> usize {
+            self.unused_symbols_of_file(file)
+                .map(|symbol| symbol.report_count())
+                .sum()
+        };
+
+        for file in self.graph.files.iter() {
+            if !is_used(&file.file_tags) {
+                let file_path = display_path(&file.file_path, base_dir.as_deref());
+                write_unused_file(f, &file_path, unused_symbol_count(file))?;
+            }
+        }
+
+        for file in self.graph.files.iter() {
+            let item_count = unused_symbol_count(file);
+            if !is_used(&file.file_tags) || item_count == 0 {
+                continue;
+            }
+            let file_path = display_path(&file.file_path, base_dir.as_deref());
+            write_partially_unused_file(f, &file_path, item_count)?;
+            for symbol in self.unused_symbols_of_file(file) {
+                for item in symbol.to_reports() {
+                    write_unused_symbol(f, &item)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
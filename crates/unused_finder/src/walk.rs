@@ -1,23 +1,33 @@
 use crate::ignore_file::IgnoreFile;
 use crate::parse::exports_visitor_runner::SourceFileParseError;
-use crate::parse::{get_file_import_export_info, RawImportExportInfo};
+use crate::parse::{get_file_import_export_info_or_scan, RawImportExportInfo};
 use crate::walked_file::{WalkedPackage, WalkedSourceFile};
+use crate::workspaces::find_workspace_package_jsons;
 use ahashmap::AHashMap;
 use anyhow::Context;
-use ignore::overrides::OverrideBuilder;
+use ignore::overrides::{Override, OverrideBuilder};
 use ignore::DirEntry;
 use logger::Logger;
+use multi_err::MultiErr;
+use path_utils::{join_normalized, Glob};
 use rayon::iter::Either;
 use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
+use tsconfig_paths::TsconfigFileSet;
 
+// A file visited by the unused finder's walk
 #[derive(Debug, PartialEq)]
 enum WalkedFile {
-    SourceFile(PathBuf, RawImportExportInfo),
-    PackageJson(WalkedPackage),
+    // a parsed source file, with the name of its owning package
+    SourceFile(Option<String>, RawImportExportInfo),
+    // a source file that failed to parse, with the parse error
+    UnparseableSourceFile(String),
     IgnoreFile(IgnoreFile),
+    FenceFile,
 }
 
 #[derive(Debug)]
@@ -121,6 +131,24 @@ impl RepoPackages {
     pub fn iter_names(&self) -> impl Iterator<Item = &String> {
         self.packages_by_name.keys()
     }
+
+    // get the implicit root package synthesized for a repo without any package.json
+    pub fn get_synthetic_root(&self) -> Option<&WalkedPackage> {
+        self.packages.iter().find(|package| package.synthetic)
+    }
+}
+
+/// How the packages of a repo are discovered during the walk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PackageDiscovery {
+    /// Every package.json found while walking the root paths is a package
+    #[default]
+    Walk,
+    /// Only the packages matched by the workspace globs of the root
+    /// package.json (`workspaces`) or of pnpm-workspace.yaml (`packages`)
+    /// are packages. Falls back to walking if the repo declares no workspaces.
+    Workspaces,
 }
 
 #[derive(Debug)]
@@ -132,6 +160,175 @@ pub struct WalkedFiles {
     pub source_files: Vec<WalkedSourceFile>,
     // List of files to ignore unused symbols in entirely
     pub ignore_files: Vec<IgnoreFile>,
+    // Paths of the good-fences `fence.json` files found during the walk.
+    // These are not used by the unused finder, but let good-fences share the walk.
+    pub fence_files: Vec<PathBuf>,
+    // Source files that failed to parse, with their parse errors. Files whose
+    // imports could be scanned without parsing are kept in `source_files` as
+    // degraded files, and the others are left out.
+    pub parse_errors: Vec<(PathBuf, String)>,
+}
+
+/// Configuration of a walk over the files of a repo with [walk_repo],
+/// independent of the configuration of the unused finder
+#[derive(Debug, Clone, Default)]
+pub struct WalkConfig {
+    /// Absolute path to the root of the repo
+    pub repo_root: PathBuf,
+    /// Paths to walk, either absolute or relative to the repo root
+    pub root_paths: Vec<PathBuf>,
+    /// Globs of file and directory names to skip, in addition to the
+    /// `DEFAULT_OVERRIDE_PATTERNS`
+    pub skip: Vec<String>,
+    /// How packages are discovered during the walk
+    pub package_discovery: PackageDiscovery,
+}
+
+/// A file found by [walk_repo], as passed to a [FileVisitor]
+#[derive(Debug, Clone, Copy)]
+pub struct VisitedFile<'a> {
+    /// The absolute path of the file
+    pub path: &'a Path,
+    /// The package the file belongs to, if any
+    pub owning_package: Option<&'a WalkedPackage>,
+}
+
+impl VisitedFile<'_> {
+    /// Reads the contents of the file. The walk itself never reads the files
+    /// it visits, so visitors only pay for the contents they need.
+    pub fn read(&self) -> std::io::Result<Vec<u8>> {
+        std::fs::read(self.path)
+    }
+
+    /// Reads the contents of the file as a string
+    pub fn read_to_string(&self) -> std::io::Result<String> {
+        std::fs::read_to_string(self.path)
+    }
+}
+
+/// Visits each file found by [walk_repo], e.g. to parse source files or to
+/// count lines of code.
+///
+/// Files are visited in parallel, after all packages of the repo are known.
+pub trait FileVisitor {
+    /// The result of visiting a single file
+    type Output: Send;
+
+    /// Visits a single file, returning None to leave it out of the results.
+    ///
+    /// package.json files are read by the walk to discover packages, and are
+    /// not visited.
+    fn visit_file(&self, file: VisitedFile) -> Result<Option<Self::Output>, anyhow::Error>;
+}
+
+/// The result of a [walk_repo]
+pub struct WalkSummary<T> {
+    /// Packages discovered during the walk
+    pub packages: RepoPackages,
+    /// The output of the visitor for each visited file, sorted by path
+    pub files: Vec<(PathBuf, T)>,
+    /// Errors of the walk and of the visitor. These do not stop the walk, but
+    /// the files they occurred on are left out of `files`.
+    pub errors: MultiErr<anyhow::Error>,
+}
+
+/// Walks the root paths of a repo in parallel, skipping the configured
+/// files and directories, and calls the visitor for each file along with the
+/// package that owns it.
+///
+/// Packages are discovered before any file is visited, so that each visit
+/// knows its owning package. Results are sorted by path, so they do not
+/// depend on the order of the parallel walk.
+pub fn walk_repo<V: FileVisitor + Sync>(
+    logger: impl Logger + Sync,
+    config: WalkConfig,
+    visitor: V,
+) -> Result<WalkSummary<V::Output>, anyhow::Error> {
+    let workspace_package_jsons = match config.package_discovery {
+        PackageDiscovery::Walk => None,
+        PackageDiscovery::Workspaces => {
+            let package_jsons = find_workspace_package_jsons(&logger, &config.repo_root)?;
+            if package_jsons.is_none() {
+                logger.warn(format!(
+                    "No workspaces declared in {}, falling back to walking for packages",
+                    config.repo_root.display()
+                ));
+            }
+            package_jsons
+        }
+    };
+    // package.json files are only picked up by the walk if they were not
+    // already discovered through the workspace globs
+    let walk_package_jsons = workspace_package_jsons.is_none();
+
+    let mut errors = MultiErr::new();
+    let mut walked_packages: Vec<WalkedPackage> = Vec::new();
+    let mut file_paths: Vec<PathBuf> = Vec::new();
+    // Parallel walk of each root path in sequence
+    for root_path in config.root_paths.iter() {
+        let abs_root_path = abspath::join_abspath(&config.repo_root, root_path)?;
+        let walk = build_walk(&logger, &abs_root_path, config.skip.as_slice())
+            .with_context(|| format!("Error constructing walk over {}", abs_root_path.display()))?;
+        for entry in collect_walk(walk, walk_package_jsons) {
+            match entry {
+                Ok(WalkEntry::PackageJson(package)) => walked_packages.push(package),
+                Ok(WalkEntry::File(file_path)) => file_paths.push(file_path),
+                Err(e) => errors.add_single(e),
+            }
+        }
+    }
+
+    for package_json_path in workspace_package_jsons.iter().flatten() {
+        match WalkedPackage::from_path(package_json_path.as_path())
+            .with_context(|| "Failed to read workspace package.json")
+        {
+            Ok(package) => walked_packages.push(package),
+            Err(e) => errors.add_single(e),
+        }
+    }
+
+    // sort packages, so that duplicate package names are reported consistently
+    walked_packages.sort_by(|a, b| a.package_path.cmp(&b.package_path));
+    let mut packages = RepoPackages::new();
+    for package in walked_packages {
+        if let Err(e) = packages.add(package) {
+            errors.add_single(e);
+        }
+    }
+
+    // visit the files once their owning packages are known. Root paths may
+    // overlap, so the same file can be walked more than once.
+    file_paths.sort();
+    file_paths.dedup();
+    let (files, visit_errors): (Vec<(PathBuf, V::Output)>, Vec<anyhow::Error>) = file_paths
+        .into_par_iter()
+        .filter_map(
+            |file_path| -> Option<Result<(PathBuf, V::Output), anyhow::Error>> {
+                let owning_package = match packages.get_by_child_path(&file_path) {
+                    Ok(owning_package) => owning_package,
+                    Err(e) => return Some(Err(e)),
+                };
+                let visited_file = VisitedFile {
+                    path: &file_path,
+                    owning_package,
+                };
+                match visitor.visit_file(visited_file) {
+                    Ok(Some(output)) => Some(Ok((file_path, output))),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(
+                        e.context(format!("Failed to visit {}", file_path.display()))
+                    )),
+                }
+            },
+        )
+        .partition_map(split_errs);
+    errors.add_iter(visit_errors.into_iter());
+
+    Ok(WalkSummary {
+        packages,
+        files,
+        errors,
+    })
 }
 
 /// Walks the root paths of a project and returns a list of source files and packages
@@ -140,89 +337,218 @@ pub fn walk_src_files(
     root_paths: &[impl AsRef<Path> + Debug],
     repo_root_path: impl AsRef<Path>,
     ignored_filenames: &[impl AsRef<str> + Debug],
+    opaque_module_extensions: &[impl AsRef<str> + Sync],
+    package_discovery: PackageDiscovery,
 ) -> Result<WalkedFiles, anyhow::Error> {
-    let (tx, rx) = std::sync::mpsc::channel::<Result<WalkedFile, anyhow::Error>>();
-    let mut all_walked_files: Vec<WalkedFile> = Vec::new();
-    // create a mutable reference to all_walked_files, so we can
-    // pass ownership of it to the collector thread
-    let all_walked_ref = &mut all_walked_files;
-    // creates a new scope for spawning threads. This allows us to
-    // safely borrow &Logger, because the scope guarantees the threads
-    // will be joined after the scope ends.
-    std::thread::scope(|scope| -> Result<(), anyhow::Error> {
-        let collector_thread = scope.spawn(|| {
-            for file in rx {
-                match file {
-                    Ok(file) => {
-                        all_walked_ref.push(file);
-                    }
-                    Err(e) => {
-                        logger.log(format!("Error during walk: {:?}", e));
-                    }
-                }
+    let config = WalkConfig {
+        repo_root: repo_root_path.as_ref().to_path_buf(),
+        root_paths: root_paths
+            .iter()
+            .map(|root_path| root_path.as_ref().to_path_buf())
+            .collect(),
+        skip: ignored_filenames
+            .iter()
+            .map(|filename| filename.as_ref().to_string())
+            .collect(),
+        package_discovery,
+    };
+    let summary = walk_repo(
+        &logger,
+        config,
+        UnusedFinderVisitor {
+            opaque_module_extensions,
+        },
+    )?;
+    for error in Vec::from(summary.errors) {
+        logger.log(format!("Error during walk: {:?}", error));
+    }
+
+    let mut result = collect_results(summary.packages, summary.files);
+    match IgnoreFile::read(repo_root_path.as_ref().to_path_buf().join(".unusedignore")) {
+        Ok(ignore_file) => result.ignore_files.push(ignore_file),
+        Err(e) => {
+            // a missing root ignore file is not an error
+            if e.downcast_ref::<std::io::Error>()
+                .map_or(false, |e| e.kind() != std::io::ErrorKind::NotFound)
+            {
+                logger.log(format!("Error during walk: {:?}", e));
             }
-        });
+        }
+    }
+
+    Ok(result)
+}
+
+/// The default set of patterns to skip during the walk
+///
+/// This can be overridden by the user by specifying individual negations to
+/// these patterns in the `skip` field of the UnusedFinderConfig.
+pub const DEFAULT_OVERRIDE_PATTERNS: &[&str] = &["!node_modules", "!lib", "!target"];
+
+/// The default extensions of files that are tracked as opaque modules
+///
+/// Opaque modules are not parsed. They are walked as modules with a single
+/// default export and no imports, so that importing them marks them as used.
+pub const DEFAULT_OPAQUE_MODULE_EXTENSIONS: &[&str] = &[".vue", ".svelte"];
 
-        // Parallel walk of each root path in sequence
+/// Matches paths against the skip patterns used during the walk, so that
+/// imports resolving into skipped directories can be told apart from imports
+/// of files that were never walked for other reasons.
+#[derive(Debug)]
+pub struct SkipMatcher {
+    // overrides for each walked root path, as used by the walk
+    root_overrides: Vec<(PathBuf, Override)>,
+}
+
+impl SkipMatcher {
+    pub fn new(
+        repo_root_path: impl AsRef<Path>,
+        root_paths: &[impl AsRef<Path>],
+        ignored_filenames: &[impl AsRef<str>],
+    ) -> Result<Self, anyhow::Error> {
+        Self::new_with_case(repo_root_path, root_paths, ignored_filenames, false)
+    }
+
+    fn new_with_case(
+        repo_root_path: impl AsRef<Path>,
+        root_paths: &[impl AsRef<Path>],
+        ignored_filenames: &[impl AsRef<str>],
+        case_insensitive: bool,
+    ) -> Result<Self, anyhow::Error> {
+        let mut root_overrides = Vec::with_capacity(root_paths.len());
         for root_path in root_paths {
             let abs_root_path = abspath::join_abspath(&repo_root_path, root_path)?;
-            match build_walk(&logger, &abs_root_path, ignored_filenames) {
-                Ok(walk) => collect_walk(walk, &tx),
-                Err(e) => {
-                    return Err(anyhow!(format!(
-                        "Error constructing walk over {}: {}",
-                        &abs_root_path.display(),
-                        e
-                    )));
-                }
-            }
+            let overrides = build_overrides(&abs_root_path, ignored_filenames, case_insensitive)?;
+            root_overrides.push((abs_root_path, overrides));
         }
+        Ok(Self { root_overrides })
+    }
 
-        match IgnoreFile::read(repo_root_path.as_ref().to_path_buf().join(".unusedignore")) {
-            Ok(ignore_file) => tx.send(Ok(WalkedFile::IgnoreFile(ignore_file))).unwrap(),
-            Err(e) => {
-                // check if the error was file not found.
-                // if it was, ignore it, otherwise, send the error to the collector
-                if e.downcast_ref::<std::io::Error>()
-                    .map_or(false, |e| e.kind() != std::io::ErrorKind::NotFound)
-                {
-                    tx.send(Err(e)).unwrap();
-                }
+    /// Gets the skip patterns that would have skipped a path if they were
+    /// matched case-insensitively, e.g. `dist` for a file under `Dist/`.
+    pub fn case_insensitive_matches(
+        repo_root_path: impl AsRef<Path>,
+        root_paths: &[impl AsRef<Path>],
+        ignored_filenames: &[impl AsRef<str>],
+        path: &Path,
+    ) -> Result<Vec<String>, anyhow::Error> {
+        let no_patterns: &[&str] = &[];
+        // the default patterns alone would skip it, so no pattern is to blame
+        if Self::new_with_case(&repo_root_path, root_paths, no_patterns, true)?.is_skipped(path) {
+            return Ok(Vec::new());
+        }
+        let mut matches = Vec::new();
+        for ignored_filename in ignored_filenames {
+            let pattern = [ignored_filename.as_ref()];
+            if Self::new_with_case(&repo_root_path, root_paths, &pattern, true)?.is_skipped(path) {
+                matches.push(ignored_filename.as_ref().to_string());
             }
         }
+        Ok(matches)
+    }
 
-        // drop the sender to signal the collector thread to stop
-        drop(tx);
+    /// Checks if a path would have been skipped by the walk, either because
+    /// it matches a skip pattern or because one of its parent directories does.
+    pub fn is_skipped(&self, path: &Path) -> bool {
+        self.root_overrides.iter().any(|(root_path, overrides)| {
+            let relative_path = match path.strip_prefix(root_path) {
+                Ok(relative_path) => relative_path,
+                Err(_) => return false,
+            };
+            // the walk does not descend into skipped directories, so check each
+            // of the path's ancestors below the root as well as the path itself.
+            let component_count = relative_path.components().count();
+            let mut current_path = root_path.clone();
+            relative_path
+                .components()
+                .enumerate()
+                .any(|(i, component)| {
+                    current_path.push(component);
+                    let is_dir = i + 1 < component_count;
+                    overrides.matched(&current_path, is_dir).is_ignore()
+                })
+        })
+    }
 
-        collector_thread.join().unwrap();
+    /// Checks if a path is within any of the walked root paths
+    pub fn is_within_root_paths(&self, path: &Path) -> bool {
+        self.root_overrides
+            .iter()
+            .any(|(root_path, _)| path.starts_with(root_path))
+    }
+}
 
-        Ok(())
-    })?;
+/// Matches paths against the `files`, `include` and `exclude` of a
+/// tsconfig.json, to restrict the walked source files to the files of the
+/// TypeScript project.
+#[derive(Debug)]
+pub struct TsconfigFileFilter {
+    // files that are always included, even if they match `exclude`
+    files: Vec<PathBuf>,
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+}
 
-    // after the walk is complete, collec the results. This allows us to
-    // defer resolving the package ownership of source files until all
-    // source files and packages have been collected.
-    let (result, pkg_assignment_errs) = collect_results(all_walked_files.into_iter());
-    for error in pkg_assignment_errs {
-        logger.log(format!("Error during package assignment: {:?}", error));
+impl TsconfigFileFilter {
+    /// Reads the file set of the tsconfig.json at the provided path, merged
+    /// over the tsconfigs it `extends`
+    pub fn from_path(tsconfig_path: &Path) -> Result<Self, anyhow::Error> {
+        let file_set = TsconfigFileSet::from_path(&tsconfig_path.to_string_lossy())?;
+        let tsconfig_dir = tsconfig_path.parent().unwrap_or(Path::new(""));
+        // without `include`, a tsconfig includes every file unless it lists `files`
+        let include = match (file_set.include, &file_set.files) {
+            (Some(include), _) => include,
+            (None, Some(_)) => Vec::new(),
+            (None, None) => vec!["**/*".to_string()],
+        };
+        Ok(Self {
+            files: file_set
+                .files
+                .unwrap_or_default()
+                .iter()
+                .map(|file| join_normalized(tsconfig_dir, file))
+                .collect(),
+            include: tsconfig_globs(tsconfig_dir, &include)?,
+            exclude: tsconfig_globs(tsconfig_dir, &file_set.exclude.unwrap_or_default())?,
+        })
     }
 
-    Ok(result)
+    /// Checks if the tsconfig makes a file part of its project
+    pub fn is_included(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path)
+            || (self.include.iter().any(|glob| glob.matches_path(path))
+                && !self.exclude.iter().any(|glob| glob.matches_path(path)))
+    }
 }
 
-/// The default set of patterns to skip during the walk
-///
-/// This can be overridden by the user by specifying individual negations to
-/// these patterns in the `skip` field of the UnusedFinderConfig.
-pub const DEFAULT_OVERRIDE_PATTERNS: &[&str] = &["!node_modules", "!lib", "!target"];
+// Compiles the patterns of a tsconfig `include` or `exclude` into globs of
+// absolute paths. As in tsconfig, a pattern whose last segment has no
+// extension or wildcard names a directory, and matches every file within it.
+fn tsconfig_globs(tsconfig_dir: &Path, patterns: &[String]) -> Result<Vec<Glob>, anyhow::Error> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            let path = join_normalized(tsconfig_dir, pattern);
+            let is_dir = path.file_name().map_or(true, |name| {
+                !name.to_string_lossy().contains(['*', '?', '.'])
+            });
+            let path = if is_dir { path.join("**") } else { path };
+            Glob::new(&path.to_string_lossy())
+                .with_context(|| format!("Invalid tsconfig pattern {pattern:?}"))
+        })
+        .collect()
+}
 
-fn build_walk(
-    logger: impl Logger,
+fn build_overrides(
     root_path: impl AsRef<Path>,
     ingnored_filenames: &[impl AsRef<str>],
-) -> Result<ignore::WalkParallel, anyhow::Error> {
+    case_insensitive: bool,
+) -> Result<Override, anyhow::Error> {
     // Build overrides matcher
     let mut override_builder = OverrideBuilder::new(root_path.as_ref());
+    override_builder
+        .case_insensitive(case_insensitive)
+        .expect("case sensitivity is set before any globs are added");
     // permit all matches by default
     override_builder
         .add("*")
@@ -248,10 +574,18 @@ fn build_walk(
             .with_context(|| format!("Failed to override {:?}", as_ref))?;
     }
 
-    // add overrides to the builder
-    let overrides = override_builder
+    override_builder
         .build()
-        .context("Failed to build overrides")?;
+        .context("Failed to build overrides")
+}
+
+fn build_walk(
+    logger: impl Logger,
+    root_path: impl AsRef<Path>,
+    ingnored_filenames: &[impl AsRef<str>],
+) -> Result<ignore::WalkParallel, anyhow::Error> {
+    // add overrides to the builder
+    let overrides = build_overrides(&root_path, ingnored_filenames, false)?;
 
     // build the walker
     let mut walk_builder = ignore::WalkBuilder::new(root_path.as_ref());
@@ -276,109 +610,144 @@ fn build_walk(
     Ok(walk_builder.build_parallel())
 }
 
+// A file found during the walk, before any files are visited
+enum WalkEntry {
+    PackageJson(WalkedPackage),
+    File(PathBuf),
+}
+
 fn collect_walk(
     walk: ignore::WalkParallel,
-    tx: &std::sync::mpsc::Sender<Result<WalkedFile, anyhow::Error>>,
-) {
+    walk_package_jsons: bool,
+) -> Vec<Result<WalkEntry, anyhow::Error>> {
+    let (tx, rx) = std::sync::mpsc::channel::<Result<WalkEntry, anyhow::Error>>();
+    let tx = &tx;
     walk.run(|| {
         Box::new(move |result| -> ignore::WalkState {
-            match result {
-                Ok(entry) => {
-                    let walked_file = visit_entry(entry);
-                    let send_result = match walked_file {
-                        Ok(Some(file)) => tx.send(Ok(file)),
-                        Ok(None) => return ignore::WalkState::Continue,
-                        Err(e) => tx.send(Err(e)),
-                    };
-
-                    send_result.unwrap();
-                }
-                Err(e) => {
-                    tx.send(Err(anyhow!(e))).unwrap();
-                }
+            let walk_entry = match result {
+                Ok(entry) => match visit_entry(entry, walk_package_jsons) {
+                    Some(walk_entry) => walk_entry,
+                    None => return ignore::WalkState::Continue,
+                },
+                Err(e) => Err(anyhow!(e)),
             };
+            tx.send(walk_entry).unwrap();
             ignore::WalkState::Continue
         })
     });
+    // the walk is complete once run() returns, so all entries have been sent
+    rx.try_iter().collect()
 }
 
 fn collect_results(
-    walked_files: impl Iterator<Item = WalkedFile>,
-) -> (WalkedFiles, Vec<anyhow::Error>) {
+    packages: RepoPackages,
+    visited_files: Vec<(PathBuf, WalkedFile)>,
+) -> WalkedFiles {
     // partition the results
-    let mut packages = RepoPackages::new();
-    let mut source_files: Vec<(PathBuf, RawImportExportInfo)> = Vec::new();
+    let mut source_files: Vec<WalkedSourceFile> = Vec::new();
     let mut ignore_files: Vec<IgnoreFile> = Vec::new();
-    let mut errors: Vec<anyhow::Error> = Vec::new();
-    for file in walked_files.into_iter() {
+    let mut fence_files: Vec<PathBuf> = Vec::new();
+    let mut parse_errors: Vec<(PathBuf, String)> = Vec::new();
+    for (file_path, file) in visited_files.into_iter() {
         match file {
-            // noop the source files
-            WalkedFile::SourceFile(file_path, imports) => {
-                source_files.push((file_path, imports));
+            WalkedFile::SourceFile(owning_package, import_export_info) => {
+                if let Some(parse_error) = &import_export_info.parse_error {
+                    parse_errors.push((file_path.clone(), parse_error.clone()));
+                }
+                source_files.push(WalkedSourceFile {
+                    owning_package,
+                    source_file_path: file_path,
+                    import_export_info,
+                });
+            }
+            WalkedFile::UnparseableSourceFile(error) => {
+                parse_errors.push((file_path, error));
             }
-            WalkedFile::PackageJson(file) => match packages.add(file) {
-                Ok(_) => {}
-                Err(e) => errors.push(e),
-            },
             WalkedFile::IgnoreFile(file) => ignore_files.push(file),
+            WalkedFile::FenceFile => fence_files.push(file_path),
         }
     }
 
-    // Use `packages` to add owners to our list of source files
-    let (source_files, mut pkg_assignment_errs): (Vec<WalkedSourceFile>, Vec<anyhow::Error>) = source_files
-        .into_par_iter()
-        .map(
-            |(source_file_path, import_export_info)| -> Result<WalkedSourceFile, anyhow::Error> {
-                Ok(WalkedSourceFile {
-                    owning_package: packages
-                        .get_by_child_path(&source_file_path)?
-                        .and_then(|package| package.package_json.name.clone()),
-                    source_file_path,
-                    import_export_info,
-                })
-            },
-        )
-        .partition_map(split_errs);
-
-    let result = WalkedFiles {
+    WalkedFiles {
         packages,
         source_files,
         ignore_files,
-    };
+        fence_files,
+        parse_errors,
+    }
+}
 
-    errors.append(&mut pkg_assignment_errs);
-    (result, errors)
+// callback meant to be called during a file walk of a directory. package.json
+// files are read right away to discover packages, while other files are
+// visited once all packages are known.
+fn visit_entry(
+    entry: DirEntry,
+    walk_package_jsons: bool,
+) -> Option<Result<WalkEntry, anyhow::Error>> {
+    if entry
+        .file_type()
+        .is_some_and(|file_type| file_type.is_dir())
+    {
+        return None;
+    }
+    if entry.file_name() == "package.json" {
+        if !walk_package_jsons {
+            return None;
+        }
+        return Some(
+            WalkedPackage::from_path(entry.path())
+                .with_context(|| "Failed to walk package.json")
+                .map(WalkEntry::PackageJson),
+        );
+    }
+    Some(Ok(WalkEntry::File(entry.into_path())))
 }
 
-// callback meant to be called during a file walk of a directory
-// (e.g. with jwalk's process_read_dir() callback)
-fn visit_entry(entry: DirEntry) -> Result<Option<WalkedFile>, anyhow::Error> {
-    let dir_path = entry.path();
-    let file_name = entry.file_name();
-    if file_name == "package.json" {
-        WalkedPackage::from_path(dir_path)
-            .with_context(|| "Failed to walk package.json")
-            .map(|package| Some(WalkedFile::PackageJson(package)))
-    } else if file_name == ".unusedignore" {
-        let ignore_file = IgnoreFile::read(dir_path.to_path_buf())?;
-        Ok(Some(WalkedFile::IgnoreFile(ignore_file)))
-    } else if is_js_ts_file(file_name) {
-        // Source file [.ts, .tsx, .js, .jsx]
-        match get_file_import_export_info(entry.path()).map(|import_export_info| {
-            Some(WalkedFile::SourceFile(
-                dir_path.to_path_buf(),
-                import_export_info,
-            ))
-        }) {
-            // Pass-through results
-            Ok(file) => Ok(file),
-            // Skip auto-generated files -- they are not relevant to analysis
-            Err(SourceFileParseError::AutogeneratedFile) => Ok(None),
-            // Return other parse errors as anyhow errors
-            Err(e) => Err(e).with_context(|| format!("Failed to read source file: {:?}", dir_path)),
+/// Visits the files of the walk for the unused finder, parsing source files
+/// and collecting ignore files and fence files
+struct UnusedFinderVisitor<'a, E> {
+    opaque_module_extensions: &'a [E],
+}
+
+impl<E: AsRef<str> + Sync> FileVisitor for UnusedFinderVisitor<'_, E> {
+    type Output = WalkedFile;
+
+    fn visit_file(&self, file: VisitedFile) -> Result<Option<WalkedFile>, anyhow::Error> {
+        let file_name = match file.path.file_name() {
+            Some(file_name) => file_name,
+            None => return Ok(None),
+        };
+        let owning_package = || {
+            file.owning_package
+                .and_then(|package| package.package_json.name.clone())
+        };
+
+        if file_name == ".unusedignore" {
+            let ignore_file = IgnoreFile::read(file.path.to_path_buf())?;
+            Ok(Some(WalkedFile::IgnoreFile(ignore_file)))
+        } else if file_name == "fence.json" {
+            Ok(Some(WalkedFile::FenceFile))
+        } else if is_js_ts_file(file_name) {
+            // Source file [.ts, .tsx, .js, .jsx]
+            match get_file_import_export_info_or_scan(file.path) {
+                Ok(import_export_info) => Ok(Some(WalkedFile::SourceFile(
+                    owning_package(),
+                    import_export_info,
+                ))),
+                // Skip auto-generated files -- they are not relevant to analysis
+                Err(SourceFileParseError::AutogeneratedFile) => Ok(None),
+                // Keep other parse errors, so the caller can decide whether they fail the walk
+                Err(e) => Ok(Some(WalkedFile::UnparseableSourceFile(e.to_string()))),
+            }
+        } else if is_opaque_module(file.path, self.opaque_module_extensions) {
+            // Opaque module [.vue, .svelte], tracked without parsing its contents
+            Ok(Some(WalkedFile::SourceFile(
+                owning_package(),
+                RawImportExportInfo::new_opaque_module(),
+            )))
+        } else {
+            Ok(None)
         }
-    } else {
-        Ok(None)
     }
 }
 
@@ -391,6 +760,17 @@ fn is_js_ts_file(s: &OsStr) -> bool {
     false
 }
 
+/// Checks if a file should be tracked as an opaque module, based on its extension
+pub fn is_opaque_module(path: &Path, opaque_module_extensions: &[impl AsRef<str>]) -> bool {
+    let file_name = match path.file_name() {
+        Some(file_name) => file_name.as_encoded_bytes(),
+        None => return false,
+    };
+    opaque_module_extensions
+        .iter()
+        .any(|ext| file_name.ends_with(ext.as_ref().as_bytes()))
+}
+
 fn split_errs<A, B>(x: Result<A, B>) -> Either<A, B> {
     match x {
         Ok(file) => Either::Left(file),
@@ -403,6 +783,7 @@ mod test {
     use logger::StdioLogger;
 
     use super::*;
+    use std::collections::BTreeMap;
     use test_tmpdir::test_tmpdir;
 
     #[test]
@@ -425,6 +806,8 @@ mod test {
             &[tmpdir.root().join("packages"), tmpdir.root().join("shared")],
             tmpdir.root(),
             &["*.ignored.ts"],
+            DEFAULT_OPAQUE_MODULE_EXTENSIONS,
+            PackageDiscovery::Walk,
         );
 
         let walk_result = walk_result.unwrap();
@@ -460,6 +843,8 @@ mod test {
             &[tmpdir.root().join("packages"), tmpdir.root().join("shared")],
             tmpdir.root(),
             &["*.ignored.ts"],
+            DEFAULT_OPAQUE_MODULE_EXTENSIONS,
+            PackageDiscovery::Walk,
         );
 
         let walk_result = walk_result.unwrap();
@@ -473,4 +858,128 @@ mod test {
             .any(|x| x.path == tmpdir.root().join("shared")),);
         assert_eq!(walk_result.ignore_files.len(), 2);
     }
+
+    #[test]
+    fn test_discovers_workspace_packages() {
+        let tmpdir = test_tmpdir!(
+            "package.json" => r#"{
+                "name": "monorepo-root",
+                "workspaces": ["packages/*"]
+            }"#,
+            "packages/a/package.json" => r#"{ "name": "a" }"#,
+            "packages/a/index.ts" => "export const a = 1;",
+            "packages/b/package.json" => r#"{ "name": "b" }"#,
+            "packages/b/index.ts" => "export const b = 1;",
+            "fixtures/x/package.json" => r#"{ "name": "x" }"#,
+            "fixtures/x/index.ts" => "export const x = 1;"
+        );
+
+        let test_logger = StdioLogger::new();
+        let walk_result = walk_src_files(
+            &test_logger,
+            &[tmpdir.root()],
+            tmpdir.root(),
+            &[] as &[&str],
+            DEFAULT_OPAQUE_MODULE_EXTENSIONS,
+            PackageDiscovery::Workspaces,
+        )
+        .unwrap();
+
+        let mut package_names = walk_result.packages.iter_names().collect::<Vec<_>>();
+        package_names.sort();
+        assert_eq!(package_names, vec!["a", "b"]);
+        // files outside of the workspace packages are still walked
+        assert_eq!(walk_result.source_files.len(), 3);
+        let fixture_file = walk_result
+            .source_files
+            .iter()
+            .find(|file| file.source_file_path == tmpdir.root().join("fixtures/x/index.ts"))
+            .unwrap();
+        assert_eq!(fixture_file.owning_package, None);
+    }
+
+    // counts the typescript files of each package
+    struct PackageCounter;
+
+    impl FileVisitor for PackageCounter {
+        type Output = Option<String>;
+
+        fn visit_file(&self, file: VisitedFile) -> Result<Option<Option<String>>, anyhow::Error> {
+            if file.path.extension() != Some(OsStr::new("ts")) {
+                return Ok(None);
+            }
+            Ok(Some(
+                file.owning_package
+                    .and_then(|package| package.package_json.name.clone()),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_walk_repo_visits_files_by_package() {
+        let tmpdir = test_tmpdir!(
+            "package.json" => r#"{ "name": "root" }"#,
+            "src/index.ts" => "export const root = 1;",
+            "packages/a/package.json" => r#"{ "name": "a" }"#,
+            "packages/a/index.ts" => "export const a = 1;",
+            "packages/a/util.ts" => "export const util = 1;",
+            "packages/a/README.md" => "# a",
+            "packages/b/package.json" => r#"{ "name": "b" }"#,
+            "packages/b/index.ts" => "export const b = 1;",
+            "node_modules/dep/index.ts" => "export const dep = 1;"
+        );
+
+        let test_logger = StdioLogger::new();
+        let summary = walk_repo(
+            &test_logger,
+            WalkConfig {
+                repo_root: tmpdir.root().to_path_buf(),
+                root_paths: vec![PathBuf::from(".")],
+                ..Default::default()
+            },
+            PackageCounter,
+        )
+        .unwrap();
+
+        assert!(Vec::from(summary.errors).is_empty());
+        let paths = summary
+            .files
+            .iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        let mut sorted_paths = paths.clone();
+        sorted_paths.sort();
+        assert_eq!(paths, sorted_paths);
+
+        let mut counts: BTreeMap<Option<String>, usize> = BTreeMap::new();
+        for (_, package) in summary.files.iter() {
+            *counts.entry(package.clone()).or_default() += 1;
+        }
+        assert_eq!(
+            counts,
+            BTreeMap::from([
+                (Some("a".to_string()), 2),
+                (Some("b".to_string()), 1),
+                (Some("root".to_string()), 1),
+            ])
+        );
+
+        // the unused finder's walk assigns the same packages
+        let walk_result = walk_src_files(
+            &test_logger,
+            &["."],
+            tmpdir.root(),
+            &[] as &[&str],
+            DEFAULT_OPAQUE_MODULE_EXTENSIONS,
+            PackageDiscovery::Walk,
+        )
+        .unwrap();
+        let mut walked_counts: BTreeMap<Option<String>, usize> = BTreeMap::new();
+        for source_file in walk_result.source_files.iter() {
+            *walked_counts
+                .entry(source_file.owning_package.clone())
+                .or_default() += 1;
+        }
+        assert_eq!(counts, walked_counts);
+    }
 }
@@ -37,6 +37,9 @@ pub struct WalkedPackage {
     /// The cleaned form of the "module" field of this package.json, if it
     /// exists and needs cleaning. Otherwise, this will be None.
     pub cleaned_module: Option<String>,
+    /// True if this package was synthesized for a repo without any
+    /// package.json, rather than read from disk.
+    pub synthetic: bool,
 }
 
 impl WalkedPackage {
@@ -93,24 +96,99 @@ impl WalkedPackage {
             export_info,
             cleaned_main,
             cleaned_module,
+            synthetic: false,
         })
     }
 
-    pub fn is_abspath_exported(
-        &self,
-        // The absolute path of the file to check
-        abs_path: impl AsRef<Path>,
-    ) -> Result<bool, anyhow::Error> {
+    /// Synthesizes an implicit package at the root of a repo that has no
+    /// package.json, named after the repo directory.
+    ///
+    /// The package "exports" exactly the given entry files, which are paths
+    /// relative to the repo root.
+    pub fn synthetic_root(
+        repo_root: impl AsRef<Path>,
+        entry_files: &[impl AsRef<Path>],
+    ) -> Result<Self, anyhow::Error> {
+        let repo_root = repo_root.as_ref();
+        let name = repo_root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "root".to_string());
+        let exports: serde_json::Map<String, serde_json::Value> = entry_files
+            .iter()
+            .map(|entry_file| {
+                let entry = format!(
+                    "./{}",
+                    entry_file.as_ref().to_path_buf().clean().to_slash_lossy()
+                );
+                (entry.clone(), serde_json::Value::String(entry))
+            })
+            .collect();
+        let package_json = serde_json::json!({
+            "name": name,
+            "exports": exports,
+        });
+
+        let mut package = Self::read(
+            repo_root.join("package.json"),
+            package_json.to_string().as_bytes(),
+        )?;
+        package.synthetic = true;
+        Ok(package)
+    }
+
+    /// Gets the "./"-prefixed path of a file relative to this package's directory,
+    /// or None if the file is outside of the package
+    fn package_relative_path(&self, abs_path: impl AsRef<Path>) -> Result<Option<String>> {
         let as_buf = PathBuf::from(&self.package_path);
         let package_dir_path = as_buf.parent().unwrap_or_else(|| &as_buf);
         let as_relative_path = pathdiff::diff_paths(abs_path, package_dir_path)
             .with_context(|| "Failed to diff paths")?;
 
         if as_relative_path.starts_with(concatcp!("..", path::MAIN_SEPARATOR)) {
-            // The file is outside the package, so it's not exported
-            return Ok(false);
+            // The file is outside the package
+            return Ok(None);
         }
 
+        let as_rel_slashed = as_relative_path.to_slash().unwrap();
+        let mut package_relative_path = String::with_capacity(as_rel_slashed.len() + 2);
+        package_relative_path.push_str("./");
+        package_relative_path.push_str(&as_rel_slashed);
+        Ok(Some(package_relative_path))
+    }
+
+    /// Gets the package.json fields that explicitly declare entrypoints of this
+    /// package, out of "exports", "main" and "module"
+    pub fn entrypoint_fields(&self) -> Vec<&'static str> {
+        let fields = [
+            ("exports", self.package_json.exports.is_some()),
+            ("main", self.package_json.main.is_some()),
+            ("module", self.package_json.module.is_some()),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, is_set)| *is_set)
+            .map(|(field, _)| field)
+            .collect()
+    }
+
+    /// Checks if a package-relative path is the "main" or "module" entry of this package
+    fn is_main_or_module(&self, package_relative_path: &str) -> bool {
+        self.cleaned_main.as_deref() == Some(package_relative_path)
+            || self.cleaned_module.as_deref() == Some(package_relative_path)
+    }
+
+    pub fn is_abspath_exported(
+        &self,
+        // The absolute path of the file to check
+        abs_path: impl AsRef<Path>,
+    ) -> Result<bool, anyhow::Error> {
+        let package_relative_path = match self.package_relative_path(abs_path)? {
+            Some(package_relative_path) => package_relative_path,
+            // The file is outside the package, so it's not exported
+            None => return Ok(false),
+        };
+
         let export_info = match &self.export_info {
             Some(info) => info,
             None => {
@@ -119,26 +197,62 @@ impl WalkedPackage {
             }
         };
 
-        let as_rel_slashed = as_relative_path.to_slash().unwrap();
-        let mut package_relative_path = String::with_capacity(as_rel_slashed.len() + 2);
-        package_relative_path.push_str("./");
-        package_relative_path.push_str(&as_rel_slashed);
-
         // Check main and module fields
-        if let Some(ref main) = self.cleaned_main {
-            if main == &package_relative_path {
-                return Ok(true);
-            }
-        }
-        if let Some(ref module) = self.cleaned_module {
-            if module == &package_relative_path {
-                return Ok(true);
-            }
+        if self.is_main_or_module(&package_relative_path) {
+            return Ok(true);
         }
 
         // check against the export info
         Ok(export_info.is_exported(&package_relative_path))
     }
+
+    /// Checks if a file is explicitly exported by this package, either as its
+    /// "main" / "module" entry or through its "exports" field.
+    ///
+    /// Unlike `is_abspath_exported`, the files of a package without an
+    /// "exports" field are not all treated as exported.
+    pub fn is_abspath_explicitly_exported(
+        &self,
+        // The absolute path of the file to check
+        abs_path: impl AsRef<Path>,
+    ) -> Result<bool, anyhow::Error> {
+        let package_relative_path = match self.package_relative_path(abs_path)? {
+            Some(package_relative_path) => package_relative_path,
+            None => return Ok(false),
+        };
+
+        if self.is_main_or_module(&package_relative_path) {
+            return Ok(true);
+        }
+
+        Ok(self
+            .export_info
+            .as_ref()
+            .is_some_and(|export_info| export_info.is_exported(&package_relative_path)))
+    }
+
+    /// Checks if a file is this package's public barrel: the file that is loaded
+    /// when the package is imported by name, either through the "." entry of the
+    /// "exports" field, or through the "main" / "module" fields.
+    pub fn is_abspath_barrel(
+        &self,
+        // The absolute path of the file to check
+        abs_path: impl AsRef<Path>,
+    ) -> Result<bool, anyhow::Error> {
+        let package_relative_path = match self.package_relative_path(abs_path)? {
+            Some(package_relative_path) => package_relative_path,
+            None => return Ok(false),
+        };
+
+        if self.is_main_or_module(&package_relative_path) {
+            return Ok(true);
+        }
+
+        Ok(self
+            .export_info
+            .as_ref()
+            .is_some_and(|export_info| export_info.is_index_export(&package_relative_path)))
+    }
 }
 
 #[cfg(test)]
@@ -182,6 +296,27 @@ mod test {
         assert!(!pkg.is_abspath_exported("/path/to/cjs/module.js").unwrap());
     }
 
+    #[test]
+    fn test_synthetic_root_exports_entry_files() {
+        let pkg = WalkedPackage::synthetic_root(
+            PathBuf::from_slash("/path/to/my-app"),
+            &["src/index.ts", "./scripts/../src/main.ts"],
+        )
+        .unwrap();
+
+        assert_eq!(pkg.package_json.name.as_deref(), Some("my-app"));
+        assert!(pkg.synthetic);
+        assert!(pkg
+            .is_abspath_exported("/path/to/my-app/src/index.ts")
+            .unwrap());
+        assert!(pkg
+            .is_abspath_exported("/path/to/my-app/src/main.ts")
+            .unwrap());
+        assert!(!pkg
+            .is_abspath_exported("/path/to/my-app/src/orphan.ts")
+            .unwrap());
+    }
+
     #[test]
     fn test_exports_all_export_conditions() {
         let pkg = WalkedPackage::read(
@@ -0,0 +1,249 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use logger::Logger;
+use packagejson::PackageJson;
+use path_slash::PathExt;
+
+/// Finds the package.json files of the packages declared by the workspace
+/// globs of a monorepo, from either the `workspaces` field of the root
+/// package.json or the `packages` field of pnpm-workspace.yaml.
+///
+/// Returns None if the repo root declares no workspaces. Globs starting with
+/// `!` exclude the package directories they match, and packages installed in
+/// `node_modules` are never matched.
+pub fn find_workspace_package_jsons(
+    logger: impl Logger,
+    repo_root: impl AsRef<Path>,
+) -> Result<Option<Vec<PathBuf>>, anyhow::Error> {
+    let repo_root = repo_root.as_ref();
+    let globs = match read_workspace_globs(logger, repo_root)? {
+        Some(globs) => globs,
+        None => return Ok(None),
+    };
+
+    let (exclude_globs, include_globs): (Vec<&str>, Vec<&str>) = globs
+        .iter()
+        .map(|glob| glob.trim_start_matches("./"))
+        .partition(|glob| glob.starts_with('!'));
+    let exclude_patterns = exclude_globs
+        .iter()
+        .map(|glob| glob::Pattern::new(glob.trim_start_matches('!').trim_start_matches("./")))
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to parse workspace globs")?;
+
+    let mut package_jsons: Vec<PathBuf> = Vec::new();
+    for include_glob in include_globs {
+        let pattern = repo_root.join(include_glob).join("package.json");
+        let pattern = pattern.to_string_lossy();
+        let matches = glob::glob(&pattern)
+            .with_context(|| format!("Failed to parse workspace glob {include_glob:?}"))?;
+        for package_json in matches {
+            let package_json = package_json?;
+            let package_dir = package_json
+                .parent()
+                .and_then(|package_dir| package_dir.strip_prefix(repo_root).ok());
+            // e.g. `packages/**` also matches the dependencies installed under each package
+            let is_installed = package_dir.is_some_and(|package_dir| {
+                package_dir
+                    .components()
+                    .any(|component| component.as_os_str() == "node_modules")
+            });
+            let is_excluded = package_dir
+                .and_then(|package_dir| package_dir.to_slash())
+                .is_some_and(|package_dir| {
+                    exclude_patterns
+                        .iter()
+                        .any(|pattern| pattern.matches(&package_dir))
+                });
+            if !is_installed && !is_excluded {
+                package_jsons.push(package_json);
+            }
+        }
+    }
+    package_jsons.sort();
+    package_jsons.dedup();
+    Ok(Some(package_jsons))
+}
+
+// reads the workspace globs of the root package.json, falling back to pnpm-workspace.yaml
+fn read_workspace_globs(
+    logger: impl Logger,
+    repo_root: &Path,
+) -> Result<Option<Vec<String>>, anyhow::Error> {
+    let root_package_json_path = repo_root.join("package.json");
+    if root_package_json_path.exists() {
+        let contents = std::fs::read_to_string(&root_package_json_path)?;
+        let package_json: PackageJson = serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "Failed to read package.json file at path: {}",
+                root_package_json_path.display()
+            )
+        })?;
+        if let Some(workspaces) = package_json.workspaces {
+            return Ok(Some(workspaces.globs().to_vec()));
+        }
+    }
+
+    let pnpm_workspace_path = repo_root.join("pnpm-workspace.yaml");
+    if pnpm_workspace_path.exists() {
+        let contents = std::fs::read_to_string(&pnpm_workspace_path)?;
+        match parse_pnpm_workspace_globs(&contents) {
+            Some(globs) => return Ok(Some(globs)),
+            None => logger.warn(format!(
+                "Unsupported `packages` field in {}, ignoring its workspaces",
+                pnpm_workspace_path.display()
+            )),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Reads the `packages` globs of a pnpm-workspace.yaml file.
+///
+/// The `packages` key may be a block sequence or a flow sequence, e.g.
+/// ```yaml
+/// packages:
+///   - "packages/*"
+///   - "!**/fixtures/**"
+/// ```
+/// or `packages: ['packages/*', '!**/fixtures/**']`. Returns None if the
+/// `packages` key has any other form.
+fn parse_pnpm_workspace_globs(contents: &str) -> Option<Vec<String>> {
+    let mut globs = Vec::new();
+    let mut in_packages = false;
+    // text of a flow sequence of the `packages` key, which may span lines,
+    // until its closing bracket
+    let mut flow_sequence: Option<String> = None;
+    for line in contents.lines() {
+        // drop comments and surrounding whitespace
+        let line = match line.find(" #") {
+            Some(comment_start) => &line[..comment_start],
+            None => line,
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(sequence) = flow_sequence.as_mut() {
+            sequence.push_str(trimmed);
+        } else if !line.starts_with([' ', '\t', '-']) {
+            // a new top-level key ends the previous one
+            in_packages = false;
+            if let Some(value) = trimmed.strip_prefix("packages:") {
+                let value = value.trim();
+                if value.is_empty() {
+                    in_packages = true;
+                } else if value.starts_with('[') {
+                    flow_sequence = Some(value.to_string());
+                } else {
+                    return None;
+                }
+            }
+        } else if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                globs.extend(unquote(item));
+            }
+        }
+
+        if let Some(sequence) = flow_sequence.take_if(|sequence| sequence.ends_with(']')) {
+            globs.extend(
+                sequence[1..sequence.len() - 1]
+                    .split(',')
+                    .filter_map(unquote),
+            );
+        }
+    }
+    // an unterminated flow sequence
+    if flow_sequence.is_some() {
+        return None;
+    }
+    Some(globs)
+}
+
+// the glob of a sequence item, without its quotes
+fn unquote(item: &str) -> Option<String> {
+    let item = item.trim().trim_matches(|c| c == '"' || c == '\'');
+    (!item.is_empty()).then(|| item.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_workspace_package_jsons, parse_pnpm_workspace_globs};
+    use test_tmpdir::test_tmpdir;
+
+    #[test]
+    fn test_parse_pnpm_workspace_globs() {
+        let globs = parse_pnpm_workspace_globs(
+            r#"
+# all packages in direct subdirs of packages/
+packages:
+  - 'packages/*'
+  - "apps/**" # nested apps
+  - '!**/fixtures/**'
+catalog:
+  - not-a-package
+"#,
+        );
+
+        assert_eq!(
+            globs,
+            Some(vec![
+                "packages/*".to_string(),
+                "apps/**".to_string(),
+                "!**/fixtures/**".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_pnpm_workspace_flow_globs() {
+        assert_eq!(
+            parse_pnpm_workspace_globs("packages: ['packages/*', \"apps/**\"]\n"),
+            Some(vec!["packages/*".to_string(), "apps/**".to_string()])
+        );
+        assert_eq!(
+            parse_pnpm_workspace_globs(
+                r#"
+packages: [
+  'packages/*',
+  '!**/fixtures/**', # not packages
+]
+"#
+            ),
+            Some(vec![
+                "packages/*".to_string(),
+                "!**/fixtures/**".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_unsupported_pnpm_workspace_globs() {
+        assert_eq!(parse_pnpm_workspace_globs("packages: packages/*\n"), None);
+        assert_eq!(
+            parse_pnpm_workspace_globs("packages: ['packages/*'\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_workspace_globs_skip_node_modules() {
+        let tmpdir = test_tmpdir!(
+            "package.json" => r#"{ "workspaces": ["packages/**"] }"#,
+            "packages/a/package.json" => r#"{ "name": "a" }"#,
+            "packages/a/node_modules/dep/package.json" => r#"{ "name": "dep" }"#
+        );
+
+        let package_jsons = find_workspace_package_jsons(logger::StdioLogger::new(), tmpdir.root())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            package_jsons,
+            vec![tmpdir.root().join("packages/a/package.json")]
+        );
+    }
+}
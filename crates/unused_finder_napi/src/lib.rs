@@ -24,26 +24,193 @@ pub struct UnusedFinderJSONConfig {
     ///
     /// Matches are made against the names of the individual directories,
     /// NOT the full directory paths
+    ///
+    /// Globs support `*`, `?`, character classes and `{a,b}` alternation
+    /// (e.g. `{dist,build}`).
     pub skip: Option<Vec<String>>,
     /// If true, individual exported symbols are also tracked
     pub report_exported_symbols: Option<bool>,
     pub allow_unused_types: Option<bool>,
+    /// If true, symbols re-exported from a package's public barrel are
+    /// considered used, even if nothing imports them from the barrel.
+    pub barrel_exports_count_as_used: Option<bool>,
+    /// If true, unused exports are reported in the files of entry packages
+    /// that are only exported because their package has no "exports" field.
+    pub report_unused_entry_exports: Option<bool>,
     /// List of packages that should be considered "entry" packages
     /// All transitive imports from the exposed exports of these packages
     /// will be considered used
     ///
     /// Items are parsed in one of three ways:
     /// 1. If the item starts with "./", it is treated as a path glob, and evaluated against the paths of package folders, relative to the repo root.
-    /// 2. If the item contains any of "~)('!*", it is treated as a name-glob, and evaluated as a glob against the names of packages.
+    /// 2. If the item contains any of "~)('!*?[{", it is treated as a name-glob, and evaluated as a glob against the names of packages.
     /// 3. Otherwise, the item is treated as the name of an individual package, and matched literally.
+    ///
+    /// Globs support `*`, `**`, `?`, character classes and `{a,b}` alternation, with the same syntax as `test_files`.
+    ///
+    /// In repos without any package.json, "<root>" marks the `index.ts` and `main.ts` files under each root path as entrypoints.
     pub entry_packages: Vec<String>,
+    /// Files to treat as entrypoints in repos without any package.json, relative to the repo root
+    pub entry_files: Option<Vec<String>>,
     /// List of glob patterns to mark as "tests".
     /// These files will be marked as used, and all of their transitive
     /// dependencies will also be marked as used
     ///
     /// glob patterns are matched against the relative file path from the
     /// root of the repository
+    ///
+    /// Globs support `*`, `**`, `?`, character classes and `{a,b}` alternation
+    /// (e.g. `**/*.{test,spec}.ts`), with the same syntax as `entryPackages`.
     pub test_files: Option<Vec<String>>,
+    /// If true, files under the test directory declared in each package's
+    /// package.json "directories.test" are also marked as tests
+    pub infer_test_dirs_from_package_json: Option<bool>,
+    /// If true, packages without a declared test directory fall back to
+    /// `test`, `tests` and `__tests__` when inferring test directories
+    pub use_default_test_dirs: Option<bool>,
+    /// If true, warns about files tagged as tests that do not call any
+    /// test framework functions
+    pub verify_test_files: Option<bool>,
+    /// If true, unused exported symbols are listed with the other files
+    /// exporting a symbol of the same name
+    pub suggest_same_name_exports: Option<bool>,
+    /// Which names exported by more than one file are listed in the report,
+    /// all of them or only the ones imported from more than one of the files
+    /// exporting them. Defaults to none
+    pub report_ambiguous_symbol_names: Option<AmbiguousSymbolNames>,
+    /// If true, the report counts the imports of each kind across all files
+    pub count_import_kinds: Option<bool>,
+    /// If true, the report lists imports of a deep path into another package
+    /// for symbols that the package also exports from its public barrel
+    pub report_barrel_bypass_imports: Option<bool>,
+    /// If true, the report lists the imported bindings of each file that are
+    /// never referenced within the file
+    pub report_unused_imports: Option<bool>,
+    /// If true (the default), side-effect imports also use the symbols
+    /// re-exported by the imported file
+    pub effect_imports_mark_symbols: Option<bool>,
+    /// If true, the run fails if any source file cannot be parsed, instead
+    /// of skipping the file with a warning
+    pub fail_on_parse_error: Option<bool>,
+    /// If true, walked files are only analyzed if the tsconfig.json at the
+    /// repo root includes them in its project
+    pub restrict_to_tsconfig_files: Option<bool>,
+    /// If true, the run fails if an entry package does not explicitly export
+    /// any source file, instead of reporting the package with a warning
+    pub strict_entry_packages: Option<bool>,
+    /// If true, files exported by entry packages are only entrypoints if the
+    /// `exports` of their good-fences fences cover them
+    pub respect_fences: Option<bool>,
+    /// If set, files exporting more than this many symbols are listed in the
+    /// report as large modules
+    pub god_module_threshold: Option<u32>,
+    /// Extensions of files that are tracked as opaque modules, without
+    /// parsing their contents. Defaults to `.vue` and `.svelte`
+    pub opaque_module_extensions: Option<Vec<String>>,
+    /// How file paths are rendered in the report. Defaults to absolute paths
+    pub path_display: Option<PathDisplay>,
+    /// Names of class decorators that mark exported classes as used, for
+    /// frameworks that wire classes together at runtime (e.g. `Injectable`)
+    pub keep_alive_decorators: Option<Vec<String>>,
+    /// Pragmas that mark an exported symbol as kept when found in a line
+    /// comment leading its export statement (e.g. `good-fences-used`)
+    pub keep_pragmas: Option<Vec<String>>,
+    /// How packages are discovered: by walking for every package.json, or
+    /// from the workspace globs of the repo root. Defaults to walking
+    pub package_discovery: Option<PackageDiscovery>,
+    /// Directories of generated files that mirror directories of source
+    /// files, relative to the repo root (e.g. `packages/*/lib/` mirroring
+    /// `packages/*/src/`). Imports of generated files also use their source files
+    pub path_mirrors: Option<Vec<PathMirror>>,
+    /// The environment imports are resolved for, which decides the export
+    /// conditions of packages that are matched. Defaults to the browser
+    pub target_env: Option<ResolverTargetEnv>,
+}
+
+#[derive(Debug, Default, Clone)]
+#[napi(object)]
+pub struct PathMirror {
+    pub from: String,
+    pub to: String,
+}
+
+impl From<PathMirror> for unused_finder::PathMirror {
+    fn from(val: PathMirror) -> Self {
+        unused_finder::PathMirror {
+            from: val.from,
+            to: val.to,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[napi(string_enum)]
+pub enum PackageDiscovery {
+    Walk,
+    Workspaces,
+}
+
+impl From<PackageDiscovery> for unused_finder::PackageDiscovery {
+    fn from(val: PackageDiscovery) -> Self {
+        match val {
+            PackageDiscovery::Walk => unused_finder::PackageDiscovery::Walk,
+            PackageDiscovery::Workspaces => unused_finder::PackageDiscovery::Workspaces,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[napi(string_enum)]
+pub enum ResolverTargetEnv {
+    Browser,
+    Node,
+}
+
+impl From<ResolverTargetEnv> for unused_finder::ResolverTargetEnv {
+    fn from(val: ResolverTargetEnv) -> Self {
+        match val {
+            ResolverTargetEnv::Browser => unused_finder::ResolverTargetEnv::Browser,
+            ResolverTargetEnv::Node => unused_finder::ResolverTargetEnv::Node,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[napi(string_enum)]
+pub enum PathDisplay {
+    Absolute,
+    RepoRelative,
+    CwdRelative,
+}
+
+impl From<PathDisplay> for unused_finder::PathDisplay {
+    fn from(val: PathDisplay) -> Self {
+        match val {
+            PathDisplay::Absolute => unused_finder::PathDisplay::Absolute,
+            PathDisplay::RepoRelative => unused_finder::PathDisplay::RepoRelative,
+            PathDisplay::CwdRelative => unused_finder::PathDisplay::CwdRelative,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[napi(string_enum)]
+pub enum AmbiguousSymbolNames {
+    Off,
+    All,
+    ConflictingImports,
+}
+
+impl From<AmbiguousSymbolNames> for unused_finder::AmbiguousSymbolNames {
+    fn from(val: AmbiguousSymbolNames) -> Self {
+        match val {
+            AmbiguousSymbolNames::Off => unused_finder::AmbiguousSymbolNames::Off,
+            AmbiguousSymbolNames::All => unused_finder::AmbiguousSymbolNames::All,
+            AmbiguousSymbolNames::ConflictingImports => {
+                unused_finder::AmbiguousSymbolNames::ConflictingImports
+            }
+        }
+    }
 }
 
 impl From<UnusedFinderJSONConfig> for unused_finder::UnusedFinderJSONConfig {
@@ -54,8 +221,45 @@ impl From<UnusedFinderJSONConfig> for unused_finder::UnusedFinderJSONConfig {
             skip: val.skip.unwrap_or_default(),
             report_exported_symbols: val.report_exported_symbols.unwrap_or_default(),
             entry_packages: val.entry_packages,
+            entry_files: val.entry_files.unwrap_or_default(),
             allow_unused_types: val.allow_unused_types.unwrap_or_default(),
+            barrel_exports_count_as_used: val.barrel_exports_count_as_used.unwrap_or_default(),
+            report_unused_entry_exports: val.report_unused_entry_exports.unwrap_or_default(),
             test_files: val.test_files.unwrap_or_default(),
+            infer_test_dirs_from_package_json: val
+                .infer_test_dirs_from_package_json
+                .unwrap_or_default(),
+            use_default_test_dirs: val.use_default_test_dirs.unwrap_or_default(),
+            verify_test_files: val.verify_test_files.unwrap_or_default(),
+            suggest_same_name_exports: val.suggest_same_name_exports.unwrap_or_default(),
+            report_ambiguous_symbol_names: val
+                .report_ambiguous_symbol_names
+                .map(Into::into)
+                .unwrap_or_default(),
+            count_import_kinds: val.count_import_kinds.unwrap_or_default(),
+            report_barrel_bypass_imports: val.report_barrel_bypass_imports.unwrap_or_default(),
+            report_unused_imports: val.report_unused_imports.unwrap_or_default(),
+            effect_imports_mark_symbols: val.effect_imports_mark_symbols.unwrap_or(true),
+            fail_on_parse_error: val.fail_on_parse_error.unwrap_or_default(),
+            restrict_to_tsconfig_files: val.restrict_to_tsconfig_files.unwrap_or_default(),
+            strict_entry_packages: val.strict_entry_packages.unwrap_or_default(),
+            respect_fences: val.respect_fences.unwrap_or_default(),
+            god_module_threshold: val.god_module_threshold.map(|threshold| threshold as usize),
+            opaque_module_extensions: val.opaque_module_extensions.unwrap_or_else(|| {
+                unused_finder::DEFAULT_OPAQUE_MODULE_EXTENSIONS
+                    .iter()
+                    .map(|ext| ext.to_string())
+                    .collect()
+            }),
+            path_display: val.path_display.map(Into::into).unwrap_or_default(),
+            keep_alive_decorators: val.keep_alive_decorators.unwrap_or_default(),
+            keep_pragmas: val.keep_pragmas.unwrap_or_default(),
+            package_discovery: val.package_discovery.map(Into::into).unwrap_or_default(),
+            path_mirrors: val
+                .path_mirrors
+                .map(|mirrors| mirrors.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+            target_env: val.target_env.map(Into::into).unwrap_or_default(),
         }
     }
 }
@@ -87,6 +291,10 @@ pub struct SymbolReport {
     pub id: String,
     pub start: u32,
     pub end: u32,
+    // if true, the symbol is used within its own file, so only the export is unused
+    pub locally_used: bool,
+    // for default exports, the local name of the exported value, if it has one
+    pub local_name: Option<String>,
 }
 
 impl From<unused_finder::SymbolReport> for SymbolReport {
@@ -95,6 +303,8 @@ impl From<unused_finder::SymbolReport> for SymbolReport {
             id: val.id,
             start: val.start,
             end: val.end,
+            locally_used: val.locally_used,
+            local_name: val.local_name,
         }
     }
 }
@@ -115,6 +325,217 @@ impl From<unused_finder::SymbolReportWithTags> for SymbolReportWithTags {
     }
 }
 
+// A file exporting more symbols than the configured god_module_threshold
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq)]
+#[napi(object)]
+pub struct LargeModuleReport {
+    pub file_path: String,
+    pub num_exported_symbols: u32,
+}
+
+impl From<unused_finder::LargeModuleReport> for LargeModuleReport {
+    fn from(val: unused_finder::LargeModuleReport) -> Self {
+        LargeModuleReport {
+            file_path: val.file_path,
+            num_exported_symbols: val.num_exported_symbols as u32,
+        }
+    }
+}
+
+// A file that failed to parse, whose imports were found by a lexical scan
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq)]
+#[napi(object)]
+pub struct DegradedFileReport {
+    pub file_path: String,
+    pub parse_error: String,
+}
+
+impl From<unused_finder::DegradedFileReport> for DegradedFileReport {
+    fn from(val: unused_finder::DegradedFileReport) -> Self {
+        DegradedFileReport {
+            file_path: val.file_path,
+            parse_error: val.parse_error,
+        }
+    }
+}
+
+// A name exported from an entry file that resolves to more than one definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct SymbolCollisionReport {
+    pub entry_file: String,
+    pub name: String,
+    // every definition of the name, in the order the entry file lists them
+    pub definitions: Vec<SymbolDefinitionReport>,
+}
+
+impl From<unused_finder::SymbolCollisionReport> for SymbolCollisionReport {
+    fn from(val: unused_finder::SymbolCollisionReport) -> Self {
+        SymbolCollisionReport {
+            entry_file: val.entry_file,
+            name: val.name,
+            definitions: val.definitions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// A name that a file gets from more than one `export *` with different
+// definitions, so the file does not export it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct AmbiguousReexportReport {
+    pub file_path: String,
+    pub name: String,
+    // the files of the `export *` contributing the name
+    pub sources: Vec<String>,
+}
+
+impl From<unused_finder::AmbiguousReexportReport> for AmbiguousReexportReport {
+    fn from(val: unused_finder::AmbiguousReexportReport) -> Self {
+        AmbiguousReexportReport {
+            file_path: val.file_path,
+            name: val.name,
+            sources: val.sources,
+        }
+    }
+}
+
+// An import of a deep path into another package, for a symbol that the
+// package also exports from its public barrel
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct BarrelBypassImportReport {
+    pub importer: String,
+    pub imported_file: String,
+    pub symbol: String,
+    // the package to import the symbol from instead
+    pub package_name: String,
+    pub barrel_file: String,
+    // the name the barrel exports the symbol as
+    pub barrel_name: String,
+}
+
+impl From<unused_finder::BarrelBypassImportReport> for BarrelBypassImportReport {
+    fn from(val: unused_finder::BarrelBypassImportReport) -> Self {
+        BarrelBypassImportReport {
+            importer: val.importer,
+            imported_file: val.imported_file,
+            symbol: val.symbol,
+            package_name: val.package_name,
+            barrel_file: val.barrel_file,
+            barrel_name: val.barrel_name,
+        }
+    }
+}
+
+// A definition of a colliding name
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct SymbolDefinitionReport {
+    pub file_path: String,
+    pub symbol: String,
+    pub start: u32,
+    pub end: u32,
+    // true for the definition that importers of the name currently get
+    pub wins: bool,
+}
+
+impl From<unused_finder::SymbolDefinitionReport> for SymbolDefinitionReport {
+    fn from(val: unused_finder::SymbolDefinitionReport) -> Self {
+        SymbolDefinitionReport {
+            file_path: val.file_path,
+            symbol: val.symbol,
+            start: val.start,
+            end: val.end,
+            wins: val.wins,
+        }
+    }
+}
+
+// An unused exported symbol, with the other files exporting the same name
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct SameNameExportReport {
+    pub id: String,
+    pub file_paths: Vec<String>,
+}
+
+impl From<unused_finder::SameNameExportReport> for SameNameExportReport {
+    fn from(val: unused_finder::SameNameExportReport) -> Self {
+        SameNameExportReport {
+            id: val.id,
+            file_paths: val.file_paths,
+        }
+    }
+}
+
+// A name exported by more than one file, with the files it is imported from
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct AmbiguousSymbolNameReport {
+    pub name: String,
+    pub file_paths: Vec<String>,
+    pub imported_from: Vec<String>,
+}
+
+impl From<unused_finder::AmbiguousSymbolNameReport> for AmbiguousSymbolNameReport {
+    fn from(val: unused_finder::AmbiguousSymbolNameReport) -> Self {
+        AmbiguousSymbolNameReport {
+            name: val.name,
+            file_paths: val.file_paths,
+            imported_from: val.imported_from,
+        }
+    }
+}
+
+// An imported binding that is never referenced within the importing file
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct UnusedImportReport {
+    pub specifier: String,
+    pub id: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl From<unused_finder::UnusedImportReport> for UnusedImportReport {
+    fn from(val: unused_finder::UnusedImportReport) -> Self {
+        UnusedImportReport {
+            specifier: val.specifier,
+            id: val.id,
+            start: val.start,
+            end: val.end,
+        }
+    }
+}
+
+// Number of imports of each kind across the files of a report
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct ImportKindCounts {
+    pub static_named: u32,
+    pub static_default: u32,
+    pub namespace: u32,
+    pub dynamic: u32,
+    pub require: u32,
+    pub re_export: u32,
+    pub effect_only: u32,
+}
+
+impl From<unused_finder::ImportKindCounts> for ImportKindCounts {
+    fn from(val: unused_finder::ImportKindCounts) -> Self {
+        ImportKindCounts {
+            static_named: val.static_named as u32,
+            static_default: val.static_default as u32,
+            namespace: val.namespace as u32,
+            dynamic: val.dynamic as u32,
+            require: val.require as u32,
+            re_export: val.re_export as u32,
+            effect_only: val.effect_only as u32,
+        }
+    }
+}
+
 // Report of unused symbols within a project
 #[derive(Debug, Clone, Default, PartialEq)]
 #[napi]
@@ -127,6 +548,28 @@ pub struct UnusedFinderReport {
     pub unused_symbols: HashMap<String, Vec<SymbolReport>>,
     pub extra_file_tags: HashMap<String, Vec<UsedTagEnum>>,
     pub extra_symbol_tags: HashMap<String, Vec<SymbolReportWithTags>>,
+    // files exporting more symbols than the god_module_threshold, largest first
+    pub large_modules: Vec<LargeModuleReport>,
+    // files tracked as opaque modules, whose own imports are not analyzed
+    pub opaque_modules: Vec<String>,
+    // files that failed to parse, whose imports were found by a lexical scan
+    pub degraded_files: Vec<DegradedFileReport>,
+    // implicit root package synthesized for a repo without any package.json
+    pub synthetic_root_package: Option<String>,
+    // names exported from entry files that resolve to more than one definition
+    pub symbol_collisions: Vec<SymbolCollisionReport>,
+    // names that files get from more than one `export *` with different definitions
+    pub ambiguous_reexports: Vec<AmbiguousReexportReport>,
+    // deep imports into other packages, if report_barrel_bypass_imports is configured
+    pub barrel_bypass_imports: Vec<BarrelBypassImportReport>,
+    // unused exported symbols of each file, with the other files exporting the same name
+    pub same_name_exports: HashMap<String, Vec<SameNameExportReport>>,
+    // names exported by more than one file, if report_ambiguous_symbol_names is configured
+    pub ambiguous_symbol_names: Vec<AmbiguousSymbolNameReport>,
+    // imported bindings of each file that are never referenced, if report_unused_imports is configured
+    pub unused_imports: HashMap<String, Vec<UnusedImportReport>>,
+    // number of imports of each kind, if count_import_kinds is configured
+    pub import_kind_counts: Option<ImportKindCounts>,
 }
 
 impl From<unused_finder::UnusedFinderReport> for UnusedFinderReport {
@@ -148,6 +591,117 @@ impl From<unused_finder::UnusedFinderReport> for UnusedFinderReport {
                 .into_iter()
                 .map(|(k, v)| (k, v.into_iter().map(Into::into).collect()))
                 .collect(),
+            large_modules: val.large_modules.into_iter().map(Into::into).collect(),
+            opaque_modules: val.opaque_modules,
+            degraded_files: val.degraded_files.into_iter().map(Into::into).collect(),
+            synthetic_root_package: val.synthetic_root_package,
+            symbol_collisions: val.symbol_collisions.into_iter().map(Into::into).collect(),
+            ambiguous_reexports: val
+                .ambiguous_reexports
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            barrel_bypass_imports: val
+                .barrel_bypass_imports
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            same_name_exports: val
+                .same_name_exports
+                .into_iter()
+                .map(|(k, v)| (k, v.into_iter().map(Into::into).collect()))
+                .collect(),
+            ambiguous_symbol_names: val
+                .ambiguous_symbol_names
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            unused_imports: val
+                .unused_imports
+                .into_iter()
+                .map(|(k, v)| (k, v.into_iter().map(Into::into).collect()))
+                .collect(),
+            import_kind_counts: val.import_kind_counts.map(Into::into),
+        }
+    }
+}
+
+// An exported symbol to check the usage of
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct SymbolQuery {
+    pub file_path: String,
+    // canonical string form of the symbol: the exported name, "default" for the
+    // default export, or "*" for the namespace. Names that equal one of those
+    // (or start with a backslash) are escaped with a leading backslash, e.g. "\default"
+    pub symbol_name: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[napi(string_enum)]
+pub enum SymbolStatusKind {
+    Used,
+    Unused,
+    UnknownFile,
+    UnknownSymbol,
+}
+
+// Usage status of a single queried symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct SymbolStatus {
+    pub file_path: String,
+    pub symbol_name: String,
+    pub status: SymbolStatusKind,
+    // what the symbol is used from. Empty unless the status is Used
+    pub tags: Vec<UsedTagEnum>,
+}
+
+impl SymbolStatus {
+    fn new(query: SymbolQuery, status: unused_finder::SymbolStatus) -> Self {
+        let (status, tags) = match status {
+            unused_finder::SymbolStatus::Used(tags) => (
+                SymbolStatusKind::Used,
+                tags.into_iter().map(Into::into).collect(),
+            ),
+            unused_finder::SymbolStatus::Unused => (SymbolStatusKind::Unused, vec![]),
+            unused_finder::SymbolStatus::UnknownFile => (SymbolStatusKind::UnknownFile, vec![]),
+            unused_finder::SymbolStatus::UnknownSymbol => (SymbolStatusKind::UnknownSymbol, vec![]),
+        };
+        SymbolStatus {
+            file_path: query.file_path,
+            symbol_name: query.symbol_name,
+            status,
+            tags,
+        }
+    }
+}
+
+// A place where an exported symbol is imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[napi(object)]
+pub struct ImportSite {
+    pub file_path: String,
+    pub start: u32,
+    pub end: u32,
+    // if true, the symbol is imported through a namespace, and the span covers the namespace import
+    pub namespace: bool,
+    // barrel files the import passes through, starting from the one re-exporting the defining file
+    pub via: Vec<String>,
+}
+
+impl From<unused_finder::ImportSite> for ImportSite {
+    fn from(val: unused_finder::ImportSite) -> Self {
+        ImportSite {
+            file_path: val.file.to_string_lossy().to_string(),
+            start: val.span.lo.0,
+            end: val.span.hi.0,
+            namespace: val.namespace,
+            via: val
+                .via
+                .iter()
+                .map(|barrel| barrel.to_string_lossy().to_string())
+                .collect(),
         }
     }
 }
@@ -204,6 +758,25 @@ impl UnusedFinder {
         }
     }
 
+    /// Gets the fully-resolved configuration of this finder, as a JSON string
+    #[napi]
+    pub fn get_effective_config(&self) -> Result<String> {
+        match &self.inner {
+            Ok(ref inner) => Ok(inner.1.config().print_effective_config()),
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// Eagerly populates the resolver caches for every package root, so the
+    /// first query after startup is fast
+    #[napi]
+    pub fn warm_caches(&self) -> Result<()> {
+        match &self.inner {
+            Ok(ref inner) => inner.1.warm_caches().into_napi(),
+            Err(e) => Err(e.clone()),
+        }
+    }
+
     pub fn find_unused(&mut self) -> Result<UnusedFinderReport> {
         match &mut self.inner {
             Ok(ref mut inner) => {
@@ -213,6 +786,77 @@ impl UnusedFinder {
             Err(e) => Err(e.clone()),
         }
     }
+
+    /// Finds unused items across the repo, but only reports files owned by the given package
+    pub fn find_unused_for_package(&mut self, package_name: String) -> Result<UnusedFinderReport> {
+        match &mut self.inner {
+            Ok(ref mut inner) => {
+                let result = inner.1.find_unused(&inner.0);
+                result
+                    .into_napi()
+                    .map(|result| result.report_for_package(&package_name).into())
+            }
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// Checks whether each of the given exported symbols is used, reusing the
+    /// last result unless files were marked dirty since it was computed
+    #[napi]
+    pub fn check_symbols(&mut self, queries: Vec<SymbolQuery>) -> Result<Vec<SymbolStatus>> {
+        match &mut self.inner {
+            Ok(ref mut inner) => {
+                let entries: Vec<(&str, &str)> = queries
+                    .iter()
+                    .map(|query| (query.file_path.as_str(), query.symbol_name.as_str()))
+                    .collect();
+                let statuses = inner.1.check_symbols(&inner.0, entries).into_napi()?;
+                Ok(queries
+                    .into_iter()
+                    .zip(statuses)
+                    .map(|(query, status)| SymbolStatus::new(query, status))
+                    .collect())
+            }
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// Finds every import of an exported symbol, including imports through barrel files
+    /// that re-export it under another name. `symbol_name` is in the same canonical
+    /// form as `SymbolQuery.symbol_name`
+    #[napi]
+    pub fn find_import_sites(
+        &mut self,
+        file_path: String,
+        symbol_name: String,
+    ) -> Result<Vec<ImportSite>> {
+        match &mut self.inner {
+            Ok(ref mut inner) => {
+                let import_sites = inner
+                    .1
+                    .find_import_sites(&inner.0, &file_path, &symbol_name)
+                    .into_napi()?;
+                Ok(import_sites.into_iter().map(Into::into).collect())
+            }
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// Dumps everything known about how a file was analyzed, as a JSON string.
+    /// Meant for debugging why a file or its exports were reported as unused
+    #[napi]
+    pub fn dump_file_analysis(&mut self, file_path: String) -> Result<String> {
+        match &mut self.inner {
+            Ok(ref mut inner) => {
+                let dump = inner
+                    .1
+                    .dump_file_analysis(&inner.0, &file_path)
+                    .into_napi()?;
+                Ok(dump.to_pretty_json())
+            }
+            Err(e) => Err(e.clone()),
+        }
+    }
 }
 
 #[napi]